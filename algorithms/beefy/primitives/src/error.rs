@@ -57,6 +57,8 @@ pub enum BeefyClientError {
 	},
 	/// Incomplete Signature threshold
 	IncompleteSignatureThreshold,
+	/// The same authority signed the commitment more than once
+	DuplicateAuthoritySignature,
 	/// Error recovering public key from signature
 	InvalidSignature,
 	/// Some invalid merkle root hash
@@ -24,15 +24,16 @@ extern crate alloc;
 mod tests;
 
 use beefy_light_client_primitives::{
-	error::BeefyClientError, BeefyNextAuthoritySet, ClientState, HostFunctions, MerkleHasher,
-	MmrUpdateProof, NodesUtils, ParachainsUpdateProof, SignatureWithAuthorityIndex, HASH_LENGTH,
+	error::BeefyClientError, BeefyNextAuthoritySet, ClientState, Hash, HostFunctions, MerkleHasher,
+	MmrUpdateProof, NodesUtils, ParachainsUpdateProof, SignatureWithAuthorityIndex,
+	SignedCommitment, HASH_LENGTH,
 };
 use beefy_primitives::{known_payloads::MMR_ROOT_ID, mmr::MmrLeaf};
 use codec::{Decode, Encode};
 use frame_support::sp_runtime::{app_crypto::ByteArray, traits::Convert};
 use sp_core::H256;
 
-use alloc::{format, string::ToString};
+use alloc::{collections::BTreeSet, format, string::ToString};
 use sp_runtime::{generic::Header, traits::BlakeTwo256};
 use sp_std::{prelude::*, vec};
 use sp_trie::LayoutV0;
@@ -48,25 +49,12 @@ pub fn verify_mmr_root_with_proof<H>(
 where
 	H: HostFunctions + Clone,
 {
-	let current_authority_set = &trusted_client_state.current_authorities;
-	let next_authority_set = &trusted_client_state.next_authorities;
-	let signatures_len = mmr_update.signed_commitment.signatures.len();
-	let validator_set_id = mmr_update.signed_commitment.commitment.validator_set_id;
-
-	// If signature threshold is not satisfied, return
-	if !validate_sigs_against_threshold(current_authority_set, signatures_len)
-		&& !validate_sigs_against_threshold(next_authority_set, signatures_len)
-	{
-		return Err(BeefyClientError::IncompleteSignatureThreshold);
-	}
-
-	if current_authority_set.id != validator_set_id && next_authority_set.id != validator_set_id {
-		return Err(BeefyClientError::AuthoritySetMismatch {
-			current_set_id: current_authority_set.id,
-			next_set_id: next_authority_set.id,
-			commitment_set_id: validator_set_id,
-		});
-	}
+	let signing_authority_set = verify_signed_commitment::<H>(
+		&trusted_client_state,
+		&mmr_update.signed_commitment,
+		mmr_update.authority_proof,
+	)?;
+	let authorities_changed = signing_authority_set.id == trusted_client_state.next_authorities.id;
 
 	// Extract root hash from signed commitment and validate it
 	let mmr_root_vec = {
@@ -86,66 +74,6 @@ where
 
 	let mmr_root_hash = H256::from_slice(&*mmr_root_vec);
 
-	// Beefy validators sign the keccak_256 hash of the scale encoded commitment
-	let encoded_commitment = mmr_update.signed_commitment.commitment.encode();
-	let commitment_hash = H::keccak_256(&*encoded_commitment);
-
-	let mut authority_indices = Vec::new();
-	let authority_leaves = mmr_update
-		.signed_commitment
-		.signatures
-		.into_iter()
-		.map(|SignatureWithAuthorityIndex { index, signature }| {
-			H::secp256k1_ecdsa_recover_compressed(&signature, &commitment_hash)
-				.and_then(|public_key_bytes| {
-					beefy_primitives::ecdsa_crypto::AuthorityId::from_slice(&public_key_bytes).ok()
-				})
-				.map(|pub_key| {
-					authority_indices.push(index as usize);
-					H::keccak_256(&beefy_mmr::BeefyEcdsaToEthereum::convert(pub_key))
-				})
-				.ok_or(BeefyClientError::InvalidSignature)
-		})
-		.collect::<Result<Vec<_>, BeefyClientError>>()?;
-
-	let mut authorities_changed = false;
-
-	let authorities_merkle_proof =
-		rs_merkle::MerkleProof::<MerkleHasher<H>>::new(mmr_update.authority_proof);
-	// Verify mmr_update.authority_proof against store root hash
-	match validator_set_id {
-		id if id == current_authority_set.id => {
-			let root_hash = current_authority_set.keyset_commitment;
-			if !authorities_merkle_proof.verify(
-				root_hash.into(),
-				&authority_indices,
-				&authority_leaves,
-				current_authority_set.len as usize,
-			) {
-				return Err(BeefyClientError::InvalidAuthorityProof);
-			}
-		},
-		id if id == next_authority_set.id => {
-			let root_hash = next_authority_set.keyset_commitment;
-			if !authorities_merkle_proof.verify(
-				root_hash.into(),
-				&authority_indices,
-				&authority_leaves,
-				next_authority_set.len as usize,
-			) {
-				return Err(BeefyClientError::InvalidAuthorityProof);
-			}
-			authorities_changed = true;
-		},
-		_ => {
-			return Err(BeefyClientError::AuthoritySetMismatch {
-				current_set_id: current_authority_set.id,
-				next_set_id: next_authority_set.id,
-				commitment_set_id: validator_set_id,
-			})
-		},
-	}
-
 	let latest_beefy_height = trusted_client_state.latest_beefy_height;
 
 	let commitment_block_number = mmr_update.signed_commitment.commitment.block_number;
@@ -165,13 +93,12 @@ where
 
 	// We are trying to verify the proof for the latest mmr leaf so we expect the proof to contain a
 	// singular leaf index
-	let leaf_index = mmr_update
-		.mmr_proof
-		.leaf_indices
-		.get(0)
-		.ok_or(BeefyClientError::ExpectedSingleLeafIndex)?;
+	let leaf_index = match mmr_update.mmr_proof.leaf_indices.as_slice() {
+		[leaf_index] => *leaf_index,
+		_ => Err(BeefyClientError::ExpectedSingleLeafIndex)?,
+	};
 
-	let leaf_pos = mmr_lib::leaf_index_to_pos(*leaf_index);
+	let leaf_pos = mmr_lib::leaf_index_to_pos(leaf_index);
 
 	let root = proof.calculate_root(vec![(leaf_pos, node.into())])?;
 	if root != mmr_root_hash {
@@ -186,12 +113,92 @@ where
 	trusted_client_state.mmr_root_hash = mmr_root_hash;
 
 	if authorities_changed {
-		trusted_client_state.current_authorities = next_authority_set.clone();
+		trusted_client_state.current_authorities = trusted_client_state.next_authorities.clone();
 		trusted_client_state.next_authorities = mmr_update.latest_mmr_leaf.beefy_next_authority_set;
 	}
 	Ok(trusted_client_state)
 }
 
+/// Verifies that the [`SignedCommitment`] was signed by a supermajority of the current or the
+/// next authority set of the trusted client state, whichever its validator set id is. The signing
+/// authorities are proven to be members of the authority set with the `authority_proof` merkle
+/// multi-proof.
+/// Returns the authority set that signed the commitment.
+pub fn verify_signed_commitment<H>(
+	trusted_client_state: &ClientState,
+	signed_commitment: &SignedCommitment,
+	authority_proof: Vec<Hash>,
+) -> Result<BeefyNextAuthoritySet<H256>, BeefyClientError>
+where
+	H: HostFunctions + Clone,
+{
+	let current_authority_set = &trusted_client_state.current_authorities;
+	let next_authority_set = &trusted_client_state.next_authorities;
+	let signatures_len = signed_commitment.signatures.len();
+	let validator_set_id = signed_commitment.commitment.validator_set_id;
+
+	let authority_set = match validator_set_id {
+		id if id == current_authority_set.id => current_authority_set,
+		id if id == next_authority_set.id => next_authority_set,
+		_ => {
+			return Err(BeefyClientError::AuthoritySetMismatch {
+				current_set_id: current_authority_set.id,
+				next_set_id: next_authority_set.id,
+				commitment_set_id: validator_set_id,
+			})
+		},
+	};
+
+	// The threshold is the one of the set the commitment claims to be signed by
+	if !validate_sigs_against_threshold(authority_set, signatures_len) {
+		return Err(BeefyClientError::IncompleteSignatureThreshold);
+	}
+
+	// Each authority may only be counted once towards the threshold checked above
+	let unique_authorities = signed_commitment
+		.signatures
+		.iter()
+		.map(|signature| signature.index)
+		.collect::<BTreeSet<_>>();
+	if unique_authorities.len() != signatures_len {
+		return Err(BeefyClientError::DuplicateAuthoritySignature);
+	}
+
+	// Beefy validators sign the keccak_256 hash of the scale encoded commitment
+	let encoded_commitment = signed_commitment.commitment.encode();
+	let commitment_hash = H::keccak_256(&*encoded_commitment);
+
+	let mut authority_indices = Vec::new();
+	let authority_leaves = signed_commitment
+		.signatures
+		.iter()
+		.map(|SignatureWithAuthorityIndex { index, signature }| {
+			H::secp256k1_ecdsa_recover_compressed(signature, &commitment_hash)
+				.and_then(|public_key_bytes| {
+					beefy_primitives::ecdsa_crypto::AuthorityId::from_slice(&public_key_bytes).ok()
+				})
+				.map(|pub_key| {
+					authority_indices.push(*index as usize);
+					H::keccak_256(&beefy_mmr::BeefyEcdsaToEthereum::convert(pub_key))
+				})
+				.ok_or(BeefyClientError::InvalidSignature)
+		})
+		.collect::<Result<Vec<_>, BeefyClientError>>()?;
+
+	// Verify the authority proof against the authority set root hash
+	let authorities_merkle_proof = rs_merkle::MerkleProof::<MerkleHasher<H>>::new(authority_proof);
+	if !authorities_merkle_proof.verify(
+		authority_set.keyset_commitment.into(),
+		&authority_indices,
+		&authority_leaves,
+		authority_set.len as usize,
+	) {
+		return Err(BeefyClientError::InvalidAuthorityProof);
+	}
+
+	Ok(authority_set.clone())
+}
+
 /// Takes the updated client state and parachains headers update proof
 /// and verifies inclusion in mmr
 pub fn verify_parachain_headers<H>(
@@ -209,6 +216,22 @@ where
 		))?
 	}
 
+	// The mmr batch proof only proves a single node per position, so every parachain header must
+	// be proven against its own leaf.
+	let mut leaf_indices = mmr_proof.leaf_indices.clone();
+	leaf_indices.sort_unstable();
+	leaf_indices.dedup();
+	if leaf_indices.len() != mmr_proof.leaf_indices.len() {
+		Err(BeefyClientError::Custom("Duplicate leaf indices in mmr batch proof".to_string()))?
+	}
+
+	if let Some(leaf_index) = leaf_indices.last().filter(|index| **index >= mmr_proof.leaf_count) {
+		Err(BeefyClientError::Custom(format!(
+			"Leaf index {leaf_index} is out of bounds for mmr with {} leaves",
+			mmr_proof.leaf_count
+		)))?
+	}
+
 	for (index, parachain_header) in parachain_headers.into_iter().enumerate() {
 		let decoded_para_header =
 			Header::<u32, BlakeTwo256>::decode(&mut &*parachain_header.parachain_header)?;
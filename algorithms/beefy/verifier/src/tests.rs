@@ -14,19 +14,24 @@
 // limitations under the License.
 
 use beefy_light_client_primitives::{
-	error::BeefyClientError, EncodedVersionedFinalityProof, MmrUpdateProof, ParachainsUpdateProof,
+	error::BeefyClientError, ClientState, EncodedVersionedFinalityProof, MerkleHasher,
+	MmrUpdateProof, ParachainHeader, ParachainsUpdateProof, PartialMmrLeaf,
 	SignatureWithAuthorityIndex, SignedCommitment,
 };
 use beefy_primitives::{
 	known_payloads::MMR_ROOT_ID,
-	mmr::{BeefyNextAuthoritySet, MmrLeaf},
+	mmr::{BeefyNextAuthoritySet, MmrLeaf, MmrLeafVersion},
 	Payload, VersionedFinalityProof,
 };
 use beefy_prover::{Crypto, Prover};
+use codec::{Compact, Encode};
 use futures::stream::StreamExt;
 use hyperspace_core::substrate::DefaultConfig as PolkadotConfig;
+use mmr_lib::util::MemMMR;
 use pallet_mmr_primitives::Proof;
-use sp_core::bytes::to_hex;
+use sp_core::{bytes::to_hex, keccak_256, H256};
+use sp_runtime::{generic::Header, traits::BlakeTwo256};
+use sp_trie::{LayoutV0, MemoryDB, TrieDBMutBuilder, TrieMut};
 use subxt::rpc::{rpc_params, Subscription};
 
 #[tokio::test]
@@ -123,7 +128,7 @@ async fn should_fail_with_incomplete_signature_threshold() {
 			commitment: beefy_primitives::Commitment {
 				payload: Payload::from_single_entry(MMR_ROOT_ID, vec![0u8; 32]),
 				block_number: Default::default(),
-				validator_set_id: 3,
+				validator_set_id: 0,
 			},
 			signatures: vec![SignatureWithAuthorityIndex { index: 0, signature: [0u8; 65] }; 2],
 		},
@@ -155,6 +160,88 @@ async fn should_fail_with_incomplete_signature_threshold() {
 	}
 }
 
+#[tokio::test]
+async fn should_check_signature_threshold_of_the_commitment_authority_set() {
+	let mut client_state = Prover::<PolkadotConfig>::get_initial_client_state(None).await;
+	client_state.next_authorities.len = 10;
+	// enough signatures for the current set, but the commitment claims the larger next set
+	let signatures = (0..4)
+		.map(|index| SignatureWithAuthorityIndex { index, signature: [0u8; 65] })
+		.collect::<Vec<_>>();
+	let mmr_update = MmrUpdateProof {
+		signed_commitment: SignedCommitment {
+			commitment: beefy_primitives::Commitment {
+				payload: Payload::from_single_entry(MMR_ROOT_ID, vec![0u8; 32]),
+				block_number: Default::default(),
+				validator_set_id: client_state.next_authorities.id,
+			},
+			signatures,
+		},
+		latest_mmr_leaf: MmrLeaf {
+			version: Default::default(),
+			parent_number_and_hash: (Default::default(), Default::default()),
+			beefy_next_authority_set: BeefyNextAuthoritySet {
+				id: 0,
+				len: 0,
+				root: Default::default(),
+			},
+			leaf_extra: Default::default(),
+		},
+		mmr_proof: Proof { leaf_indices: vec![0], leaf_count: 0, items: vec![] },
+		authority_proof: vec![],
+	};
+
+	let res = crate::verify_mmr_root_with_proof::<Crypto>(client_state, mmr_update);
+
+	match res {
+		Err(BeefyClientError::IncompleteSignatureThreshold) => {},
+		Err(err) =>
+			panic!("Expected {:?}  found {:?}", BeefyClientError::IncompleteSignatureThreshold, err),
+		Ok(val) =>
+			panic!("Expected {:?}  found {:?}", BeefyClientError::IncompleteSignatureThreshold, val),
+	}
+}
+
+#[tokio::test]
+async fn should_fail_with_duplicate_authority_signatures() {
+	let mmr_update = MmrUpdateProof {
+		signed_commitment: SignedCommitment {
+			commitment: beefy_primitives::Commitment {
+				payload: Payload::from_single_entry(MMR_ROOT_ID, vec![0u8; 32]),
+				block_number: Default::default(),
+				validator_set_id: 0,
+			},
+			// a single authority signing repeatedly can't make up the threshold
+			signatures: vec![SignatureWithAuthorityIndex { index: 0, signature: [0u8; 65] }; 5],
+		},
+		latest_mmr_leaf: MmrLeaf {
+			version: Default::default(),
+			parent_number_and_hash: (Default::default(), Default::default()),
+			beefy_next_authority_set: BeefyNextAuthoritySet {
+				id: 0,
+				len: 0,
+				root: Default::default(),
+			},
+			leaf_extra: Default::default(),
+		},
+		mmr_proof: Proof { leaf_indices: vec![0], leaf_count: 0, items: vec![] },
+		authority_proof: vec![],
+	};
+
+	let res = crate::verify_mmr_root_with_proof::<Crypto>(
+		Prover::<PolkadotConfig>::get_initial_client_state(None).await,
+		mmr_update,
+	);
+
+	match res {
+		Err(BeefyClientError::DuplicateAuthoritySignature) => {},
+		Err(err) =>
+			panic!("Expected {:?}  found {:?}", BeefyClientError::DuplicateAuthoritySignature, err),
+		Ok(val) =>
+			panic!("Expected {:?}  found {:?}", BeefyClientError::DuplicateAuthoritySignature, val),
+	}
+}
+
 #[tokio::test]
 async fn should_fail_with_invalid_validator_set_id() {
 	let mmr_update = MmrUpdateProof {
@@ -296,3 +383,145 @@ async fn verify_parachain_headers() {
 		);
 	}
 }
+
+const PARA_ID: u32 = 2000;
+
+/// Builds a parachain header with a timestamp extrinsic proof, along with the mmr leaf of the
+/// relay chain block it was included in.
+fn parachain_header_with_leaf(
+	relay_block: u32,
+) -> (ParachainHeader, MmrLeaf<u32, H256, H256, H256>) {
+	let timestamp_ext_key = Compact(0u64).encode();
+	let timestamp_extrinsic = (relay_block as u64 * 6000).encode();
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let mut extrinsics_root = Default::default();
+	{
+		let mut trie =
+			TrieDBMutBuilder::<LayoutV0<BlakeTwo256>>::new(&mut db, &mut extrinsics_root).build();
+		trie.insert(&timestamp_ext_key, &timestamp_extrinsic).unwrap();
+	}
+	let extrinsic_proof = sp_trie::generate_trie_proof::<LayoutV0<BlakeTwo256>, _, _, _>(
+		&db,
+		extrinsics_root,
+		&[timestamp_ext_key],
+	)
+	.unwrap();
+
+	let header = Header::<u32, BlakeTwo256> {
+		parent_hash: Default::default(),
+		number: relay_block,
+		state_root: Default::default(),
+		extrinsics_root,
+		digest: Default::default(),
+	}
+	.encode();
+	let heads_leaf = keccak_256(&(PARA_ID, header.clone()).encode());
+	let heads_tree = rs_merkle::MerkleTree::<MerkleHasher<Crypto>>::from_leaves(&[heads_leaf]);
+
+	let mmr_leaf = MmrLeaf {
+		version: MmrLeafVersion::new(0, 0),
+		parent_number_and_hash: (relay_block - 1, H256::repeat_byte(relay_block as u8)),
+		beefy_next_authority_set: BeefyNextAuthoritySet {
+			id: 1,
+			len: 5,
+			keyset_commitment: Default::default(),
+		},
+		leaf_extra: H256::from(heads_tree.root().unwrap()),
+	};
+	let parachain_header = ParachainHeader {
+		parachain_header: header,
+		partial_mmr_leaf: PartialMmrLeaf {
+			version: mmr_leaf.version,
+			parent_number_and_hash: mmr_leaf.parent_number_and_hash,
+			beefy_next_authority_set: mmr_leaf.beefy_next_authority_set.clone(),
+		},
+		para_id: PARA_ID,
+		parachain_heads_proof: heads_tree.proof(&[0]).proof_hashes().to_vec(),
+		heads_leaf_index: 0,
+		heads_total_count: 1,
+		extrinsic_proof,
+		timestamp_extrinsic,
+	};
+
+	(parachain_header, mmr_leaf)
+}
+
+/// Builds an mmr over `leaf_count` relay chain blocks and a batch proof for the parachain
+/// headers included at `leaf_indices`.
+fn parachain_headers_with_batch_proof(
+	leaf_count: u64,
+	leaf_indices: &[u64],
+) -> (ClientState, ParachainsUpdateProof) {
+	let mut mmr = MemMMR::<H256, MerkleHasher<Crypto>>::default();
+	let mut headers = vec![];
+	let mut positions = vec![];
+	for leaf_index in 0..leaf_count {
+		let (header, leaf) = parachain_header_with_leaf(leaf_index as u32 + 1);
+		let pos = mmr.push(H256::from(keccak_256(&leaf.encode()))).unwrap();
+		if leaf_indices.contains(&leaf_index) {
+			headers.push(header);
+			positions.push(pos);
+		}
+	}
+	let proof = mmr.gen_proof(positions).unwrap();
+
+	let client_state = ClientState {
+		latest_beefy_height: leaf_count as u32,
+		mmr_root_hash: mmr.get_root().unwrap(),
+		current_authorities: BeefyNextAuthoritySet {
+			id: 0,
+			len: 5,
+			keyset_commitment: Default::default(),
+		},
+		next_authorities: BeefyNextAuthoritySet {
+			id: 1,
+			len: 5,
+			keyset_commitment: Default::default(),
+		},
+	};
+	let update = ParachainsUpdateProof {
+		parachain_headers: headers,
+		mmr_proof: Proof {
+			leaf_indices: leaf_indices.to_vec(),
+			leaf_count,
+			items: proof.proof_items().to_vec(),
+		},
+	};
+
+	(client_state, update)
+}
+
+#[test]
+fn should_verify_parachain_headers_with_batch_proof() {
+	let (client_state, update) = parachain_headers_with_batch_proof(20, &[2, 7, 8, 15]);
+	crate::verify_parachain_headers::<Crypto>(client_state, update).unwrap();
+
+	let (client_state, update) = parachain_headers_with_batch_proof(20, &[19]);
+	crate::verify_parachain_headers::<Crypto>(client_state, update).unwrap();
+}
+
+#[test]
+fn should_fail_with_invalid_batch_proof() {
+	// leaf indices don't match the proven headers
+	let (client_state, mut update) = parachain_headers_with_batch_proof(20, &[2, 7, 8]);
+	update.mmr_proof.leaf_indices = vec![2, 8, 7];
+	assert!(crate::verify_parachain_headers::<Crypto>(client_state, update).is_err());
+
+	// a header proven against the leaf of another header
+	let (client_state, mut update) = parachain_headers_with_batch_proof(20, &[2, 7]);
+	let (forged_header, _) = parachain_header_with_leaf(30);
+	update.parachain_headers.push(forged_header);
+	update.mmr_proof.leaf_indices.push(7);
+	match crate::verify_parachain_headers::<Crypto>(client_state, update) {
+		Err(BeefyClientError::Custom(_)) => {},
+		res => panic!("Expected duplicate leaf indices error, found {res:?}"),
+	}
+
+	// leaf index outside of the mmr
+	let (client_state, mut update) = parachain_headers_with_batch_proof(20, &[2, 7]);
+	update.mmr_proof.leaf_indices[1] = 20;
+	match crate::verify_parachain_headers::<Crypto>(client_state, update) {
+		Err(BeefyClientError::Custom(_)) => {},
+		res => panic!("Expected out of bounds leaf index error, found {res:?}"),
+	}
+}
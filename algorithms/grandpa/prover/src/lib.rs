@@ -66,6 +66,9 @@ pub struct GrandpaProver<T: Config> {
 	pub para_id: u32,
 	/// Delay between rpc calls to the RPC
 	pub rpc_call_delay: Duration,
+	/// Whether the "relay chain" is a standalone chain following its own GRANDPA finality, in
+	/// which case both clients point to the same chain and its headers are proven directly.
+	pub solo_chain: bool,
 }
 
 // We redefine these here because we want the header to be bounded by subxt::config::Header in the
@@ -98,6 +101,7 @@ impl<T: Config> Clone for GrandpaProver<T> {
 			para_ws_client: self.para_ws_client.clone(),
 			para_id: self.para_id,
 			rpc_call_delay: self.rpc_call_delay,
+			solo_chain: self.solo_chain,
 		}
 	}
 }
@@ -128,6 +132,7 @@ where
 			para_client,
 			para_id,
 			rpc_call_delay,
+			solo_chain: false,
 		})
 	}
 
@@ -203,6 +208,8 @@ where
 	}
 
 	/// Returns the latest finalized parachain header at the given finalized relay chain height.
+	///
+	/// For a solo chain, this is the header at the given height itself.
 	pub async fn query_latest_finalized_parachain_header(
 		&self,
 		latest_finalized_height: u32,
@@ -216,6 +223,14 @@ where
 			.block_hash(Some(latest_finalized_height.into()))
 			.await?
 			.ok_or_else(|| anyhow!("Block hash not found for number: {latest_finalized_height}"))?;
+		if self.solo_chain {
+			return self
+				.relay_client
+				.rpc()
+				.header(Some(latest_finalized_hash))
+				.await?
+				.ok_or_else(|| anyhow!("Header not found for hash: {latest_finalized_hash:?}"))
+		}
 		let key = T::Storage::paras_heads(self.para_id);
 		let header = <T::Storage as RuntimeStorage>::HeadData::from_inner(
 			self.relay_client
@@ -332,6 +347,18 @@ where
 			}
 		}
 
+		unknown_headers.sort_by_key(|header| header.number());
+
+		if self.solo_chain {
+			return self
+				.query_finalized_solo_chain_headers_with_proof(
+					finality_proof,
+					unknown_headers,
+					header_numbers,
+				)
+				.await
+		}
+
 		// we are interested only in the blocks where our parachain header changes.
 		let para_storage_key = parachain_header_storage_key(self.para_id);
 		let keys = vec![para_storage_key.as_ref()];
@@ -427,7 +454,6 @@ where
 			}
 		}
 
-		unknown_headers.sort_by_key(|header| header.number());
 		// overwrite unknown headers
 		finality_proof.unknown_headers = unknown_headers;
 
@@ -438,6 +464,49 @@ where
 		})
 	}
 
+	/// Proves the timestamp extrinsics of the solo chain headers with the given `header_numbers`
+	/// among the `unknown_headers` finalized by `finality_proof`. The headers are finalized by the
+	/// chain's own justifications, so they are keyed by their own hash and carry no state proof.
+	async fn query_finalized_solo_chain_headers_with_proof<H>(
+		&self,
+		mut finality_proof: FinalityProof<H>,
+		unknown_headers: Vec<H>,
+		header_numbers: Vec<<<T as subxt::Config>::Header as Header>::Number>,
+	) -> Result<ParachainHeadersWithFinalityProof<H>, anyhow::Error>
+	where
+		H: Header + codec::Decode + Send + 'static,
+		u32: From<<H as Header>::Number>,
+		T::Hash: From<<H::Hasher as subxt::config::Hasher>::Output>,
+	{
+		let header_numbers = header_numbers.into_iter().map(u32::from).collect::<BTreeSet<u32>>();
+		let mut headers_with_proof = BTreeMap::<H256, ParachainHeaderProofs>::default();
+		let mut latest_para_height = 0u32;
+		for header in &unknown_headers {
+			let number = u32::from(header.number());
+			// skip genesis header or any unknown headers
+			if number == 0 || !header_numbers.contains(&number) {
+				continue
+			}
+
+			let hash = T::Hash::from(header.hash());
+			let TimeStampExtWithProof { ext: extrinsic, proof: extrinsic_proof } =
+				fetch_timestamp_extrinsic_with_proof(&self.para_client, Some(hash))
+					.await
+					.map_err(|err| anyhow!("Error fetching timestamp with proof: {err:?}"))?;
+			let proofs = ParachainHeaderProofs { state_proof: vec![], extrinsic, extrinsic_proof };
+			latest_para_height = latest_para_height.max(number);
+			headers_with_proof.insert(H256::from(hash), proofs);
+		}
+
+		finality_proof.unknown_headers = unknown_headers;
+
+		Ok(ParachainHeadersWithFinalityProof {
+			finality_proof,
+			parachain_headers: headers_with_proof,
+			latest_para_height,
+		})
+	}
+
 	/// Queries the block at which the epoch for the given block belongs to ends.
 	pub async fn session_start_and_end_for_block(
 		&self,
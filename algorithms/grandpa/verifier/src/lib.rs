@@ -21,7 +21,7 @@
 
 extern crate alloc;
 
-use alloc::vec;
+use alloc::{vec, vec::Vec};
 use anyhow::anyhow;
 use codec::{Decode, Encode};
 use finality_grandpa::Chain;
@@ -30,7 +30,7 @@ use light_client_common::state_machine;
 use primitives::{
 	error,
 	justification::{find_scheduled_change, AncestryChain, GrandpaJustification},
-	parachain_header_storage_key, ClientState, HostFunctions, ParachainHeaderProofs,
+	parachain_header_storage_key, ClientState, FinalityProof, HostFunctions, ParachainHeaderProofs,
 	ParachainHeadersWithFinalityProof,
 };
 use sp_core::H256;
@@ -45,7 +45,67 @@ mod tests;
 /// Next, we prove the finality of parachain headers, by verifying patricia-merkle trie state proofs
 /// of these headers, stored at the recently finalized relay chain heights.
 pub fn verify_parachain_headers_with_grandpa_finality_proof<H, Host>(
-	mut client_state: ClientState,
+	client_state: ClientState,
+	proof: ParachainHeadersWithFinalityProof<H>,
+) -> Result<ClientState, error::Error>
+where
+	H: Header<Hash = H256, Number = u32>,
+	H::Number: finality_grandpa::BlockNumberOps + Into<u32>,
+	Host: HostFunctions,
+	Host::BlakeTwo256: Hasher<Out = H256>,
+{
+	let ParachainHeadersWithFinalityProof { finality_proof, parachain_headers, latest_para_height } =
+		proof;
+
+	// 1 & 2. validate unknown headers and verify justification.
+	let (headers, finalized) = verify_finality_proof::<H, Host>(&client_state, &finality_proof)?;
+
+	// 3. verify state proofs of parachain headers in finalized relay chain headers.
+	let mut para_heights = vec![];
+	for (hash, proofs) in parachain_headers {
+		if finalized.binary_search(&hash).is_err() {
+			// seems relay hash isn't in the finalized chain.
+			continue
+		}
+		let relay_chain_header =
+			headers.header(&hash).expect("Headers have been checked by AncestryChain; qed");
+
+		let ParachainHeaderProofs { extrinsic_proof, extrinsic, state_proof } = proofs;
+		let proof = StorageProof::new(state_proof);
+		let key = parachain_header_storage_key(client_state.para_id);
+		// verify patricia-merkle state proofs
+		let header = state_machine::read_proof_check::<Host::BlakeTwo256, _>(
+			relay_chain_header.state_root(),
+			proof,
+			&[key.as_ref()],
+		)
+		.map_err(|err| anyhow!("error verifying parachain header state proof: {err}"))?
+		.remove(key.as_ref())
+		.flatten()
+		.ok_or_else(|| anyhow!("Invalid proof, parachain header not found"))?;
+		let parachain_header = H::decode(&mut &header[..])?;
+		para_heights.push(parachain_header.number().clone().into());
+		verify_timestamp_extrinsic::<H, Host>(&parachain_header, &extrinsic, &extrinsic_proof)?;
+	}
+
+	// 4. set new client state, optionally rotating authorities
+	update_client_state(
+		client_state,
+		&headers,
+		&finality_proof.block,
+		para_heights,
+		latest_para_height,
+	)
+}
+
+/// This function verifies the GRANDPA finality proof of a standalone chain's own headers.
+///
+/// Standalone chains aren't secured by a relay chain, so the headers in the finality proof are
+/// the chain's own headers and the ones finalized by it are proven directly, only their timestamp
+/// extrinsics need to be checked against the header's extrinsics root. `parachain_headers` is
+/// keyed by the hashes of these headers and its state proofs are left empty.
+pub fn verify_solo_chain_headers_with_grandpa_finality_proof<H, Host>(
+	client_state: ClientState,
 	proof: ParachainHeadersWithFinalityProof<H>,
 ) -> Result<ClientState, error::Error>
 where
@@ -57,6 +117,43 @@ where
 	let ParachainHeadersWithFinalityProof { finality_proof, parachain_headers, latest_para_height } =
 		proof;
 
+	// 1 & 2. validate unknown headers and verify justification.
+	let (headers, finalized) = verify_finality_proof::<H, Host>(&client_state, &finality_proof)?;
+
+	// 3. verify timestamp extrinsics of the finalized headers.
+	let mut heights = vec![];
+	for (hash, proofs) in parachain_headers {
+		if finalized.binary_search(&hash).is_err() {
+			// seems header isn't in the finalized chain.
+			continue
+		}
+		let header = headers
+			.header(&hash)
+			.ok_or_else(|| anyhow!("No header found for hash: {hash:?}"))?;
+
+		if !proofs.state_proof.is_empty() {
+			Err(anyhow!("Headers of a solo chain don't have state proofs"))?;
+		}
+		heights.push(header.number().clone().into());
+		verify_timestamp_extrinsic::<H, Host>(header, &proofs.extrinsic, &proofs.extrinsic_proof)?;
+	}
+
+	// 4. set new client state, optionally rotating authorities
+	update_client_state(client_state, &headers, &finality_proof.block, heights, latest_para_height)
+}
+
+/// Validates the unknown headers of the finality proof against the client state and verifies the
+/// justification of its target. Returns the ancestry of the unknown headers along with the sorted
+/// hashes of the headers finalized by the proof.
+fn verify_finality_proof<H, Host>(
+	client_state: &ClientState,
+	finality_proof: &FinalityProof<H>,
+) -> Result<(AncestryChain<H>, Vec<H256>), error::Error>
+where
+	H: Header<Hash = H256, Number = u32>,
+	H::Number: finality_grandpa::BlockNumberOps + Into<u32>,
+	Host: HostFunctions,
+{
 	// 1. First validate unknown headers.
 	let headers = AncestryChain::<H>::new(&finality_proof.unknown_headers);
 
@@ -101,53 +198,56 @@ where
 	// 2. verify justification.
 	justification.verify::<Host>(client_state.current_set_id, &client_state.current_authorities)?;
 
-	// 3. verify state proofs of parachain headers in finalized relay chain headers.
-	let mut para_heights = vec![];
-	for (hash, proofs) in parachain_headers {
-		if finalized.binary_search(&hash).is_err() {
-			// seems relay hash isn't in the finalized chain.
-			continue
-		}
-		let relay_chain_header =
-			headers.header(&hash).expect("Headers have been checked by AncestryChain; qed");
+	Ok((headers, finalized))
+}
 
-		let ParachainHeaderProofs { extrinsic_proof, extrinsic, state_proof } = proofs;
-		let proof = StorageProof::new(state_proof);
-		let key = parachain_header_storage_key(client_state.para_id);
-		// verify patricia-merkle state proofs
-		let header = state_machine::read_proof_check::<Host::BlakeTwo256, _>(
-			relay_chain_header.state_root(),
-			proof,
-			&[key.as_ref()],
-		)
-		.map_err(|err| anyhow!("error verifying parachain header state proof: {err}"))?
-		.remove(key.as_ref())
-		.flatten()
-		.ok_or_else(|| anyhow!("Invalid proof, parachain header not found"))?;
-		let parachain_header = H::decode(&mut &header[..])?;
-		para_heights.push(parachain_header.number().clone().into());
-		// Timestamp extrinsic should be the first inherent and hence the first extrinsic
-		// https://github.com/paritytech/substrate/blob/d602397a0bbb24b5d627795b797259a44a5e29e9/primitives/trie/src/lib.rs#L99-L101
-		let key = codec::Compact(0u64).encode();
-		// verify extrinsic proof for timestamp extrinsic
-		sp_trie::verify_trie_proof::<LayoutV0<Host::BlakeTwo256>, _, _, _>(
-			parachain_header.extrinsics_root(),
-			&extrinsic_proof,
-			&vec![(key, Some(&extrinsic[..]))],
-		)
-		.map_err(|_| anyhow!("Invalid extrinsic proof"))?;
-	}
+/// Verifies the proof of the timestamp extrinsic against the extrinsics root of the given header.
+fn verify_timestamp_extrinsic<H, Host>(
+	header: &H,
+	extrinsic: &[u8],
+	extrinsic_proof: &[Vec<u8>],
+) -> Result<(), error::Error>
+where
+	H: Header<Hash = H256>,
+	Host: HostFunctions,
+	Host::BlakeTwo256: Hasher<Out = H256>,
+{
+	// Timestamp extrinsic should be the first inherent and hence the first extrinsic
+	// https://github.com/paritytech/substrate/blob/d602397a0bbb24b5d627795b797259a44a5e29e9/primitives/trie/src/lib.rs#L99-L101
+	let key = codec::Compact(0u64).encode();
+	// verify extrinsic proof for timestamp extrinsic
+	sp_trie::verify_trie_proof::<LayoutV0<Host::BlakeTwo256>, _, _, _>(
+		header.extrinsics_root(),
+		extrinsic_proof,
+		&vec![(key, Some(extrinsic))],
+	)
+	.map_err(|_| anyhow!("Invalid extrinsic proof"))?;
 
-	// 4. set new client state, optionally rotating authorities
+	Ok(())
+}
+
+/// Moves the client state to the target of the finality proof, rotating the authorities if the
+/// target schedules a change.
+fn update_client_state<H>(
+	mut client_state: ClientState,
+	headers: &AncestryChain<H>,
+	target: &H256,
+	heights: Vec<u32>,
+	latest_para_height: u32,
+) -> Result<ClientState, error::Error>
+where
+	H: Header<Hash = H256, Number = u32>,
+{
+	let target = headers.header(target).expect("Target has been checked by AncestryChain; qed");
 	client_state.latest_relay_hash = target.hash();
-	client_state.latest_relay_height = (*target.number()).into();
-	if let Some(max_height) = para_heights.into_iter().max() {
+	client_state.latest_relay_height = *target.number();
+	if let Some(max_height) = heights.into_iter().max() {
 		if max_height != latest_para_height {
 			Err(anyhow!("Latest parachain header height doesn't match the one in the proof"))?;
 		}
 		client_state.latest_para_height = max_height;
 	}
-	if let Some(scheduled_change) = find_scheduled_change::<H>(&target) {
+	if let Some(scheduled_change) = find_scheduled_change::<H>(target) {
 		client_state.current_set_id += 1;
 		client_state.current_authorities = scheduled_change.next_authorities;
 	}
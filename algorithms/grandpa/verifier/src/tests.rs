@@ -13,8 +13,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::verify_parachain_headers_with_grandpa_finality_proof;
-use codec::{Decode, Encode};
+use crate::{
+	verify_parachain_headers_with_grandpa_finality_proof,
+	verify_solo_chain_headers_with_grandpa_finality_proof,
+};
+use codec::{Compact, Decode, Encode};
+use finality_grandpa::{Precommit, SignedPrecommit};
 use futures::StreamExt;
 use grandpa_prover::{
 	beefy_prover::helpers::unsafe_arc_cast, host_functions::HostFunctionsProvider, GrandpaProver,
@@ -22,11 +26,21 @@ use grandpa_prover::{
 use hyperspace_core::substrate::DefaultConfig as PolkadotConfig;
 use polkadot_core_primitives::Header;
 use primitives::{
-	justification::GrandpaJustification, FinalityProof, ParachainHeadersWithFinalityProof,
+	justification::GrandpaJustification, ClientState, Commit, FinalityProof, ParachainHeaderProofs,
+	ParachainHeadersWithFinalityProof,
 };
 use serde::{Deserialize, Serialize};
-use sp_core::H256;
-use std::time::Duration;
+use sp_consensus_grandpa::{
+	AuthorityId, AuthorityList, AuthoritySignature, ConsensusLog, ScheduledChange,
+	GRANDPA_ENGINE_ID,
+};
+use sp_core::{ed25519, Pair, H256};
+use sp_runtime::{
+	generic::{Digest, DigestItem},
+	traits::Header as _,
+};
+use sp_trie::{generate_trie_proof, LayoutV0, MemoryDB, TrieDBMutBuilder, TrieMut};
+use std::{collections::BTreeMap, time::Duration};
 use subxt::{
 	config::substrate::{BlakeTwo256, SubstrateHeader},
 	rpc_params,
@@ -151,3 +165,198 @@ async fn follow_grandpa_justifications() {
 		println!("========= Successfully verified grandpa justification =========");
 	}
 }
+
+const SET_ID: u64 = 1;
+const ROUND: u64 = 1;
+
+fn authority_keys(seed: &str) -> Vec<ed25519::Pair> {
+	(1..=3)
+		.map(|i| ed25519::Pair::from_string(&format!("//{seed}{i}"), None).unwrap())
+		.collect()
+}
+
+fn authority_list(keys: &[ed25519::Pair]) -> AuthorityList {
+	keys.iter().map(|key| (AuthorityId::from(key.public()), 100)).collect()
+}
+
+/// Builds `count` headers of a solo chain on top of the latest finalized header of the returned
+/// client state, the last one finalized by a justification of the current authority set and
+/// carrying the given digest. Every header is proven along with its timestamp extrinsic.
+fn solo_chain_finality_proof(
+	count: u32,
+	digest: Digest,
+) -> (ClientState, ParachainHeadersWithFinalityProof<Header>) {
+	let keys = authority_keys("Authority");
+	let client_state = ClientState {
+		current_authorities: authority_list(&keys),
+		current_set_id: SET_ID,
+		latest_relay_height: 1,
+		latest_para_height: 1,
+		latest_relay_hash: H256::repeat_byte(1),
+		para_id: 2000,
+	};
+
+	// the timestamp extrinsic is prefixed with its length and version
+	let mut extrinsic = (1u8, 0u8, Compact(1_650_894_363_000u64)).encode();
+	extrinsic.splice(0..0, [0u8, 0u8]);
+	let key = Compact(0u64).encode();
+	let mut db = MemoryDB::<sp_runtime::traits::BlakeTwo256>::default();
+	let extrinsics_root = {
+		let mut root = Default::default();
+		let mut trie =
+			TrieDBMutBuilder::<LayoutV0<sp_runtime::traits::BlakeTwo256>>::new(&mut db, &mut root)
+				.build();
+		trie.insert(&key, &extrinsic).unwrap();
+		*trie.root()
+	};
+	let extrinsic_proof =
+		generate_trie_proof::<LayoutV0<sp_runtime::traits::BlakeTwo256>, _, _, _>(
+			&db,
+			extrinsics_root,
+			vec![&key],
+		)
+		.unwrap();
+
+	let mut parent_hash = client_state.latest_relay_hash;
+	let mut headers = vec![];
+	for i in 1..=count {
+		let header = Header {
+			parent_hash,
+			number: client_state.latest_relay_height + i,
+			state_root: H256::repeat_byte(i as u8),
+			extrinsics_root,
+			digest: if i == count { digest.clone() } else { Default::default() },
+		};
+		parent_hash = header.hash();
+		headers.push(header);
+	}
+
+	let target = headers.last().unwrap().clone();
+	let precommit = Precommit { target_hash: target.hash(), target_number: target.number };
+	let message = finality_grandpa::Message::Precommit(precommit.clone());
+	let payload = sp_consensus_grandpa::localized_payload(ROUND, SET_ID, &message);
+	let precommits = keys
+		.iter()
+		.map(|key| SignedPrecommit {
+			precommit: precommit.clone(),
+			signature: AuthoritySignature::from(key.sign(&payload)),
+			id: AuthorityId::from(key.public()),
+		})
+		.collect();
+	let justification = GrandpaJustification::<Header> {
+		round: ROUND,
+		commit: Commit::<Header> {
+			target_hash: target.hash(),
+			target_number: target.number,
+			precommits,
+		},
+		votes_ancestries: vec![],
+	};
+
+	let parachain_headers = headers
+		.iter()
+		.map(|header| {
+			let proofs = ParachainHeaderProofs {
+				state_proof: vec![],
+				extrinsic: extrinsic.clone(),
+				extrinsic_proof: extrinsic_proof.clone(),
+			};
+			(header.hash(), proofs)
+		})
+		.collect::<BTreeMap<_, _>>();
+
+	let proof = ParachainHeadersWithFinalityProof {
+		finality_proof: FinalityProof {
+			block: target.hash(),
+			justification: justification.encode(),
+			unknown_headers: headers,
+		},
+		parachain_headers,
+		latest_para_height: target.number,
+	};
+
+	(client_state, proof)
+}
+
+#[test]
+fn verify_solo_chain_headers() {
+	let (client_state, proof) = solo_chain_finality_proof(3, Default::default());
+	let target = proof.finality_proof.block;
+
+	let client_state = verify_solo_chain_headers_with_grandpa_finality_proof::<
+		Header,
+		HostFunctionsProvider,
+	>(client_state, proof)
+	.unwrap();
+
+	assert_eq!(client_state.latest_relay_hash, target);
+	assert_eq!(client_state.latest_relay_height, 4);
+	assert_eq!(client_state.latest_para_height, 4);
+	assert_eq!(client_state.current_set_id, SET_ID);
+}
+
+#[test]
+fn solo_chain_headers_outside_the_finalized_chain_are_ignored() {
+	let (client_state, mut proof) = solo_chain_finality_proof(3, Default::default());
+	let target = proof.finality_proof.block;
+
+	// a proof for a header of another fork isn't used to update the client
+	let fork = Header {
+		parent_hash: client_state.latest_relay_hash,
+		number: client_state.latest_relay_height + 1,
+		state_root: H256::repeat_byte(0xff),
+		extrinsics_root: Default::default(),
+		digest: Default::default(),
+	};
+	let proofs = proof.parachain_headers.values().next().unwrap().clone();
+	proof.parachain_headers = BTreeMap::from([(fork.hash(), proofs)]);
+
+	let client_state = verify_solo_chain_headers_with_grandpa_finality_proof::<
+		Header,
+		HostFunctionsProvider,
+	>(client_state, proof)
+	.unwrap();
+
+	assert_eq!(client_state.latest_relay_hash, target);
+	assert_eq!(client_state.latest_relay_height, 4);
+	assert_eq!(client_state.latest_para_height, 1);
+}
+
+#[test]
+fn solo_chain_headers_with_state_proofs_are_rejected() {
+	let (client_state, mut proof) = solo_chain_finality_proof(3, Default::default());
+	for proofs in proof.parachain_headers.values_mut() {
+		proofs.state_proof = vec![vec![0u8; 32]];
+	}
+
+	let err =
+		verify_solo_chain_headers_with_grandpa_finality_proof::<Header, HostFunctionsProvider>(
+			client_state,
+			proof,
+		)
+		.err()
+		.unwrap();
+
+	assert!(err.to_string().contains("don't have state proofs"), "unexpected error: {err}");
+}
+
+#[test]
+fn solo_chain_authority_set_change_is_applied() {
+	let next_authorities = authority_list(&authority_keys("NextAuthority"));
+	let change = ConsensusLog::<u32>::ScheduledChange(ScheduledChange {
+		next_authorities: next_authorities.clone(),
+		delay: 0,
+	});
+	let digest = Digest { logs: vec![DigestItem::Consensus(GRANDPA_ENGINE_ID, change.encode())] };
+	let (client_state, proof) = solo_chain_finality_proof(3, digest);
+
+	let client_state = verify_solo_chain_headers_with_grandpa_finality_proof::<
+		Header,
+		HostFunctionsProvider,
+	>(client_state, proof)
+	.unwrap();
+
+	assert_eq!(client_state.current_set_id, SET_ID + 1);
+	assert_eq!(client_state.current_authorities, next_authorities);
+	assert_eq!(client_state.latest_para_height, 4);
+}
@@ -329,6 +329,8 @@ pub enum Error {
 	DecodingError { msg: Option<String> },
 	/// Failed to decode commitment prefix
 	ErrorDecodingPrefix,
+	/// The transfer exceeds the rate limit of the channel
+	RateLimitExceeded { msg: Option<String> },
 	/// Some other error
 	Other { msg: Option<String> },
 }
@@ -353,6 +355,7 @@ impl TryFrom<&OpenChannelParams> for Order {
 		match value.order {
 			1 => Ok(Order::Unordered),
 			2 => Ok(Order::Ordered),
+			3 => Ok(Order::OrderedAllowTimeout),
 			_ => Err(Error::Other { msg: None }),
 		}
 	}
@@ -507,6 +510,19 @@ pub fn get_channel_escrow_address(
 	hex_string.parse::<Signer>().map_err(Ics20Error::signer)
 }
 
+/// Address holding the tokens received on a channel while they are forwarded to the next hop.
+pub fn get_forward_address(port_id: &PortId, channel_id: ChannelId) -> Result<Signer, Ics20Error> {
+	let contents = format!("forward/{port_id}/{channel_id}");
+	let mut data = VERSION.as_bytes().to_vec();
+	data.extend_from_slice(&[0]);
+	data.extend_from_slice(contents.as_bytes());
+
+	let hash = sp_io::hashing::sha2_256(&data).to_vec();
+	let mut hex_string = hex::encode_upper(hash);
+	hex_string.insert_str(0, "0x");
+	hex_string.parse::<Signer>().map_err(Ics20Error::signer)
+}
+
 // This is needed because Ics20 traits require an implementation of TryFrom<Signer> for AccountId
 // associated type
 #[derive(Clone)]
@@ -27,7 +27,7 @@ use ibc::{
 	core::{
 		ics02_client::{
 			client_state::ClientState,
-			context::ClientKeeper,
+			context::{ClientKeeper, ClientReader},
 			height::Height,
 			msgs::{
 				create_client::{MsgCreateAnyClient, TYPE_URL},
@@ -1377,6 +1377,34 @@ benchmarks! {
 			assert_eq!(last_removed_ack, i - 1);
 		}
 	}
+
+	prune_consensus_states {
+		let i in 1..crate::client::MAX_PRUNED_CONSENSUS_STATES;
+		let mut ctx = routing::Context::<T>::new();
+		let now: <T as pallet_timestamp::Config>::Moment = TENDERMINT_TIMESTAMP.saturating_mul(1000).saturating_add(1_000_000);
+		set_timestamp::<T>(now);
+
+		let (mut mock_client_state, mock_cs_state) = create_mock_state();
+		mock_client_state.latest_height = Height::new(0, i as u64 + 1);
+		let mock_client_state = AnyClientState::Tendermint(mock_client_state);
+		let mock_cs_state = AnyConsensusState::Tendermint(mock_cs_state);
+		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		ctx.store_client_type(client_id.clone(), mock_client_state.client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), mock_client_state).unwrap();
+		for height in 1..=i {
+			ctx.store_consensus_state(client_id.clone(), Height::new(0, height as u64), mock_cs_state.clone()).unwrap();
+		}
+		assert_eq!(ConsensusHeights::<T>::get(client_id.as_bytes().to_vec()).len(), i as usize);
+		let client_state = ctx.client_state(&client_id).unwrap();
+
+		// all of the consensus states are well past the trusting period
+		let year = 365 * 24 * 60 * 60 * 1000;
+		pallet_timestamp::Pallet::<T>::set_timestamp(now.saturating_add(year));
+	}: { ctx.prune_expired_consensus_states(&client_id, &client_state).unwrap() }
+	verify {
+		assert!(ConsensusHeights::<T>::get(client_id.as_bytes().to_vec()).is_empty());
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 1)).is_err());
+	}
 }
 
 fn set_timestamp<T: pallet_timestamp::Config + pallet_aura::Config>(
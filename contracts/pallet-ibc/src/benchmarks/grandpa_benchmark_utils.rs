@@ -158,6 +158,7 @@ pub fn generate_finality_proof(
 		para_id,
 		current_set_id: set_id,
 		current_authorities: authorities.into_iter().map(|authority| (authority, 100)).collect(),
+		solo_chain: false,
 		_phantom: Default::default(),
 	};
 
@@ -7,9 +7,14 @@ use sp_core::crypto::AccountId32;
 
 use crate::{
 	ics23::{
-		acknowledgements::Acknowledgements, channels::Channels, next_seq_ack::NextSequenceAck,
-		next_seq_recv::NextSequenceRecv, next_seq_send::NextSequenceSend,
-		packet_commitments::PacketCommitment, receipts::PacketReceipt,
+		acknowledgements::Acknowledgements,
+		channel_upgrades::{ChannelUpgradeErrors, ChannelUpgrades},
+		channels::Channels,
+		next_seq_ack::NextSequenceAck,
+		next_seq_recv::NextSequenceRecv,
+		next_seq_send::NextSequenceSend,
+		packet_commitments::PacketCommitment,
+		receipts::PacketReceipt,
 	},
 	impls::host_height,
 	routing::Context,
@@ -24,6 +29,7 @@ use ibc::{
 			error::Error as ICS04Error,
 			msgs::acknowledgement::Acknowledgement,
 			packet::{Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
@@ -161,6 +167,7 @@ where
 			})?;
 			let data = match data.as_ref() {
 				"Ok" => Receipt::Ok,
+				"Timeout" => Receipt::Timeout,
 				_ => return Err(ICS04Error::packet_receipt_not_found(seq.into())),
 			};
 			log::trace!(target: "pallet_ibc", "in channel : [get_packet_receipt] >> packet_receipt = {:?}", data);
@@ -257,6 +264,62 @@ where
 		let expected = T::ExpectedBlockTime::get();
 		Duration::from_millis(expected)
 	}
+
+	fn channel_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, ICS04Error> {
+		log::trace!(target: "pallet_ibc",
+			"in channel : [channel_upgrade] >> port_id = {:?}, channel_id = {:?}",
+			port_channel_id.0,
+			port_channel_id.1
+		);
+		let data = <ChannelUpgrades<T>>::get(port_channel_id.0.clone(), port_channel_id.1)
+			.ok_or_else(|| {
+				ICS04Error::upgrade_not_found(port_channel_id.0.clone(), port_channel_id.1)
+			})?;
+		Upgrade::decode_vec(&data)
+			.map_err(|e| ICS04Error::implementation_specific(format!("[channel_upgrade]: {e:?}")))
+	}
+
+	fn counterparty_channel_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, ICS04Error> {
+		let key = (
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+		);
+		let data = <CounterpartyChannelUpgrades<T>>::get(key).ok_or_else(|| {
+			ICS04Error::upgrade_not_found(port_channel_id.0.clone(), port_channel_id.1)
+		})?;
+		Upgrade::decode_vec(&data).map_err(|e| {
+			ICS04Error::implementation_specific(format!("[counterparty_channel_upgrade]: {e:?}"))
+		})
+	}
+
+	fn channel_upgrade_error(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, ICS04Error> {
+		let data = <ChannelUpgradeErrors<T>>::get(port_channel_id.0.clone(), port_channel_id.1)
+			.ok_or_else(|| {
+				ICS04Error::upgrade_error_receipt_not_found(
+					port_channel_id.0.clone(),
+					port_channel_id.1,
+				)
+			})?;
+		ErrorReceipt::decode_vec(&data).map_err(|e| {
+			ICS04Error::implementation_specific(format!("[channel_upgrade_error]: {e:?}"))
+		})
+	}
+
+	fn has_inflight_packets(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<bool, ICS04Error> {
+		Ok(<PacketCommitment<T>>::has_commitments(port_channel_id.0.clone(), port_channel_id.1))
+	}
 }
 
 impl<T: Config + Sync + Send> ChannelKeeper for Context<T>
@@ -338,9 +401,7 @@ where
 		key: (PortId, ChannelId, Sequence),
 		receipt: Receipt,
 	) -> Result<(), ICS04Error> {
-		let receipt = match receipt {
-			Receipt::Ok => b"Ok".to_vec(),
-		};
+		let receipt = receipt.as_bytes().to_vec();
 
 		<PacketReceipt<T>>::insert((key.0.clone(), key.1, key.2), receipt);
 
@@ -465,6 +526,59 @@ where
 		Ok(())
 	}
 
+	fn store_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), ICS04Error> {
+		<ChannelUpgrades<T>>::insert(port_channel_id.0, port_channel_id.1, &upgrade);
+
+		Ok(())
+	}
+
+	fn store_counterparty_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), ICS04Error> {
+		let key = (
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+		);
+		let data = upgrade.encode_vec().map_err(|e| {
+			ICS04Error::implementation_specific(format!(
+				"[store_counterparty_channel_upgrade]: {e:?}"
+			))
+		})?;
+		<CounterpartyChannelUpgrades<T>>::insert(key, data);
+
+		Ok(())
+	}
+
+	fn delete_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+	) -> Result<(), ICS04Error> {
+		let key = (
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+		);
+		<CounterpartyChannelUpgrades<T>>::remove(key);
+		<ChannelUpgrades<T>>::remove(port_channel_id.0, port_channel_id.1);
+
+		Ok(())
+	}
+
+	fn store_channel_upgrade_error(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		error_receipt: ErrorReceipt,
+	) -> Result<(), ICS04Error> {
+		<ChannelUpgradeErrors<T>>::insert(port_channel_id.0, port_channel_id.1, &error_receipt);
+
+		Ok(())
+	}
+
 	/// Called upon channel identifier creation (Init or Try message processing).
 	/// Increases the counter which keeps track of how many channels have been created.
 	/// Should never fail.
@@ -25,6 +25,10 @@ use ibc::{
 use sp_runtime::SaturatedConversion;
 use tendermint_proto::Protobuf;
 
+/// Maximum number of expired consensus states removed on every client state update, this keeps
+/// the cost of client messages bounded.
+pub(crate) const MAX_PRUNED_CONSENSUS_STATES: u32 = 8;
+
 #[derive(Encode, Decode)]
 pub struct HostConsensusProof {
 	pub header: Vec<u8>,
@@ -107,6 +111,9 @@ where
 		client_id: &ClientId,
 		height: Height,
 	) -> Result<Option<AnyConsensusState>, ICS02Error> {
+		if !uses_neighbouring_consensus_states(client_id) {
+			return Ok(None)
+		}
 		let consensus_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
 		let cs_state = consensus_heights
 			.into_iter()
@@ -121,6 +128,9 @@ where
 		client_id: &ClientId,
 		height: Height,
 	) -> Result<Option<AnyConsensusState>, ICS02Error> {
+		if !uses_neighbouring_consensus_states(client_id) {
+			return Ok(None)
+		}
 		let consensus_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
 		let cs_state = consensus_heights
 			.into_iter()
@@ -181,6 +191,12 @@ where
 		client_state: &AnyClientState,
 	) -> Result<AnyConsensusState, ICS02Error> {
 		log::trace!(target: "pallet_ibc", "in client: [host_consensus_state] height = {:?}", height);
+		// the counterparty of a localhost client is this chain, there's nothing to prove
+		if let AnyClientState::Localhost(_) = client_state.unpack_recursive() {
+			return Ok(AnyConsensusState::Localhost(
+				ics09_localhost::consensus_state::ConsensusState::new(self.host_timestamp()),
+			))
+		}
 		use codec::Compact;
 		use sp_core::H256;
 		use sp_runtime::traits::{BlakeTwo256, Header};
@@ -345,6 +361,7 @@ where
 		let data = client_state.encode_to_vec().map_err(ICS02Error::encode)?;
 		// store client states key-value
 		<ClientStates<T>>::insert(&client_id, data);
+		self.prune_expired_consensus_states(&client_id, &client_state)?;
 
 		Ok(())
 	}
@@ -359,23 +376,28 @@ where
 			client_id, height, consensus_state);
 
 		let data = consensus_state.encode_to_vec().map_err(ICS02Error::encode)?;
-		// todo: pruning
 		ConsensusStates::<T>::insert(client_id.clone(), height, data);
-		// We do not need this hack for neither beefy nor grandpa clients
-		if !client_id.as_str().starts_with("10-grandpa") &&
-			!client_id.as_str().starts_with("11-beefy")
-		{
-			let mut stored_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
-			if let Err(val) = stored_heights.try_insert(height) {
+		let mut stored_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
+		if stored_heights.try_insert(height).is_err() {
+			// Make room by pruning the expired consensus states first
+			if let Ok(client_state) = self.client_state(&client_id) {
+				self.prune_expired_consensus_states(&client_id, &client_state)?;
+				stored_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
+			}
+			// Otherwise the oldest consensus state is removed along with its index entry, a stored
+			// state that isn't indexed would never be pruned
+			if let Err(height) = stored_heights.try_insert(height) {
 				let first =
 					*stored_heights.iter().next().expect("Cannot fail as a value always exists");
+				log::warn!(target: "pallet_ibc", "in client : [store_consensus_state] >> consensus heights of {:?} are full, removing the consensus state at {:?}", client_id, first);
 				stored_heights.remove(&first);
+				remove_consensus_state::<T>(&client_id, first)?;
 				stored_heights
-					.try_insert(val)
+					.try_insert(height)
 					.expect("Cannot panic, since bounds cannot be exceeded at this point");
 			}
-			ConsensusHeights::<T>::insert(client_id.as_bytes().to_vec(), stored_heights);
 		}
+		ConsensusHeights::<T>::insert(client_id.as_bytes().to_vec(), stored_heights);
 
 		Ok(())
 	}
@@ -418,6 +440,16 @@ where
 
 	fn validate_self_client(&self, client_state: &AnyClientState) -> Result<(), ICS02Error> {
 		let unpacked = client_state.unpack_recursive();
+		if let AnyClientState::Localhost(client_state) = unpacked {
+			let host_height = self.host_height();
+			if client_state.latest_height() > host_height {
+				Err(ICS02Error::implementation_specific(format!(
+					"localhost client has latest height {} greater than host height {host_height}",
+					client_state.latest_height()
+				)))?
+			}
+			return Ok(())
+		}
 		let (relay_chain, para_id, latest_para_height) = match unpacked {
 			AnyClientState::Beefy(client_state) => {
 				if client_state.frozen_height.is_some() {
@@ -459,3 +491,70 @@ where
 		Ok(())
 	}
 }
+
+impl<T: Config + Send + Sync> Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	<T as frame_system::Config>::BlockNumber: From<u32>,
+{
+	/// Removes up to [`MAX_PRUNED_CONSENSUS_STATES`] of the oldest consensus states that are past
+	/// the trusting period of the client, along with their update time and height. The consensus
+	/// state at the latest client height is never removed.
+	///
+	/// It runs whenever a client state is stored, and again when the consensus heights of the
+	/// client are full. Only then, if no state has expired, is the oldest state removed anyway.
+	pub(crate) fn prune_expired_consensus_states(
+		&self,
+		client_id: &ClientId,
+		client_state: &AnyClientState,
+	) -> Result<(), ICS02Error> {
+		let mut heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
+		let latest_height = client_state.latest_height();
+		let now = self.host_timestamp();
+		let expired = heights
+			.iter()
+			.copied()
+			.take_while(|height| *height < latest_height)
+			.take(MAX_PRUNED_CONSENSUS_STATES as usize)
+			.take_while(|height| match self.consensus_state(client_id, *height) {
+				Ok(consensus_state) => {
+					let elapsed =
+						now.duration_since(&consensus_state.timestamp()).unwrap_or_default();
+					client_state.expired(elapsed)
+				},
+				// nothing left to keep for this height
+				Err(_) => true,
+			})
+			.collect::<Vec<_>>();
+		if expired.is_empty() {
+			return Ok(())
+		}
+		for height in expired {
+			log::trace!(target: "pallet_ibc", "in client : [prune_expired_consensus_states] >> client_id: {:?}, height = {:?}", client_id, height);
+			heights.remove(&height);
+			remove_consensus_state::<T>(client_id, height)?;
+		}
+		ConsensusHeights::<T>::insert(client_id.as_bytes().to_vec(), heights);
+		Ok(())
+	}
+}
+
+/// Beefy and grandpa clients never look up the consensus states around a height, their consensus
+/// heights are only tracked for pruning.
+fn uses_neighbouring_consensus_states(client_id: &ClientId) -> bool {
+	!client_id.as_str().starts_with("10-grandpa") && !client_id.as_str().starts_with("11-beefy")
+}
+
+/// Removes the consensus state of the client at the given height, along with its update time and
+/// height.
+fn remove_consensus_state<T: Config>(
+	client_id: &ClientId,
+	height: Height,
+) -> Result<(), ICS02Error> {
+	ConsensusStates::<T>::remove(client_id.clone(), height);
+	let height = height.encode_vec().map_err(ICS02Error::encode)?;
+	let client_id = client_id.as_bytes().to_vec();
+	ClientUpdateTime::<T>::remove(&client_id, &height);
+	ClientUpdateHeight::<T>::remove(&client_id, &height);
+	Ok(())
+}
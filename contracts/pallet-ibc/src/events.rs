@@ -219,6 +219,86 @@ pub enum IbcEvent {
 	AppModule { kind: Vec<u8>, module_id: Vec<u8> },
 	/// Push WASM Code
 	PushWasmCode { wasm_checksum: Checksum },
+	/// Channel upgrade init
+	UpgradeInitChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade try
+	UpgradeTryChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade ack
+	UpgradeAckChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade confirm
+	UpgradeConfirmChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade open
+	UpgradeOpenChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade timeout
+	UpgradeTimeoutChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade cancel
+	UpgradeCancelChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade error
+	UpgradeErrorChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
 }
 
 impl From<RawIbcEvent> for IbcEvent {
@@ -442,6 +522,102 @@ impl From<RawIbcEvent> for IbcEvent {
 				let wasm_checksum = ev.0;
 				IbcEvent::PushWasmCode { wasm_checksum }
 			},
+			RawIbcEvent::UpgradeInitChannel(ev) => IbcEvent::UpgradeInitChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.0.port_id.as_bytes().to_vec(),
+				channel_id: ev.0.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.0.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.0
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.0.upgrade_sequence,
+			},
+			RawIbcEvent::UpgradeTryChannel(ev) => IbcEvent::UpgradeTryChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.0.port_id.as_bytes().to_vec(),
+				channel_id: ev.0.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.0.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.0
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.0.upgrade_sequence,
+			},
+			RawIbcEvent::UpgradeAckChannel(ev) => IbcEvent::UpgradeAckChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.0.port_id.as_bytes().to_vec(),
+				channel_id: ev.0.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.0.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.0
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.0.upgrade_sequence,
+			},
+			RawIbcEvent::UpgradeConfirmChannel(ev) => IbcEvent::UpgradeConfirmChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.0.port_id.as_bytes().to_vec(),
+				channel_id: ev.0.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.0.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.0
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.0.upgrade_sequence,
+			},
+			RawIbcEvent::UpgradeOpenChannel(ev) => IbcEvent::UpgradeOpenChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.0.port_id.as_bytes().to_vec(),
+				channel_id: ev.0.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.0.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.0
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.0.upgrade_sequence,
+			},
+			RawIbcEvent::UpgradeTimeoutChannel(ev) => IbcEvent::UpgradeTimeoutChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.0.port_id.as_bytes().to_vec(),
+				channel_id: ev.0.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.0.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.0
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.0.upgrade_sequence,
+			},
+			RawIbcEvent::UpgradeCancelChannel(ev) => IbcEvent::UpgradeCancelChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.0.port_id.as_bytes().to_vec(),
+				channel_id: ev.0.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.0.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.0
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.0.upgrade_sequence,
+			},
+			RawIbcEvent::UpgradeErrorChannel(ev) => IbcEvent::UpgradeErrorChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.0.port_id.as_bytes().to_vec(),
+				channel_id: ev.0.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.0.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.0
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.0.upgrade_sequence,
+			},
 		}
 	}
 }
@@ -547,6 +723,144 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 			))),
 			IbcEvent::PushWasmCode { wasm_checksum } =>
 				Ok(RawIbcEvent::PushWasmCode(ClientEvents::PushWasmCode(wasm_checksum))),
+			IbcEvent::UpgradeInitChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} =>
+				Ok(RawIbcEvent::UpgradeInitChannel(ChannelEvents::UpgradeInit(upgrade_attributes(
+					Height::new(revision_number, revision_height),
+					port_id,
+					channel_id,
+					counterparty_port_id,
+					counterparty_channel_id,
+					upgrade_sequence,
+				)?))),
+			IbcEvent::UpgradeTryChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeTryChannel(ChannelEvents::UpgradeTry(upgrade_attributes(
+				Height::new(revision_number, revision_height),
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			)?))),
+			IbcEvent::UpgradeAckChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeAckChannel(ChannelEvents::UpgradeAck(upgrade_attributes(
+				Height::new(revision_number, revision_height),
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			)?))),
+			IbcEvent::UpgradeConfirmChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeConfirmChannel(ChannelEvents::UpgradeConfirm(
+				upgrade_attributes(
+					Height::new(revision_number, revision_height),
+					port_id,
+					channel_id,
+					counterparty_port_id,
+					counterparty_channel_id,
+					upgrade_sequence,
+				)?,
+			))),
+			IbcEvent::UpgradeOpenChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} =>
+				Ok(RawIbcEvent::UpgradeOpenChannel(ChannelEvents::UpgradeOpen(upgrade_attributes(
+					Height::new(revision_number, revision_height),
+					port_id,
+					channel_id,
+					counterparty_port_id,
+					counterparty_channel_id,
+					upgrade_sequence,
+				)?))),
+			IbcEvent::UpgradeTimeoutChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeTimeoutChannel(ChannelEvents::UpgradeTimeout(
+				upgrade_attributes(
+					Height::new(revision_number, revision_height),
+					port_id,
+					channel_id,
+					counterparty_port_id,
+					counterparty_channel_id,
+					upgrade_sequence,
+				)?,
+			))),
+			IbcEvent::UpgradeCancelChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeCancelChannel(ChannelEvents::UpgradeCancel(
+				upgrade_attributes(
+					Height::new(revision_number, revision_height),
+					port_id,
+					channel_id,
+					counterparty_port_id,
+					counterparty_channel_id,
+					upgrade_sequence,
+				)?,
+			))),
+			IbcEvent::UpgradeErrorChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeErrorChannel(ChannelEvents::UpgradeError(
+				upgrade_attributes(
+					Height::new(revision_number, revision_height),
+					port_id,
+					channel_id,
+					counterparty_port_id,
+					counterparty_channel_id,
+					upgrade_sequence,
+				)?,
+			))),
 			IbcEvent::OpenInitConnection {
 				revision_height,
 				revision_number,
@@ -1053,3 +1367,30 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 		}
 	}
 }
+
+fn upgrade_attributes(
+	height: Height,
+	port_id: Vec<u8>,
+	channel_id: Vec<u8>,
+	counterparty_port_id: Vec<u8>,
+	counterparty_channel_id: Option<Vec<u8>>,
+	upgrade_sequence: u64,
+) -> Result<ChannelEvents::UpgradeAttributes, &'static str> {
+	Ok(ChannelEvents::UpgradeAttributes {
+		height,
+		port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+			.map_err(|_| ERROR_STR)?,
+		channel_id: ChannelId::from_str(&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?)
+			.map_err(|_| ERROR_STR)?,
+		counterparty_port_id: PortId::from_str(
+			&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+		)
+		.map_err(|_| ERROR_STR)?,
+		counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+			String::from_utf8(channel_id)
+				.ok()
+				.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+		}),
+		upgrade_sequence,
+	})
+}
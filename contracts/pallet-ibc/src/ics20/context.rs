@@ -11,9 +11,15 @@ use ibc::{
 		error::Error as Ics20Error,
 		PORT_ID_STR,
 	},
-	core::ics24_host::identifier::{ChannelId, PortId},
+	core::{
+		ics04_channel::packet::{Packet, Sequence},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	signer::Signer,
 };
-use ibc_primitives::get_channel_escrow_address;
+use ibc_primitives::{get_channel_escrow_address, get_forward_address};
+use ibc_proto::ibc::core::channel::v1::Packet as RawPacket;
+use prost::Message;
 use sp_core::crypto::AccountId32;
 use sp_runtime::traits::IdentifyAccount;
 
@@ -40,6 +46,22 @@ where
 		})
 	}
 
+	fn get_forward_address(
+		&self,
+		port_id: &PortId,
+		channel_id: ChannelId,
+	) -> Result<Signer, Ics20Error> {
+		get_forward_address(port_id, channel_id)
+	}
+
+	fn get_forwarded_packet(&self, key: &(PortId, ChannelId, Sequence)) -> Option<Packet> {
+		let (port_id, channel_id, sequence) = key;
+		let key = (port_id.as_bytes().to_vec(), channel_id.sequence(), u64::from(*sequence));
+		ForwardedTransferPackets::<T>::get(key)
+			.and_then(|packet| <RawPacket as Message>::decode(packet.as_slice()).ok())
+			.and_then(|packet| Packet::try_from(packet).ok())
+	}
+
 	fn is_send_enabled(&self) -> bool {
 		T::IsSendEnabled::get()
 	}
@@ -55,6 +77,27 @@ where
 	AccountId32: From<<T as frame_system::Config>::AccountId>,
 {
 	type AccountId = T::AccountIdConversion;
+
+	fn store_forwarded_packet(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		packet: Packet,
+	) -> Result<(), Ics20Error> {
+		let (port_id, channel_id, sequence) = key;
+		let key = (port_id.as_bytes().to_vec(), channel_id.sequence(), u64::from(sequence));
+		ForwardedTransferPackets::<T>::insert(key, RawPacket::from(packet).encode_to_vec());
+		Ok(())
+	}
+
+	fn delete_forwarded_packet(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+	) -> Result<(), Ics20Error> {
+		let (port_id, channel_id, sequence) = key;
+		let key = (port_id.as_bytes().to_vec(), channel_id.sequence(), u64::from(sequence));
+		ForwardedTransferPackets::<T>::remove(key);
+		Ok(())
+	}
 }
 
 impl<T: Config + Send + Sync> Ics20Context for Context<T>
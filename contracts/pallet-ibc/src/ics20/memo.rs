@@ -2,6 +2,7 @@ use crate::{ics20::HandleMemo, Config};
 use alloc::{
 	format,
 	string::{String, ToString},
+	vec,
 };
 use core::{fmt::Debug, str::FromStr};
 use ibc::{
@@ -152,6 +153,81 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 		self.inner.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)
 	}
 
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_order: Order,
+		proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Error> {
+		self.inner.on_chan_upgrade_init(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			proposed_order,
+			proposed_connection_hops,
+			proposed_version,
+		)
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_order: Order,
+		proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Error> {
+		self.inner.on_chan_upgrade_try(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			proposed_order,
+			proposed_connection_hops,
+			proposed_version,
+		)
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Error> {
+		self.inner
+			.on_chan_upgrade_ack(ctx, output, port_id, channel_id, counterparty_version)
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<(), Error> {
+		self.inner.on_chan_upgrade_open(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
 	fn on_recv_packet(
 		&self,
 		ctx: &dyn ModuleCallbackContext,
@@ -159,7 +235,13 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 		packet: &mut Packet,
 		relayer: &Signer,
 	) -> Result<Acknowledgement, Error> {
+		// Middlewares may change the packet data, the packet is acknowledged as it was received
+		let received = packet.clone();
 		let ack = self.inner.on_recv_packet(ctx, output, packet, relayer)?;
+		// Packets whose tokens are forwarded to the next hop are acknowledged later on
+		if ack.as_ref().is_empty() {
+			return Ok(ack)
+		}
 		let ics20_ack = Ics20Acknowledgement::from_str(&String::from_utf8_lossy(ack.as_ref()))
 			.map_err(|_| Error::invalid_acknowledgement())?;
 
@@ -172,8 +254,17 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 
 		// We want the whole chain of calls to fail only if the ics20 transfer fails, because
 		// the other modules are not part of ics-20 standard
-		match Self::process_memo(packet) {
+		let deferred = <T as Config>::HandleMemo::default().defers_acknowledgement(&received);
+		match Self::process_memo(&received, packet, deferred) {
+			// The memo handler writes the acknowledgement once the forwarded transfer completes
+			Ok(_) if deferred => Ok(Acknowledgement::from_bytes(vec![])),
 			Ok(_) => Ok(ack),
+			// A failed deferred memo is acknowledged by the handler once the receive is reverted,
+			// an error acknowledgement written here would refund tokens the receiver still holds
+			Err(err) if deferred => {
+				log::error!(target: "pallet_ibc", "Error while handling memo: {:?}", err);
+				Ok(Acknowledgement::from_bytes(vec![]))
+			},
 			Err(err) => {
 				log::error!(target: "pallet_ibc", "Error while handling memo: {:?}", err);
 				Ok(Acknowledgement::from_bytes(
@@ -196,7 +287,25 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 		relayer: &Signer,
 	) -> Result<(), Error> {
 		self.inner
-			.on_acknowledgement_packet(ctx, output, packet, acknowledgement, relayer)
+			.on_acknowledgement_packet(ctx, output, packet, acknowledgement, relayer)?;
+		// Only an explicit error acknowledgement fails a forwarded transfer
+		let success =
+			Ics20Acknowledgement::from_str(&String::from_utf8_lossy(acknowledgement.as_ref()))
+				.map_err(|e| {
+					Error::implementation_specific(format!(
+						"Failed to decode memo acknowledgement {:?}",
+						e
+					))
+				})?
+				.is_successful();
+		<T as Config>::HandleMemo::default()
+			.on_acknowledgement(packet, success)
+			.map_err(|e| {
+				Error::implementation_specific(format!(
+					"Failed to handle memo acknowledgement {:?}",
+					e
+				))
+			})
 	}
 
 	fn on_timeout_packet(
@@ -206,13 +315,23 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 		packet: &mut Packet,
 		relayer: &Signer,
 	) -> Result<(), Error> {
-		self.inner.on_timeout_packet(ctx, output, packet, relayer)
+		self.inner.on_timeout_packet(ctx, output, packet, relayer)?;
+		// Forwarded transfers are resent once the tokens have been refunded by the inner module
+		<T as Config>::HandleMemo::default().on_timeout(packet).map_err(|e| {
+			Error::implementation_specific(format!("Failed to handle memo timeout {:?}", e))
+		})
 	}
 }
 
 impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Debug> Memo<T, S> {
-	fn process_memo(packet: &mut Packet) -> Result<(), Error> {
-		<T as Config>::HandleMemo::default().execute_memo(packet).map_err(|e| {
+	fn process_memo(received: &Packet, packet: &Packet, deferred: bool) -> Result<(), Error> {
+		let handler = <T as Config>::HandleMemo::default();
+		let result = if deferred {
+			handler.execute_deferred_memo(received, packet)
+		} else {
+			handler.execute_memo(packet)
+		};
+		result.map_err(|e| {
 			Error::implementation_specific(format!("Failed to execute memo {:?}", e))
 		})?;
 		Ok(())
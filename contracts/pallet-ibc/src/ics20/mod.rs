@@ -2,25 +2,31 @@ pub mod context;
 pub mod memo;
 
 use crate::{
-	routing::Context, ChannelIds, Config, DenomToAssetId, Event, Pallet, SequenceFee, WeightInfo,
+	ics20_rate_limit::Ics20RateLimiter, routing::Context, ChannelIds, Config, DenomToAssetId,
+	Event, ForwardedPackets, ForwardedTransferPackets, Pallet, SequenceFee, WeightInfo,
 };
 use alloc::{
 	format,
 	str::FromStr,
 	string::{String, ToString},
+	vec::Vec,
 };
 
-use frame_support::weights::Weight;
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	weights::Weight,
+};
 pub use ibc::applications::transfer::{MODULE_ID_STR, PORT_ID_STR};
 use ibc::{
 	applications::transfer::{
 		acknowledgement::{Acknowledgement as Ics20Acknowledgement, ACK_ERR_STR},
 		context::{
-			on_chan_close_confirm, on_chan_close_init, on_chan_open_ack, on_chan_open_confirm,
-			on_chan_open_init, on_chan_open_try, BankKeeper,
+			decode_packet_data, on_chan_close_confirm, on_chan_close_init, on_chan_open_ack,
+			on_chan_open_confirm, on_chan_open_init, on_chan_open_try, on_chan_upgrade_ack,
+			on_chan_upgrade_init, on_chan_upgrade_try, BankKeeper, Ics20Reader,
 		},
 		is_receiver_chain_source, is_sender_chain_source,
-		packet::PacketData,
+		packet::PacketDataV2,
 		relay::{
 			on_ack_packet::process_ack_packet, on_recv_packet::process_recv_packet,
 			on_timeout_packet::process_timeout_packet,
@@ -30,6 +36,7 @@ use ibc::{
 	core::{
 		ics04_channel::{
 			channel::{Counterparty, Order},
+			context::ChannelReader,
 			error::Error as Ics04Error,
 			msgs::acknowledgement::Acknowledgement,
 			packet::Packet,
@@ -41,8 +48,10 @@ use ibc::{
 	signer::Signer,
 };
 use ibc_primitives::{CallbackWeight, HandlerMessage, IbcHandler};
+use ibc_proto::ibc::core::channel::v1::Packet as RawPacket;
+use prost::Message;
 use sp_core::crypto::AccountId32;
-use sp_runtime::traits::IdentifyAccount;
+use sp_runtime::{traits::IdentifyAccount, DispatchError, SaturatedConversion};
 use sp_std::marker::PhantomData;
 
 pub type Ics20TransferMsg = ibc::applications::transfer::msgs::transfer::MsgTransfer<
@@ -200,6 +209,65 @@ where
 			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
 	}
 
+	fn on_chan_upgrade_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_order: Order,
+		proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_upgrade_init(
+			&mut ctx,
+			output,
+			port_id,
+			channel_id,
+			proposed_order,
+			proposed_connection_hops,
+			proposed_version,
+		)
+		.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_order: Order,
+		proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_upgrade_try(
+			&mut ctx,
+			output,
+			port_id,
+			channel_id,
+			proposed_order,
+			proposed_connection_hops,
+			proposed_version,
+		)
+		.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_upgrade_ack(&mut ctx, output, port_id, channel_id, counterparty_version)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
 	fn on_recv_packet(
 		&self,
 		_ctx: &dyn ModuleCallbackContext,
@@ -209,34 +277,47 @@ where
 	) -> Result<Acknowledgement, Ics04Error> {
 		let mut ctx = Context::<T>::default();
 
-		let result = serde_json::from_slice(packet.data.as_slice())
-			.map_err(|e| {
-				Ics04Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
-			})
-			.and_then(|packet_data: PacketData| {
-				// We need to reject transaction amounts that are larger than u128 since we expect
-				// the balance type of the runtime to be a u128; For a U256 to be converted to a
-				// u128 without truncating, the last two words should be zero
-
-				// in order to properly calculate rate limits, we need to adjust the full denom,
-				// which wan not included as part of the token in the packet data.
-
-				let mut token = packet_data.token.clone();
-				let denom = full_ibc_denom(packet, packet_data.token.clone());
-				token.denom = PrefixedDenom::from_str(&denom).map_err(|_| {
-					Ics04Error::implementation_specific("Failed to parse token denom".to_string())
-				})?;
-
-				let amount = packet_data.token.amount.as_u256();
+		let result = decode_packet_data(
+			&ctx,
+			&packet.destination_port,
+			packet.destination_channel,
+			packet.data.as_slice(),
+		)
+		.map_err(|e| {
+			Ics04Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
+		})
+		.and_then(|packet_data| {
+			// We need to reject transaction amounts that are larger than u128 since we expect
+			// the balance type of the runtime to be a u128; For a U256 to be converted to a
+			// u128 without truncating, the last two words should be zero
+			for token in &packet_data.tokens {
+				let amount = token.amount.as_u256();
 				u128::try_from(amount)
 					.map_err(|e| Ics04Error::implementation_specific(format!("{e:?}")))?;
-				process_recv_packet(&mut ctx, output, packet, packet_data.clone())
-					.map(|_| packet_data)
-					.map_err(|e| {
-						log::trace!(target: "pallet_ibc", "[on_recv_packet]: token: {}, error: {:?}", denom, e);
-						Ics04Error::implementation_specific(e.to_string())
-					})
-			});
+			}
+			// All the tokens of a packet are received or none of them is
+			with_transaction(|| {
+				let result =
+					Self::limit_forwarded_outflows(&ctx, packet, &packet_data).and_then(|_| {
+						process_recv_packet(&mut ctx, output, packet, packet_data.clone())
+					});
+				match result {
+					Ok(()) => TransactionOutcome::Commit(Ok(Ok(()))),
+					Err(e) => TransactionOutcome::Rollback(Ok(Err(e))),
+				}
+			})
+			.map_err(|e: DispatchError| Ics04Error::implementation_specific(format!("{e:?}")))?
+			.map(|_| packet_data.clone())
+			.map_err(|e| {
+				let denoms = packet_data
+					.tokens
+					.iter()
+					.map(|token| full_ibc_denom(packet, token.clone()))
+					.collect::<Vec<_>>();
+				log::trace!(target: "pallet_ibc", "[on_recv_packet]: tokens: {:?}, error: {:?}", denoms, e);
+				Ics04Error::implementation_specific(e.to_string())
+			})
+		});
 
 		let ack = match result {
 			Err(err) => {
@@ -254,33 +335,49 @@ where
 				ack
 			},
 			Ok(packet_data) => {
-				let denom = full_ibc_denom(packet, packet_data.token.clone());
-				let prefixed_denom = PrefixedDenom::from_str(&denom).map_err(|_| {
-					Ics04Error::implementation_specific("Failed to parse token denom".to_string())
-				})?;
-				Pallet::<T>::deposit_event(Event::<T>::TokenReceived {
-					from: packet_data.sender,
-					to: packet_data.receiver,
-					ibc_denom: denom.as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom)
+				let forwarded = packet_data.should_forward();
+				for token in packet_data.tokens {
+					let denom = full_ibc_denom(packet, token.clone());
+					let prefixed_denom = PrefixedDenom::from_str(&denom).map_err(|_| {
+						Ics04Error::implementation_specific(
+							"Failed to parse token denom".to_string(),
+						)
+					})?;
+					Pallet::<T>::deposit_event(Event::<T>::TokenReceived {
+						from: packet_data.sender.clone(),
+						to: packet_data.receiver.clone(),
+						ibc_denom: denom.as_bytes().to_vec(),
+						local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+							&denom,
+						)
 						.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_receiver_source: is_receiver_chain_source(
-						packet.source_port.clone(),
-						packet.source_channel,
-						&prefixed_denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				});
-				let packet = packet.clone();
-				Pallet::<T>::handle_message(HandlerMessage::WriteAck {
-					packet,
-					ack: Ics20Acknowledgement::success().to_string().into_bytes(),
-				})
-				.map_err(|e| {
-					Ics04Error::implementation_specific(format!("[on_recv_packet] {e:#?}"))
-				})?;
+						amount: token.amount.as_u256().as_u128().into(),
+						is_receiver_source: is_receiver_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel,
+							&prefixed_denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+					});
+				}
+				// Forwarded packets are acknowledged once the next hop has acknowledged them
+				if forwarded {
+					return Ok(Acknowledgement::from_bytes(Vec::new()))
+				}
+				if !T::HandleMemo::default().defers_acknowledgement(packet) {
+					Pallet::<T>::handle_message(HandlerMessage::WriteAck {
+						packet: packet.clone(),
+						ack: Ics20Acknowledgement::success().to_string().into_bytes(),
+					})
+					.map_err(|e| {
+						Ics04Error::implementation_specific(format!("[on_recv_packet] {e:#?}"))
+					})?;
+				}
 				Ics20Acknowledgement::success().to_string().into_bytes()
 			},
 		};
@@ -290,16 +387,21 @@ where
 	fn on_acknowledgement_packet(
 		&mut self,
 		_ctx: &dyn ModuleCallbackContext,
-		_output: &mut ModuleOutputBuilder,
+		output: &mut ModuleOutputBuilder,
 		packet: &mut Packet,
 		acknowledgement: &Acknowledgement,
 		_relayer: &Signer,
 	) -> Result<(), Ics04Error> {
 		let mut ctx = Context::<T>::default();
-		let packet_data: PacketData =
-			serde_json::from_slice(packet.data.as_slice()).map_err(|e| {
-				Ics04Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
-			})?;
+		let packet_data = decode_packet_data(
+			&ctx,
+			&packet.source_port,
+			packet.source_channel,
+			packet.data.as_slice(),
+		)
+		.map_err(|e| {
+			Ics04Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
+		})?;
 		let ack = serde_json::from_slice::<Ics20Acknowledgement>(acknowledgement.as_ref())
 			.map_err(|e| {
 				Ics04Error::implementation_specific(format!(
@@ -307,7 +409,7 @@ where
 				))
 			})?;
 		let sequence: u64 = packet.sequence.into();
-		process_ack_packet(&mut ctx, packet, &packet_data, &ack)
+		process_ack_packet(&mut ctx, output, packet, &packet_data, &ack)
 			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))?;
 		match ack.into_result() {
 			Ok(_) => {
@@ -315,23 +417,29 @@ where
 					SequenceFee::<T>::remove(sequence);
 					Pallet::<T>::deposit_event(Event::<T>::ChargingFeeConfirmed { sequence });
 				}
-				Pallet::<T>::deposit_event(Event::<T>::TokenTransferCompleted {
-					from: packet_data.sender,
-					to: packet_data.receiver,
-					ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-						&packet_data.token.denom.to_string(),
-					)
-					.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_sender_source: is_sender_chain_source(
-						packet.source_port.clone(),
-						packet.source_channel,
-						&packet_data.token.denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				})
+				for token in &packet_data.tokens {
+					Pallet::<T>::deposit_event(Event::<T>::TokenTransferCompleted {
+						from: packet_data.sender.clone(),
+						to: packet_data.receiver.clone(),
+						ibc_denom: token.denom.to_string().as_bytes().to_vec(),
+						local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+							&token.denom.to_string(),
+						)
+						.ok(),
+						amount: token.amount.as_u256().as_u128().into(),
+						is_sender_source: is_sender_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel,
+							&token.denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+					})
+				}
 			},
 			Err(e) => {
 				log::trace!(
@@ -343,23 +451,29 @@ where
 					sequence,
 				});
 
-				Pallet::<T>::deposit_event(Event::<T>::TokenTransferFailed {
-					from: packet_data.sender,
-					to: packet_data.receiver,
-					ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-						&packet_data.token.denom.to_string(),
-					)
-					.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_sender_source: is_sender_chain_source(
-						packet.source_port.clone(),
-						packet.source_channel,
-						&packet_data.token.denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				})
+				for token in &packet_data.tokens {
+					Pallet::<T>::deposit_event(Event::<T>::TokenTransferFailed {
+						from: packet_data.sender.clone(),
+						to: packet_data.receiver.clone(),
+						ibc_denom: token.denom.to_string().as_bytes().to_vec(),
+						local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+							&token.denom.to_string(),
+						)
+						.ok(),
+						amount: token.amount.as_u256().as_u128().into(),
+						is_sender_source: is_sender_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel,
+							&token.denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+					})
+				}
 			},
 		}
 
@@ -369,36 +483,43 @@ where
 	fn on_timeout_packet(
 		&mut self,
 		_ctx: &dyn ModuleCallbackContext,
-		_output: &mut ModuleOutputBuilder,
+		output: &mut ModuleOutputBuilder,
 		packet: &mut Packet,
 		_relayer: &Signer,
 	) -> Result<(), Ics04Error> {
 		let mut ctx = Context::<T>::default();
-		let packet_data: PacketData = serde_json::from_slice(packet.data.as_slice())
-			.map_err(|e| Ics04Error::app_module(format!("Failed to decode packet data {e:?}")))?;
-		process_timeout_packet(&mut ctx, packet, &packet_data)
+		let packet_data = decode_packet_data(
+			&ctx,
+			&packet.source_port,
+			packet.source_channel,
+			packet.data.as_slice(),
+		)
+		.map_err(|e| Ics04Error::app_module(format!("Failed to decode packet data {e:?}")))?;
+		process_timeout_packet(&mut ctx, output, packet, &packet_data)
 			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
 		let sequence: u64 = packet.sequence.into();
 		Self::refund_fee(packet, &packet_data)?;
 		Pallet::<T>::deposit_event(Event::<T>::ChargingFeeTimeout { sequence });
 
-		Pallet::<T>::deposit_event(Event::<T>::TokenTransferTimeout {
-			from: packet_data.sender,
-			to: packet_data.receiver,
-			ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
-			local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-				&packet_data.token.denom.to_string(),
-			)
-			.ok(),
-			amount: packet_data.token.amount.as_u256().as_u128().into(),
-			is_sender_source: is_sender_chain_source(
-				packet.source_port.clone(),
-				packet.source_channel,
-				&packet_data.token.denom,
-			),
-			source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-			destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-		});
+		for token in &packet_data.tokens {
+			Pallet::<T>::deposit_event(Event::<T>::TokenTransferTimeout {
+				from: packet_data.sender.clone(),
+				to: packet_data.receiver.clone(),
+				ibc_denom: token.denom.to_string().as_bytes().to_vec(),
+				local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+					&token.denom.to_string(),
+				)
+				.ok(),
+				amount: token.amount.as_u256().as_u128().into(),
+				is_sender_source: is_sender_chain_source(
+					packet.source_port.clone(),
+					packet.source_channel,
+					&token.denom,
+				),
+				source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+				destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
+			});
+		}
 		Ok(())
 	}
 }
@@ -409,6 +530,29 @@ where
 	u32: From<<T as frame_system::Config>::BlockNumber>,
 	AccountId32: From<<T as frame_system::Config>::AccountId>,
 {
+	/// Tokens forwarded to the next hop leave through its channel, so they count against the
+	/// outflow quota of that channel like any transfer sent from this chain.
+	fn limit_forwarded_outflows(
+		ctx: &Context<T>,
+		packet: &Packet,
+		packet_data: &PacketDataV2,
+	) -> Result<(), Ics20Error> {
+		let hop = match packet_data.forwarding.hops.first() {
+			Some(hop) => hop,
+			None => return Ok(()),
+		};
+		let sequence = ctx
+			.get_next_sequence_send(&(hop.port_id().clone(), hop.channel_id()))
+			.map_err(Ics20Error::ics04_channel)?;
+		for token in &packet_data.tokens {
+			let denom = PrefixedDenom::from_str(&full_ibc_denom(packet, token.clone()))?;
+			let coin = PrefixedCoin { denom, amount: token.amount };
+			T::Ics20RateLimiter::on_send(hop.channel_id(), sequence, &coin)
+				.map_err(|e| Ics20Error::implementation_specific(format!("{e:?}")))?;
+		}
+		Ok(())
+	}
+
 	/// Refunds the fee from the FeeAccount to the sender of the packet.
 	///
 	/// This function is called on `on_timeout_packet` and `on_acknowledgement_packet` in case of
@@ -417,7 +561,8 @@ where
 	/// # Parameters
 	///
 	/// - `packet`: The packet that failed to be delivered.
-	/// - `packet_data`: The data associated with the packet.
+	/// - `packet_data`: The data associated with the packet. The fee is charged in the first token
+	///   of the packet.
 	///
 	/// # Returns
 	///
@@ -430,7 +575,7 @@ where
 	///
 	/// - The fee cannot be refunded to the sender's account. ctx.send_coins failed.
 	/// - The sender's account cannot be parsed from the packet data.
-	fn refund_fee(packet: &Packet, packet_data: &PacketData) -> Result<(), Ics04Error> {
+	fn refund_fee(packet: &Packet, packet_data: &PacketDataV2) -> Result<(), Ics04Error> {
 		use ibc::bigint::U256;
 		use sp_core::Get;
 		let sequence: u64 = packet.sequence.into();
@@ -442,7 +587,9 @@ where
 		let fee_account = T::FeeAccount::get();
 
 		let mut ctx = Context::<T>::default();
-		let mut fee_coin = packet_data.token.clone();
+		let mut fee_coin = packet_data.tokens.first().cloned().ok_or_else(|| {
+			Ics04Error::implementation_specific("Packet has no tokens".to_string())
+		})?;
 
 		fee_coin.amount = U256::from(fee).into();
 
@@ -497,20 +644,49 @@ impl<T: Config> CallbackWeight for WeightHandler<T> {
 		<T as Config>::WeightInfo::on_chan_close_confirm()
 	}
 
-	fn on_recv_packet(&self, _packet: &Packet) -> Weight {
-		<T as Config>::WeightInfo::on_recv_packet()
+	fn on_recv_packet(&self, packet: &Packet) -> Weight {
+		// Forward memos and `ics20-2` forwarding send the received tokens on to the next hop
+		let forwards = PacketDataV2::decode(&Version::ics20_v2(), &packet.data)
+			.map(|packet_data| packet_data.should_forward())
+			.unwrap_or_default();
+		let forward_weight = if forwards || T::HandleMemo::default().defers_acknowledgement(packet)
+		{
+			<T as Config>::WeightInfo::transfer()
+		} else {
+			Weight::zero()
+		};
+		<T as Config>::WeightInfo::on_recv_packet().saturating_add(forward_weight)
 	}
 
 	fn on_acknowledgement_packet(
 		&self,
-		_packet: &Packet,
+		packet: &Packet,
 		_acknowledgement: &Acknowledgement,
 	) -> Weight {
 		<T as Config>::WeightInfo::on_acknowledgement_packet()
+			.saturating_add(Self::forward_completion_weight(packet))
 	}
 
-	fn on_timeout_packet(&self, _packet: &Packet) -> Weight {
+	fn on_timeout_packet(&self, packet: &Packet) -> Weight {
 		<T as Config>::WeightInfo::on_timeout_packet()
+			.saturating_add(Self::forward_completion_weight(packet))
+	}
+}
+
+impl<T: Config> WeightHandler<T> {
+	/// Weight of resending or unwinding a forwarded transfer and acknowledging the packet that
+	/// carried its memo or forwarding hops.
+	fn forward_completion_weight(packet: &Packet) -> Weight {
+		let key = (packet.source_channel.sequence(), u64::from(packet.sequence));
+		let transfer_key = (packet.source_port.as_bytes().to_vec(), key.0, key.1);
+		if ForwardedPackets::<T>::contains_key(key) ||
+			ForwardedTransferPackets::<T>::contains_key(transfer_key)
+		{
+			<T as Config>::WeightInfo::transfer()
+				.saturating_add(<T as Config>::WeightInfo::on_recv_packet())
+		} else {
+			Weight::zero()
+		}
 	}
 }
 
@@ -532,6 +708,30 @@ use ibc::applications::transfer::error::Error as Ics20Error;
 
 pub trait HandleMemo<T: Config> {
 	fn execute_memo(&self, packet: &Packet) -> Result<(), Ics20Error>;
+
+	/// Whether the acknowledgement of a received packet is written by the handler once its memo
+	/// has completed, rather than when its tokens are received.
+	fn defers_acknowledgement(&self, _packet: &Packet) -> bool {
+		false
+	}
+
+	/// Executes the memo of a packet whose acknowledgement is deferred. `received` is the packet
+	/// as it was received and is the one acknowledged, `packet` carries the amounts credited to
+	/// the receiver once the middlewares took their fees.
+	fn execute_deferred_memo(&self, _received: &Packet, packet: &Packet) -> Result<(), Ics20Error> {
+		self.execute_memo(packet)
+	}
+
+	/// Called once a packet sent from this chain has timed out and its tokens were refunded.
+	fn on_timeout(&self, _packet: &Packet) -> Result<(), Ics20Error> {
+		Ok(())
+	}
+
+	/// Called once a packet sent from this chain has been acknowledged, `success` is false if the
+	/// acknowledgement was an error and the tokens were refunded.
+	fn on_acknowledgement(&self, _packet: &Packet, _success: bool) -> Result<(), Ics20Error> {
+		Ok(())
+	}
 }
 
 impl<T: Config> HandleMemo<T> for () {
@@ -637,6 +837,36 @@ pub enum MemoType {
 	XCM(MemoXcm),
 }
 
+/// A transfer sent to the next hop of an IBC forward memo, kept in [`ForwardedPackets`] until it
+/// is acknowledged so that it can be resent when it times out and the packet that carried the
+/// memo can be acknowledged.
+#[derive(Clone, Debug, PartialEq, Eq, codec::Encode, codec::Decode, scale_info::TypeInfo)]
+pub struct InFlightForward<AccountId, AssetId, Balance, MemoMessage> {
+	/// Account on this chain that received the tokens and forwarded them
+	pub forwarder: AccountId,
+	/// Receiver on the next chain
+	pub receiver: Vec<u8>,
+	pub asset_id: AssetId,
+	pub amount: Balance,
+	/// Channel the tokens are forwarded on
+	pub channel: u64,
+	/// Timeout offset of the forwarded transfer in seconds
+	pub timeout: u64,
+	pub next_memo: Option<MemoMessage>,
+	/// Number of times the transfer is resent when it times out
+	pub retries: u64,
+	/// Protobuf encoded packet that carried the memo, it is acknowledged once the transfer
+	/// completes, fails or runs out of retries
+	pub received_packet: Vec<u8>,
+}
+
+pub type InFlightForwardOf<T> = InFlightForward<
+	<T as frame_system::Config>::AccountId,
+	<T as Config>::AssetId,
+	<T as Config>::Balance,
+	<T as Config>::MemoMessage,
+>;
+
 impl Forward {
 	pub fn get_memo(&self) -> Result<MemoType, Ics20Error> {
 		if self.substrate.unwrap_or_default() {
@@ -684,11 +914,105 @@ where
 {
 	fn execute_memo(&self, packet: &Packet) -> Result<(), Ics20Error> {
 		self.inner.execute_memo(packet)?;
+		Self::forward(packet, packet)
+	}
 
-		let packet_data: PacketData =
-			serde_json::from_slice(packet.data.as_slice()).map_err(|e| {
-				Ics20Error::implementation_specific(format!("Failed to decode packet data {:?}", e))
-			})?;
+	fn defers_acknowledgement(&self, packet: &Packet) -> bool {
+		// Transfers forwarded over IBC are acknowledged once the next hop has acknowledged them
+		decode_packet_data(
+			&Context::<T>::default(),
+			&packet.destination_port,
+			packet.destination_channel,
+			packet.data.as_slice(),
+		)
+		.ok()
+		.and_then(|packet_data| serde_json::from_str::<MemoData>(&packet_data.memo).ok())
+		.map(|memo| !memo.forward.substrate.unwrap_or_default())
+		.unwrap_or_default()
+	}
+
+	fn execute_deferred_memo(&self, received: &Packet, packet: &Packet) -> Result<(), Ics20Error> {
+		let result = self.inner.execute_memo(packet).and_then(|_| Self::forward(received, packet));
+		if result.is_err() {
+			// Nothing was forwarded, the tokens credited to the receiver are sent back
+			let credited = decode_packet_data(
+				&Context::<T>::default(),
+				&packet.destination_port,
+				packet.destination_channel,
+				packet.data.as_slice(),
+			)
+			.map(|packet_data| packet_data.tokens)
+			.unwrap_or_default();
+			Self::fail_received(received, credited);
+		}
+		result
+	}
+
+	fn on_timeout(&self, packet: &Packet) -> Result<(), Ics20Error> {
+		self.inner.on_timeout(packet)?;
+
+		let key = (packet.source_channel.sequence(), u64::from(packet.sequence));
+		let forward = match ForwardedPackets::<T>::take(key) {
+			Some(forward) => forward,
+			None => return Ok(()),
+		};
+
+		if forward.retries == 0 {
+			Self::complete_forward(&forward, false);
+			return Ok(())
+		}
+
+		let forward = InFlightForward { retries: forward.retries - 1, ..forward };
+		match Self::send_forward(&forward) {
+			Ok(sequence) => {
+				crate::Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoIbcTokenTransferRetried {
+					from: forward.forwarder.clone(),
+					to: forward.receiver.clone(),
+					asset_id: forward.asset_id.clone(),
+					amount: forward.amount,
+					channel: forward.channel,
+					retries: forward.retries,
+				});
+				ForwardedPackets::<T>::insert((forward.channel, sequence), forward);
+			},
+			Err(e) => {
+				log::warn!(target: "pallet_ibc", "Failed to resend forwarded transfer: {:?}", e);
+				Self::complete_forward(&forward, false);
+			},
+		}
+		Ok(())
+	}
+
+	fn on_acknowledgement(&self, packet: &Packet, success: bool) -> Result<(), Ics20Error> {
+		self.inner.on_acknowledgement(packet, success)?;
+
+		let key = (packet.source_channel.sequence(), u64::from(packet.sequence));
+		if let Some(forward) = ForwardedPackets::<T>::take(key) {
+			// Error acknowledgements are final, only timeouts are retried
+			Self::complete_forward(&forward, success);
+		}
+		Ok(())
+	}
+}
+
+impl<T, H> IbcMemoHandler<H, T>
+where
+	T: Config + Send + Sync + pallet_timestamp::Config,
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+	u128: From<T::AssetId>,
+{
+	/// Forwards the tokens of a packet received with an IBC or XCM forward memo.
+	fn forward(received: &Packet, packet: &Packet) -> Result<(), Ics20Error> {
+		let packet_data = decode_packet_data(
+			&Context::<T>::default(),
+			&packet.destination_port,
+			packet.destination_channel,
+			packet.data.as_slice(),
+		)
+		.map_err(|e| {
+			Ics20Error::implementation_specific(format!("Failed to decode packet data {:?}", e))
+		})?;
 		let receiver = <T as Config>::AccountIdConversion::try_from(packet_data.receiver.clone())
 			.map_err(|_| {
 				Ics20Error::implementation_specific(format!("Failed to parse receiver account"))
@@ -717,19 +1041,34 @@ where
 			))
 		})?;
 
+		// Memo forwarding moves a single asset
+		let token = match packet_data.tokens.as_slice() {
+			[token] => token.clone(),
+			_ => {
+				IbcModule::<T>::emit_memo_execution_failed_event(
+					receiver.clone(),
+					packet_data.memo.clone(),
+					14,
+				);
+				return Err(Ics20Error::implementation_specific(
+					"Memo forwarding supports a single token per packet".to_string(),
+				))
+			},
+		};
+
 		let prefixed_coin = if is_receiver_chain_source(
 			packet.source_port.clone(),
 			packet.source_channel,
-			&packet_data.token.denom,
+			&token.denom,
 		) {
 			let prefix = TracePrefix::new(packet.source_port.clone(), packet.source_channel);
-			let mut c = packet_data.token.clone();
+			let mut c = token.clone();
 			c.denom.remove_trace_prefix(&prefix);
 			c
 		} else {
 			let prefix =
 				TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
-			let mut c = packet_data.token.clone();
+			let mut c = token.clone();
 			c.denom.add_trace_prefix(prefix);
 			c
 		};
@@ -745,7 +1084,7 @@ where
 				Ics20Error::implementation_specific("asset does not exist".to_string())
 			})?;
 
-		let amount = packet_data.token.amount.as_u256().low_u128();
+		let amount = token.amount.as_u256().low_u128();
 
 		let memo_forward = memo.forward.get_memo().map_err(|_| {
 			IbcModule::<T>::emit_memo_execution_failed_event(
@@ -820,10 +1159,6 @@ where
 
 		let raw_bytes = memo_forward.receiver.into_bytes();
 
-		let transfer_ibc_account_to =
-			crate::MultiAddress::<<T as frame_system::Config>::AccountId>::Raw(raw_bytes.clone());
-
-		let origin = RawOrigin::Signed(receiver.clone());
 		let channel_id = memo_forward
 			.channel
 			.split('-')
@@ -849,15 +1184,6 @@ where
 				Ics20Error::implementation_specific("Failed to parse channel ID".to_string())
 			})?;
 
-		let params = crate::TransferParams::<<T as frame_system::Config>::AccountId> {
-			to: transfer_ibc_account_to,
-			source_channel: channel_id,
-			timeout: ibc_primitives::Timeout::Offset {
-				timestamp: Some(memo_forward.timeout),
-				height: Some(1000),
-			},
-		};
-
 		let mut next_memo: Option<T::MemoMessage> = None;
 		if let Some(memo) = memo.forward.next {
 			let memo_result = <T as crate::Config>::MemoMessage::try_from(*memo).map_err(|_| {
@@ -872,14 +1198,19 @@ where
 			next_memo = Some(memo_result);
 		}
 
-		crate::Pallet::<T>::transfer(
-			origin.into(),
-			params,
-			asset_id.clone(),
-			amount.into(),
-			next_memo.clone(),
-		)
-		.map_err(|_| {
+		let forward = InFlightForward {
+			forwarder: receiver.clone(),
+			receiver: raw_bytes.clone(),
+			asset_id: asset_id.clone(),
+			amount: amount.into(),
+			channel: channel_id,
+			timeout: memo_forward.timeout,
+			next_memo: next_memo.clone(),
+			retries: memo_forward.retries,
+			received_packet: RawPacket::from(received.clone()).encode_to_vec(),
+		};
+
+		let sequence = Self::send_forward(&forward).map_err(|_| {
 			crate::Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoIbcTokenTransferFailed {
 				from: receiver.clone(),
 				to: raw_bytes.clone(),
@@ -892,6 +1223,7 @@ where
 				"Pallet ibc transfer failed to send message".to_string(),
 			)
 		})?;
+		ForwardedPackets::<T>::insert((channel_id, sequence), forward);
 
 		crate::Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoIbcTokenTransferSuccess {
 			from: receiver,
@@ -903,6 +1235,195 @@ where
 		});
 		Ok(())
 	}
+
+	/// Sends the forwarded transfer from the forwarder's account and returns the sequence of the
+	/// packet.
+	fn send_forward(forward: &InFlightForwardOf<T>) -> Result<u64, DispatchError> {
+		let source_channel = ChannelId::new(forward.channel);
+		let sequence = Context::<T>::default()
+			.get_next_sequence_send(&(PortId::transfer(), source_channel))
+			.map_err(|_| crate::Error::<T>::ChannelNotFound)?;
+
+		let params = crate::TransferParams::<<T as frame_system::Config>::AccountId> {
+			to: crate::MultiAddress::Raw(forward.receiver.clone()),
+			source_channel: forward.channel,
+			timeout: ibc_primitives::Timeout::Offset {
+				timestamp: Some(forward.timeout),
+				height: Some(1000),
+			},
+		};
+		crate::Pallet::<T>::transfer(
+			RawOrigin::Signed(forward.forwarder.clone()).into(),
+			params,
+			forward.asset_id.clone(),
+			forward.amount,
+			forward.next_memo.clone(),
+		)?;
+
+		Ok(sequence.into())
+	}
+
+	/// Acknowledges the packet that carried the memo of a forwarded transfer once the transfer
+	/// has completed or failed.
+	fn complete_forward(forward: &InFlightForwardOf<T>, success: bool) {
+		let received = match <RawPacket as Message>::decode(forward.received_packet.as_slice())
+			.map_err(|e| format!("{e:?}"))
+			.and_then(|raw| Packet::try_from(raw).map_err(|e| format!("{e:?}")))
+		{
+			Ok(received) => received,
+			Err(e) => {
+				log::warn!(target: "pallet_ibc", "Failed to decode forwarded packet: {}", e);
+				return
+			},
+		};
+		if success {
+			Self::write_received_ack(&received, Ics20Acknowledgement::success());
+			return
+		}
+		// The failed transfer was refunded to the forwarder, which holds the forwarded amount of
+		// the single token of the packet
+		let credited = decode_packet_data(
+			&Context::<T>::default(),
+			&received.destination_port,
+			received.destination_channel,
+			received.data.as_slice(),
+		)
+		.map(|packet_data| match packet_data.tokens.as_slice() {
+			[token] => vec![PrefixedCoin {
+				denom: token.denom.clone(),
+				amount: forward.amount.saturated_into::<u128>().into(),
+			}],
+			_ => vec![],
+		})
+		.unwrap_or_default();
+		Self::fail_received(&received, credited);
+	}
+
+	/// Reverts the receive of the tokens of `received` and acknowledges it with an error, so
+	/// that the original sender is refunded on the chain the tokens came from. `credited` holds
+	/// the amounts left to the receiver once the middlewares took their fees, in the order of
+	/// the tokens of the packet. If the receive can't be reverted the packet is left
+	/// unacknowledged, the tokens are kept by the receiver until it is acknowledged.
+	fn fail_received(received: &Packet, credited: Vec<PrefixedCoin>) {
+		match Self::unwind_receive(received, credited) {
+			Ok(()) => Self::write_received_ack(
+				received,
+				Ics20Acknowledgement::Error(format!(
+					"{ACK_ERR_STR}: forwarding packet {} failed",
+					received.sequence
+				)),
+			),
+			Err(e) => log::error!(
+				target: "pallet_ibc",
+				"Failed to unwind forwarded packet {}, it is left unacknowledged: {:?}",
+				received.sequence,
+				e
+			),
+		}
+	}
+
+	/// Gives the fees taken from the received tokens back to the receiver, then escrows the
+	/// received native tokens again and burns the received vouchers, the reverse of
+	/// `process_recv_packet`.
+	fn unwind_receive(received: &Packet, credited: Vec<PrefixedCoin>) -> Result<(), Ics20Error> {
+		let mut ctx = Context::<T>::default();
+		let packet_data = decode_packet_data(
+			&ctx,
+			&received.destination_port,
+			received.destination_channel,
+			received.data.as_slice(),
+		)?;
+		let receiver = <T as Config>::AccountIdConversion::try_from(packet_data.receiver)
+			.map_err(|_| Ics20Error::parse_account_failure())?;
+		if packet_data.tokens.is_empty() || credited.len() != packet_data.tokens.len() {
+			return Err(Ics20Error::implementation_specific(
+				"Credited tokens don't match the received tokens".to_string(),
+			))
+		}
+
+		with_transaction(|| {
+			let result =
+				packet_data.tokens.iter().zip(&credited).try_for_each(|(token, credited)| {
+					Self::unwind_token(
+						&mut ctx,
+						received,
+						&receiver,
+						&packet_data.sender,
+						token,
+						credited,
+					)
+				});
+			match result {
+				Ok(()) => TransactionOutcome::Commit(Ok(Ok(()))),
+				Err(e) => TransactionOutcome::Rollback(Ok(Err(e))),
+			}
+		})
+		.map_err(|e: DispatchError| Ics20Error::implementation_specific(format!("{e:?}")))?
+	}
+
+	/// Unwinds the receive of a single token of `received`, of which `credited` was left to the
+	/// receiver.
+	fn unwind_token(
+		ctx: &mut Context<T>,
+		received: &Packet,
+		receiver: &T::AccountIdConversion,
+		sender: &Signer,
+		token: &PrefixedCoin,
+		credited: &PrefixedCoin,
+	) -> Result<(), Ics20Error> {
+		use sp_core::Get;
+		let denom = PrefixedDenom::from_str(&full_ibc_denom(received, token.clone()))?;
+		let fee =
+			token.amount.as_u256().checked_sub(credited.amount.as_u256()).ok_or_else(|| {
+				Ics20Error::implementation_specific(
+					"Credited more tokens than received".to_string(),
+				)
+			})?;
+		if !fee.is_zero() {
+			let fee_coin = PrefixedCoin { denom: denom.clone(), amount: fee.into() };
+			ctx.send_coins(&T::FeeAccount::get(), receiver, &fee_coin)?;
+		}
+		let coin = PrefixedCoin { denom, amount: token.amount };
+		if is_receiver_chain_source(
+			received.source_port.clone(),
+			received.source_channel,
+			&token.denom,
+		) {
+			let escrow = ctx.get_channel_escrow_address(
+				&received.destination_port,
+				received.destination_channel,
+			)?;
+			ctx.send_coins(receiver, &escrow, &coin)?;
+		} else {
+			ctx.burn_coins(receiver, &coin)?;
+		}
+		let asset_id =
+			T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&coin.denom.to_string())
+				.map_err(|_| Ics20Error::invalid_token())?;
+		crate::Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoIbcTokenTransferRefunded {
+			from: receiver.clone().into_account(),
+			to: sender.to_string().into_bytes(),
+			asset_id,
+			amount: coin.amount.as_u256().as_u128().into(),
+			channel: received.destination_channel.sequence(),
+		});
+		Ok(())
+	}
+
+	fn write_received_ack(received: &Packet, ack: Ics20Acknowledgement) {
+		let success = ack.is_successful();
+		if let Err(e) = crate::Pallet::<T>::handle_message(HandlerMessage::WriteAck {
+			packet: received.clone(),
+			ack: ack.to_string().into_bytes(),
+		}) {
+			log::warn!(target: "pallet_ibc", "Failed to acknowledge forwarded packet: {:?}", e);
+		}
+		T::Ics20RateLimiter::on_recv_acknowledged(
+			received.destination_channel,
+			received.sequence,
+			success,
+		);
+	}
 }
 
 impl<T> IbcModule<T>
@@ -0,0 +1,833 @@
+use crate::{
+	ics20::{full_ibc_denom, HandleMemo},
+	routing::Context,
+	DenomToAssetId,
+};
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData};
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::{fungibles::Inspect, Currency},
+};
+use ibc::{
+	applications::transfer::{
+		acknowledgement::{Acknowledgement as Ics20Ack, ACK_ERR_STR},
+		context::{decode_packet_data, Ics20Reader},
+		packet::PacketDataV2,
+		PrefixedCoin,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement,
+			packet::{Packet, Sequence},
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module as IbcModule, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use ibc_primitives::{HandlerMessage, IbcHandler};
+use sp_core::crypto::AccountId32;
+use sp_runtime::{
+	traits::{Get, Saturating, Zero},
+	DispatchError, Perbill,
+};
+
+pub use pallet::*;
+
+/// Accounts for the tokens sent from this chain, so that transfers exceeding the quota of their
+/// channel are rejected.
+pub trait Ics20RateLimiter {
+	/// Called before `coin` is sent over `channel_id` in the packet with the given `sequence`.
+	fn on_send(
+		channel_id: ChannelId,
+		sequence: Sequence,
+		coin: &PrefixedCoin,
+	) -> Result<(), ibc_primitives::Error>;
+
+	/// Called when the deferred acknowledgement of the packet with the given `sequence` received
+	/// over `channel_id` is written. The received tokens were taken back if it failed.
+	fn on_recv_acknowledged(channel_id: ChannelId, sequence: Sequence, success: bool);
+}
+
+impl Ics20RateLimiter for () {
+	fn on_send(
+		_channel_id: ChannelId,
+		_sequence: Sequence,
+		_coin: &PrefixedCoin,
+	) -> Result<(), ibc_primitives::Error> {
+		Ok(())
+	}
+
+	fn on_recv_acknowledged(_channel_id: ChannelId, _sequence: Sequence, _success: bool) {}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::OriginFor;
+	use sp_runtime::{traits::Zero, Perbill};
+
+	/// Maximum net amount of an asset that can be moved over a channel in a rolling window.
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub enum Quota<Balance> {
+		/// Absolute amount of the asset.
+		Absolute(Balance),
+		/// Share of the total supply of the asset, taken when the window starts.
+		SupplyPercentage(Perbill),
+	}
+
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct RateLimit<Balance, BlockNumber> {
+		/// Quota of the tokens sent from this chain, net of the tokens received.
+		pub max_send: Quota<Balance>,
+		/// Quota of the tokens received by this chain, net of the tokens sent.
+		pub max_recv: Quota<Balance>,
+		/// Length of the rolling window in blocks.
+		pub period: BlockNumber,
+	}
+
+	/// Amounts moved over a channel during the current and the previous window.
+	///
+	/// The quotas are checked against a window of `period` blocks ending at the current block,
+	/// which covers the current window and the tail of the previous one. The flows of the
+	/// previous window are assumed to be spread evenly over it, so they count in proportion to
+	/// the part of it that is still covered.
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct Flow<Balance, BlockNumber> {
+		pub inflow: Balance,
+		pub outflow: Balance,
+		pub previous_inflow: Balance,
+		pub previous_outflow: Balance,
+		/// Total supply of the asset when the window started.
+		pub supply: Balance,
+		/// Block at which the current window started.
+		pub window_start: BlockNumber,
+	}
+
+	pub type RateLimitOf<T> =
+		RateLimit<<T as crate::Config>::Balance, <T as frame_system::Config>::BlockNumber>;
+	pub type FlowOf<T> =
+		Flow<<T as crate::Config>::Balance, <T as frame_system::Config>::BlockNumber>;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + crate::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	/// channel_id.sequence(), local denom => rate limit of the asset over the channel
+	pub type RateLimits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		Vec<u8>,
+		RateLimitOf<T>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// channel_id.sequence(), local denom => flow of the asset over the channel in the current
+	/// window
+	pub type Flows<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		Vec<u8>,
+		FlowOf<T>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// channel_id.sequence(), packet sequence => local denoms of the rate limited tokens sent in
+	/// the packet and the start of the window their outflow was recorded in
+	pub type PacketOutflows<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		u64,
+		Vec<(Vec<u8>, <T as frame_system::Config>::BlockNumber)>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	/// channel_id.sequence(), packet sequence => local denoms and amounts of the rate limited
+	/// tokens received in a packet that isn't acknowledged yet, and the start of the window their
+	/// inflow was recorded in
+	pub type PacketInflows<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		u64,
+		Vec<(Vec<u8>, <T as crate::Config>::Balance, <T as frame_system::Config>::BlockNumber)>,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub (super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		RateLimitSet {
+			channel: u64,
+			denom: Vec<u8>,
+			rate_limit: RateLimitOf<T>,
+		},
+		RateLimitRemoved {
+			channel: u64,
+			denom: Vec<u8>,
+		},
+		/// A received packet was rejected because it exceeds the receive quota
+		InflowRateLimited {
+			channel: u64,
+			denom: Vec<u8>,
+			amount: T::Balance,
+		},
+		/// Tokens sent over a channel were refunded and no longer count towards its quota
+		OutflowReverted {
+			channel: u64,
+			denom: Vec<u8>,
+			amount: T::Balance,
+		},
+		/// Tokens received over a channel were taken back and no longer count towards its quota
+		InflowReverted {
+			channel: u64,
+			denom: Vec<u8>,
+			amount: T::Balance,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The transfer exceeds the quota of the channel
+		QuotaExceeded,
+		/// The period of a rate limit must be at least one block
+		InvalidPeriod,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight(0)]
+		pub fn set_rate_limit(
+			origin: OriginFor<T>,
+			channel: u64,
+			denom: Vec<u8>,
+			rate_limit: RateLimitOf<T>,
+		) -> DispatchResult {
+			<T as crate::Config>::AdminOrigin::ensure_origin(origin)?;
+			ensure!(!rate_limit.period.is_zero(), Error::<T>::InvalidPeriod);
+			RateLimits::<T>::insert(channel, &denom, rate_limit.clone());
+			// The new quotas apply from a fresh window
+			Flows::<T>::remove(channel, &denom);
+			Self::deposit_event(Event::<T>::RateLimitSet { channel, denom, rate_limit });
+			Ok(())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight(0)]
+		pub fn remove_rate_limit(
+			origin: OriginFor<T>,
+			channel: u64,
+			denom: Vec<u8>,
+		) -> DispatchResult {
+			<T as crate::Config>::AdminOrigin::ensure_origin(origin)?;
+			RateLimits::<T>::remove(channel, &denom);
+			Flows::<T>::remove(channel, &denom);
+			Self::deposit_event(Event::<T>::RateLimitRemoved { channel, denom });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn quota(quota: &Quota<T::Balance>, supply: T::Balance) -> T::Balance {
+		match quota {
+			Quota::Absolute(amount) => *amount,
+			Quota::SupplyPercentage(percent) => percent.mul_floor(supply),
+		}
+	}
+
+	fn supply(denom: &str) -> T::Balance {
+		match T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(denom) {
+			Ok(asset_id) if asset_id == T::NativeAssetId::get() =>
+				<T::NativeCurrency as Currency<T::AccountId>>::total_issuance(),
+			Ok(asset_id) => <T::Fungibles as Inspect<T::AccountId>>::total_issuance(asset_id),
+			Err(_) => Default::default(),
+		}
+	}
+
+	/// Returns the flow of the window the current block belongs to, the windows that elapsed
+	/// since the last transfer are rolled over.
+	fn current_flow(channel: u64, denom: &str, rate_limit: &RateLimitOf<T>) -> FlowOf<T> {
+		let now = frame_system::Pallet::<T>::block_number();
+		let new_window = |window_start, previous_inflow, previous_outflow| Flow {
+			inflow: Default::default(),
+			outflow: Default::default(),
+			previous_inflow,
+			previous_outflow,
+			supply: Self::supply(denom),
+			window_start,
+		};
+		let flow = match Flows::<T>::get(channel, denom.as_bytes()) {
+			Some(flow) => flow,
+			None => return new_window(now, Default::default(), Default::default()),
+		};
+		let elapsed_windows = now.saturating_sub(flow.window_start) / rate_limit.period;
+		if elapsed_windows.is_zero() {
+			flow
+		} else {
+			let window_start = flow
+				.window_start
+				.saturating_add(elapsed_windows.saturating_mul(rate_limit.period));
+			if elapsed_windows == 1u32.into() {
+				new_window(window_start, flow.inflow, flow.outflow)
+			} else {
+				new_window(window_start, Default::default(), Default::default())
+			}
+		}
+	}
+
+	/// Returns the net outflow and the net inflow over the rolling window ending at the current
+	/// block.
+	fn rolling_flows(flow: &FlowOf<T>, rate_limit: &RateLimitOf<T>) -> (T::Balance, T::Balance) {
+		let now = frame_system::Pallet::<T>::block_number();
+		let elapsed = now.saturating_sub(flow.window_start);
+		let covered =
+			Perbill::from_rational(rate_limit.period.saturating_sub(elapsed), rate_limit.period);
+		let outflow = flow.outflow.saturating_add(covered.mul_floor(flow.previous_outflow));
+		let inflow = flow.inflow.saturating_add(covered.mul_floor(flow.previous_inflow));
+		(outflow.saturating_sub(inflow), inflow.saturating_sub(outflow))
+	}
+
+	fn amount(coin: &PrefixedCoin) -> Result<T::Balance, Error<T>> {
+		u128::try_from(coin.amount.as_u256())
+			.map(Into::into)
+			.map_err(|_| Error::<T>::QuotaExceeded)
+	}
+
+	/// Records `amount` of `denom` leaving this chain over `channel` in the packet with the given
+	/// `sequence`.
+	pub fn record_outflow(
+		channel: u64,
+		sequence: u64,
+		denom: &str,
+		amount: T::Balance,
+	) -> Result<(), Error<T>> {
+		let rate_limit = match RateLimits::<T>::get(channel, denom.as_bytes()) {
+			Some(rate_limit) => rate_limit,
+			None => return Ok(()),
+		};
+		let mut flow = Self::current_flow(channel, denom, &rate_limit);
+		flow.outflow = flow.outflow.saturating_add(amount);
+		let (net_outflow, _) = Self::rolling_flows(&flow, &rate_limit);
+		if net_outflow > Self::quota(&rate_limit.max_send, flow.supply) {
+			return Err(Error::<T>::QuotaExceeded)
+		}
+		PacketOutflows::<T>::append(
+			channel,
+			sequence,
+			(denom.as_bytes().to_vec(), flow.window_start),
+		);
+		Flows::<T>::insert(channel, denom.as_bytes(), flow);
+		Ok(())
+	}
+
+	/// Records `amount` of `denom` entering this chain over `channel` in the packet with the given
+	/// `sequence`.
+	pub fn record_inflow(
+		channel: u64,
+		sequence: u64,
+		denom: &str,
+		amount: T::Balance,
+	) -> Result<(), Error<T>> {
+		let rate_limit = match RateLimits::<T>::get(channel, denom.as_bytes()) {
+			Some(rate_limit) => rate_limit,
+			None => return Ok(()),
+		};
+		let mut flow = Self::current_flow(channel, denom, &rate_limit);
+		flow.inflow = flow.inflow.saturating_add(amount);
+		let (_, net_inflow) = Self::rolling_flows(&flow, &rate_limit);
+		if net_inflow > Self::quota(&rate_limit.max_recv, flow.supply) {
+			return Err(Error::<T>::QuotaExceeded)
+		}
+		PacketInflows::<T>::append(
+			channel,
+			sequence,
+			(denom.as_bytes().to_vec(), amount, flow.window_start),
+		);
+		Flows::<T>::insert(channel, denom.as_bytes(), flow);
+		Ok(())
+	}
+
+	/// Removes the refunded tokens of the packet with the given `sequence` from the outflow of the
+	/// window they were recorded in, as long as that window still counts towards the quota.
+	pub fn revert_outflow(channel: u64, sequence: u64, denom: &str, amount: T::Balance) {
+		let mut outflows = PacketOutflows::<T>::get(channel, sequence);
+		let window_start = match outflows.iter().position(|(d, _)| d == denom.as_bytes()) {
+			Some(index) => outflows.remove(index).1,
+			None => return,
+		};
+		if outflows.is_empty() {
+			PacketOutflows::<T>::remove(channel, sequence);
+		} else {
+			PacketOutflows::<T>::insert(channel, sequence, outflows);
+		}
+
+		if Self::revert_flow(channel, denom, window_start, amount, false) {
+			Self::deposit_event(Event::<T>::OutflowReverted {
+				channel,
+				denom: denom.as_bytes().to_vec(),
+				amount,
+			});
+		}
+	}
+
+	/// Settles the inflow of the packet with the given `sequence` once it is acknowledged. If it
+	/// failed, the received tokens are removed from the inflow of the window they were recorded
+	/// in, as long as that window still counts towards the quota.
+	pub fn settle_inflows(channel: u64, sequence: u64, success: bool) {
+		let inflows = PacketInflows::<T>::take(channel, sequence);
+		if success {
+			return
+		}
+		for (denom, amount, window_start) in inflows {
+			let reverted = core::str::from_utf8(&denom)
+				.map(|denom| Self::revert_flow(channel, denom, window_start, amount, true))
+				.unwrap_or_default();
+			if reverted {
+				Self::deposit_event(Event::<T>::InflowReverted { channel, denom, amount });
+			}
+		}
+	}
+
+	/// Subtracts `amount` from the inflow (or outflow) of the window starting at `window_start`.
+	/// Returns false if the limit was reset or the window no longer counts towards the quota.
+	fn revert_flow(
+		channel: u64,
+		denom: &str,
+		window_start: <T as frame_system::Config>::BlockNumber,
+		amount: T::Balance,
+		inflow: bool,
+	) -> bool {
+		let rate_limit = match RateLimits::<T>::get(channel, denom.as_bytes()) {
+			Some(rate_limit) => rate_limit,
+			None => return false,
+		};
+		// The limit was reset since the tokens were moved
+		if !Flows::<T>::contains_key(channel, denom.as_bytes()) {
+			return false
+		}
+		let mut flow = Self::current_flow(channel, denom, &rate_limit);
+		let (current, previous) = if inflow {
+			(&mut flow.inflow, &mut flow.previous_inflow)
+		} else {
+			(&mut flow.outflow, &mut flow.previous_outflow)
+		};
+		if window_start == flow.window_start {
+			*current = current.saturating_sub(amount);
+		} else if window_start.saturating_add(rate_limit.period) == flow.window_start {
+			*previous = previous.saturating_sub(amount);
+		} else {
+			// The window has rolled past the transfer
+			return false
+		}
+		Flows::<T>::insert(channel, denom.as_bytes(), flow);
+		true
+	}
+}
+
+impl<T: Config> Ics20RateLimiter for Pallet<T> {
+	fn on_send(
+		channel_id: ChannelId,
+		sequence: Sequence,
+		coin: &PrefixedCoin,
+	) -> Result<(), ibc_primitives::Error> {
+		Self::amount(coin)
+			.and_then(|amount| {
+				Self::record_outflow(
+					channel_id.sequence(),
+					sequence.into(),
+					&coin.denom.to_string(),
+					amount,
+				)
+			})
+			.map_err(|_| ibc_primitives::Error::RateLimitExceeded {
+				msg: Some(format!("Sending {coin} over {channel_id} exceeds its quota")),
+			})
+	}
+
+	fn on_recv_acknowledged(channel_id: ChannelId, sequence: Sequence, success: bool) {
+		Self::settle_inflows(channel_id.sequence(), sequence.into(), success)
+	}
+}
+
+/// Middleware enforcing the receive quotas and restoring the send quotas of refunded transfers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ics20RateLimit<T: Config, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> {
+	inner: S,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> Default
+	for Ics20RateLimit<T, S>
+{
+	fn default() -> Self {
+		Self { inner: S::default(), _phantom: Default::default() }
+	}
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> IbcModule
+	for Ics20RateLimit<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_open_init(
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		self.inner.on_chan_open_try(
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			counterparty_version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner
+			.on_chan_open_ack(ctx, output, port_id, channel_id, counterparty_version, relayer)
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_open_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_close_init(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_order: Order,
+		proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		self.inner.on_chan_upgrade_init(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			proposed_order,
+			proposed_connection_hops,
+			proposed_version,
+		)
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_order: Order,
+		proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		self.inner.on_chan_upgrade_try(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			proposed_order,
+			proposed_connection_hops,
+			proposed_version,
+		)
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Ics04Error> {
+		self.inner
+			.on_chan_upgrade_ack(ctx, output, port_id, channel_id, counterparty_version)
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_upgrade_open(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_recv_packet(
+		&self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<Acknowledgement, Ics04Error> {
+		// Packets that can't be decoded are rejected by the inner module
+		let inflows = match Self::inflows(packet) {
+			Some(inflows) => inflows,
+			None => return self.inner.on_recv_packet(ctx, output, packet, relayer),
+		};
+		let channel = packet.destination_channel.sequence();
+		let sequence = u64::from(packet.sequence);
+
+		let recorded = with_transaction(|| {
+			for (denom, amount) in &inflows {
+				if Pallet::<T>::record_inflow(channel, sequence, denom, *amount).is_err() {
+					return TransactionOutcome::Rollback(Ok(Err((denom.clone(), *amount))))
+				}
+			}
+			TransactionOutcome::Commit(Ok(Ok(())))
+		})
+		.map_err(|e: DispatchError| Ics04Error::implementation_specific(format!("{e:?}")))?;
+
+		if let Err((denom, amount)) = recorded {
+			Pallet::<T>::deposit_event(Event::<T>::InflowRateLimited {
+				channel,
+				denom: denom.as_bytes().to_vec(),
+				amount,
+			});
+			let ack = Ics20Ack::Error(format!(
+				"{ACK_ERR_STR}: receiving {denom} over {} exceeds its quota",
+				packet.destination_channel
+			))
+			.to_string()
+			.into_bytes();
+			crate::Pallet::<T>::handle_message(HandlerMessage::WriteAck {
+				packet: packet.clone(),
+				ack: ack.clone(),
+			})
+			.map_err(|e| Ics04Error::implementation_specific(format!("[on_recv_packet] {e:#?}")))?;
+			return Ok(Acknowledgement::from_bytes(ack))
+		}
+
+		let ack = self.inner.on_recv_packet(ctx, output, packet, relayer)?;
+		let successful = serde_json::from_slice::<Ics20Ack>(ack.as_ref())
+			.map(|ack| ack.is_successful())
+			.unwrap_or_default();
+		// An empty acknowledgement is written once the received tokens have been forwarded, and a
+		// deferred one once the memo has been executed. The inflow is settled then.
+		let deferred = successful && T::HandleMemo::default().defers_acknowledgement(packet);
+		if ack.as_ref().is_empty() || deferred {
+			return Ok(ack)
+		}
+		Pallet::<T>::settle_inflows(channel, sequence, successful);
+		Ok(ack)
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &Acknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let forwarded = Self::forwarded_receive(packet);
+		self.inner
+			.on_acknowledgement_packet(ctx, output, packet, acknowledgement, relayer)?;
+		let successful = serde_json::from_slice::<Ics20Ack>(acknowledgement.as_ref())
+			.map(|ack| ack.is_successful())
+			.unwrap_or_default();
+		if let Some((channel, sequence)) = forwarded {
+			Pallet::<T>::settle_inflows(channel, sequence, successful);
+		}
+		if successful {
+			PacketOutflows::<T>::remove(
+				packet.source_channel.sequence(),
+				u64::from(packet.sequence),
+			);
+		} else {
+			Self::revert_outflows(packet);
+		}
+		Ok(())
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let forwarded = Self::forwarded_receive(packet);
+		self.inner.on_timeout_packet(ctx, output, packet, relayer)?;
+		if let Some((channel, sequence)) = forwarded {
+			Pallet::<T>::settle_inflows(channel, sequence, false);
+		}
+		Self::revert_outflows(packet);
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug>
+	Ics20RateLimit<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn packet_data(
+		packet: &Packet,
+		port_id: &PortId,
+		channel_id: ChannelId,
+	) -> Option<PacketDataV2> {
+		decode_packet_data(&Context::<T>::default(), port_id, channel_id, packet.data.as_slice())
+			.map_err(
+				|e| log::trace!(target: "pallet_ibc", "[rate_limit]: failed to decode packet data: {e:?}"),
+			)
+			.ok()
+	}
+
+	/// Returns the local denoms and amounts of the tokens received in `packet`.
+	fn inflows(packet: &Packet) -> Option<Vec<(String, T::Balance)>> {
+		let data = Self::packet_data(packet, &packet.destination_port, packet.destination_channel)?;
+		data.tokens
+			.into_iter()
+			.map(|token| {
+				let amount = Pallet::<T>::amount(&token).ok()?;
+				Some((full_ibc_denom(packet, token), amount))
+			})
+			.collect()
+	}
+
+	/// Returns the destination channel and the sequence of the received packet whose tokens were
+	/// forwarded in `packet`, if any. The received packet is acknowledged once `packet` completes.
+	fn forwarded_receive(packet: &Packet) -> Option<(u64, u64)> {
+		let key = (packet.source_port.clone(), packet.source_channel, packet.sequence);
+		let received = Context::<T>::default().get_forwarded_packet(&key)?;
+		Some((received.destination_channel.sequence(), u64::from(received.sequence)))
+	}
+
+	fn revert_outflows(packet: &Packet) {
+		let channel = packet.source_channel.sequence();
+		let sequence = u64::from(packet.sequence);
+		if let Some(data) = Self::packet_data(packet, &packet.source_port, packet.source_channel) {
+			for token in data.tokens {
+				if let Ok(amount) = Pallet::<T>::amount(&token) {
+					Pallet::<T>::revert_outflow(
+						channel,
+						sequence,
+						&token.denom.to_string(),
+						amount,
+					);
+				}
+			}
+		}
+		PacketOutflows::<T>::remove(channel, sequence);
+	}
+}
@@ -0,0 +1,239 @@
+use super::{pallet::*, Config};
+use crate::routing::Context;
+use alloc::{format, string::ToString};
+use core::{fmt::Debug, marker::PhantomData};
+use ibc::{
+	applications::{
+		interchain_accounts::{controller_port_owner, host_port_id, metadata::Metadata},
+		transfer::acknowledgement::Acknowledgement as JsonAcknowledgement,
+	},
+	core::{
+		ics03_connection::context::ConnectionReader,
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			context::ChannelReader,
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module as IbcModule, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use sp_core::crypto::AccountId32;
+
+/// Controller submodule, it's routed all the `icacontroller-` ports.
+#[derive(Clone, PartialEq, Eq)]
+pub struct IcaController<T: Config>(PhantomData<T>);
+
+impl<T: Config> Default for IcaController<T> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Debug for IcaController<T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "ics27-controller")
+	}
+}
+
+pub(super) fn counterparty_connection_id<T: Config + Send + Sync>(
+	connection_id: &ConnectionId,
+) -> Result<ConnectionId, Ics04Error>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+{
+	let ctx = Context::<T>::default();
+	ctx.connection_end(connection_id)
+		.map_err(Ics04Error::ics03_connection)?
+		.counterparty()
+		.connection_id()
+		.cloned()
+		.ok_or_else(|| Ics04Error::undefined_connection_counterparty(connection_id.clone()))
+}
+
+impl<T: Config + Send + Sync> IbcModule for IcaController<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		_channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		if order != Order::Ordered {
+			return Err(Ics04Error::unknown_order_type(order.to_string()))
+		}
+		if controller_port_owner(port_id).is_none() || counterparty.port_id() != &host_port_id() {
+			return Err(Ics04Error::app_module(format!(
+				"Invalid interchain account ports {port_id} and {}",
+				counterparty.port_id()
+			)))
+		}
+		let connection_id = connection_hops.first().ok_or_else(|| {
+			Ics04Error::app_module("Interchain account channels need a connection".to_string())
+		})?;
+		let counterparty_connection_id = counterparty_connection_id::<T>(connection_id)?;
+		Metadata::from_version(version)
+			.and_then(|metadata| metadata.validate(connection_id, &counterparty_connection_id))
+			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		if Pallet::<T>::active_channel(connection_id, port_id).is_some() {
+			return Err(Ics04Error::app_module(format!(
+				"Interchain account of {port_id} already has an open channel on {connection_id}"
+			)))
+		}
+		Ok(())
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		_counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		Err(Ics04Error::app_module(
+			"Interchain account channels can only be opened by the controller".to_string(),
+		))
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let ctx = Context::<T>::default();
+		let channel_end = ctx.channel_end(&(port_id.clone(), *channel_id))?;
+		let connection_id = channel_end.connection_hops().first().ok_or_else(|| {
+			Ics04Error::app_module("Interchain account channels need a connection".to_string())
+		})?;
+		let counterparty_connection_id = counterparty_connection_id::<T>(connection_id)?;
+		let metadata = Metadata::from_version(counterparty_version)
+			.and_then(|metadata| {
+				metadata.validate(connection_id, &counterparty_connection_id)?;
+				Ok(metadata)
+			})
+			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		if metadata.address.is_empty() {
+			return Err(Ics04Error::app_module(
+				"Host did not provide the interchain account address".to_string(),
+			))
+		}
+
+		let connection_id = connection_id.as_bytes().to_vec();
+		let port_id = port_id.as_bytes().to_vec();
+		let address = metadata.address.into_bytes();
+		ActiveChannels::<T>::insert(&connection_id, &port_id, channel_id.sequence());
+		InterchainAccounts::<T>::insert(&connection_id, &port_id, &address);
+		Pallet::<T>::deposit_event(Event::<T>::InterchainAccountRegistered {
+			connection_id,
+			port_id,
+			channel_id: channel_id.sequence(),
+			address,
+		});
+		Ok(())
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics04Error::app_module(
+			"Interchain account channels can only be opened by the controller".to_string(),
+		))
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics04Error::app_module(
+			"Interchain account channels can't be closed by the controller".to_string(),
+		))
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Ok(())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<Acknowledgement, Ics04Error> {
+		Err(Ics04Error::app_module(
+			"Interchain account controllers don't receive packets".to_string(),
+		))
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &Acknowledgement,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let success = serde_json::from_slice::<JsonAcknowledgement>(acknowledgement.as_ref())
+			.map(|ack| ack.is_successful())
+			.unwrap_or(false);
+		Pallet::<T>::deposit_event(Event::<T>::InterchainTxAcknowledged {
+			port_id: packet.source_port.as_bytes().to_vec(),
+			sequence: packet.sequence.into(),
+			success,
+		});
+		Ok(())
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Pallet::<T>::deposit_event(Event::<T>::InterchainTxTimedOut {
+			port_id: packet.source_port.as_bytes().to_vec(),
+			sequence: packet.sequence.into(),
+		});
+		Ok(())
+	}
+}
@@ -0,0 +1,323 @@
+use super::{controller::counterparty_connection_id, pallet::*, Config, RUNTIME_CALL_TYPE_URL};
+use crate::routing::Context;
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+use codec::Decode;
+use core::{fmt::Debug, marker::PhantomData};
+use frame_support::{
+	dispatch::GetDispatchInfo,
+	pallet_prelude::Weight,
+	storage::{with_transaction, TransactionOutcome},
+	traits::{Contains, Get},
+};
+use ibc::{
+	applications::{
+		interchain_accounts::{
+			controller_port_owner, host_port_id, metadata::Metadata,
+			packet::InterchainAccountPacketData,
+		},
+		transfer::acknowledgement::Acknowledgement as JsonAcknowledgement,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			context::ChannelReader,
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module as IbcModule, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use ibc_primitives::{HandlerMessage, IbcHandler};
+use sp_core::crypto::AccountId32;
+use sp_runtime::{traits::Dispatchable, DispatchError};
+
+/// Host submodule, it's routed the `icahost` port.
+#[derive(Clone, PartialEq, Eq)]
+pub struct IcaHost<T: Config>(PhantomData<T>);
+
+impl<T: Config> Default for IcaHost<T> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> Debug for IcaHost<T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "ics27-host")
+	}
+}
+
+/// Decodes the runtime calls of an interchain account transaction, rejecting the calls that are
+/// not allowed by [`Config::CallFilter`].
+pub(super) fn decode_calls<T: Config>(
+	packet: &Packet,
+) -> Result<Vec<<T as Config>::RuntimeCall>, String> {
+	let data =
+		InterchainAccountPacketData::try_from(packet.data.as_slice()).map_err(|e| e.to_string())?;
+	let calls = data
+		.cosmos_tx()
+		.map_err(|e| e.to_string())?
+		.messages
+		.into_iter()
+		.map(|msg| {
+			if msg.type_url != RUNTIME_CALL_TYPE_URL {
+				return Err(format!("Unsupported message type {}", msg.type_url))
+			}
+			let call = <T as Config>::RuntimeCall::decode(&mut &*msg.value)
+				.map_err(|e| format!("Failed to decode runtime call: {e:?}"))?;
+			if !T::CallFilter::contains(&call) {
+				return Err("Runtime call is not allowed".to_string())
+			}
+			Ok(call)
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	if calls.is_empty() {
+		return Err("Transaction has no messages".to_string())
+	}
+	Ok(calls)
+}
+
+impl<T: Config + Send + Sync> IcaHost<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	/// Executes the calls of the packet from the interchain account, they are reverted if any of
+	/// them fails. Transactions heavier than [`Config::MaxHostTxWeight`] are rejected.
+	fn execute_tx(account: &T::AccountId, packet: &Packet) -> Result<(), String> {
+		let calls = decode_calls::<T>(packet)?;
+		let weight = calls
+			.iter()
+			.fold(Weight::zero(), |acc, call| acc.saturating_add(call.get_dispatch_info().weight));
+		if weight.any_gt(T::MaxHostTxWeight::get()) {
+			return Err(format!("Transaction weight {weight:?} exceeds the limit"))
+		}
+
+		with_transaction(|| {
+			for call in calls {
+				let origin = frame_system::RawOrigin::Signed(account.clone()).into();
+				if let Err(e) = call.dispatch(origin) {
+					return TransactionOutcome::Rollback(Err(e.error))
+				}
+			}
+			TransactionOutcome::Commit(Ok::<_, DispatchError>(()))
+		})
+		.map_err(|e| format!("Failed to execute runtime call: {e:?}"))
+	}
+}
+
+impl<T: Config + Send + Sync> IbcModule for IcaHost<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics04Error::app_module(
+			"Interchain account channels can only be opened by the controller".to_string(),
+		))
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		_channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		_version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		if order != Order::Ordered {
+			return Err(Ics04Error::unknown_order_type(order.to_string()))
+		}
+		if port_id != &host_port_id() || controller_port_owner(counterparty.port_id()).is_none() {
+			return Err(Ics04Error::app_module(format!(
+				"Invalid interchain account ports {port_id} and {}",
+				counterparty.port_id()
+			)))
+		}
+		let connection_id = connection_hops.first().ok_or_else(|| {
+			Ics04Error::app_module("Interchain account channels need a connection".to_string())
+		})?;
+		let controller_connection_id = counterparty_connection_id::<T>(connection_id)?;
+		let metadata = Metadata::from_version(counterparty_version)
+			.and_then(|metadata| {
+				metadata.validate(&controller_connection_id, connection_id)?;
+				Ok(metadata)
+			})
+			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
+
+		let controller_port_id = counterparty.port_id();
+		if Pallet::<T>::active_host_channel(connection_id, controller_port_id).is_some() {
+			return Err(Ics04Error::app_module(format!(
+				"Interchain account of {controller_port_id} already has an open channel on {connection_id}"
+			)))
+		}
+		let account = Pallet::<T>::host_account(connection_id, controller_port_id);
+		let address = Pallet::<T>::account_to_string(account.clone());
+		let connection_id = connection_id.as_bytes().to_vec();
+		let port_id = controller_port_id.as_bytes().to_vec();
+		HostAccounts::<T>::insert(&connection_id, &port_id, &account);
+		Pallet::<T>::deposit_event(Event::<T>::HostAccountRegistered {
+			connection_id,
+			port_id,
+			account,
+		});
+		Ok(metadata.with_address(address).to_version())
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics04Error::app_module(
+			"Interchain account channels can only be opened by the controller".to_string(),
+		))
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let ctx = Context::<T>::default();
+		let channel_end = ctx.channel_end(&(port_id.clone(), *channel_id))?;
+		let connection_id = channel_end.connection_hops().first().ok_or_else(|| {
+			Ics04Error::app_module("Interchain account channels need a connection".to_string())
+		})?;
+		ActiveChannels::<T>::insert(
+			connection_id.as_bytes(),
+			channel_end.counterparty().port_id().as_bytes(),
+			channel_id.sequence(),
+		);
+		Ok(())
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics04Error::app_module(
+			"Interchain account channels can't be closed by the host".to_string(),
+		))
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Ok(())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<Acknowledgement, Ics04Error> {
+		let ctx = Context::<T>::default();
+		let channel_end =
+			ctx.channel_end(&(packet.destination_port.clone(), packet.destination_channel))?;
+		let connection_id = channel_end.connection_hops().first().ok_or_else(|| {
+			Ics04Error::app_module("Interchain account channels need a connection".to_string())
+		})?;
+		if Pallet::<T>::active_host_channel(connection_id, &packet.source_port) !=
+			Some(packet.destination_channel)
+		{
+			return Err(Ics04Error::app_module(format!(
+				"{} is not the active channel of the interchain account of {}",
+				packet.destination_channel, packet.source_port
+			)))
+		}
+		let account =
+			HostAccounts::<T>::get(connection_id.as_bytes(), packet.source_port.as_bytes())
+				.ok_or_else(|| {
+					Ics04Error::app_module(format!(
+						"No interchain account for {} on {connection_id}",
+						packet.source_port
+					))
+				})?;
+
+		let result = Self::execute_tx(&account, packet);
+		Pallet::<T>::deposit_event(Event::<T>::HostTxExecuted {
+			account,
+			sequence: packet.sequence.into(),
+			success: result.is_ok(),
+		});
+		let ack = match result {
+			Ok(()) => JsonAcknowledgement::success(),
+			Err(e) => {
+				log::debug!(target: "pallet_ibc", "Failed to execute interchain account tx: {e}");
+				JsonAcknowledgement::Error(e)
+			},
+		}
+		.to_string()
+		.into_bytes();
+		crate::Pallet::<T>::handle_message(HandlerMessage::WriteAck {
+			packet: packet.clone(),
+			ack: ack.clone(),
+		})
+		.map_err(|e| Ics04Error::implementation_specific(format!("{e:?}")))?;
+		Ok(ack.into())
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_packet: &mut Packet,
+		_acknowledgement: &Acknowledgement,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics04Error::app_module("Interchain account hosts don't send packets".to_string()))
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics04Error::app_module("Interchain account hosts don't send packets".to_string()))
+	}
+}
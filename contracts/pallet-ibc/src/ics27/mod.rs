@@ -0,0 +1,352 @@
+//! ICS-27 interchain accounts. The controller submodule lets an account of this chain open an
+//! interchain account on a host chain and send it transactions, while the host submodule derives
+//! an account for every controller and executes the calls it receives from them.
+//!
+//! Transactions sent to this chain are
+//! [`CosmosTx`](ibc::applications::interchain_accounts::packet::CosmosTx)s whose messages are SCALE
+//! encoded runtime calls with the [`RUNTIME_CALL_TYPE_URL`] type url. Only the calls allowed by
+//! [`Config::CallFilter`] are executed, all of the calls of a transaction are reverted if any of
+//! them fails. Transactions whose calls weigh more than [`Config::MaxHostTxWeight`] are rejected.
+
+use crate::routing::Context;
+use alloc::{string::String, vec, vec::Vec};
+use codec::{Decode, Encode};
+use ibc::{
+	applications::interchain_accounts::{
+		controller_port_id, host_port_id,
+		metadata::Metadata,
+		packet::{CosmosTx, InterchainAccountPacketData},
+	},
+	core::{
+		ics03_connection::context::ConnectionReader,
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, Order, State},
+			context::ChannelReader,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+	},
+};
+use ibc_primitives::{runtime_interface, HandlerMessage, IbcHandler, Timeout};
+use ibc_proto::google::protobuf::Any;
+use sp_core::crypto::AccountId32;
+use sp_runtime::traits::{Get, TrailingZeroInput};
+
+mod controller;
+mod host;
+mod weight;
+
+pub use controller::IcaController;
+pub use host::IcaHost;
+pub use pallet::*;
+pub use weight::{WeightHandler, WeightInfo};
+
+/// Type url of the runtime calls executed by interchain accounts hosted on this chain
+pub const RUNTIME_CALL_TYPE_URL: &str = "/pallet_ibc.ics27.RuntimeCall";
+
+/// Message executed by an interchain account on the host chain, eg. a protobuf encoded
+/// `/cosmos.bank.v1beta1.MsgSend`
+#[derive(Clone, PartialEq, Eq, Encode, Decode, sp_runtime::RuntimeDebug, scale_info::TypeInfo)]
+pub struct InterchainMessage {
+	pub type_url: Vec<u8>,
+	pub value: Vec<u8>,
+}
+
+impl TryFrom<InterchainMessage> for Any {
+	type Error = ();
+
+	fn try_from(msg: InterchainMessage) -> Result<Self, Self::Error> {
+		Ok(Any { type_url: String::from_utf8(msg.type_url).map_err(|_| ())?, value: msg.value })
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		dispatch::{GetDispatchInfo, PostDispatchInfo},
+		pallet_prelude::*,
+		traits::Contains,
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::Dispatchable;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + crate::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Runtime calls executed by the interchain accounts hosted on this chain
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + GetDispatchInfo;
+		/// Calls that interchain accounts hosted on this chain are allowed to execute
+		type CallFilter: Contains<<Self as Config>::RuntimeCall>;
+		/// Maximum total weight of the calls of a transaction executed by an interchain account
+		/// hosted on this chain
+		#[pallet::constant]
+		type MaxHostTxWeight: Get<Weight>;
+		/// Weight information for the extrinsics and callbacks of this pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	/// Channel of the interchain accounts, keyed by connection_id and the controller port_id
+	pub type ActiveChannels<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Vec<u8>, Blake2_128Concat, Vec<u8>, u64, OptionQuery>;
+
+	#[pallet::storage]
+	/// Address on the host chain of the interchain accounts controlled from this chain, keyed by
+	/// connection_id and the controller port_id
+	pub type InterchainAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// Interchain accounts hosted on this chain, keyed by connection_id and the controller
+	/// port_id
+	pub type HostAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		T::AccountId,
+		OptionQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub (super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The channel of an interchain account was initialized
+		InterchainAccountRegistrationStarted {
+			owner: T::AccountId,
+			connection_id: Vec<u8>,
+			port_id: Vec<u8>,
+		},
+		/// The channel of an interchain account controlled from this chain was opened
+		InterchainAccountRegistered {
+			connection_id: Vec<u8>,
+			port_id: Vec<u8>,
+			channel_id: u64,
+			address: Vec<u8>,
+		},
+		/// A transaction was sent to an interchain account
+		InterchainTxSent { owner: T::AccountId, connection_id: Vec<u8>, channel_id: u64 },
+		/// A transaction sent to an interchain account was acknowledged
+		InterchainTxAcknowledged { port_id: Vec<u8>, sequence: u64, success: bool },
+		/// A transaction sent to an interchain account timed out, the channel is closed
+		InterchainTxTimedOut { port_id: Vec<u8>, sequence: u64 },
+		/// A channel was opened for an interchain account hosted on this chain
+		HostAccountRegistered { connection_id: Vec<u8>, port_id: Vec<u8>, account: T::AccountId },
+		/// A transaction was executed by an interchain account hosted on this chain
+		HostTxExecuted { account: T::AccountId, sequence: u64, success: bool },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Connection id is not valid utf8 or was not found
+		ConnectionNotFound,
+		/// Owner can't be encoded into a port id
+		InvalidOwner,
+		/// Interchain account already has an open channel
+		ActiveChannelExists,
+		/// Interchain account has no open channel
+		ActiveChannelNotFound,
+		/// Message type url is not valid utf8
+		InvalidMessage,
+		/// Failed to open the channel
+		ChannelInitError,
+		/// Failed to send the transaction
+		SendTxError,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T>
+	where
+		T: Send + Sync,
+		u32: From<<T as frame_system::Config>::BlockNumber>,
+		AccountId32: From<<T as frame_system::Config>::AccountId>,
+	{
+		/// Opens an ordered channel to the host chain on the given connection, the address of the
+		/// interchain account is available once the channel handshake completes.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_interchain_account())]
+		#[frame_support::transactional]
+		pub fn register_interchain_account(
+			origin: OriginFor<T>,
+			connection_id: Vec<u8>,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let port_id = Self::controller_port(&owner)?;
+			let connection_id = Self::connection_id(&connection_id)?;
+			ensure!(
+				Self::active_channel(&connection_id, &port_id).is_none(),
+				Error::<T>::ActiveChannelExists
+			);
+			let ctx = Context::<T>::default();
+			let connection_end =
+				ctx.connection_end(&connection_id).map_err(|_| Error::<T>::ConnectionNotFound)?;
+			let counterparty_connection_id = connection_end
+				.counterparty()
+				.connection_id()
+				.cloned()
+				.ok_or(Error::<T>::ConnectionNotFound)?;
+			let channel_end = ChannelEnd::new(
+				State::Init,
+				Order::Ordered,
+				Counterparty::new(host_port_id(), None),
+				vec![connection_id.clone()],
+				Metadata::new(&connection_id, &counterparty_connection_id).to_version(),
+			);
+			crate::Pallet::<T>::handle_message(HandlerMessage::OpenChannel {
+				port_id: port_id.clone(),
+				channel_end,
+			})
+			.map_err(|e| {
+				log::debug!(target: "pallet_ibc", "[register_interchain_account] error: {e:?}");
+				Error::<T>::ChannelInitError
+			})?;
+			Self::deposit_event(Event::<T>::InterchainAccountRegistrationStarted {
+				owner,
+				connection_id: connection_id.as_bytes().to_vec(),
+				port_id: port_id.as_bytes().to_vec(),
+			});
+			Ok(())
+		}
+
+		/// Sends a transaction to the interchain account of the sender on the given connection.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::send_tx())]
+		#[frame_support::transactional]
+		pub fn send_tx(
+			origin: OriginFor<T>,
+			connection_id: Vec<u8>,
+			messages: Vec<InterchainMessage>,
+			memo: Vec<u8>,
+			timeout: Timeout,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let port_id = Self::controller_port(&owner)?;
+			let connection_id = Self::connection_id(&connection_id)?;
+			let channel_id = Self::active_channel(&connection_id, &port_id)
+				.ok_or(Error::<T>::ActiveChannelNotFound)?;
+			let messages = messages
+				.into_iter()
+				.map(Any::try_from)
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(|_| Error::<T>::InvalidMessage)?;
+			let memo = String::from_utf8(memo).map_err(|_| Error::<T>::InvalidMessage)?;
+			let data = InterchainAccountPacketData::execute_tx(CosmosTx { messages }, memo);
+			crate::Pallet::<T>::handle_message(HandlerMessage::SendPacket {
+				data: data.to_bytes(),
+				timeout,
+				port_id,
+				channel_id,
+			})
+			.map_err(|e| {
+				log::debug!(target: "pallet_ibc", "[send_tx] error: {e:?}");
+				Error::<T>::SendTxError
+			})?;
+			Self::deposit_event(Event::<T>::InterchainTxSent {
+				owner,
+				connection_id: connection_id.as_bytes().to_vec(),
+				channel_id: channel_id.sequence(),
+			});
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T>
+	where
+		AccountId32: From<<T as frame_system::Config>::AccountId>,
+	{
+		/// Port bound by the interchain accounts of the owner
+		pub fn controller_port(owner: &T::AccountId) -> Result<PortId, Error<T>> {
+			controller_port_id(&Self::account_to_string(owner.clone()))
+				.map_err(|_| Error::<T>::InvalidOwner)
+		}
+
+		/// SS58 address of the account
+		pub(crate) fn account_to_string(account: T::AccountId) -> String {
+			let account_id_32: AccountId32 = account.into();
+			runtime_interface::account_id_to_ss58(
+				account_id_32.into(),
+				<T as frame_system::Config>::SS58Prefix::get(),
+			)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn connection_id(connection_id: &[u8]) -> Result<ConnectionId, Error<T>> {
+			core::str::from_utf8(connection_id)
+				.ok()
+				.and_then(|connection_id| connection_id.parse().ok())
+				.ok_or(Error::<T>::ConnectionNotFound)
+		}
+
+		/// Open channel of the interchain account bound to the port on the given connection
+		pub fn active_channel(connection_id: &ConnectionId, port_id: &PortId) -> Option<ChannelId>
+		where
+			T: Send + Sync,
+			u32: From<<T as frame_system::Config>::BlockNumber>,
+		{
+			Self::open_channel(connection_id, port_id, port_id)
+		}
+
+		/// Open channel of the interchain account hosted on this chain for the controller port on
+		/// the given connection
+		pub fn active_host_channel(
+			connection_id: &ConnectionId,
+			controller_port_id: &PortId,
+		) -> Option<ChannelId>
+		where
+			T: Send + Sync,
+			u32: From<<T as frame_system::Config>::BlockNumber>,
+		{
+			Self::open_channel(connection_id, controller_port_id, &host_port_id())
+		}
+
+		fn open_channel(
+			connection_id: &ConnectionId,
+			controller_port_id: &PortId,
+			port_id: &PortId,
+		) -> Option<ChannelId>
+		where
+			T: Send + Sync,
+			u32: From<<T as frame_system::Config>::BlockNumber>,
+		{
+			let channel_id = ChannelId::new(ActiveChannels::<T>::get(
+				connection_id.as_bytes(),
+				controller_port_id.as_bytes(),
+			)?);
+			let ctx = Context::<T>::default();
+			// ordered channels are closed when a packet times out, a new channel can then be
+			// opened for the same account
+			ctx.channel_end(&(port_id.clone(), channel_id))
+				.ok()
+				.filter(|channel_end| channel_end.state_matches(&State::Open))
+				.map(|_| channel_id)
+		}
+
+		/// Account hosted on this chain for the controller port on the given connection, it's
+		/// derived from them so that it doesn't change if the channel is reopened.
+		pub fn host_account(connection_id: &ConnectionId, port_id: &PortId) -> T::AccountId {
+			let entropy = (b"ics27-host", connection_id.as_bytes(), port_id.as_bytes())
+				.using_encoded(sp_io::hashing::blake2_256);
+			Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+				.expect("infinite length input; no invalid inputs for type; qed")
+		}
+	}
+}
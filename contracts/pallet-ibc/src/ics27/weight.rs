@@ -0,0 +1,114 @@
+use super::{host::decode_calls, Config};
+use core::marker::PhantomData;
+use frame_support::{
+	dispatch::GetDispatchInfo,
+	pallet_prelude::Weight,
+	traits::Get,
+	weights::constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_MICROS},
+};
+use ibc::core::{
+	ics04_channel::{msgs::acknowledgement::Acknowledgement, packet::Packet},
+	ics24_host::identifier::{ChannelId, PortId},
+};
+use ibc_primitives::CallbackWeight;
+
+pub trait WeightInfo {
+	fn register_interchain_account() -> Weight;
+	fn send_tx() -> Weight;
+	fn on_chan_open() -> Weight;
+	fn on_recv_packet() -> Weight;
+	fn on_acknowledgement_packet() -> Weight;
+}
+
+/// Estimates from the storage accessed by each call, for runtimes that don't run the benchmarks.
+impl WeightInfo for () {
+	fn register_interchain_account() -> Weight {
+		Weight::from_parts(50 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().reads_writes(8, 6))
+	}
+
+	fn send_tx() -> Weight {
+		Weight::from_parts(40 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().reads_writes(6, 4))
+	}
+
+	fn on_chan_open() -> Weight {
+		Weight::from_parts(20 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().reads_writes(3, 1))
+	}
+
+	fn on_recv_packet() -> Weight {
+		Weight::from_parts(40 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().reads_writes(5, 4))
+	}
+
+	fn on_acknowledgement_packet() -> Weight {
+		Weight::from_parts(10 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().reads_writes(1, 0))
+	}
+}
+
+/// Callback weights of the interchain account ports, the weight of the calls executed by a
+/// hosted account is added to the weight of the packet that carries them.
+pub struct WeightHandler<T: Config>(PhantomData<T>);
+
+impl<T: Config> Default for WeightHandler<T> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> CallbackWeight for WeightHandler<T> {
+	fn on_chan_open_init(&self) -> Weight {
+		<T as Config>::WeightInfo::on_chan_open()
+	}
+
+	fn on_chan_open_try(&self) -> Weight {
+		<T as Config>::WeightInfo::on_chan_open()
+	}
+
+	fn on_chan_open_ack(&self, _port_id: &PortId, _channel_id: &ChannelId) -> Weight {
+		<T as Config>::WeightInfo::on_chan_open()
+	}
+
+	fn on_chan_open_confirm(&self, _port_id: &PortId, _channel_id: &ChannelId) -> Weight {
+		<T as Config>::WeightInfo::on_chan_open()
+	}
+
+	fn on_chan_close_init(&self, _port_id: &PortId, _channel_id: &ChannelId) -> Weight {
+		Weight::default()
+	}
+
+	fn on_chan_close_confirm(&self, _port_id: &PortId, _channel_id: &ChannelId) -> Weight {
+		Weight::default()
+	}
+
+	fn on_recv_packet(&self, packet: &Packet) -> Weight {
+		// Transactions over the limit are rejected before any call is dispatched.
+		let calls_weight = decode_calls::<T>(packet)
+			.map(|calls| {
+				calls.iter().fold(Weight::zero(), |acc, call| {
+					acc.saturating_add(call.get_dispatch_info().weight)
+				})
+			})
+			.unwrap_or_default();
+		let calls_weight = if calls_weight.any_gt(T::MaxHostTxWeight::get()) {
+			Weight::zero()
+		} else {
+			calls_weight
+		};
+		<T as Config>::WeightInfo::on_recv_packet().saturating_add(calls_weight)
+	}
+
+	fn on_acknowledgement_packet(
+		&self,
+		_packet: &Packet,
+		_acknowledgement: &Acknowledgement,
+	) -> Weight {
+		<T as Config>::WeightInfo::on_acknowledgement_packet()
+	}
+
+	fn on_timeout_packet(&self, _packet: &Packet) -> Weight {
+		<T as Config>::WeightInfo::on_acknowledgement_packet()
+	}
+}
@@ -0,0 +1,83 @@
+//! Benchmarking setup for the ICS-29 fee middleware
+
+use super::*;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use ibc::core::ics04_channel::{
+	commitment::PacketCommitment, context::ChannelKeeper, packet::Sequence,
+};
+use sp_std::vec;
+
+const CHANNEL_ID: u64 = 0;
+
+/// Enables fees on the transfer channel and funds the payer, returns the fee paid with `c` coins.
+fn setup_fee_enabled_channel<T: Config + Send + Sync>(
+	payer: &T::AccountId,
+	c: u32,
+) -> Fee<T::AssetId, T::Balance>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	let port_id = PortId::transfer();
+	FeeEnabledChannels::<T>::insert((port_id.as_bytes().to_vec(), CHANNEL_ID), true);
+	Context::<T>::default()
+		.store_next_sequence_send((port_id, ChannelId::new(CHANNEL_ID)), Sequence::from(1))
+		.unwrap();
+	let _ = <T::NativeCurrency as Currency<T::AccountId>>::make_free_balance_be(
+		payer,
+		u128::from(u64::MAX).into(),
+	);
+	let coins = (0..c).map(|_| (T::NativeAssetId::get(), 1_000_000u128.into())).collect();
+	Fee { recv_fee: coins, ack_fee: vec![], timeout_fee: vec![] }
+}
+
+benchmarks! {
+	where_clause {
+		where T: Send + Sync,
+			u32: From<<T as frame_system::Config>::BlockNumber>,
+			AccountId32: From<<T as frame_system::Config>::AccountId>,
+	}
+
+	pay_packet_fee {
+		let c in 1 .. 10;
+		let caller: T::AccountId = whitelisted_caller();
+		let fee = setup_fee_enabled_channel::<T>(&caller, c);
+	}: _(RawOrigin::Signed(caller), PortId::transfer().as_bytes().to_vec(), CHANNEL_ID, 1, fee)
+	verify {
+		assert_eq!(EscrowedFees::<T>::get((PortId::transfer().as_bytes().to_vec(), CHANNEL_ID), 1).len(), 1);
+	}
+
+	pay_packet_fee_async {
+		let c in 1 .. 10;
+		let caller: T::AccountId = whitelisted_caller();
+		let fee = setup_fee_enabled_channel::<T>(&caller, c);
+		Context::<T>::default()
+			.store_packet_commitment(
+				(PortId::transfer(), ChannelId::new(CHANNEL_ID), Sequence::from(1)),
+				PacketCommitment::from(vec![1; 32]),
+			)
+			.unwrap();
+	}: _(RawOrigin::Signed(caller), PortId::transfer().as_bytes().to_vec(), CHANNEL_ID, 1, fee)
+	verify {
+		assert_eq!(EscrowedFees::<T>::get((PortId::transfer().as_bytes().to_vec(), CHANNEL_ID), 1).len(), 1);
+	}
+
+	register_payee {
+		let caller: T::AccountId = whitelisted_caller();
+		let payee: T::AccountId = frame_benchmarking::account("payee", 0, 0);
+	}: _(RawOrigin::Signed(caller.clone()), CHANNEL_ID, payee.clone())
+	verify {
+		assert_eq!(Payees::<T>::get(CHANNEL_ID, caller), Some(payee));
+	}
+
+	register_counterparty_payee {
+		let caller: T::AccountId = whitelisted_caller();
+		let payee = b"cosmos1m9l358xunhhwds0568za49mzhvuxx9uxre5tud".to_vec();
+	}: _(RawOrigin::Signed(caller.clone()), CHANNEL_ID, payee.clone())
+	verify {
+		assert_eq!(CounterpartyPayees::<T>::get(CHANNEL_ID, caller), Some(payee));
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
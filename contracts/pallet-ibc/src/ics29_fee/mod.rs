@@ -0,0 +1,887 @@
+//! ICS-29 fee middleware. Fees are escrowed from the payer when they are attached to a packet,
+//! and paid out to the relayers once the packet is acknowledged or times out. Unlike
+//! [`crate::ics20_fee`], which charges a service fee for the chain, these fees incentivise the
+//! relayers of the packets.
+//!
+//! The middleware can wrap any application, fees are only handled on channels that negotiated the
+//! fee version (see [`ibc::applications::fee::metadata::Metadata`]), all other channels are passed
+//! through to the underlying application.
+//!
+//! Note that the acknowledgement written by the underlying application in `on_recv_packet` is
+//! replaced with an [`IncentivizedAcknowledgement`], applications that write their
+//! acknowledgements asynchronously are not supported.
+
+use crate::routing::Context;
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::fmt::Debug;
+use frame_support::traits::{
+	fungibles::Mutate, tokens::Preservation, Currency, ExistenceRequirement, Get,
+};
+use ibc::{
+	applications::fee::{
+		acknowledgement::{is_app_ack_successful, IncentivizedAcknowledgement},
+		metadata::Metadata,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			context::{ChannelKeeper, ChannelReader},
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module as IbcModule, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use sp_core::crypto::AccountId32;
+use sp_runtime::traits::{AccountIdConversion, IdentifyAccount, Zero};
+
+pub use pallet::*;
+pub use weight::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod weight;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{pallet_prelude::*, PalletId};
+	use frame_system::pallet_prelude::*;
+
+	/// Fees paid to the relayers of a packet, in local assets.
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct Fee<AssetId, Balance> {
+		/// Paid to the relayer that submitted the packet to the destination chain
+		pub recv_fee: Vec<(AssetId, Balance)>,
+		/// Paid to the relayer that submitted the acknowledgement back to this chain
+		pub ack_fee: Vec<(AssetId, Balance)>,
+		/// Paid to the relayer that submitted the timeout to this chain
+		pub timeout_fee: Vec<(AssetId, Balance)>,
+	}
+
+	impl<AssetId, Balance> Fee<AssetId, Balance> {
+		/// Number of coins escrowed for the fee
+		pub fn coins(&self) -> u32 {
+			(self.recv_fee.len() + self.ack_fee.len() + self.timeout_fee.len()) as u32
+		}
+	}
+
+	/// Fee escrowed for a packet, the fees that are not paid out are refunded to the payer.
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct PacketFee<AccountId, AssetId, Balance> {
+		pub fee: Fee<AssetId, Balance>,
+		pub refund_account: AccountId,
+	}
+
+	pub type PacketFeeOf<T> = PacketFee<
+		<T as frame_system::Config>::AccountId,
+		<T as crate::Config>::AssetId,
+		<T as crate::Config>::Balance,
+	>;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + crate::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Id of the account that holds the escrowed fees
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+		/// Weights of the extrinsics
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	/// Channels that negotiated the fee version, keyed by (port_id, channel_id)
+	pub type FeeEnabledChannels<T: Config> =
+		StorageMap<_, Blake2_128Concat, (Vec<u8>, u64), bool, ValueQuery>;
+
+	#[pallet::storage]
+	/// Account paid the ack and timeout fees earned by a relayer on a channel, when it is not the
+	/// relayer itself
+	pub type Payees<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		T::AccountId,
+		T::AccountId,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// Address on the counterparty chain paid the recv fees earned by a relayer on a channel
+	pub type CounterpartyPayees<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		T::AccountId,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// Fees escrowed for the packets sent on a channel, keyed by (port_id, channel_id) and
+	/// sequence
+	pub type EscrowedFees<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(Vec<u8>, u64),
+		Blake2_128Concat,
+		u64,
+		Vec<PacketFeeOf<T>>,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub (super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Fees were escrowed for a packet
+		FeeEscrowed {
+			port_id: Vec<u8>,
+			channel_id: u64,
+			sequence: u64,
+			payer: T::AccountId,
+		},
+		/// The escrowed fees of a packet were paid out to the relayers, or refunded
+		FeesDistributed {
+			port_id: Vec<u8>,
+			channel_id: u64,
+			sequence: u64,
+		},
+		/// The escrowed fees of the packets of a closed channel were refunded
+		FeesRefunded {
+			port_id: Vec<u8>,
+			channel_id: u64,
+		},
+		PayeeRegistered {
+			channel_id: u64,
+			relayer: T::AccountId,
+			payee: T::AccountId,
+		},
+		CounterpartyPayeeRegistered {
+			channel_id: u64,
+			relayer: T::AccountId,
+			counterparty_payee: Vec<u8>,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Fee middleware is not enabled on the channel
+		FeeNotEnabled,
+		/// Fee is empty or holds a zero amount
+		InvalidFee,
+		/// Port id is not valid utf8
+		InvalidPortId,
+		/// Channel or its next sequence was not found
+		ChannelNotFound,
+		/// Packet was already sent, or the sequence is not the next one sent on the channel
+		InvalidSequence,
+		/// Packet was already acknowledged or timed out
+		PacketNotFound,
+		/// Counterparty payee is empty
+		InvalidCounterpartyPayee,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T>
+	where
+		T: Send + Sync,
+		u32: From<<T as frame_system::Config>::BlockNumber>,
+	{
+		/// Escrows the fees of the packet that will be sent with `sequence`, which must be the next
+		/// sequence sent on the channel. It should be batched atomically (eg. with
+		/// `utility::batch_all`) before the call sending the packet, so that the fees can't end up
+		/// on a packet sent by someone else.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::pay_packet_fee(fee.coins()))]
+		#[frame_support::transactional]
+		pub fn pay_packet_fee(
+			origin: OriginFor<T>,
+			port_id: Vec<u8>,
+			channel_id: u64,
+			sequence: u64,
+			fee: Fee<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			let key = Self::channel_key(&port_id, channel_id)?;
+			let ctx = Context::<T>::default();
+			let next_sequence =
+				ctx.get_next_sequence_send(&key).map_err(|_| Error::<T>::ChannelNotFound)?;
+			ensure!(u64::from(next_sequence) == sequence, Error::<T>::InvalidSequence);
+			Self::escrow_packet_fee(port_id, channel_id, sequence, payer, fee)
+		}
+
+		/// Escrows additional fees for a packet that was already sent, but not acknowledged or
+		/// timed out yet.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::pay_packet_fee_async(fee.coins()))]
+		#[frame_support::transactional]
+		pub fn pay_packet_fee_async(
+			origin: OriginFor<T>,
+			port_id: Vec<u8>,
+			channel_id: u64,
+			sequence: u64,
+			fee: Fee<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			let (port, channel) = Self::channel_key(&port_id, channel_id)?;
+			let ctx = Context::<T>::default();
+			ctx.get_packet_commitment(&(port, channel, sequence.into()))
+				.map_err(|_| Error::<T>::PacketNotFound)?;
+			Self::escrow_packet_fee(port_id, channel_id, sequence, payer, fee)
+		}
+
+		/// Registers the account paid the ack and timeout fees earned by the sender on the
+		/// channel.
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_payee())]
+		pub fn register_payee(
+			origin: OriginFor<T>,
+			channel_id: u64,
+			payee: T::AccountId,
+		) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			Payees::<T>::insert(channel_id, &relayer, &payee);
+			Self::deposit_event(Event::<T>::PayeeRegistered { channel_id, relayer, payee });
+			Ok(())
+		}
+
+		/// Registers the address on the counterparty chain paid the recv fees earned by the
+		/// sender on the channel.
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_counterparty_payee())]
+		pub fn register_counterparty_payee(
+			origin: OriginFor<T>,
+			channel_id: u64,
+			counterparty_payee: Vec<u8>,
+		) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			let payee = String::from_utf8(counterparty_payee.clone())
+				.map_err(|_| Error::<T>::InvalidCounterpartyPayee)?;
+			ensure!(!payee.trim().is_empty(), Error::<T>::InvalidCounterpartyPayee);
+			CounterpartyPayees::<T>::insert(channel_id, &relayer, &counterparty_payee);
+			Self::deposit_event(Event::<T>::CounterpartyPayeeRegistered {
+				channel_id,
+				relayer,
+				counterparty_payee,
+			});
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Account that holds the escrowed fees
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		pub fn is_fee_enabled(port_id: &PortId, channel_id: &ChannelId) -> bool {
+			FeeEnabledChannels::<T>::get((port_id.as_bytes().to_vec(), channel_id.sequence()))
+		}
+
+		fn channel_key(port_id: &[u8], channel_id: u64) -> Result<(PortId, ChannelId), Error<T>> {
+			let port_id = core::str::from_utf8(port_id)
+				.ok()
+				.and_then(|port_id| port_id.parse::<PortId>().ok())
+				.ok_or(Error::<T>::InvalidPortId)?;
+			let channel_id = ChannelId::new(channel_id);
+			ensure!(Self::is_fee_enabled(&port_id, &channel_id), Error::<T>::FeeNotEnabled);
+			Ok((port_id, channel_id))
+		}
+
+		fn escrow_packet_fee(
+			port_id: Vec<u8>,
+			channel_id: u64,
+			sequence: u64,
+			payer: T::AccountId,
+			fee: Fee<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let mut total =
+				fee.recv_fee.iter().chain(&fee.ack_fee).chain(&fee.timeout_fee).peekable();
+			ensure!(total.peek().is_some(), Error::<T>::InvalidFee);
+			let escrow = Self::account_id();
+			for (asset_id, amount) in total {
+				ensure!(!amount.is_zero(), Error::<T>::InvalidFee);
+				Self::transfer(asset_id.clone(), &payer, &escrow, *amount)?;
+			}
+			EscrowedFees::<T>::append(
+				(port_id.clone(), channel_id),
+				sequence,
+				PacketFee { fee, refund_account: payer.clone() },
+			);
+			Self::deposit_event(Event::<T>::FeeEscrowed { port_id, channel_id, sequence, payer });
+			Ok(())
+		}
+
+		fn transfer(
+			asset_id: T::AssetId,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			if asset_id == T::NativeAssetId::get() {
+				<T::NativeCurrency as Currency<T::AccountId>>::transfer(
+					from,
+					to,
+					amount,
+					ExistenceRequirement::AllowDeath,
+				)
+			} else {
+				<T::Fungibles as Mutate<T::AccountId>>::transfer(
+					asset_id,
+					from,
+					to,
+					amount,
+					Preservation::Expendable,
+				)
+				.map(|_| ())
+			}
+		}
+
+		/// Pays out escrowed coins, they are refunded if the payment fails, eg. because the
+		/// recipient can't exist.
+		fn pay_out(
+			coins: &[(T::AssetId, T::Balance)],
+			recipient: Option<&T::AccountId>,
+			refund_account: &T::AccountId,
+		) {
+			let escrow = Self::account_id();
+			for (asset_id, amount) in coins {
+				let paid = recipient
+					.map(|recipient| Self::transfer(asset_id.clone(), &escrow, recipient, *amount))
+					.unwrap_or_else(|| Err(Error::<T>::InvalidFee.into()));
+				if paid.is_err() {
+					let _ = Self::transfer(asset_id.clone(), &escrow, refund_account, *amount)
+						.map_err(|e| {
+							log::error!(target: "pallet_ibc", "Failed to refund relayer fee: {e:?}");
+						});
+				}
+			}
+		}
+
+		fn payee(channel_id: u64, relayer: T::AccountId) -> T::AccountId {
+			Payees::<T>::get(channel_id, &relayer).unwrap_or(relayer)
+		}
+
+		/// Pays the recv fees to the forward relayer and the ack fees to the reverse relayer, the
+		/// timeout fees are refunded.
+		pub(crate) fn distribute_fees_on_ack(
+			packet: &Packet,
+			forward_relayer: Option<T::AccountId>,
+			reverse_relayer: Option<T::AccountId>,
+		) {
+			let channel_id = packet.source_channel.sequence();
+			let key = (packet.source_port.as_bytes().to_vec(), channel_id);
+			let sequence = u64::from(packet.sequence);
+			let reverse_payee = reverse_relayer.map(|relayer| Self::payee(channel_id, relayer));
+			for PacketFee { fee, refund_account } in EscrowedFees::<T>::take(&key, sequence) {
+				Self::pay_out(&fee.recv_fee, forward_relayer.as_ref(), &refund_account);
+				Self::pay_out(&fee.ack_fee, reverse_payee.as_ref(), &refund_account);
+				Self::pay_out(&fee.timeout_fee, Some(&refund_account), &refund_account);
+			}
+			Self::deposit_event(Event::<T>::FeesDistributed {
+				port_id: key.0,
+				channel_id,
+				sequence,
+			});
+		}
+
+		/// Pays the timeout fees to the timeout relayer, the recv and ack fees are refunded.
+		pub(crate) fn distribute_fees_on_timeout(
+			packet: &Packet,
+			timeout_relayer: Option<T::AccountId>,
+		) {
+			let channel_id = packet.source_channel.sequence();
+			let key = (packet.source_port.as_bytes().to_vec(), channel_id);
+			let sequence = u64::from(packet.sequence);
+			let timeout_payee = timeout_relayer.map(|relayer| Self::payee(channel_id, relayer));
+			for PacketFee { fee, refund_account } in EscrowedFees::<T>::take(&key, sequence) {
+				Self::pay_out(&fee.recv_fee, Some(&refund_account), &refund_account);
+				Self::pay_out(&fee.ack_fee, Some(&refund_account), &refund_account);
+				Self::pay_out(&fee.timeout_fee, timeout_payee.as_ref(), &refund_account);
+			}
+			Self::deposit_event(Event::<T>::FeesDistributed {
+				port_id: key.0,
+				channel_id,
+				sequence,
+			});
+		}
+
+		/// Refunds the escrowed fees of all the packets sent on the channel.
+		pub(crate) fn refund_channel_fees(port_id: &PortId, channel_id: &ChannelId) {
+			let key = (port_id.as_bytes().to_vec(), channel_id.sequence());
+			for (_, packet_fees) in EscrowedFees::<T>::drain_prefix(&key) {
+				for PacketFee { fee, refund_account } in packet_fees {
+					let total = fee
+						.recv_fee
+						.into_iter()
+						.chain(fee.ack_fee)
+						.chain(fee.timeout_fee)
+						.collect::<Vec<_>>();
+					Self::pay_out(&total, Some(&refund_account), &refund_account);
+				}
+			}
+			FeeEnabledChannels::<T>::remove(&key);
+			Self::deposit_event(Event::<T>::FeesRefunded { port_id: key.0, channel_id: key.1 });
+		}
+	}
+}
+
+/// Fee middleware, it should wrap the outermost layer of an application stack.
+/// USAGE:
+/// ```rust
+/// # #[cfg(any())] // This is just to make the doc test compile
+/// pub struct Router {
+///     ics20: crate::ics29_fee::Ics29Fee<
+///         Runtime,
+///         crate::ics20::memo::Memo<Runtime, crate::ics20::IbcModule<Runtime>>,
+///     >,
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ics29Fee<T: Config, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> {
+	inner: S,
+	_phantom: core::marker::PhantomData<T>,
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> Default
+	for Ics29Fee<T, S>
+{
+	fn default() -> Self {
+		Self { inner: S::default(), _phantom: Default::default() }
+	}
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug>
+	Ics29Fee<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn enable_fee(port_id: &PortId, channel_id: &ChannelId) {
+		FeeEnabledChannels::<T>::insert((port_id.as_bytes().to_vec(), channel_id.sequence()), true);
+	}
+
+	fn account(signer: &Signer) -> Option<T::AccountId> {
+		<T as crate::Config>::AccountIdConversion::try_from(signer.clone())
+			.ok()
+			.map(|account| account.into_account())
+	}
+
+	/// Replaces the acknowledgement written by the underlying application.
+	fn rewrite_acknowledgement(packet: &Packet, ack: Acknowledgement) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		// acknowledgements are stored under the source port and channel, see
+		// `ChannelKeeper::store_packet_result`
+		let key = (packet.source_port.clone(), packet.source_channel, packet.sequence);
+		if ctx.get_packet_acknowledgement(&key).is_err() {
+			log::warn!(target: "pallet_ibc", "Acknowledgement of packet {} was not written, it can't be incentivized", packet.sequence);
+			return Ok(())
+		}
+		ctx.delete_packet_acknowledgement(key.clone())?;
+		let ack_commitment = ctx.ack_commitment(ack.clone());
+		ctx.store_packet_acknowledgement(key.clone(), ack_commitment)?;
+		ctx.store_raw_acknowledgement(key, ack)
+	}
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> IbcModule
+	for Ics29Fee<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		// the version may not be fee metadata, in which case it belongs to the application
+		let Some(metadata) = Metadata::from_version(version) else {
+			return self.inner.on_chan_open_init(
+				ctx,
+				output,
+				order,
+				connection_hops,
+				port_id,
+				channel_id,
+				counterparty,
+				version,
+				relayer,
+			)
+		};
+		metadata.validate().map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		self.inner.on_chan_open_init(
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			&metadata.app_version(),
+			relayer,
+		)?;
+		Self::enable_fee(port_id, channel_id);
+		Ok(())
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		let Some(counterparty_metadata) = Metadata::from_version(counterparty_version) else {
+			return self.inner.on_chan_open_try(
+				ctx,
+				output,
+				order,
+				connection_hops,
+				port_id,
+				channel_id,
+				counterparty,
+				version,
+				counterparty_version,
+				relayer,
+			)
+		};
+		counterparty_metadata
+			.validate()
+			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		let app_version = Metadata::from_version(version)
+			.map(|metadata| metadata.app_version())
+			.unwrap_or_else(|| version.clone());
+		let app_version = self.inner.on_chan_open_try(
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			&app_version,
+			&counterparty_metadata.app_version(),
+			relayer,
+		)?;
+		Self::enable_fee(port_id, channel_id);
+		Ok(Metadata::new(&app_version).to_version())
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		if !Pallet::<T>::is_fee_enabled(port_id, channel_id) {
+			return self.inner.on_chan_open_ack(
+				ctx,
+				output,
+				port_id,
+				channel_id,
+				counterparty_version,
+				relayer,
+			)
+		}
+		let metadata = Metadata::from_version(counterparty_version).ok_or_else(|| {
+			Ics04Error::app_module(format!(
+				"counterparty version is not ICS29 metadata: {counterparty_version}"
+			))
+		})?;
+		metadata.validate().map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		self.inner.on_chan_open_ack(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			&metadata.app_version(),
+			relayer,
+		)
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_open_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_close_init(ctx, output, port_id, channel_id, relayer)?;
+		if Pallet::<T>::is_fee_enabled(port_id, channel_id) {
+			Pallet::<T>::refund_channel_fees(port_id, channel_id);
+		}
+		Ok(())
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)?;
+		if Pallet::<T>::is_fee_enabled(port_id, channel_id) {
+			Pallet::<T>::refund_channel_fees(port_id, channel_id);
+		}
+		Ok(())
+	}
+
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_order: Order,
+		proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		// the upgrade may add, keep or drop the fee metadata around the application version
+		let Some(metadata) = Metadata::from_version(proposed_version) else {
+			return self.inner.on_chan_upgrade_init(
+				ctx,
+				output,
+				port_id,
+				channel_id,
+				proposed_order,
+				proposed_connection_hops,
+				proposed_version,
+			)
+		};
+		metadata.validate().map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		let app_version = self.inner.on_chan_upgrade_init(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			proposed_order,
+			proposed_connection_hops,
+			&metadata.app_version(),
+		)?;
+		Ok(Metadata::new(&app_version).to_version())
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_order: Order,
+		proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		let Some(metadata) = Metadata::from_version(proposed_version) else {
+			return self.inner.on_chan_upgrade_try(
+				ctx,
+				output,
+				port_id,
+				channel_id,
+				proposed_order,
+				proposed_connection_hops,
+				proposed_version,
+			)
+		};
+		metadata.validate().map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		let app_version = self.inner.on_chan_upgrade_try(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			proposed_order,
+			proposed_connection_hops,
+			&metadata.app_version(),
+		)?;
+		Ok(Metadata::new(&app_version).to_version())
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Ics04Error> {
+		let Some(metadata) = Metadata::from_version(counterparty_version) else {
+			return self.inner.on_chan_upgrade_ack(
+				ctx,
+				output,
+				port_id,
+				channel_id,
+				counterparty_version,
+			)
+		};
+		metadata.validate().map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		self.inner
+			.on_chan_upgrade_ack(ctx, output, port_id, channel_id, &metadata.app_version())
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<(), Ics04Error> {
+		match Metadata::from_version(version) {
+			Some(metadata) => {
+				self.inner.on_chan_upgrade_open(
+					ctx,
+					output,
+					port_id,
+					channel_id,
+					order,
+					connection_hops,
+					&metadata.app_version(),
+				)?;
+				Self::enable_fee(port_id, channel_id);
+			},
+			None => {
+				self.inner.on_chan_upgrade_open(
+					ctx,
+					output,
+					port_id,
+					channel_id,
+					order,
+					connection_hops,
+					version,
+				)?;
+				// the upgrade removed fee incentivization from the channel
+				if Pallet::<T>::is_fee_enabled(port_id, channel_id) {
+					Pallet::<T>::refund_channel_fees(port_id, channel_id);
+				}
+			},
+		}
+		Ok(())
+	}
+
+	fn on_recv_packet(
+		&self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<Acknowledgement, Ics04Error> {
+		let ack = self.inner.on_recv_packet(ctx, output, packet, relayer)?;
+		// Acknowledgements written asynchronously by the application are not incentivized
+		if ack.as_ref().is_empty() ||
+			!Pallet::<T>::is_fee_enabled(&packet.destination_port, &packet.destination_channel)
+		{
+			return Ok(ack)
+		}
+		let forward_relayer_address = Self::account(relayer)
+			.and_then(|relayer| {
+				CounterpartyPayees::<T>::get(packet.destination_channel.sequence(), relayer)
+			})
+			.and_then(|payee| String::from_utf8(payee).ok())
+			.unwrap_or_default();
+		let ack: Acknowledgement = IncentivizedAcknowledgement {
+			underlying_app_success: is_app_ack_successful(&ack),
+			app_acknowledgement: ack.into_bytes(),
+			forward_relayer_address,
+		}
+		.into();
+		Self::rewrite_acknowledgement(packet, ack.clone())?;
+		Ok(ack)
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &Acknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		if !Pallet::<T>::is_fee_enabled(&packet.source_port, &packet.source_channel) {
+			return self.inner.on_acknowledgement_packet(
+				ctx,
+				output,
+				packet,
+				acknowledgement,
+				relayer,
+			)
+		}
+		let ack = IncentivizedAcknowledgement::try_from(acknowledgement)
+			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		self.inner.on_acknowledgement_packet(
+			ctx,
+			output,
+			packet,
+			&ack.app_acknowledgement(),
+			relayer,
+		)?;
+		let forward_relayer = ack
+			.forward_relayer_address
+			.parse::<Signer>()
+			.ok()
+			.and_then(|signer| Self::account(&signer));
+		Pallet::<T>::distribute_fees_on_ack(packet, forward_relayer, Self::account(relayer));
+		Ok(())
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_timeout_packet(ctx, output, packet, relayer)?;
+		if Pallet::<T>::is_fee_enabled(&packet.source_port, &packet.source_channel) {
+			Pallet::<T>::distribute_fees_on_timeout(packet, Self::account(relayer));
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,47 @@
+use frame_support::{
+	pallet_prelude::Weight,
+	traits::Get,
+	weights::constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_MICROS},
+};
+
+pub trait WeightInfo {
+	/// `c` is the number of coins in the fee
+	fn pay_packet_fee(c: u32) -> Weight;
+	/// `c` is the number of coins in the fee
+	fn pay_packet_fee_async(c: u32) -> Weight;
+	fn register_payee() -> Weight;
+	fn register_counterparty_payee() -> Weight;
+}
+
+/// Estimates from the storage accessed by each call, for runtimes that don't run the benchmarks.
+impl WeightInfo for () {
+	fn pay_packet_fee(c: u32) -> Weight {
+		Weight::from_parts(30 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().reads_writes(3, 2))
+			.saturating_add(
+				Weight::from_parts(40 * WEIGHT_REF_TIME_PER_MICROS, 0)
+					.saturating_add(RocksDbWeight::get().reads_writes(3, 3))
+					.saturating_mul(c.into()),
+			)
+	}
+
+	fn pay_packet_fee_async(c: u32) -> Weight {
+		Weight::from_parts(30 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().reads_writes(3, 2))
+			.saturating_add(
+				Weight::from_parts(40 * WEIGHT_REF_TIME_PER_MICROS, 0)
+					.saturating_add(RocksDbWeight::get().reads_writes(3, 3))
+					.saturating_mul(c.into()),
+			)
+	}
+
+	fn register_payee() -> Weight {
+		Weight::from_parts(15 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn register_counterparty_payee() -> Weight {
+		Weight::from_parts(15 * WEIGHT_REF_TIME_PER_MICROS, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
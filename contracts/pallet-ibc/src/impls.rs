@@ -2,6 +2,7 @@ use alloc::{format, str::FromStr, string::String};
 use core::time::Duration;
 
 use crate::{
+	ics20_rate_limit::Ics20RateLimiter,
 	ics23::{
 		acknowledgements::Acknowledgements, channels::Channels, client_states::ClientStates,
 		connections::Connections, consensus_states::ConsensusStates,
@@ -434,7 +435,8 @@ where
 		let receipt = String::from_utf8(receipt).map_err(|_| Error::<T>::DecodingError)?;
 		let receipt_path = format!("{}", ReceiptsPath { port_id, channel_id, sequence });
 		let key = apply_prefix(T::PalletPrefix::get(), vec![receipt_path]);
-		let receipt = &receipt == "Ok";
+		// timeout receipts also mark the packet as processed by this chain
+		let receipt = matches!(receipt.as_str(), "Ok" | "Timeout");
 		Ok(QueryPacketReceiptResponse { receipt, trie_key: key, height: host_height::<T>() })
 	}
 
@@ -1038,12 +1040,20 @@ where
 			timeout_height,
 			timeout_timestamp,
 			memo,
+			additional_tokens: vec![],
+			forwarding: None,
 		};
 		Ok(msg)
 	}
 
 	pub(crate) fn send_transfer(msg: MsgTransfer<PrefixedCoin>) -> Result<(), IbcHandlerError> {
 		let mut ctx = Context::<T>::default();
+		let sequence =
+			ctx.get_next_sequence_send(&(msg.source_port.clone(), msg.source_channel))
+				.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
+		for coin in core::iter::once(&msg.token).chain(&msg.additional_tokens) {
+			T::Ics20RateLimiter::on_send(msg.source_channel, sequence, coin)?;
+		}
 		let mut handler_output = HandlerOutputBuilder::default();
 		send_transfer::<_, _>(&mut ctx, &mut handler_output, msg)
 			.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
@@ -128,6 +128,9 @@ mod mock;
 mod tests;
 
 pub mod ics20_fee;
+pub mod ics20_rate_limit;
+pub mod ics27;
+pub mod ics29_fee;
 mod impls;
 pub mod weight;
 
@@ -162,7 +165,7 @@ pub mod pallet {
 		next_seq_send::NextSequenceSend,
 	};
 	use crate::{
-		ics20::{HandleMemo, SubstrateMultihopXcmHandler},
+		ics20::{HandleMemo, InFlightForwardOf, SubstrateMultihopXcmHandler},
 		light_clients::AnyConsensusState,
 		routing::{Context, ModuleRouter},
 	};
@@ -264,6 +267,8 @@ pub mod pallet {
 		type RelayerOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
 		/// Handle Ics20 Memo
 		type HandleMemo: HandleMemo<Self> + Default;
+		/// Rate limits the tokens sent over Ics20 channels
+		type Ics20RateLimiter: ics20_rate_limit::Ics20RateLimiter;
 		/// Memo Message types supported by the runtime
 		type MemoMessage: codec::Codec
 			+ FromStr
@@ -362,6 +367,21 @@ pub mod pallet {
 	/// number
 	pub type SequenceFee<T: Config> = StorageMap<_, Blake2_128Concat, u64, u128, ValueQuery>;
 
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (source_channel.sequence(), packet sequence) => transfer forwarded by the memo handler that
+	/// hasn't been acknowledged yet
+	pub type ForwardedPackets<T: Config> =
+		StorageMap<_, Blake2_128Concat, (u64, u64), InFlightForwardOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (port_id, channel_id, sequence) of a packet forwarding the tokens of an `ics20-2` packet to
+	/// the next hop => protobuf encoded received packet, acknowledged once the forwarding packet
+	/// completes
+	pub type ForwardedTransferPackets<T: Config> =
+		StorageMap<_, Blake2_128Concat, (Vec<u8>, u64, u64), Vec<u8>, OptionQuery>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
 	/// counter for clients
@@ -413,8 +433,8 @@ pub mod pallet {
 
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
-	/// Consensus heights
-	/// Stored as a tuple of (revision_number, revision_height)
+	/// Consensus heights of every client, oldest first. Used to find neighbouring consensus states
+	/// and to prune the expired ones
 	pub type ConsensusHeights<T: Config> = StorageMap<
 		_,
 		Blake2_128Concat,
@@ -452,6 +472,13 @@ pub mod pallet {
 	pub type PendingRecvPacketSeqs<T: Config> =
 		StorageMap<_, Blake2_128Concat, (Vec<u8>, Vec<u8>), (BTreeSet<u64>, u64), ValueQuery>;
 
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (port_id, channel_id) => protobuf encoded counterparty `Upgrade`, recorded during an
+	/// in-flight channel upgrade. Not part of the commitment trie since it is never proven.
+	pub type CounterpartyChannelUpgrades<T: Config> =
+		StorageMap<_, Blake2_128Concat, (Vec<u8>, Vec<u8>), Vec<u8>, OptionQuery>;
+
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	pub struct AssetConfig<AssetId> {
 		pub id: AssetId,
@@ -604,6 +631,12 @@ pub mod pallet {
 			client_id: String,
 			height: Height,
 		},
+		/// A frozen or expired client was recovered with the state of a substitute client
+		ClientRecovered {
+			subject_client_id: String,
+			substitute_client_id: String,
+			height: Height,
+		},
 		ExecuteMemoStarted {
 			account_id: T::AccountId,
 			memo: Option<String>,
@@ -629,6 +662,24 @@ pub mod pallet {
 			channel: u64,
 			next_memo: Option<T::MemoMessage>,
 		},
+		/// A forwarded transfer timed out and has been sent again
+		ExecuteMemoIbcTokenTransferRetried {
+			from: T::AccountId,
+			to: Vec<u8>,
+			asset_id: T::AssetId,
+			amount: T::Balance,
+			channel: u64,
+			retries: u64,
+		},
+		/// A forwarded transfer failed and its tokens have been taken back from the receiver, the
+		/// error acknowledgement refunds the original sender
+		ExecuteMemoIbcTokenTransferRefunded {
+			from: T::AccountId,
+			to: Vec<u8>,
+			asset_id: T::AssetId,
+			amount: T::Balance,
+			channel: u64,
+		},
 		ExecuteMemoXcmSuccess {
 			from: T::AccountId,
 			to: T::AccountId,
@@ -716,6 +767,7 @@ pub mod pallet {
 		ClientFreezeFailed,
 		/// Access denied
 		AccessDenied,
+		/// The transfer exceeds the rate limit of the channel
 		RateLimiter,
 		/// Fee errors
 		FailedSendFeeToAccount,
@@ -726,6 +778,8 @@ pub mod pallet {
 		/// - The memo is in invalid format
 		/// - The memo contains unsupported middlewares
 		InvalidMemo,
+		/// The subject client could not be recovered with the substitute client
+		ClientRecoveryFailed,
 	}
 
 	#[pallet::hooks]
@@ -985,6 +1039,8 @@ pub mod pallet {
 				timeout_height,
 				timeout_timestamp,
 				memo: memo.map(|memo| memo.to_string()).unwrap_or_default(),
+				additional_tokens: vec![],
+				forwarding: None,
 			};
 
 			let is_sender_source = is_sender_chain_source(
@@ -1035,6 +1091,8 @@ pub mod pallet {
 					DecodingError { .. } => Error::<T>::TransferSerde,
 					ErrorDecodingPrefix => Error::<T>::TransferSerde,
 
+					RateLimitExceeded { .. } => Error::<T>::RateLimiter,
+
 					Other { .. } => Error::<T>::TransferOther,
 				}
 			})?;
@@ -1112,8 +1170,18 @@ pub mod pallet {
 							.map_err(|_| Error::<T>::ClientFreezeFailed)?,
 					)
 				},
-				AnyClientState::Wasm(_) => return Err(Error::<T>::ClientFreezeFailed.into()),
-				#[cfg(test)]
+				AnyClientState::SoloMachine(solo_machine) =>
+					AnyClientState::wrap(&solo_machine.with_frozen()),
+				AnyClientState::Near(near) => {
+					let latest_height = near.latest_height();
+					AnyClientState::wrap(
+						&near
+							.with_frozen_height(Height::new(latest_height.revision_number, height)),
+					)
+				},
+				AnyClientState::Localhost(_) | AnyClientState::Wasm(_) =>
+					return Err(Error::<T>::ClientFreezeFailed.into()),
+				#[cfg(any(test, feature = "mocks"))]
 				AnyClientState::Mock(mut ms) => {
 					ms.frozen_height =
 						Some(Height::new(ms.latest_height().revision_number, height));
@@ -1230,7 +1298,9 @@ pub mod pallet {
 		}
 
 		#[pallet::call_index(9)]
-		#[pallet::weight(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::prune_consensus_states(
+			crate::client::MAX_PRUNED_CONSENSUS_STATES,
+		))]
 		#[frame_support::transactional]
 		pub fn substitute_client_state(
 			origin: OriginFor<T>,
@@ -1264,6 +1334,55 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Recover an expired or frozen client by copying over the latest consensus state of an
+		/// active substitute client. The light client checks that both clients match on every
+		/// field that must stay the same.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::prune_consensus_states(
+			crate::client::MAX_PRUNED_CONSENSUS_STATES,
+		))]
+		#[frame_support::transactional]
+		pub fn recover_client(
+			origin: OriginFor<T>,
+			subject_client_id: String,
+			substitute_client_id: String,
+		) -> DispatchResult {
+			use ibc::core::ics02_client::{
+				client_state::ClientState, context::ClientKeeper, handler::recover_client,
+			};
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let subject_client_id =
+				ClientId::from_str(&subject_client_id).map_err(|_| Error::<T>::Other)?;
+			let substitute_client_id =
+				ClientId::from_str(&substitute_client_id).map_err(|_| Error::<T>::Other)?;
+
+			let mut ctx = routing::Context::<T>::default();
+			let output = recover_client::process(
+				&ctx,
+				subject_client_id.clone(),
+				substitute_client_id.clone(),
+			)
+			.map_err(|e| {
+				log::debug!(target: "pallet_ibc", "[recover_client]: error: {:?}", &e);
+				Error::<T>::ClientRecoveryFailed
+			})?;
+			let height = match &output.result {
+				ibc::core::ics02_client::handler::ClientResult::Update(res) =>
+					res.client_state.latest_height(),
+				_ => return Err(Error::<T>::ClientRecoveryFailed.into()),
+			};
+			ctx.store_client_result(output.result)
+				.map_err(|_| Error::<T>::ClientRecoveryFailed)?;
+
+			Self::deposit_event(Event::<T>::ClientRecovered {
+				subject_client_id: subject_client_id.to_string(),
+				substitute_client_id: substitute_client_id.to_string(),
+				height,
+			});
+
+			Ok(())
+		}
 	}
 }
 
@@ -1,6 +1,7 @@
 use alloc::{borrow::ToOwned, boxed::Box, format, string::ToString, vec::Vec};
 use frame_support::{
 	pallet_prelude::{StorageValue, ValueQuery},
+	storage::{child, child::ChildInfo},
 	traits::StorageInstance,
 };
 use ibc::core::{
@@ -12,6 +13,11 @@ use ibc::core::{
 use ibc_derive::{ClientDef, ClientMessage, ClientState, ConsensusState, Protobuf};
 use ibc_primitives::runtime_interface;
 use ibc_proto::google::protobuf::Any;
+use ics06_solomachine::{
+	client_message::{SOLOMACHINE_HEADER_TYPE_URL, SOLOMACHINE_MISBEHAVIOUR_TYPE_URL},
+	client_state::SOLOMACHINE_CLIENT_STATE_TYPE_URL,
+	consensus_state::SOLOMACHINE_CONSENSUS_STATE_TYPE_URL,
+};
 use ics07_tendermint::{
 	client_message::{
 		TENDERMINT_CLIENT_MESSAGE_TYPE_URL, TENDERMINT_HEADER_TYPE_URL,
@@ -24,6 +30,10 @@ use ics08_wasm::{
 	client_message::WASM_CLIENT_MESSAGE_TYPE_URL, client_state::WASM_CLIENT_STATE_TYPE_URL,
 	consensus_state::WASM_CONSENSUS_STATE_TYPE_URL, Bytes,
 };
+use ics09_localhost::{
+	client_message::LOCALHOST_HEADER_TYPE_URL, client_state::LOCALHOST_CLIENT_STATE_TYPE_URL,
+	consensus_state::LOCALHOST_CONSENSUS_STATE_TYPE_URL,
+};
 use ics10_grandpa::{
 	client_message::{
 		RelayChainHeader, GRANDPA_CLIENT_MESSAGE_TYPE_URL, GRANDPA_HEADER_TYPE_URL,
@@ -36,6 +46,10 @@ use ics11_beefy::{
 	client_message::BEEFY_CLIENT_MESSAGE_TYPE_URL, client_state::BEEFY_CLIENT_STATE_TYPE_URL,
 	consensus_state::BEEFY_CONSENSUS_STATE_TYPE_URL,
 };
+use ics13_near::{
+	client_state::NEAR_CLIENT_STATE_TYPE_URL, consensus_state::NEAR_CONSENSUS_STATE_TYPE_URL,
+	header::NEAR_HEADER_TYPE_URL,
+};
 use prost::Message;
 use sp_core::{crypto::ByteArray, ed25519, H256};
 use sp_runtime::{
@@ -43,6 +57,7 @@ use sp_runtime::{
 	traits::{BlakeTwo256, ConstU32, Header},
 	BoundedBTreeSet, BoundedVec,
 };
+use sp_trie::LayoutV0;
 use tendermint::{
 	crypto::{
 		signature::{Error as TendermintCryptoError, Verifier},
@@ -116,6 +131,134 @@ impl Verifier for HostFunctionsManager {
 
 impl ics07_tendermint::HostFunctionsProvider for HostFunctionsManager {}
 
+impl ics06_solomachine::HostFunctionsProvider for HostFunctionsManager {
+	fn sha256_digest(data: &[u8]) -> [u8; 32] {
+		sp_io::hashing::sha2_256(data)
+	}
+
+	fn ed25519_verify(signature: &[u8], msg: &[u8], public_key: &[u8]) -> bool {
+		match (ed25519::Signature::from_slice(signature), ed25519::Public::from_slice(public_key)) {
+			(Some(signature), Ok(public_key)) =>
+				sp_io::crypto::ed25519_verify(&signature, msg, &public_key),
+			_ => false,
+		}
+	}
+
+	fn secp256k1_verify(signature: &[u8], msg: &[u8; 32], public_key: &[u8]) -> bool {
+		if signature.len() != 64 {
+			return false
+		}
+		// the signature carries no recovery id, so try both candidates
+		(0..2).any(|recovery_id| {
+			let mut raw = [0u8; 65];
+			raw[..64].copy_from_slice(signature);
+			raw[64] = recovery_id;
+			sp_io::crypto::secp256k1_ecdsa_recover_compressed(&raw, msg)
+				.map_or(false, |recovered| recovered[..] == *public_key)
+		})
+	}
+}
+
+impl ics09_localhost::HostFunctionsProvider for HostFunctionsManager {
+	fn get_storage(prefix: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+		child::get_raw(&ChildInfo::new_default(prefix), key)
+	}
+}
+
+impl ics13_near::client_def::HostFunctions for HostFunctionsManager {
+	fn keccak_256(input: &[u8]) -> [u8; 32] {
+		sp_io::hashing::keccak_256(input)
+	}
+
+	fn secp256k1_ecdsa_recover_compressed(signature: &[u8; 65], msg: &[u8; 32]) -> Option<Vec<u8>> {
+		sp_io::crypto::secp256k1_ecdsa_recover_compressed(signature, msg)
+			.ok()
+			.map(|pub_key| pub_key.to_vec())
+	}
+
+	fn ed25519_verify(signature: &[u8; 64], msg: &[u8], pubkey: &[u8]) -> bool {
+		match ed25519::Public::from_slice(pubkey) {
+			Ok(pubkey) => sp_io::crypto::ed25519_verify(
+				&ed25519::Signature::from_raw(*signature),
+				msg,
+				&pubkey,
+			),
+			Err(_) => false,
+		}
+	}
+
+	fn verify_membership_trie_proof(
+		root: &[u8; 32],
+		proof: &[Vec<u8>],
+		key: &[u8],
+		value: &[u8],
+	) -> Result<(), ics02_client::error::Error> {
+		sp_trie::verify_trie_proof::<LayoutV0<BlakeTwo256>, _, _, _>(
+			&H256::from(*root),
+			proof,
+			&[(key, Some(value))],
+		)
+		.map_err(|e| {
+			ics02_client::error::Error::implementation_specific(format!(
+				"invalid membership proof: {e:?}"
+			))
+		})
+	}
+
+	fn verify_non_membership_trie_proof(
+		root: &[u8; 32],
+		proof: &[Vec<u8>],
+		key: &[u8],
+	) -> Result<(), ics02_client::error::Error> {
+		sp_trie::verify_trie_proof::<LayoutV0<BlakeTwo256>, _, &[u8], &[u8]>(
+			&H256::from(*root),
+			proof,
+			&[(key, None)],
+		)
+		.map_err(|e| {
+			ics02_client::error::Error::implementation_specific(format!(
+				"invalid non-membership proof: {e:?}"
+			))
+		})
+	}
+
+	fn verify_timestamp_extrinsic(
+		root: &[u8; 32],
+		proof: &[Vec<u8>],
+		value: &[u8],
+	) -> Result<(), ics02_client::error::Error> {
+		// the timestamp extrinsic is the first extrinsic of the block
+		let key = codec::Encode::encode(&codec::Compact(0u32));
+		Self::verify_membership_trie_proof(root, proof, &key, value)
+	}
+
+	fn sha256_digest(data: &[u8]) -> [u8; 32] {
+		sp_io::hashing::sha2_256(data)
+	}
+
+	fn sha2_256(message: &[u8]) -> [u8; 32] {
+		sp_io::hashing::sha2_256(message)
+	}
+
+	fn sha2_512(message: &[u8]) -> [u8; 64] {
+		runtime_interface::sha2_512(message)
+	}
+
+	fn sha2_512_truncated(message: &[u8]) -> [u8; 32] {
+		runtime_interface::sha2_512_truncated(message)
+	}
+
+	fn sha3_512(message: &[u8]) -> [u8; 64] {
+		runtime_interface::sha3_512(message)
+	}
+
+	fn ripemd160(message: &[u8]) -> [u8; 20] {
+		runtime_interface::ripemd160(message)
+	}
+}
+
+impl ics13_near::client_def::HostFunctionsTrait for HostFunctionsManager {}
+
 pub struct GrandpaHeaderHashesStorageInstance;
 impl StorageInstance for GrandpaHeaderHashesStorageInstance {
 	fn pallet_prefix() -> &'static str {
@@ -209,8 +352,11 @@ pub enum AnyClient {
 	Grandpa(ics10_grandpa::client_def::GrandpaClient<HostFunctionsManager>),
 	Beefy(ics11_beefy::client_def::BeefyClient<HostFunctionsManager>),
 	Tendermint(ics07_tendermint::client_def::TendermintClient<HostFunctionsManager>),
+	SoloMachine(ics06_solomachine::client_def::SoloMachineClient<HostFunctionsManager>),
+	Localhost(ics09_localhost::client_def::LocalhostClient<HostFunctionsManager>),
+	Near(ics13_near::client_def::NearClient<HostFunctionsManager>),
 	Wasm(ics08_wasm::client_def::WasmClient<AnyClient, AnyClientState, AnyConsensusState>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	Mock(ibc::mock::client_def::MockClient),
 }
 
@@ -219,8 +365,11 @@ pub enum AnyUpgradeOptions {
 	Grandpa(ics10_grandpa::client_state::UpgradeOptions),
 	Beefy(ics11_beefy::client_state::UpgradeOptions),
 	Tendermint(ics07_tendermint::client_state::UpgradeOptions),
+	SoloMachine(()),
+	Localhost(()),
+	Near(ics13_near::client_state::NearUpgradeOptions),
 	Wasm(Box<Self>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	Mock(()),
 }
 
@@ -232,9 +381,15 @@ pub enum AnyClientState {
 	Beefy(ics11_beefy::client_state::ClientState<HostFunctionsManager>),
 	#[ibc(proto_url = "TENDERMINT_CLIENT_STATE_TYPE_URL")]
 	Tendermint(ics07_tendermint::client_state::ClientState<HostFunctionsManager>),
+	#[ibc(proto_url = "SOLOMACHINE_CLIENT_STATE_TYPE_URL")]
+	SoloMachine(ics06_solomachine::client_state::ClientState<HostFunctionsManager>),
+	#[ibc(proto_url = "LOCALHOST_CLIENT_STATE_TYPE_URL")]
+	Localhost(ics09_localhost::client_state::ClientState<HostFunctionsManager>),
+	#[ibc(proto_url = "NEAR_CLIENT_STATE_TYPE_URL")]
+	Near(ics13_near::client_state::NearClientState<HostFunctionsManager>),
 	#[ibc(proto_url = "WASM_CLIENT_STATE_TYPE_URL")]
 	Wasm(ics08_wasm::client_state::ClientState<AnyClient, Self, AnyConsensusState>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	#[ibc(proto_url = "MOCK_CLIENT_STATE_TYPE_URL")]
 	Mock(ibc::mock::client_state::MockClientState),
 }
@@ -268,6 +423,17 @@ impl AnyClientState {
 			c => c,
 		}
 	}
+
+	/// Returns the trusting period of the client, or `None` if the client doesn't expire.
+	pub fn trusting_period(&self) -> Option<core::time::Duration> {
+		match self.unpack_recursive() {
+			AnyClientState::Grandpa(client_state) =>
+				client_state.relay_chain.trusting_period().ok(),
+			AnyClientState::Beefy(client_state) => client_state.relay_chain.trusting_period().ok(),
+			AnyClientState::Tendermint(client_state) => Some(client_state.trusting_period),
+			_ => None,
+		}
+	}
 }
 
 impl AnyClientState {
@@ -292,9 +458,15 @@ pub enum AnyConsensusState {
 	Beefy(ics11_beefy::consensus_state::ConsensusState),
 	#[ibc(proto_url = "TENDERMINT_CONSENSUS_STATE_TYPE_URL")]
 	Tendermint(ics07_tendermint::consensus_state::ConsensusState),
+	#[ibc(proto_url = "SOLOMACHINE_CONSENSUS_STATE_TYPE_URL")]
+	SoloMachine(ics06_solomachine::consensus_state::ConsensusState),
+	#[ibc(proto_url = "LOCALHOST_CONSENSUS_STATE_TYPE_URL")]
+	Localhost(ics09_localhost::consensus_state::ConsensusState),
+	#[ibc(proto_url = "NEAR_CONSENSUS_STATE_TYPE_URL")]
+	Near(ics13_near::consensus_state::ConsensusState),
 	#[ibc(proto_url = "WASM_CONSENSUS_STATE_TYPE_URL")]
 	Wasm(ics08_wasm::consensus_state::ConsensusState<Self>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	#[ibc(proto_url = "MOCK_CONSENSUS_STATE_TYPE_URL")]
 	Mock(ibc::mock::client_state::MockConsensusState),
 }
@@ -317,9 +489,15 @@ pub enum AnyClientMessage {
 	Beefy(ics11_beefy::client_message::ClientMessage),
 	#[ibc(proto_url = "TENDERMINT_CLIENT_MESSAGE_TYPE_URL")]
 	Tendermint(ics07_tendermint::client_message::ClientMessage),
+	#[ibc(proto_url = "SOLOMACHINE_HEADER_TYPE_URL")]
+	SoloMachine(ics06_solomachine::client_message::ClientMessage),
+	#[ibc(proto_url = "LOCALHOST_HEADER_TYPE_URL")]
+	Localhost(ics09_localhost::client_message::ClientMessage),
+	#[ibc(proto_url = "NEAR_HEADER_TYPE_URL")]
+	Near(ics13_near::header::NearClientMessage),
 	#[ibc(proto_url = "WASM_CLIENT_MESSAGE_TYPE_URL")]
 	Wasm(ics08_wasm::client_message::ClientMessage<Self>),
-	#[cfg(test)]
+	#[cfg(any(test, feature = "mocks"))]
 	#[ibc(proto_url = "MOCK_CLIENT_MESSAGE_TYPE_URL")]
 	Mock(ibc::mock::header::MockClientMessage),
 }
@@ -380,10 +558,36 @@ impl TryFrom<Any> for AnyClientMessage {
 					ics07_tendermint::client_message::Misbehaviour::decode_vec(&value.value)
 						.map_err(ics02_client::error::Error::decode_raw_header)?,
 				))),
+			SOLOMACHINE_HEADER_TYPE_URL =>
+				Ok(Self::SoloMachine(ics06_solomachine::client_message::ClientMessage::Header(
+					ics06_solomachine::client_message::Header::decode_vec(&value.value)
+						.map_err(ics02_client::error::Error::decode_raw_header)?,
+				))),
+			SOLOMACHINE_MISBEHAVIOUR_TYPE_URL => Ok(Self::SoloMachine(
+				ics06_solomachine::client_message::ClientMessage::Misbehaviour(
+					ics06_solomachine::client_message::Misbehaviour::decode_vec(&value.value)
+						.map_err(ics02_client::error::Error::decode_raw_header)?,
+				),
+			)),
+			LOCALHOST_HEADER_TYPE_URL =>
+				Ok(Self::Localhost(ics09_localhost::client_message::ClientMessage::Header(
+					ics09_localhost::client_message::Header::decode_vec(&value.value)
+						.map_err(ics02_client::error::Error::decode_raw_header)?,
+				))),
+			NEAR_HEADER_TYPE_URL => Ok(Self::Near(ics13_near::header::NearClientMessage::Header(
+				ics13_near::header::NearHeader::decode_vec(&value.value)
+					.map_err(ics02_client::error::Error::decode_raw_header)?,
+			))),
 			WASM_CLIENT_MESSAGE_TYPE_URL => Ok(Self::Wasm(
 				ics08_wasm::client_message::ClientMessage::decode_vec(&value.value)
 					.map_err(ics02_client::error::Error::decode_raw_header)?,
 			)),
+			#[cfg(any(test, feature = "mocks"))]
+			ibc::mock::header::MOCK_HEADER_TYPE_URL |
+			ibc::mock::misbehaviour::MOCK_MISBEHAVIOUR_TYPE_URL => {
+				let ibc::mock::header::AnyClientMessage::Mock(msg) = value.try_into()?;
+				Ok(Self::Mock(msg))
+			},
 			_ => Err(ics02_client::error::Error::unknown_consensus_state_type(value.type_url)),
 		}
 	}
@@ -414,17 +618,38 @@ impl From<AnyClientMessage> for Any {
 				type_url: TENDERMINT_CLIENT_MESSAGE_TYPE_URL.to_string(),
 				value: msg.encode_vec().expect("encode_vec failed"),
 			},
-
-			#[cfg(test)]
-			AnyClientMessage::Mock(_msg) => panic!("MockHeader can't be serialized"),
+			AnyClientMessage::SoloMachine(msg) => match msg {
+				ics06_solomachine::client_message::ClientMessage::Header(h) => Any {
+					type_url: SOLOMACHINE_HEADER_TYPE_URL.to_string(),
+					value: h.encode_vec().expect("encode_vec failed"),
+				},
+				ics06_solomachine::client_message::ClientMessage::Misbehaviour(m) => Any {
+					type_url: SOLOMACHINE_MISBEHAVIOUR_TYPE_URL.to_string(),
+					value: m.encode_vec().expect("encode_vec failed"),
+				},
+			},
+			AnyClientMessage::Localhost(msg) => match msg {
+				ics09_localhost::client_message::ClientMessage::Header(h) => Any {
+					type_url: LOCALHOST_HEADER_TYPE_URL.to_string(),
+					value: h.encode_vec().expect("encode_vec failed"),
+				},
+			},
+			AnyClientMessage::Near(msg) => match msg {
+				ics13_near::header::NearClientMessage::Header(h) => Any {
+					type_url: NEAR_HEADER_TYPE_URL.to_string(),
+					value: h.encode_vec().expect("encode_vec failed"),
+				},
+			},
+			#[cfg(any(test, feature = "mocks"))]
+			AnyClientMessage::Mock(msg) => ibc::mock::header::AnyClientMessage::Mock(msg).into(),
 		}
 	}
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "mocks"))]
 pub use mocks::*;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "mocks"))]
 mod mocks {
 	pub const MOCK_CLIENT_STATE_TYPE_URL: &str = "/ibc.mock.ClientState";
 	pub const MOCK_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.mock.ClientMessage";
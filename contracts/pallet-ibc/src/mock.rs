@@ -14,9 +14,11 @@ use frame_support::{
 		},
 		AsEnsureOriginWithArg, ConstU64, Everything,
 	},
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight},
 };
 use frame_system as system;
 use frame_system::EnsureSigned;
+use ibc::applications::interchain_accounts;
 use ibc_primitives::{runtime_interface::ss58_to_account_id_32, IbcAccount};
 use light_client_common::RelayChain;
 use orml_traits::parameter_type_with_key;
@@ -182,7 +184,7 @@ impl pallet_assets::Config for Test {
 parameter_types! {
 	pub const MaxLocks: u32 = 256;
 	pub static ParachainId: ParaId = ParaId::from(2087);
-	pub static RelayChainId: RelayChain = RelayChain::Rococo;
+	pub static RelayChainId: RelayChain = RelayChain::ROCOCO;
 	pub const SpamProtectionDeposit: u128 = 0;
 }
 
@@ -212,6 +214,7 @@ parameter_types! {
 	pub const IbcTriePrefix : &'static [u8] = b"ibc/";
 	pub const ServiceCharge: Perbill = Perbill::from_percent(1);
 	pub const PalletId: frame_support::PalletId = frame_support::PalletId(*b"ics20fee");
+	pub const RelayerFeePalletId: frame_support::PalletId = frame_support::PalletId(*b"ics29fee");
 	pub const FlatFeeAssetId: AssetId = 130;
 	pub const FlatFeeAmount: AssetId = 10_000_000;
 	pub FeeAccount: <Test as Config>::AccountIdConversion = create_alice_key();
@@ -286,6 +289,7 @@ impl Config for Test {
 	type TransferOrigin = EnsureSigned<Self::IbcAccountId>;
 	type RelayerOrigin = EnsureSigned<Self::AccountId>;
 	type HandleMemo = IbcMemoHandler<(), Test>;
+	type Ics20RateLimiter = Ics20RateLimit;
 	type MemoMessage = RawMemo;
 	type IsReceiveEnabled = sp_core::ConstBool<true>;
 	type IsSendEnabled = sp_core::ConstBool<true>;
@@ -320,6 +324,37 @@ impl crate::ics20_fee::Config for Test {
 	type PalletId = PalletId;
 }
 
+impl crate::ics20_rate_limit::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+}
+
+impl crate::ics29_fee::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type PalletId = RelayerFeePalletId;
+	type WeightInfo = ();
+}
+
+/// Interchain accounts hosted by the mock runtime may only post remarks
+pub struct IcaCallFilter;
+impl frame_support::traits::Contains<RuntimeCall> for IcaCallFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(call, RuntimeCall::System(frame_system::Call::remark_with_event { .. }))
+	}
+}
+
+parameter_types! {
+	pub static IcaMaxHostTxWeight: Weight =
+		Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND, u64::MAX);
+}
+
+impl crate::ics27::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type CallFilter = IcaCallFilter;
+	type MaxHostTxWeight = IcaMaxHostTxWeight;
+	type WeightInfo = ();
+}
+
 #[derive(
 	Debug, codec::Encode, Clone, codec::Decode, PartialEq, Eq, scale_info::TypeInfo, Default,
 )]
@@ -422,11 +457,16 @@ where
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Router {
-	ibc_ping: pallet_ibc_ping::IbcModule<Test>,
+	ibc_ping: crate::ics29_fee::Ics29Fee<Test, pallet_ibc_ping::IbcModule<Test>>,
 	ics20: crate::ics20::memo::Memo<
 		Test,
-		crate::ics20_fee::Ics20ServiceCharge<Test, crate::ics20::IbcModule<Test>>,
+		crate::ics20_fee::Ics20ServiceCharge<
+			Test,
+			crate::ics20_rate_limit::Ics20RateLimit<Test, crate::ics20::IbcModule<Test>>,
+		>,
 	>,
+	ica_controller: crate::ics27::IcaController<Test>,
+	ica_host: crate::ics27::IcaHost<Test>,
 }
 
 impl ModuleRouter for Router {
@@ -437,6 +477,8 @@ impl ModuleRouter for Router {
 		match module_id.as_ref() {
 			pallet_ibc_ping::MODULE_ID => Some(&mut self.ibc_ping),
 			ibc::applications::transfer::MODULE_ID_STR => Some(&mut self.ics20),
+			interchain_accounts::CONTROLLER_MODULE_ID_STR => Some(&mut self.ica_controller),
+			interchain_accounts::HOST_MODULE_ID_STR => Some(&mut self.ica_host),
 			&_ => None,
 		}
 	}
@@ -444,7 +486,10 @@ impl ModuleRouter for Router {
 	fn has_route(module_id: &ibc::core::ics26_routing::context::ModuleId) -> bool {
 		matches!(
 			module_id.as_ref(),
-			pallet_ibc_ping::MODULE_ID | ibc::applications::transfer::MODULE_ID_STR
+			pallet_ibc_ping::MODULE_ID |
+				ibc::applications::transfer::MODULE_ID_STR |
+				interchain_accounts::CONTROLLER_MODULE_ID_STR |
+				interchain_accounts::HOST_MODULE_ID_STR
 		)
 	}
 
@@ -460,6 +505,26 @@ impl ModuleRouter for Router {
 					ibc::applications::transfer::MODULE_ID_STR,
 				)
 				.ok(),
+			interchain_accounts::HOST_PORT_ID_STR =>
+				ibc::core::ics26_routing::context::ModuleId::from_str(
+					interchain_accounts::HOST_MODULE_ID_STR,
+				)
+				.ok(),
+			_ if interchain_accounts::controller_port_owner(port_id).is_some() =>
+				ibc::core::ics26_routing::context::ModuleId::from_str(
+					interchain_accounts::CONTROLLER_MODULE_ID_STR,
+				)
+				.ok(),
+			_ => None,
+		}
+	}
+
+	fn callback_weight(port_id: &str) -> Option<Box<dyn ibc_primitives::CallbackWeight>> {
+		match port_id {
+			interchain_accounts::HOST_PORT_ID_STR =>
+				Some(Box::new(crate::ics27::WeightHandler::<Test>::default())),
+			_ if port_id.starts_with(interchain_accounts::CONTROLLER_PORT_PREFIX) =>
+				Some(Box::new(crate::ics27::WeightHandler::<Test>::default())),
 			_ => None,
 		}
 	}
@@ -480,6 +545,9 @@ frame_support::construct_runtime!(
 		PalletBalances: balances,
 		IbcPing: pallet_ibc_ping,
 		Ics20Fee: crate::ics20_fee,
+		Ics20RateLimit: crate::ics20_rate_limit,
+		Ics29Fee: crate::ics29_fee,
+		Ics27: crate::ics27,
 		Ibc: pallet_ibc,
 		Aura: pallet_aura,
 		Membership: pallet_membership::<Instance2>,
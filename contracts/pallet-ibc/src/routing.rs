@@ -9,6 +9,7 @@ use ibc::{
 		},
 	},
 };
+use ibc_primitives::CallbackWeight;
 use scale_info::prelude::string::ToString;
 use sp_core::crypto::AccountId32;
 
@@ -50,6 +51,11 @@ pub trait ModuleRouter: Default + Clone + Eq + PartialEq + Debug {
 	fn has_route(module_id: &ModuleId) -> bool;
 	/// Should return the module_id associated with a given port_id
 	fn lookup_module_by_port(port_id: &PortId) -> Option<ModuleId>;
+	/// Returns the callback weights of the module bound to the given port, modules without
+	/// them are charged the maximum weight
+	fn callback_weight(_port_id: &str) -> Option<Box<dyn CallbackWeight>> {
+		None
+	}
 }
 
 impl<T: Config + Send + Sync> Router for IbcRouter<T>
@@ -1,24 +1,28 @@
 use crate::{
+	ics20::{HandleMemo, IbcMemoHandler, InFlightForward},
 	ics20_fee::FlatFeeConverter,
-	light_clients::{AnyClientState, AnyConsensusState},
+	ics20_rate_limit::{Ics20RateLimiter, PacketInflows, PacketOutflows, Quota, RateLimit},
+	light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager},
 	mock::*,
 	routing::Context,
-	Any, Config, ConsensusHeights, DenomToAssetId, Event, MultiAddress, Pallet,
-	PendingRecvPacketSeqs, PendingSendPacketSeqs, Timeout, TransferParams, MODULE_ID,
+	Any, Config, ConsensusHeights, DenomToAssetId, Event, ForwardedPackets,
+	ForwardedTransferPackets, MultiAddress, Pallet, PendingRecvPacketSeqs, PendingSendPacketSeqs,
+	Timeout, TransferParams, MODULE_ID,
 };
 use core::time::Duration;
 use frame_support::{
 	assert_noop, assert_ok,
 	traits::{
 		fungibles::{Inspect, Mutate},
-		Currency, Hooks, Len,
+		Currency, Get, Hooks, Len,
 	},
 	weights::Weight,
 };
 use ibc::{
 	applications::transfer::{
-		acknowledgement::Acknowledgement as Ics20Acknowledgement, packet::PacketData, Coin,
-		PrefixedDenom, VERSION,
+		acknowledgement::Acknowledgement as Ics20Acknowledgement,
+		packet::{ForwardingPacketData, PacketData, PacketDataV2},
+		Coin, PrefixedDenom, TracePrefix, VERSION,
 	},
 	core::{
 		ics02_client::{
@@ -52,9 +56,11 @@ use ibc::{
 	},
 	proofs::Proofs,
 	signer::Signer,
+	timestamp::Timestamp,
 	tx_msg::Msg,
 };
-use ibc_primitives::{get_channel_escrow_address, HandlerMessage, IbcHandler};
+use ibc_primitives::{get_channel_escrow_address, get_forward_address, HandlerMessage, IbcHandler};
+use ibc_proto::ibc::core::channel::v1::Packet as RawPacket;
 use sp_core::Pair;
 use sp_runtime::{
 	traits::{Bounded, IdentifyAccount},
@@ -499,6 +505,460 @@ fn on_deliver_ics20_recv_packet() {
 	})
 }
 
+#[test]
+fn on_deliver_ics20_v2_recv_packet_with_multiple_tokens() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		// Upgrade the channel to ics20-2
+		let mut ctx = Context::<Test>::default();
+		let channel_id = ChannelId::new(0);
+		let mut channel_end = ctx.channel_end(&(PortId::transfer(), channel_id)).unwrap();
+		channel_end.version = ChanVersion::ics20_v2();
+		ctx.store_channel((PortId::transfer(), channel_id), &channel_end).unwrap();
+
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap()
+				.into_account();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&channel_escrow_address, 100000 * MILLIS);
+
+		let amounts = [1000 * MILLIS, 500 * MILLIS];
+		let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+		let recv_packet = |sequence: u64, hops| {
+			let packet_data = PacketDataV2 {
+				tokens: amounts
+					.iter()
+					.map(|amount| Coin {
+						denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+						amount: (*amount).into(),
+					})
+					.collect(),
+				sender: Signer::from_str("alice").unwrap(),
+				receiver: Signer::from_str(&ss58_address).unwrap(),
+				memo: "".to_string(),
+				forwarding: ForwardingPacketData { destination_memo: "".to_string(), hops },
+			};
+			let packet = Packet {
+				sequence: sequence.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: channel_id,
+				data: packet_data.encode(&ChanVersion::ics20_v2()).unwrap(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: Timestamp::from_nanoseconds(time_now as u64 + 10000000).unwrap(),
+			};
+			let msg = MsgRecvPacket {
+				packet,
+				proofs: Proofs::new(
+					vec![0u8; 32].try_into().unwrap(),
+					None,
+					None,
+					None,
+					Height::new(0, 1),
+				)
+				.unwrap(),
+				signer: Signer::from_str(MODULE_ID).unwrap(),
+			};
+			Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() }
+		};
+		let balance = || {
+			<<Test as Config>::NativeCurrency as Currency<
+				<Test as frame_system::Config>::AccountId,
+			>>::free_balance(&AccountId32::new(pair.public().0))
+		};
+
+		Ibc::deliver(
+			RuntimeOrigin::signed(AccountId32::new([0; 32])),
+			vec![recv_packet(1, vec![])],
+		)
+		.unwrap();
+		let fee: u128 = amounts
+			.iter()
+			.map(|amount| <Test as crate::ics20_fee::Config>::ServiceChargeIn::get() * *amount)
+			.sum();
+		assert_eq!(balance(), amounts.iter().sum::<u128>() - fee);
+	})
+}
+
+#[test]
+fn should_forward_ics20_v2_tokens_and_acknowledge_once_the_next_hop_completes() {
+	use crate::ics20::IbcModule;
+	use ibc::core::ics26_routing::context::{Module, ModuleOutputBuilder};
+
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		// Tokens are received on channel-0 and forwarded over channel-2, both are ics20-2
+		let mut ctx = Context::<Test>::default();
+		let mut channel_end = ctx.channel_end(&(PortId::transfer(), ChannelId::new(0))).unwrap();
+		channel_end.version = ChanVersion::ics20_v2();
+		ctx.store_channel((PortId::transfer(), ChannelId::new(0)), &channel_end)
+			.unwrap();
+		channel_end.remote = ChanCounterParty::new(PortId::transfer(), Some(ChannelId::new(3)));
+		ctx.store_channel((PortId::transfer(), ChannelId::new(2)), &channel_end)
+			.unwrap();
+		ctx.store_next_sequence_send((PortId::transfer(), ChannelId::new(2)), 1.into())
+			.unwrap();
+
+		let account = |signer: Signer| {
+			<Test as Config>::AccountIdConversion::try_from(signer)
+				.map_err(|_| ())
+				.unwrap()
+				.into_account()
+		};
+		let balance = |account: &AccountId32| {
+			<<Test as Config>::NativeCurrency as Currency<
+				<Test as frame_system::Config>::AccountId,
+			>>::free_balance(account)
+		};
+		let escrow = |channel| {
+			account(
+				get_channel_escrow_address(&PortId::transfer(), ChannelId::new(channel)).unwrap(),
+			)
+		};
+		let forwarder = get_forward_address(&PortId::transfer(), ChannelId::new(0)).unwrap();
+		let escrowed = 100000 * MILLIS;
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&escrow(0), escrowed);
+
+		let amounts = [1000 * MILLIS, 500 * MILLIS];
+		let total = amounts.iter().sum::<u128>();
+		let tokens = |denom: &str| {
+			amounts
+				.iter()
+				.map(|amount| Coin {
+					denom: PrefixedDenom::from_str(denom).unwrap(),
+					amount: (*amount).into(),
+				})
+				.collect::<Vec<_>>()
+		};
+		let hop = TracePrefix::new(PortId::transfer(), ChannelId::new(2));
+		let recv_packet = |sequence: u64| {
+			let packet_data = PacketDataV2 {
+				tokens: tokens("transfer/channel-1/PICA"),
+				sender: Signer::from_str("alice").unwrap(),
+				receiver: Signer::from_str(&ss58_address).unwrap(),
+				memo: "".to_string(),
+				forwarding: ForwardingPacketData {
+					destination_memo: "memo".to_string(),
+					hops: vec![hop.clone()],
+				},
+			};
+			let packet = Packet {
+				sequence: sequence.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: ChannelId::new(0),
+				data: packet_data.encode(&ChanVersion::ics20_v2()).unwrap(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: Default::default(),
+			};
+			let msg = MsgRecvPacket {
+				packet,
+				proofs: Proofs::new(
+					vec![0u8; 32].try_into().unwrap(),
+					None,
+					None,
+					None,
+					Height::new(0, 1),
+				)
+				.unwrap(),
+				signer: Signer::from_str(MODULE_ID).unwrap(),
+			};
+			Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() }
+		};
+		// The packet sent by the forward address to the next hop, as the transfer callbacks see it
+		let forwarded_packet = |sequence: u64| Packet {
+			sequence: sequence.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(2),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(3),
+			data: PacketDataV2 {
+				tokens: tokens("PICA"),
+				sender: forwarder.clone(),
+				receiver: Signer::from_str(&ss58_address).unwrap(),
+				memo: "memo".to_string(),
+				forwarding: Default::default(),
+			}
+			.encode(&ChanVersion::ics20_v2())
+			.unwrap(),
+			timeout_height: Height::zero(),
+			timeout_timestamp: Default::default(),
+		};
+		let forwarded = |sequence: u64| {
+			ForwardedTransferPackets::<Test>::contains_key((b"transfer".to_vec(), 2, sequence))
+		};
+		let relayer = Signer::from_str(MODULE_ID).unwrap();
+
+		// The tokens go through the forward address to the escrow of the next hop, the received
+		// packet isn't acknowledged yet
+		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![recv_packet(1)])
+			.unwrap();
+		assert_eq!(balance(&AccountId32::new(pair.public().0)), 0);
+		assert_eq!(balance(&account(forwarder.clone())), 0);
+		assert_eq!(balance(&escrow(0)), escrowed - total);
+		assert_eq!(balance(&escrow(2)), total);
+		assert!(forwarded(1));
+		assert!(forwarded_packet_ack(1).is_none());
+		assert_eq!(
+			ctx.get_next_sequence_send(&(PortId::transfer(), ChannelId::new(2))).unwrap(),
+			2.into()
+		);
+
+		// The next hop acknowledged the tokens, so is the received packet
+		IbcModule::<Test>::default()
+			.on_acknowledgement_packet(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&mut forwarded_packet(1),
+				&Acknowledgement::from_bytes(
+					Ics20Acknowledgement::success().to_string().into_bytes(),
+				),
+				&relayer,
+			)
+			.unwrap();
+		assert!(!forwarded(1));
+		assert!(forwarded_packet_ack(1).unwrap().is_successful());
+
+		// The forward timed out, the tokens are escrowed back on the receiving channel and the
+		// received packet fails so that the sender is refunded
+		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![recv_packet(2)])
+			.unwrap();
+		assert!(forwarded(2));
+		IbcModule::<Test>::default()
+			.on_timeout_packet(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&mut forwarded_packet(2),
+				&relayer,
+			)
+			.unwrap();
+		assert!(!forwarded(2));
+		assert!(!forwarded_packet_ack(2).unwrap().is_successful());
+		assert_eq!(balance(&account(forwarder)), 0);
+		assert_eq!(balance(&escrow(0)), escrowed - total);
+		assert_eq!(balance(&escrow(2)), total);
+	})
+}
+
+#[test]
+fn should_rate_limit_ics20_transfers_over_channel_quota() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"PICA",
+			)
+			.unwrap();
+		let channel_id = ChannelId::new(0);
+		let sender = AccountId32::new([0; 32]);
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&sender, 100000 * MILLIS);
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap()
+				.into_account();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&channel_escrow_address, 100000 * MILLIS);
+
+		assert_ok!(Ics20RateLimit::set_rate_limit(
+			RuntimeOrigin::root(),
+			channel_id.sequence(),
+			b"PICA".to_vec(),
+			RateLimit {
+				max_send: Quota::Absolute(1000 * MILLIS),
+				max_recv: Quota::Absolute(500 * MILLIS),
+				period: 100,
+			},
+		));
+
+		let transfer = |amount: u128| {
+			Ibc::transfer(
+				RuntimeOrigin::signed(sender.clone()),
+				TransferParams {
+					to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
+					source_channel: channel_id.sequence(),
+					timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+				},
+				asset_id,
+				amount,
+				None,
+			)
+		};
+		assert_noop!(transfer(2000 * MILLIS), crate::Error::<Test>::RateLimiter);
+		assert_ok!(transfer(800 * MILLIS));
+
+		let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+		let recv_packet = |sequence: u64, amount: u128| {
+			let packet_data = PacketData {
+				token: Coin {
+					denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+					amount: amount.into(),
+				},
+				sender: Signer::from_str("alice").unwrap(),
+				receiver: Signer::from_str(&ss58_address).unwrap(),
+				memo: "".to_string(),
+			};
+			let msg = MsgRecvPacket {
+				packet: Packet {
+					sequence: sequence.into(),
+					source_port: PortId::transfer(),
+					source_channel: ChannelId::new(1),
+					destination_port: PortId::transfer(),
+					destination_channel: channel_id,
+					data: serde_json::to_vec(&packet_data).unwrap(),
+					timeout_height: Height::new(2000, 5),
+					timeout_timestamp: Timestamp::from_nanoseconds(time_now as u64 + 10000000)
+						.unwrap(),
+				},
+				proofs: Proofs::new(
+					vec![0u8; 32].try_into().unwrap(),
+					None,
+					None,
+					None,
+					Height::new(0, 1),
+				)
+				.unwrap(),
+				signer: Signer::from_str(MODULE_ID).unwrap(),
+			};
+			Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() }
+		};
+		let balance = || {
+			<<Test as Config>::NativeCurrency as Currency<
+				<Test as frame_system::Config>::AccountId,
+			>>::free_balance(&AccountId32::new(pair.public().0))
+		};
+
+		// The inflow is netted against the tokens sent in the same window
+		Ibc::deliver(RuntimeOrigin::signed(sender.clone()), vec![recv_packet(1, 1000 * MILLIS)])
+			.unwrap();
+		let credited = balance();
+		assert!(credited > 0);
+
+		// Over the receive quota, the packet is acknowledged with an error
+		Ibc::deliver(RuntimeOrigin::signed(sender.clone()), vec![recv_packet(2, 1000 * MILLIS)])
+			.unwrap();
+		assert_eq!(balance(), credited);
+		assert!(System::events().iter().any(|a| matches!(
+			&a.event,
+			RuntimeEvent::Ics20RateLimit(crate::ics20_rate_limit::Event::InflowRateLimited {
+				denom,
+				..
+			}) if denom == b"PICA"
+		)));
+
+		// The window rolls: right after the first window, all of its flows still count
+		frame_system::Pallet::<Test>::set_block_number(101u32);
+		Ibc::deliver(RuntimeOrigin::signed(sender.clone()), vec![recv_packet(3, 400 * MILLIS)])
+			.unwrap();
+		assert_eq!(balance(), credited);
+
+		// Half way through the next window, only half of them do
+		frame_system::Pallet::<Test>::set_block_number(151u32);
+		Ibc::deliver(RuntimeOrigin::signed(sender), vec![recv_packet(4, 400 * MILLIS)]).unwrap();
+		assert!(balance() > credited);
+	})
+}
+
+#[test]
+fn should_only_revert_outflows_still_counted_in_the_rolling_window() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let channel = 0;
+		assert_ok!(Ics20RateLimit::set_rate_limit(
+			RuntimeOrigin::root(),
+			channel,
+			b"PICA".to_vec(),
+			RateLimit {
+				max_send: Quota::Absolute(1000),
+				max_recv: Quota::Absolute(1000),
+				period: 100,
+			},
+		));
+		assert_ok!(Ics20RateLimit::record_outflow(channel, 1, "PICA", 600));
+
+		// Half of the previous window is still covered by the rolling window
+		frame_system::Pallet::<Test>::set_block_number(151u32);
+		assert!(Ics20RateLimit::record_outflow(channel, 2, "PICA", 800).is_err());
+		assert_ok!(Ics20RateLimit::record_outflow(channel, 2, "PICA", 700));
+
+		// Refunding the first transfer frees its share of the rolling window
+		Ics20RateLimit::revert_outflow(channel, 1, "PICA", 600);
+		assert_ok!(Ics20RateLimit::record_outflow(channel, 3, "PICA", 300));
+
+		// The window of the second transfer has rolled past, refunding it frees nothing
+		frame_system::Pallet::<Test>::set_block_number(351u32);
+		assert_ok!(Ics20RateLimit::record_outflow(channel, 4, "PICA", 1000));
+		Ics20RateLimit::revert_outflow(channel, 2, "PICA", 700);
+		assert!(Ics20RateLimit::record_outflow(channel, 5, "PICA", 1).is_err());
+		assert!(!PacketOutflows::<Test>::contains_key(channel, 2));
+	})
+}
+
+#[test]
+fn should_revert_inflows_of_received_packets_acknowledged_with_an_error() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let channel = 0;
+		assert_ok!(Ics20RateLimit::set_rate_limit(
+			RuntimeOrigin::root(),
+			channel,
+			b"PICA".to_vec(),
+			RateLimit {
+				max_send: Quota::Absolute(1000),
+				max_recv: Quota::Absolute(1000),
+				period: 100,
+			},
+		));
+		// Forwarded packets are acknowledged once the forward completes
+		assert_ok!(Ics20RateLimit::record_inflow(channel, 1, "PICA", 600));
+		assert_ok!(Ics20RateLimit::record_inflow(channel, 2, "PICA", 400));
+		assert!(Ics20RateLimit::record_inflow(channel, 3, "PICA", 1).is_err());
+
+		// The forward of the first packet failed, its tokens no longer count
+		Ics20RateLimit::on_recv_acknowledged(ChannelId::new(channel), 1u64.into(), false);
+		assert_ok!(Ics20RateLimit::record_inflow(channel, 3, "PICA", 600));
+		assert!(!PacketInflows::<Test>::contains_key(channel, 1));
+
+		// The forward of the second packet succeeded, its tokens still count
+		Ics20RateLimit::on_recv_acknowledged(ChannelId::new(channel), 2u64.into(), true);
+		assert!(Ics20RateLimit::record_inflow(channel, 4, "PICA", 1).is_err());
+		assert!(!PacketInflows::<Test>::contains_key(channel, 2));
+	})
+}
+
 #[test]
 fn on_deliver_ics20_recv_packet_incorrect_memo() {
 	let mut ext = new_test_ext();
@@ -1310,3 +1770,884 @@ fn test_next_and_previous_consensus_state_for_other_client_types() {
 		assert!(ctx.next_consensus_state(&client_id, Height::new(0, 400)).unwrap().is_some());
 	})
 }
+
+#[test]
+fn full_consensus_heights_remove_the_oldest_consensus_state() {
+	new_test_ext().execute_with(|| {
+		let client_id = ClientId::from_str("10-grandpa-0").unwrap();
+		let mut ctx = Context::<Test>::default();
+		let mock_cs_state = MockConsensusState::new(MockHeader::default());
+		for i in 1..=257u64 {
+			ctx.store_consensus_state(
+				client_id.clone(),
+				Height::new(0, i),
+				AnyConsensusState::Mock(mock_cs_state.clone()),
+			)
+			.unwrap();
+			ctx.store_update_time(
+				client_id.clone(),
+				Height::new(0, i),
+				Timestamp::from_nanoseconds(i).unwrap(),
+			)
+			.unwrap();
+		}
+
+		let stored_heights = ConsensusHeights::<Test>::get(client_id.as_bytes().to_vec());
+		assert_eq!(stored_heights.len(), 256);
+		assert_eq!(stored_heights.iter().next(), Some(&Height::new(0, 2)));
+
+		// no consensus state is kept without being tracked for pruning
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 1)).is_err());
+		assert!(ctx.client_update_time(&client_id, Height::new(0, 1)).is_err());
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 2)).is_ok());
+	})
+}
+
+#[test]
+fn full_consensus_heights_prune_the_expired_consensus_states_first() {
+	new_test_ext().execute_with(|| {
+		let client_id = ClientId::from_str("10-grandpa-0").unwrap();
+		let mut ctx = Context::<Test>::default();
+		let mut client_state =
+			ics10_grandpa::client_state::ClientState::<HostFunctionsManager>::default();
+		client_state.latest_para_height = 257;
+		let revision = client_state.latest_height().revision_number;
+		let trusting_period = client_state.relay_chain.trusting_period().unwrap();
+		let now = trusting_period + Duration::from_secs(1000);
+		pallet_timestamp::Pallet::<Test>::set_timestamp(now.as_millis() as u64);
+		ctx.store_client_state(client_id.clone(), AnyClientState::Grandpa(client_state))
+			.unwrap();
+
+		// heights 1 and 2 are past the trusting period
+		for height in 1..=257u64 {
+			let age = if height <= 2 {
+				trusting_period + Duration::from_secs(10)
+			} else {
+				trusting_period / 2
+			};
+			let timestamp = Timestamp::from_nanoseconds((now - age).as_nanos() as u64).unwrap();
+			let consensus_state = ics10_grandpa::consensus_state::ConsensusState::new(
+				vec![],
+				timestamp.into_tm_time().unwrap(),
+			);
+			ctx.store_consensus_state(
+				client_id.clone(),
+				Height::new(revision, height),
+				AnyConsensusState::Grandpa(consensus_state),
+			)
+			.unwrap();
+		}
+
+		let stored_heights = ConsensusHeights::<Test>::get(client_id.as_bytes().to_vec());
+		assert_eq!(stored_heights.len(), 255);
+		assert_eq!(stored_heights.iter().next(), Some(&Height::new(revision, 3)));
+		for (height, pruned) in [(1, true), (2, true), (3, false), (257, false)] {
+			let height = Height::new(revision, height);
+			assert_eq!(ctx.consensus_state(&client_id, height).is_err(), pruned);
+		}
+	})
+}
+
+#[test]
+fn only_expired_consensus_states_are_pruned() {
+	new_test_ext().execute_with(|| {
+		let client_id = ClientId::from_str("10-grandpa-0").unwrap();
+		let mut ctx = Context::<Test>::default();
+		let mut client_state =
+			ics10_grandpa::client_state::ClientState::<HostFunctionsManager>::default();
+		client_state.latest_para_height = 4;
+		let revision = client_state.latest_height().revision_number;
+		let trusting_period = client_state.relay_chain.trusting_period().unwrap();
+		let now = trusting_period + Duration::from_secs(1000);
+		pallet_timestamp::Pallet::<Test>::set_timestamp(now.as_millis() as u64);
+
+		// heights 1 and 2 are past the trusting period, 3 isn't, and 4 is the latest height
+		let ages = [
+			trusting_period + Duration::from_secs(20),
+			trusting_period + Duration::from_secs(10),
+			trusting_period / 2,
+			trusting_period + Duration::from_secs(30),
+		];
+		for (height, age) in (1..).zip(ages) {
+			let timestamp = Timestamp::from_nanoseconds((now - age).as_nanos() as u64).unwrap();
+			let consensus_state = ics10_grandpa::consensus_state::ConsensusState::new(
+				vec![],
+				timestamp.into_tm_time().unwrap(),
+			);
+			ctx.store_consensus_state(
+				client_id.clone(),
+				Height::new(revision, height),
+				AnyConsensusState::Grandpa(consensus_state),
+			)
+			.unwrap();
+			ctx.store_update_time(client_id.clone(), Height::new(revision, height), timestamp)
+				.unwrap();
+		}
+		// storing a consensus state never removes one
+		assert_eq!(ConsensusHeights::<Test>::get(client_id.as_bytes().to_vec()).len(), 4);
+
+		ctx.store_client_state(client_id.clone(), AnyClientState::Grandpa(client_state))
+			.unwrap();
+
+		let stored_heights = ConsensusHeights::<Test>::get(client_id.as_bytes().to_vec());
+		assert_eq!(
+			stored_heights.into_iter().collect::<Vec<_>>(),
+			vec![Height::new(revision, 3), Height::new(revision, 4)]
+		);
+		for (height, pruned) in [(1, true), (2, true), (3, false), (4, false)] {
+			let height = Height::new(revision, height);
+			assert_eq!(ctx.consensus_state(&client_id, height).is_err(), pruned);
+			assert_eq!(ctx.client_update_time(&client_id, height).is_err(), pruned);
+		}
+	})
+}
+
+#[test]
+fn should_recover_frozen_client_with_substitute() {
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let create_client = |height: u64| {
+			let msg = MsgCreateAnyClient::<Context<Test>>::new(
+				AnyClientState::Mock(MockClientState::new(MockClientMessage::from(
+					MockHeader::new(Height::new(0, height)),
+				))),
+				AnyConsensusState::Mock(MockConsensusState::new(MockHeader::new(Height::new(
+					0, height,
+				)))),
+				Signer::from_str(MODULE_ID).unwrap(),
+			)
+			.unwrap()
+			.encode_vec()
+			.unwrap();
+			let msg = Any { type_url: TYPE_URL.to_string(), value: msg };
+			assert_ok!(Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]));
+		};
+
+		create_client(1);
+		let subject_client_id = ClientId::new(&MockClientState::client_type(), 0).unwrap();
+		let substitute_client_id = ClientId::new(&MockClientState::client_type(), 1).unwrap();
+
+		// an active client cannot be recovered
+		create_client(5);
+		assert_noop!(
+			Ibc::recover_client(
+				RuntimeOrigin::root(),
+				subject_client_id.to_string(),
+				substitute_client_id.to_string(),
+			),
+			crate::Error::<Test>::ClientRecoveryFailed
+		);
+
+		assert_ok!(Ibc::freeze_client(
+			RuntimeOrigin::root(),
+			subject_client_id.as_bytes().to_vec(),
+			1
+		));
+		assert_ok!(Ibc::recover_client(
+			RuntimeOrigin::root(),
+			subject_client_id.to_string(),
+			substitute_client_id.to_string(),
+		));
+		System::assert_last_event(RuntimeEvent::Ibc(Event::<Test>::ClientRecovered {
+			subject_client_id: subject_client_id.to_string(),
+			substitute_client_id: substitute_client_id.to_string(),
+			height: Height::new(0, 5),
+		}));
+
+		let ctx = Context::<Test>::default();
+		let client_state = ctx.client_state(&subject_client_id).unwrap();
+		assert_eq!(client_state.latest_height(), Height::new(0, 5));
+		assert!(client_state.frozen_height().is_none());
+		assert!(ctx.consensus_state(&subject_client_id, Height::new(0, 5)).is_ok());
+	})
+}
+
+fn timed_out_forward_packet(sequence: u64) -> Packet {
+	Packet {
+		sequence: sequence.into(),
+		source_port: PortId::transfer(),
+		source_channel: ChannelId::new(0),
+		destination_port: PortId::transfer(),
+		destination_channel: ChannelId::new(1),
+		data: vec![],
+		timeout_height: Height::new(0, 1),
+		timeout_timestamp: Default::default(),
+	}
+}
+
+/// Packet received on channel-0 whose tokens were forwarded by `receiver`
+fn received_forward_packet(sequence: u64, denom: &str, receiver: &str, amount: u128) -> Packet {
+	let packet_data = PacketData {
+		token: Coin {
+			denom: PrefixedDenom::from_str(denom).unwrap(),
+			amount: ibc::applications::transfer::Amount::from_str(&amount.to_string()).unwrap(),
+		},
+		sender: Signer::from_str("alice").unwrap(),
+		receiver: Signer::from_str(receiver).unwrap(),
+		memo: "".to_string(),
+	};
+	Packet {
+		sequence: sequence.into(),
+		source_port: PortId::transfer(),
+		source_channel: ChannelId::new(1),
+		destination_port: PortId::transfer(),
+		destination_channel: ChannelId::new(0),
+		data: serde_json::to_vec(&packet_data).unwrap(),
+		timeout_height: Height::new(2000, 5),
+		timeout_timestamp: Default::default(),
+	}
+}
+
+fn forwarded_packet_ack(sequence: u64) -> Option<Ics20Acknowledgement> {
+	let key = Pallet::<Test>::ack_key(b"channel-0".to_vec(), b"transfer".to_vec(), sequence);
+	crate::Acks::<Test>::get(key)
+		.map(|ack| Ics20Acknowledgement::from_str(&String::from_utf8(ack).unwrap()).unwrap())
+}
+
+#[test]
+fn should_retry_forwarded_transfer_on_timeout_and_unwind_when_out_of_retries() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"PICA",
+			)
+			.unwrap();
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let forwarder = AccountId32::new(pair.public().0);
+		let amount = 1000 * MILLIS;
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&forwarder, 10 * amount);
+		let escrow = <Test as Config>::AccountIdConversion::try_from(
+			get_channel_escrow_address(&PortId::transfer(), ChannelId::new(0)).unwrap(),
+		)
+		.map_err(|_| ())
+		.unwrap()
+		.into_account();
+
+		// native tokens unescrowed from channel-0 when they were received
+		let received = received_forward_packet(5, "transfer/channel-1/PICA", &ss58_address, amount);
+		let forward = InFlightForward {
+			forwarder: forwarder.clone(),
+			receiver: b"bob".to_vec(),
+			asset_id,
+			amount,
+			channel: 0,
+			timeout: 1000,
+			next_memo: None,
+			retries: 1,
+			received_packet: prost::Message::encode_to_vec(&RawPacket::from(received)),
+		};
+		ForwardedPackets::<Test>::insert((0, 7), forward.clone());
+		let handler = IbcMemoHandler::<(), Test>::default();
+
+		// the timed out transfer is resent with the next sequence
+		handler.on_timeout(&timed_out_forward_packet(7)).unwrap();
+		assert!(!ForwardedPackets::<Test>::contains_key((0, 7)));
+		assert_eq!(
+			ForwardedPackets::<Test>::get((0, 1)),
+			Some(InFlightForward { retries: 0, ..forward.clone() })
+		);
+		assert!(forwarded_packet_ack(5).is_none());
+
+		// acknowledgements of other packets are ignored
+		handler.on_acknowledgement(&timed_out_forward_packet(7), false).unwrap();
+		assert!(ForwardedPackets::<Test>::contains_key((0, 1)));
+
+		// out of retries, the tokens are escrowed again and the received packet fails
+		handler.on_timeout(&timed_out_forward_packet(1)).unwrap();
+		assert_eq!(ForwardedPackets::<Test>::iter().count(), 0);
+		assert!(!forwarded_packet_ack(5).unwrap().is_successful());
+		assert_eq!(
+			<<Test as Config>::NativeCurrency as Currency<
+				<Test as frame_system::Config>::AccountId,
+			>>::free_balance(&escrow),
+			2 * amount
+		);
+		assert_eq!(
+			System::events()
+				.iter()
+				.filter(|a| {
+					matches!(
+						&a.event,
+						RuntimeEvent::Ibc(Event::<Test>::ExecuteMemoIbcTokenTransferRefunded {
+							from,
+							to,
+							channel: 0,
+							..
+						}) if from == &forwarder && to == b"alice"
+					)
+				})
+				.count(),
+			1
+		);
+
+		// only the retry was sent, the refund goes through the acknowledgement
+		let ctx = Context::<Test>::default();
+		assert_eq!(
+			ctx.get_next_sequence_send(&(PortId::transfer(), ChannelId::new(0))).unwrap(),
+			2.into()
+		);
+	});
+}
+
+#[test]
+fn should_acknowledge_forwarded_packet_once_forward_is_acknowledged() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		// vouchers minted when they were received
+		let denom = "ATOM";
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"transfer/channel-0/ATOM",
+			)
+			.unwrap();
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let forwarder = AccountId32::new(pair.public().0);
+		let amount = 1000 * MILLIS;
+		assert_ok!(<<Test as Config>::Fungibles as Mutate<AccountId>>::mint_into(
+			asset_id,
+			&forwarder,
+			10 * amount
+		));
+
+		let forward = |sequence| InFlightForward {
+			forwarder: forwarder.clone(),
+			receiver: b"bob".to_vec(),
+			asset_id,
+			amount,
+			channel: 0,
+			timeout: 1000,
+			next_memo: None,
+			retries: 3,
+			received_packet: prost::Message::encode_to_vec(&RawPacket::from(
+				received_forward_packet(sequence, denom, &ss58_address, amount),
+			)),
+		};
+		ForwardedPackets::<Test>::insert((0, 7), forward(5));
+		ForwardedPackets::<Test>::insert((0, 8), forward(6));
+		let handler = IbcMemoHandler::<(), Test>::default();
+
+		// successful acknowledgements complete the received packet
+		handler.on_acknowledgement(&timed_out_forward_packet(7), true).unwrap();
+		assert!(!ForwardedPackets::<Test>::contains_key((0, 7)));
+		assert!(forwarded_packet_ack(5).unwrap().is_successful());
+		assert_eq!(Assets::balance(asset_id, forwarder.clone()), 10 * amount);
+
+		// error acknowledgements aren't retried, the vouchers are burnt
+		handler.on_acknowledgement(&timed_out_forward_packet(8), false).unwrap();
+		assert_eq!(ForwardedPackets::<Test>::iter().count(), 0);
+		assert!(!forwarded_packet_ack(6).unwrap().is_successful());
+		assert_eq!(Assets::balance(asset_id, forwarder.clone()), 9 * amount);
+		assert!(System::events().iter().any(|a| matches!(
+			&a.event,
+			RuntimeEvent::Ibc(Event::<Test>::ExecuteMemoIbcTokenTransferRefunded { to, .. })
+				if to == b"alice"
+		)));
+	});
+}
+
+#[test]
+fn should_unwind_the_received_amount_and_fee_when_forward_fails() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"transfer/channel-0/ATOM",
+			)
+			.unwrap();
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let forwarder = AccountId32::new(pair.public().0);
+		let fee_account = <Test as Config>::FeeAccount::get().into_account();
+		let amount = 1000 * MILLIS;
+		let fee = 10 * MILLIS;
+		// the service charge was taken from the received vouchers and the rest was forwarded
+		assert_ok!(<<Test as Config>::Fungibles as Mutate<AccountId>>::mint_into(
+			asset_id,
+			&forwarder,
+			amount - fee
+		));
+		assert_ok!(<<Test as Config>::Fungibles as Mutate<AccountId>>::mint_into(
+			asset_id,
+			&fee_account,
+			fee
+		));
+
+		let forward = |sequence, amount| InFlightForward {
+			forwarder: forwarder.clone(),
+			receiver: b"bob".to_vec(),
+			asset_id,
+			amount,
+			channel: 0,
+			timeout: 1000,
+			next_memo: None,
+			retries: 0,
+			received_packet: prost::Message::encode_to_vec(&RawPacket::from(
+				received_forward_packet(sequence, "ATOM", &ss58_address, 1000 * MILLIS),
+			)),
+		};
+		ForwardedPackets::<Test>::insert((0, 7), forward(5, amount - fee));
+		let handler = IbcMemoHandler::<(), Test>::default();
+
+		// all the received vouchers are burnt, the fee included
+		handler.on_acknowledgement(&timed_out_forward_packet(7), false).unwrap();
+		assert!(!forwarded_packet_ack(5).unwrap().is_successful());
+		assert_eq!(Assets::balance(asset_id, forwarder.clone()), 0);
+		assert_eq!(Assets::balance(asset_id, fee_account), 0);
+		assert!(System::events().iter().any(|a| matches!(
+			&a.event,
+			RuntimeEvent::Ibc(Event::<Test>::ExecuteMemoIbcTokenTransferRefunded { amount, .. })
+				if *amount == 1000 * MILLIS
+		)));
+
+		// the vouchers are gone, the packet is left unacknowledged rather than refunded
+		ForwardedPackets::<Test>::insert((0, 8), forward(6, amount - fee));
+		handler.on_acknowledgement(&timed_out_forward_packet(8), false).unwrap();
+		assert!(forwarded_packet_ack(6).is_none());
+	});
+}
+
+#[test]
+fn should_pay_relayer_fees_on_incentivized_acknowledgement() {
+	use crate::ics29_fee::{self, Fee};
+	use ibc::{
+		applications::fee::acknowledgement::IncentivizedAcknowledgement,
+		core::ics26_routing::context::{Module, ModuleOutputBuilder},
+	};
+
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let channel_id = ChannelId::new(0);
+		let port_id = PortId::transfer().as_bytes().to_vec();
+		let native_asset_id = <Test as Config>::NativeAssetId::get();
+		let balance = |account: &AccountId32| {
+			<<Test as Config>::NativeCurrency as Currency<AccountId>>::free_balance(account)
+		};
+
+		let payer = AccountId32::new([0; 32]);
+		let forward_relayer = AccountId32::new([1; 32]);
+		let reverse_relayer = AccountId32::new([2; 32]);
+		let _ = <<Test as Config>::NativeCurrency as Currency<AccountId>>::deposit_creating(
+			&payer,
+			10_000 * MILLIS,
+		);
+		let fee = Fee {
+			recv_fee: vec![(native_asset_id, 100 * MILLIS)],
+			ack_fee: vec![(native_asset_id, 50 * MILLIS)],
+			timeout_fee: vec![(native_asset_id, 20 * MILLIS)],
+		};
+
+		// fees can only be paid on channels that negotiated the fee version
+		assert_noop!(
+			Ics29Fee::pay_packet_fee(
+				RuntimeOrigin::signed(payer.clone()),
+				port_id.clone(),
+				channel_id.sequence(),
+				1,
+				fee.clone()
+			),
+			ics29_fee::Error::<Test>::FeeNotEnabled
+		);
+		ics29_fee::FeeEnabledChannels::<Test>::insert(
+			(port_id.clone(), channel_id.sequence()),
+			true,
+		);
+		// fees are only escrowed for the next packet sent on the channel
+		assert_noop!(
+			Ics29Fee::pay_packet_fee(
+				RuntimeOrigin::signed(payer.clone()),
+				port_id.clone(),
+				channel_id.sequence(),
+				2,
+				fee.clone()
+			),
+			ics29_fee::Error::<Test>::InvalidSequence
+		);
+		assert_ok!(Ics29Fee::pay_packet_fee(
+			RuntimeOrigin::signed(payer.clone()),
+			port_id.clone(),
+			channel_id.sequence(),
+			1,
+			fee
+		));
+		assert_eq!(balance(&payer), 10_000 * MILLIS - 170 * MILLIS);
+		assert_eq!(balance(&ics29_fee::Pallet::<Test>::account_id()), 170 * MILLIS);
+
+		let packet = Packet {
+			sequence: 1.into(),
+			source_port: PortId::transfer(),
+			source_channel: channel_id,
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(1),
+			data: vec![],
+			timeout_height: Height::new(0, 10),
+			timeout_timestamp: Default::default(),
+		};
+		let ack = IncentivizedAcknowledgement {
+			app_acknowledgement: b"pong".to_vec(),
+			forward_relayer_address: ibc_primitives::runtime_interface::account_id_to_ss58(
+				forward_relayer.clone().into(),
+				49,
+			),
+			underlying_app_success: true,
+		};
+		let relayer = Signer::from_str(&ibc_primitives::runtime_interface::account_id_to_ss58(
+			reverse_relayer.clone().into(),
+			49,
+		))
+		.unwrap();
+		let mut module = ics29_fee::Ics29Fee::<Test, pallet_ibc_ping::IbcModule<Test>>::default();
+		module
+			.on_acknowledgement_packet(
+				&Context::<Test>::default(),
+				&mut ModuleOutputBuilder::new(),
+				&mut packet.clone(),
+				&ack.into(),
+				&relayer,
+			)
+			.unwrap();
+
+		// recv fee goes to the forward relayer, ack fee to the reverse relayer and the timeout fee
+		// is refunded
+		assert_eq!(balance(&forward_relayer), 100 * MILLIS);
+		assert_eq!(balance(&reverse_relayer), 50 * MILLIS);
+		assert_eq!(balance(&payer), 10_000 * MILLIS - 150 * MILLIS);
+		assert!(ics29_fee::EscrowedFees::<Test>::get(
+			(port_id, channel_id.sequence()),
+			u64::from(packet.sequence)
+		)
+		.is_empty());
+	})
+}
+
+#[test]
+fn should_enable_relayer_fees_when_handshake_negotiates_fee_version() {
+	use crate::{ics29_fee, routing::ModuleRouter};
+	use ibc::{
+		applications::fee::metadata::Metadata,
+		core::ics26_routing::context::{ModuleId, ModuleOutputBuilder},
+	};
+
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let ctx = Context::<Test>::default();
+		let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+		let app_version = ChanVersion::new(pallet_ibc_ping::VERSION.to_string());
+		let fee_version = Metadata::new(&app_version).to_version();
+		let counterparty = ChanCounterParty::new(port_id.clone(), Some(ChannelId::new(1)));
+		let relayer = Signer::from_str(MODULE_ID).unwrap();
+		let mut router = Router::default();
+		let module_id = ModuleId::from_str(pallet_ibc_ping::MODULE_ID).unwrap();
+		let module = router.get_route_mut(&module_id).unwrap();
+
+		// the counterparty proposed the fee version, the application gets its own version
+		let version = module
+			.on_chan_open_try(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				Order::Ordered,
+				&[ConnectionId::new(0)],
+				&port_id,
+				&ChannelId::new(0),
+				&counterparty,
+				&fee_version,
+				&fee_version,
+				&relayer,
+			)
+			.unwrap();
+		assert_eq!(version, fee_version);
+		assert!(ics29_fee::Pallet::<Test>::is_fee_enabled(&port_id, &ChannelId::new(0)));
+
+		// channels opened with the application version are passed through
+		let version = module
+			.on_chan_open_try(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				Order::Ordered,
+				&[ConnectionId::new(0)],
+				&port_id,
+				&ChannelId::new(1),
+				&counterparty,
+				&app_version,
+				&app_version,
+				&relayer,
+			)
+			.unwrap();
+		assert_eq!(version, app_version);
+		assert!(!ics29_fee::Pallet::<Test>::is_fee_enabled(&port_id, &ChannelId::new(1)));
+
+		// an initialised channel must get the fee version back from the counterparty
+		module
+			.on_chan_open_init(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				Order::Ordered,
+				&[ConnectionId::new(0)],
+				&port_id,
+				&ChannelId::new(2),
+				&counterparty,
+				&fee_version,
+				&relayer,
+			)
+			.unwrap();
+		assert!(ics29_fee::Pallet::<Test>::is_fee_enabled(&port_id, &ChannelId::new(2)));
+		assert!(module
+			.on_chan_open_ack(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&port_id,
+				&ChannelId::new(2),
+				&app_version,
+				&relayer,
+			)
+			.is_err());
+		assert_ok!(module.on_chan_open_ack(
+			&ctx,
+			&mut ModuleOutputBuilder::new(),
+			&port_id,
+			&ChannelId::new(2),
+			&fee_version,
+			&relayer,
+		));
+	})
+}
+
+#[test]
+fn should_register_interchain_account_and_send_tx() {
+	use crate::ics27::{self, IcaController, InterchainMessage};
+	use ibc::{
+		applications::interchain_accounts::{host_port_id, metadata::Metadata},
+		core::ics26_routing::context::{Module, ModuleOutputBuilder},
+	};
+
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let owner = AccountId32::new([1; 32]);
+		let connection_id = ConnectionId::new(0);
+		let port_id = ics27::Pallet::<Test>::controller_port(&owner).unwrap();
+		let mut ctx = Context::<Test>::default();
+		let channel_id = ChannelId::new(ctx.channel_counter().unwrap());
+
+		assert_ok!(Ics27::register_interchain_account(
+			RuntimeOrigin::signed(owner.clone()),
+			connection_id.as_bytes().to_vec()
+		));
+		let channel_end = ctx.channel_end(&(port_id.clone(), channel_id)).unwrap();
+		assert!(channel_end.state_matches(&State::Init));
+		assert_eq!(channel_end.ordering(), &Order::Ordered);
+		assert_eq!(channel_end.counterparty().port_id(), &host_port_id());
+
+		// complete the handshake with the version picked by the host
+		let counterparty = ChanCounterParty::new(host_port_id(), Some(ChannelId::new(1)));
+		let version = Metadata::new(&connection_id, &ConnectionId::new(1))
+			.with_address("host-account".to_string())
+			.to_version();
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			counterparty,
+			vec![connection_id.clone()],
+			version.clone(),
+		);
+		ctx.store_channel((port_id.clone(), channel_id), &channel_end).unwrap();
+		IcaController::<Test>::default()
+			.on_chan_open_ack(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&port_id,
+				&channel_id,
+				&version,
+				&Signer::from_str(MODULE_ID).unwrap(),
+			)
+			.unwrap();
+		assert_eq!(
+			ics27::InterchainAccounts::<Test>::get(connection_id.as_bytes(), port_id.as_bytes()),
+			Some(b"host-account".to_vec())
+		);
+		assert_noop!(
+			Ics27::register_interchain_account(
+				RuntimeOrigin::signed(owner.clone()),
+				connection_id.as_bytes().to_vec()
+			),
+			ics27::Error::<Test>::ActiveChannelExists
+		);
+
+		ctx.store_next_sequence_send((port_id.clone(), channel_id), 1.into()).unwrap();
+		assert_ok!(Ics27::send_tx(
+			RuntimeOrigin::signed(owner),
+			connection_id.as_bytes().to_vec(),
+			vec![InterchainMessage {
+				type_url: b"/cosmos.bank.v1beta1.MsgSend".to_vec(),
+				value: vec![1, 2, 3],
+			}],
+			vec![],
+			Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+		));
+		assert!(ctx.get_packet_commitment(&(port_id, channel_id, 1.into())).is_ok());
+	})
+}
+
+#[test]
+fn should_execute_allowed_calls_from_hosted_interchain_account() {
+	use crate::ics27::{self, IcaHost, RUNTIME_CALL_TYPE_URL};
+	use codec::Encode;
+	use ibc::{
+		applications::interchain_accounts::{
+			controller_port_id, host_port_id,
+			metadata::Metadata,
+			packet::{CosmosTx, InterchainAccountPacketData},
+		},
+		core::ics26_routing::context::{Module, ModuleOutputBuilder},
+	};
+	use ibc_proto::google::protobuf::Any as ProtoAny;
+
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let connection_id = ConnectionId::new(0);
+		let controller_port = controller_port_id("controller").unwrap();
+		let channel_id = ChannelId::new(1);
+		let counterparty = ChanCounterParty::new(controller_port.clone(), Some(ChannelId::new(0)));
+		let relayer = Signer::from_str(MODULE_ID).unwrap();
+		let mut ctx = Context::<Test>::default();
+		let mut host = IcaHost::<Test>::default();
+
+		// the controller chain is on the other end of the connection
+		let version = host
+			.on_chan_open_try(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				Order::Ordered,
+				&[connection_id.clone()],
+				&host_port_id(),
+				&channel_id,
+				&counterparty,
+				&ChanVersion::default(),
+				&Metadata::new(&ConnectionId::new(1), &connection_id).to_version(),
+				&relayer,
+			)
+			.unwrap();
+		let account = ics27::Pallet::<Test>::host_account(&connection_id, &controller_port);
+		assert_eq!(
+			ics27::HostAccounts::<Test>::get(connection_id.as_bytes(), controller_port.as_bytes()),
+			Some(account.clone())
+		);
+		assert!(!Metadata::from_version(&version).unwrap().address.is_empty());
+
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			counterparty,
+			vec![connection_id.clone()],
+			version,
+		);
+		ctx.store_channel((host_port_id(), channel_id), &channel_end).unwrap();
+		host.on_chan_open_confirm(
+			&ctx,
+			&mut ModuleOutputBuilder::new(),
+			&host_port_id(),
+			&channel_id,
+			&relayer,
+		)
+		.unwrap();
+
+		// the account can't open a second channel while this one is open
+		assert!(host
+			.on_chan_open_try(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				Order::Ordered,
+				&[connection_id.clone()],
+				&host_port_id(),
+				&ChannelId::new(2),
+				&channel_end.remote,
+				&ChanVersion::default(),
+				&Metadata::new(&ConnectionId::new(1), &connection_id).to_version(),
+				&relayer,
+			)
+			.is_err());
+
+		let packet = |sequence: u64, call: RuntimeCall| {
+			let tx = CosmosTx {
+				messages: vec![ProtoAny {
+					type_url: RUNTIME_CALL_TYPE_URL.to_string(),
+					value: call.encode(),
+				}],
+			};
+			Packet {
+				sequence: sequence.into(),
+				source_port: controller_port.clone(),
+				source_channel: ChannelId::new(0),
+				destination_port: host_port_id(),
+				destination_channel: channel_id,
+				data: InterchainAccountPacketData::execute_tx(tx, String::new()).to_bytes(),
+				timeout_height: Height::new(0, 10),
+				timeout_timestamp: Default::default(),
+			}
+		};
+
+		let remark = RuntimeCall::System(frame_system::Call::remark_with_event {
+			remark: b"hello".to_vec(),
+		});
+		let ack = host
+			.on_recv_packet(&ctx, &mut ModuleOutputBuilder::new(), &mut packet(1, remark), &relayer)
+			.unwrap();
+		let ack = serde_json::from_slice::<Ics20Acknowledgement>(ack.as_ref()).unwrap();
+		assert!(ack.is_successful());
+		assert!(System::events().iter().any(|record| matches!(
+			&record.event,
+			RuntimeEvent::System(frame_system::Event::Remarked { sender, .. }) if sender == &account
+		)));
+
+		// calls that are not allowed are rejected with an error acknowledgement
+		let remark = RuntimeCall::System(frame_system::Call::remark { remark: b"hello".to_vec() });
+		let ack = host
+			.on_recv_packet(&ctx, &mut ModuleOutputBuilder::new(), &mut packet(2, remark), &relayer)
+			.unwrap();
+		let ack = serde_json::from_slice::<Ics20Acknowledgement>(ack.as_ref()).unwrap();
+		assert!(!ack.is_successful());
+
+		// packets are only accepted on the active channel of the account
+		let remark = RuntimeCall::System(frame_system::Call::remark_with_event {
+			remark: b"hello".to_vec(),
+		});
+		let mut other_channel_packet = packet(3, remark.clone());
+		other_channel_packet.destination_channel = ChannelId::new(2);
+		assert!(host
+			.on_recv_packet(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&mut other_channel_packet,
+				&relayer
+			)
+			.is_err());
+
+		// transactions heavier than the configured limit are rejected
+		let max_weight = IcaMaxHostTxWeight::get();
+		IcaMaxHostTxWeight::set(Weight::from_parts(1, u64::MAX));
+		let ack = host
+			.on_recv_packet(&ctx, &mut ModuleOutputBuilder::new(), &mut packet(3, remark), &relayer)
+			.unwrap();
+		IcaMaxHostTxWeight::set(max_weight);
+		let ack = serde_json::from_slice::<Ics20Acknowledgement>(ack.as_ref()).unwrap();
+		assert!(!ack.is_successful());
+	})
+}
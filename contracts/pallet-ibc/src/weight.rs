@@ -1,5 +1,7 @@
 use super::*;
-use crate::{light_clients::AnyClientMessage, routing::Context};
+use crate::{
+	client::MAX_PRUNED_CONSENSUS_STATES, light_clients::AnyClientMessage, routing::Context,
+};
 use core::marker::PhantomData;
 use frame_support::{pallet_prelude::Weight, weights::constants::WEIGHT_REF_TIME_PER_MILLIS};
 use grandpa_client_primitives::justification::GrandpaJustification;
@@ -7,7 +9,7 @@ use ibc::core::{
 	ics02_client::msgs::ClientMsg,
 	ics03_connection::{context::ConnectionReader, msgs::ConnectionMsg},
 	ics04_channel::msgs::{ChannelMsg, PacketMsg},
-	ics24_host::identifier::ClientId,
+	ics24_host::identifier::{ChannelId, ClientId, PortId},
 	ics26_routing::msgs::Ics26Envelope,
 };
 use ibc_primitives::{client_id_from_bytes, CallbackWeight};
@@ -42,6 +44,7 @@ pub trait WeightInfo {
 	fn on_timeout_packet() -> Weight;
 	fn update_grandpa_client(i: u32, j: u32) -> Weight;
 	fn packet_cleanup(i: u32) -> Weight;
+	fn prune_consensus_states(i: u32) -> Weight;
 }
 
 impl WeightInfo for () {
@@ -152,12 +155,20 @@ impl WeightInfo for () {
 	fn packet_cleanup(_i: u32) -> Weight {
 		Weight::default()
 	}
+
+	fn prune_consensus_states(_i: u32) -> Weight {
+		Weight::default()
+	}
 }
 
 pub struct WeightRouter<T: Config>(PhantomData<T>);
 
 impl<T: Config> WeightRouter<T> {
 	pub fn get_weight(port_id: &str) -> Option<Box<dyn CallbackWeight>> {
+		// check if the runtime defined weights for its custom routes
+		if let Some(weight) = T::Router::callback_weight(port_id) {
+			return Some(weight)
+		}
 		match port_id {
 			#[allow(clippy::box_default)]
 			ibc::applications::transfer::PORT_ID_STR => Some(Box::new(ics20::WeightHandler::<T>::default())),
@@ -180,6 +191,26 @@ pub fn channel_client<T: Config>(channel_id: &[u8], port_id: &[u8]) -> Result<Cl
 	Err(Error::<T>::Other)
 }
 
+/// Returns `weight` if the channel is verified by a tendermint client, the only client type the
+/// channel handshake is benchmarked for.
+fn channel_lc_verification_weight<T: Config>(
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	weight: Weight,
+) -> Weight {
+	match channel_client::<T>(port_id.as_bytes(), channel_id.to_string().as_bytes()) {
+		Ok(client_id) => {
+			let client_type =
+				client_id.as_str().rsplit_once('-').map(|(client_type_str, ..)| client_type_str);
+			match client_type {
+				Some(ty) if ty.contains("tendermint") => weight,
+				_ => Weight::default(),
+			}
+		},
+		Err(_) => Weight::default(),
+	}
+}
+
 pub(crate) fn deliver<T: Config + Send + Sync>(msgs: &[Any]) -> Weight
 where
 	u32: From<<T as frame_system::Config>::BlockNumber>,
@@ -194,9 +225,14 @@ where
 		.fold(Weight::default(), |acc, msg| {
 			// Add benchmarked weight for that message type
 			// Add benchmarked weight for module callback
+			// Every client message stores a client state, which may prune expired consensus
+			// states
+			let prune_weight =
+				<T as Config>::WeightInfo::prune_consensus_states(MAX_PRUNED_CONSENSUS_STATES);
 			let temp = match msg {
 				Ics26Envelope::Ics2Msg(msgs) => match msgs {
-					ClientMsg::CreateClient(_) => <T as Config>::WeightInfo::create_client(),
+					ClientMsg::CreateClient(_) =>
+						<T as Config>::WeightInfo::create_client().saturating_add(prune_weight),
 					ClientMsg::UpdateClient(msg) => {
 						let client_type = msg
 							.client_id
@@ -256,8 +292,9 @@ where
 							},
 							_ => Weight::default(),
 						}
+						.saturating_add(prune_weight)
 					},
-					ClientMsg::UpgradeClient(_) => Weight::default(),
+					ClientMsg::UpgradeClient(_) => prune_weight,
 				},
 				Ics26Envelope::Ics3Msg(msgs) => match msgs {
 					ConnectionMsg::ConnectionOpenInit(_) =>
@@ -453,6 +490,90 @@ where
 						};
 						cb_weight.saturating_add(lc_verification_weight)
 					},
+					// The upgrade handshake steps are weighed like the opening handshake steps
+					// that verify the same proofs and write the same channel state.
+					ChannelMsg::ChannelUpgradeInit(channel_msg) => {
+						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+							.unwrap_or_else(|| Box::new(()));
+						let cb_weight = cb.on_chan_open_init();
+						let lc_verification_weight = channel_lc_verification_weight::<T>(
+							&channel_msg.port_id,
+							&channel_msg.channel_id,
+							<T as Config>::WeightInfo::channel_open_init(),
+						);
+						cb_weight.saturating_add(lc_verification_weight)
+					},
+					ChannelMsg::ChannelUpgradeTry(channel_msg) => {
+						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+							.unwrap_or_else(|| Box::new(()));
+						let cb_weight = cb.on_chan_open_try();
+						let lc_verification_weight = channel_lc_verification_weight::<T>(
+							&channel_msg.port_id,
+							&channel_msg.channel_id,
+							<T as Config>::WeightInfo::channel_open_try_tendermint(),
+						);
+						cb_weight.saturating_add(lc_verification_weight)
+					},
+					ChannelMsg::ChannelUpgradeAck(channel_msg) => {
+						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+							.unwrap_or_else(|| Box::new(()));
+						let cb_weight =
+							cb.on_chan_open_ack(&channel_msg.port_id, &channel_msg.channel_id);
+						let lc_verification_weight = channel_lc_verification_weight::<T>(
+							&channel_msg.port_id,
+							&channel_msg.channel_id,
+							<T as Config>::WeightInfo::channel_open_ack_tendermint(),
+						);
+						cb_weight.saturating_add(lc_verification_weight)
+					},
+					ChannelMsg::ChannelUpgradeConfirm(channel_msg) => {
+						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+							.unwrap_or_else(|| Box::new(()));
+						let cb_weight =
+							cb.on_chan_open_confirm(&channel_msg.port_id, &channel_msg.channel_id);
+						let lc_verification_weight = channel_lc_verification_weight::<T>(
+							&channel_msg.port_id,
+							&channel_msg.channel_id,
+							<T as Config>::WeightInfo::channel_open_ack_tendermint(),
+						);
+						cb_weight.saturating_add(lc_verification_weight)
+					},
+					ChannelMsg::ChannelUpgradeOpen(channel_msg) => {
+						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+							.unwrap_or_else(|| Box::new(()));
+						let cb_weight =
+							cb.on_chan_open_confirm(&channel_msg.port_id, &channel_msg.channel_id);
+						let lc_verification_weight = channel_lc_verification_weight::<T>(
+							&channel_msg.port_id,
+							&channel_msg.channel_id,
+							<T as Config>::WeightInfo::channel_open_confirm_tendermint(),
+						);
+						cb_weight.saturating_add(lc_verification_weight)
+					},
+					ChannelMsg::ChannelUpgradeTimeout(channel_msg) => {
+						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+							.unwrap_or_else(|| Box::new(()));
+						let cb_weight =
+							cb.on_chan_close_confirm(&channel_msg.port_id, &channel_msg.channel_id);
+						let lc_verification_weight = channel_lc_verification_weight::<T>(
+							&channel_msg.port_id,
+							&channel_msg.channel_id,
+							<T as Config>::WeightInfo::channel_close_confirm_tendermint(),
+						);
+						cb_weight.saturating_add(lc_verification_weight)
+					},
+					ChannelMsg::ChannelUpgradeCancel(channel_msg) => {
+						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+							.unwrap_or_else(|| Box::new(()));
+						let cb_weight =
+							cb.on_chan_close_confirm(&channel_msg.port_id, &channel_msg.channel_id);
+						let lc_verification_weight = channel_lc_verification_weight::<T>(
+							&channel_msg.port_id,
+							&channel_msg.channel_id,
+							<T as Config>::WeightInfo::channel_close_confirm_tendermint(),
+						);
+						cb_weight.saturating_add(lc_verification_weight)
+					},
 				},
 				Ics26Envelope::Ics4PacketMsg(msgs) => match msgs {
 					PacketMsg::RecvPacket(packet_msg) => {
@@ -60,6 +60,8 @@ use ibc_proto::{
 	},
 };
 use ics08_wasm::Bytes;
+#[cfg(feature = "near")]
+use near::{Client as NearClient, ClientConfig as NearClientConfig};
 use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState};
 #[cfg(any(test, feature = "testing"))]
 use pallet_ibc::Timeout;
@@ -69,7 +71,7 @@ use primitives::{
 	MisbehaviourHandler, UpdateType,
 };
 use serde::{Deserialize, Serialize};
-use std::{pin::Pin, time::Duration};
+use std::{collections::BTreeMap, pin::Pin, time::Duration};
 use tendermint_proto::Protobuf;
 use thiserror::Error;
 
@@ -83,6 +85,69 @@ pub struct Config {
 #[derive(Serialize, Deserialize)]
 pub struct CoreConfig {
 	pub prometheus_endpoint: Option<String>,
+	/// Path to the relayer database. When set, the relayer state is persisted so that it can
+	/// resume after a restart.
+	pub store_path: Option<String>,
+}
+
+/// Configuration for relaying several paths from a single process. Each chain is connected to
+/// once and shared between all the paths that reference it.
+#[derive(Serialize, Deserialize)]
+pub struct MultiPathConfig {
+	/// Chain configurations keyed by a name that paths use to refer to them.
+	pub chains: BTreeMap<String, AnyConfig>,
+	/// Paths to relay between the configured chains.
+	pub paths: Vec<PathConfig>,
+	pub core: CoreConfig,
+}
+
+/// A path between two of the chains in a [`MultiPathConfig`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathConfig {
+	/// Name used for logging and metrics, defaults to `{chain_a}-{chain_b}`.
+	pub name: Option<String>,
+	pub chain_a: PathEnd,
+	pub chain_b: PathEnd,
+}
+
+impl PathConfig {
+	pub fn name(&self) -> String {
+		self.name
+			.clone()
+			.unwrap_or_else(|| format!("{}-{}", self.chain_a.chain, self.chain_b.chain))
+	}
+}
+
+/// Path specific state of one of the chains in a [`PathConfig`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathEnd {
+	/// Name of the chain in [`MultiPathConfig::chains`].
+	pub chain: String,
+	/// Light client id on counterparty chain
+	pub client_id: Option<ClientId>,
+	/// Connection Id
+	pub connection_id: Option<ConnectionId>,
+	/// Channels cleared for packet relay
+	#[serde(default)]
+	pub channel_whitelist: Vec<(ChannelId, PortId)>,
+}
+
+impl PathEnd {
+	/// Returns a fork of the shared chain client with this path's state applied.
+	pub fn into_chain(self, chains: &BTreeMap<String, AnyChain>) -> anyhow::Result<AnyChain> {
+		let mut chain = chains
+			.get(&self.chain)
+			.ok_or_else(|| anyhow::anyhow!("Chain {} is not configured", self.chain))?
+			.fork();
+		if let Some(client_id) = self.client_id {
+			chain.set_client_id(client_id);
+		}
+		if let Some(connection_id) = self.connection_id {
+			chain.set_connection_id(connection_id);
+		}
+		chain.set_channel_whitelist(self.channel_whitelist.into_iter().collect());
+		Ok(chain)
+	}
 }
 
 impl From<String> for AnyError {
@@ -91,6 +156,24 @@ impl From<String> for AnyError {
 	}
 }
 
+impl AnyChain {
+	/// Registers the ICS-29 counterparty payees of the chain's signers on its whitelisted
+	/// channels. Only Cosmos chains are supported, this is a no-op for the other chains.
+	pub async fn register_counterparty_payees(&self) -> anyhow::Result<()> {
+		let chain = match self {
+			Self::Wasm(chain) => chain.inner.as_ref(),
+			chain => chain,
+		};
+		match chain {
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.register_counterparty_payees().await.map_err(|e| {
+				anyhow::anyhow!("Failed to register counterparty payees on {}: {e:?}", chain.name)
+			}),
+			_ => Ok(()),
+		}
+	}
+}
+
 chains! {
 	Parachain(ParachainClientConfig, ParachainClient<DefaultConfig>),
 	// Dali(ParachainClientConfig, ParachainClient<DaliConfig>),
@@ -99,6 +182,8 @@ chains! {
 	PicassoKusama(ParachainClientConfig, ParachainClient<PicassoKusamaConfig>),
 	#[cfg(feature = "cosmos")]
 	Cosmos(CosmosClientConfig, CosmosClient<DefaultConfig>),
+	#[cfg(feature = "near")]
+	Near(NearClientConfig, NearClient),
 }
 
 fn wrap_any_msg_into_wasm(msg: Any, checksum: Bytes) -> Result<Any, anyhow::Error> {
@@ -13,19 +13,21 @@
 // limitations under the License.
 
 use crate::{
-	chain::{AnyConfig, Config, CoreConfig},
-	fish, relay, Mode,
+	chain::{AnyChain, AnyConfig, Config, CoreConfig, MultiPathConfig},
+	fish, relay, spawn_path, FinalityBroadcast, Mode,
 };
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use ibc::core::{ics04_channel::channel::Order, ics24_host::identifier::PortId};
 use metrics::{data::Metrics, handler::MetricsHandler, init_prometheus};
 use primitives::{
+	keys::{EncryptedKeystore, PasswordSource},
+	store::RelayerStore,
 	utils::{create_channel, create_clients, create_connection},
 	Chain, IbcProvider,
 };
 use prometheus::Registry;
-use std::{num::NonZeroU64, path::PathBuf, str::FromStr, time::Duration};
+use std::{collections::BTreeMap, num::NonZeroU64, path::PathBuf, str::FromStr, time::Duration};
 
 #[derive(Debug, Parser)]
 pub struct Cli {
@@ -38,6 +40,11 @@ pub struct Cli {
 pub enum Subcommand {
 	#[clap(name = "relay", about = "Start relaying messages between two chains")]
 	Relay(Cmd),
+	#[clap(
+		name = "relay-paths",
+		about = "Start relaying messages on multiple paths between chains"
+	)]
+	RelayPaths(MultiPathCmd),
 	#[clap(name = "upload-wasm", about = "Upload a WASM blob to the chain")]
 	UploadWasm(UploadWasmCmd),
 	#[clap(
@@ -51,6 +58,11 @@ pub enum Subcommand {
 	CreateConnection(Cmd),
 	#[clap(name = "create-channel", about = "Creates a channel on the specified port")]
 	CreateChannel(Cmd),
+	#[clap(
+		name = "encrypt-key",
+		about = "Encrypts a signing key read from stdin into a keystore file"
+	)]
+	EncryptKey(EncryptKeyCmd),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -84,6 +96,13 @@ pub struct Cmd {
 	pub out_config_b: Option<String>,
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct MultiPathCmd {
+	/// Relayer multi-path config path.
+	#[clap(long)]
+	config: String,
+}
+
 #[derive(Debug, Clone, Parser)]
 pub struct UploadWasmCmd {
 	/// Relayer chain config path.
@@ -97,6 +116,37 @@ pub struct UploadWasmCmd {
 	wasm_path: PathBuf,
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct EncryptKeyCmd {
+	/// Path of the keystore file to create.
+	#[clap(long)]
+	out: PathBuf,
+	/// Environment variable holding the keystore password.
+	#[clap(long)]
+	password_env: Option<String>,
+	/// File holding the keystore password.
+	#[clap(long)]
+	password_file: Option<PathBuf>,
+}
+
+impl EncryptKeyCmd {
+	/// Reads the secret (a secret URI or a mnemonic) from stdin and writes it to an encrypted
+	/// keystore, which chain configs can then reference through a `key_source`.
+	pub fn run(&self) -> Result<()> {
+		let password = match (&self.password_env, &self.password_file) {
+			(Some(var), None) => PasswordSource::PasswordEnv(var.clone()),
+			(None, Some(path)) => PasswordSource::PasswordFile(path.clone()),
+			_ =>
+				return Err(anyhow!("Exactly one of --password-env and --password-file is required")),
+		}
+		.read()?;
+		let mut secret = String::new();
+		std::io::stdin().read_line(&mut secret)?;
+		EncryptedKeystore::encrypt(secret.trim(), &password)?.write(&self.out)?;
+		Ok(())
+	}
+}
+
 impl UploadWasmCmd {
 	pub async fn run(&self) -> Result<AnyConfig> {
 		use tokio::fs::read_to_string;
@@ -118,6 +168,84 @@ impl UploadWasmCmd {
 	}
 }
 
+impl MultiPathCmd {
+	async fn parse_config(&self) -> Result<MultiPathConfig> {
+		use tokio::fs::read_to_string;
+		let path: PathBuf = self.config.parse()?;
+		let file_content = read_to_string(path).await?;
+		Ok(toml::from_str(&file_content)?)
+	}
+
+	/// Run the relayer on every configured path. Each chain is connected to once and its client
+	/// and finality notifications are shared by all the paths using it.
+	pub async fn run(&self) -> Result<()> {
+		let config = self.parse_config().await?;
+		if config.paths.is_empty() {
+			return Err(anyhow!("No paths configured"))
+		}
+
+		let mut chains = BTreeMap::new();
+		let mut finality = BTreeMap::new();
+		for (name, chain_config) in config.chains {
+			let chain = chain_config.into_client().await?;
+			finality.insert(name.clone(), FinalityBroadcast::spawn(chain.clone()));
+			chains.insert(name, chain);
+		}
+
+		let store = config.core.store_path.as_ref().map(RelayerStore::open).transpose()?;
+		let registry =
+			Registry::new_custom(None, None).expect("this can only fail if the prefix is empty");
+		let mut handles = Vec::with_capacity(config.paths.len());
+		for path in config.paths {
+			let path_name = path.name();
+			let finality_a = finality
+				.get(&path.chain_a.chain)
+				.ok_or_else(|| anyhow!("Chain {} is not configured", path.chain_a.chain))?;
+			let finality_b = finality
+				.get(&path.chain_b.chain)
+				.ok_or_else(|| anyhow!("Chain {} is not configured", path.chain_b.chain))?;
+			let mut chain_a = path.chain_a.into_chain(&chains)?;
+			let mut chain_b = path.chain_b.into_chain(&chains)?;
+			register_counterparty_payees(&chain_a).await;
+			register_counterparty_payees(&chain_b).await;
+			if let Some(store) = &store {
+				attach_store(store, &path_name, &mut chain_a);
+				attach_store(store, &path_name, &mut chain_b);
+			}
+
+			handles
+				.push(spawn_path(path_name, chain_a, chain_b, finality_a, finality_b, &registry)?);
+		}
+
+		if let Some(addr) = config.core.prometheus_endpoint.and_then(|s| s.parse().ok()) {
+			tokio::spawn(init_prometheus(addr, registry.clone()));
+		}
+
+		// stop as soon as any of the paths fails
+		futures::future::try_join_all(
+			handles
+				.into_iter()
+				.map(|handle| async move { handle.await.map_err(|e| anyhow!(e))? }),
+		)
+		.await?;
+
+		Ok(())
+	}
+}
+
+/// Persists the state of the chain on the given path in the relayer store.
+fn attach_store(store: &RelayerStore, path_name: &str, chain: &mut AnyChain) {
+	let path_store = store.path_store(path_name, chain.name());
+	chain.common_state_mut().store = Some(path_store);
+}
+
+/// Relayer fees are optional, failing to register the payees shouldn't stop the relayer.
+async fn register_counterparty_payees(chain: &AnyChain) {
+	if let Err(e) = chain.register_counterparty_payees().await {
+		log::error!("{e}");
+	}
+}
+
 impl Cmd {
 	async fn parse_config(&self) -> Result<Config> {
 		use tokio::fs::read_to_string;
@@ -138,8 +266,17 @@ impl Cmd {
 	/// Run the command
 	pub async fn run(&self) -> Result<()> {
 		let config = self.parse_config().await?;
-		let chain_a = config.chain_a.into_client().await?;
-		let chain_b = config.chain_b.into_client().await?;
+		let mut chain_a = config.chain_a.into_client().await?;
+		let mut chain_b = config.chain_b.into_client().await?;
+		register_counterparty_payees(&chain_a).await;
+		register_counterparty_payees(&chain_b).await;
+
+		if let Some(path) = &config.core.store_path {
+			let store = RelayerStore::open(path)?;
+			let path_name = format!("{}-{}", chain_a.name(), chain_b.name());
+			attach_store(&store, &path_name, &mut chain_a);
+			attach_store(&store, &path_name, &mut chain_b);
+		}
 
 		let registry =
 			Registry::new_custom(None, None).expect("this can only fail if the prefix is empty");
@@ -233,7 +370,7 @@ impl Cmd {
 			.as_ref()
 			.expect("version must be specified when creating a channel")
 			.clone();
-		let order = self.order.as_ref().expect("order must be specified when creating a channel, expected one of 'ordered' or 'unordered'").as_str();
+		let order = self.order.as_ref().expect("order must be specified when creating a channel, expected one of 'ordered', 'unordered' or 'ordered_allow_timeout'").as_str();
 		let mut config = self.parse_config().await?;
 		let mut chain_a = config.chain_a.clone().into_client().await?;
 		let mut chain_b = config.chain_b.clone().into_client().await?;
@@ -246,7 +383,8 @@ impl Cmd {
 				.unwrap();
 		});
 
-		let order = Order::from_str(order).expect("Expected one of 'ordered' or 'unordered'");
+		let order = Order::from_str(order)
+			.expect("Expected one of 'ordered', 'unordered' or 'ordered_allow_timeout'");
 		let connection_id = chain_a.connection_id().expect("Connection id should be defined");
 		let (channel_id_a, channel_id_b) = create_channel(
 			&mut chain_a,
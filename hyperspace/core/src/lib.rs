@@ -24,15 +24,30 @@ pub mod queue;
 pub mod substrate;
 mod utils;
 
-use crate::utils::RecentStream;
+pub use crate::utils::{FinalityBroadcast, RecentStream};
 use anyhow::anyhow;
 use events::{has_packet_events, parse_events};
 use futures::{future::ready, StreamExt, TryFutureExt};
-use ibc::{events::IbcEvent, Height};
+use ibc::{
+	core::ics02_client::{
+		client_consensus::ConsensusState as ConsensusStateT,
+		client_state::ClientState as ClientStateT, msgs::update_client::MsgUpdateAnyClient,
+	},
+	events::IbcEvent,
+	timestamp::Timestamp,
+	tx_msg::Msg,
+	Height,
+};
 use ibc_proto::google::protobuf::Any;
-use metrics::handler::MetricsHandler;
-use primitives::{Chain, IbcProvider, UndeliveredType, UpdateType};
-use std::collections::HashSet;
+use metrics::{data::Metrics, handler::MetricsHandler, Registry};
+use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState};
+use primitives::{mock::LocalClientTypes, Chain, IbcProvider, UndeliveredType, UpdateType};
+use std::{collections::HashSet, time::Duration};
+use tokio::task::JoinHandle;
+
+/// Interval at which the signer balances and client expiries are reported. Reporting them takes
+/// several queries to both chains, so it isn't done on every finality event.
+const CHAIN_METRICS_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Copy, Debug, Clone)]
 pub enum Mode {
@@ -43,8 +58,29 @@ pub enum Mode {
 /// Core relayer loop, waits for new finality events and forwards any new [`ibc::IbcEvents`]
 /// to the counter party chain.
 pub async fn relay<A, B>(
+	chain_a: A,
+	chain_b: B,
+	chain_a_metrics: Option<MetricsHandler>,
+	chain_b_metrics: Option<MetricsHandler>,
+	mode: Option<Mode>,
+) -> Result<(), anyhow::Error>
+where
+	A: Chain,
+	B: Chain,
+{
+	let stream_a = RecentStream::new(chain_a.finality_notifications().await?);
+	let stream_b = RecentStream::new(chain_b.finality_notifications().await?);
+	relay_with_streams(chain_a, chain_b, stream_a, stream_b, chain_a_metrics, chain_b_metrics, mode)
+		.await
+}
+
+/// Same as [`relay`], but uses the provided finality streams instead of subscribing to the
+/// chains. This allows a single finality stream to be shared between several relay paths.
+pub async fn relay_with_streams<A, B>(
 	mut chain_a: A,
 	mut chain_b: B,
+	mut chain_a_finality: RecentStream<A::FinalityEvent>,
+	mut chain_b_finality: RecentStream<B::FinalityEvent>,
 	mut chain_a_metrics: Option<MetricsHandler>,
 	mut chain_b_metrics: Option<MetricsHandler>,
 	mode: Option<Mode>,
@@ -53,17 +89,30 @@ where
 	A: Chain,
 	B: Chain,
 {
-	let stream_a = RecentStream::new(chain_a.finality_notifications().await?);
-	let stream_b = RecentStream::new(chain_b.finality_notifications().await?);
-	let (mut chain_a_finality, mut chain_b_finality) = (stream_a, stream_b);
+	restore_state(&chain_a).await?;
+	restore_state(&chain_b).await?;
+	// replay the misbehaviour reports that weren't submitted before a restart
+	for result in [
+		submit_misbehaviour_messages(&chain_a, &chain_b).await,
+		submit_misbehaviour_messages(&chain_b, &chain_a).await,
+	] {
+		if let Err(e) = result {
+			log::error!("{e}");
+		}
+	}
 
 	// Introduce altering between branches so that each branch gets a chance to execute first after
 	// another one
 	let mut first_executed = false;
+	let mut chain_metrics_interval = tokio::time::interval(CHAIN_METRICS_INTERVAL);
 
 	// loop forever
 	loop {
 		tokio::select! {
+			_ = chain_metrics_interval.tick() => {
+				report_chain_metrics(&chain_a, &chain_b, &chain_a_metrics).await;
+				report_chain_metrics(&chain_b, &chain_a, &chain_b_metrics).await;
+			}
 			// new finality event from chain A
 			result = chain_a_finality.next(), if !first_executed => {
 				first_executed = true;
@@ -81,6 +130,114 @@ where
 	}
 }
 
+/// Starts relaying a single path of a multi-path relayer. The finality notifications of both
+/// chains are received from the given broadcasts, which are shared with the other paths, and the
+/// metrics of both chains are registered labelled with the path's name.
+pub fn spawn_path<A, B>(
+	path_name: String,
+	chain_a: A,
+	chain_b: B,
+	finality_a: &FinalityBroadcast<A::FinalityEvent>,
+	finality_b: &FinalityBroadcast<B::FinalityEvent>,
+	registry: &Registry,
+) -> anyhow::Result<JoinHandle<anyhow::Result<()>>>
+where
+	A: Chain,
+	A::FinalityEvent: Sync,
+	B: Chain,
+	B::FinalityEvent: Sync,
+{
+	let metrics_a = Metrics::register_for_path(chain_a.name(), &path_name, registry)?;
+	let metrics_b = Metrics::register_for_path(chain_b.name(), &path_name, registry)?;
+	let mut metrics_handler_a = MetricsHandler::new(registry.clone(), metrics_a);
+	let mut metrics_handler_b = MetricsHandler::new(registry.clone(), metrics_b);
+	metrics_handler_a.link_with_counterparty(&mut metrics_handler_b);
+
+	let stream_a = finality_a.subscribe();
+	let stream_b = finality_b.subscribe();
+	log::info!("Starting relayer on path {path_name}");
+	Ok(tokio::spawn(async move {
+		relay_with_streams(
+			chain_a,
+			chain_b,
+			stream_a,
+			stream_b,
+			Some(metrics_handler_a),
+			Some(metrics_handler_b),
+			None,
+		)
+		.await
+		.map_err(|e| anyhow!("Relayer on path {path_name} failed: {e}"))
+	}))
+}
+
+/// Restores the relayer state persisted before a restart, if the store is enabled.
+async fn restore_state<C: Chain>(chain: &C) -> anyhow::Result<()> {
+	let common_state = chain.common_state();
+	let Some(store) = &common_state.store else { return Ok(()) };
+	common_state.restore_from_store().await?;
+	if let Some(height) = store.last_finality_height()? {
+		log::info!(
+			"Resuming {} from last processed finality height {height}, events up to it were already relayed",
+			chain.name()
+		);
+	}
+	Ok(())
+}
+
+/// Submits the misbehaviour reports queued on `source` for its client on `sink`. The reports are
+/// queued again if the submission fails, so that they are retried later, also after a restart.
+async fn submit_misbehaviour_messages<A: Chain, B: Chain>(
+	source: &A,
+	sink: &B,
+) -> anyhow::Result<()> {
+	let common_state = source.common_state();
+	let messages = common_state.take_misbehaviour_messages().await?;
+	if messages.is_empty() {
+		return Ok(())
+	}
+	let msgs = messages
+		.iter()
+		.cloned()
+		.map(|message| {
+			MsgUpdateAnyClient::<LocalClientTypes>::new(
+				source.client_id(),
+				message,
+				sink.account_id(),
+			)
+			.to_any()
+		})
+		.collect();
+	if let Err(e) = sink.submit(msgs).await {
+		for message in messages {
+			common_state.queue_misbehaviour_message(message).await?;
+		}
+		return Err(anyhow!("Failed to submit misbehaviour report to {}: {e:?}", sink.name()))
+	}
+	log::info!("Submitted misbehaviour report of {} to {}", source.name(), sink.name());
+	Ok(())
+}
+
+/// Drops the events at heights that were already processed before, see
+/// [`primitives::store::PathStore::last_finality_height`]. The client updates are kept, as they may
+/// still be required by the counterparty.
+fn skip_processed_events(
+	updates: &mut [(Any, Height, Vec<IbcEvent>, UpdateType)],
+	last_processed_height: Option<Height>,
+) {
+	let Some(last_processed_height) = last_processed_height else { return };
+	for (_, height, events, _) in updates.iter_mut() {
+		if *height <= last_processed_height && !events.is_empty() {
+			log::debug!(
+				target: "hyperspace",
+				"Skipping {} events at {height}, they were already relayed",
+				events.len()
+			);
+			events.clear();
+		}
+	}
+}
+
 pub async fn fish<A, B>(chain_a: A, chain_b: B) -> Result<(), anyhow::Error>
 where
 	A: Chain,
@@ -121,6 +278,7 @@ where
 				}
 				let message = chain_a.query_client_message(update).await.map_err(|e| { log::info!("error: {}", e); e })?;
 				chain_b.check_for_misbehaviour(&chain_a, message).await.map_err(|e| { log::info!("error: {}", e); e })?;
+				submit_misbehaviour_messages(&chain_b, &chain_a).await?;
 			}
 			// new finality event from chain B
 			update = chain_b_client_updates.next() => {
@@ -134,6 +292,7 @@ where
 				}
 				let message = chain_b.query_client_message(update).await.map_err(|e| { log::info!("error: {}", e); e })?;
 				chain_a.check_for_misbehaviour(&chain_b, message).await.map_err(|e| { log::info!("error: {}", e); e })?;
+				submit_misbehaviour_messages(&chain_a, &chain_b).await?;
 			}
 		}
 	}
@@ -211,10 +370,18 @@ async fn process_some_finality_event<A: Chain, B: Chain>(
 	mode: Option<Mode>,
 	finality_event: <A as IbcProvider>::FinalityEvent,
 ) -> anyhow::Result<()> {
-	let updates = source
+	let mut updates = source
 		.query_latest_ibc_events(finality_event, &*sink)
 		.await
 		.map_err(|e| anyhow!("Failed to fetch IBC events for finality event {e}"))?;
+	for (_, _, events, _) in &updates {
+		queue::confirm_in_flight_packets(&*source, events)?;
+	}
+	let last_processed_height = match &source.common_state().store {
+		Some(store) => store.last_finality_height()?,
+		None => None,
+	};
+	skip_processed_events(&mut updates, last_processed_height);
 	log::trace!(target: "hyperspace", "Received updates count: {}", updates.len());
 	// query packets that can now be sent, at this sink height because of connection
 	// delay.
@@ -247,15 +414,79 @@ async fn process_some_finality_event<A: Chain, B: Chain>(
 		timeout_msgs.len()
 	);
 
+	let latest_update_height = updates.iter().map(|(_, height, ..)| *height).max();
 	process_updates(source, sink, metrics, mode, updates, &mut msgs).await?;
 
 	msgs.extend(ready_packets);
 
+	if let Err(e) = submit_misbehaviour_messages(&*source, &*sink).await {
+		log::error!("{e}");
+	}
 	process_messages(sink, metrics, msgs).await?;
 	process_timeouts(source, metrics, timeout_msgs).await?;
+
+	if let (Some(store), Some(height)) = (&source.common_state().store, latest_update_height) {
+		store.set_last_finality_height(height)?;
+	}
 	Ok(())
 }
 
+/// Reports the balance of the relayer's signer on `source`, and the time left until the client
+/// of `source` on `sink` expires.
+async fn report_chain_metrics<A: Chain, B: Chain>(
+	source: &A,
+	sink: &B,
+	metrics: &Option<MetricsHandler>,
+) {
+	let Some(metrics) = metrics.as_ref() else { return };
+	if let Err(e) = query_chain_metrics(source, sink, metrics).await {
+		log::error!("Failed to report chain metrics for {} {:?}", source.name(), e);
+	}
+}
+
+async fn query_chain_metrics<A: Chain, B: Chain>(
+	source: &A,
+	sink: &B,
+	metrics: &MetricsHandler,
+) -> anyhow::Result<()> {
+	if let Some(balance) = source.query_signer_balance().await? {
+		metrics.handle_signer_balance(balance);
+	}
+
+	let client_id = source.client_id();
+	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
+	let client_state = sink
+		.query_client_state(sink_height, client_id.clone())
+		.await?
+		.client_state
+		.and_then(|client_state| AnyClientState::decode_recursive(client_state, |_| true))
+		.ok_or_else(|| anyhow!("Invalid client state for {client_id} on {}", sink.name()))?;
+	let Some(trusting_period) = client_state.trusting_period() else { return Ok(()) };
+
+	let consensus_state = sink
+		.query_client_consensus(sink_height, client_id.clone(), client_state.latest_height())
+		.await?
+		.consensus_state
+		.and_then(|consensus_state| AnyConsensusState::try_from(consensus_state).ok())
+		.ok_or_else(|| anyhow!("Invalid consensus state for {client_id} on {}", sink.name()))?;
+	metrics.handle_client_expiry(
+		&client_id,
+		seconds_until_expiry(consensus_state.timestamp(), trusting_period, sink_timestamp),
+	);
+	Ok(())
+}
+
+/// Seconds left at `now` until a client whose latest consensus state was produced at
+/// `consensus_timestamp` expires, negative once it has expired.
+fn seconds_until_expiry(
+	consensus_timestamp: Timestamp,
+	trusting_period: Duration,
+	now: Timestamp,
+) -> i64 {
+	let expires_at = consensus_timestamp.nanoseconds() as i128 + trusting_period.as_nanos() as i128;
+	((expires_at - now.nanoseconds() as i128) / 1_000_000_000) as i64
+}
+
 async fn process_updates<A: Chain, B: Chain>(
 	source: &mut A,
 	sink: &mut B,
@@ -418,3 +649,170 @@ pub mod send_packet_relay {
 		RELAY_PACKETS.store(status, Ordering::SeqCst);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::{
+		applications::transfer::{msgs::transfer::MsgTransfer, Amount, PrefixedCoin, VERSION},
+		core::{
+			ics04_channel::channel::Order,
+			ics24_host::identifier::{ChainId, ChannelId, PortId},
+		},
+	};
+	use primitives::{
+		mock::chain::{MockChain, MockChainConfig},
+		utils::{create_channel, create_clients, create_connection},
+		KeyProvider, TestProvider,
+	};
+	use std::str::FromStr;
+
+	async fn mock_chain(name: &str) -> MockChain {
+		let config = MockChainConfig {
+			name: name.to_string(),
+			chain_id: ChainId::new(name.to_string(), 1),
+			..Default::default()
+		};
+		MockChain::new(config).await.unwrap()
+	}
+
+	/// Opens a transfer channel between the chains and returns its id on `chain_a`.
+	async fn open_transfer_channel(chain_a: &mut MockChain, chain_b: &mut MockChain) -> ChannelId {
+		let (client_b, client_a) = create_clients(chain_b, chain_a).await.unwrap();
+		chain_a.set_client_id(client_a);
+		chain_b.set_client_id(client_b);
+
+		let handle = tokio::spawn(relay(chain_a.clone(), chain_b.clone(), None, None, None));
+		let (connection_a, connection_b) =
+			create_connection(chain_a, chain_b, Duration::ZERO).await.unwrap();
+		let (channel_a, channel_b) = create_channel(
+			chain_a,
+			chain_b,
+			connection_a.clone(),
+			PortId::transfer(),
+			VERSION.to_string(),
+			Order::Unordered,
+		)
+		.await
+		.unwrap();
+		handle.abort();
+
+		chain_a.set_connection_id(connection_a);
+		chain_b.set_connection_id(connection_b);
+		chain_a.set_channel_whitelist([(channel_a, PortId::transfer())].into_iter().collect());
+		chain_b.set_channel_whitelist([(channel_b, PortId::transfer())].into_iter().collect());
+		channel_a
+	}
+
+	async fn send_transfer(chain: &MockChain, counterparty: &MockChain, channel: ChannelId) {
+		let (mut timeout_height, timestamp) =
+			counterparty.latest_height_and_timestamp().await.unwrap();
+		timeout_height.revision_height += 200;
+		let msg = MsgTransfer {
+			source_port: PortId::transfer(),
+			source_channel: channel,
+			token: PrefixedCoin {
+				denom: chain.denom().parse().unwrap(),
+				amount: Amount::from_str("1000").unwrap(),
+			},
+			sender: chain.account_id(),
+			receiver: counterparty.account_id(),
+			timeout_height,
+			timeout_timestamp: (timestamp + Duration::from_secs(60 * 60)).unwrap(),
+			memo: String::new(),
+			additional_tokens: vec![],
+			forwarding: None,
+		};
+		chain.send_transfer(msg).await.unwrap();
+	}
+
+	/// Values of the `path` label of the metrics in the given family.
+	fn path_labels(registry: &Registry, family: &str) -> HashSet<String> {
+		registry
+			.gather()
+			.iter()
+			.filter(|metric_family| metric_family.get_name() == family)
+			.flat_map(|metric_family| metric_family.get_metric())
+			.flat_map(|metric| metric.get_label())
+			.filter(|label| label.get_name() == "path")
+			.map(|label| label.get_value().to_string())
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn relays_paths_sharing_a_chain() {
+		let chain_a = mock_chain("mock_a").await;
+		let mut chain_b = mock_chain("mock_b").await;
+		let mut chain_c = mock_chain("mock_c").await;
+		// chain A is shared by both paths, each of them relays through its own fork
+		let mut chain_a_b = chain_a.fork();
+		let mut chain_a_c = chain_a.fork();
+		let channel_a_b = open_transfer_channel(&mut chain_a_b, &mut chain_b).await;
+		let channel_a_c = open_transfer_channel(&mut chain_a_c, &mut chain_c).await;
+
+		let registry = Registry::new();
+		let finality_a = FinalityBroadcast::spawn(chain_a.clone());
+		let finality_b = FinalityBroadcast::spawn(chain_b.clone());
+		let finality_c = FinalityBroadcast::spawn(chain_c.clone());
+		let handles = [
+			spawn_path(
+				"a-b".to_string(),
+				chain_a_b,
+				chain_b.clone(),
+				&finality_a,
+				&finality_b,
+				&registry,
+			)
+			.unwrap(),
+			spawn_path(
+				"a-c".to_string(),
+				chain_a_c,
+				chain_c.clone(),
+				&finality_a,
+				&finality_c,
+				&registry,
+			)
+			.unwrap(),
+		];
+
+		let mut events = chain_a.ibc_events().await;
+		send_transfer(&chain_a, &chain_b, channel_a_b).await;
+		send_transfer(&chain_a, &chain_c, channel_a_c).await;
+
+		let mut acknowledged = HashSet::new();
+		tokio::time::timeout(Duration::from_secs(120), async {
+			while acknowledged.len() < 2 {
+				if let Some(IbcEvent::AcknowledgePacket(ack)) = events.next().await {
+					acknowledged.insert(ack.packet.source_channel);
+				}
+			}
+		})
+		.await
+		.expect("packets weren't acknowledged on both paths");
+		assert_eq!(acknowledged, HashSet::from([channel_a_b, channel_a_c]));
+
+		// the metrics of chain A are reported once per path
+		let paths = HashSet::from(["a-b".to_string(), "a-c".to_string()]);
+		assert_eq!(path_labels(&registry, "hyperspace_number_of_sent_packets"), paths);
+		assert_eq!(path_labels(&registry, "hyperspace_sent_packets_per_channel"), paths);
+
+		for handle in handles {
+			handle.abort();
+		}
+	}
+
+	#[test]
+	fn client_expiry_counts_down_from_the_latest_consensus_state() {
+		let consensus_timestamp = Timestamp::from_nanoseconds(1_000_000_000_000).unwrap();
+		let trusting_period = Duration::from_secs(60 * 60);
+		let at = |seconds: u64| {
+			Timestamp::from_nanoseconds(1_000_000_000_000 + seconds * 1_000_000_000).unwrap()
+		};
+
+		assert_eq!(seconds_until_expiry(consensus_timestamp, trusting_period, at(0)), 3600);
+		assert_eq!(seconds_until_expiry(consensus_timestamp, trusting_period, at(1800)), 1800);
+		assert_eq!(seconds_until_expiry(consensus_timestamp, trusting_period, at(3600)), 0);
+		// expired clients report how long ago they expired
+		assert_eq!(seconds_until_expiry(consensus_timestamp, trusting_period, at(4000)), -400);
+	}
+}
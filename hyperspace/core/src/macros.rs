@@ -37,7 +37,7 @@ macro_rules! chains {
 			Wasm(WasmChain),
 		}
 
-		#[derive(Debug)]
+		#[derive(Debug, Clone)]
 		pub enum AnyFinalityEvent {
 			$(
 				$(#[$($meta)*])*
@@ -815,6 +815,27 @@ macro_rules! chains {
 				}
 			}
 
+			async fn submit_chunks(&self, chunks: Vec<Vec<Any>>) -> Result<(), Self::Error> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain.submit_chunks(chunks).await.map_err(AnyError::$name),
+					)*
+					Self::Wasm(chain) => {
+						let chunks = chunks
+							.into_iter()
+							.map(|messages| {
+								messages
+									.into_iter()
+									.map(|msg| wrap_any_msg_into_wasm(msg, chain.checksum.clone()))
+									.collect::<Result<Vec<_>, _>>()
+							})
+							.collect::<Result<Vec<_>, _>>()?;
+						chain.inner.submit_chunks(chunks).await.map_err(AnyError::into)
+					},
+				}
+			}
+
 			async fn query_client_message(
 				&self,
 				update: UpdateClient,
@@ -838,6 +859,16 @@ macro_rules! chains {
 				}
 			}
 
+			async fn query_signer_balance(&self) -> Result<Option<u128>, Self::Error> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain.query_signer_balance().await.map_err(AnyError::$name),
+					)*
+					Self::Wasm(c) => c.inner.query_signer_balance().await,
+				}
+			}
+
 			async fn handle_error(&mut self, e: &anyhow::Error) -> std::result::Result<(), anyhow::Error> {
 				match self {
 					$(
@@ -926,6 +957,33 @@ macro_rules! chains {
 			}
 		}
 
+		impl AnyChain {
+			/// Returns a copy of this chain that shares the underlying rpc clients, but has its own
+			/// client, connection, channel and undelivered packets state. Used to relay several
+			/// paths through the same chain connection.
+			pub fn fork(&self) -> Self {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => {
+							let mut chain = chain.clone();
+							chain.client_id = Default::default();
+							chain.connection_id = Default::default();
+							chain.channel_whitelist = Default::default();
+							chain.common_state.maybe_has_undelivered_packets = Default::default();
+							chain.common_state.misbehaviour_client_msg_queue = Default::default();
+							chain.common_state.store = None;
+							Self::$name(chain)
+						},
+					)*
+					Self::Wasm(c) => Self::Wasm(WasmChain {
+						inner: Box::new(c.inner.fork()),
+						checksum: c.checksum.clone(),
+					}),
+				}
+			}
+		}
+
 		#[cfg(any(test, feature = "testing"))]
 		impl AnyChain {
 			pub fn set_client_id(&mut self, client_id: ClientId) {
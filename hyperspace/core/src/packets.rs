@@ -30,11 +30,11 @@ use crate::packets::utils::{
 	get_timeout_proof_height, verify_delay_passed, VerifyDelayOn,
 };
 use ibc::{
-	applications::transfer::packet::PacketData,
+	applications::transfer::packet::PacketDataV2,
 	core::{
 		ics02_client::client_state::ClientState as ClientStateT,
 		ics03_connection::connection::ConnectionEnd,
-		ics04_channel::channel::{ChannelEnd, State},
+		ics04_channel::channel::{ChannelEnd, Order, State},
 	},
 	Height,
 };
@@ -228,23 +228,33 @@ pub async fn query_ready_and_timed_out_packets(
 						Error::Custom(format!("Packet height not found for packet {packet:?}"))
 					})?;
 
-					if packet.timed_out(&sink_timestamp, sink_height) {
+					// On channels that allow timeouts, the sink has to receive a timed out packet
+					// first. It then moves past it and writes the timeout receipt the source needs.
+					let allow_timeout = sink_channel_end.ordering == Order::OrderedAllowTimeout;
+					let awaiting_timeout_receipt = allow_timeout &&
+						sink_channel_end.state != State::Closed &&
+						u64::from(packet.sequence) >= next_sequence_recv.next_sequence_receive;
+
+					if packet.timed_out(&sink_timestamp, sink_height) && !awaiting_timeout_receipt {
 						timeout_packets_count.fetch_add(1, Ordering::SeqCst);
 						// so we know this packet has timed out on the sink, we need to find the maximum
 						// consensus state height at which we can generate a non-membership proof of the
 						// packet for the sink's client on the source.
-						let proof_height =
-							if let Some(proof_height) = get_timeout_proof_height(
-								&**source,
-								&**sink,
-								source_height,
-								sink_height,
-								sink_timestamp,
-								latest_sink_height_on_source,
-								&packet,
-								packet_height,
-							)
-							.await
+						let proof_height = if allow_timeout {
+							// the timeout receipt only exists from the height the sink moved past
+							// the packet, so prove it at the latest height known to the source.
+							latest_sink_height_on_source
+						} else if let Some(proof_height) = get_timeout_proof_height(
+							&**source,
+							&**sink,
+							source_height,
+							sink_height,
+							sink_timestamp,
+							latest_sink_height_on_source,
+							&packet,
+							packet_height,
+						)
+						.await
 						{
 							proof_height
 						} else {
@@ -351,14 +361,14 @@ pub async fn query_ready_and_timed_out_packets(
 
 					let list = &source.common_state().skip_tokens_list;
 
-					let decoded_dara: PacketData = serde_json::from_str(&String::from_utf8_lossy(packet.data.as_ref())).map_err(|e| {
+					let decoded_dara = PacketDataV2::decode(sink_channel_end.version(), packet.data.as_ref()).map_err(|e| {
 						Error::Custom(format!(
 						"Failed to decode packet data for packet {:?}: {:?}",
 						packet, e
 						))
 					})?;
 
-					if list.iter().any(|skiped_denom| decoded_dara.token.denom.base_denom.as_str() == skiped_denom) {
+					if decoded_dara.tokens.iter().any(|token| list.iter().any(|skiped_denom| token.denom.base_denom.as_str() == skiped_denom)) {
 						log::info!(target: "hyperspace", "Skipping packet with ignored token: {:?}", packet);
 						return Ok(None)
 					}
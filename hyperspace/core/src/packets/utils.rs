@@ -236,10 +236,13 @@ pub async fn construct_timeout_message(
 	next_sequence_recv: u64,
 	proof_height: Height,
 ) -> Result<Any, anyhow::Error> {
-	let path_type = if sink_channel_end.ordering == Order::Ordered {
-		KeyPathType::SeqRecv
-	} else {
-		KeyPathType::ReceiptPath
+	let path_type = match sink_channel_end.ordering {
+		Order::Ordered => KeyPathType::SeqRecv,
+		// the sink moved past the packet, writing a timeout receipt for it
+		Order::OrderedAllowTimeout if u64::from(packet.sequence) < next_sequence_recv =>
+			KeyPathType::ReceiptPath,
+		Order::OrderedAllowTimeout => KeyPathType::SeqRecv,
+		Order::Unordered => KeyPathType::ReceiptPath,
 	};
 	let key = get_key_path(path_type, &packet).into_bytes();
 
@@ -12,16 +12,135 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use ibc_proto::google::protobuf::Any;
+use ibc::{
+	core::{
+		ics02_client::msgs::update_client::TYPE_URL as UPDATE_CLIENT_TYPE_URL,
+		ics04_channel::msgs::{
+			acknowledgement::TYPE_URL as ACK_PACKET_TYPE_URL,
+			recv_packet::TYPE_URL as RECV_PACKET_TYPE_URL,
+			timeout::TYPE_URL as TIMEOUT_PACKET_TYPE_URL,
+			timeout_on_close::TYPE_URL as TIMEOUT_ON_CLOSE_PACKET_TYPE_URL,
+		},
+	},
+	events::IbcEvent,
+};
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::channel::v1::{MsgAcknowledgement, MsgRecvPacket, MsgTimeout, MsgTimeoutOnClose},
+};
 use metrics::handler::MetricsHandler;
-use primitives::Chain;
+use primitives::{store::PacketKey, Chain};
+use prost::Message;
 
 /// This sends messages to the sink chain in a gas-aware manner.
+///
+/// When the relayer store is enabled, packet messages are recorded as in flight until the chain
+/// emits the event of their execution, see [`confirm_in_flight_packets`], or they time out.
+/// Packet messages that are still in flight from a previous submission, e.g. before a restart, are
+/// not submitted again.
 pub async fn flush_message_batch(
 	msgs: Vec<Any>,
 	metrics: Option<&MetricsHandler>,
 	sink: &impl Chain,
 ) -> Result<(), anyhow::Error> {
+	let Some(store) = sink.common_state().store.clone() else {
+		return submit_message_batch(msgs, metrics, sink).await
+	};
+
+	let keys = msgs.iter().map(packet_key).collect::<Vec<_>>();
+	let in_flight = store.insert_in_flight_packets(keys.iter().flatten().cloned())?;
+	let (msgs, keys): (Vec<_>, Vec<_>) = msgs
+		.into_iter()
+		.zip(keys)
+		.filter(|(_, key)| match key {
+			Some(key) if in_flight.contains(key) => {
+				log::info!(
+					"Skipping {} for packet {}/{}/{} on {}, it is already in flight",
+					key.type_url,
+					key.port_id,
+					key.channel_id,
+					key.sequence,
+					sink.name()
+				);
+				false
+			},
+			_ => true,
+		})
+		.unzip();
+
+	let only_client_updates = msgs.iter().all(|msg| msg.type_url == UPDATE_CLIENT_TYPE_URL);
+	let result = if !in_flight.is_empty() && only_client_updates {
+		// only the client updates for the packets that are in flight are left
+		Ok(())
+	} else {
+		submit_message_batch(msgs, metrics, sink).await
+	};
+	// a failed submission may be retried right away, otherwise the packets stay in flight until
+	// their execution is confirmed
+	if result.is_err() {
+		store.remove_in_flight_packets(keys.iter().flatten())?;
+	}
+	result
+}
+
+/// Removes the packet messages executed by the chain from the in flight packets, given the events
+/// emitted by the chain.
+pub fn confirm_in_flight_packets(chain: &impl Chain, events: &[IbcEvent]) -> anyhow::Result<()> {
+	let Some(store) = &chain.common_state().store else { return Ok(()) };
+	let keys = events.iter().flat_map(executed_packet_keys).collect::<Vec<_>>();
+	if keys.is_empty() {
+		return Ok(())
+	}
+	store.remove_in_flight_packets(&keys)
+}
+
+/// Returns the keys of the packet messages whose execution emitted the event.
+fn executed_packet_keys(event: &IbcEvent) -> Vec<PacketKey> {
+	let (type_urls, packet): (&[&str], _) = match event {
+		IbcEvent::ReceivePacket(ev) => (&[RECV_PACKET_TYPE_URL], &ev.packet),
+		IbcEvent::AcknowledgePacket(ev) => (&[ACK_PACKET_TYPE_URL], &ev.packet),
+		IbcEvent::TimeoutPacket(ev) =>
+			(&[TIMEOUT_PACKET_TYPE_URL, TIMEOUT_ON_CLOSE_PACKET_TYPE_URL], &ev.packet),
+		IbcEvent::TimeoutOnClosePacket(ev) => (&[TIMEOUT_ON_CLOSE_PACKET_TYPE_URL], &ev.packet),
+		_ => return vec![],
+	};
+	type_urls
+		.iter()
+		.map(|type_url| PacketKey {
+			type_url: type_url.to_string(),
+			port_id: packet.source_port.to_string(),
+			channel_id: packet.source_channel.to_string(),
+			sequence: packet.sequence.into(),
+		})
+		.collect()
+}
+
+/// Returns the key of the packet, if the message is a packet message.
+fn packet_key(msg: &Any) -> Option<PacketKey> {
+	let packet = match msg.type_url.as_str() {
+		RECV_PACKET_TYPE_URL => MsgRecvPacket::decode(msg.value.as_slice()).ok()?.packet,
+		ACK_PACKET_TYPE_URL => MsgAcknowledgement::decode(msg.value.as_slice()).ok()?.packet,
+		TIMEOUT_PACKET_TYPE_URL => MsgTimeout::decode(msg.value.as_slice()).ok()?.packet,
+		TIMEOUT_ON_CLOSE_PACKET_TYPE_URL =>
+			MsgTimeoutOnClose::decode(msg.value.as_slice()).ok()?.packet,
+		_ => None,
+	}?;
+	Some(PacketKey {
+		type_url: msg.type_url.clone(),
+		port_id: packet.source_port,
+		channel_id: packet.source_channel,
+		sequence: packet.sequence,
+	})
+}
+
+async fn submit_message_batch(
+	msgs: Vec<Any>,
+	metrics: Option<&MetricsHandler>,
+	sink: &impl Chain,
+) -> Result<(), anyhow::Error> {
+	if msgs.is_empty() {
+		return Ok(())
+	}
 	let block_max_weight = sink.block_max_weight();
 	let batch_weight = sink.estimate_weight(msgs.clone()).await?;
 
@@ -51,10 +170,46 @@ pub async fn flush_message_batch(
 	);
 	let chunk_size = (msgs.len() / chunk).max(1);
 	// TODO: return number of failed messages and record it to metrics
-	for batch in msgs.chunks(chunk_size) {
-		// send out batches.
-		sink.submit(batch.to_vec()).await?;
-	}
+	sink.submit_chunks(msgs.chunks(chunk_size).map(|batch| batch.to_vec()).collect())
+		.await?;
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::{
+		core::{
+			ics04_channel::{events::ReceivePacket, packet::Packet},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		Height,
+	};
+
+	#[test]
+	fn executed_packet_keys_match_submitted_messages() {
+		let packet = Packet {
+			sequence: 7.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(0),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(1),
+			data: vec![],
+			timeout_height: Height::new(0, 100),
+			timeout_timestamp: Default::default(),
+		};
+		let msg = Any {
+			type_url: RECV_PACKET_TYPE_URL.to_string(),
+			value: MsgRecvPacket {
+				packet: Some(packet.clone().into()),
+				proof_commitment: vec![],
+				proof_height: None,
+				signer: String::new(),
+			}
+			.encode_to_vec(),
+		};
+		let event = IbcEvent::ReceivePacket(ReceivePacket { height: Height::new(0, 10), packet });
+		assert_eq!(executed_packet_keys(&event), vec![packet_key(&msg).unwrap()]);
+	}
+}
@@ -2,13 +2,14 @@ use self::parachain_subxt::api::{
 	ibc::calls::types::{Deliver, Transfer},
 	runtime_types::{
 		common::ibc::RawMemo,
-		frame_system::{extensions::check_nonce::CheckNonce, EventRecord},
+		frame_system::{extensions::check_nonce::CheckNonce, AccountInfo, EventRecord},
+		pallet_balances::types::AccountData,
 		pallet_ibc::{events::IbcEvent as MetadataIbcEvent, TransferParams as RawTransferParams},
 	},
 	sudo::calls::types::Sudo,
 };
 use crate::{
-	define_any_wrapper, define_event_record, define_events, define_head_data,
+	define_account_info, define_any_wrapper, define_event_record, define_events, define_head_data,
 	define_ibc_event_wrapper, define_id, define_para_lifecycle, define_runtime_call,
 	define_runtime_event, define_runtime_storage, define_runtime_transactions,
 	define_transfer_params,
@@ -21,8 +22,8 @@ use async_trait::async_trait;
 use codec::{Compact, Decode, Encode};
 use ibc_proto::google::protobuf::Any;
 use light_client_common::config::{
-	EventRecordT, IbcEventsT, LocalAddress, ParaLifecycleT, RuntimeCall, RuntimeStorage,
-	RuntimeTransactions,
+	AccountInfoT, EventRecordT, IbcEventsT, LocalAddress, ParaLifecycleT, RuntimeCall,
+	RuntimeStorage, RuntimeTransactions,
 };
 use pallet_ibc::{events::IbcEvent as RawIbcEvent, MultiAddress, Timeout, TransferParams};
 use pallet_ibc_ping::SendPingParams;
@@ -109,6 +110,8 @@ define_head_data!(
 
 define_para_lifecycle!(ComposableParaLifecycle, ParaLifecycle);
 
+define_account_info!(ComposableAccountInfo, AccountInfo<u32, AccountData<u128>>);
+
 // #[cfg(feature = "composable-beefy")]
 // define_beefy_authority_set!(ComposableBeefyAuthoritySet, BeefyAuthoritySet<T>);
 
@@ -123,6 +126,7 @@ define_runtime_storage!(
 	ComposableId,
 	ComposableParaLifecycle,
 	ComposableBeefyAuthoritySetToUse,
+	ComposableAccountInfo,
 	parachain_subxt::api::storage().timestamp().now(),
 	|x| relaychain::api::storage().paras().heads(x),
 	|x| relaychain::api::storage().paras().para_lifecycles(x),
@@ -156,7 +160,8 @@ define_runtime_storage!(
 			"relaychain::api::storage().mmr_leaf().beefy_next_authorities()",
 		)
 	},
-	relaychain::api::storage().babe().epoch_start()
+	relaychain::api::storage().babe().epoch_start(),
+	|x| parachain_subxt::api::storage().system().account(x)
 );
 
 define_transfer_params!(
@@ -2,25 +2,26 @@ use self::parachain_subxt::api::{
 	ibc::calls::{Deliver, Transfer},
 	ibc_ping::calls::SendPing,
 	runtime_types::{
-		frame_system::{extensions::check_nonce::CheckNonce, EventRecord},
+		frame_system::{extensions::check_nonce::CheckNonce, AccountInfo, EventRecord},
+		pallet_balances::AccountData,
 		pallet_ibc::{events::IbcEvent as MetadataIbcEvent, TransferParams as RawTransferParams},
 		pallet_ibc_ping::SendPingParams as RawSendPingParams,
 	},
 	sudo::calls::Sudo,
 };
 use crate::{
-	define_any_wrapper, define_asset_id, define_beefy_authority_set, define_event_record,
-	define_events, define_head_data, define_ibc_event_wrapper, define_id, define_para_lifecycle,
-	define_runtime_call, define_runtime_event, define_runtime_storage, define_runtime_transactions,
-	define_send_ping_params, define_transfer_params,
+	define_account_info, define_any_wrapper, define_asset_id, define_beefy_authority_set,
+	define_event_record, define_events, define_head_data, define_ibc_event_wrapper, define_id,
+	define_para_lifecycle, define_runtime_call, define_runtime_event, define_runtime_storage,
+	define_runtime_transactions, define_send_ping_params, define_transfer_params,
 	substrate::dali::relaychain::api::runtime_types::sp_beefy::mmr::BeefyAuthoritySet,
 };
 use async_trait::async_trait;
 use codec::{Compact, Decode, Encode};
 use ibc_proto::google::protobuf::Any;
 use light_client_common::config::{
-	BeefyAuthoritySetT, EventRecordT, IbcEventsT, LocalAddress, ParaLifecycleT, RuntimeCall,
-	RuntimeStorage, RuntimeTransactions,
+	AccountInfoT, BeefyAuthoritySetT, EventRecordT, IbcEventsT, LocalAddress, ParaLifecycleT,
+	RuntimeCall, RuntimeStorage, RuntimeTransactions,
 };
 use pallet_ibc::{events::IbcEvent as RawIbcEvent, MultiAddress, Timeout, TransferParams};
 use pallet_ibc_ping::SendPingParams;
@@ -69,6 +70,8 @@ define_head_data!(
 
 define_para_lifecycle!(DaliParaLifecycle, ParaLifecycle);
 
+define_account_info!(DaliAccountInfo, AccountInfo<u32, AccountData<u128>>);
+
 define_beefy_authority_set!(DaliBeefyAuthoritySet, BeefyAuthoritySet<T>);
 
 define_runtime_storage!(
@@ -77,6 +80,7 @@ define_runtime_storage!(
 	DaliId,
 	DaliParaLifecycle,
 	DaliBeefyAuthoritySet<H256>,
+	DaliAccountInfo,
 	parachain_subxt::api::storage().timestamp().now(),
 	|x| relaychain::api::storage().paras().heads(x),
 	|x| relaychain::api::storage().paras().para_lifecycles(x),
@@ -85,7 +89,8 @@ define_runtime_storage!(
 	relaychain::api::storage().beefy().validator_set_id(),
 	relaychain::api::storage().beefy().authorities(),
 	relaychain::api::storage().mmr_leaf().beefy_next_authorities(),
-	relaychain::api::storage().babe().epoch_start()
+	relaychain::api::storage().babe().epoch_start(),
+	|x| parachain_subxt::api::storage().system().account(x)
 );
 
 define_send_ping_params!(SendPingParamsWrapper, SendPingParams, RawSendPingParams);
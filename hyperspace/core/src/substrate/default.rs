@@ -2,7 +2,8 @@ use self::parachain_subxt::api::{
 	ibc::calls::types::{Deliver, Transfer},
 	ibc_ping::calls::types::SendPing,
 	runtime_types::{
-		frame_system::{extensions::check_nonce::CheckNonce, EventRecord},
+		frame_system::{extensions::check_nonce::CheckNonce, AccountInfo, EventRecord},
+		pallet_balances::types::AccountData,
 		pallet_ibc::{events::IbcEvent as MetadataIbcEvent, TransferParams as RawTransferParams},
 		pallet_ibc_ping::SendPingParams as RawSendPingParams,
 		parachain_runtime::RawMemo,
@@ -11,7 +12,7 @@ use self::parachain_subxt::api::{
 };
 use super::{unimplemented, DummyBeefyAuthoritySet};
 use crate::{
-	define_any_wrapper, define_event_record, define_events, define_head_data,
+	define_account_info, define_any_wrapper, define_event_record, define_events, define_head_data,
 	define_ibc_event_wrapper, define_id, define_para_lifecycle, define_runtime_call,
 	define_runtime_event, define_runtime_storage, define_runtime_transactions,
 	define_send_ping_params, define_transfer_params,
@@ -20,8 +21,8 @@ use async_trait::async_trait;
 use codec::{Compact, Decode, Encode};
 use ibc_proto::google::protobuf::Any;
 use light_client_common::config::{
-	EventRecordT, IbcEventsT, LocalAddress, ParaLifecycleT, RuntimeCall, RuntimeStorage,
-	RuntimeTransactions,
+	AccountInfoT, EventRecordT, IbcEventsT, LocalAddress, ParaLifecycleT, RuntimeCall,
+	RuntimeStorage, RuntimeTransactions,
 };
 use pallet_ibc::{events::IbcEvent as RawIbcEvent, MultiAddress, Timeout, TransferParams};
 use pallet_ibc_ping::SendPingParams;
@@ -76,12 +77,15 @@ define_head_data!(
 
 define_para_lifecycle!(DefaultParaLifecycle, ParaLifecycle);
 
+define_account_info!(DefaultAccountInfo, AccountInfo<u32, AccountData<u128>>);
+
 define_runtime_storage!(
 	DefaultRuntimeStorage,
 	DefaultHeadData,
 	DefaultId,
 	DefaultParaLifecycle,
 	DummyBeefyAuthoritySet,
+	DefaultAccountInfo,
 	parachain_subxt::api::storage().timestamp().now(),
 	|x| relaychain::api::storage().paras().heads(x),
 	|x| relaychain::api::storage().paras().para_lifecycles(x),
@@ -94,7 +98,8 @@ define_runtime_storage!(
 	unimplemented::<Address<StaticStorageMapKey, (), Yes, Yes, ()>>(
 		"relaychain::api::storage().mmr_leaf().beefy_next_authorities()"
 	),
-	relaychain::api::storage().babe().epoch_start()
+	relaychain::api::storage().babe().epoch_start(),
+	|x| parachain_subxt::api::storage().system().account(x)
 );
 
 define_send_ping_params!(SendPingParamsWrapper, SendPingParams, RawSendPingParams);
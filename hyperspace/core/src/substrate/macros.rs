@@ -87,6 +87,31 @@ macro_rules! define_para_lifecycle {
 	};
 }
 
+#[macro_export]
+macro_rules! define_account_info {
+	(
+		$name: ident,
+		$ty: ty
+	) => {
+		#[derive(Decode, Encode)]
+		pub struct $name(pub $ty);
+
+		impl AccountInfoT for $name {
+			fn free_balance(&self) -> u128 {
+				self.0.data.free
+			}
+		}
+
+		impl AsInner for $name {
+			type Inner = $ty;
+
+			fn from_inner(inner: Self::Inner) -> Self {
+				$name(inner)
+			}
+		}
+	};
+}
+
 #[macro_export]
 macro_rules! define_beefy_authority_set {
 	(
@@ -575,6 +600,7 @@ macro_rules! define_runtime_storage {
 		$id:ty,
 		$para_lifecycle:ty,
 		$beefy_authority_set:ty,
+		$account_info:ty,
 		$timestamp_now:expr,
 		$paras_heads:expr,
 		$paras_para_lifecycles:expr,
@@ -583,7 +609,8 @@ macro_rules! define_runtime_storage {
 		$beefy_validator_set_id:expr,
 		$beefy_authorities:expr,
 		$mmr_leaf_beefy_next_authorities:expr,
-		$babe_epoch_start:expr
+		$babe_epoch_start:expr,
+		$system_account:expr
 	) => {
 		use subxt::utils::Static;
 
@@ -593,6 +620,7 @@ macro_rules! define_runtime_storage {
 			type Id = $id;
 			type ParaLifecycle = $para_lifecycle;
 			type BeefyAuthoritySet = $beefy_authority_set;
+			type AccountInfo = $account_info;
 
 			fn timestamp_now() -> Address<StaticStorageMapKey, u64, Yes, Yes, ()> {
 				$timestamp_now
@@ -663,6 +691,19 @@ macro_rules! define_runtime_storage {
 			fn babe_epoch_start() -> Address<StaticStorageMapKey, (u32, u32), Yes, Yes, ()> {
 				$babe_epoch_start
 			}
+
+			fn system_account(
+				account: [u8; 32],
+			) -> LocalAddress<
+				StaticStorageMapKey,
+				<Self::AccountInfo as AsInner>::Inner,
+				Yes,
+				Yes,
+				Yes,
+			> {
+				let storage = $system_account(&subxt::utils::AccountId32(account));
+				LocalAddress::new(storage)
+			}
 		}
 	};
 }
@@ -2,23 +2,24 @@ use self::parachain_subxt::api::{
 	ibc::calls::types::{Deliver, Transfer},
 	runtime_types::{
 		common::ibc::RawMemo,
-		frame_system::{extensions::check_nonce::CheckNonce, EventRecord},
+		frame_system::{extensions::check_nonce::CheckNonce, AccountInfo, EventRecord},
+		pallet_balances::AccountData,
 		pallet_ibc::{events::IbcEvent as MetadataIbcEvent, TransferParams as RawTransferParams},
 	},
 	sudo::calls::types::Sudo,
 };
 use crate::{
-	define_any_wrapper, define_asset_id, define_event_record, define_events, define_head_data,
-	define_ibc_event_wrapper, define_id, define_para_lifecycle, define_runtime_call,
-	define_runtime_event, define_runtime_storage, define_runtime_transactions,
+	define_account_info, define_any_wrapper, define_asset_id, define_event_record, define_events,
+	define_head_data, define_ibc_event_wrapper, define_id, define_para_lifecycle,
+	define_runtime_call, define_runtime_event, define_runtime_storage, define_runtime_transactions,
 	define_transfer_params, substrate::DummyBeefyAuthoritySet,
 };
 use async_trait::async_trait;
 use codec::{Compact, Decode, Encode};
 use ibc_proto::google::protobuf::Any;
 use light_client_common::config::{
-	EventRecordT, IbcEventsT, LocalAddress, ParaLifecycleT, RuntimeCall, RuntimeStorage,
-	RuntimeTransactions,
+	AccountInfoT, EventRecordT, IbcEventsT, LocalAddress, ParaLifecycleT, RuntimeCall,
+	RuntimeStorage, RuntimeTransactions,
 };
 use pallet_ibc::{events::IbcEvent as RawIbcEvent, MultiAddress, Timeout, TransferParams};
 use pallet_ibc_ping::SendPingParams;
@@ -94,12 +95,15 @@ define_head_data!(
 
 define_para_lifecycle!(PicassoParaLifecycle, ParaLifecycle);
 
+define_account_info!(PicassoAccountInfo, AccountInfo<u32, AccountData<u128>>);
+
 define_runtime_storage!(
 	PicassoRuntimeStorage,
 	PicassoHeadData,
 	PicassoId,
 	PicassoParaLifecycle,
 	DummyBeefyAuthoritySet,
+	PicassoAccountInfo,
 	parachain_subxt::api::storage().timestamp().now(),
 	|x| relaychain::api::storage().paras().heads(x),
 	|x| relaychain::api::storage().paras().para_lifecycles(x),
@@ -108,7 +112,8 @@ define_runtime_storage!(
 	relaychain::api::storage().beefy().validator_set_id(),
 	relaychain::api::storage().beefy().authorities(),
 	relaychain::api::storage().mmr_leaf().beefy_next_authorities(),
-	relaychain::api::storage().babe().epoch_start()
+	relaychain::api::storage().babe().epoch_start(),
+	|x| parachain_subxt::api::storage().system().account(x)
 );
 
 define_transfer_params!(
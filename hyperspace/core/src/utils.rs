@@ -1,9 +1,12 @@
 use futures::{Stream, StreamExt};
+use primitives::Chain;
 use std::{
 	pin::Pin,
 	sync::{Arc, Mutex},
 	task::Poll,
+	time::Duration,
 };
+use tokio::sync::broadcast::{self, error::RecvError};
 
 /// Keeps the most recent value of a stream and acts as stream itself.
 pub struct RecentStream<T: Send + 'static> {
@@ -45,3 +48,55 @@ impl<T: Send> Stream for RecentStream<T> {
 		}
 	}
 }
+
+/// Shares the finality notifications of a single chain between several relay paths. The
+/// notifications are read from the chain once and broadcast to every subscriber.
+pub struct FinalityBroadcast<T> {
+	sender: broadcast::Sender<T>,
+}
+
+impl<T: Clone + Send + 'static> FinalityBroadcast<T> {
+	/// Spawns a task forwarding the finality notifications of the chain. The task reconnects to
+	/// the chain whenever the notification stream closes.
+	pub fn spawn<C>(mut chain: C) -> Self
+	where
+		C: Chain<FinalityEvent = T> + 'static,
+	{
+		let (sender, _) = broadcast::channel(16);
+		let sender_cloned = sender.clone();
+		tokio::spawn(async move {
+			loop {
+				match chain.finality_notifications().await {
+					Ok(mut stream) => {
+						while let Some(event) = stream.next().await {
+							// sending only fails when there are no subscribers yet
+							let _ = sender_cloned.send(event);
+						}
+						log::warn!("Stream closed for {}", chain.name());
+					},
+					Err(e) => {
+						log::error!("Failed to get finality notifications for {} {:?}. Trying again in 30 seconds...", chain.name(), e);
+						tokio::time::sleep(Duration::from_secs(30)).await;
+						let _ = chain.reconnect().await;
+					},
+				}
+			}
+		});
+		Self { sender }
+	}
+
+	/// Returns a new stream of the chain's finality notifications.
+	pub fn subscribe(&self) -> RecentStream<T> {
+		let stream = futures::stream::unfold(self.sender.subscribe(), |mut receiver| async move {
+			loop {
+				match receiver.recv().await {
+					Ok(event) => return Some((event, receiver)),
+					// only the most recent event is relevant to the relayer
+					Err(RecvError::Lagged(_)) => continue,
+					Err(RecvError::Closed) => return None,
+				}
+			}
+		});
+		RecentStream::new(Box::pin(stream))
+	}
+}
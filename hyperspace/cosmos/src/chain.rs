@@ -137,6 +137,12 @@ where
 		Ok(Self::TransactionId { hash })
 	}
 
+	async fn submit_chunks(&self, chunks: Vec<Vec<Any>>) -> Result<(), Error> {
+		let hashes = self.submit_chunks_call(chunks).await?;
+		log::debug!(target: "hyperspace_cosmos", "Submitted. Tx hashes: {:?}", hashes);
+		Ok(())
+	}
+
 	async fn query_client_message(
 		&self,
 		update: UpdateClient,
@@ -224,6 +230,11 @@ where
 		block_height.increment()
 	}
 
+	async fn query_signer_balance(&self) -> Result<Option<u128>, Self::Error> {
+		let balance = self.query_ibc_balance(self.fee_denom.clone()).await?;
+		Ok(balance.first().map(|coin| coin.amount.as_u256().low_u128()))
+	}
+
 	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error> {
 		let err_str = if let Some(rpc_err) = error.downcast_ref::<Error>() {
 			match rpc_err {
@@ -280,7 +291,7 @@ where
 			amount: vec![Coin { denom: self.fee_denom.clone(), amount: self.fee_amount.clone() }],
 			gas_limit: self.gas_limit,
 			payer: "".to_string(),
-			granter: "".to_string(),
+			granter: self.fee_granter.clone().unwrap_or_default(),
 		}
 	}
 
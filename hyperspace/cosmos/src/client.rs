@@ -1,25 +1,38 @@
 #![allow(clippy::all)]
 use super::{
-	key_provider::KeyEntry,
+	key_provider::{KeyEntry, Secp256k1Signer},
 	light_client::LightClient,
-	tx::{broadcast_tx, confirm_tx, sign_tx, simulate_tx},
+	tx::{
+		broadcast_tx, calculate_fee, confirm_tx, query_gas_price, set_msg_signer, sign_tx,
+		simulate_tx, DEFAULT_GAS_MULTIPLIER, FEE_BUMP_MULTIPLIER,
+	},
 };
 use crate::error::Error;
-use bech32::ToBase32;
-use bip32::{DerivationPath, ExtendedPrivateKey, XPrv, XPub as ExtendedPublicKey};
+use bip32::{DerivationPath, XPrv, XPub as ExtendedPublicKey};
 use core::convert::{From, Into, TryFrom};
-use digest::Digest;
-use ibc::core::{
-	ics02_client::height::Height,
-	ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes},
-	ics24_host::{
-		identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
-		IBC_QUERY_PATH,
+use ibc::{
+	applications::fee::msgs::register_counterparty_payee::MsgRegisterCounterpartyPayee,
+	core::{
+		ics02_client::height::Height,
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes},
+		ics24_host::{
+			identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+			IBC_QUERY_PATH,
+		},
 	},
+	signer::Signer,
+	tx_msg::Msg,
 };
 use ibc_proto::{
-	cosmos::auth::v1beta1::{query_client::QueryClient, BaseAccount, QueryAccountRequest},
+	cosmos::{
+		auth::v1beta1::{query_client::QueryClient, BaseAccount, QueryAccountRequest},
+		tx::v1beta1::Fee,
+	},
 	google::protobuf::Any,
+	ibc::applications::fee::v1::{
+		query_client::QueryClient as FeeQueryClient, QueryCounterpartyPayeeRequest,
+		QueryFeeEnabledChannelRequest,
+	},
 };
 use ics07_tendermint::{
 	client_message::Header, client_state::ClientState, consensus_state::ConsensusState,
@@ -27,17 +40,20 @@ use ics07_tendermint::{
 };
 use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager};
 use primitives::{
+	keys::{load_key, Key, KeySource},
 	Chain, CommonClientConfig, CommonClientState, IbcProvider, KeyProvider, UpdateType,
 };
 use prost::Message;
 use quick_cache::sync::Cache;
 use rand::Rng;
-use ripemd::Ripemd160;
 use serde::{Deserialize, Serialize};
 use std::{
 	collections::HashSet,
 	str::FromStr,
-	sync::{Arc, Mutex},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
 	time::Duration,
 };
 use tendermint::{block::Height as TmHeight, Hash};
@@ -53,6 +69,7 @@ use tokio::{
 const DEFAULT_FEE_DENOM: &str = "stake";
 const DEFAULT_FEE_AMOUNT: &str = "4000";
 const DEFAULT_GAS_LIMIT: u64 = (i64::MAX - 1) as u64;
+const DEFAULT_MAX_FEE_RETRIES: u32 = 3;
 
 fn default_gas_limit() -> u64 {
 	DEFAULT_GAS_LIMIT
@@ -66,6 +83,14 @@ fn default_fee_amount() -> String {
 	DEFAULT_FEE_AMOUNT.to_string()
 }
 
+fn default_gas_multiplier() -> f64 {
+	DEFAULT_GAS_MULTIPLIER
+}
+
+fn default_max_fee_retries() -> u32 {
+	DEFAULT_MAX_FEE_RETRIES
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConfigKeyEntry {
 	pub public_key: String,
@@ -75,12 +100,17 @@ pub struct ConfigKeyEntry {
 }
 
 impl TryFrom<ConfigKeyEntry> for KeyEntry {
-	type Error = bip32::Error;
+	type Error = Error;
 
 	fn try_from(value: ConfigKeyEntry) -> Result<Self, Self::Error> {
+		let private_key =
+			XPrv::from_str(&value.private_key).map_err(|e| Error::from(e.to_string()))?;
 		Ok(KeyEntry {
-			public_key: ExtendedPublicKey::from_str(&value.public_key)?,
-			private_key: ExtendedPrivateKey::from_str(&value.private_key)?,
+			public_key: ExtendedPublicKey::from_str(&value.public_key)
+				.map_err(|e| Error::from(e.to_string()))?
+				.to_bytes()
+				.to_vec(),
+			signer: Arc::new(Secp256k1Signer::new(&private_key)?),
 			account: value.account,
 			address: value.address,
 		})
@@ -88,38 +118,57 @@ impl TryFrom<ConfigKeyEntry> for KeyEntry {
 }
 
 impl TryFrom<MnemonicEntry> for KeyEntry {
-	type Error = bip32::Error;
+	type Error = Error;
 
 	fn try_from(mnemonic_entry: MnemonicEntry) -> Result<Self, Self::Error> {
-		// From mnemonic to pubkey
+		// From mnemonic to private key
 		let mnemonic =
 			bip39::Mnemonic::from_phrase(&mnemonic_entry.mnemonic, bip39::Language::English)
-				.unwrap();
+				.map_err(|e| Error::from(format!("Invalid mnemonic {e}")))?;
 		let seed = bip39::Seed::new(&mnemonic, "");
-		let key_m = XPrv::derive_from_path(seed, &DerivationPath::from_str("m/44'/118'/0'/0/0")?)?;
-
-		// From pubkey to address
-		let sha256 = sha2::Sha256::digest(key_m.public_key().to_bytes());
-		let public_key_hash: [u8; 20] = Ripemd160::digest(sha256).into();
-		let account = bech32::encode(
-			&mnemonic_entry.prefix,
-			public_key_hash.to_base32(),
-			bech32::Variant::Bech32,
-		)
-		.unwrap();
-		Ok(KeyEntry {
-			public_key: key_m.public_key(),
-			private_key: key_m,
-			account,
-			address: public_key_hash.into(),
-		})
+		let key_m = DerivationPath::from_str("m/44'/118'/0'/0/0")
+			.and_then(|path| XPrv::derive_from_path(seed, &path))
+			.map_err(|e| Error::from(e.to_string()))?;
+
+		KeyEntry::from_signer(Arc::new(Secp256k1Signer::new(&key_m)?), &mnemonic_entry.prefix)
 	}
 }
+
+/// Loads a signing key, configured either as a plaintext mnemonic or through a [`KeySource`]
+fn load_key_entry(
+	mnemonic: Option<&str>,
+	source: Option<&KeySource>,
+	prefix: &str,
+) -> Result<KeyEntry, Error> {
+	match load_key(mnemonic, source).map_err(|e| Error::from(e.to_string()))? {
+		Key::Secret(mnemonic) =>
+			KeyEntry::try_from(MnemonicEntry { mnemonic, prefix: prefix.to_string() }),
+		Key::Remote(remote) => KeyEntry::from_signer(Arc::new(remote), prefix),
+	}
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MnemonicEntry {
 	pub mnemonic: String,
 	pub prefix: String,
 }
+
+/// An account that signs transactions, along with a local cache of its account info.
+#[derive(Clone)]
+pub struct TxSigner {
+	/// The key that signs transactions
+	pub keybase: KeyEntry,
+	/// Cached account info. The sequence is incremented locally after every broadcast
+	/// transaction, so that several transactions can be in flight at once, and is resynced from
+	/// the chain whenever a transaction fails to be broadcast.
+	pub account: Arc<AsyncMutex<Option<BaseAccount>>>,
+}
+
+impl TxSigner {
+	pub fn new(keybase: KeyEntry) -> Self {
+		Self { keybase, account: Default::default() }
+	}
+}
 // Implements the [`crate::Chain`] trait for cosmos.
 /// This is responsible for:
 /// 1. Tracking a cosmos light client on a counter-party chain, advancing this light
@@ -163,13 +212,29 @@ pub struct CosmosClient<H> {
 	pub fee_amount: String,
 	/// Fee amount
 	pub gas_limit: u64,
+	/// Gas price in `fee_denom`, fees are computed from the simulated gas when set
+	pub gas_price: Option<f64>,
+	/// Multiplier applied to the simulated gas
+	pub gas_multiplier: f64,
+	/// Maximum gas limit of a transaction
+	pub max_gas: u64,
+	/// Account paying the fees through a fee grant
+	pub fee_granter: Option<String>,
+	/// Fetch the gas price from the chain's feemarket module
+	pub query_gas_price: bool,
+	/// Number of times a transaction is retried with a bumped fee
+	pub max_fee_retries: u32,
 	/// Maximun transaction size
 	pub max_tx_size: usize,
+	/// Address on the counterparty chain paid the ICS-29 fees earned by relaying packets to this
+	/// chain
+	pub counterparty_payee: Option<String>,
 	/// Finality protocol to use, eg Tenderminet
 	pub _phantom: std::marker::PhantomData<H>,
-	/// Mutex used to sequentially send transactions. This is necessary because
-	/// account sequence numbers are not updated until the transaction is processed.
-	pub tx_mutex: Arc<tokio::sync::Mutex<()>>,
+	/// Accounts used to sign transactions, the first one is always `keybase`
+	pub signers: Vec<TxSigner>,
+	/// Index of the signer to use for the next transaction
+	pub next_signer: Arc<AtomicUsize>,
 	/// Light-client blocks cache
 	pub light_block_cache: Arc<Cache<TmHeight, LightBlock>>,
 	/// Relayer data
@@ -206,10 +271,36 @@ pub struct CosmosClientConfig {
 	/// Fee amount
 	#[serde(default = "default_gas_limit")]
 	pub gas_limit: u64,
+	/// Gas price in `fee_denom`. When set, the fee of each transaction is computed from its
+	/// simulated gas instead of using the fixed `fee_amount`.
+	#[serde(default)]
+	pub gas_price: Option<f64>,
+	/// Multiplier applied to the simulated gas to get the gas limit of a transaction
+	#[serde(default = "default_gas_multiplier")]
+	pub gas_multiplier: f64,
+	/// Maximum gas limit of a transaction, defaults to `gas_limit`
+	#[serde(default)]
+	pub max_gas: Option<u64>,
+	/// Account paying the fees through a fee grant
+	#[serde(default)]
+	pub fee_granter: Option<String>,
+	/// Fetch the gas price from the chain's feemarket module, falling back to `gas_price` if
+	/// the query fails
+	#[serde(default)]
+	pub query_gas_price: bool,
+	/// Number of times a transaction is retried with a bumped fee after failing with an
+	/// insufficient fee or running out of gas
+	#[serde(default = "default_max_fee_retries")]
+	pub max_fee_retries: u32,
 	/// Store prefix
 	pub store_prefix: String,
 	/// Maximun transaction size
 	pub max_tx_size: usize,
+	/// Address on the counterparty chain paid the ICS-29 fees earned by relaying packets to this
+	/// chain. It is registered for every signer on the whitelisted channels when the relayer
+	/// starts.
+	#[serde(default)]
+	pub counterparty_payee: Option<String>,
 	/// All the client states and headers will be wrapped in WASM ones using the WASM code ID.
 	#[serde(default)]
 	pub wasm_checksum: Option<String>,
@@ -220,23 +311,28 @@ pub struct CosmosClientConfig {
 	pub key_store_type: Store,					// TODO: Could be set to any of SyncCryptoStorePtr or KeyStore or KeyEntry types, but not sure yet
 	pub rpc_timeout: Duration,				    // TODO: Could be set to '15s' by default
 	pub default_gas: Option<u64>,	  			// TODO: Could be set to `0` by default
-	pub max_gas: Option<u64>,                   // TODO: DEFAULT_MAX_GAS: u64 = 400_000
-	pub gas_multiplier: Option<GasMultiplier>,  // TODO: Could be set to `1.1` by default
-	pub fee_granter: Option<String>,            // TODO: DEFAULT_FEE_GRANTER: &str = ""
 	pub max_msg_num: MaxMsgNum,                 // TODO: Default is 30, Could be set usize = 1 for test
 												// TODO: Could be set to const MAX_LEN: usize = 50;
 	pub proof_specs: Option<ProofSpecs>,        // TODO: Could be set to None
 	pub sequential_batch_tx: bool,			    // TODO: sequential_send_batched_messages_and_wait_commit() or send_batched_messages_and_wait_commit() ?
 	pub trust_threshold: TrustThreshold,
-	pub gas_price: GasPrice,   				    // TODO: Could be set to `0`
 	pub packet_filter: PacketFilter,            // TODO: AllowAll
 	pub address_type: AddressType,			    // TODO: Type = cosmos
 	pub extension_options: Vec<ExtensionOption>,// TODO: Could be set to None
 	*/
 	/// Whitelisted channels
 	pub channel_whitelist: Vec<(ChannelId, PortId)>,
-	/// The key that signs transactions
-	pub mnemonic: String,
+	/// The key that signs transactions. Prefer `key_source`, so that the mnemonic isn't stored
+	/// in plaintext in the config.
+	#[serde(default)]
+	pub mnemonic: Option<String>,
+	/// Where the key that signs transactions is loaded from
+	#[serde(default)]
+	pub key_source: Option<KeySource>,
+	/// Additional accounts used to sign transactions, loaded from key sources. Transactions are
+	/// spread over these and the main account, so that more of them can be included per block.
+	#[serde(default)]
+	pub signer_keys: Vec<KeySource>,
 	/// Common client config
 	#[serde(flatten)]
 	pub common: CommonClientConfig,
@@ -269,11 +365,19 @@ where
 		let commitment_prefix = CommitmentPrefix::try_from(config.store_prefix.as_bytes().to_vec())
 			.map_err(|e| Error::from(format!("Invalid store prefix {:?}", e)))?;
 
-		let keybase: KeyEntry = KeyEntry::try_from(MnemonicEntry {
-			mnemonic: config.mnemonic,
-			prefix: config.account_prefix.clone(),
-		})
-		.map_err(|e| e.to_string())?;
+		let keybase = load_key_entry(
+			config.mnemonic.as_deref(),
+			config.key_source.as_ref(),
+			&config.account_prefix,
+		)?;
+		let mut signers = vec![TxSigner::new(keybase.clone())];
+		for source in config.signer_keys {
+			signers.push(TxSigner::new(load_key_entry(
+				None,
+				Some(&source),
+				&config.account_prefix,
+			)?));
+		}
 
 		let rpc_call_delay = Duration::from_millis(1000);
 		Ok(Self {
@@ -294,10 +398,18 @@ where
 			fee_denom: config.fee_denom,
 			fee_amount: config.fee_amount,
 			gas_limit: config.gas_limit,
+			gas_price: config.gas_price,
+			gas_multiplier: config.gas_multiplier,
+			max_gas: config.max_gas.unwrap_or(config.gas_limit),
+			fee_granter: config.fee_granter,
+			query_gas_price: config.query_gas_price,
+			max_fee_retries: config.max_fee_retries,
 			max_tx_size: config.max_tx_size,
+			counterparty_payee: config.counterparty_payee,
 			keybase,
 			_phantom: std::marker::PhantomData,
-			tx_mutex: Default::default(),
+			signers,
+			next_signer: Default::default(),
 			light_block_cache: Arc::new(Cache::new(100000)),
 			common_state: CommonClientState {
 				skip_optional_client_updates: config.common.skip_optional_client_updates,
@@ -307,6 +419,7 @@ where
 				misbehaviour_client_msg_queue: Arc::new(AsyncMutex::new(vec![])),
 				max_packets_to_process: config.common.max_packets_to_process as usize,
 				skip_tokens_list: config.skip_tokens_list.unwrap_or_default(),
+				store: None,
 			},
 			join_handles: Arc::new(TokioMutex::new(vec![ws_driver_jh])),
 		})
@@ -353,29 +466,319 @@ where
 	}
 
 	pub async fn submit_call(&self, messages: Vec<Any>) -> Result<Hash, Error> {
-		let _lock = self.tx_mutex.lock().await;
-		let account_info = self.query_account().await?;
+		let (signer, mut chunks) = self.select_signer(vec![messages]);
+		self.submit_with_signer(signer, chunks.remove(0)).await
+	}
+
+	/// Submits the chunks of a batch in order, all of them signed by the same account so that
+	/// they can't be included out of order.
+	pub async fn submit_chunks_call(&self, chunks: Vec<Vec<Any>>) -> Result<Vec<Hash>, Error> {
+		let (signer, chunks) = self.select_signer(chunks);
+		let mut hashes = Vec::with_capacity(chunks.len());
+		for messages in chunks {
+			hashes.push(self.submit_with_signer(signer, messages).await?);
+		}
+		Ok(hashes)
+	}
+
+	async fn submit_with_signer(
+		&self,
+		signer: &TxSigner,
+		messages: Vec<Any>,
+	) -> Result<Hash, Error> {
+		let mut gas_price = self.gas_price().await;
+		let mut gas_multiplier = self.gas_multiplier;
+		let mut retries = 0;
+		let mut resynced = false;
+		loop {
+			// Broadcast transaction and wait for confirmation
+			let mut gas_limit = None;
+			let result = match self
+				.broadcast_with_signer(signer, &messages, gas_price, gas_multiplier)
+				.await
+			{
+				Ok((hash, limit)) => {
+					log::debug!(target: "hyperspace_cosmos", "🤝 Transaction sent with hash: {:?}", hash);
+					gas_limit = Some(limit);
+					confirm_tx(&self.rpc_client, hash).await
+				},
+				Err(e) => Err(e),
+			};
+
+			match result {
+				// the cached account was reset, so try again with the sequence from the chain
+				Err(e) if e.is_sequence_mismatch() && !resynced => {
+					resynced = true;
+					log::warn!(
+						target: "hyperspace_cosmos",
+						"Resyncing account sequence of {} on {}: {e}",
+						signer.keybase.account, self.name
+					);
+				},
+				Err(e) if gas_price.is_some() && retries < self.max_fee_retries => {
+					if e.is_insufficient_fee() {
+						gas_price = gas_price.map(|price| price * FEE_BUMP_MULTIPLIER);
+					} else if e.is_out_of_gas() &&
+						gas_limit.map_or(true, |limit| limit < self.max_gas)
+					{
+						// the gas limit is below the cap, so a higher multiplier raises it
+						gas_multiplier *= FEE_BUMP_MULTIPLIER;
+					} else {
+						return Err(e)
+					}
+					retries += 1;
+					log::warn!(
+						target: "hyperspace_cosmos",
+						"Retrying transaction on {} with a bumped fee ({retries}/{}): {e}",
+						self.name, self.max_fee_retries
+					);
+				},
+				result => return result,
+			}
+		}
+	}
+
+	/// Registers the configured counterparty payee of every signer on the whitelisted channels, so
+	/// that the recv fees of the packets relayed to this chain are paid on the counterparty chain.
+	/// Channels that didn't negotiate the fee version and payees that are already registered are
+	/// skipped, a failed registration is only logged.
+	pub async fn register_counterparty_payees(&self) -> Result<(), Error> {
+		let Some(counterparty_payee) = self.counterparty_payee.clone() else { return Ok(()) };
+		let channels = self.channel_whitelist.lock().unwrap().clone();
+		if channels.is_empty() {
+			return Ok(())
+		}
+		let mut grpc_client = FeeQueryClient::connect(self.grpc_url.clone().to_string())
+			.await
+			.map_err(|e| Error::from(e.to_string()))?;
+		let mut fee_enabled_channels = Vec::with_capacity(channels.len());
+		for (channel_id, port_id) in channels {
+			let request = QueryFeeEnabledChannelRequest {
+				port_id: port_id.to_string(),
+				channel_id: channel_id.to_string(),
+			};
+			match grpc_client.fee_enabled_channel(request).await {
+				Ok(response) if response.get_ref().fee_enabled =>
+					fee_enabled_channels.push((channel_id, port_id)),
+				Ok(_) => log::debug!(
+					target: "hyperspace_cosmos",
+					"Fees are not enabled on {port_id}/{channel_id} of {}", self.name
+				),
+				Err(e) => log::warn!(
+					target: "hyperspace_cosmos",
+					"Failed to query fee enabled channel {port_id}/{channel_id} of {}: {e}", self.name
+				),
+			}
+		}
+		if fee_enabled_channels.is_empty() {
+			return Ok(())
+		}
+
+		let gas_price = self.gas_price().await;
+		for signer in &self.signers {
+			let relayer = Signer::from_str(&signer.keybase.account)
+				.map_err(|e| Error::from(format!("Invalid relayer address: {e}")))?;
+			let mut messages = vec![];
+			for (channel_id, port_id) in &fee_enabled_channels {
+				let request = QueryCounterpartyPayeeRequest {
+					channel_id: channel_id.to_string(),
+					relayer: signer.keybase.account.clone(),
+				};
+				// the payee is not found until it is registered
+				let registered = grpc_client
+					.counterparty_payee(request)
+					.await
+					.map(|response| response.into_inner().counterparty_payee)
+					.ok();
+				if registered.as_deref() == Some(counterparty_payee.as_str()) {
+					continue
+				}
+				messages.push(
+					MsgRegisterCounterpartyPayee {
+						port_id: port_id.clone(),
+						channel_id: *channel_id,
+						relayer: relayer.clone(),
+						counterparty_payee: counterparty_payee.clone(),
+					}
+					.to_any(),
+				);
+			}
+			if messages.is_empty() {
+				continue
+			}
+			let result = async {
+				let (hash, _) = self
+					.broadcast_with_signer(signer, &messages, gas_price, self.gas_multiplier)
+					.await?;
+				confirm_tx(&self.rpc_client, hash).await
+			}
+			.await;
+			match result {
+				Ok(_) => log::info!(
+					target: "hyperspace_cosmos",
+					"Registered counterparty payee {counterparty_payee} of {} on {}",
+					signer.keybase.account, self.name
+				),
+				Err(e) => log::error!(
+					target: "hyperspace_cosmos",
+					"Failed to register counterparty payee of {} on {}: {e:?}",
+					signer.keybase.account, self.name
+				),
+			}
+		}
+		Ok(())
+	}
+
+	/// Picks the signer of the next batch of transactions in a round robin fashion, replacing the
+	/// signer of the messages if needed. Falls back to the main account if any of the messages
+	/// can't be signed by another account.
+	fn select_signer(&self, chunks: Vec<Vec<Any>>) -> (&TxSigner, Vec<Vec<Any>>) {
+		let index = self.next_signer.fetch_add(1, Ordering::Relaxed) % self.signers.len();
+		if index == 0 {
+			return (&self.signers[0], chunks)
+		}
+		let signer = &self.signers[index];
+		match chunks
+			.iter()
+			.map(|messages| {
+				messages
+					.iter()
+					.map(|msg| set_msg_signer(msg, &signer.keybase.account))
+					.collect::<Option<Vec<_>>>()
+			})
+			.collect::<Option<Vec<_>>>()
+		{
+			Some(chunks) => (signer, chunks),
+			None => (&self.signers[0], chunks),
+		}
+	}
 
-		// Sign transaction
+	/// Signs and broadcasts the messages using the cached account sequence of the signer. The
+	/// account is held for the duration of the broadcast only, so that the transaction doesn't
+	/// block others while it's waiting for confirmation. Returns the transaction hash and its gas
+	/// limit.
+	async fn broadcast_with_signer(
+		&self,
+		signer: &TxSigner,
+		messages: &[Any],
+		gas_price: Option<f64>,
+		gas_multiplier: f64,
+	) -> Result<(Hash, u64), Error> {
+		let mut account = signer.account.lock().await;
+		let result = async {
+			let (mut account_info, cached) = match account.as_ref() {
+				Some(account_info) => (account_info.clone(), true),
+				None => (self.query_account_of(&signer.keybase.account).await?, false),
+			};
+			let fee = match self
+				.estimate_fee(&signer.keybase, &account_info, messages, gas_price, gas_multiplier)
+				.await
+			{
+				Ok(fee) => fee,
+				// the simulation fails if the cached sequence is stale, so retry with the one from
+				// the chain
+				Err(_) if cached => {
+					account_info = self.query_account_of(&signer.keybase.account).await?;
+					self.estimate_fee(
+						&signer.keybase,
+						&account_info,
+						messages,
+						gas_price,
+						gas_multiplier,
+					)
+					.await?
+				},
+				Err(e) => return Err(e),
+			};
+
+			let gas_limit = fee.gas_limit;
+
+			// Sign transaction
+			let (_, _, tx_bytes) = sign_tx(
+				signer.keybase.clone(),
+				self.chain_id.clone(),
+				&account_info,
+				messages.to_vec(),
+				fee,
+			)?;
+
+			let hash = broadcast_tx(&self.rpc_client, tx_bytes).await?;
+			account_info.sequence += 1;
+			Ok::<_, Error>((hash, gas_limit, account_info))
+		}
+		.await;
+
+		match result {
+			Ok((hash, gas_limit, account_info)) => {
+				*account = Some(account_info);
+				Ok((hash, gas_limit))
+			},
+			Err(e) => {
+				// the sequence wasn't used, or is out of sync, so resync it from the chain
+				*account = None;
+				Err(e)
+			},
+		}
+	}
+
+	/// Returns the gas price used to compute transaction fees, or `None` if the fixed fee should
+	/// be used.
+	async fn gas_price(&self) -> Option<f64> {
+		if self.query_gas_price {
+			match query_gas_price(&self.rpc_http_client, &self.fee_denom).await {
+				Ok(price) => return Some(price),
+				Err(e) => log::warn!(
+					target: "hyperspace_cosmos",
+					"Failed to query gas price for {}, falling back to the configured one: {e}",
+					self.name
+				),
+			}
+		}
+		self.gas_price
+	}
+
+	/// Simulates the transaction and computes its fee from the used gas. Returns the fixed fee if
+	/// no gas price is given.
+	async fn estimate_fee(
+		&self,
+		keybase: &KeyEntry,
+		account_info: &BaseAccount,
+		messages: &[Any],
+		gas_price: Option<f64>,
+		gas_multiplier: f64,
+	) -> Result<Fee, Error> {
 		let (tx, _, tx_bytes) = sign_tx(
-			self.keybase.clone(),
+			keybase.clone(),
 			self.chain_id.clone(),
-			&account_info,
-			messages,
+			account_info,
+			messages.to_vec(),
 			self.get_fee(),
 		)?;
 
 		// Simulate transaction
-		let res = simulate_tx(self.grpc_url.clone(), tx, tx_bytes.clone()).await?;
+		let res = simulate_tx(self.grpc_url.clone(), tx, tx_bytes).await?;
 		res.result
 			.map(|r| log::debug!(target: "hyperspace_cosmos", "Simulated transaction: events: {:?}\nlogs: {}", r.events, r.log));
 
-		// Broadcast transaction
-		let hash = broadcast_tx(&self.rpc_client, tx_bytes).await?;
-		log::debug!(target: "hyperspace_cosmos", "🤝 Transaction sent with hash: {:?}", hash);
-
-		// wait for confirmation
-		confirm_tx(&self.rpc_client, hash).await
+		let Some(gas_price) = gas_price else { return Ok(self.get_fee()) };
+		let gas_used = res
+			.gas_info
+			.map(|info| info.gas_used)
+			.ok_or_else(|| Error::from("Simulation didn't return the used gas".to_string()))?;
+		let fee = calculate_fee(
+			gas_used,
+			gas_multiplier,
+			self.max_gas,
+			gas_price,
+			&self.fee_denom,
+			self.fee_granter.as_deref().unwrap_or_default(),
+		)?;
+		log::debug!(
+			target: "hyperspace_cosmos",
+			"Estimated fee for {}: gas used {gas_used}, gas limit {}, amount {:?}",
+			self.name, fee.gas_limit, fee.amount
+		);
+		Ok(fee)
 	}
 
 	pub async fn fetch_light_block_with_cache(
@@ -460,12 +863,16 @@ where
 
 	/// Uses the GRPC client to retrieve the account sequence
 	pub async fn query_account(&self) -> Result<BaseAccount, Error> {
+		self.query_account_of(&self.keybase.account).await
+	}
+
+	/// Uses the GRPC client to retrieve the sequence of the given account
+	pub async fn query_account_of(&self, address: &str) -> Result<BaseAccount, Error> {
 		let mut client = QueryClient::connect(self.grpc_url.clone().to_string())
 			.await
 			.map_err(|e| Error::from(format!("GRPC client error: {:?}", e)))?;
 
-		let request =
-			tonic::Request::new(QueryAccountRequest { address: self.keybase.account.to_string() });
+		let request = tonic::Request::new(QueryAccountRequest { address: address.to_string() });
 
 		let response = client.account(request).await;
 
@@ -543,6 +950,7 @@ fn is_validators_equal(set_a: &ValidatorSet, set_b: &ValidatorSet) -> bool {
 pub mod tests {
 	use super::MnemonicEntry;
 	use crate::key_provider::KeyEntry;
+	use k256::ecdsa::{signature::Signer as _, Signature, SigningKey};
 
 	struct TestVector {
 		mnemonic: &'static str,
@@ -585,8 +993,11 @@ pub mod tests {
 				prefix: "cosmos".to_string(),
 			}) {
 				Ok(key_entry) => {
-					assert_eq!(key_entry.private_key.to_bytes(), vector.private_key);
-					assert_eq!(key_entry.public_key.to_bytes(), vector.public_key);
+					// signatures are deterministic, so the entry signs with the expected key
+					let signing_key = SigningKey::from_bytes(&vector.private_key).unwrap();
+					let signature: Signature = signing_key.sign(b"payload");
+					assert_eq!(key_entry.signer.sign(b"payload").unwrap(), signature.as_ref());
+					assert_eq!(key_entry.public_key, vector.public_key);
 					assert_eq!(key_entry.account, vector.account);
 				},
 				Err(_) => panic!("Try from mnemonic failed"),
@@ -8,13 +8,11 @@ use ibc_proto::{
 	},
 	google::protobuf::Any,
 };
-use k256::ecdsa::{signature::Signer as _, Signature, SigningKey};
 use prost::Message;
 
 pub fn encode_key_bytes(key: &KeyEntry) -> Result<Vec<u8>, Error> {
 	let mut pk_buf = Vec::new();
-	Message::encode(&key.public_key.public_key().to_bytes().as_slice().to_vec(), &mut pk_buf)
-		.map_err(|e| Error::from(e.to_string()))?;
+	Message::encode(&key.public_key, &mut pk_buf).map_err(|e| Error::from(e.to_string()))?;
 	Ok(pk_buf)
 }
 
@@ -50,11 +48,7 @@ pub fn encode_sign_doc(
 	Message::encode(&sign_doc, &mut signdoc_buf)?;
 
 	// Create signature
-	let private_key_bytes = key.private_key.private_key().to_bytes();
-	let signing_key = SigningKey::from_bytes(private_key_bytes.as_slice())
-		.map_err(|e| Error::from(e.to_string()))?;
-	let signature: Signature = signing_key.sign(&signdoc_buf);
-	let signature_bytes = signature.as_ref().to_vec();
+	let signature_bytes = key.signer.sign(&signdoc_buf).map_err(|e| Error::from(e.to_string()))?;
 
 	Ok(signature_bytes)
 }
@@ -25,6 +25,37 @@ pub enum Error {
 	/// Tendermint error
 	#[error("Tendermint error: {0}")]
 	TendermintError(#[from] tendermint::Error),
+	/// Transaction was rejected or failed on chain
+	#[error("Transaction failed with code {code}: {log}")]
+	TransactionFailed { code: u32, log: String },
+}
+
+/// Cosmos SDK error code of `ErrOutOfGas`
+const ERR_OUT_OF_GAS: u32 = 11;
+/// Cosmos SDK error code of `ErrInsufficientFee`
+const ERR_INSUFFICIENT_FEE: u32 = 13;
+/// Cosmos SDK error code of `ErrWrongSequence`
+const ERR_WRONG_SEQUENCE: u32 = 32;
+
+impl Error {
+	/// Returns true if the transaction was rejected because its fee was below the minimum gas
+	/// price of the chain.
+	pub fn is_insufficient_fee(&self) -> bool {
+		matches!(self, Self::TransactionFailed { code, log }
+			if *code == ERR_INSUFFICIENT_FEE && log.contains("insufficient fee"))
+	}
+
+	/// Returns true if the transaction was signed with a sequence that doesn't match the on chain
+	/// sequence of the account.
+	pub fn is_sequence_mismatch(&self) -> bool {
+		matches!(self, Self::TransactionFailed { code, .. } if *code == ERR_WRONG_SEQUENCE)
+	}
+
+	/// Returns true if the transaction ran out of gas.
+	pub fn is_out_of_gas(&self) -> bool {
+		matches!(self, Self::TransactionFailed { code, log }
+			if *code == ERR_OUT_OF_GAS && log.contains("out of gas"))
+	}
 }
 
 impl From<String> for Error {
@@ -81,6 +81,14 @@ pub fn event_is_type_channel(ev: &IbcEvent) -> bool {
 			IbcEvent::OpenConfirmChannel(_) |
 			IbcEvent::CloseInitChannel(_) |
 			IbcEvent::CloseConfirmChannel(_) |
+			IbcEvent::UpgradeInitChannel(_) |
+			IbcEvent::UpgradeTryChannel(_) |
+			IbcEvent::UpgradeAckChannel(_) |
+			IbcEvent::UpgradeConfirmChannel(_) |
+			IbcEvent::UpgradeOpenChannel(_) |
+			IbcEvent::UpgradeTimeoutChannel(_) |
+			IbcEvent::UpgradeCancelChannel(_) |
+			IbcEvent::UpgradeErrorChannel(_) |
 			IbcEvent::SendPacket(_) |
 			IbcEvent::ReceivePacket(_) |
 			IbcEvent::WriteAcknowledgement(_) |
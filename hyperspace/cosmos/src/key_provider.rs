@@ -1,16 +1,45 @@
 use super::client::CosmosClient;
+use crate::error::Error;
 use bech32::{ToBase32, Variant};
-use bip32::{XPrv as ExtendedPrivateKey, XPub as ExtendedPublicKey};
-use primitives::{error::Error, KeyProvider};
-use std::str::FromStr;
+use bip32::XPrv as ExtendedPrivateKey;
+use digest::Digest;
+use k256::ecdsa::{signature::Signer as _, Signature, SigningKey};
+use primitives::{keys::SigningBackend, KeyProvider};
+use ripemd::Ripemd160;
+use std::{fmt, str::FromStr, sync::Arc};
 use tendermint::account::Id as AccountId;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// A [`SigningBackend`] holding a secp256k1 private key in memory.
+pub struct Secp256k1Signer {
+	signing_key: SigningKey,
+	public_key: Vec<u8>,
+}
+
+impl Secp256k1Signer {
+	pub fn new(private_key: &ExtendedPrivateKey) -> Result<Self, Error> {
+		let signing_key = SigningKey::from_bytes(private_key.private_key().to_bytes().as_slice())
+			.map_err(|e| Error::from(e.to_string()))?;
+		Ok(Self { signing_key, public_key: private_key.public_key().to_bytes().to_vec() })
+	}
+}
+
+impl SigningBackend for Secp256k1Signer {
+	fn public_key(&self) -> &[u8] {
+		&self.public_key
+	}
+
+	fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, primitives::error::Error> {
+		let signature: Signature = self.signing_key.sign(payload);
+		Ok(signature.as_ref().to_vec())
+	}
+}
+
+#[derive(Clone)]
 pub struct KeyEntry {
-	/// Public key
-	pub public_key: ExtendedPublicKey,
-	/// Private key
-	pub private_key: ExtendedPrivateKey,
+	/// Compressed public key
+	pub public_key: Vec<u8>,
+	/// Signs transactions on behalf of the account
+	pub signer: Arc<dyn SigningBackend>,
 	/// Account Bech32 format
 	pub account: String,
 	/// Address
@@ -19,12 +48,31 @@ pub struct KeyEntry {
 
 impl KeyEntry {
 	pub fn new(
-		public_key: ExtendedPublicKey,
-		private_key: ExtendedPrivateKey,
+		public_key: Vec<u8>,
+		signer: Arc<dyn SigningBackend>,
 		account: String,
 		address: Vec<u8>,
 	) -> Self {
-		Self { public_key, private_key, account, address }
+		Self { public_key, signer, account, address }
+	}
+
+	/// Creates the entry of the account controlled by the signer
+	pub fn from_signer(signer: Arc<dyn SigningBackend>, prefix: &str) -> Result<Self, Error> {
+		let public_key = signer.public_key().to_vec();
+		let sha256 = sha2::Sha256::digest(&public_key);
+		let public_key_hash: [u8; 20] = Ripemd160::digest(sha256).into();
+		let account = bech32::encode(prefix, public_key_hash.to_base32(), Variant::Bech32)
+			.map_err(|e| Error::from(format!("Could not encode account id {e}")))?;
+		Ok(Self { public_key, signer, account, address: public_key_hash.into() })
+	}
+}
+
+impl fmt::Debug for KeyEntry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("KeyEntry")
+			.field("public_key", &hex::encode(&self.public_key))
+			.field("account", &self.account)
+			.finish()
 	}
 }
 
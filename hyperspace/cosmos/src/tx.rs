@@ -8,22 +8,82 @@ use super::{
 use crate::error::Error;
 use core::time::Duration;
 use futures::TryFutureExt;
-use ibc::core::ics24_host::identifier::ChainId;
+use ibc::core::{
+	ics02_client::msgs::{
+		create_client::TYPE_URL as CREATE_CLIENT_TYPE_URL,
+		update_client::TYPE_URL as UPDATE_CLIENT_TYPE_URL,
+	},
+	ics03_connection::msgs::{
+		conn_open_ack::TYPE_URL as CONN_OPEN_ACK_TYPE_URL,
+		conn_open_confirm::TYPE_URL as CONN_OPEN_CONFIRM_TYPE_URL,
+		conn_open_init::TYPE_URL as CONN_OPEN_INIT_TYPE_URL,
+		conn_open_try::TYPE_URL as CONN_OPEN_TRY_TYPE_URL,
+	},
+	ics04_channel::msgs::{
+		acknowledgement::TYPE_URL as ACK_PACKET_TYPE_URL,
+		chan_close_confirm::TYPE_URL as CHAN_CLOSE_CONFIRM_TYPE_URL,
+		chan_close_init::TYPE_URL as CHAN_CLOSE_INIT_TYPE_URL,
+		chan_open_ack::TYPE_URL as CHAN_OPEN_ACK_TYPE_URL,
+		chan_open_confirm::TYPE_URL as CHAN_OPEN_CONFIRM_TYPE_URL,
+		chan_open_init::TYPE_URL as CHAN_OPEN_INIT_TYPE_URL,
+		chan_open_try::TYPE_URL as CHAN_OPEN_TRY_TYPE_URL,
+		recv_packet::TYPE_URL as RECV_PACKET_TYPE_URL,
+		timeout::TYPE_URL as TIMEOUT_PACKET_TYPE_URL,
+		timeout_on_close::TYPE_URL as TIMEOUT_ON_CLOSE_PACKET_TYPE_URL,
+	},
+	ics24_host::identifier::ChainId,
+};
 use ibc_proto::{
 	cosmos::{
 		auth::v1beta1::BaseAccount,
+		base::v1beta1::{Coin, DecCoin},
 		tx::v1beta1::{
 			service_client::ServiceClient, Fee, SimulateRequest, SimulateResponse, Tx, TxRaw,
 		},
 	},
 	google::protobuf::Any,
+	ibc::core::{
+		channel::v1::{
+			MsgAcknowledgement, MsgChannelCloseConfirm, MsgChannelCloseInit, MsgChannelOpenAck,
+			MsgChannelOpenConfirm, MsgChannelOpenInit, MsgChannelOpenTry, MsgRecvPacket,
+			MsgTimeout, MsgTimeoutOnClose,
+		},
+		client::v1::{MsgCreateClient, MsgUpdateClient},
+		connection::v1::{
+			MsgConnectionOpenAck, MsgConnectionOpenConfirm, MsgConnectionOpenInit,
+			MsgConnectionOpenTry,
+		},
+	},
 };
 use prost::Message;
 use tendermint::Hash;
 use tendermint_rpc::{
-	endpoint::tx::Response as TxResponse, query::Query, Client, Order, Url, WebSocketClient,
+	endpoint::tx::Response as TxResponse, query::Query, Client, HttpClient, Order, Url,
+	WebSocketClient,
 };
 
+/// Default multiplier applied to the gas used by a simulated transaction
+pub const DEFAULT_GAS_MULTIPLIER: f64 = 1.1;
+/// Multiplier applied to the gas price (or the gas multiplier) when a transaction is retried
+/// after failing with an insufficient fee (or running out of gas)
+pub const FEE_BUMP_MULTIPLIER: f64 = 1.5;
+/// ABCI query path of the feemarket module's gas price query
+const FEEMARKET_GAS_PRICE_PATH: &str = "/feemarket.feemarket.v1.Query/GasPrice";
+
+/// Request type of the feemarket module's `GasPrice` query
+#[derive(Clone, PartialEq, Message)]
+pub struct GasPriceRequest {
+	#[prost(string, tag = "1")]
+	pub denom: String,
+}
+
+/// Response type of the feemarket module's `GasPrice` query
+#[derive(Clone, PartialEq, Message)]
+pub struct GasPriceResponse {
+	#[prost(message, optional, tag = "1")]
+	pub price: Option<DecCoin>,
+}
+
 pub fn sign_tx(
 	key: KeyEntry,
 	chain_id: ChainId,
@@ -57,6 +117,47 @@ pub fn sign_tx(
 	Ok((tx, tx_raw, tx_bytes))
 }
 
+/// Replaces the signer of an IBC message, so that it can be submitted by another account.
+/// Returns `None` for messages that are not supported.
+pub fn set_msg_signer(msg: &Any, signer: &str) -> Option<Any> {
+	fn set<M: Message + Default>(
+		value: &[u8],
+		signer: &str,
+		field: impl FnOnce(&mut M) -> &mut String,
+	) -> Option<Vec<u8>> {
+		let mut msg = M::decode(value).ok()?;
+		*field(&mut msg) = signer.to_string();
+		Some(msg.encode_to_vec())
+	}
+
+	let value = &msg.value[..];
+	let value = match msg.type_url.as_str() {
+		CREATE_CLIENT_TYPE_URL => set(value, signer, |m: &mut MsgCreateClient| &mut m.signer),
+		UPDATE_CLIENT_TYPE_URL => set(value, signer, |m: &mut MsgUpdateClient| &mut m.signer),
+		CONN_OPEN_INIT_TYPE_URL =>
+			set(value, signer, |m: &mut MsgConnectionOpenInit| &mut m.signer),
+		CONN_OPEN_TRY_TYPE_URL => set(value, signer, |m: &mut MsgConnectionOpenTry| &mut m.signer),
+		CONN_OPEN_ACK_TYPE_URL => set(value, signer, |m: &mut MsgConnectionOpenAck| &mut m.signer),
+		CONN_OPEN_CONFIRM_TYPE_URL =>
+			set(value, signer, |m: &mut MsgConnectionOpenConfirm| &mut m.signer),
+		CHAN_OPEN_INIT_TYPE_URL => set(value, signer, |m: &mut MsgChannelOpenInit| &mut m.signer),
+		CHAN_OPEN_TRY_TYPE_URL => set(value, signer, |m: &mut MsgChannelOpenTry| &mut m.signer),
+		CHAN_OPEN_ACK_TYPE_URL => set(value, signer, |m: &mut MsgChannelOpenAck| &mut m.signer),
+		CHAN_OPEN_CONFIRM_TYPE_URL =>
+			set(value, signer, |m: &mut MsgChannelOpenConfirm| &mut m.signer),
+		CHAN_CLOSE_INIT_TYPE_URL => set(value, signer, |m: &mut MsgChannelCloseInit| &mut m.signer),
+		CHAN_CLOSE_CONFIRM_TYPE_URL =>
+			set(value, signer, |m: &mut MsgChannelCloseConfirm| &mut m.signer),
+		RECV_PACKET_TYPE_URL => set(value, signer, |m: &mut MsgRecvPacket| &mut m.signer),
+		ACK_PACKET_TYPE_URL => set(value, signer, |m: &mut MsgAcknowledgement| &mut m.signer),
+		TIMEOUT_PACKET_TYPE_URL => set(value, signer, |m: &mut MsgTimeout| &mut m.signer),
+		TIMEOUT_ON_CLOSE_PACKET_TYPE_URL =>
+			set(value, signer, |m: &mut MsgTimeoutOnClose| &mut m.signer),
+		_ => None,
+	}?;
+	Some(Any { type_url: msg.type_url.clone(), value })
+}
+
 pub async fn simulate_tx(
 	grpc_url: Url,
 	tx: Tx,
@@ -82,11 +183,71 @@ pub async fn simulate_tx(
 	Ok(response)
 }
 
+/// Computes the fee of a transaction from the gas it used in simulation. The gas limit is the
+/// simulated gas scaled by `gas_multiplier` and capped at `max_gas`. Fails if the simulated gas
+/// alone exceeds `max_gas`, since the transaction would run out of gas.
+pub fn calculate_fee(
+	gas_used: u64,
+	gas_multiplier: f64,
+	max_gas: u64,
+	gas_price: f64,
+	fee_denom: &str,
+	fee_granter: &str,
+) -> Result<Fee, Error> {
+	if gas_used > max_gas {
+		return Err(Error::from(format!(
+			"Simulated gas {gas_used} exceeds the configured max gas {max_gas}"
+		)))
+	}
+	let gas_limit = ((gas_used as f64 * gas_multiplier).ceil() as u64).min(max_gas);
+	let amount = (gas_limit as f64 * gas_price).ceil() as u128;
+	Ok(Fee {
+		amount: vec![Coin { denom: fee_denom.to_string(), amount: amount.to_string() }],
+		gas_limit,
+		payer: "".to_string(),
+		granter: fee_granter.to_string(),
+	})
+}
+
+/// Queries the current minimum gas price of `denom` from the chain's feemarket module.
+pub async fn query_gas_price(rpc_client: &HttpClient, denom: &str) -> Result<f64, Error> {
+	let data = GasPriceRequest { denom: denom.to_string() }.encode_to_vec();
+	let response = rpc_client
+		.abci_query(Some(FEEMARKET_GAS_PRICE_PATH.to_string()), data, None, false)
+		.await
+		.map_err(|e| Error::RpcError(format!("failed to query gas price {e:?}")))?;
+	if !response.code.is_ok() {
+		return Err(Error::from(format!(
+			"Gas price query failed with code {:?} and log {:?}",
+			response.code, response.log
+		)))
+	}
+	let price = GasPriceResponse::decode(response.value.as_slice())?
+		.price
+		.ok_or_else(|| Error::from("gas price missing from feemarket response".to_string()))?;
+	parse_dec(&price.amount)
+}
+
+/// Parses an `sdk.Dec`. These are encoded as an integer scaled by 10^18 in protobuf messages,
+/// and as a decimal string elsewhere.
+fn parse_dec(value: &str) -> Result<f64, Error> {
+	let parsed = value
+		.parse::<f64>()
+		.map_err(|e| Error::from(format!("invalid decimal {value}: {e}")))?;
+	Ok(if value.contains('.') { parsed } else { parsed / 1e18 })
+}
+
 pub async fn broadcast_tx(rpc_client: &WebSocketClient, tx_bytes: Vec<u8>) -> Result<Hash, Error> {
 	let response = rpc_client
 		.broadcast_tx_sync(tx_bytes)
 		.await
 		.map_err(|e| Error::from(format!("failed to broadcast transaction {e:?}")))?;
+	if response.code.is_err() {
+		return Err(Error::TransactionFailed {
+			code: response.code.value(),
+			log: response.log.to_string(),
+		})
+	}
 	Ok(response.hash)
 }
 
@@ -124,7 +285,11 @@ pub async fn confirm_tx(rpc_client: &WebSocketClient, tx_hash: Hash) -> Result<H
 
 	let response_code = response.tx_result.code;
 	if response_code.is_err() {
-		return Err(Error::from(format!("transaction {tx_hash} failed with code {response_code:?}")))
+		log::debug!(target: "hyperspace_cosmos", "Transaction {tx_hash} failed with code {response_code:?}");
+		return Err(Error::TransactionFailed {
+			code: response_code.value(),
+			log: response.tx_result.log.to_string(),
+		})
 	}
 	Ok(response.hash)
 }
@@ -147,3 +312,53 @@ pub fn encoded_tx_metrics(
 
 	Ok((total_len, envelope_len))
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn calculate_fee_applies_multiplier_and_cap() {
+		let fee = calculate_fee(100_000, 1.5, 400_000, 0.25, "stake", "").unwrap();
+		assert_eq!(fee.gas_limit, 150_000);
+		assert_eq!(
+			fee.amount,
+			vec![Coin { denom: "stake".to_string(), amount: "37500".to_string() }]
+		);
+		assert_eq!(fee.granter, "");
+
+		let fee = calculate_fee(300_000, 1.5, 400_000, 0.25, "stake", "cosmos1granter").unwrap();
+		assert_eq!(fee.gas_limit, 400_000);
+		assert_eq!(fee.amount[0].amount, "100000");
+		assert_eq!(fee.granter, "cosmos1granter");
+
+		// the transaction would run out of gas even at the cap
+		assert!(calculate_fee(1_000_000, 1.5, 400_000, 0.25, "stake", "").is_err());
+	}
+
+	#[test]
+	fn set_msg_signer_replaces_the_signer() {
+		let msg = MsgUpdateClient {
+			client_id: "07-tendermint-0".to_string(),
+			client_message: None,
+			signer: "cosmos1primary".to_string(),
+		};
+		let any = Any { type_url: UPDATE_CLIENT_TYPE_URL.to_string(), value: msg.encode_to_vec() };
+		let resigned = set_msg_signer(&any, "cosmos1secondary").unwrap();
+		assert_eq!(resigned.type_url, UPDATE_CLIENT_TYPE_URL);
+		let decoded = MsgUpdateClient::decode(resigned.value.as_slice()).unwrap();
+		assert_eq!(decoded.signer, "cosmos1secondary");
+		assert_eq!(decoded.client_id, msg.client_id);
+
+		let unsupported =
+			Any { type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(), value: vec![] };
+		assert!(set_msg_signer(&unsupported, "cosmos1secondary").is_none());
+	}
+
+	#[test]
+	fn parse_dec_handles_both_encodings() {
+		assert_eq!(parse_dec("0.025").unwrap(), 0.025);
+		assert_eq!(parse_dec("25000000000000000").unwrap(), 0.025);
+		assert!(parse_dec("abc").is_err());
+	}
+}
@@ -14,9 +14,30 @@
 
 use super::*;
 use crate::register;
-use ibc::{core::ics24_host::identifier::ClientId, Height};
+use ibc::{
+	core::{ics04_channel::packet::Packet, ics24_host::identifier::ClientId},
+	Height,
+};
+use prometheus::core::{MetricVec, MetricVecBuilder};
 use std::collections::HashMap;
 
+/// Variable labels of the per channel metric families. The channel and port are the packet's
+/// source ones, the counterparty is the metrics prefix of the other chain.
+const CHANNEL_LABELS: &[&str] = &["channel_id", "port_id", "counterparty"];
+
+/// Returns the metric of a per channel family for the given packet.
+pub fn with_packet_labels<T: MetricVecBuilder>(
+	family: &MetricVec<T>,
+	packet: &Packet,
+	counterparty: &str,
+) -> T::M {
+	family.with_label_values(&[
+		&packet.source_channel.to_string(),
+		packet.source_port.as_str(),
+		counterparty,
+	])
+}
+
 /// Optional shareable link to basic metrics.
 #[derive(Clone, Default)]
 pub struct MetricsLink(Option<Metrics>);
@@ -50,7 +71,7 @@ pub struct LightClientMetrics {
 impl LightClientMetrics {
 	pub fn register(
 		client_id: &ClientId,
-		prefix: &str,
+		labels: &HashMap<String, String>,
 		registry: &Registry,
 	) -> Result<Self, PrometheusError> {
 		Ok(Self {
@@ -60,8 +81,8 @@ impl LightClientMetrics {
 						"hyperspace_light_client_revision_height",
 						"The height of the last trusted state",
 					)
-					.const_label("client_id", client_id.to_string())
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone())
+					.const_label("client_id", client_id.to_string()),
 				)?,
 				registry,
 			)?,
@@ -71,8 +92,8 @@ impl LightClientMetrics {
 						"hyperspace_light_client_revision",
 						"The revision of the last trusted state",
 					)
-					.const_label("client_id", client_id.to_string())
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone())
+					.const_label("client_id", client_id.to_string()),
 				)?,
 				registry,
 			)?,
@@ -82,8 +103,8 @@ impl LightClientMetrics {
 						"hyperspace_number_of_received_header_updates",
 						"Total number of header updates received",
 					)
-					.const_label("client_id", client_id.to_string())
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone())
+					.const_label("client_id", client_id.to_string()),
 				)?,
 				registry,
 			)?,
@@ -118,6 +139,38 @@ pub struct Metrics {
 	pub number_of_undelivered_packets: Gauge<U64>,
 	/// Number of undelivered acknowledgements over time.
 	pub number_of_undelivered_acknowledgements: Gauge<U64>,
+
+	/// Total number of "send packet" events received, per channel.
+	pub send_packet_events_per_channel: CounterVec<U64>,
+	/// Total number of "receive packet" events received, per channel.
+	pub receive_packet_events_per_channel: CounterVec<U64>,
+	/// Total number of "acknowledge packet" events received, per channel.
+	pub acknowledge_packet_events_per_channel: CounterVec<U64>,
+	/// Total number of "timeout packet" events received, per channel.
+	pub timeout_packet_events_per_channel: CounterVec<U64>,
+
+	/// "Receive packet" events on the counterparty's side, per channel.
+	pub counterparty_receive_packet_events_per_channel: Option<CounterVec<U64>>,
+	/// "Acknowledge packet" events on the counterparty's side, per channel.
+	pub counterparty_acknowledge_packet_events_per_channel: Option<CounterVec<U64>>,
+
+	/// Total number of sent packets, per channel.
+	pub sent_packets_per_channel: CounterVec<U64>,
+	/// Total number of sent acknowledgments, per channel.
+	pub sent_acknowledgments_per_channel: CounterVec<U64>,
+	/// Total number of timed out packets, per channel.
+	pub sent_timeout_packets_per_channel: CounterVec<U64>,
+
+	/// Number of undelivered packets over time, per channel.
+	pub undelivered_packets_per_channel: GaugeVec<U64>,
+	/// Number of undelivered acknowledgements over time, per channel.
+	pub undelivered_acknowledgements_per_channel: GaugeVec<U64>,
+
+	/// Seconds until the trusting period of a client on the counterparty expires.
+	pub client_seconds_until_expiry: GaugeVec<I64>,
+	/// Balance of the relayer's signer, in the chain's smallest denomination.
+	pub signer_balance: Gauge<F64>,
+
 	/// Gas cost for every sent tx bundle.
 	pub gas_cost_for_sent_tx_bundle: Histogram,
 	/// Transaction length (in bytes) for every sent tx bundle.
@@ -146,10 +199,37 @@ pub struct Metrics {
 
 	/// Metrics prefix.
 	pub prefix: String,
+	/// Metrics prefix of the counterparty chain.
+	pub counterparty: String,
+	/// Constant labels of every metric, i.e. the prefix and the relayed path, if any.
+	pub labels: HashMap<String, String>,
 }
 
 impl Metrics {
 	pub fn register(prefix: &str, registry: &Registry) -> Result<Self, PrometheusError> {
+		Self::register_with_labels(prefix, None, registry)
+	}
+
+	/// Registers the metrics of a chain relaying on one of the paths of a multi-path relayer. They
+	/// are labelled with the name of the path, so that a chain shared by several paths reports
+	/// each of them separately.
+	pub fn register_for_path(
+		prefix: &str,
+		path: &str,
+		registry: &Registry,
+	) -> Result<Self, PrometheusError> {
+		Self::register_with_labels(prefix, Some(path), registry)
+	}
+
+	fn register_with_labels(
+		prefix: &str,
+		path: Option<&str>,
+		registry: &Registry,
+	) -> Result<Self, PrometheusError> {
+		let mut labels = HashMap::from([("name".to_string(), prefix.to_string())]);
+		if let Some(path) = path {
+			labels.insert("path".to_string(), path.to_string());
+		}
 		Ok(Self {
 			number_of_received_send_packets: register(
 				Counter::with_opts(
@@ -157,7 +237,7 @@ impl Metrics {
 						format!("hyperspace_{prefix}_number_of_send_packet_events"),
 						"Total number of 'send packet' events.",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -167,7 +247,7 @@ impl Metrics {
 						format!("hyperspace_{prefix}_number_of_receive_packet_events"),
 						"Total number of 'receive packet' events.",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -177,7 +257,7 @@ impl Metrics {
 						"hyperspace_number_of_acknowledge_packet_events".to_string(),
 						"Total number of 'acknowledge packet' events.",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -187,7 +267,7 @@ impl Metrics {
 						"hyperspace_number_of_timeout_packet_events".to_string(),
 						"Total number of 'timeout packet' events.",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -199,7 +279,7 @@ impl Metrics {
 						"hyperspace_number_of_sent_packets".to_string(),
 						"Total number of sent packets",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -209,7 +289,7 @@ impl Metrics {
 						"hyperspace_number_of_sent_acknowledgments".to_string(),
 						"Total number of sent acknowledgments",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -219,7 +299,7 @@ impl Metrics {
 						"hyperspace_number_of_timed_out_packets".to_string(),
 						"Total number of timed out packets",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -229,7 +309,7 @@ impl Metrics {
 						"hyperspace_number_of_undelivered_packets".to_string(),
 						"Number of undelivered packets over time",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -239,7 +319,126 @@ impl Metrics {
 						"hyperspace_number_of_undelivered_acknowledgements".to_string(),
 						"Number of undelivered acknowledgements over time",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
+				)?,
+				registry,
+			)?,
+			send_packet_events_per_channel: register(
+				CounterVec::new(
+					Opts::new(
+						"hyperspace_send_packet_events_per_channel",
+						"Total number of 'send packet' events, per channel.",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			receive_packet_events_per_channel: register(
+				CounterVec::new(
+					Opts::new(
+						"hyperspace_receive_packet_events_per_channel",
+						"Total number of 'receive packet' events, per channel.",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			acknowledge_packet_events_per_channel: register(
+				CounterVec::new(
+					Opts::new(
+						"hyperspace_acknowledge_packet_events_per_channel",
+						"Total number of 'acknowledge packet' events, per channel.",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			timeout_packet_events_per_channel: register(
+				CounterVec::new(
+					Opts::new(
+						"hyperspace_timeout_packet_events_per_channel",
+						"Total number of 'timeout packet' events, per channel.",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			counterparty_receive_packet_events_per_channel: None,
+			counterparty_acknowledge_packet_events_per_channel: None,
+			sent_packets_per_channel: register(
+				CounterVec::new(
+					Opts::new(
+						"hyperspace_sent_packets_per_channel",
+						"Total number of sent packets, per channel",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			sent_acknowledgments_per_channel: register(
+				CounterVec::new(
+					Opts::new(
+						"hyperspace_sent_acknowledgments_per_channel",
+						"Total number of sent acknowledgments, per channel",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			sent_timeout_packets_per_channel: register(
+				CounterVec::new(
+					Opts::new(
+						"hyperspace_timed_out_packets_per_channel",
+						"Total number of timed out packets, per channel",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			undelivered_packets_per_channel: register(
+				GaugeVec::new(
+					Opts::new(
+						"hyperspace_undelivered_packets_per_channel",
+						"Number of undelivered packets over time, per channel",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			undelivered_acknowledgements_per_channel: register(
+				GaugeVec::new(
+					Opts::new(
+						"hyperspace_undelivered_acknowledgements_per_channel",
+						"Number of undelivered acknowledgements over time, per channel",
+					)
+					.const_labels(labels.clone()),
+					CHANNEL_LABELS,
+				)?,
+				registry,
+			)?,
+			client_seconds_until_expiry: register(
+				GaugeVec::new(
+					Opts::new(
+						"hyperspace_client_seconds_until_expiry",
+						"Seconds until the trusting period of the client expires",
+					)
+					.const_labels(labels.clone()),
+					&["client_id", "counterparty"],
+				)?,
+				registry,
+			)?,
+			signer_balance: register(
+				Gauge::with_opts(
+					Opts::new("hyperspace_signer_balance", "Balance of the relayer's signer")
+						.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -250,7 +449,7 @@ impl Metrics {
 						"Gas cost for every sent tx bundle",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -261,7 +460,7 @@ impl Metrics {
 						"Transaction length for every sent tx bundle",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -273,7 +472,7 @@ impl Metrics {
 						"Time it takes to process a 'send packet' event",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -284,7 +483,7 @@ impl Metrics {
 						"Time it takes to process a 'receive packet' event",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -295,7 +494,7 @@ impl Metrics {
 						"Time it takes to process a 'acknowledge packet' event",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -306,7 +505,7 @@ impl Metrics {
 						"Time it takes to send and receive a packet",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -317,7 +516,7 @@ impl Metrics {
 						"Time it takes to send and receive an acknowledgment",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -328,7 +527,7 @@ impl Metrics {
 						"Time it takes to send and receive a timeout packet",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -339,7 +538,7 @@ impl Metrics {
 						"Average time between client updates",
 					)
 					.buckets(vec![1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0, 1000000.0])
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
@@ -349,11 +548,13 @@ impl Metrics {
 						"hyperspace_latest_processed_height".to_string(),
 						"Latest processed finalized height",
 					)
-					.const_label("name", prefix.to_string()),
+					.const_labels(labels.clone()),
 				)?,
 				registry,
 			)?,
 			prefix: prefix.to_string(),
+			counterparty: String::new(),
+			labels,
 		})
 	}
 
@@ -367,6 +568,18 @@ impl Metrics {
 			Some(self.number_of_received_receive_packets.clone());
 		other.counterparty_number_of_received_acknowledgments =
 			Some(self.number_of_received_acknowledge_packets.clone());
+
+		self.counterparty_receive_packet_events_per_channel =
+			Some(other.receive_packet_events_per_channel.clone());
+		self.counterparty_acknowledge_packet_events_per_channel =
+			Some(other.acknowledge_packet_events_per_channel.clone());
+		self.counterparty = other.prefix.clone();
+
+		other.counterparty_receive_packet_events_per_channel =
+			Some(self.receive_packet_events_per_channel.clone());
+		other.counterparty_acknowledge_packet_events_per_channel =
+			Some(self.acknowledge_packet_events_per_channel.clone());
+		other.counterparty = self.prefix.clone();
 	}
 
 	pub fn counterparty_number_of_received_packets(&self) -> &Counter<U64> {
@@ -381,6 +594,18 @@ impl Metrics {
             .expect("counterparty_number_of_received_acknowledgments is not set. Perhaps you forgot to call `link_with_counterparty_metrics`?")
 	}
 
+	pub fn counterparty_receive_packet_events_per_channel(&self) -> &CounterVec<U64> {
+		self.counterparty_receive_packet_events_per_channel
+            .as_ref()
+            .expect("counterparty_receive_packet_events_per_channel is not set. Perhaps you forgot to call `link_with_counterparty_metrics`?")
+	}
+
+	pub fn counterparty_acknowledge_packet_events_per_channel(&self) -> &CounterVec<U64> {
+		self.counterparty_acknowledge_packet_events_per_channel
+            .as_ref()
+            .expect("counterparty_acknowledge_packet_events_per_channel is not set. Perhaps you forgot to call `link_with_counterparty_metrics`?")
+	}
+
 	pub fn update_light_client_height(
 		&mut self,
 		client_id: &ClientId,
@@ -397,7 +622,7 @@ impl Metrics {
 			},
 			None => {
 				let light_client_metrics =
-					LightClientMetrics::register(client_id, &self.prefix, registry)?;
+					LightClientMetrics::register(client_id, &self.labels, registry)?;
 				self.light_client_height.insert(client_id.clone(), light_client_metrics);
 				Ok(())
 			},
@@ -408,4 +633,14 @@ impl Metrics {
 		self.latest_processed_height.set(revision_height);
 		Ok(())
 	}
+
+	pub fn update_client_seconds_until_expiry(&self, client_id: &ClientId, seconds: i64) {
+		self.client_seconds_until_expiry
+			.with_label_values(&[client_id.as_str(), &self.counterparty])
+			.set(seconds);
+	}
+
+	pub fn update_signer_balance(&self, balance: u128) {
+		self.signer_balance.set(balance as f64);
+	}
 }
@@ -12,19 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::data::Metrics;
+use crate::data::{with_packet_labels, Metrics};
 use ibc::{
 	core::{
 		ics04_channel::{
 			events::{TimeoutOnClosePacket, TimeoutPacket},
+			msgs::{
+				acknowledgement::MsgAcknowledgement, recv_packet::MsgRecvPacket,
+				timeout::MsgTimeout, timeout_on_close::MsgTimeoutOnClose,
+			},
 			packet::{Packet, Sequence},
 		},
-		ics24_host::identifier::{ChannelId, PortId},
+		ics24_host::identifier::{ChannelId, ClientId, PortId},
 	},
 	events::IbcEvent,
+	protobuf::Protobuf,
 };
 use ibc_proto::google::protobuf::Any;
-use prometheus::{Histogram, Registry};
+use prometheus::{
+	core::{MetricVec, MetricVecBuilder},
+	Histogram, Registry,
+};
 use std::{
 	collections::HashMap,
 	ops::DerefMut,
@@ -111,11 +119,21 @@ impl MetricsHandler {
 			match event {
 				IbcEvent::SendPacket(packet) => {
 					self.metrics.number_of_received_send_packets.inc();
+					self.packet_metric(
+						&self.metrics.send_packet_events_per_channel,
+						&packet.packet,
+					)
+					.inc();
 					let packet_id = packet.packet.clone().into();
 					self.last_sent_packet_time.lock().unwrap().insert(packet_id, Instant::now());
 				},
 				IbcEvent::ReceivePacket(packet) => {
 					self.metrics.number_of_received_receive_packets.inc();
+					self.packet_metric(
+						&self.metrics.receive_packet_events_per_channel,
+						&packet.packet,
+					)
+					.inc();
 					self.observe_last_packet_time(
 						&packet.packet,
 						&self.counterparty_last_sent_packet_time,
@@ -131,6 +149,11 @@ impl MetricsHandler {
 				},
 				IbcEvent::AcknowledgePacket(packet) => {
 					self.metrics.number_of_received_acknowledge_packets.inc();
+					self.packet_metric(
+						&self.metrics.acknowledge_packet_events_per_channel,
+						&packet.packet,
+					)
+					.inc();
 					self.observe_last_packet_time(
 						&packet.packet,
 						&self.counterparty_last_sent_acknowledgment_time,
@@ -140,6 +163,8 @@ impl MetricsHandler {
 				IbcEvent::TimeoutPacket(TimeoutPacket { packet, .. }) |
 				IbcEvent::TimeoutOnClosePacket(TimeoutOnClosePacket { packet, .. }) => {
 					self.metrics.number_of_received_timeouts.inc();
+					self.packet_metric(&self.metrics.timeout_packet_events_per_channel, packet)
+						.inc();
 					self.observe_last_packet_time(
 						packet,
 						&self.counterparty_last_sent_timeout_packet_time,
@@ -180,14 +205,49 @@ impl MetricsHandler {
 					self.metrics
 						.number_of_undelivered_acknowledgements
 						.set(number_of_undelivered_acknowledgements);
+
+					let Ok(msg) = MsgAcknowledgement::decode_vec(&message.value) else {
+						log::warn!("Failed to decode MsgAcknowledgement");
+						continue
+					};
+					let sent = self
+						.packet_metric(&self.metrics.sent_acknowledgments_per_channel, &msg.packet);
+					sent.inc();
+					// Acknowledgements are delivered to the packet's source chain, which labels
+					// its events with our prefix as the counterparty.
+					let received = with_packet_labels(
+						self.metrics.counterparty_acknowledge_packet_events_per_channel(),
+						&msg.packet,
+						&self.metrics.prefix,
+					);
+					self.packet_metric(
+						&self.metrics.undelivered_acknowledgements_per_channel,
+						&msg.packet,
+					)
+					.set(sent.get().saturating_sub(received.get()));
 				},
 				"/ibc.core.channel.v1.MsgRecvPacket" => {
+					self.metrics.number_of_sent_packets.inc();
 					self.metrics.number_of_undelivered_packets.set(
 						self.metrics.number_of_sent_packets.get().saturating_sub(
 							self.metrics.counterparty_number_of_received_packets().get(),
 						),
 					);
-					self.metrics.number_of_sent_packets.inc();
+
+					let Ok(msg) = MsgRecvPacket::decode_vec(&message.value) else {
+						log::warn!("Failed to decode MsgRecvPacket");
+						continue
+					};
+					let sent =
+						self.packet_metric(&self.metrics.sent_packets_per_channel, &msg.packet);
+					sent.inc();
+					let received = with_packet_labels(
+						self.metrics.counterparty_receive_packet_events_per_channel(),
+						&msg.packet,
+						&self.metrics.prefix,
+					);
+					self.packet_metric(&self.metrics.undelivered_packets_per_channel, &msg.packet)
+						.set(sent.get().saturating_sub(received.get()));
 				},
 				_ => (),
 			}
@@ -213,8 +273,29 @@ impl MetricsHandler {
 	pub async fn handle_timeouts(&self, timeouts: &[Any]) {
 		for message in timeouts {
 			match message.type_url.as_str() {
-				"/ibc.core.channel.v1.MsgTimeout" | "/ibc.core.channel.v1.MsgTimeoutOnClose" => {
+				"/ibc.core.channel.v1.MsgTimeout" => {
+					self.metrics.number_of_sent_timeout_packets.inc();
+					match MsgTimeout::decode_vec(&message.value) {
+						Ok(msg) => self
+							.packet_metric(
+								&self.metrics.sent_timeout_packets_per_channel,
+								&msg.packet,
+							)
+							.inc(),
+						Err(_) => log::warn!("Failed to decode MsgTimeout"),
+					}
+				},
+				"/ibc.core.channel.v1.MsgTimeoutOnClose" => {
 					self.metrics.number_of_sent_timeout_packets.inc();
+					match MsgTimeoutOnClose::decode_vec(&message.value) {
+						Ok(msg) => self
+							.packet_metric(
+								&self.metrics.sent_timeout_packets_per_channel,
+								&msg.packet,
+							)
+							.inc(),
+						Err(_) => log::warn!("Failed to decode MsgTimeoutOnClose"),
+					}
 				},
 				_ => (),
 			}
@@ -227,6 +308,21 @@ impl MetricsHandler {
 		self.metrics.transaction_length_for_sent_tx_bundle.observe(batch_size as f64);
 	}
 
+	/// Reports the time left until the trusting period of the given client expires.
+	pub fn handle_client_expiry(&self, client_id: &ClientId, seconds_until_expiry: i64) {
+		self.metrics.update_client_seconds_until_expiry(client_id, seconds_until_expiry);
+	}
+
+	pub fn handle_signer_balance(&self, balance: u128) {
+		self.metrics.update_signer_balance(balance);
+	}
+
+	/// Returns the metric of a per channel family for the given packet, labelled with our
+	/// counterparty.
+	fn packet_metric<T: MetricVecBuilder>(&self, family: &MetricVec<T>, packet: &Packet) -> T::M {
+		with_packet_labels(family, packet, &self.metrics.counterparty)
+	}
+
 	pub fn observe_last_packet_time(
 		&self,
 		packet: &Packet,
@@ -257,3 +353,146 @@ fn observe_delta_time(maybe_time: &mut Option<Instant>, time_metrics: &Histogram
 		*maybe_time = Some(now);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::{
+		core::{
+			ics04_channel::{
+				events::{AcknowledgePacket, ReceivePacket},
+				msgs::acknowledgement::Acknowledgement,
+			},
+			ics23_commitment::commitment::CommitmentProofBytes,
+		},
+		proofs::Proofs,
+		signer::Signer,
+		tx_msg::Msg,
+		Height,
+	};
+	use std::str::FromStr;
+
+	/// Handlers of two linked chains, `chain_a` and `chain_b`, sharing a registry.
+	fn linked_handlers() -> (MetricsHandler, MetricsHandler) {
+		let registry = Registry::new();
+		let mut handler_a =
+			MetricsHandler::new(registry.clone(), Metrics::register("chain_a", &registry).unwrap());
+		let mut handler_b =
+			MetricsHandler::new(registry.clone(), Metrics::register("chain_b", &registry).unwrap());
+		handler_a.link_with_counterparty(&mut handler_b);
+		(handler_a, handler_b)
+	}
+
+	/// Packet sent from `chain_a` over channel-0 to channel-1 of `chain_b`.
+	fn packet(sequence: u64) -> Packet {
+		Packet {
+			sequence: sequence.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(0),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(1),
+			data: vec![1],
+			timeout_height: Height::new(0, 100),
+			..Default::default()
+		}
+	}
+
+	fn proofs() -> Proofs {
+		let proof = CommitmentProofBytes::try_from(vec![1]).unwrap();
+		Proofs::new(proof, None, None, None, Height::new(0, 1)).unwrap()
+	}
+
+	fn recv_packet(sequence: u64) -> Any {
+		MsgRecvPacket::new(packet(sequence), proofs(), Signer::from_str("relayer").unwrap())
+			.to_any()
+	}
+
+	fn acknowledgement(sequence: u64) -> Any {
+		MsgAcknowledgement::new(
+			packet(sequence),
+			Acknowledgement::from_bytes(vec![1]),
+			proofs(),
+			Signer::from_str("relayer").unwrap(),
+		)
+		.to_any()
+	}
+
+	/// Per channel metric of `family` for the packets of `chain_a`, as labelled by its handler.
+	fn channel_value(handler: &MetricsHandler, family: &str) -> u64 {
+		handler
+			.registry
+			.gather()
+			.iter()
+			.filter(|metric_family| metric_family.get_name() == family)
+			.flat_map(|metric_family| metric_family.get_metric())
+			.find(|metric| {
+				let labels = metric
+					.get_label()
+					.iter()
+					.map(|label| (label.get_name(), label.get_value()))
+					.collect::<HashMap<_, _>>();
+				labels.get("name") == Some(&handler.metrics.prefix.as_str()) &&
+					labels.get("channel_id") == Some(&"channel-0") &&
+					labels.get("port_id") == Some(&"transfer") &&
+					labels.get("counterparty") == Some(&handler.metrics.counterparty.as_str())
+			})
+			.map(|metric| {
+				if metric.has_gauge() {
+					metric.get_gauge().get_value() as u64
+				} else {
+					metric.get_counter().get_value() as u64
+				}
+			})
+			.unwrap_or_else(|| panic!("{family} isn't reported for channel-0"))
+	}
+
+	#[tokio::test]
+	async fn reports_undelivered_packets_per_channel() {
+		let (mut handler_a, mut handler_b) = linked_handlers();
+
+		handler_a.handle_messages(&[recv_packet(1)]).await;
+		assert_eq!(channel_value(&handler_a, "hyperspace_sent_packets_per_channel"), 1);
+		assert_eq!(channel_value(&handler_a, "hyperspace_undelivered_packets_per_channel"), 1);
+		assert_eq!(handler_a.metrics.number_of_undelivered_packets.get(), 1);
+
+		let received =
+			IbcEvent::ReceivePacket(ReceivePacket { height: Height::new(0, 2), packet: packet(1) });
+		handler_b.handle_events(&[received]).await.unwrap();
+		assert_eq!(channel_value(&handler_b, "hyperspace_receive_packet_events_per_channel"), 1);
+
+		handler_a.handle_messages(&[recv_packet(2)]).await;
+		assert_eq!(channel_value(&handler_a, "hyperspace_sent_packets_per_channel"), 2);
+		assert_eq!(channel_value(&handler_a, "hyperspace_undelivered_packets_per_channel"), 1);
+		assert_eq!(handler_a.metrics.number_of_undelivered_packets.get(), 1);
+	}
+
+	#[tokio::test]
+	async fn reports_undelivered_acknowledgements_per_channel() {
+		let (mut handler_a, handler_b) = linked_handlers();
+
+		// acknowledgements of the packets of chain A are relayed from chain B
+		handler_b.handle_messages(&[acknowledgement(1)]).await;
+		assert_eq!(channel_value(&handler_b, "hyperspace_sent_acknowledgments_per_channel"), 1);
+		assert_eq!(
+			channel_value(&handler_b, "hyperspace_undelivered_acknowledgements_per_channel"),
+			1
+		);
+
+		let acknowledged = IbcEvent::AcknowledgePacket(AcknowledgePacket {
+			height: Height::new(0, 2),
+			packet: packet(1),
+		});
+		handler_a.handle_events(&[acknowledged]).await.unwrap();
+		assert_eq!(
+			channel_value(&handler_a, "hyperspace_acknowledge_packet_events_per_channel"),
+			1
+		);
+
+		handler_b.handle_messages(&[acknowledgement(2)]).await;
+		assert_eq!(channel_value(&handler_b, "hyperspace_sent_acknowledgments_per_channel"), 2);
+		assert_eq!(
+			channel_value(&handler_b, "hyperspace_undelivered_acknowledgements_per_channel"),
+			1
+		);
+	}
+}
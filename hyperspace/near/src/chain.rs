@@ -12,72 +12,106 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::pin::Pin;
-
-use futures::Stream;
-use ibc::core::ics03_connection::msgs::{conn_open_ack, conn_open_init};
+use crate::{
+	client::{EXPECTED_BLOCK_TIME, MAX_GAS},
+	error::Error,
+	provider::FinalityEvent,
+	Client,
+};
+use futures::{channel::mpsc, Stream};
+use ibc::core::ics02_client::events::UpdateClient;
 use ibc_proto::google::protobuf::Any;
-use near_primitives::{hash::CryptoHash, transaction::Transaction};
-use primitives::{Chain, IbcProvider};
-use tokio_stream::wrappers::ReceiverStream;
-
-use super::error::Error;
-use crate::Client;
+use pallet_ibc::light_clients::AnyClientMessage;
+use primitives::{Chain, CommonClientState, IbcProvider};
+use prost::Message;
+use std::pin::Pin;
 
 #[async_trait::async_trait]
 impl Chain for Client {
+	fn name(&self) -> &str {
+		&self.name
+	}
+
+	fn block_max_weight(&self) -> u64 {
+		MAX_GAS
+	}
+
+	async fn estimate_weight(&self, messages: Vec<Any>) -> Result<u64, Self::Error> {
+		Ok(self.gas_per_message * messages.len() as u64)
+	}
+
 	async fn finality_notifications(
 		&self,
-	) -> Pin<Box<dyn Stream<Item = <Self as IbcProvider>::FinalityEvent> + Send + Sync>> {
-		let stream = self.indexer.streamer();
-		Box::pin(ReceiverStream::new(stream))
+	) -> Result<
+		Pin<Box<dyn Stream<Item = <Self as IbcProvider>::FinalityEvent> + Send + Sync>>,
+		Error,
+	> {
+		let client = self.clone();
+		let (tx, rx) = mpsc::unbounded();
+		tokio::spawn(async move {
+			let mut last_height = 0;
+			loop {
+				tokio::time::sleep(EXPECTED_BLOCK_TIME).await;
+				match client.final_block().await {
+					Ok(block) if block.header.height > last_height => {
+						last_height = block.header.height;
+						let event =
+							FinalityEvent { height: block.header.height, hash: block.header.hash };
+						if tx.unbounded_send(event).is_err() {
+							break
+						}
+					},
+					Ok(_) => {},
+					Err(e) => {
+						log::warn!(target: "hyperspace_near", "Failed to fetch final block: {e}")
+					},
+				}
+			}
+		});
+		Ok(Box::pin(rx))
 	}
 
-	async fn submit(&self, mut messages: Vec<Any>) -> Result<(), Error> {
-		let update_client_message = messages.remove(0);
-		let mut permissioned_messages = vec![];
-		let mut non_permissioned_messages = vec![];
+	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Error> {
+		let outcome = self.deliver(messages).await?;
+		Ok(outcome.transaction.hash)
+	}
 
-		for msg in messages {
-			if matches!(msg.type_url.as_str(), conn_open_init::TYPE_URL | conn_open_ack::TYPE_URL) {
-				permissioned_messages.push(msg)
-			} else {
-				non_permissioned_messages.push(msg)
-			}
-		}
+	async fn query_client_message(
+		&self,
+		update: UpdateClient,
+	) -> Result<AnyClientMessage, Self::Error> {
+		let header = update
+			.header
+			.ok_or_else(|| Error::from("UpdateClient event has no header".to_string()))?;
+		let any = Any::decode(&*header)
+			.map_err(|e| Error::from(format!("Failed to decode client message: {e:?}")))?;
+		AnyClientMessage::try_from(any)
+			.map_err(|e| Error::from(format!("Failed to decode client message: {e:?}")))
+	}
 
-		let mut messages = vec![update_client_message];
-		if !non_permissioned_messages.is_empty() {
-			messages.extend(non_permissioned_messages.into_iter())
-		}
+	async fn get_proof_height(&self, block_height: ibc::Height) -> ibc::Height {
+		block_height
+	}
 
-		let transaction = Transaction::new(
-			self.signer.clone(),
-			self.public_key(),
-			self.contract_id.clone(),
-			0,
-			CryptoHash::default(),
-		);
+	async fn query_signer_balance(&self) -> Result<Option<u128>, Self::Error> {
+		Ok(Some(self.signer_balance().await?))
+	}
 
-		let gas = 1_000_000_000_000;
-		let deliver_tx = transaction.clone().function_call(
-			"deliver".to_owned(),
-			serde_json::to_vec(&messages)?,
-			gas,
-			0,
-		);
-		// TODO: handle intermediate receipts
-		let _ = self.send_transaction(deliver_tx).await?;
+	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error> {
+		log::error!(target: "hyperspace_near", "{error:?}");
+		Ok(())
+	}
+
+	fn common_state(&self) -> &CommonClientState {
+		&self.common_state
+	}
+
+	fn common_state_mut(&mut self) -> &mut CommonClientState {
+		&mut self.common_state
+	}
 
-		if !permissioned_messages.is_empty() {
-			let deliver_permissioned_tx = transaction.clone().function_call(
-				"deliver_permissioned".to_owned(),
-				serde_json::to_vec(&permissioned_messages)?,
-				gas,
-				0,
-			);
-			let _ = self.send_transaction(deliver_permissioned_tx).await?;
-		}
+	async fn reconnect(&mut self) -> anyhow::Result<()> {
+		self.rpc_client = near_jsonrpc_client::JsonRpcClient::connect(&self.rpc_url);
 		Ok(())
 	}
 }
@@ -0,0 +1,326 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{error::Error, key_provider::Ed25519Signer};
+use ibc::core::{
+	ics23_commitment::commitment::CommitmentPrefix,
+	ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+};
+use ibc_proto::google::protobuf::Any;
+use near_crypto::{ED25519PublicKey, PublicKey, Signature};
+use near_jsonrpc_client::{
+	methods::{
+		broadcast_tx_commit::RpcBroadcastTxCommitRequest,
+		query::{RpcQueryRequest, RpcQueryResponse},
+	},
+	JsonRpcClient,
+};
+use near_jsonrpc_primitives::types::query::QueryResponseKind;
+use near_primitives::{
+	transaction::{Action, FunctionCallAction, SignedTransaction, Transaction},
+	types::{AccountId, BlockHeight, BlockId, BlockReference, Finality, FunctionArgs, Gas},
+	views::{FinalExecutionOutcomeView, FinalExecutionStatus, QueryRequest},
+};
+use primitives::{
+	keys::{load_key, Key, KeySource, SigningBackend},
+	CommonClientConfig, CommonClientState,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+	collections::HashSet,
+	str::FromStr,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+use tokio::sync::Mutex as AsyncMutex;
+
+/// Maximum amount of gas that can be attached to a transaction
+pub const MAX_GAS: Gas = 300_000_000_000_000;
+/// Gas attached to the `deliver` call for every message
+pub const DEFAULT_GAS_PER_MESSAGE: Gas = 30_000_000_000_000;
+/// Expected time between two blocks
+pub const EXPECTED_BLOCK_TIME: Duration = Duration::from_millis(1200);
+/// Number of blocks in an epoch of NEAR mainnet and testnet
+pub const DEFAULT_EPOCH_LENGTH: BlockHeight = 43_200;
+
+fn default_gas_per_message() -> Gas {
+	DEFAULT_GAS_PER_MESSAGE
+}
+
+fn default_epoch_length() -> BlockHeight {
+	DEFAULT_EPOCH_LENGTH
+}
+
+/// Implements the [`primitives::Chain`] trait for NEAR.
+#[derive(Clone)]
+pub struct Client {
+	/// Chain name
+	pub name: String,
+	/// Near rpc client
+	pub rpc_client: JsonRpcClient,
+	/// Near rpc address
+	pub rpc_url: String,
+	/// Chain id, it's the revision number of all the heights
+	pub chain_id: ChainId,
+	/// Account of the IBC contract
+	pub contract_id: AccountId,
+	/// Account the transactions are signed for
+	pub signer_id: AccountId,
+	/// Access key of `signer_id` that signs transactions
+	pub signer: Arc<dyn SigningBackend>,
+	/// Light client id on counterparty chain
+	pub client_id: Arc<Mutex<Option<ClientId>>>,
+	/// Connection Id
+	pub connection_id: Arc<Mutex<Option<ConnectionId>>>,
+	/// Channels cleared for packet relay
+	pub channel_whitelist: Arc<Mutex<HashSet<(ChannelId, PortId)>>>,
+	/// Commitment prefix of the contract storage
+	pub commitment_prefix: CommitmentPrefix,
+	/// Gas attached to the `deliver` call for every message
+	pub gas_per_message: Gas,
+	/// Number of blocks in an epoch, the trusting period of the NEAR light client is derived from
+	/// it
+	pub epoch_length: BlockHeight,
+	/// Serializes the transactions of the signer, so that their nonces don't collide
+	pub tx_lock: Arc<AsyncMutex<()>>,
+	/// Relayer data
+	pub common_state: CommonClientState,
+}
+
+/// config options for [`Client`]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClientConfig {
+	/// Chain name
+	pub name: String,
+	/// rpc url for the Near node
+	pub rpc_url: String,
+	/// Near chain id, eg. `mainnet` or `sandbox`
+	pub chain_id: String,
+	/// Account of the IBC contract
+	pub contract_id: AccountId,
+	/// Account the transactions are signed for
+	pub signer_id: AccountId,
+	/// Light client id on counterparty chain
+	pub client_id: Option<ClientId>,
+	/// Connection Id
+	pub connection_id: Option<ConnectionId>,
+	/// Whitelisted channels
+	pub channel_whitelist: Vec<(ChannelId, PortId)>,
+	/// Commitment prefix of the contract storage
+	pub commitment_prefix: String,
+	/// Gas attached to the `deliver` call for every message
+	#[serde(default = "default_gas_per_message")]
+	pub gas_per_message: Gas,
+	/// Number of blocks in an epoch, the `epoch_length` of the genesis config of the chain
+	#[serde(default = "default_epoch_length")]
+	pub epoch_length: BlockHeight,
+	/// All the client states and headers will be wrapped in WASM ones using the WASM code ID.
+	#[serde(default)]
+	pub wasm_checksum: Option<String>,
+	/// Secret key of the signer's access key, eg. `ed25519:...`. Prefer `key_source`, so that
+	/// the key isn't stored in plaintext in the config.
+	#[serde(default)]
+	pub secret_key: Option<String>,
+	/// Where the secret key of the signer's access key is loaded from
+	#[serde(default)]
+	pub key_source: Option<KeySource>,
+	/// Common client config
+	#[serde(flatten)]
+	pub common: CommonClientConfig,
+}
+
+impl Client {
+	/// Initializes a [`Client`] given a [`ClientConfig`]
+	pub async fn new(config: ClientConfig) -> Result<Self, Error> {
+		let rpc_client = JsonRpcClient::connect(&config.rpc_url);
+		let commitment_prefix =
+			CommitmentPrefix::try_from(config.commitment_prefix.as_bytes().to_vec())
+				.map_err(|e| Error::from(format!("Invalid commitment prefix {:?}", e)))?;
+		let signer: Arc<dyn SigningBackend> =
+			match load_key(config.secret_key.as_deref(), config.key_source.as_ref())? {
+				Key::Secret(secret_key) => Arc::new(Ed25519Signer::from_str(&secret_key)?),
+				Key::Remote(remote) => Arc::new(remote),
+			};
+
+		let rpc_call_delay = Duration::from_millis(100);
+		Ok(Self {
+			name: config.name,
+			rpc_client,
+			rpc_url: config.rpc_url,
+			chain_id: ChainId::from(config.chain_id),
+			contract_id: config.contract_id,
+			signer_id: config.signer_id,
+			signer,
+			client_id: Arc::new(Mutex::new(config.client_id)),
+			connection_id: Arc::new(Mutex::new(config.connection_id)),
+			channel_whitelist: Arc::new(Mutex::new(config.channel_whitelist.into_iter().collect())),
+			commitment_prefix,
+			gas_per_message: config.gas_per_message,
+			epoch_length: config.epoch_length,
+			tx_lock: Default::default(),
+			common_state: CommonClientState {
+				skip_optional_client_updates: config.common.skip_optional_client_updates,
+				maybe_has_undelivered_packets: Default::default(),
+				rpc_call_delay,
+				initial_rpc_call_delay: rpc_call_delay,
+				misbehaviour_client_msg_queue: Arc::new(AsyncMutex::new(vec![])),
+				max_packets_to_process: config.common.max_packets_to_process as usize,
+				skip_tokens_list: Default::default(),
+				store: None,
+			},
+		})
+	}
+
+	pub fn client_id(&self) -> ClientId {
+		self.client_id
+			.lock()
+			.unwrap()
+			.as_ref()
+			.expect("Client Id should be defined")
+			.clone()
+	}
+
+	pub fn set_client_id(&mut self, client_id: ClientId) {
+		*self.client_id.lock().unwrap() = Some(client_id);
+	}
+
+	/// Public key of the signer's access key
+	pub fn public_key(&self) -> Result<PublicKey, Error> {
+		let public_key = ED25519PublicKey::try_from(self.signer.public_key())
+			.map_err(|e| Error::from(format!("Invalid ed25519 public key: {e:?}")))?;
+		Ok(PublicKey::from(public_key))
+	}
+
+	/// Calls the view method of the contract at the given block height, passing the JSON
+	/// encoded `args` and decoding the result as JSON.
+	pub async fn view_at<A: Serialize, R: DeserializeOwned>(
+		&self,
+		at: BlockHeight,
+		method: &str,
+		args: &A,
+	) -> Result<R, Error> {
+		self.view(BlockReference::BlockId(BlockId::Height(at)), method, args).await
+	}
+
+	/// Calls the view method of the contract at the latest final block.
+	pub async fn view_final<A: Serialize, R: DeserializeOwned>(
+		&self,
+		method: &str,
+		args: &A,
+	) -> Result<R, Error> {
+		self.view(BlockReference::Finality(Finality::Final), method, args).await
+	}
+
+	async fn view<A: Serialize, R: DeserializeOwned>(
+		&self,
+		block_reference: BlockReference,
+		method: &str,
+		args: &A,
+	) -> Result<R, Error> {
+		let response = self
+			.query(RpcQueryRequest {
+				block_reference,
+				request: QueryRequest::CallFunction {
+					account_id: self.contract_id.clone(),
+					method_name: method.to_string(),
+					args: FunctionArgs::from(serde_json::to_vec(args)?),
+				},
+			})
+			.await?;
+		match response.kind {
+			QueryResponseKind::CallResult(result) => Ok(serde_json::from_slice(&result.result)?),
+			kind => Err(Error::from(format!("Unexpected response to `{method}`: {kind:?}"))),
+		}
+	}
+
+	async fn query(&self, request: RpcQueryRequest) -> Result<RpcQueryResponse, Error> {
+		Ok(self.rpc_client.call(request).await?)
+	}
+
+	/// Returns the balance of the relayer's account at the latest final block, in yoctoNEAR.
+	pub async fn signer_balance(&self) -> Result<u128, Error> {
+		let response = self
+			.query(RpcQueryRequest {
+				block_reference: BlockReference::Finality(Finality::Final),
+				request: QueryRequest::ViewAccount { account_id: self.signer_id.clone() },
+			})
+			.await?;
+		match response.kind {
+			QueryResponseKind::ViewAccount(account) => Ok(account.amount),
+			kind => Err(Error::from(format!("Unexpected account response: {kind:?}"))),
+		}
+	}
+
+	/// Delivers the messages to the contract in a single transaction, and waits for its final
+	/// outcome.
+	pub async fn deliver(&self, messages: Vec<Any>) -> Result<FinalExecutionOutcomeView, Error> {
+		let gas = (self.gas_per_message * messages.len() as Gas).min(MAX_GAS);
+		let args = serde_json::to_vec(&serde_json::json!({ "messages": messages }))?;
+		self.call("deliver", args, gas, 0).await
+	}
+
+	/// Calls a method of the contract in a transaction signed by the relayer's access key.
+	pub async fn call(
+		&self,
+		method: &str,
+		args: Vec<u8>,
+		gas: Gas,
+		deposit: u128,
+	) -> Result<FinalExecutionOutcomeView, Error> {
+		let _guard = self.tx_lock.lock().await;
+		let public_key = self.public_key()?;
+		let access_key = self
+			.query(RpcQueryRequest {
+				block_reference: BlockReference::Finality(Finality::Final),
+				request: QueryRequest::ViewAccessKey {
+					account_id: self.signer_id.clone(),
+					public_key: public_key.clone(),
+				},
+			})
+			.await?;
+		let nonce = match access_key.kind {
+			QueryResponseKind::AccessKey(access_key) => access_key.nonce,
+			kind => Err(Error::from(format!("Unexpected access key response: {kind:?}")))?,
+		};
+
+		let transaction = Transaction {
+			signer_id: self.signer_id.clone(),
+			public_key,
+			nonce: nonce + 1,
+			receiver_id: self.contract_id.clone(),
+			block_hash: access_key.block_hash,
+			actions: vec![Action::FunctionCall(FunctionCallAction {
+				method_name: method.to_string(),
+				args,
+				gas,
+				deposit,
+			})],
+		};
+		let signature = self.signer.sign(transaction.get_hash_and_size().0.as_ref())?;
+		let signature = Signature::from_parts(near_crypto::KeyType::ED25519, &signature)
+			.map_err(|e| Error::from(format!("Invalid ed25519 signature: {e:?}")))?;
+		let signed_transaction = SignedTransaction::new(signature, transaction);
+
+		let outcome =
+			self.rpc_client.call(RpcBroadcastTxCommitRequest { signed_transaction }).await?;
+		if let FinalExecutionStatus::Failure(e) = &outcome.status {
+			return Err(Error::TransactionFailed {
+				tx_hash: outcome.transaction.hash.to_string(),
+				reason: e.to_string(),
+			})
+		}
+		log::debug!(target: "hyperspace_near", "Transaction {} executed", outcome.transaction.hash);
+		Ok(outcome)
+	}
+}
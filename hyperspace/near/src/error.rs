@@ -16,8 +16,8 @@ use ibc::core::ics02_client;
 
 use near_jsonrpc_client::errors::JsonRpcError;
 use near_jsonrpc_primitives::types::{
-	blocks::RpcBlockError, query::RpcQueryError, transactions::RpcTransactionError,
-	validator::RpcValidatorError,
+	blocks::RpcBlockError, light_client::RpcLightClientNextBlockError, query::RpcQueryError,
+	transactions::RpcTransactionError, validator::RpcValidatorError,
 };
 use std::io;
 use thiserror::Error;
@@ -33,6 +33,8 @@ pub enum RpcError {
 	Block(#[from] JsonRpcError<RpcBlockError>),
 	#[error("{0}")]
 	Validator(#[from] JsonRpcError<RpcValidatorError>),
+	#[error("{0}")]
+	LightClient(#[from] JsonRpcError<RpcLightClientNextBlockError>),
 }
 
 /// Error definition for the NEAR client
@@ -47,15 +49,18 @@ pub enum Error {
 	/// RPC error
 	#[error("RPC error: {0}")]
 	Rpc(#[from] RpcError),
-	/// Update pallet name in call definition
-	#[error("Pallet '{0}' not found in metadata, update static definition of call")]
-	PalletNotFound(&'static str),
 	/// hex error
 	#[error("Error decoding hex: {0:?}")]
 	Hex(#[from] hex::FromHexError),
 	/// Custom error
 	#[error("{0}")]
 	Custom(String),
+	/// Transaction failed on chain
+	#[error("Transaction {tx_hash} failed: {reason}")]
+	TransactionFailed { tx_hash: String, reason: String },
+	/// Signing error
+	#[error("Signing error: {0}")]
+	Signer(#[from] primitives::error::Error),
 	#[error("Ibc channel error")]
 	IbcChannel(#[from] ibc::core::ics04_channel::error::Error),
 	/// Error querying packets
@@ -70,6 +75,9 @@ pub enum Error {
 	/// Errors associated with ics-02 client
 	#[error("Ibc client error: {0}")]
 	IbcClient(#[from] ics02_client::error::Error),
+	/// Errors associated with the NEAR light client
+	#[error("Near light client error: {0}")]
+	NearClient(#[from] ics13_near::error::Error),
 }
 
 impl From<String> for Error {
@@ -0,0 +1,94 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The IBC contract logs the events it emits as [NEP-297] events, with the list of
+//! [`IbcEvent`]s as their data.
+//!
+//! [NEP-297]: https://nomicon.io/Standards/EventsFormat
+
+use ibc::events::IbcEvent;
+use near_primitives::views::FinalExecutionOutcomeView;
+use serde::Deserialize;
+
+/// Prefix of the logs holding NEP-297 events
+pub const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+/// Standard of the events emitted by the IBC contract
+pub const IBC_EVENT_STANDARD: &str = "ibc";
+
+#[derive(Debug, Deserialize)]
+struct EventLog {
+	standard: String,
+	data: Vec<IbcEvent>,
+}
+
+/// Parses the IBC events out of a contract log, returns `None` for any other log.
+pub fn parse_log(log: &str) -> Option<Vec<IbcEvent>> {
+	let event = log.strip_prefix(EVENT_LOG_PREFIX)?;
+	match serde_json::from_str::<EventLog>(event) {
+		Ok(event) if event.standard == IBC_EVENT_STANDARD => Some(event.data),
+		Ok(_) => None,
+		Err(e) => {
+			log::warn!(target: "hyperspace_near", "Failed to parse event log {log}: {e}");
+			None
+		},
+	}
+}
+
+/// Returns the IBC events emitted by the receipts of a transaction.
+pub fn ibc_events_from_outcome(outcome: &FinalExecutionOutcomeView) -> Vec<IbcEvent> {
+	outcome
+		.receipts_outcome
+		.iter()
+		.flat_map(|receipt| receipt.outcome.logs.iter())
+		.filter_map(|log| parse_log(log))
+		.flatten()
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::core::{
+		ics02_client::{events::Attributes, height::Height},
+		ics24_host::identifier::ClientId,
+	};
+
+	#[test]
+	fn parses_ibc_event_logs() {
+		let client_id = ClientId::new("13-near", 0).unwrap();
+		let event = IbcEvent::CreateClient(
+			Attributes {
+				height: Height::new(0, 10),
+				client_id: client_id.clone(),
+				client_type: "13-near".to_string(),
+				consensus_height: Height::new(0, 9),
+			}
+			.into(),
+		);
+		let log = format!(
+			"{EVENT_LOG_PREFIX}{}",
+			serde_json::json!({
+				"standard": IBC_EVENT_STANDARD,
+				"version": "1.0.0",
+				"event": "ibc_events",
+				"data": [event],
+			})
+		);
+
+		let events = parse_log(&log).unwrap();
+		assert!(matches!(&events[..], [IbcEvent::CreateClient(e)] if e.client_id() == &client_id));
+		assert!(parse_log("Transfer 10 tokens").is_none());
+		assert!(parse_log(&log.replace(IBC_EVENT_STANDARD, "nep171")).is_none());
+	}
+}
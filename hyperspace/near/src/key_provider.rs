@@ -13,11 +13,51 @@
 // limitations under the License.
 
 use super::Client;
-use primitives::KeyProvider;
+use crate::error::Error;
+use borsh::BorshSerialize;
+use near_crypto::{KeyType, SecretKey};
+use primitives::{keys::SigningBackend, KeyProvider};
 use std::str::FromStr;
 
+/// A [`SigningBackend`] holding the ed25519 secret key of a NEAR access key in memory.
+pub struct Ed25519Signer {
+	secret_key: SecretKey,
+	public_key: Vec<u8>,
+}
+
+impl FromStr for Ed25519Signer {
+	type Err = Error;
+
+	/// Parses a secret key in the `ed25519:<base58>` format used by the NEAR tooling
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let secret_key = SecretKey::from_str(s.trim())
+			.map_err(|e| Error::from(format!("Invalid secret key: {e}")))?;
+		if secret_key.key_type() != KeyType::ED25519 {
+			return Err(Error::from(format!("Unsupported key type {}", secret_key.key_type())))
+		}
+		let public_key = secret_key.public_key().key_data().to_vec();
+		Ok(Self { secret_key, public_key })
+	}
+}
+
+impl SigningBackend for Ed25519Signer {
+	fn public_key(&self) -> &[u8] {
+		&self.public_key
+	}
+
+	fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, primitives::error::Error> {
+		// The borsh encoding of a signature is its key type followed by the raw signature
+		let signature = self
+			.secret_key
+			.sign(payload)
+			.try_to_vec()
+			.map_err(|e| primitives::error::Error::from(e.to_string()))?;
+		Ok(signature[1..].to_vec())
+	}
+}
+
 impl KeyProvider for Client {
 	fn account_id(&self) -> ibc::signer::Signer {
-		ibc::signer::Signer::from_str(self.signer.as_str()).expect("Account Id should be valid")
+		ibc::signer::Signer::from_str(self.signer_id.as_str()).expect("Account Id should be valid")
 	}
 }
@@ -0,0 +1,184 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Light blocks of the NEAR light client tracked by the counterparty.
+//!
+//! The client only advances through `next_light_client_block`, which returns the latest final
+//! block if it belongs to the epoch of the given head or to the next one, and the last final
+//! block of the next epoch otherwise. A light block of a new epoch is therefore a mandatory
+//! update, the client can't verify any later block without it.
+
+use crate::{error::Error, Client};
+use borsh::{BorshDeserialize, BorshSerialize};
+use ibc::{
+	core::ics02_client::msgs::update_client::MsgUpdateAnyClient, events::IbcEvent,
+	protobuf::Protobuf, tx_msg::Msg,
+};
+use ibc_proto::google::protobuf::Any;
+use ics13_near::{
+	client_state::NearClientState,
+	header::{NearClientMessage, NearHeader},
+	types::{CryptoHash, LightClientBlockView, ValidatorStakeView},
+};
+use near_jsonrpc_client::methods::{
+	next_light_client_block::RpcLightClientNextBlockRequest,
+	EXPERIMENTAL_validators_ordered::RpcValidatorsOrderedRequest,
+};
+use near_primitives::types::BlockId;
+use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, HostFunctionsManager};
+use primitives::{
+	mock::LocalClientTypes, Chain, KeyProvider, LightClientSync, MisbehaviourHandler, UpdateType,
+};
+
+/// Maximum number of mandatory updates fetched in one go
+const MAX_MANDATORY_UPDATES: usize = 50;
+
+/// Converts a NEAR RPC type into its light client counterpart, both have the same borsh encoding.
+pub fn borsh_convert<T: BorshSerialize, U: BorshDeserialize>(value: &T) -> Result<U, Error> {
+	Ok(U::try_from_slice(&value.try_to_vec()?)?)
+}
+
+impl Client {
+	/// Queries the NEAR client state of this chain on the counterparty
+	pub async fn counterparty_client_state<C: Chain>(
+		&self,
+		counterparty: &C,
+	) -> Result<NearClientState<HostFunctionsManager>, anyhow::Error> {
+		let latest_cp_height = counterparty.latest_height_and_timestamp().await?.0;
+		let response = counterparty.query_client_state(latest_cp_height, self.client_id()).await?;
+		let client_state = response
+			.client_state
+			.ok_or_else(|| Error::Custom("counterparty returned empty client state".to_string()))?;
+		match AnyClientState::decode_recursive(client_state, |c| {
+			matches!(c, AnyClientState::Near(_))
+		}) {
+			Some(AnyClientState::Near(client_state)) => Ok(client_state),
+			_ => Err(Error::ClientStateRehydration(
+				"counterparty client state is not a NEAR client state".to_string(),
+			))?,
+		}
+	}
+
+	/// Returns the light block following `head`, if the chain has finalized one.
+	pub async fn next_light_block(
+		&self,
+		head: &LightClientBlockView,
+	) -> Result<Option<LightClientBlockView>, Error> {
+		let last_block_hash = head.current_block_hash::<HostFunctionsManager>();
+		let block = self
+			.rpc_client
+			.call(RpcLightClientNextBlockRequest {
+				last_block_hash: near_primitives::hash::CryptoHash(last_block_hash.0),
+			})
+			.await?;
+		match block {
+			Some(block) => {
+				let block: LightClientBlockView = borsh_convert(&block)?;
+				Ok((block.inner_lite.height > head.inner_lite.height).then_some(block))
+			},
+			None => Ok(None),
+		}
+	}
+
+	/// Returns the block producers of the epoch of the given block.
+	pub async fn block_producers(
+		&self,
+		block_hash: CryptoHash,
+	) -> Result<Vec<ValidatorStakeView>, Error> {
+		let validators = self
+			.rpc_client
+			.call(RpcValidatorsOrderedRequest {
+				block_id: Some(BlockId::Hash(near_primitives::hash::CryptoHash(block_hash.0))),
+			})
+			.await?;
+		validators.iter().map(borsh_convert).collect()
+	}
+
+	/// Returns the `MsgUpdateClient` advancing the counterparty client to the given light block.
+	pub fn msg_update_client<C: KeyProvider>(
+		&self,
+		block: LightClientBlockView,
+		counterparty: &C,
+	) -> Result<Any, Error> {
+		let msg = MsgUpdateAnyClient::<LocalClientTypes> {
+			client_id: self.client_id(),
+			client_message: AnyClientMessage::Near(NearClientMessage::Header(NearHeader::new(
+				block,
+			))),
+			signer: counterparty.account_id(),
+		};
+		let value = msg
+			.encode_vec()
+			.map_err(|e| Error::from(format!("Failed to encode MsgUpdateClient {msg:?}: {e:?}")))?;
+		Ok(Any { value, type_url: msg.type_url() })
+	}
+}
+
+/// Light blocks of a new epoch carry the block producers of the following one, so they can't be
+/// skipped.
+pub fn update_type(head: &LightClientBlockView, block: &LightClientBlockView) -> UpdateType {
+	if block.inner_lite.epoch_id != head.inner_lite.epoch_id || block.next_bps.is_some() {
+		UpdateType::Mandatory
+	} else {
+		UpdateType::Optional
+	}
+}
+
+#[async_trait::async_trait]
+impl LightClientSync for Client {
+	async fn is_synced<C: Chain>(&self, counterparty: &C) -> Result<bool, anyhow::Error> {
+		let client_state = self.counterparty_client_state(counterparty).await?;
+		let head = client_state.get_head();
+		let synced = match self.next_light_block(head).await? {
+			Some(block) => update_type(head, &block).is_optional(),
+			None => true,
+		};
+		Ok(synced)
+	}
+
+	async fn fetch_mandatory_updates<C: Chain>(
+		&self,
+		counterparty: &C,
+	) -> Result<(Vec<Any>, Vec<IbcEvent>), anyhow::Error> {
+		let client_state = self.counterparty_client_state(counterparty).await?;
+		let mut head = client_state.get_head().clone();
+		let mut messages = vec![];
+		while messages.len() < MAX_MANDATORY_UPDATES {
+			let Some(block) = self.next_light_block(&head).await? else { break };
+			if update_type(&head, &block).is_optional() {
+				break
+			}
+			log::debug!(
+				target: "hyperspace_near",
+				"Fetched mandatory update at {}", block.inner_lite.height
+			);
+			messages.push(self.msg_update_client(block.clone(), counterparty)?);
+			head = block;
+		}
+		Ok((messages, vec![]))
+	}
+}
+
+#[async_trait::async_trait]
+impl MisbehaviourHandler for Client {
+	async fn check_for_misbehaviour<C: Chain>(
+		&self,
+		_counterparty: &C,
+		_client_message: AnyClientMessage,
+	) -> Result<(), anyhow::Error> {
+		// Light blocks are only accepted with the approvals of the epoch's block producers, and
+		// there is no misbehaviour message for the NEAR client yet.
+		Ok(())
+	}
+}
@@ -12,127 +12,169 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[cfg(feature = "testing")]
-use actix::dev::Stream;
-#[cfg(feature = "testing")]
-use std::pin::Pin;
-
-use super::error::Error;
-use crate::Client;
+use crate::{
+	client::EXPECTED_BLOCK_TIME,
+	error::Error,
+	events::ibc_events_from_outcome,
+	light_client::{borsh_convert, update_type},
+	Client,
+};
+use borsh::BorshSerialize;
+use futures::{stream, Stream, StreamExt};
 use ibc::{
+	applications::transfer::PrefixedCoin,
 	core::{
-		ics02_client::{
-			client_consensus::AnyConsensusState, client_state::AnyClientState, header::AnyHeader,
-		},
-		ics04_channel::packet::Packet,
+		ics02_client::client_state::{ClientState as _, ClientType},
 		ics23_commitment::commitment::CommitmentPrefix,
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
 	events::IbcEvent,
+	timestamp::Timestamp,
 	Height,
 };
-use ibc_proto::ibc::core::{
-	channel::v1::{
-		Packet as RawPacket, QueryChannelResponse, QueryNextSequenceReceiveResponse,
-		QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
-		QueryPacketReceiptResponse,
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::{
+		channel::v1::{
+			QueryChannelResponse, QueryChannelsResponse, QueryNextSequenceReceiveResponse,
+			QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
+			QueryPacketReceiptResponse,
+		},
+		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
+		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
 	},
-	client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
-	connection::v1::QueryConnectionResponse,
 };
-use near_indexer::StreamerMessage;
-use near_jsonrpc_primitives::types::{
-	blocks::RpcBlockRequest,
-	query::{QueryResponseKind, RpcQueryRequest},
-	validator::RpcValidatorRequest,
+use ibc_rpc::PacketInfo;
+use ics13_near::{
+	client_state::{trusting_period, NearClientState},
+	consensus_state::{ConsensusState, HostConsensusProof},
+	types::LightClientBlockView,
+};
+use near_jsonrpc_client::methods::{
+	block::RpcBlockRequest, next_light_client_block::RpcLightClientNextBlockRequest,
+	tx::RpcTransactionStatusRequest,
 };
+use near_jsonrpc_primitives::types::transactions::TransactionInfo;
 use near_primitives::{
-	types::{BlockId, BlockReference, EpochReference, Finality, FunctionArgs},
-	views::QueryRequest,
+	block_header::BlockHeader,
+	hash::CryptoHash,
+	types::{BlockHeight, BlockId, BlockReference, Finality},
+	views::{BlockView, LightClientBlockLiteView as NearLightClientBlockLiteView},
 };
-use near_sdk::BlockHeight;
+use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager};
 use primitives::{Chain, IbcProvider, UpdateType};
-use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use std::{collections::HashSet, pin::Pin, time::Duration};
+
+/// A block finalized by the chain
+#[derive(Debug, Clone)]
+pub struct FinalityEvent {
+	pub height: BlockHeight,
+	pub hash: CryptoHash,
+}
+
+/// IBC events emitted by the contract, grouped by the height of their block
+type BlockEvents = Vec<(BlockHeight, Vec<IbcEvent>)>;
+
+/// Heights of NEAR blocks, there are no revisions
+pub fn to_height(height: BlockHeight) -> Height {
+	Height::new(0, height)
+}
 
 impl Client {
-	fn make_contract_query_at<T: Serialize>(
-		&self,
-		at: BlockHeight,
-		method: impl ToString,
-		args: &T,
-	) -> Result<RpcQueryRequest, <Self as IbcProvider>::Error> {
-		Ok(RpcQueryRequest {
-			block_reference: BlockReference::BlockId(BlockId::Height(at)),
-			request: QueryRequest::CallFunction {
-				account_id: self.contract_id.clone(),
-				method_name: method.to_string(),
-				args: FunctionArgs::from(serde_json::to_vec(args)?),
-			},
-		})
+	pub async fn block(&self, block_reference: BlockReference) -> Result<BlockView, Error> {
+		Ok(self.rpc_client.call(RpcBlockRequest { block_reference }).await?)
 	}
 
-	fn make_contract_query_at_final<T: Serialize>(
-		&self,
-		method: impl ToString,
-		args: &T,
-	) -> Result<RpcQueryRequest, <Self as IbcProvider>::Error> {
-		Ok(RpcQueryRequest {
-			block_reference: BlockReference::Finality(Finality::Final),
-			request: QueryRequest::CallFunction {
-				account_id: self.contract_id.clone(),
-				method_name: method.to_string(),
-				args: FunctionArgs::from(serde_json::to_vec(args)?),
-			},
-		})
+	/// Returns the latest final block
+	pub async fn final_block(&self) -> Result<BlockView, Error> {
+		self.block(BlockReference::Finality(Finality::Final)).await
 	}
 
-	async fn send_query<R: DeserializeOwned>(
+	/// Returns the IBC events emitted in the blocks `from..=to`
+	pub async fn query_ibc_events_in(
 		&self,
-		query: RpcQueryRequest,
-	) -> Result<R, <Self as IbcProvider>::Error> {
-		self.rpc_client
-			.call(query)
-			.await
-			.map_err(|e| e.into())
-			.and_then(|resp| match resp.kind {
-				QueryResponseKind::CallResult(res) =>
-					serde_json::from_slice(&res.result).map_err(|e| e.into()),
-				_ => unreachable!(),
+		from: BlockHeight,
+		to: BlockHeight,
+	) -> Result<Vec<IbcEvent>, Error> {
+		let events: BlockEvents =
+			self.view_at(to, "query_ibc_events", &json!({ "from": from, "to": to })).await?;
+		Ok(events.into_iter().flat_map(|(_, events)| events).collect())
+	}
+
+	/// Returns the IBC events emitted by the transaction
+	pub async fn transaction_events(&self, tx_id: CryptoHash) -> Result<Vec<IbcEvent>, Error> {
+		let outcome = self
+			.rpc_client
+			.call(RpcTransactionStatusRequest {
+				transaction_info: TransactionInfo::TransactionId {
+					hash: tx_id,
+					account_id: self.signer_id.clone(),
+				},
 			})
+			.await?;
+		Ok(ibc_events_from_outcome(&outcome))
 	}
 }
 
 #[async_trait::async_trait]
 impl IbcProvider for Client {
-	type IbcEvent = Result<Vec<IbcEvent>, String>;
-	type FinalityEvent = StreamerMessage;
+	type FinalityEvent = FinalityEvent;
+	type TransactionId = CryptoHash;
+	type AssetId = String;
 	type Error = Error;
 
-	async fn client_update_header<C>(
+	async fn query_latest_ibc_events<C>(
 		&mut self,
-		_finality_event: Self::FinalityEvent,
-		_counterparty: &C,
-	) -> Result<(AnyHeader, AnyClientState, UpdateType), Self::Error>
+		finality_event: Self::FinalityEvent,
+		counterparty: &C,
+	) -> Result<Vec<(Any, Height, Vec<IbcEvent>, UpdateType)>, anyhow::Error>
 	where
 		C: Chain,
-		Self::Error: From<C::Error>,
 	{
-		unimplemented!()
-	}
+		let client_state = self.counterparty_client_state(counterparty).await?;
+		let head = client_state.get_head();
+		let Some(block) = self.next_light_block(head).await? else { return Ok(vec![]) };
+		let from = head.inner_lite.height + 1;
+		let to = block.inner_lite.height;
+		log::info!(
+			target: "hyperspace_near",
+			"Getting blocks {}..={} (finalized {})", from, to, finality_event.height
+		);
 
-	async fn query_latest_ibc_events(
-		&mut self,
-		_header: &AnyHeader,
-		_client_state: &AnyClientState,
-	) -> Result<Vec<IbcEvent>, Self::Error> {
-		unimplemented!()
+		let events = self.query_ibc_events_in(from, to).await?;
+		let update_type = update_type(head, &block);
+		let height = block.get_height();
+		let update = self.msg_update_client(block, counterparty)?;
+		Ok(vec![(update, height, events, update_type)])
 	}
 
-	async fn host_consensus_state(
-		&self,
-		_height: Height,
-	) -> Result<AnyConsensusState, Self::Error> {
-		unimplemented!()
+	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
+		let client = self.clone();
+		let stream = stream::unfold((client, None), |(client, last_height)| async move {
+			loop {
+				tokio::time::sleep(EXPECTED_BLOCK_TIME).await;
+				let height = match client.final_block().await {
+					Ok(block) => block.header.height,
+					Err(e) => {
+						log::warn!(target: "hyperspace_near", "Failed to fetch final block: {e}");
+						continue
+					},
+				};
+				let from = last_height.map(|h: BlockHeight| h + 1).unwrap_or(height);
+				if from > height {
+					continue
+				}
+				match client.query_ibc_events_in(from, height).await {
+					Ok(events) => return Some((stream::iter(events), (client, Some(height)))),
+					Err(e) => {
+						log::warn!(target: "hyperspace_near", "Failed to query ibc events: {e}")
+					},
+				}
+			}
+		})
+		.flatten();
+		Box::pin(stream)
 	}
 
 	async fn query_client_consensus(
@@ -142,9 +184,7 @@ impl IbcProvider for Client {
 		consensus_height: Height,
 	) -> Result<QueryConsensusStateResponse, Self::Error> {
 		let args = (consensus_height, client_id, false);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_client_consensus_state", &args)?;
-		self.send_query(query).await
+		self.view_at(at.revision_height, "query_client_consensus_state", &args).await
 	}
 
 	async fn query_client_state(
@@ -152,9 +192,7 @@ impl IbcProvider for Client {
 		at: Height,
 		client_id: ClientId,
 	) -> Result<QueryClientStateResponse, Self::Error> {
-		let args = (client_id,);
-		let query = self.make_contract_query_at(at.revision_height, "query_client_state", &args)?;
-		self.send_query(query).await
+		self.view_at(at.revision_height, "query_client_state", &(client_id,)).await
 	}
 
 	async fn query_connection_end(
@@ -162,9 +200,7 @@ impl IbcProvider for Client {
 		at: Height,
 		connection_id: ConnectionId,
 	) -> Result<QueryConnectionResponse, Self::Error> {
-		let args = (connection_id,);
-		let query = self.make_contract_query_at(at.revision_height, "query_connection", &args)?;
-		self.send_query(query).await
+		self.view_at(at.revision_height, "query_connection", &(connection_id,)).await
 	}
 
 	async fn query_channel_end(
@@ -173,38 +209,11 @@ impl IbcProvider for Client {
 		channel_id: ChannelId,
 		port_id: PortId,
 	) -> Result<QueryChannelResponse, Self::Error> {
-		let args = (channel_id, port_id);
-		let query = self.make_contract_query_at(at.revision_height, "query_channel", &args)?;
-		self.send_query(query).await
+		self.view_at(at.revision_height, "query_channel", &(channel_id, port_id)).await
 	}
 
 	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
-		let args = (keys,);
-		let query = self.make_contract_query_at(at.revision_height, "query_proof", &args)?;
-		self.send_query(query).await
-	}
-
-	async fn query_packets(
-		&self,
-		_at: Height,
-		port_id: &PortId,
-		channel_id: &ChannelId,
-		seqs: Vec<u64>,
-	) -> Result<Vec<Packet>, Self::Error> {
-		let args = (port_id, channel_id, &seqs);
-		let query = self.make_contract_query_at_final("query_packets", &args)?;
-		let packets: Vec<RawPacket> =
-			self.send_query(query).await.map_err(|e| Error::QueryPackets {
-				channel_id: channel_id.to_string(),
-				port_id: port_id.to_string(),
-				sequences: seqs,
-				err: e.to_string(),
-			})?;
-		let packets = packets
-			.into_iter()
-			.map(|raw_packet| raw_packet.try_into())
-			.collect::<Result<Vec<Packet>, _>>()?;
-		Ok(packets)
+		self.view_at(at.revision_height, "query_proof", &(keys,)).await
 	}
 
 	async fn query_packet_commitment(
@@ -215,9 +224,7 @@ impl IbcProvider for Client {
 		seq: u64,
 	) -> Result<QueryPacketCommitmentResponse, Self::Error> {
 		let args = (port_id, channel_id, seq);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_packet_commitment", &args)?;
-		self.send_query(query).await
+		self.view_at(at.revision_height, "query_packet_commitment", &args).await
 	}
 
 	async fn query_packet_acknowledgement(
@@ -228,9 +235,7 @@ impl IbcProvider for Client {
 		seq: u64,
 	) -> Result<QueryPacketAcknowledgementResponse, Self::Error> {
 		let args = (port_id, channel_id, seq);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_packet_acknowledgement", &args)?;
-		self.send_query(query).await
+		self.view_at(at.revision_height, "query_packet_acknowledgement", &args).await
 	}
 
 	async fn query_next_sequence_recv(
@@ -239,10 +244,8 @@ impl IbcProvider for Client {
 		port_id: &PortId,
 		channel_id: &ChannelId,
 	) -> Result<QueryNextSequenceReceiveResponse, Self::Error> {
-		let args = (port_id, channel_id);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_next_seq_recv", &args)?;
-		self.send_query(query).await
+		self.view_at(at.revision_height, "query_next_seq_recv", &(port_id, channel_id))
+			.await
 	}
 
 	async fn query_packet_receipt(
@@ -253,44 +256,294 @@ impl IbcProvider for Client {
 		seq: u64,
 	) -> Result<QueryPacketReceiptResponse, Self::Error> {
 		let args = (port_id, channel_id, seq);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_packet_receipt", &args)?;
-		self.send_query(query).await
+		self.view_at(at.revision_height, "query_packet_receipt", &args).await
 	}
 
-	async fn latest_height_and_timestamp(&self) -> Result<Height, Self::Error> {
-		let finalized_block = self
-			.rpc_client
-			.call(RpcBlockRequest { block_reference: BlockReference::Finality(Finality::Final) })
-			.await?;
-		let validator_response = self
-			.rpc_client
-			.call(RpcValidatorRequest {
-				epoch_reference: EpochReference::BlockId(BlockId::Height(
-					finalized_block.header.height,
-				)),
+	async fn latest_height_and_timestamp(&self) -> Result<(Height, Timestamp), Self::Error> {
+		let block = self.final_block().await?;
+		let timestamp = Timestamp::from_nanoseconds(block.header.timestamp_nanosec)
+			.map_err(|e| Error::from(format!("Invalid block timestamp: {e:?}")))?;
+		Ok((to_height(block.header.height), timestamp))
+	}
+
+	async fn query_packet_commitments(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.view_at(at.revision_height, "query_packet_commitments", &(channel_id, port_id))
+			.await
+	}
+
+	async fn query_packet_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.view_at(at.revision_height, "query_packet_acknowledgements", &(channel_id, port_id))
+			.await
+	}
+
+	async fn query_unreceived_packets(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		let args = (channel_id, port_id, seqs);
+		self.view_at(at.revision_height, "query_unreceived_packets", &args).await
+	}
+
+	async fn query_unreceived_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		let args = (channel_id, port_id, seqs);
+		self.view_at(at.revision_height, "query_unreceived_acknowledgements", &args)
+			.await
+	}
+
+	fn channel_whitelist(&self) -> HashSet<(ChannelId, PortId)> {
+		self.channel_whitelist.lock().unwrap().clone()
+	}
+
+	async fn query_connection_channels(
+		&self,
+		at: Height,
+		connection_id: &ConnectionId,
+	) -> Result<QueryChannelsResponse, Self::Error> {
+		self.view_at(at.revision_height, "query_connection_channels", &(connection_id,))
+			.await
+	}
+
+	async fn query_send_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		let args = (&channel_id, &port_id, &seqs);
+		self.view_final("query_send_packets", &args)
+			.await
+			.map_err(|e| Error::QueryPackets {
+				channel_id: channel_id.to_string(),
+				port_id: port_id.to_string(),
+				sequences: seqs,
+				err: e.to_string(),
+			})
+	}
+
+	async fn query_received_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		let args = (&channel_id, &port_id, &seqs);
+		self.view_final("query_received_packets", &args)
+			.await
+			.map_err(|e| Error::QueryPackets {
+				channel_id: channel_id.to_string(),
+				port_id: port_id.to_string(),
+				sequences: seqs,
+				err: e.to_string(),
 			})
+	}
+
+	fn expected_block_time(&self) -> Duration {
+		EXPECTED_BLOCK_TIME
+	}
+
+	async fn query_client_update_time_and_height(
+		&self,
+		client_id: ClientId,
+		client_height: Height,
+	) -> Result<(Height, Timestamp), Self::Error> {
+		let (height, timestamp): (Height, u64) = self
+			.view_final("query_client_update_time_and_height", &(client_id, client_height))
 			.await?;
-		if validator_response.epoch_start_height > finalized_block.header.height {
-			return Err(Error::Custom(
-				"validator epoch height is greater than finalized header epoch height".to_string(),
-			))
-		}
-		let epoch = validator_response.epoch_height;
-		let latest_height = finalized_block.header.height;
-		Ok(Height::new(epoch, latest_height.into()))
+		let timestamp = Timestamp::from_nanoseconds(timestamp)
+			.map_err(|e| Error::from(format!("Invalid client update timestamp: {e:?}")))?;
+		Ok((height, timestamp))
+	}
+
+	async fn query_host_consensus_state_proof(
+		&self,
+		client_state: &AnyClientState,
+	) -> Result<Option<Vec<u8>>, Self::Error> {
+		let height = client_state.latest_height().revision_height;
+		let block = self.block(BlockReference::BlockId(BlockId::Height(height))).await?;
+		// the block view doesn't carry the hash of the rest of the header, it's computed from the
+		// full header
+		let header = NearLightClientBlockLiteView::from(BlockHeader::from(block.header));
+		let checksum = if let AnyClientState::Wasm(client_state) = client_state {
+			Some(client_state.checksum.clone())
+		} else {
+			None
+		};
+		let proof = HostConsensusProof { block: borsh_convert(&header)?, checksum };
+		Ok(Some(proof.try_to_vec()?))
+	}
+
+	async fn query_ibc_balance(
+		&self,
+		asset_id: Self::AssetId,
+	) -> Result<Vec<PrefixedCoin>, Self::Error> {
+		self.view_final("query_balance", &(&self.signer_id, asset_id)).await
 	}
 
 	fn connection_prefix(&self) -> CommitmentPrefix {
-		CommitmentPrefix::try_from(self.commitment_prefix.clone()).expect("Should not fail")
+		self.commitment_prefix.clone()
 	}
 
 	fn client_id(&self) -> ClientId {
 		self.client_id()
 	}
 
-	#[cfg(feature = "testing")]
-	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + Sync>> {
-		todo!()
+	fn set_client_id(&mut self, client_id: ClientId) {
+		self.set_client_id(client_id)
+	}
+
+	fn connection_id(&self) -> Option<ConnectionId> {
+		self.connection_id.lock().unwrap().clone()
+	}
+
+	fn set_channel_whitelist(&mut self, channel_whitelist: HashSet<(ChannelId, PortId)>) {
+		*self.channel_whitelist.lock().unwrap() = channel_whitelist;
+	}
+
+	fn add_channel_to_whitelist(&mut self, channel: (ChannelId, PortId)) {
+		self.channel_whitelist.lock().unwrap().insert(channel);
+	}
+
+	fn set_connection_id(&mut self, connection_id: ConnectionId) {
+		*self.connection_id.lock().unwrap() = Some(connection_id);
+	}
+
+	fn client_type(&self) -> ClientType {
+		NearClientState::<HostFunctionsManager>::client_type()
+	}
+
+	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
+		let block = self.block(BlockReference::BlockId(BlockId::Height(block_number))).await?;
+		Ok(block.header.timestamp_nanosec)
+	}
+
+	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
+		self.view_final("query_clients", &json!([])).await
+	}
+
+	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
+		self.view_final("query_channels", &json!([])).await
+	}
+
+	async fn query_connection_using_client(
+		&self,
+		height: u32,
+		client_id: String,
+	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		self.view_at(height as BlockHeight, "query_connection_using_client", &(client_id,))
+			.await
+	}
+
+	async fn is_update_required(
+		&self,
+		latest_height: u64,
+		latest_client_height_on_counterparty: u64,
+	) -> Result<bool, Self::Error> {
+		let latest = self.block(BlockReference::BlockId(BlockId::Height(latest_height))).await?;
+		let client_block = self
+			.block(BlockReference::BlockId(BlockId::Height(latest_client_height_on_counterparty)))
+			.await?;
+		// the client can't follow the chain into a new epoch without a light block of it
+		Ok(latest.header.epoch_id != client_block.header.epoch_id)
+	}
+
+	async fn initialize_client_state(
+		&self,
+	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
+		// the light block returned for the parent of the final block is the final block itself
+		let final_block = self.final_block().await?;
+		let light_block = self
+			.rpc_client
+			.call(RpcLightClientNextBlockRequest { last_block_hash: final_block.header.prev_hash })
+			.await?
+			.ok_or_else(|| Error::from("No light block for the final block".to_string()))?;
+		let head: LightClientBlockView = borsh_convert(&light_block)?;
+
+		let current_validators =
+			self.block_producers(head.current_block_hash::<HostFunctionsManager>()).await?;
+		// blocks of the next epoch are verified once the light block carrying its block producers
+		// has been relayed
+		let next_validators = head.next_bps.clone().unwrap_or_default();
+		let consensus_state = ConsensusState::from_light_block(&head);
+		let epoch_duration = EXPECTED_BLOCK_TIME
+			.saturating_mul(u32::try_from(self.epoch_length).unwrap_or(u32::MAX));
+		let client_state = NearClientState::<HostFunctionsManager>::new(
+			self.chain_id.clone(),
+			head,
+			current_validators,
+			next_validators,
+			trusting_period(epoch_duration),
+		);
+		Ok((AnyClientState::Near(client_state), AnyConsensusState::Near(consensus_state)))
+	}
+
+	async fn query_client_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ClientId, Self::Error> {
+		self.transaction_events(tx_id)
+			.await?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::CreateClient(ev) => Some(ev.client_id().clone()),
+				_ => None,
+			})
+			.ok_or_else(|| Error::Custom(format!("No client was created in transaction {tx_id}")))
+	}
+
+	async fn query_connection_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ConnectionId, Self::Error> {
+		self.transaction_events(tx_id)
+			.await?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::OpenInitConnection(ev) => ev.connection_id().cloned(),
+				IbcEvent::OpenTryConnection(ev) => ev.connection_id().cloned(),
+				_ => None,
+			})
+			.ok_or_else(|| {
+				Error::Custom(format!("No connection was created in transaction {tx_id}"))
+			})
+	}
+
+	async fn query_channel_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<(ChannelId, PortId), Self::Error> {
+		self.transaction_events(tx_id)
+			.await?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::OpenInitChannel(ev) =>
+					ev.channel_id().map(|channel_id| (*channel_id, ev.port_id().clone())),
+				IbcEvent::OpenTryChannel(ev) =>
+					ev.channel_id().map(|channel_id| (*channel_id, ev.port_id().clone())),
+				_ => None,
+			})
+			.ok_or_else(|| Error::Custom(format!("No channel was created in transaction {tx_id}")))
+	}
+
+	async fn upload_wasm(&self, _wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error> {
+		Err(Error::Custom("Uploading WASM to NEAR is not supported".to_string()))
 	}
 }
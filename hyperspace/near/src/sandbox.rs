@@ -0,0 +1,360 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-process stand-in for a NEAR sandbox node with the IBC contract deployed, serving the
+//! JSON-RPC methods used by the relayer.
+//!
+//! The final block is the light block of the `ics13-near` fixtures, the headers of the other
+//! blocks are derived from it. View calls of the contract are answered with the results set with
+//! [`SandboxStandIn::set_view`], and transactions always succeed, recording the contract methods
+//! they call.
+
+use crate::{
+	client::{DEFAULT_EPOCH_LENGTH, DEFAULT_GAS_PER_MESSAGE},
+	error::Error,
+	ClientConfig,
+};
+use borsh::BorshDeserialize;
+use ics13_near::{
+	consensus_state::{ConsensusState, HostConsensusProof},
+	types::CryptoHash as NearCryptoHash,
+};
+use near_crypto::{KeyType, SecretKey, Signature};
+use near_primitives::{
+	block_header::BlockHeader,
+	hash::CryptoHash,
+	serialize::from_base64,
+	transaction::{Action, SignedTransaction},
+	types::{AccountId, BlockHeight},
+	views::{BlockHeaderView, BlockView, SignedTransactionView},
+};
+use pallet_ibc::light_clients::HostFunctionsManager;
+use primitives::CommonClientConfig;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::{TcpListener, TcpStream},
+	task::JoinHandle,
+};
+
+/// Height of the final block, the one of the light block fixture
+pub const FINAL_HEIGHT: BlockHeight = 100;
+/// Account of the IBC contract
+pub const CONTRACT_ID: &str = "ibc.test.near";
+/// Account the transactions are signed for
+pub const SIGNER_ID: &str = "test.near";
+/// Nanoseconds between two blocks
+const BLOCK_TIME: u64 = 1_000_000_000;
+
+const LIGHT_BLOCK: &str =
+	include_str!("../../../light-clients/ics13-near/src/fixtures/light_client_block_100.json");
+const BLOCK_PRODUCERS: &str =
+	include_str!("../../../light-clients/ics13-near/src/fixtures/block_producers_epoch_1.json");
+
+/// A NEAR JSON-RPC server listening on a local port, stopped when dropped.
+pub struct SandboxStandIn {
+	/// Url the server listens on
+	pub rpc_url: String,
+	state: Arc<Mutex<State>>,
+	handle: JoinHandle<()>,
+}
+
+struct State {
+	light_block: Value,
+	block_producers: Value,
+	views: HashMap<String, Value>,
+	function_calls: Vec<String>,
+}
+
+impl SandboxStandIn {
+	/// Starts the server on a free local port.
+	pub async fn spawn() -> Result<Self, Error> {
+		let listener = TcpListener::bind("127.0.0.1:0").await?;
+		let rpc_url = format!("http://{}", listener.local_addr()?);
+		let state = Arc::new(Mutex::new(State {
+			light_block: serde_json::from_str(LIGHT_BLOCK)?,
+			block_producers: serde_json::from_str(BLOCK_PRODUCERS)?,
+			views: HashMap::new(),
+			function_calls: vec![],
+		}));
+		let handle = tokio::spawn(serve(listener, state.clone()));
+		Ok(Self { rpc_url, state, handle })
+	}
+
+	/// Config of a relayer client connected to the stand-in.
+	pub fn client_config(&self) -> ClientConfig {
+		ClientConfig {
+			name: "near".to_string(),
+			rpc_url: self.rpc_url.clone(),
+			chain_id: "sandbox".to_string(),
+			contract_id: CONTRACT_ID.parse().expect("Account Id should be valid"),
+			signer_id: SIGNER_ID.parse().expect("Account Id should be valid"),
+			client_id: None,
+			connection_id: None,
+			channel_whitelist: vec![],
+			commitment_prefix: "ibc".to_string(),
+			gas_per_message: DEFAULT_GAS_PER_MESSAGE,
+			epoch_length: DEFAULT_EPOCH_LENGTH,
+			wasm_checksum: None,
+			secret_key: Some(SecretKey::from_seed(KeyType::ED25519, SIGNER_ID).to_string()),
+			key_source: None,
+			common: CommonClientConfig {
+				skip_optional_client_updates: true,
+				max_packets_to_process: 200,
+			},
+		}
+	}
+
+	/// Sets the result of a view method of the contract.
+	pub fn set_view<R: Serialize>(&self, method: &str, result: &R) -> Result<(), Error> {
+		let result = serde_json::to_value(result)?;
+		self.state.lock().unwrap().views.insert(method.to_string(), result);
+		Ok(())
+	}
+
+	/// Methods of the contract called by the transactions, in order.
+	pub fn function_calls(&self) -> Vec<String> {
+		self.state.lock().unwrap().function_calls.clone()
+	}
+
+	/// Verifies a host consensus proof against the block at the given height, as the contract
+	/// does.
+	pub fn host_consensus_state(
+		&self,
+		height: BlockHeight,
+		proof: &[u8],
+	) -> Result<ConsensusState, Error> {
+		let block_hash = self.state.lock().unwrap().block(height)?.header.hash;
+		let proof = HostConsensusProof::try_from_slice(proof)?;
+		Ok(proof.verify::<HostFunctionsManager>(&NearCryptoHash(block_hash.0))?)
+	}
+}
+
+impl Drop for SandboxStandIn {
+	fn drop(&mut self) {
+		self.handle.abort();
+	}
+}
+
+impl State {
+	fn block(&self, height: BlockHeight) -> Result<BlockView, Error> {
+		let inner_lite = &self.light_block["inner_lite"];
+		let derived_hash =
+			|field: &str| json!(CryptoHash::hash_bytes(format!("{field}/{height}").as_bytes()));
+		// the block of the fixture keeps its state and outcome roots, so that its consensus state
+		// is the one of the light block
+		let (prev_hash, prev_state_root, outcome_root) = if height == FINAL_HEIGHT {
+			(
+				self.light_block["prev_block_hash"].clone(),
+				inner_lite["prev_state_root"].clone(),
+				inner_lite["outcome_root"].clone(),
+			)
+		} else {
+			(
+				derived_hash("prev_hash"),
+				derived_hash("prev_state_root"),
+				derived_hash("outcome_root"),
+			)
+		};
+		let timestamp = inner_lite["timestamp"]
+			.as_u64()
+			.ok_or_else(|| Error::from("Invalid light block timestamp".to_string()))?
+			.saturating_sub(FINAL_HEIGHT * BLOCK_TIME) +
+			height * BLOCK_TIME;
+		let header = json!({
+			"height": height,
+			"prev_height": height.checked_sub(1),
+			"epoch_id": inner_lite["epoch_id"],
+			"next_epoch_id": inner_lite["next_epoch_id"],
+			"hash": CryptoHash::default(),
+			"prev_hash": prev_hash,
+			"prev_state_root": prev_state_root,
+			"chunk_receipts_root": CryptoHash::default(),
+			"chunk_headers_root": CryptoHash::default(),
+			"chunk_tx_root": CryptoHash::default(),
+			"outcome_root": outcome_root,
+			"chunks_included": 0,
+			"challenges_root": CryptoHash::default(),
+			"timestamp": timestamp,
+			"timestamp_nanosec": timestamp.to_string(),
+			"random_value": CryptoHash::default(),
+			"validator_proposals": [],
+			"chunk_mask": [],
+			"gas_price": "0",
+			"block_ordinal": null,
+			"rent_paid": "0",
+			"validator_reward": "0",
+			"total_supply": "0",
+			"challenges_result": [],
+			"last_final_block": prev_hash,
+			"last_ds_final_block": prev_hash,
+			"next_bp_hash": inner_lite["next_bp_hash"],
+			"block_merkle_root": inner_lite["block_merkle_root"],
+			"epoch_sync_data_hash": null,
+			"approvals": [],
+			"signature": Signature::empty(KeyType::ED25519),
+			"latest_protocol_version": 29,
+		});
+		let mut header: BlockHeaderView = serde_json::from_value(header)?;
+		header.hash = *BlockHeader::from(header.clone()).hash();
+		let author: AccountId = SIGNER_ID.parse().expect("Account Id should be valid");
+		Ok(BlockView { author, header, chunks: vec![] })
+	}
+
+	fn query(&self, params: &Value) -> Result<Value, Error> {
+		let height = block_height(params);
+		let mut result = match params["request_type"].as_str() {
+			Some("call_function") => {
+				let method = params["method_name"].as_str().unwrap_or_default();
+				let view = self
+					.views
+					.get(method)
+					.ok_or_else(|| Error::from(format!("Unknown view method {method}")))?;
+				json!({ "result": serde_json::to_vec(view)?, "logs": [] })
+			},
+			Some("view_access_key") =>
+				json!({ "nonce": self.function_calls.len(), "permission": "FullAccess" }),
+			request_type => return Err(Error::from(format!("Unsupported query {request_type:?}"))),
+		};
+		result["block_height"] = json!(height);
+		result["block_hash"] = json!(self.block(height)?.header.hash);
+		Ok(result)
+	}
+
+	fn broadcast_tx_commit(&mut self, params: &Value) -> Result<Value, Error> {
+		let encoded = params[0]
+			.as_str()
+			.ok_or_else(|| Error::from("Missing signed transaction".to_string()))?;
+		let bytes = from_base64(encoded).map_err(|e| Error::from(e.to_string()))?;
+		let tx = SignedTransaction::try_from_slice(&bytes)?;
+		for action in &tx.transaction.actions {
+			if let Action::FunctionCall(call) = action {
+				self.function_calls.push(call.method_name.clone());
+			}
+		}
+		let block_hash = self.block(FINAL_HEIGHT)?.header.hash;
+		let tx_hash = tx.get_hash();
+		let signer_id = tx.transaction.signer_id.clone();
+		Ok(json!({
+			"status": { "SuccessValue": "" },
+			"transaction": SignedTransactionView::from(tx),
+			"transaction_outcome": {
+				"proof": [],
+				"block_hash": block_hash,
+				"id": tx_hash,
+				"outcome": {
+					"logs": [],
+					"receipt_ids": [],
+					"gas_burnt": 0,
+					"tokens_burnt": "0",
+					"executor_id": signer_id,
+					"status": { "SuccessValue": "" },
+					"metadata": { "version": 1, "gas_profile": null },
+				},
+			},
+			"receipts_outcome": [],
+		}))
+	}
+
+	fn handle(&mut self, method: &str, params: &Value) -> Result<Value, Error> {
+		match method {
+			"block" => Ok(serde_json::to_value(self.block(block_height(params))?)?),
+			"query" => self.query(params),
+			// the light block of the fixture is returned whatever the last known block is
+			"next_light_client_block" => Ok(self.light_block.clone()),
+			"EXPERIMENTAL_validators_ordered" => Ok(self.block_producers.clone()),
+			"broadcast_tx_commit" => self.broadcast_tx_commit(params),
+			method => Err(Error::from(format!("Unsupported method {method}"))),
+		}
+	}
+}
+
+/// Height of the block referenced by the request, the final block unless a height is given
+fn block_height(params: &Value) -> BlockHeight {
+	params["block_id"].as_u64().unwrap_or(FINAL_HEIGHT)
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<State>>) {
+	loop {
+		let Ok((stream, _)) = listener.accept().await else { continue };
+		let state = state.clone();
+		tokio::spawn(async move {
+			if let Err(e) = handle_connection(stream, state).await {
+				log::warn!(target: "hyperspace_near", "Sandbox stand-in request failed: {e}");
+			}
+		});
+	}
+}
+
+/// Answers a single HTTP request carrying a JSON-RPC call, then closes the connection.
+async fn handle_connection(mut stream: TcpStream, state: Arc<Mutex<State>>) -> Result<(), Error> {
+	let mut buf = vec![];
+	let mut chunk = [0u8; 4096];
+	let body_start = loop {
+		let read = stream.read(&mut chunk).await?;
+		if read == 0 {
+			return Ok(())
+		}
+		buf.extend_from_slice(&chunk[..read]);
+		if let Some(pos) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+			break pos + 4
+		}
+	};
+	let headers = String::from_utf8_lossy(&buf[..body_start]).to_lowercase();
+	let content_length = headers
+		.lines()
+		.find_map(|line| line.strip_prefix("content-length:"))
+		.and_then(|length| length.trim().parse::<usize>().ok())
+		.unwrap_or_default();
+	while buf.len() < body_start + content_length {
+		let read = stream.read(&mut chunk).await?;
+		if read == 0 {
+			break
+		}
+		buf.extend_from_slice(&chunk[..read]);
+	}
+
+	let request: Value = serde_json::from_slice(&buf[body_start..])?;
+	let method = request["method"].as_str().unwrap_or_default();
+	let result = state.lock().unwrap().handle(method, &request["params"]);
+	let response = match result {
+		Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+		Err(e) => json!({
+			"jsonrpc": "2.0",
+			"id": request["id"],
+			"error": {
+				"code": -32000,
+				"message": "Server error",
+				"data": e.to_string(),
+				"name": "HANDLER_ERROR",
+				"cause": { "name": "INTERNAL_ERROR", "info": { "error_message": e.to_string() } },
+			},
+		}),
+	};
+	let body = serde_json::to_vec(&response)?;
+	let head = format!(
+		"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+		 content-length: {}\r\nconnection: close\r\n\r\n",
+		body.len()
+	);
+	stream.write_all(head.as_bytes()).await?;
+	stream.write_all(&body).await?;
+	stream.shutdown().await?;
+	Ok(())
+}
@@ -0,0 +1,57 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{error::Error, Client};
+use futures::{Stream, StreamExt};
+use ibc::{
+	applications::transfer::{msgs::transfer::MsgTransfer, PrefixedCoin},
+	core::ics24_host::identifier::ChannelId,
+	tx_msg::Msg,
+};
+use primitives::{Chain, TestProvider};
+use std::pin::Pin;
+
+#[async_trait::async_trait]
+impl TestProvider for Client {
+	/// Initiate an ibc transfer on chain.
+	async fn send_transfer(&self, msg: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
+		let hash = self.submit(vec![msg.to_any()]).await?;
+		log::info!(target: "hyperspace_near", "🤝 Transfer transaction confirmed with hash: {:?}", hash);
+		Ok(())
+	}
+
+	/// Send a packet on an ordered channel
+	async fn send_ordered_packet(
+		&self,
+		_channel_id: ChannelId,
+		_timeout: pallet_ibc::Timeout,
+	) -> Result<(), Self::Error> {
+		Err(Error::Custom("send_ordered_packet is not implemented yet".to_string()))
+	}
+
+	/// Returns a stream that yields chain Block number
+	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
+		let stream = self
+			.finality_notifications()
+			.await
+			.expect("Failed to subscribe to finality notifications")
+			.map(|event| event.height);
+		Box::pin(stream)
+	}
+
+	async fn increase_counters(&mut self) -> Result<(), Self::Error> {
+		let args = serde_json::to_vec(&serde_json::json!({}))?;
+		self.call("increase_counters", args, self.gas_per_message, 0).await.map(|_| ())
+	}
+}
@@ -12,31 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{error::Error, signer::ExtrinsicSigner, ParachainClient};
+use super::{error::Error, ParachainClient};
 use crate::{parachain::UncheckedExtrinsic, provider::TransactionId, FinalityProtocol};
 use anyhow::anyhow;
 use codec::{Decode, Encode};
 use finality_grandpa::BlockNumberOps;
 use finality_grandpa_rpc::GrandpaApiClient;
-use futures::{Stream, StreamExt, TryFutureExt};
+use futures::{Stream, StreamExt};
 use grandpa_light_client_primitives::{FinalityProof, ParachainHeaderProofs};
 use ibc::{
 	core::{
-		ics02_client::{
-			events::UpdateClient,
-			msgs::{update_client::MsgUpdateAnyClient, ClientMsg},
-		},
+		ics02_client::{events::UpdateClient, msgs::ClientMsg},
 		ics26_routing::msgs::Ics26Envelope,
 	},
 	events::IbcEvent,
-	tx_msg::Msg,
 	Height,
 };
 use ibc_proto::google::protobuf::Any;
 use ics10_grandpa::client_message::{ClientMessage, Misbehaviour, RelayChainHeader};
 use itertools::Itertools;
 use jsonrpsee_ws_client::WsClientBuilder;
-use light_client_common::config::{EventRecordT, RuntimeCall, RuntimeTransactions};
+use light_client_common::config::{
+	AccountInfoT, AsInner, EventRecordT, RuntimeCall, RuntimeStorage, RuntimeTransactions,
+};
 use pallet_ibc::light_clients::AnyClientMessage;
 use primitives::{
 	mock::LocalClientTypes, Chain, CommonClientState, IbcProvider, MisbehaviourHandler,
@@ -104,13 +102,6 @@ where
 
 	async fn estimate_weight(&self, messages: Vec<Any>) -> Result<u64, Self::Error> {
 		let extrinsic = {
-			// todo: put this in utils
-			let signer = ExtrinsicSigner::<T, Self>::new(
-				self.key_store.clone(),
-				self.key_type_id.clone(),
-				self.public_key.clone(),
-			);
-
 			let messages = messages
 				.into_iter()
 				.map(|msg| Any { type_url: msg.type_url.clone(), value: msg.value })
@@ -120,12 +111,7 @@ where
 				.tip(T::Tip::from(100_000u128))
 				.era(Era::Immortal, self.para_client.genesis_hash());
 			let call = T::Tx::ibc_deliver(messages);
-			self.para_client
-				.tx()
-				.create_signed(&call, &signer, tx_params.into())
-				.await?
-				.encoded()
-				.to_vec()
+			self.create_signed(&call, tx_params.into()).await?.encoded().to_vec()
 		};
 		let dispatch_info = TransactionPaymentApiClient::<
 			H256,
@@ -323,6 +309,24 @@ where
 		block_height
 	}
 
+	async fn query_signer_balance(&self) -> Result<Option<u128>, Self::Error> {
+		let account: [u8; 32] = self.public_key.clone().into_account().into();
+		let block_hash = self
+			.para_client
+			.rpc()
+			.block_hash(None)
+			.await?
+			.ok_or_else(|| Error::from("Latest block hash query returned None".to_string()))?;
+		let account_addr = T::Storage::system_account(account);
+		// the account doesn't exist when it doesn't have any balance
+		let balance = match self.para_client.storage().at(block_hash).fetch(&account_addr).await? {
+			Some(account_info) =>
+				<T::Storage as RuntimeStorage>::AccountInfo::from_inner(account_info).free_balance(),
+			None => 0,
+		};
+		Ok(Some(balance))
+	}
+
 	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error> {
 		let err_str = if let Some(rpc_err) = error.downcast_ref::<Error>() {
 			match rpc_err {
@@ -501,14 +505,10 @@ where
 						second_finality_proof: trusted_finality_proof,
 					});
 
-					counterparty
-						.submit(vec![MsgUpdateAnyClient::<LocalClientTypes>::new(
-							self.client_id(),
-							AnyClientMessage::Grandpa(misbehaviour.clone()),
-							counterparty.account_id(),
-						)
-						.to_any()])
-						.map_err(|e| anyhow!("Failed to submit misbehaviour report: {:?}", e))
+					// the report is submitted to the counterparty by the relayer loop, it is
+					// persisted until then if the relayer store is enabled
+					self.common_state
+						.queue_misbehaviour_message(AnyClientMessage::Grandpa(misbehaviour))
 						.await?;
 				}
 			},
@@ -40,11 +40,13 @@ use frame_support::Serialize;
 use serde::Deserialize;
 
 use crate::{
-	finality_protocol::FinalityProtocol, signer::ExtrinsicSigner, utils::fetch_max_extrinsic_weight,
+	finality_protocol::FinalityProtocol,
+	signer::{ExtrinsicSigner, KeystoreSigner},
+	utils::fetch_max_extrinsic_weight,
 };
 use beefy_light_client_primitives::{ClientState, MmrUpdateProof};
 use beefy_prover::Prover;
-use codec::Decode;
+use codec::{Decode, Encode};
 use grandpa_light_client_primitives::ParachainHeaderProofs;
 use grandpa_prover::GrandpaProver;
 use ibc::{
@@ -60,10 +62,16 @@ use ics11_beefy::{
 	consensus_state::ConsensusState as BeefyConsensusState,
 };
 use jsonrpsee_ws_client::WsClientBuilder;
-use light_client_common::config::{AsInner, RuntimeStorage};
+use light_client_common::{
+	config::{AsInner, CustomExtrinsicParams, RuntimeStorage},
+	RelayChain,
+};
 use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager};
 use pallet_mmr_primitives::Proof;
-use primitives::{CommonClientState, KeyProvider};
+use primitives::{
+	keys::{load_key, Key, KeySource, SigningBackend},
+	CommonClientState, KeyProvider,
+};
 use sc_keystore::LocalKeystore;
 use sp_core::{ecdsa, ed25519, sr25519, Bytes, Pair, H256};
 use sp_keystore::KeystorePtr;
@@ -74,7 +82,7 @@ use sp_runtime::{
 use ss58_registry::Ss58AddressFormat;
 use subxt::{
 	config::{Header as HeaderT, Header},
-	tx::TxPayload,
+	tx::{SubmittableExtrinsic, TxPayload},
 };
 use tokio::sync::Mutex as AsyncMutex;
 
@@ -89,12 +97,14 @@ pub struct ParachainClient<T: light_client_common::config::Config> {
 	pub name: String,
 	/// rpc url for parachain
 	pub parachain_rpc_url: String,
-	/// rpc url for relay chain
+	/// rpc url for relay chain, the parachain rpc url for solo chains
 	pub relay_chain_rpc_url: String,
 	/// Relay chain rpc client
 	pub relay_client: subxt::OnlineClient<T>,
 	/// Parachain rpc client
 	pub para_client: subxt::OnlineClient<T>,
+	/// Parameters of the relay chain, stored in the client states created for this chain
+	pub relay_chain: RelayChain,
 	/// Relay chain ws client
 	pub relay_ws_client: Arc<jsonrpsee_ws_client::WsClient>,
 	/// Parachain ws client
@@ -111,16 +121,17 @@ pub struct ParachainClient<T: light_client_common::config::Config> {
 	pub commitment_prefix: Vec<u8>,
 	/// Public key for relayer on chain
 	pub public_key: MultiSigner,
-	/// Reference to keystore
-	pub key_store: KeystorePtr,
-	/// Key type Id
-	pub key_type_id: KeyTypeId,
+	/// Signs the extrinsics submitted by the relayer
+	pub signer: Arc<dyn SigningBackend>,
 	/// used for encoding relayer address.
 	pub ss58_version: Ss58AddressFormat,
 	/// the maximum extrinsic weight allowed by this client
 	pub max_extrinsic_weight: u64,
 	/// Finality protocol to use, eg Beefy, Grandpa
 	pub finality_protocol: FinalityProtocol,
+	/// Whether this is a standalone chain following its own GRANDPA finality, in which case the
+	/// relay chain clients point to the chain itself.
+	pub solo_chain: bool,
 	/// Common relayer data
 	pub common_state: CommonClientState,
 }
@@ -142,6 +153,39 @@ impl KeyType {
 			KeyType::Ecdsa => KeyTypeId(ecdsa::CRYPTO_ID.0),
 		}
 	}
+
+	/// Derives the public key from a secret URI
+	pub fn public_from_secret(&self, secret: &str) -> Result<MultiSigner, Error> {
+		let public_key = match self {
+			KeyType::Sr25519 => sr25519::Pair::from_string_with_seed(secret, None)
+				.map_err(|_| Error::Custom("invalid key".to_owned()))?
+				.0
+				.public()
+				.into(),
+			KeyType::Ed25519 => ed25519::Pair::from_string_with_seed(secret, None)
+				.map_err(|_| Error::Custom("invalid key".to_owned()))?
+				.0
+				.public()
+				.into(),
+			KeyType::Ecdsa => ecdsa::Pair::from_string_with_seed(secret, None)
+				.map_err(|_| Error::Custom("invalid key".to_owned()))?
+				.0
+				.public()
+				.into(),
+		};
+		Ok(public_key)
+	}
+
+	/// Decodes a raw public key of this type
+	pub fn public_from_bytes(&self, bytes: &[u8]) -> Result<MultiSigner, Error> {
+		let invalid_key = |_| Error::Custom("invalid public key".to_owned());
+		let public_key = match self {
+			KeyType::Sr25519 => sr25519::Public::try_from(bytes).map_err(invalid_key)?.into(),
+			KeyType::Ed25519 => ed25519::Public::try_from(bytes).map_err(invalid_key)?.into(),
+			KeyType::Ecdsa => ecdsa::Public::try_from(bytes).map_err(invalid_key)?.into(),
+		};
+		Ok(public_key)
+	}
 }
 
 impl FromStr for KeyType {
@@ -166,22 +210,35 @@ pub struct ParachainClientConfig {
 	pub para_id: u32,
 	/// rpc url for parachain
 	pub parachain_rpc_url: String,
-	/// rpc url for relay chain
+	/// rpc url for relay chain, unused for solo chains
+	#[serde(default)]
 	pub relay_chain_rpc_url: String,
+	/// Parameters of the relay chain, defaults to Rococo
+	#[serde(default)]
+	pub relay_chain: RelayChain,
 	/// Light client id on counterparty chain
 	pub client_id: Option<ClientId>,
 	/// Connection Id
 	pub connection_id: Option<ConnectionId>,
 	/// Commitment prefix
 	pub commitment_prefix: Bytes,
-	/// Raw private key for signing transactions
-	pub private_key: String,
+	/// Raw private key for signing transactions. Prefer `key_source`, so that the key isn't
+	/// stored in plaintext in the config.
+	#[serde(default)]
+	pub private_key: Option<String>,
+	/// Where the key for signing transactions is loaded from
+	#[serde(default)]
+	pub key_source: Option<KeySource>,
 	/// used for encoding relayer address.
 	pub ss58_version: u8,
 	/// Channels cleared for packet relay
 	pub channel_whitelist: Vec<(ChannelId, PortId)>,
 	/// Finality protocol
 	pub finality_protocol: FinalityProtocol,
+	/// Follow a standalone chain's own GRANDPA finality instead of a parachain's relay chain.
+	/// Only supported with the GRANDPA finality protocol.
+	#[serde(default)]
+	pub solo_chain: bool,
 	/// Digital signature scheme
 	pub key_type: String,
 	/// All the client states and headers will be wrapped in WASM ones using the WASM code ID.
@@ -194,7 +251,16 @@ where
 	T: light_client_common::config::Config,
 {
 	/// Initializes a [`ParachainClient`] given a [`ParachainConfig`]
-	pub async fn new(config: ParachainClientConfig) -> Result<Self, Error> {
+	pub async fn new(mut config: ParachainClientConfig) -> Result<Self, Error> {
+		if config.solo_chain {
+			if !matches!(config.finality_protocol, FinalityProtocol::Grandpa) {
+				Err(Error::Custom(
+					"Solo chains are only supported with GRANDPA finality".to_string(),
+				))?
+			}
+			// a solo chain is its own "relay chain"
+			config.relay_chain_rpc_url = config.parachain_rpc_url.clone();
+		}
 		let relay_ws_client = Arc::new(
 			WsClientBuilder::default()
 				.build(&config.relay_chain_rpc_url)
@@ -214,38 +280,33 @@ where
 
 		let max_extrinsic_weight = fetch_max_extrinsic_weight(&para_client).await?;
 
-		let temp_dir = PathBuf::from("/tmp/keystore");
-		let key_store: KeystorePtr = Arc::new(LocalKeystore::open(temp_dir, None).unwrap());
 		let key_type = KeyType::from_str(&config.key_type)?;
-		let key_type_id = key_type.to_key_type_id();
-
-		let public_key: MultiSigner = match key_type {
-			KeyType::Sr25519 => sr25519::Pair::from_string_with_seed(&config.private_key, None)
-				.map_err(|_| Error::Custom("invalid key".to_owned()))?
-				.0
-				.public()
-				.into(),
-			KeyType::Ed25519 => ed25519::Pair::from_string_with_seed(&config.private_key, None)
-				.map_err(|_| Error::Custom("invalid key".to_owned()))?
-				.0
-				.public()
-				.into(),
-			KeyType::Ecdsa => ecdsa::Pair::from_string_with_seed(&config.private_key, None)
-				.map_err(|_| Error::Custom("invalid key".to_owned()))?
-				.0
-				.public()
-				.into(),
-		};
-
-		key_store
-			.insert(key_type_id, &*config.private_key, public_key.as_ref())
-			.unwrap();
+		let (public_key, signer): (MultiSigner, Arc<dyn SigningBackend>) =
+			match load_key(config.private_key.as_deref(), config.key_source.as_ref())
+				.map_err(|e| Error::Custom(e.to_string()))?
+			{
+				Key::Secret(secret) => {
+					let temp_dir = PathBuf::from("/tmp/keystore");
+					let key_store: KeystorePtr =
+						Arc::new(LocalKeystore::open(temp_dir, None).unwrap());
+					let key_type_id = key_type.to_key_type_id();
+					let public_key = key_type.public_from_secret(&secret)?;
+
+					key_store.insert(key_type_id, &*secret, public_key.as_ref()).unwrap();
+
+					assert!(key_store.has_keys(&[(public_key.as_ref().to_vec(), key_type_id)]));
+					let signer = KeystoreSigner::new(key_store, key_type_id, public_key.clone());
+					(public_key, Arc::new(signer))
+				},
+				Key::Remote(remote) =>
+					(key_type.public_from_bytes(remote.public_key())?, Arc::new(remote)),
+			};
 
-		assert!(key_store.has_keys(&[(public_key.as_ref().to_vec(), key_type_id)]));
 		Ok(Self {
 			name: config.name,
 			parachain_rpc_url: config.parachain_rpc_url,
 			relay_chain_rpc_url: config.relay_chain_rpc_url,
+			relay_chain: config.relay_chain,
 			para_client,
 			relay_client,
 			para_id: config.para_id,
@@ -253,14 +314,14 @@ where
 			commitment_prefix: config.commitment_prefix.0,
 			connection_id: Arc::new(Mutex::new(config.connection_id)),
 			public_key,
-			key_store,
-			key_type_id,
+			signer,
 			max_extrinsic_weight,
 			para_ws_client,
 			relay_ws_client,
 			ss58_version: Ss58AddressFormat::from(config.ss58_version),
 			channel_whitelist: Arc::new(Mutex::new(config.channel_whitelist.into_iter().collect())),
 			finality_protocol: config.finality_protocol,
+			solo_chain: config.solo_chain,
 			common_state: CommonClientState {
 				skip_optional_client_updates: true,
 				maybe_has_undelivered_packets: Arc::new(Mutex::new(Default::default())),
@@ -299,6 +360,7 @@ where
 			para_ws_client,
 			para_id: self.para_id,
 			rpc_call_delay: self.common_state.rpc_call_delay,
+			solo_chain: self.solo_chain,
 		}
 	}
 
@@ -401,6 +463,25 @@ where
 		Ok(mmr_update)
 	}
 
+	/// Signs the call with the relayer key, fails instead of panicking when the signing backend is
+	/// unavailable.
+	pub async fn create_signed<C: TxPayload>(
+		&self,
+		call: &C,
+		other_params: CustomExtrinsicParams<T>,
+	) -> Result<SubmittableExtrinsic<T, subxt::OnlineClient<T>>, Error> {
+		let signer = ExtrinsicSigner::<T, Self>::new(self.signer.clone(), self.public_key.clone());
+		let partial_extrinsic = self
+			.para_client
+			.tx()
+			.create_partial_signed(call, signer.account_id(), other_params)
+			.await?;
+		let signature = signer
+			.sign(&partial_extrinsic.signer_payload())
+			.map_err(|e| Error::Custom(format!("Failed to sign extrinsic: {e}")))?;
+		Ok(partial_extrinsic.sign_with_address_and_signature(&signer.address(), &signature))
+	}
+
 	/// Submits the given transaction to the parachain node, waits for it to be included in a block
 	/// and asserts that it was successfully dispatched on-chain.
 	///
@@ -416,16 +497,9 @@ where
 
 			let other_params = T::custom_extrinsic_params(&self.para_client).await?;
 
-			let res = {
-				let signer = ExtrinsicSigner::<T, Self>::new(
-					self.key_store.clone(),
-					self.key_type_id.clone(),
-					self.public_key.clone(),
-				);
-				self.para_client
-					.tx()
-					.sign_and_submit_then_watch(&call, &signer, other_params)
-					.await
+			let res = match self.create_signed(&call, other_params).await {
+				Ok(extrinsic) => extrinsic.submit_and_watch().await.map_err(Error::from),
+				Err(e) => Err(e),
 			};
 			match res {
 				Ok(progress) => break progress,
@@ -523,7 +597,7 @@ where
 			let block_number = decoded_para_head.number;
 			let client_state = BeefyClientState::<HostFunctionsManager> {
 				chain_id: ChainId::new("relay-chain".to_string(), 0),
-				relay_chain: Default::default(),
+				relay_chain: self.relay_chain,
 				mmr_root_hash: beefy_state.mmr_root_hash,
 				latest_beefy_height: beefy_state.latest_beefy_height,
 				frozen_height: None,
@@ -584,6 +658,7 @@ where
 			para_ws_client,
 			para_id: self.para_id,
 			rpc_call_delay: self.common_state.rpc_call_delay,
+			solo_chain: self.solo_chain,
 		};
 		let api = self.relay_client.storage();
 		let para_client_api = self.para_client.storage();
@@ -593,22 +668,39 @@ where
 				.await
 				.map_err(|e| Error::from(format!("Error constructing client state: {e}")))?;
 
-			let heads_addr = T::Storage::paras_heads(self.para_id);
-			let head_data = <T::Storage as RuntimeStorage>::HeadData::from_inner(
-				api.at(light_client_state.latest_relay_hash.into())
-					.fetch(&heads_addr)
+			// a solo chain finalizes its own headers
+			let head_data = if self.solo_chain {
+				self.relay_client
+					.rpc()
+					.header(Some(light_client_state.latest_relay_hash.into()))
 					.await?
 					.ok_or_else(|| {
+						Error::Custom(format!(
+							"Couldn't find header for block {:?}",
+							light_client_state.latest_relay_hash
+						))
+					})?
+					.encode()
+			} else {
+				let heads_addr = T::Storage::paras_heads(self.para_id);
+				<T::Storage as RuntimeStorage>::HeadData::from_inner(
+					api.at(light_client_state.latest_relay_hash.into())
+						.fetch(&heads_addr)
+						.await?
+						.ok_or_else(|| {
 						Error::Custom(format!(
 							"Couldn't find header for ParaId({}) at relay block {:?}",
 							self.para_id, light_client_state.latest_relay_hash
 						))
 					})?,
-			);
+				)
+				.as_ref()
+				.to_vec()
+			};
 			let decoded_para_head = sp_runtime::generic::Header::<
 				u32,
 				sp_runtime::traits::BlakeTwo256,
-			>::decode(&mut &*head_data.as_ref())?;
+			>::decode(&mut &*head_data)?;
 			let block_number = decoded_para_head.number;
 			// we can't use the genesis block to construct the initial state.
 			if block_number == 0 {
@@ -617,7 +709,7 @@ where
 
 			let mut client_state = GrandpaClientState::<HostFunctionsManager>::default();
 
-			client_state.relay_chain = Default::default();
+			client_state.relay_chain = self.relay_chain;
 			client_state.current_authorities = light_client_state.current_authorities;
 			client_state.current_set_id = light_client_state.current_set_id;
 			client_state.latest_relay_hash = light_client_state.latest_relay_hash.into();
@@ -625,6 +717,7 @@ where
 			client_state.latest_para_height = block_number;
 			client_state.para_id = self.para_id;
 			client_state.latest_relay_height = light_client_state.latest_relay_height;
+			client_state.solo_chain = self.solo_chain;
 
 			let subxt_block_number: subxt::rpc::types::BlockNumber = block_number.into();
 			let block_hash =
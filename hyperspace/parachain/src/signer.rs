@@ -13,21 +13,61 @@
 // limitations under the License.
 
 use codec::Decode;
-use primitives::KeyProvider;
+use primitives::{error::Error, keys::SigningBackend, KeyProvider};
 use sp_keystore::{Keystore, KeystorePtr};
 use sp_runtime::{
 	traits::{IdentifyAccount, Verify},
 	KeyTypeId, MultiSignature, MultiSigner,
 };
-use subxt::tx::Signer;
+use std::sync::Arc;
 
-/// A [`Signer`] implementation.
+/// A [`SigningBackend`] that signs with a key held in a substrate keystore.
+pub struct KeystoreSigner {
+	key_store: KeystorePtr,
+	key_type_id: KeyTypeId,
+	signer: MultiSigner,
+}
+
+impl KeystoreSigner {
+	/// Creates a new [`KeystoreSigner`] for a key that was inserted into the key store
+	pub fn new(key_store: KeystorePtr, key_type_id: KeyTypeId, public_key: MultiSigner) -> Self {
+		Self { key_store, key_type_id, signer: public_key }
+	}
+}
+
+impl SigningBackend for KeystoreSigner {
+	fn public_key(&self) -> &[u8] {
+		self.signer.as_ref()
+	}
+
+	fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+		let crypto_type_id = match &self.signer {
+			MultiSigner::Ed25519(_) => sp_core::ed25519::CRYPTO_ID,
+			MultiSigner::Sr25519(_) => sp_core::sr25519::CRYPTO_ID,
+			MultiSigner::Ecdsa(_) => sp_core::ecdsa::CRYPTO_ID,
+		};
+		Keystore::sign_with(
+			&*self.key_store,
+			self.key_type_id,
+			crypto_type_id,
+			self.signer.as_ref(),
+			payload,
+		)
+		.map_err(|e| Error::Custom(format!("Keystore error: {e}")))?
+		.ok_or_else(|| Error::Custom("Key is missing from the keystore".to_string()))
+	}
+}
+
+/// Signs extrinsics through a [`SigningBackend`].
+///
+/// It doesn't implement subxt's [`Signer`](subxt::tx::Signer), which can't fail, so that an
+/// unavailable backend (eg. a remote signer that is down) is reported as an error instead of a
+/// panic. Extrinsics are signed with [`ParachainClient::create_signed`](crate::ParachainClient).
 #[derive(Clone)]
 pub struct ExtrinsicSigner<T: light_client_common::config::Config, Provider: KeyProvider> {
 	account_id: T::AccountId,
 	signer: MultiSigner,
-	key_store: KeystorePtr,
-	key_type_id: KeyTypeId,
+	backend: Arc<dyn SigningBackend>,
 	_phantom: std::marker::PhantomData<Provider>,
 }
 
@@ -42,8 +82,8 @@ where
 	<T as subxt::Config>::Address: Send + Sync,
 	<T as subxt::Config>::Signature: Send + Sync,
 {
-	/// Creates a new [`Signer`] from a key store reference and key type
-	pub fn new(key_store: KeystorePtr, key_type_id: KeyTypeId, public_key: MultiSigner) -> Self {
+	/// Creates a new [`ExtrinsicSigner`] that signs through the given backend
+	pub fn new(backend: Arc<dyn SigningBackend>, public_key: MultiSigner) -> Self {
 		let account_id =
 			<<T as light_client_common::config::Config>::Signature as Verify>::Signer::from(
 				public_key.clone(),
@@ -51,15 +91,14 @@ where
 			.into_account();
 		Self {
 			account_id,
-			key_store,
-			key_type_id,
+			backend,
 			signer: MultiSigner::from(public_key),
 			_phantom: Default::default(),
 		}
 	}
 }
 
-impl<T, P> Signer<T> for ExtrinsicSigner<T, P>
+impl<T, P> ExtrinsicSigner<T, P>
 where
 	T: light_client_common::config::Config + Send + Sync,
 	T::AccountId: Into<<T as subxt::Config>::Address> + Clone + 'static,
@@ -68,41 +107,26 @@ where
 	<T as subxt::Config>::AccountId: Send + Sync,
 	<T as subxt::Config>::Address: Send + Sync,
 {
-	fn account_id(&self) -> &T::AccountId {
+	pub fn account_id(&self) -> &T::AccountId {
 		&self.account_id
 	}
 
-	fn address(&self) -> <T as subxt::Config>::Address {
+	pub fn address(&self) -> <T as subxt::Config>::Address {
 		self.account_id.clone().into()
 	}
 
-	fn sign(&self, signer_payload: &[u8]) -> <T as subxt::Config>::Signature {
-		let (crypto_type_id, public_key) = match &self.signer {
-			MultiSigner::Ed25519(key) => (sp_core::ed25519::CRYPTO_ID, key.0.to_vec()),
-			MultiSigner::Sr25519(key) => (sp_core::sr25519::CRYPTO_ID, key.0.to_vec()),
-			MultiSigner::Ecdsa(key) => (sp_core::ecdsa::CRYPTO_ID, key.0.to_vec()),
-		};
-		let encoded_sig = Keystore::sign_with(
-			&*self.key_store,
-			self.key_type_id,
-			crypto_type_id,
-			&public_key,
-			signer_payload,
-		)
-		.ok()
-		.flatten()
-		.expect("Signing should not fail");
+	/// Signs the signer payload of an extrinsic
+	pub fn sign(&self, signer_payload: &[u8]) -> Result<<T as subxt::Config>::Signature, Error> {
+		let encoded_sig = self.backend.sign(signer_payload)?;
 		let signature: MultiSignature = match self.signer {
-			MultiSigner::Ed25519(_) => sp_core::ed25519::Signature::decode(&mut &encoded_sig[..])
-				.expect("Should decode same signature type as public key; qed")
-				.into(),
-			MultiSigner::Sr25519(_) => sp_core::sr25519::Signature::decode(&mut &encoded_sig[..])
-				.expect("Should decode same signature type as public key; qed")
-				.into(),
-			MultiSigner::Ecdsa(_) => sp_core::ecdsa::Signature::decode(&mut &encoded_sig[..])
-				.expect("Should decode same signature type as public key; qed")
-				.into(),
-		};
-		signature.into()
+			MultiSigner::Ed25519(_) =>
+				sp_core::ed25519::Signature::decode(&mut &encoded_sig[..]).map(Into::into),
+			MultiSigner::Sr25519(_) =>
+				sp_core::sr25519::Signature::decode(&mut &encoded_sig[..]).map(Into::into),
+			MultiSigner::Ecdsa(_) =>
+				sp_core::ecdsa::Signature::decode(&mut &encoded_sig[..]).map(Into::into),
+		}
+		.map_err(|e| Error::Custom(format!("Signature doesn't match the key type: {e}")))?;
+		Ok(signature.into())
 	}
 }
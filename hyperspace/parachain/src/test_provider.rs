@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{signer::ExtrinsicSigner, Error, ParachainClient};
+use crate::{Error, ParachainClient};
 use codec::Decode;
 use finality_grandpa::BlockNumberOps;
 use futures::{Stream, StreamExt};
@@ -101,21 +101,15 @@ where
 	}
 
 	pub async fn submit_sudo_call(&self, call: T::ParaRuntimeCall) -> Result<(), Error> {
-		let signer = ExtrinsicSigner::<T, Self>::new(
-			self.key_store.clone(),
-			self.key_type_id.clone(),
-			self.public_key.clone(),
-		);
-
 		let ext = T::Tx::sudo_sudo(call);
 		// Submit extrinsic to parachain node
 
 		let other_params = T::custom_extrinsic_params(&self.para_client).await?;
 
 		let _progress = self
-			.para_client
-			.tx()
-			.sign_and_submit_then_watch(&ext, &signer, other_params)
+			.create_signed(&ext, other_params)
+			.await?
+			.submit_and_watch()
 			.await?
 			.wait_for_in_block()
 			.await?
@@ -0,0 +1,381 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing key management for the relayer, so that keys don't have to be stored in plaintext in
+//! the chain configs.
+//!
+//! A key is configured through a [`KeySource`]: an encrypted keystore file, a file holding the
+//! secret, or a remote signer listening on a unix socket. Local secrets are handed to the chain
+//! clients, which derive their own key pairs from them, while remote keys never leave the signer.
+
+use crate::error::Error;
+use chacha20poly1305::{
+	aead::{Aead, KeyInit},
+	ChaCha20Poly1305, Key as CipherKey, Nonce,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+	io::{BufRead, BufReader, Write},
+	os::unix::net::UnixStream,
+	path::{Path, PathBuf},
+	time::Duration,
+};
+use tokio::runtime::RuntimeFlavor;
+
+/// Current version of the encrypted keystore format
+const KEYSTORE_VERSION: u8 = 1;
+/// Number of PBKDF2 rounds used to derive the keystore encryption key from the password
+const KEYSTORE_KDF_ROUNDS: u32 = 600_000;
+/// Timeout of a single request to a remote signer
+const REMOTE_SIGNER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Signs payloads with the relayer's key, without exposing the private key to the caller.
+pub trait SigningBackend: Send + Sync {
+	/// Raw bytes of the public key, in the encoding expected by the chain
+	fn public_key(&self) -> &[u8];
+
+	/// Signs the payload and returns the raw signature bytes. The backend hashes the payload as
+	/// required by the key's signature scheme.
+	fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// Where a signing key is loaded from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KeySource {
+	/// Secret stored in an [`EncryptedKeystore`] file
+	Keystore {
+		/// Path of the keystore file
+		path: PathBuf,
+		/// Where the keystore password is read from
+		#[serde(flatten)]
+		password: PasswordSource,
+	},
+	/// Plaintext secret read from a file, eg. a secret mounted by the orchestrator
+	File {
+		/// Path of the file
+		path: PathBuf,
+	},
+	/// Key held by an external signer, see [`RemoteSigner`]
+	Remote {
+		/// Path of the unix socket the signer listens on
+		socket: PathBuf,
+		/// Identifier of the key on the signer
+		key_id: String,
+	},
+}
+
+/// Where the password of an [`EncryptedKeystore`] is read from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordSource {
+	/// Name of the environment variable holding the password
+	PasswordEnv(String),
+	/// Path of the file holding the password
+	PasswordFile(PathBuf),
+}
+
+impl PasswordSource {
+	/// Reads the password
+	pub fn read(&self) -> Result<String, Error> {
+		match self {
+			PasswordSource::PasswordEnv(var) => std::env::var(var).map_err(|e| {
+				Error::Custom(format!("Failed to read keystore password from ${var}: {e}"))
+			}),
+			PasswordSource::PasswordFile(path) => read_secret_file(path),
+		}
+	}
+}
+
+/// A key loaded from a [`KeySource`].
+pub enum Key {
+	/// Secret held in memory: a secret URI for substrate chains, a mnemonic for cosmos chains
+	Secret(String),
+	/// Key held by an external signer
+	Remote(RemoteSigner),
+}
+
+impl KeySource {
+	/// Loads the key, decrypting the keystore or connecting to the remote signer
+	pub fn load(&self) -> Result<Key, Error> {
+		match self {
+			KeySource::Keystore { path, password } =>
+				EncryptedKeystore::read(path)?.decrypt(&password.read()?).map(Key::Secret),
+			KeySource::File { path } => read_secret_file(path).map(Key::Secret),
+			KeySource::Remote { socket, key_id } =>
+				RemoteSigner::connect(socket.clone(), key_id.clone()).map(Key::Remote),
+		}
+	}
+}
+
+/// Loads the key of a chain client, configured either through a [`KeySource`] or as a plaintext
+/// secret in the config file.
+pub fn load_key(secret: Option<&str>, source: Option<&KeySource>) -> Result<Key, Error> {
+	match (secret, source) {
+		(None, Some(source)) => source.load(),
+		(Some(secret), None) => Ok(Key::Secret(secret.to_string())),
+		(Some(_), Some(_)) =>
+			Err(Error::Custom("Both a plaintext key and a key source are configured".to_string())),
+		(None, None) => Err(Error::Custom("No signing key is configured".to_string())),
+	}
+}
+
+fn read_secret_file(path: &Path) -> Result<String, Error> {
+	let content = std::fs::read_to_string(path)
+		.map_err(|e| Error::Custom(format!("Failed to read {}: {e}", path.display())))?;
+	Ok(content.trim().to_string())
+}
+
+/// On-disk keystore holding a secret encrypted with ChaCha20-Poly1305, under a key derived from
+/// a password with PBKDF2-HMAC-SHA256.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedKeystore {
+	/// Version of the keystore format
+	pub version: u8,
+	/// Number of PBKDF2 rounds
+	pub rounds: u32,
+	/// Hex encoded PBKDF2 salt
+	pub salt: String,
+	/// Hex encoded cipher nonce
+	pub nonce: String,
+	/// Hex encoded encrypted secret
+	pub ciphertext: String,
+}
+
+impl EncryptedKeystore {
+	/// Encrypts the secret with the password
+	pub fn encrypt(secret: &str, password: &str) -> Result<Self, Error> {
+		Self::encrypt_with_rounds(secret, password, KEYSTORE_KDF_ROUNDS)
+	}
+
+	fn encrypt_with_rounds(secret: &str, password: &str, rounds: u32) -> Result<Self, Error> {
+		let mut rng = rand::thread_rng();
+		let salt: [u8; 16] = rng.gen();
+		let nonce: [u8; 12] = rng.gen();
+		let cipher = keystore_cipher(password, &salt, rounds);
+		let ciphertext = cipher
+			.encrypt(Nonce::from_slice(&nonce), secret.as_bytes())
+			.map_err(|_| Error::Custom("Failed to encrypt the keystore".to_string()))?;
+		Ok(Self {
+			version: KEYSTORE_VERSION,
+			rounds,
+			salt: hex::encode(salt),
+			nonce: hex::encode(nonce),
+			ciphertext: hex::encode(ciphertext),
+		})
+	}
+
+	/// Decrypts the secret, fails if the password is wrong or the keystore was tampered with
+	pub fn decrypt(&self, password: &str) -> Result<String, Error> {
+		if self.version != KEYSTORE_VERSION {
+			return Err(Error::Custom(format!("Unsupported keystore version {}", self.version)))
+		}
+		let nonce = hex::decode(&self.nonce)?;
+		if nonce.len() != 12 {
+			return Err(Error::Custom("Invalid keystore nonce".to_string()))
+		}
+		let cipher = keystore_cipher(password, &hex::decode(&self.salt)?, self.rounds);
+		let secret = cipher
+			.decrypt(Nonce::from_slice(&nonce), hex::decode(&self.ciphertext)?.as_slice())
+			.map_err(|_| {
+				Error::Custom("Failed to decrypt the keystore, wrong password?".to_string())
+			})?;
+		Ok(String::from_utf8(secret)?)
+	}
+
+	/// Reads a keystore file
+	pub fn read(path: &Path) -> Result<Self, Error> {
+		let content = std::fs::read_to_string(path)
+			.map_err(|e| Error::Custom(format!("Failed to read {}: {e}", path.display())))?;
+		serde_json::from_str(&content)
+			.map_err(|e| Error::Custom(format!("Invalid keystore {}: {e}", path.display())))
+	}
+
+	/// Writes the keystore to a file only readable by its owner
+	pub fn write(&self, path: &Path) -> Result<(), Error> {
+		use std::os::unix::fs::OpenOptionsExt;
+		let content = serde_json::to_string_pretty(self)
+			.map_err(|e| Error::Custom(format!("Failed to encode keystore: {e}")))?;
+		std::fs::OpenOptions::new()
+			.write(true)
+			.create_new(true)
+			.mode(0o600)
+			.open(path)
+			.and_then(|mut file| file.write_all(content.as_bytes()))
+			.map_err(|e| Error::Custom(format!("Failed to write {}: {e}", path.display())))
+	}
+}
+
+fn keystore_cipher(password: &str, salt: &[u8], rounds: u32) -> ChaCha20Poly1305 {
+	let mut key = [0u8; 32];
+	pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, rounds, &mut key);
+	ChaCha20Poly1305::new(CipherKey::from_slice(&key))
+}
+
+/// Request sent to a remote signer, encoded as a single line of JSON.
+#[derive(Debug, Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum RemoteRequest<'a> {
+	/// Returns the hex encoded public key of the key
+	PublicKey { key_id: &'a str },
+	/// Returns the hex encoded signature of the hex encoded payload
+	Sign { key_id: &'a str, payload: String },
+}
+
+/// Response of a remote signer, encoded as a single line of JSON.
+#[derive(Debug, Deserialize)]
+struct RemoteResponse {
+	#[serde(default)]
+	result: Option<String>,
+	#[serde(default)]
+	error: Option<String>,
+}
+
+/// Signs through an external signer listening on a unix socket.
+///
+/// Each request is a line of JSON, `{"method":"public_key","key_id":"relayer"}` or
+/// `{"method":"sign","key_id":"relayer","payload":"<hex>"}`, answered by a line of JSON holding
+/// either the hex encoded `result` or an `error` message. Signatures are computed the way the
+/// chain expects them for the key's scheme, eg. over the SHA-256 digest of the payload for
+/// cosmos secp256k1 keys.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+	socket: PathBuf,
+	key_id: String,
+	public_key: Vec<u8>,
+}
+
+impl RemoteSigner {
+	/// Connects to the signer and fetches the public key of `key_id`
+	pub fn connect(socket: PathBuf, key_id: String) -> Result<Self, Error> {
+		let mut signer = Self { socket, key_id, public_key: vec![] };
+		signer.public_key = signer.request(&RemoteRequest::PublicKey { key_id: &signer.key_id })?;
+		Ok(signer)
+	}
+
+	/// Sends the request over a blocking socket. Signing is synchronous but the clients sign from
+	/// async tasks, so the worker thread is handed over to the runtime while waiting on the
+	/// signer, instead of stalling the other tasks scheduled on it.
+	fn request(&self, request: &RemoteRequest) -> Result<Vec<u8>, Error> {
+		match tokio::runtime::Handle::try_current() {
+			Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread =>
+				tokio::task::block_in_place(|| self.request_blocking(request)),
+			_ => self.request_blocking(request),
+		}
+	}
+
+	fn request_blocking(&self, request: &RemoteRequest) -> Result<Vec<u8>, Error> {
+		let map_err = |e: std::io::Error| {
+			Error::Custom(format!("Remote signer {} failed: {e}", self.socket.display()))
+		};
+		let mut stream = UnixStream::connect(&self.socket).map_err(map_err)?;
+		stream.set_read_timeout(Some(REMOTE_SIGNER_TIMEOUT)).map_err(map_err)?;
+		stream.set_write_timeout(Some(REMOTE_SIGNER_TIMEOUT)).map_err(map_err)?;
+
+		let mut line = serde_json::to_vec(request)
+			.map_err(|e| Error::Custom(format!("Failed to encode signer request: {e}")))?;
+		line.push(b'\n');
+		stream.write_all(&line).map_err(map_err)?;
+
+		let mut response = String::new();
+		BufReader::new(stream).read_line(&mut response).map_err(map_err)?;
+		let response: RemoteResponse = serde_json::from_str(&response)
+			.map_err(|e| Error::Custom(format!("Invalid remote signer response: {e}")))?;
+		match response {
+			RemoteResponse { error: Some(error), .. } =>
+				Err(Error::Custom(format!("Remote signer error: {error}"))),
+			RemoteResponse { result: Some(result), .. } => Ok(hex::decode(result)?),
+			_ => Err(Error::Custom("Empty remote signer response".to_string())),
+		}
+	}
+}
+
+impl SigningBackend for RemoteSigner {
+	fn public_key(&self) -> &[u8] {
+		&self.public_key
+	}
+
+	fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+		self.request(&RemoteRequest::Sign { key_id: &self.key_id, payload: hex::encode(payload) })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::os::unix::net::UnixListener;
+
+	#[test]
+	fn keystore_roundtrip() {
+		let keystore =
+			EncryptedKeystore::encrypt_with_rounds("//Alice", "password", 1_000).unwrap();
+		assert_eq!(keystore.decrypt("password").unwrap(), "//Alice");
+		assert!(keystore.decrypt("wrong password").is_err());
+
+		let mut tampered = keystore.clone();
+		tampered.ciphertext = hex::encode(vec![0u8; tampered.ciphertext.len() / 2]);
+		assert!(tampered.decrypt("password").is_err());
+	}
+
+	#[test]
+	fn key_source_from_toml() {
+		let source: KeySource = toml::from_str(
+			r#"
+			type = "keystore"
+			path = "/keys/relayer.json"
+			password_env = "HYPERSPACE_PASSWORD"
+			"#,
+		)
+		.unwrap();
+		assert_eq!(
+			source,
+			KeySource::Keystore {
+				path: "/keys/relayer.json".into(),
+				password: PasswordSource::PasswordEnv("HYPERSPACE_PASSWORD".to_string()),
+			}
+		);
+	}
+
+	#[test]
+	fn remote_signer_requests() {
+		let socket =
+			std::env::temp_dir().join(format!("hyperspace-signer-{}", rand::random::<u64>()));
+		let listener = UnixListener::bind(&socket).unwrap();
+		let server = std::thread::spawn(move || {
+			for response in [r#"{"result":"0102"}"#, r#"{"error":"unknown key"}"#] {
+				let (stream, _) = listener.accept().unwrap();
+				let mut reader = BufReader::new(stream);
+				let mut request = String::new();
+				reader.read_line(&mut request).unwrap();
+				writeln!(reader.get_mut(), "{response}").unwrap();
+			}
+		});
+
+		let signer = RemoteSigner::connect(socket.clone(), "relayer".to_string()).unwrap();
+		assert_eq!(signer.public_key(), &[1, 2]);
+		assert!(signer.sign(b"payload").is_err());
+		server.join().unwrap();
+		std::fs::remove_file(socket).unwrap();
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn remote_signer_down_is_an_error() {
+		let socket =
+			std::env::temp_dir().join(format!("hyperspace-signer-{}", rand::random::<u64>()));
+		let signer = RemoteSigner { socket, key_id: "relayer".to_string(), public_key: vec![1, 2] };
+		assert!(signer.sign(b"payload").is_err());
+	}
+}
@@ -14,6 +14,7 @@
 
 #![allow(clippy::all)]
 
+use codec::{Decode, Encode};
 use futures::Stream;
 use ibc_proto::{
 	google::protobuf::Any,
@@ -39,7 +40,7 @@ use std::{
 };
 use tokio::{sync::Mutex as AsyncMutex, task::JoinSet, time::sleep};
 
-use crate::error::Error;
+use crate::{error::Error, store::PathStore};
 #[cfg(any(feature = "testing", test))]
 use ibc::applications::transfer::msgs::transfer::MsgTransfer;
 use ibc::{
@@ -70,7 +71,9 @@ use ibc_rpc::PacketInfo;
 use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState};
 
 pub mod error;
+pub mod keys;
 pub mod mock;
+pub mod store;
 pub mod utils;
 
 pub enum UpdateMessage {
@@ -133,6 +136,9 @@ pub struct CommonClientState {
 	pub misbehaviour_client_msg_queue: Arc<AsyncMutex<Vec<AnyClientMessage>>>,
 	pub max_packets_to_process: usize,
 	pub skip_tokens_list: Vec<String>,
+	/// Persistent store of the relay path this client is used on. Set by the relayer, when
+	/// persistence is enabled.
+	pub store: Option<PathStore>,
 }
 
 impl Default for CommonClientState {
@@ -146,6 +152,7 @@ impl Default for CommonClientState {
 			misbehaviour_client_msg_queue: Arc::new(Default::default()),
 			max_packets_to_process: 100,
 			skip_tokens_list: Default::default(),
+			store: None,
 		}
 	}
 }
@@ -157,7 +164,57 @@ impl CommonClientState {
 			"on_undelivered_sequences: {:?}, type: {kind:?}",
 			has
 		);
-		self.maybe_has_undelivered_packets.lock().unwrap().insert(kind, has);
+		let undelivered = {
+			let mut undelivered = self.maybe_has_undelivered_packets.lock().unwrap();
+			undelivered.insert(kind, has);
+			undelivered.clone()
+		};
+		if let Some(store) = &self.store {
+			if let Err(e) = store.set_undelivered_packets(&undelivered) {
+				log::error!(target: "hyperspace", "Failed to persist undelivered sequences: {e}");
+			}
+		}
+	}
+
+	/// Restores the state persisted in the [`PathStore`], if any, after a restart.
+	pub async fn restore_from_store(&self) -> anyhow::Result<()> {
+		let Some(store) = &self.store else { return Ok(()) };
+		*self.maybe_has_undelivered_packets.lock().unwrap() = store.undelivered_packets()?;
+		let messages = store
+			.misbehaviour_messages()?
+			.into_iter()
+			.map(AnyClientMessage::try_from)
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(|e| anyhow::anyhow!("Failed to decode misbehaviour message: {e:?}"))?;
+		*self.misbehaviour_client_msg_queue.lock().await = messages;
+		Ok(())
+	}
+
+	/// Queues a misbehaviour message to be submitted, persisting it if the store is enabled.
+	pub async fn queue_misbehaviour_message(
+		&self,
+		message: AnyClientMessage,
+	) -> anyhow::Result<()> {
+		let mut queue = self.misbehaviour_client_msg_queue.lock().await;
+		queue.push(message);
+		self.persist_misbehaviour_messages(&queue)
+	}
+
+	/// Takes all of the queued misbehaviour messages.
+	pub async fn take_misbehaviour_messages(&self) -> anyhow::Result<Vec<AnyClientMessage>> {
+		let mut queue = self.misbehaviour_client_msg_queue.lock().await;
+		let messages = std::mem::take(&mut *queue);
+		self.persist_misbehaviour_messages(&queue)?;
+		Ok(messages)
+	}
+
+	fn persist_misbehaviour_messages(&self, queue: &[AnyClientMessage]) -> anyhow::Result<()> {
+		match &self.store {
+			Some(store) => store.set_misbehaviour_messages(
+				&queue.iter().cloned().map(Any::from).collect::<Vec<_>>(),
+			),
+			None => Ok(()),
+		}
 	}
 
 	pub fn has_undelivered_sequences(&self, kind: UndeliveredType) -> bool {
@@ -189,7 +246,7 @@ pub fn apply_prefix(mut commitment_prefix: Vec<u8>, path: impl Into<Vec<u8>>) ->
 /// - acknowledgement packet (`Acks`),
 /// - receive packet (`Recvs`)
 /// - timeout packet (`Timeouts`)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encode, Decode)]
 pub enum UndeliveredType {
 	Acks,
 	Recvs,
@@ -201,7 +258,7 @@ pub enum UndeliveredType {
 #[async_trait::async_trait]
 pub trait IbcProvider {
 	/// Finality event type, passed on to [`Chain::query_latest_ibc_events`]
-	type FinalityEvent: Debug + Send + 'static;
+	type FinalityEvent: Debug + Clone + Send + 'static;
 	/// A representation of the transaction id for the chain
 	type TransactionId: Debug;
 	/// Asset Id
@@ -543,6 +600,16 @@ pub trait Chain:
 	/// Should return the transaction id
 	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Self::Error>;
 
+	/// Submits the chunks of a batch that is too heavy for a single transaction, in order. Chains
+	/// that spread transactions over several accounts should send all the chunks from the same
+	/// one.
+	async fn submit_chunks(&self, chunks: Vec<Vec<Any>>) -> Result<(), Self::Error> {
+		for messages in chunks {
+			self.submit(messages).await?;
+		}
+		Ok(())
+	}
+
 	/// Returns an [`AnyClientMessage`] for an [`UpdateClient`] event
 	async fn query_client_message(
 		&self,
@@ -551,6 +618,12 @@ pub trait Chain:
 
 	async fn get_proof_height(&self, block_height: Height) -> Height;
 
+	/// Returns the balance of the relayer's signer in the token that pays for transactions, or
+	/// `None` if the chain doesn't support querying it.
+	async fn query_signer_balance(&self) -> Result<Option<u128>, Self::Error> {
+		Ok(None)
+	}
+
 	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error>;
 
 	fn common_state(&self) -> &CommonClientState;
@@ -625,7 +698,29 @@ pub async fn query_undelivered_sequences(
 			.query_next_sequence_recv(sink_height, &counterparty_port_id, &counterparty_channel_id)
 			.await?
 			.next_sequence_receive;
-		seqs.into_iter().filter(|seq| *seq > next_seq_recv).collect()
+		let mut undelivered =
+			seqs.iter().copied().filter(|seq| *seq > next_seq_recv).collect::<Vec<_>>();
+		if channel_end.ordering == Order::OrderedAllowTimeout {
+			// The sink moved past these packets with a timeout receipt, their timeout still has to
+			// be relayed back to the source.
+			for seq in seqs.into_iter().filter(|seq| *seq < next_seq_recv) {
+				let has_receipt = sink
+					.query_packet_receipt(
+						sink_height,
+						&counterparty_port_id,
+						&counterparty_channel_id,
+						seq,
+					)
+					.await
+					.map(|response| response.received)
+					.unwrap_or_default();
+				if has_receipt {
+					undelivered.push(seq);
+				}
+			}
+			undelivered.sort_unstable();
+		}
+		undelivered
 	};
 
 	Ok(undelivered_sequences)
@@ -893,7 +988,7 @@ pub fn filter_events_by_ids(
 	use ibc::core::{
 		ics02_client::events::Attributes as ClientAttributes,
 		ics03_connection::events::Attributes as ConnectionAttributes,
-		ics04_channel::events::Attributes as ChannelAttributes,
+		ics04_channel::events::{Attributes as ChannelAttributes, UpgradeAttributes},
 	};
 	let channel_ids = channel_and_port_ids
 		.iter()
@@ -927,6 +1022,14 @@ pub fn filter_events_by_ids(
 				.map(|id| channel_ids.contains(&id))
 				.unwrap_or(false)
 	};
+	let filter_upgrade_attributes = |attributes: &UpgradeAttributes| {
+		channel_ids.contains(&&attributes.channel_id) ||
+			attributes
+				.counterparty_channel_id
+				.as_ref()
+				.map(|id| channel_ids.contains(&id))
+				.unwrap_or(false)
+	};
 
 	let v = match ev {
 		IbcEvent::SendPacket(e) => filter_packet(&e.packet),
@@ -955,6 +1058,14 @@ pub fn filter_events_by_ids(
 			filter_channel_attributes(&ChannelAttributes::from(e.clone())),
 		IbcEvent::CloseConfirmChannel(e) =>
 			filter_channel_attributes(&ChannelAttributes::from(e.clone())),
+		IbcEvent::UpgradeInitChannel(e) => filter_upgrade_attributes(&e.0),
+		IbcEvent::UpgradeTryChannel(e) => filter_upgrade_attributes(&e.0),
+		IbcEvent::UpgradeAckChannel(e) => filter_upgrade_attributes(&e.0),
+		IbcEvent::UpgradeConfirmChannel(e) => filter_upgrade_attributes(&e.0),
+		IbcEvent::UpgradeOpenChannel(e) => filter_upgrade_attributes(&e.0),
+		IbcEvent::UpgradeTimeoutChannel(e) => filter_upgrade_attributes(&e.0),
+		IbcEvent::UpgradeCancelChannel(e) => filter_upgrade_attributes(&e.0),
+		IbcEvent::UpgradeErrorChannel(e) => filter_upgrade_attributes(&e.0),
 		IbcEvent::PushWasmCode(_) => true,
 		IbcEvent::NewBlock(_) |
 		IbcEvent::AppModule(_) |
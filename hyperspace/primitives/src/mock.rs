@@ -12,6 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "testing")]
+pub mod chain;
+#[cfg(feature = "testing")]
+pub mod module;
+
 use ibc::core::ics02_client::context::ClientTypes;
 use pallet_ibc::light_clients::{AnyClient, AnyClientMessage, AnyClientState, AnyConsensusState};
 
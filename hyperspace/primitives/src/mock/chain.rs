@@ -0,0 +1,1248 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-process chain backed by the ibc [`MockContext`], so that the relayer and the testsuite can
+//! run without any live nodes.
+//!
+//! Blocks are produced every [`MockChainConfig::block_interval`] and each of them advances the
+//! chain time by [`MockChainConfig::block_time`], which lets timeouts and connection delays elapse
+//! much faster than on a real chain. A block is final as soon as it is sealed, proofs are not
+//! verified by the mock light client, so dummy proofs are returned by all of the queries. The
+//! host consensus state "proof" is the encoded consensus state itself, which the chain compares
+//! with its own block when it is handed back in the connection handshake.
+
+use super::module::{Bank, MockPingModule, MockTransferModule};
+use crate::{
+	error::Error, Chain, CommonClientState, IbcProvider, KeyProvider, LightClientSync,
+	MisbehaviourHandler, TestProvider, UpdateType,
+};
+use futures::{
+	channel::mpsc::{unbounded, UnboundedSender},
+	Stream,
+};
+use ibc::{
+	applications::transfer::{
+		msgs::transfer::MsgTransfer, packet::PacketData, PrefixedCoin, PrefixedDenom, MODULE_ID_STR,
+	},
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState as ConsensusStateT,
+			client_state::{ClientState as ClientStateT, ClientType},
+			context::{ClientKeeper, ClientReader},
+			events::UpdateClient,
+			msgs::update_client::MsgUpdateAnyClient,
+		},
+		ics03_connection::{
+			connection::IdentifiedConnectionEnd,
+			context::{ConnectionKeeper, ConnectionReader},
+		},
+		ics04_channel::{
+			channel::IdentifiedChannelEnd,
+			context::{ChannelKeeper, ChannelReader},
+			handler::send_packet::send_packet,
+			packet::{Packet, Sequence},
+		},
+		ics23_commitment::commitment::CommitmentPrefix,
+		ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+		ics26_routing::{
+			context::{ModuleId, RouterBuilder},
+			handler::deliver,
+		},
+	},
+	events::IbcEvent,
+	handler::HandlerOutput,
+	mock::{
+		client_state::{
+			AnyConsensusState as MockAnyConsensusState, MockClientState, MockConsensusState,
+		},
+		context::{MockClientTypes, MockContext, MockIbcStore, MockRouterBuilder},
+		header::{MockClientMessage, MockHeader},
+		host::{HostBlock, MockHostBlock, MockHostType},
+		misbehaviour::MockMisbehaviour,
+	},
+	protobuf::Protobuf,
+	signer::Signer,
+	test_utils::get_dummy_proof,
+	timestamp::Timestamp,
+	tx_msg::Msg,
+	Height,
+};
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::{
+		channel::v1::{
+			QueryChannelResponse, QueryChannelsResponse, QueryNextSequenceReceiveResponse,
+			QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
+			QueryPacketReceiptResponse,
+		},
+		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
+		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
+	},
+};
+use ibc_rpc::PacketInfo;
+use pallet_ibc::{
+	light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState},
+	Timeout,
+};
+use std::{
+	collections::{BTreeMap, HashSet},
+	pin::Pin,
+	str::FromStr,
+	sync::{Arc, Mutex, Weak},
+	time::Duration,
+};
+
+/// Maximum number of blocks the counterparty light client is updated with in one go.
+const NUMBER_OF_BLOCKS_TO_PROCESS_PER_ITER: u64 = 100;
+
+/// Port of the [`MockPingModule`], used for ordered channels.
+pub const PING_PORT_ID: &str = "ping";
+
+fn ping_port_id() -> PortId {
+	PortId::from_str(PING_PORT_ID).expect("ping port id is valid; qed")
+}
+
+/// Configuration of a [`MockChain`].
+#[derive(Debug, Clone)]
+pub struct MockChainConfig {
+	/// Name of the chain, used in logs
+	pub name: String,
+	/// Chain id, its version is used as the revision number of the block heights
+	pub chain_id: ChainId,
+	/// Time that passes on chain between two blocks
+	pub block_time: Duration,
+	/// Real time between two blocks
+	pub block_interval: Duration,
+	/// Number of blocks kept in the history, along with their ibc state and events
+	pub max_history_size: usize,
+	/// Account used to sign messages and to hold the test tokens
+	pub account: String,
+	/// Native denomination of the chain
+	pub denom: String,
+	/// Balance of the native denomination the account starts with
+	pub initial_balance: u128,
+}
+
+impl Default for MockChainConfig {
+	fn default() -> Self {
+		Self {
+			name: "mock".to_string(),
+			chain_id: ChainId::new("mock".to_string(), 1),
+			block_time: Duration::from_secs(6),
+			block_interval: Duration::from_millis(100),
+			max_history_size: 10_000,
+			account: "relayer".to_string(),
+			denom: "stake".to_string(),
+			initial_balance: 1_000_000_000_000_000,
+		}
+	}
+}
+
+/// Chain state shared between the [`MockChain`] handles and the block production task.
+struct MockChainState {
+	ctx: MockContext<MockClientTypes>,
+	/// Ibc store at the end of every sealed block
+	snapshots: BTreeMap<u64, MockIbcStore<MockClientTypes>>,
+	/// Events emitted in every block
+	events: BTreeMap<u64, Vec<IbcEvent>>,
+	/// Events emitted by every transaction, indexed by the transaction id
+	transactions: Vec<Vec<IbcEvent>>,
+	finality_subscribers: Vec<UnboundedSender<Height>>,
+	event_subscribers: Vec<UnboundedSender<IbcEvent>>,
+	block_subscribers: Vec<UnboundedSender<u64>>,
+}
+
+impl MockChainState {
+	/// Height of the block that is currently being built.
+	fn pending_height(&self) -> Height {
+		self.ctx.latest_height()
+	}
+
+	/// Height of the last sealed block.
+	fn finalized_height(&self) -> Height {
+		self.pending_height().decrement().expect("chain starts at height 2; qed")
+	}
+
+	fn header(&self, height: u64) -> Result<MockHeader, Error> {
+		let height = Height::new(self.pending_height().revision_number, height);
+		match self.ctx.host_block(height) {
+			Some(MockHostBlock::Mock(header)) => Ok(*header),
+			None => Err(Error::Custom(format!("Block {height} not found"))),
+		}
+	}
+
+	/// `MsgUpdateClient` of the light client `client_id` on the counterparty with the header at
+	/// `height`.
+	fn msg_update_client(
+		&self,
+		client_id: ClientId,
+		height: u64,
+		signer: Signer,
+	) -> Result<(Any, MockHeader), Error> {
+		let header = self.header(height)?;
+		let msg = MsgUpdateAnyClient::<MockClientTypes> {
+			client_id,
+			client_message: header.into(),
+			signer,
+		};
+		let value = msg.encode_vec().map_err(|e| {
+			Error::Custom(format!("Failed to encode MsgUpdateClient {msg:?}: {e:?}"))
+		})?;
+		Ok((Any { type_url: msg.type_url(), value }, header))
+	}
+
+	/// Runs `f` against the ibc store at the end of block `at`, or against the pending store if
+	/// that block wasn't sealed yet.
+	fn query<T>(
+		&self,
+		at: Height,
+		f: impl FnOnce(&MockIbcStore<MockClientTypes>) -> Result<T, Error>,
+	) -> Result<T, Error> {
+		if at.revision_height >= self.pending_height().revision_height {
+			return f(&self.ctx.ibc_store.lock().unwrap())
+		}
+		let store = self
+			.snapshots
+			.get(&at.revision_height)
+			.ok_or_else(|| Error::Custom(format!("Ibc state at {at} is not available")))?;
+		f(store)
+	}
+
+	fn record_transaction(&mut self, events: Vec<IbcEvent>) -> usize {
+		let height = self.pending_height().revision_height;
+		self.events.entry(height).or_default().extend(events.iter().cloned());
+		self.transactions.push(events);
+		self.transactions.len() - 1
+	}
+
+	/// Delivers the messages atomically, all of the state changes are reverted if any of them
+	/// fails.
+	fn execute(&mut self, messages: Vec<Any>, bank: &Bank) -> Result<usize, Error> {
+		let store = self.ctx.ibc_store.lock().unwrap().clone();
+		let balances = bank.snapshot();
+		let mut events = vec![];
+		for message in messages {
+			let type_url = message.type_url.clone();
+			match deliver(&mut self.ctx, message) {
+				Ok(receipt) => events.extend(receipt.events),
+				Err(e) => {
+					*self.ctx.ibc_store.lock().unwrap() = store;
+					bank.restore(balances);
+					return Err(Error::Custom(format!("Failed to deliver {type_url}: {e}")))
+				},
+			}
+		}
+		Ok(self.record_transaction(events))
+	}
+
+	fn send_packet(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+		data: Vec<u8>,
+		timeout_height: Height,
+		timeout_timestamp: Timestamp,
+	) -> Result<usize, Error> {
+		let port_channel = (port_id.clone(), channel_id);
+		let channel_end = self.ctx.channel_end(&port_channel)?;
+		let counterparty = channel_end.counterparty();
+		let packet = Packet {
+			sequence: self.ctx.get_next_sequence_send(&port_channel)?,
+			source_port: port_id,
+			source_channel: channel_id,
+			destination_port: counterparty.port_id.clone(),
+			destination_channel: *counterparty.channel_id().ok_or_else(|| {
+				Error::Custom(format!("Channel {channel_id} is missing its counterparty"))
+			})?,
+			data,
+			timeout_height,
+			timeout_timestamp,
+		};
+		let HandlerOutput { result, events, .. } = send_packet(&self.ctx, packet)?;
+		self.ctx.store_packet_result(result)?;
+		Ok(self.record_transaction(events))
+	}
+
+	/// Seals the pending block: makes its events and the block itself final, then starts a new
+	/// block.
+	fn seal_block(&mut self) {
+		let height = self.pending_height();
+		let store = self.ctx.ibc_store.lock().unwrap().clone();
+		self.snapshots.insert(height.revision_height, store);
+
+		let events = self.events.get(&height.revision_height).cloned().unwrap_or_default();
+		self.event_subscribers
+			.retain(|tx| events.iter().all(|event| tx.unbounded_send(event.clone()).is_ok()));
+		self.finality_subscribers.retain(|tx| tx.unbounded_send(height).is_ok());
+		self.block_subscribers
+			.retain(|tx| tx.unbounded_send(height.revision_height).is_ok());
+
+		self.ctx.advance_host_chain_height();
+
+		// drop the state of the blocks that were pruned from the history
+		let oldest = self.ctx.history[0].height().revision_height;
+		self.snapshots = self.snapshots.split_off(&oldest);
+		self.events = self.events.split_off(&oldest);
+	}
+}
+
+/// Produces blocks until all of the chain handles are dropped.
+async fn produce_blocks(state: Weak<Mutex<MockChainState>>, block_interval: Duration) {
+	let mut interval = tokio::time::interval(block_interval);
+	// the first tick completes immediately
+	interval.tick().await;
+	loop {
+		interval.tick().await;
+		let Some(state) = state.upgrade() else { break };
+		state.lock().unwrap().seal_block();
+	}
+}
+
+/// In-process chain, see the [module docs](self).
+#[derive(Clone)]
+pub struct MockChain {
+	name: String,
+	account: Signer,
+	denom: String,
+	state: Arc<Mutex<MockChainState>>,
+	bank: Bank,
+	/// Light client id on the counterparty chain
+	client_id: Arc<Mutex<Option<ClientId>>>,
+	/// Connection Id
+	connection_id: Arc<Mutex<Option<ConnectionId>>>,
+	/// Channels cleared for packet relay
+	channel_whitelist: Arc<Mutex<HashSet<(ChannelId, PortId)>>>,
+	common_state: CommonClientState,
+}
+
+impl MockChain {
+	/// Creates the chain and starts producing blocks, must be called from within a tokio runtime.
+	pub async fn new(config: MockChainConfig) -> Result<Self, Error> {
+		let account = Signer::from_str(&config.account)
+			.map_err(|e| Error::Custom(format!("Invalid account {}: {e:?}", config.account)))?;
+		let bank = Bank::default();
+		bank.mint(account.as_ref(), &config.denom, config.initial_balance);
+
+		let transfer_module_id = ModuleId::from_str(MODULE_ID_STR)
+			.map_err(|e| Error::Custom(format!("Invalid module id: {e:?}")))?;
+		let ping_module_id = ModuleId::from_str(PING_PORT_ID)
+			.map_err(|e| Error::Custom(format!("Invalid module id: {e:?}")))?;
+		let router = MockRouterBuilder::default()
+			.add_route(transfer_module_id.clone(), MockTransferModule::new(bank.clone()))?
+			.add_route(ping_module_id.clone(), MockPingModule)?
+			.build();
+
+		let revision_number = config.chain_id.version();
+		let mut ctx = MockContext::<MockClientTypes>::new(
+			config.chain_id,
+			MockHostType::Mock,
+			config.max_history_size,
+			Height::new(revision_number, 2),
+		)
+		.with_router(router);
+		ctx.block_time = config.block_time;
+		ctx.scope_port_to_module(PortId::transfer(), transfer_module_id);
+		ctx.scope_port_to_module(ping_port_id(), ping_module_id);
+
+		let state = Arc::new(Mutex::new(MockChainState {
+			ctx,
+			snapshots: Default::default(),
+			events: Default::default(),
+			transactions: vec![],
+			finality_subscribers: vec![],
+			event_subscribers: vec![],
+			block_subscribers: vec![],
+		}));
+		tokio::spawn(produce_blocks(Arc::downgrade(&state), config.block_interval));
+
+		Ok(Self {
+			name: config.name,
+			account,
+			denom: config.denom,
+			state,
+			bank,
+			client_id: Default::default(),
+			connection_id: Default::default(),
+			channel_whitelist: Default::default(),
+			common_state: CommonClientState::default(),
+		})
+	}
+
+	/// Native denomination of the chain.
+	pub fn denom(&self) -> &str {
+		&self.denom
+	}
+
+	/// Balance of the given denomination held by the account.
+	pub fn balance(&self, denom: &str) -> u128 {
+		self.bank.balance(self.account.as_ref(), denom)
+	}
+
+	/// Returns a copy of this chain that shares its state, but has its own client, connection,
+	/// channel and undelivered packets state, like the forks of a chain relaying several paths.
+	pub fn fork(&self) -> Self {
+		let mut chain = self.clone();
+		chain.client_id = Default::default();
+		chain.connection_id = Default::default();
+		chain.channel_whitelist = Default::default();
+		chain.common_state.maybe_has_undelivered_packets = Default::default();
+		chain.common_state.misbehaviour_client_msg_queue = Default::default();
+		chain.common_state.store = None;
+		chain
+	}
+
+	fn query<T>(
+		&self,
+		at: Height,
+		f: impl FnOnce(&MockIbcStore<MockClientTypes>) -> Result<T, Error>,
+	) -> Result<T, Error> {
+		self.state.lock().unwrap().query(at, f)
+	}
+
+	/// Latest height of the light client of this chain on the counterparty.
+	async fn counterparty_client_height<C: Chain>(
+		&self,
+		counterparty: &C,
+	) -> Result<Height, anyhow::Error> {
+		let latest_cp_height = counterparty.latest_height_and_timestamp().await?.0;
+		let client_state = counterparty
+			.query_client_state(latest_cp_height, self.client_id())
+			.await?
+			.client_state
+			.ok_or_else(|| Error::Custom("counterparty returned empty client state".to_string()))?;
+		let client_state = AnyClientState::try_from(client_state)
+			.map_err(|e| Error::Custom(format!("Failed to decode client state: {e:?}")))?;
+		Ok(client_state.latest_height())
+	}
+
+	fn transaction_events(&self, tx_id: usize) -> Result<Vec<IbcEvent>, Error> {
+		self.state
+			.lock()
+			.unwrap()
+			.transactions
+			.get(tx_id)
+			.cloned()
+			.ok_or_else(|| Error::Custom(format!("Transaction {tx_id} not found")))
+	}
+
+	fn packet_infos(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+		f: impl Fn(&IbcEvent) -> Option<(&Packet, Option<&Vec<u8>>)>,
+	) -> Result<Vec<PacketInfo>, Error> {
+		let state = self.state.lock().unwrap();
+		let channel_end = state.ctx.channel_end(&(port_id, channel_id))?;
+		let mut packets = BTreeMap::new();
+		for (height, events) in &state.events {
+			for (packet, ack) in events.iter().filter_map(&f) {
+				if !seqs.contains(&packet.sequence.0) {
+					continue
+				}
+				let info = PacketInfo {
+					height: Some(*height),
+					sequence: packet.sequence.0,
+					source_port: packet.source_port.to_string(),
+					source_channel: packet.source_channel.to_string(),
+					destination_port: packet.destination_port.to_string(),
+					destination_channel: packet.destination_channel.to_string(),
+					channel_order: channel_end.ordering.to_string(),
+					data: packet.data.clone(),
+					timeout_height: packet.timeout_height.into(),
+					timeout_timestamp: packet.timeout_timestamp.nanoseconds(),
+					ack: ack.cloned(),
+				};
+				// later events for the same sequence take precedence
+				packets.insert(packet.sequence.0, info);
+			}
+		}
+		Ok(packets.into_values().collect())
+	}
+}
+
+#[async_trait::async_trait]
+impl IbcProvider for MockChain {
+	type FinalityEvent = Height;
+	type TransactionId = usize;
+	type AssetId = String;
+	type Error = Error;
+
+	async fn query_latest_ibc_events<T>(
+		&mut self,
+		finality_event: Self::FinalityEvent,
+		counterparty: &T,
+	) -> Result<Vec<(Any, Height, Vec<IbcEvent>, UpdateType)>, anyhow::Error>
+	where
+		T: Chain,
+	{
+		let client_id = self.client_id();
+		let from = self.counterparty_client_height(counterparty).await?.revision_height + 1;
+		let to = finality_event
+			.revision_height
+			.min(from + NUMBER_OF_BLOCKS_TO_PROCESS_PER_ITER - 1);
+
+		let state = self.state.lock().unwrap();
+		let mut updates = vec![];
+		for height in from..=to {
+			let (msg, header) =
+				state.msg_update_client(client_id.clone(), height, counterparty.account_id())?;
+			let events = state.events.get(&height).cloned().unwrap_or_default();
+			// the client must make progress when there are more blocks to process
+			let update_type = if height == to && to < finality_event.revision_height {
+				UpdateType::Mandatory
+			} else {
+				UpdateType::Optional
+			};
+			updates.push((msg, header.height, events, update_type));
+		}
+		Ok(updates)
+	}
+
+	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
+		let (tx, rx) = unbounded();
+		self.state.lock().unwrap().event_subscribers.push(tx);
+		Box::pin(rx)
+	}
+
+	async fn query_client_consensus(
+		&self,
+		at: Height,
+		client_id: ClientId,
+		consensus_height: Height,
+	) -> Result<QueryConsensusStateResponse, Self::Error> {
+		let consensus_state = self.query(at, |store| {
+			store
+				.clients
+				.get(&client_id)
+				.and_then(|record| record.consensus_states.get(&consensus_height))
+				.cloned()
+				.ok_or_else(|| {
+					Error::Custom(format!(
+						"Consensus state of {client_id} at {consensus_height} not found"
+					))
+				})
+		})?;
+		Ok(QueryConsensusStateResponse {
+			consensus_state: Some(consensus_state.into()),
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_client_state(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<QueryClientStateResponse, Self::Error> {
+		let client_state = self.query(at, |store| {
+			store
+				.clients
+				.get(&client_id)
+				.and_then(|record| record.client_state.clone())
+				.ok_or_else(|| Error::Custom(format!("Client {client_id} not found")))
+		})?;
+		Ok(QueryClientStateResponse {
+			client_state: Some(client_state.into()),
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_connection_end(
+		&self,
+		at: Height,
+		connection_id: ConnectionId,
+	) -> Result<QueryConnectionResponse, Self::Error> {
+		let connection = self.query(at, |store| {
+			store
+				.connections
+				.get(&connection_id)
+				.cloned()
+				.ok_or_else(|| Error::Custom(format!("Connection {connection_id} not found")))
+		})?;
+		Ok(QueryConnectionResponse {
+			connection: Some(connection.into()),
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_channel_end(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryChannelResponse, Self::Error> {
+		let channel =
+			self.query(at, |store| {
+				store.channels.get(&(port_id.clone(), channel_id)).cloned().ok_or_else(|| {
+					Error::Custom(format!("Channel {port_id}/{channel_id} not found"))
+				})
+			})?;
+		Ok(QueryChannelResponse {
+			channel: Some(channel.into()),
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_proof(&self, _at: Height, _keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
+		Ok(get_dummy_proof())
+	}
+
+	async fn query_packet_commitment(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketCommitmentResponse, Self::Error> {
+		let commitment = self.query(at, |store| {
+			Ok(store
+				.packet_commitment
+				.get(&(port_id.clone(), *channel_id, Sequence::from(seq)))
+				.cloned()
+				.map(|commitment| commitment.into_vec())
+				.unwrap_or_default())
+		})?;
+		Ok(QueryPacketCommitmentResponse {
+			commitment,
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_packet_acknowledgement(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketAcknowledgementResponse, Self::Error> {
+		let acknowledgement = self.query(at, |store| {
+			Ok(store
+				.packet_acknowledgement
+				.get(&(port_id.clone(), *channel_id, Sequence::from(seq)))
+				.cloned()
+				.map(|commitment| commitment.into_vec())
+				.unwrap_or_default())
+		})?;
+		Ok(QueryPacketAcknowledgementResponse {
+			acknowledgement,
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_next_sequence_recv(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceReceiveResponse, Self::Error> {
+		let next_sequence_receive = self.query(at, |store| {
+			Ok(store
+				.next_sequence_recv
+				.get(&(port_id.clone(), *channel_id))
+				.map(|seq| seq.0)
+				.unwrap_or(1))
+		})?;
+		Ok(QueryNextSequenceReceiveResponse {
+			next_sequence_receive,
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_packet_receipt(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketReceiptResponse, Self::Error> {
+		let received = self.query(at, |store| {
+			Ok(store.packet_receipt.contains_key(&(
+				port_id.clone(),
+				*channel_id,
+				Sequence::from(seq),
+			)))
+		})?;
+		Ok(QueryPacketReceiptResponse {
+			received,
+			proof: get_dummy_proof(),
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn latest_height_and_timestamp(&self) -> Result<(Height, Timestamp), Self::Error> {
+		let state = self.state.lock().unwrap();
+		let height = state.finalized_height();
+		let header = state.header(height.revision_height)?;
+		Ok((height, header.timestamp))
+	}
+
+	async fn query_packet_commitments(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.query(at, |store| {
+			Ok(store
+				.packet_commitment
+				.keys()
+				.filter(|(port, channel, _)| *port == port_id && *channel == channel_id)
+				.map(|(.., seq)| seq.0)
+				.collect())
+		})
+	}
+
+	async fn query_packet_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.query(at, |store| {
+			Ok(store
+				.packet_acknowledgement
+				.keys()
+				.filter(|(port, channel, _)| *port == port_id && *channel == channel_id)
+				.map(|(.., seq)| seq.0)
+				.collect())
+		})
+	}
+
+	async fn query_unreceived_packets(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.query(at, |store| {
+			Ok(seqs
+				.into_iter()
+				.filter(|seq| {
+					!store.packet_receipt.contains_key(&(
+						port_id.clone(),
+						channel_id,
+						Sequence::from(*seq),
+					))
+				})
+				.collect())
+		})
+	}
+
+	async fn query_unreceived_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		// the packet commitment is deleted once the acknowledgement is received
+		self.query(at, |store| {
+			Ok(seqs
+				.into_iter()
+				.filter(|seq| {
+					store.packet_commitment.contains_key(&(
+						port_id.clone(),
+						channel_id,
+						Sequence::from(*seq),
+					))
+				})
+				.collect())
+		})
+	}
+
+	fn channel_whitelist(&self) -> HashSet<(ChannelId, PortId)> {
+		self.channel_whitelist.lock().unwrap().clone()
+	}
+
+	async fn query_connection_channels(
+		&self,
+		at: Height,
+		connection_id: &ConnectionId,
+	) -> Result<QueryChannelsResponse, Self::Error> {
+		let channels = self.query(at, |store| {
+			Ok(store
+				.connection_channels
+				.get(connection_id)
+				.into_iter()
+				.flatten()
+				.filter_map(|(port_id, channel_id)| {
+					let channel_end = store.channels.get(&(port_id.clone(), *channel_id))?;
+					Some(
+						IdentifiedChannelEnd::new(
+							port_id.clone(),
+							*channel_id,
+							channel_end.clone(),
+						)
+						.into(),
+					)
+				})
+				.collect())
+		})?;
+		Ok(QueryChannelsResponse { channels, pagination: None, height: Some(at.into()) })
+	}
+
+	async fn query_send_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.packet_infos(channel_id, port_id.clone(), seqs, |event| match event {
+			IbcEvent::SendPacket(ev)
+				if ev.packet.source_port == port_id && ev.packet.source_channel == channel_id =>
+				Some((&ev.packet, None)),
+			_ => None,
+		})
+	}
+
+	async fn query_received_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.packet_infos(channel_id, port_id.clone(), seqs, |event| match event {
+			IbcEvent::WriteAcknowledgement(ev)
+				if ev.packet.destination_port == port_id &&
+					ev.packet.destination_channel == channel_id =>
+				Some((&ev.packet, Some(&ev.ack))),
+			_ => None,
+		})
+	}
+
+	fn expected_block_time(&self) -> Duration {
+		self.state.lock().unwrap().ctx.block_time
+	}
+
+	async fn query_client_update_time_and_height(
+		&self,
+		client_id: ClientId,
+		client_height: Height,
+	) -> Result<(Height, Timestamp), Self::Error> {
+		let state = self.state.lock().unwrap();
+		let height = state.ctx.client_update_height(&client_id, client_height)?;
+		let timestamp = state.ctx.client_update_time(&client_id, client_height)?;
+		Ok((height, timestamp))
+	}
+
+	async fn query_host_consensus_state_proof(
+		&self,
+		client_state: &AnyClientState,
+	) -> Result<Option<Vec<u8>>, Self::Error> {
+		// the mock host checks the consensus state itself against its own block
+		let header = self
+			.state
+			.lock()
+			.unwrap()
+			.header(client_state.latest_height().revision_height)?;
+		let consensus_state = MockAnyConsensusState::Mock(MockConsensusState::new(header))
+			.encode_vec()
+			.map_err(|e| Error::Custom(format!("Failed to encode consensus state: {e:?}")))?;
+		Ok(Some(consensus_state))
+	}
+
+	async fn query_ibc_balance(
+		&self,
+		asset_id: Self::AssetId,
+	) -> Result<Vec<PrefixedCoin>, Self::Error> {
+		let denom = PrefixedDenom::from_str(&asset_id)
+			.map_err(|e| Error::Custom(format!("Invalid denom {asset_id}: {e:?}")))?;
+		Ok(vec![PrefixedCoin { denom, amount: self.balance(&asset_id).into() }])
+	}
+
+	fn connection_prefix(&self) -> CommitmentPrefix {
+		self.state.lock().unwrap().ctx.commitment_prefix()
+	}
+
+	fn client_id(&self) -> ClientId {
+		self.client_id
+			.lock()
+			.unwrap()
+			.as_ref()
+			.expect("Client Id should be defined")
+			.clone()
+	}
+
+	fn set_client_id(&mut self, client_id: ClientId) {
+		*self.client_id.lock().unwrap() = Some(client_id);
+	}
+
+	fn connection_id(&self) -> Option<ConnectionId> {
+		self.connection_id.lock().unwrap().clone()
+	}
+
+	fn set_channel_whitelist(&mut self, channel_whitelist: HashSet<(ChannelId, PortId)>) {
+		*self.channel_whitelist.lock().unwrap() = channel_whitelist;
+	}
+
+	fn add_channel_to_whitelist(&mut self, channel: (ChannelId, PortId)) {
+		self.channel_whitelist.lock().unwrap().insert(channel);
+	}
+
+	fn set_connection_id(&mut self, connection_id: ConnectionId) {
+		*self.connection_id.lock().unwrap() = Some(connection_id);
+	}
+
+	fn client_type(&self) -> ClientType {
+		MockClientState::client_type()
+	}
+
+	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
+		let header = self.state.lock().unwrap().header(block_number)?;
+		Ok(header.timestamp.nanoseconds())
+	}
+
+	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
+		let state = self.state.lock().unwrap();
+		let clients = state.ctx.ibc_store.lock().unwrap().clients.keys().cloned().collect();
+		Ok(clients)
+	}
+
+	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
+		let state = self.state.lock().unwrap();
+		let channels = state
+			.ctx
+			.ibc_store
+			.lock()
+			.unwrap()
+			.channels
+			.keys()
+			.map(|(port_id, channel_id)| (*channel_id, port_id.clone()))
+			.collect();
+		Ok(channels)
+	}
+
+	async fn query_connection_using_client(
+		&self,
+		height: u32,
+		client_id: String,
+	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		let state = self.state.lock().unwrap();
+		let at = Height::new(state.pending_height().revision_number, height as u64);
+		state.query(at, |store| {
+			Ok(store
+				.connections
+				.iter()
+				.filter(|(_, connection_end)| connection_end.client_id().as_str() == client_id)
+				.map(|(connection_id, connection_end)| {
+					IdentifiedConnectionEnd::new(connection_id.clone(), connection_end.clone())
+						.into()
+				})
+				.collect())
+		})
+	}
+
+	async fn is_update_required(
+		&self,
+		_latest_height: u64,
+		_latest_client_height_on_counterparty: u64,
+	) -> Result<bool, Self::Error> {
+		Ok(false)
+	}
+
+	async fn initialize_client_state(
+		&self,
+	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
+		let header = {
+			let state = self.state.lock().unwrap();
+			state.header(state.finalized_height().revision_height)?
+		};
+		Ok((
+			AnyClientState::Mock(MockClientState::new(MockClientMessage::Header(header))),
+			AnyConsensusState::Mock(MockConsensusState::new(header)),
+		))
+	}
+
+	async fn query_client_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ClientId, Self::Error> {
+		self.transaction_events(tx_id)?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::CreateClient(ev) => Some(ev.client_id().clone()),
+				_ => None,
+			})
+			.ok_or_else(|| Error::Custom(format!("No client was created in transaction {tx_id}")))
+	}
+
+	async fn query_connection_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ConnectionId, Self::Error> {
+		self.transaction_events(tx_id)?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::OpenInitConnection(ev) => ev.connection_id().cloned(),
+				IbcEvent::OpenTryConnection(ev) => ev.connection_id().cloned(),
+				_ => None,
+			})
+			.ok_or_else(|| {
+				Error::Custom(format!("No connection was created in transaction {tx_id}"))
+			})
+	}
+
+	async fn query_channel_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<(ChannelId, PortId), Self::Error> {
+		self.transaction_events(tx_id)?
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::OpenInitChannel(ev) =>
+					ev.channel_id().map(|channel_id| (*channel_id, ev.port_id().clone())),
+				IbcEvent::OpenTryChannel(ev) =>
+					ev.channel_id().map(|channel_id| (*channel_id, ev.port_id().clone())),
+				_ => None,
+			})
+			.ok_or_else(|| Error::Custom(format!("No channel was created in transaction {tx_id}")))
+	}
+
+	async fn upload_wasm(&self, _wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error> {
+		Err(Error::Custom("Uploading WASM to a mock chain is not supported".to_string()))
+	}
+}
+
+impl KeyProvider for MockChain {
+	fn account_id(&self) -> Signer {
+		self.account.clone()
+	}
+}
+
+#[async_trait::async_trait]
+impl MisbehaviourHandler for MockChain {
+	async fn check_for_misbehaviour<C: Chain>(
+		&self,
+		_counterparty: &C,
+		client_message: AnyClientMessage,
+	) -> Result<(), anyhow::Error> {
+		let AnyClientMessage::Mock(MockClientMessage::Header(header)) =
+			client_message.unpack_recursive_into()
+		else {
+			return Ok(())
+		};
+		let own_header = {
+			let state = self.state.lock().unwrap();
+			if header.height.revision_height > state.finalized_height().revision_height {
+				return Ok(())
+			}
+			match state.header(header.height.revision_height) {
+				Ok(own_header) => own_header,
+				// the block was pruned from the history
+				Err(_) => return Ok(()),
+			}
+		};
+		if own_header != header {
+			log::warn!(
+				target: "hyperspace",
+				"Found misbehaviour of {} at {}: {header:?} != {own_header:?}",
+				self.name, header.height
+			);
+			let misbehaviour = MockMisbehaviour {
+				client_id: self.client_id(),
+				header1: own_header,
+				header2: header,
+			};
+			self.common_state
+				.queue_misbehaviour_message(AnyClientMessage::Mock(
+					MockClientMessage::Misbehaviour(misbehaviour),
+				))
+				.await?;
+		}
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl LightClientSync for MockChain {
+	async fn is_synced<C: Chain>(&self, counterparty: &C) -> Result<bool, anyhow::Error> {
+		let client_height = self.counterparty_client_height(counterparty).await?;
+		let finalized_height = self.state.lock().unwrap().finalized_height();
+		// the finalized block can be reached in one batch of updates
+		Ok(client_height.revision_height + NUMBER_OF_BLOCKS_TO_PROCESS_PER_ITER >=
+			finalized_height.revision_height)
+	}
+
+	async fn fetch_mandatory_updates<C: Chain>(
+		&self,
+		counterparty: &C,
+	) -> Result<(Vec<Any>, Vec<IbcEvent>), anyhow::Error> {
+		let client_height = self.counterparty_client_height(counterparty).await?;
+		let state = self.state.lock().unwrap();
+		let finalized_height = state.finalized_height();
+		if client_height >= finalized_height {
+			return Ok((vec![], vec![]))
+		}
+		// the mock client accepts any newer header, so a single update to the finalized block
+		// catches up with all of the blocks in between
+		let (msg, _) = state.msg_update_client(
+			self.client_id(),
+			finalized_height.revision_height,
+			counterparty.account_id(),
+		)?;
+		let events = state
+			.events
+			.range(client_height.revision_height + 1..=finalized_height.revision_height)
+			.flat_map(|(_, events)| events.iter().cloned())
+			.collect();
+		Ok((vec![msg], events))
+	}
+}
+
+#[async_trait::async_trait]
+impl Chain for MockChain {
+	fn name(&self) -> &str {
+		&self.name
+	}
+
+	fn block_max_weight(&self) -> u64 {
+		u64::MAX
+	}
+
+	async fn estimate_weight(&self, msg: Vec<Any>) -> Result<u64, Self::Error> {
+		Ok(msg.iter().map(|msg| msg.value.len() as u64).sum())
+	}
+
+	async fn finality_notifications(
+		&self,
+	) -> Result<Pin<Box<dyn Stream<Item = Self::FinalityEvent> + Send + Sync>>, Self::Error> {
+		let (tx, rx) = unbounded();
+		self.state.lock().unwrap().finality_subscribers.push(tx);
+		Ok(Box::pin(rx))
+	}
+
+	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Self::Error> {
+		self.state.lock().unwrap().execute(messages, &self.bank)
+	}
+
+	async fn query_client_message(
+		&self,
+		update: UpdateClient,
+	) -> Result<AnyClientMessage, Self::Error> {
+		let state = self.state.lock().unwrap();
+		let consensus_state =
+			state.ctx.consensus_state(update.client_id(), update.consensus_height())?;
+		let MockAnyConsensusState::Mock(consensus_state) = consensus_state;
+		Ok(AnyClientMessage::Mock(MockClientMessage::Header(consensus_state.header)))
+	}
+
+	async fn get_proof_height(&self, block_height: Height) -> Height {
+		block_height
+	}
+
+	async fn handle_error(&mut self, _error: &anyhow::Error) -> Result<(), anyhow::Error> {
+		Ok(())
+	}
+
+	fn common_state(&self) -> &CommonClientState {
+		&self.common_state
+	}
+
+	fn common_state_mut(&mut self) -> &mut CommonClientState {
+		&mut self.common_state
+	}
+
+	async fn reconnect(&mut self) -> anyhow::Result<()> {
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl TestProvider for MockChain {
+	async fn send_transfer(&self, params: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
+		let denom = params.token.denom.to_string();
+		let amount = params.token.amount.as_u256().as_u128();
+		let data = PacketData {
+			token: params.token,
+			sender: params.sender.clone(),
+			receiver: params.receiver,
+			memo: params.memo,
+		};
+		let data = serde_json::to_vec(&data)
+			.map_err(|e| Error::Custom(format!("Failed to encode packet data: {e}")))?;
+
+		let mut state = self.state.lock().unwrap();
+		self.bank.burn(params.sender.as_ref(), &denom, amount)?;
+		state
+			.send_packet(
+				params.source_port,
+				params.source_channel,
+				data,
+				params.timeout_height,
+				params.timeout_timestamp,
+			)
+			.map_err(|e| {
+				self.bank.mint(params.sender.as_ref(), &denom, amount);
+				e
+			})?;
+		Ok(())
+	}
+
+	async fn send_ordered_packet(
+		&self,
+		channel_id: ChannelId,
+		timeout: Timeout,
+	) -> Result<(), Self::Error> {
+		let (height_offset, timestamp_offset) = match timeout {
+			Timeout::Offset { timestamp, height } => (height.unwrap(), timestamp.unwrap()),
+			_ => panic!("Only offset timeouts allowed"),
+		};
+		let port_id = ping_port_id();
+
+		let mut state = self.state.lock().unwrap();
+		// the timeout is relative to the latest state of the counterparty known to this chain
+		let channel_end = state.ctx.channel_end(&(port_id.clone(), channel_id))?;
+		let connection_id = channel_end
+			.connection_hops
+			.first()
+			.ok_or_else(|| Error::Custom(format!("Channel {channel_id} has no connection")))?;
+		let connection_end = state.ctx.connection_end(connection_id)?;
+		let client_id = connection_end.client_id();
+		let latest_height = state.ctx.client_state(client_id)?.latest_height();
+		let timestamp = state.ctx.consensus_state(client_id, latest_height)?.timestamp();
+		let timeout_timestamp = (timestamp + Duration::from_secs(timestamp_offset))
+			.map_err(|e| Error::Custom(format!("Invalid timeout timestamp: {e}")))?;
+
+		state.send_packet(
+			port_id,
+			channel_id,
+			"ping".as_bytes().to_vec(),
+			latest_height.add(height_offset),
+			timeout_timestamp,
+		)?;
+		Ok(())
+	}
+
+	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
+		let (tx, rx) = unbounded();
+		self.state.lock().unwrap().block_subscribers.push(tx);
+		Box::pin(rx)
+	}
+
+	async fn increase_counters(&mut self) -> Result<(), Self::Error> {
+		let mut state = self.state.lock().unwrap();
+		state.ctx.increase_client_counter();
+		state.ctx.increase_connection_counter();
+		state.ctx.increase_channel_counter();
+		Ok(())
+	}
+}
@@ -0,0 +1,213 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ibc application modules installed on the [`super::chain::MockChain`].
+
+use ibc::{
+	applications::transfer::{
+		acknowledgement::Acknowledgement, is_receiver_chain_source, packet::PacketData, TracePrefix,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error,
+			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use std::{
+	collections::BTreeMap,
+	sync::{Arc, Mutex},
+};
+
+/// Token balances of the mock chain accounts, indexed by account and denomination. Shared between
+/// the chain and its transfer module.
+#[derive(Clone, Debug, Default)]
+pub struct Bank(Arc<Mutex<BTreeMap<(String, String), u128>>>);
+
+impl Bank {
+	pub fn balance(&self, account: &str, denom: &str) -> u128 {
+		let balances = self.0.lock().unwrap();
+		balances
+			.get(&(account.to_string(), denom.to_string()))
+			.copied()
+			.unwrap_or_default()
+	}
+
+	pub fn mint(&self, account: &str, denom: &str, amount: u128) {
+		let mut balances = self.0.lock().unwrap();
+		let balance = balances.entry((account.to_string(), denom.to_string())).or_default();
+		*balance = balance.saturating_add(amount);
+	}
+
+	pub fn burn(&self, account: &str, denom: &str, amount: u128) -> Result<(), String> {
+		let mut balances = self.0.lock().unwrap();
+		let balance = balances.entry((account.to_string(), denom.to_string())).or_default();
+		*balance = balance
+			.checked_sub(amount)
+			.ok_or_else(|| format!("Insufficient {denom} balance of {account}"))?;
+		Ok(())
+	}
+
+	pub(crate) fn snapshot(&self) -> BTreeMap<(String, String), u128> {
+		self.0.lock().unwrap().clone()
+	}
+
+	pub(crate) fn restore(&self, snapshot: BTreeMap<(String, String), u128>) {
+		*self.0.lock().unwrap() = snapshot;
+	}
+}
+
+/// Minimal ICS-20 application. Tokens are burnt when sent and minted when received or refunded,
+/// escrow accounts are not modelled.
+#[derive(Clone, Debug)]
+pub struct MockTransferModule {
+	bank: Bank,
+}
+
+impl MockTransferModule {
+	pub fn new(bank: Bank) -> Self {
+		Self { bank }
+	}
+
+	fn receive(&self, packet: &Packet) -> Result<(), String> {
+		let data = serde_json::from_slice::<PacketData>(&packet.data)
+			.map_err(|e| format!("Failed to decode packet data: {e}"))?;
+		let mut denom = data.token.denom.clone();
+		if is_receiver_chain_source(packet.source_port.clone(), packet.source_channel, &denom) {
+			denom.remove_trace_prefix(&TracePrefix::new(
+				packet.source_port.clone(),
+				packet.source_channel,
+			));
+		} else {
+			denom.add_trace_prefix(TracePrefix::new(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+			));
+		}
+		self.bank.mint(
+			data.receiver.as_ref(),
+			&denom.to_string(),
+			data.token.amount.as_u256().as_u128(),
+		);
+		Ok(())
+	}
+
+	fn refund(&self, packet: &Packet) -> Result<(), Error> {
+		let data = serde_json::from_slice::<PacketData>(&packet.data).map_err(|e| {
+			Error::implementation_specific(format!("Failed to decode packet data: {e}"))
+		})?;
+		self.bank.mint(
+			data.sender.as_ref(),
+			&data.token.denom.to_string(),
+			data.token.amount.as_u256().as_u128(),
+		);
+		Ok(())
+	}
+}
+
+impl Module for MockTransferModule {
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Error> {
+		Ok(counterparty_version.clone())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<GenericAcknowledgement, Error> {
+		let ack = match self.receive(packet) {
+			Ok(()) => Acknowledgement::success(),
+			Err(e) => Acknowledgement::Error(e),
+		};
+		Ok(GenericAcknowledgement::from_bytes(ack.to_string().into_bytes()))
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &GenericAcknowledgement,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		let ack = serde_json::from_slice::<Acknowledgement>(acknowledgement.as_ref())
+			.map_err(|e| Error::implementation_specific(format!("Invalid acknowledgement: {e}")))?;
+		if !ack.is_successful() {
+			self.refund(packet)?;
+		}
+		Ok(())
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		self.refund(packet)
+	}
+}
+
+/// Counterpart of `pallet-ibc-ping`, acknowledges every packet it receives.
+#[derive(Clone, Debug, Default)]
+pub struct MockPingModule;
+
+impl Module for MockPingModule {
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Error> {
+		Ok(counterparty_version.clone())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<GenericAcknowledgement, Error> {
+		Ok(GenericAcknowledgement::from_bytes(b"pong".to_vec()))
+	}
+}
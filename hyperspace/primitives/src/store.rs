@@ -0,0 +1,258 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Embedded store used to persist the relayer state, so that the relayer can resume its work
+//! after a restart.
+
+use crate::UndeliveredType;
+use anyhow::anyhow;
+use codec::{Decode, Encode};
+use ibc::Height;
+use ibc_proto::google::protobuf::Any;
+use parity_db::{Db, Options};
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+	path::Path,
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Column that holds all of the relayer state.
+const STATE_COLUMN: u8 = 0;
+
+const LAST_FINALITY_HEIGHT_KEY: &[u8] = b"last_finality_height";
+const UNDELIVERED_PACKETS_KEY: &[u8] = b"undelivered_packets";
+const IN_FLIGHT_PACKETS_KEY: &[u8] = b"in_flight_packets";
+const MISBEHAVIOUR_MESSAGES_KEY: &[u8] = b"misbehaviour_messages";
+
+/// Time after which a packet message that was submitted, but never confirmed, is considered lost
+/// and may be submitted again.
+pub const IN_FLIGHT_PACKET_TIMEOUT_SECS: u64 = 10 * 60;
+
+/// Identifies a packet message submitted to a chain.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct PacketKey {
+	/// Type url of the message, eg. `/ibc.core.channel.v1.MsgRecvPacket`
+	pub type_url: String,
+	/// Source port of the packet
+	pub port_id: String,
+	/// Source channel of the packet
+	pub channel_id: String,
+	/// Packet sequence
+	pub sequence: u64,
+}
+
+/// Handle to the relayer database.
+#[derive(Clone)]
+pub struct RelayerStore {
+	db: Arc<Db>,
+}
+
+impl RelayerStore {
+	/// Opens the database at the given path, creating it if it doesn't exist.
+	pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+		let options = Options::with_columns(path.as_ref(), 1);
+		let db = Db::open_or_create(&options)
+			.map_err(|e| anyhow!("Failed to open relayer store: {e}"))?;
+		Ok(Self { db: Arc::new(db) })
+	}
+
+	/// Returns the store of a chain on the given relay path.
+	pub fn path_store(&self, path: &str, chain: &str) -> PathStore {
+		PathStore { db: self.db.clone(), prefix: (path, chain).encode() }
+	}
+}
+
+/// State of a single chain on a relay path.
+#[derive(Clone)]
+pub struct PathStore {
+	db: Arc<Db>,
+	prefix: Vec<u8>,
+}
+
+impl fmt::Debug for PathStore {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PathStore").field("prefix", &hex::encode(&self.prefix)).finish()
+	}
+}
+
+impl PathStore {
+	fn key(&self, item: &[u8]) -> Vec<u8> {
+		let mut key = self.prefix.clone();
+		key.extend_from_slice(item);
+		key
+	}
+
+	fn get<T: Decode>(&self, item: &[u8]) -> anyhow::Result<Option<T>> {
+		self.db
+			.get(STATE_COLUMN, &self.key(item))
+			.map_err(|e| anyhow!("Failed to read from relayer store: {e}"))?
+			.map(|value| T::decode(&mut &*value))
+			.transpose()
+			.map_err(|e| anyhow!("Failed to decode relayer store value: {e}"))
+	}
+
+	fn set<T: Encode>(&self, item: &[u8], value: &T) -> anyhow::Result<()> {
+		self.db
+			.commit(vec![(STATE_COLUMN, self.key(item), Some(value.encode()))])
+			.map_err(|e| anyhow!("Failed to write to relayer store: {e}"))
+	}
+
+	/// Height of the last finality notification that was fully processed.
+	pub fn last_finality_height(&self) -> anyhow::Result<Option<Height>> {
+		Ok(self.get::<(u64, u64)>(LAST_FINALITY_HEIGHT_KEY)?.map(
+			|(revision_number, revision_height)| Height::new(revision_number, revision_height),
+		))
+	}
+
+	pub fn set_last_finality_height(&self, height: Height) -> anyhow::Result<()> {
+		self.set(LAST_FINALITY_HEIGHT_KEY, &(height.revision_number, height.revision_height))
+	}
+
+	/// Last known undelivered sequence flags, see
+	/// [`crate::CommonClientState::maybe_has_undelivered_packets`].
+	pub fn undelivered_packets(&self) -> anyhow::Result<HashMap<UndeliveredType, bool>> {
+		Ok(self
+			.get::<Vec<(UndeliveredType, bool)>>(UNDELIVERED_PACKETS_KEY)?
+			.unwrap_or_default()
+			.into_iter()
+			.collect())
+	}
+
+	pub fn set_undelivered_packets(
+		&self,
+		undelivered: &HashMap<UndeliveredType, bool>,
+	) -> anyhow::Result<()> {
+		let undelivered = undelivered.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+		self.set(UNDELIVERED_PACKETS_KEY, &undelivered)
+	}
+
+	/// Packet messages that were submitted, but not confirmed yet, with the unix timestamp (in
+	/// seconds) of their submission.
+	pub fn in_flight_packets(&self) -> anyhow::Result<BTreeMap<PacketKey, u64>> {
+		Ok(self
+			.get::<Vec<(PacketKey, u64)>>(IN_FLIGHT_PACKETS_KEY)?
+			.unwrap_or_default()
+			.into_iter()
+			.collect())
+	}
+
+	fn set_in_flight_packets(&self, packets: BTreeMap<PacketKey, u64>) -> anyhow::Result<()> {
+		self.set(IN_FLIGHT_PACKETS_KEY, &packets.into_iter().collect::<Vec<_>>())
+	}
+
+	/// Records the packets as submitted. Returns the packets that are already in flight and
+	/// haven't timed out yet, these should not be submitted again.
+	pub fn insert_in_flight_packets(
+		&self,
+		keys: impl IntoIterator<Item = PacketKey>,
+	) -> anyhow::Result<Vec<PacketKey>> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+		let mut packets = self.in_flight_packets()?;
+		packets.retain(|_, submitted_at| {
+			now.saturating_sub(*submitted_at) < IN_FLIGHT_PACKET_TIMEOUT_SECS
+		});
+		let mut in_flight = vec![];
+		for key in keys {
+			if packets.contains_key(&key) {
+				in_flight.push(key);
+			} else {
+				packets.insert(key, now);
+			}
+		}
+		self.set_in_flight_packets(packets)?;
+		Ok(in_flight)
+	}
+
+	/// Removes the packets after their submission was either confirmed or failed.
+	pub fn remove_in_flight_packets<'a>(
+		&self,
+		keys: impl IntoIterator<Item = &'a PacketKey>,
+	) -> anyhow::Result<()> {
+		let mut packets = self.in_flight_packets()?;
+		for key in keys {
+			packets.remove(key);
+		}
+		self.set_in_flight_packets(packets)
+	}
+
+	/// Misbehaviour messages that were queued, but not submitted yet.
+	pub fn misbehaviour_messages(&self) -> anyhow::Result<Vec<Any>> {
+		Ok(self
+			.get::<Vec<(String, Vec<u8>)>>(MISBEHAVIOUR_MESSAGES_KEY)?
+			.unwrap_or_default()
+			.into_iter()
+			.map(|(type_url, value)| Any { type_url, value })
+			.collect())
+	}
+
+	pub fn set_misbehaviour_messages(&self, messages: &[Any]) -> anyhow::Result<()> {
+		let messages = messages
+			.iter()
+			.map(|msg| (msg.type_url.clone(), msg.value.clone()))
+			.collect::<Vec<_>>();
+		self.set(MISBEHAVIOUR_MESSAGES_KEY, &messages)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn packet_key(sequence: u64) -> PacketKey {
+		PacketKey {
+			type_url: "/ibc.core.channel.v1.MsgRecvPacket".to_string(),
+			port_id: "transfer".to_string(),
+			channel_id: "channel-0".to_string(),
+			sequence,
+		}
+	}
+
+	#[test]
+	fn path_store_persists_state_across_restarts() {
+		let path = std::env::temp_dir().join(format!("hyperspace-store-{}", std::process::id()));
+		{
+			let store = RelayerStore::open(&path).unwrap();
+			let path_store = store.path_store("picasso-composable", "picasso");
+			path_store.set_last_finality_height(Height::new(1, 100)).unwrap();
+			path_store
+				.set_undelivered_packets(&HashMap::from([(UndeliveredType::Recvs, true)]))
+				.unwrap();
+			let in_flight =
+				path_store.insert_in_flight_packets([packet_key(1), packet_key(2)]).unwrap();
+			assert!(in_flight.is_empty());
+			path_store.remove_in_flight_packets(&[packet_key(2)]).unwrap();
+
+			// state is scoped to the path and chain
+			let other = store.path_store("picasso-composable", "composable");
+			assert_eq!(other.last_finality_height().unwrap(), None);
+		}
+
+		let store = RelayerStore::open(&path).unwrap();
+		let path_store = store.path_store("picasso-composable", "picasso");
+		assert_eq!(path_store.last_finality_height().unwrap(), Some(Height::new(1, 100)));
+		assert_eq!(
+			path_store.undelivered_packets().unwrap().get(&UndeliveredType::Recvs),
+			Some(&true)
+		);
+		// packets that are still in flight are not submitted again
+		let in_flight =
+			path_store.insert_in_flight_packets([packet_key(1), packet_key(2)]).unwrap();
+		assert_eq!(in_flight, vec![packet_key(1)]);
+
+		drop(store);
+		let _ = std::fs::remove_dir_all(path);
+	}
+}
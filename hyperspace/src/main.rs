@@ -25,6 +25,7 @@ async fn main() -> Result<()> {
 
 	match &cli.subcommand {
 		Subcommand::Relay(cmd) => cmd.run().await,
+		Subcommand::RelayPaths(cmd) => cmd.run().await,
 		Subcommand::UploadWasm(cmd) => {
 			let new_config = cmd.run().await?;
 			cmd.save_config(&new_config).await
@@ -42,5 +43,6 @@ async fn main() -> Result<()> {
 			cmd.save_config(&new_config).await
 		},
 		Subcommand::Fish(cmd) => cmd.fish().await,
+		Subcommand::EncryptKey(cmd) => cmd.run(),
 	}
 }
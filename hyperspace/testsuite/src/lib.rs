@@ -198,6 +198,8 @@ where
 		timeout_height,
 		timeout_timestamp,
 		memo: "".to_string(),
+		additional_tokens: vec![],
+		forwarding: None,
 	};
 	chain_a.send_transfer(msg.clone()).await.expect("Failed to send transfer: ");
 	(amount, msg)
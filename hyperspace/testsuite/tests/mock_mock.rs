@@ -0,0 +1,200 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runs the testsuite scenarios between two in-process mock chains, no live nodes are required.
+
+use core::time::Duration;
+use futures::StreamExt;
+use hyperspace_core::logging;
+use hyperspace_primitives::{
+	mock::{
+		chain::{MockChain, MockChainConfig, PING_PORT_ID},
+		LocalClientTypes,
+	},
+	utils::create_clients,
+	Chain, IbcProvider, KeyProvider, TestProvider,
+};
+use hyperspace_testsuite::{
+	ibc_channel_close, ibc_messaging_packet_height_timeout_with_connection_delay,
+	ibc_messaging_packet_timeout_on_channel_close,
+	ibc_messaging_packet_timestamp_timeout_with_connection_delay,
+	ibc_messaging_with_connection_delay, ordered_channels::ibc_messaging_ordered_packet_timeout,
+	setup_connection_and_channel,
+};
+use ibc::{
+	core::{
+		ics02_client::{client_state::ClientState, msgs::update_client::MsgUpdateAnyClient},
+		ics24_host::identifier::{ChainId, PortId},
+	},
+	events::IbcEvent,
+	mock::header::{MockClientMessage, MockHeader},
+	timestamp::Timestamp,
+	tx_msg::Msg,
+};
+use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState};
+use std::str::FromStr;
+use tokio::time::timeout;
+
+async fn setup_clients() -> (MockChain, MockChain) {
+	log::info!(target: "hyperspace", "=========================== Starting Test ===========================");
+	let config_a = MockChainConfig {
+		name: "mock-a".to_string(),
+		chain_id: ChainId::new("mock-a".to_string(), 1),
+		..Default::default()
+	};
+	let config_b = MockChainConfig {
+		name: "mock-b".to_string(),
+		chain_id: ChainId::new("mock-b".to_string(), 1),
+		..Default::default()
+	};
+	let mut chain_a = MockChain::new(config_a).await.unwrap();
+	let mut chain_b = MockChain::new(config_b).await.unwrap();
+
+	// start from different identifiers on both chains, so that mixing them up fails the test
+	chain_a.increase_counters().await.unwrap();
+
+	let (client_b, client_a) = create_clients(&mut chain_b, &mut chain_a).await.unwrap();
+	chain_a.set_client_id(client_a);
+	chain_b.set_client_id(client_b);
+	(chain_a, chain_b)
+}
+
+#[tokio::test]
+async fn mock_to_mock_ibc_messaging_full_integration_test() {
+	logging::setup_logging();
+
+	let (mut chain_a, mut chain_b) = setup_clients().await;
+	let (handle, channel_a, channel_b, connection_id_a, connection_id_b) =
+		setup_connection_and_channel(&mut chain_a, &mut chain_b, Duration::from_secs(60 * 2)).await;
+	handle.abort();
+
+	// Set connections and channel whitelist
+	chain_a.set_connection_id(connection_id_a);
+	chain_b.set_connection_id(connection_id_b);
+
+	chain_a.set_channel_whitelist(vec![(channel_a, PortId::transfer())].into_iter().collect());
+	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())].into_iter().collect());
+
+	let asset_id_a = chain_a.denom().to_string();
+	let asset_id_b = chain_b.denom().to_string();
+
+	// Run tests sequentially
+
+	// no timeouts + connection delay
+	ibc_messaging_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		asset_id_a.clone(),
+		asset_id_b.clone(),
+		channel_a,
+		channel_b,
+	)
+	.await;
+
+	// timeouts + connection delay
+	ibc_messaging_packet_height_timeout_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		asset_id_a.clone(),
+		channel_a,
+		channel_b,
+	)
+	.await;
+	ibc_messaging_packet_timestamp_timeout_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		asset_id_a.clone(),
+		channel_a,
+		channel_b,
+	)
+	.await;
+
+	// channel closing semantics
+	ibc_messaging_packet_timeout_on_channel_close(
+		&mut chain_a,
+		&mut chain_b,
+		asset_id_a.clone(),
+		channel_a,
+	)
+	.await;
+	ibc_channel_close(&mut chain_a, &mut chain_b).await;
+
+	// ordered channels
+	ibc_messaging_ordered_packet_timeout(
+		&mut chain_a,
+		&mut chain_b,
+		PortId::from_str(PING_PORT_ID).unwrap(),
+		"ping-1".to_string(),
+	)
+	.await;
+
+	// freezes the client, so it must run last
+	mock_messaging_submit_misbehaviour(&chain_a, &chain_b).await;
+}
+
+/// Updates the client of chain B on chain A with a header that conflicts with a finalized block of
+/// chain B, the fisherman must report it and get the client frozen.
+async fn mock_messaging_submit_misbehaviour(chain_a: &MockChain, chain_b: &MockChain) {
+	let handle = tokio::task::spawn({
+		let (chain_a, chain_b) = (chain_a.clone(), chain_b.clone());
+		async move { hyperspace_core::fish(chain_a, chain_b).await.unwrap() }
+	});
+
+	let client_id = chain_b.client_id();
+	let client_state = {
+		let latest_height = chain_a.latest_height_and_timestamp().await.unwrap().0;
+		let response = chain_a.query_client_state(latest_height, client_id.clone()).await.unwrap();
+		AnyClientState::try_from(response.client_state.unwrap()).unwrap()
+	};
+
+	// a block of chain B that is final, but that the client wasn't updated to yet
+	let mut finality_notifications = chain_b.finality_notifications().await.unwrap();
+	let height = loop {
+		let height = finality_notifications.next().await.expect("no finality event");
+		if height > client_state.latest_height() {
+			break height
+		}
+	};
+	let header = MockHeader::new(height).with_timestamp(Timestamp::from_nanoseconds(1).unwrap());
+	let msg = MsgUpdateAnyClient::<LocalClientTypes>::new(
+		client_id.clone(),
+		AnyClientMessage::Mock(MockClientMessage::Header(header)),
+		chain_a.account_id(),
+	);
+
+	let misbehaviour_event = tokio::task::spawn({
+		let mut events = chain_a.ibc_events().await;
+		async move {
+			while let Some(event) = events.next().await {
+				if let IbcEvent::ClientMisbehaviour(_) = event {
+					return
+				}
+			}
+		}
+	});
+	chain_a.submit(vec![msg.to_any()]).await.expect("failed to submit message");
+
+	timeout(Duration::from_secs(60), misbehaviour_event)
+		.await
+		.expect("timeout")
+		.expect("failed to receive misbehaviour event");
+	handle.abort();
+
+	let latest_height = chain_a.latest_height_and_timestamp().await.unwrap().0;
+	let response = chain_a.query_client_state(latest_height, client_id).await.unwrap();
+	match AnyClientState::try_from(response.client_state.unwrap()).unwrap() {
+		AnyClientState::Mock(client_state) => assert_eq!(client_state.frozen_height, Some(height)),
+		client_state => panic!("unexpected client state {client_state:?}"),
+	}
+}
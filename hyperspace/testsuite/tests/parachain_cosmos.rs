@@ -78,13 +78,16 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		para_id: args.para_id,
 		parachain_rpc_url: args.chain_a,
 		relay_chain_rpc_url: args.relay_chain.clone(),
+		relay_chain: Default::default(),
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_a.as_bytes().to_vec().into(),
 		ss58_version: 42,
 		channel_whitelist: vec![],
 		finality_protocol: FinalityProtocol::Grandpa,
-		private_key: "//Alice".to_string(),
+		solo_chain: false,
+		private_key: Some("//Alice".to_string()),
+		key_source: None,
 		key_type: "sr25519".to_string(),
 		wasm_checksum: None,
 	};
@@ -101,11 +104,21 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		fee_denom: "stake".to_string(),
 		fee_amount: "4000".to_string(),
 		gas_limit: (i64::MAX - 1) as u64,
+		gas_price: None,
+		gas_multiplier: 1.1,
+		max_gas: None,
+		fee_granter: None,
+		query_gas_price: false,
+		max_fee_retries: 3,
 		store_prefix: args.connection_prefix_b,
 		max_tx_size: 200000,
-		mnemonic:
+		counterparty_payee: None,
+		mnemonic: Some(
 			"oxygen fall sure lava energy veteran enroll frown question detail include maximum"
 				.to_string(),
+		),
+		key_source: None,
+		signer_keys: vec![],
 		wasm_checksum: None,
 		channel_whitelist: vec![],
 		common: CommonClientConfig {
@@ -65,13 +65,16 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		para_id: args.para_id_a,
 		parachain_rpc_url: args.chain_a,
 		relay_chain_rpc_url: args.relay_chain.clone(),
+		relay_chain: Default::default(),
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_b.as_bytes().to_vec().into(),
 		ss58_version: 42,
 		channel_whitelist: vec![],
 		finality_protocol: FinalityProtocol::Grandpa,
-		private_key: "//Alice".to_string(),
+		solo_chain: false,
+		private_key: Some("//Alice".to_string()),
+		key_source: None,
 		key_type: "sr25519".to_string(),
 		wasm_checksum: None,
 	};
@@ -80,13 +83,16 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		para_id: args.para_id_b,
 		parachain_rpc_url: args.chain_b,
 		relay_chain_rpc_url: args.relay_chain,
+		relay_chain: Default::default(),
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_b.as_bytes().to_vec().into(),
-		private_key: "//Alice".to_string(),
+		private_key: Some("//Alice".to_string()),
+		key_source: None,
 		ss58_version: 42,
 		channel_whitelist: vec![],
 		finality_protocol: FinalityProtocol::Grandpa,
+		solo_chain: false,
 		key_type: "sr25519".to_string(),
 		wasm_checksum: None,
 	};
@@ -456,6 +456,112 @@ impl State {
 		}
 	}
 
+	fn impl_fn_verify_channel_upgrade(&self) -> proc_macro2::TokenStream {
+		let crate_ = &self.crate_ident;
+		let trait_ = &self.current_impl_trait;
+		let error = &self.current_impl_error;
+		let client_state_trait = &self.client_state_trait;
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(client) => {
+					let client_type = #client_state_trait::client_type(client_state).to_owned();
+					let client_state = #crate_::downcast!(client_state => Self::ClientState::#variant_ident)
+						.ok_or_else(|| #error::client_args_type_mismatch(client_type))?;
+
+					#trait_::verify_channel_upgrade(
+						client,
+						ctx,
+						client_id,
+						client_state,
+						height,
+						prefix,
+						proof,
+						root,
+						port_id,
+						channel_id,
+						upgrade,
+					)
+				}
+			}
+		});
+
+		quote! {
+			fn verify_channel_upgrade<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
+				&self,
+				ctx: &Ctx,
+				client_id: &#crate_::core::ics24_host::identifier::ClientId,
+				client_state: &Self::ClientState,
+				height: #crate_::core::ics02_client::height::Height,
+				prefix: &#crate_::core::ics23_commitment::commitment::CommitmentPrefix,
+				proof: &#crate_::core::ics23_commitment::commitment::CommitmentProofBytes,
+				root: &#crate_::core::ics23_commitment::commitment::CommitmentRoot,
+				port_id: &#crate_::core::ics24_host::identifier::PortId,
+				channel_id: &#crate_::core::ics24_host::identifier::ChannelId,
+				upgrade: &#crate_::core::ics04_channel::upgrade::Upgrade,
+			) -> ::core::result::Result<(), #error> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
+	fn impl_fn_verify_channel_upgrade_error(&self) -> proc_macro2::TokenStream {
+		let crate_ = &self.crate_ident;
+		let trait_ = &self.current_impl_trait;
+		let error = &self.current_impl_error;
+		let client_state_trait = &self.client_state_trait;
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(client) => {
+					let client_type = #client_state_trait::client_type(client_state).to_owned();
+					let client_state = #crate_::downcast!(client_state => Self::ClientState::#variant_ident)
+						.ok_or_else(|| #error::client_args_type_mismatch(client_type))?;
+
+					#trait_::verify_channel_upgrade_error(
+						client,
+						ctx,
+						client_id,
+						client_state,
+						height,
+						prefix,
+						proof,
+						root,
+						port_id,
+						channel_id,
+						error_receipt,
+					)
+				}
+			}
+		});
+
+		quote! {
+			fn verify_channel_upgrade_error<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
+				&self,
+				ctx: &Ctx,
+				client_id: &#crate_::core::ics24_host::identifier::ClientId,
+				client_state: &Self::ClientState,
+				height: #crate_::core::ics02_client::height::Height,
+				prefix: &#crate_::core::ics23_commitment::commitment::CommitmentPrefix,
+				proof: &#crate_::core::ics23_commitment::commitment::CommitmentProofBytes,
+				root: &#crate_::core::ics23_commitment::commitment::CommitmentRoot,
+				port_id: &#crate_::core::ics24_host::identifier::PortId,
+				channel_id: &#crate_::core::ics24_host::identifier::ChannelId,
+				error_receipt: &#crate_::core::ics04_channel::upgrade::ErrorReceipt,
+			) -> ::core::result::Result<(), #error> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
 	fn impl_fn_verify_client_full_state(&self) -> proc_macro2::TokenStream {
 		let crate_ = &self.crate_ident;
 		let trait_ = &self.current_impl_trait;
@@ -732,6 +838,98 @@ impl State {
 		}
 	}
 
+	fn impl_fn_verify_packet_timeout_receipt(&self) -> proc_macro2::TokenStream {
+		let crate_ = &self.crate_ident;
+		let trait_ = &self.current_impl_trait;
+		let error = &self.current_impl_error;
+		let client_state_trait = &self.client_state_trait;
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			let _client_state_path = &client.client_state_path;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(client) => {
+					let client_type = #client_state_trait::client_type(client_state).to_owned();
+					let client_state = #crate_::downcast!(
+						client_state => Self::ClientState::#variant_ident
+					)
+					.ok_or_else(|| #error::client_args_type_mismatch(client_type))?;
+
+					#trait_::verify_packet_timeout_receipt(
+						client,
+						ctx,
+						client_id,
+						client_state,
+						height,
+						connection_end,
+						proof,
+						root,
+						port_id,
+						channel_id,
+						sequence,
+					)
+				}
+			}
+		});
+
+		quote! {
+			fn verify_packet_timeout_receipt<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
+				&self,
+				ctx: &Ctx,
+				client_id: &#crate_::core::ics24_host::identifier::ClientId,
+				client_state: &Self::ClientState,
+				height: #crate_::core::ics02_client::height::Height,
+				connection_end: &#crate_::core::ics03_connection::connection::ConnectionEnd,
+				proof: &#crate_::core::ics23_commitment::commitment::CommitmentProofBytes,
+				root: &#crate_::core::ics23_commitment::commitment::CommitmentRoot,
+				port_id: &#crate_::core::ics24_host::identifier::PortId,
+				channel_id: &#crate_::core::ics24_host::identifier::ChannelId,
+				sequence: #crate_::core::ics04_channel::packet::Sequence,
+			) -> ::core::result::Result<(), #error> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
+	fn impl_fn_update_state_on_verified_proof(&self) -> proc_macro2::TokenStream {
+		let crate_ = &self.crate_ident;
+		let trait_ = &self.current_impl_trait;
+		let error = &self.current_impl_error;
+		let client_state_trait = &self.client_state_trait;
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(client) => {
+					let client_type = #client_state_trait::client_type(&client_state).to_owned();
+					let client_state = #crate_::downcast!(
+						client_state => Self::ClientState::#variant_ident
+					)
+					.ok_or_else(|| #error::client_args_type_mismatch(client_type))?;
+
+					let client_state = #trait_::update_state_on_verified_proof(client, client_state, proof)?;
+					Ok(Self::ClientState::#variant_ident(client_state))
+				}
+			}
+		});
+
+		quote! {
+			fn update_state_on_verified_proof(
+				&self,
+				client_state: Self::ClientState,
+				proof: &#crate_::core::ics23_commitment::commitment::CommitmentProofBytes,
+			) -> ::core::result::Result<Self::ClientState, #error> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
 	pub fn impl_client_def(&mut self) -> proc_macro2::TokenStream {
 		let crate_ = &self.crate_ident;
 		self.current_impl_trait =
@@ -755,11 +953,15 @@ impl State {
 		let fn_verify_client_consensus_state = self.impl_fn_verify_client_consensus_state();
 		let fn_verify_connection_state = self.impl_fn_verify_connection_state();
 		let fn_verify_channel_state = self.impl_fn_verify_channel_state();
+		let fn_verify_channel_upgrade = self.impl_fn_verify_channel_upgrade();
+		let fn_verify_channel_upgrade_error = self.impl_fn_verify_channel_upgrade_error();
 		let fn_verify_client_full_state = self.impl_fn_verify_client_full_state();
 		let fn_verify_packet_data = self.impl_fn_verify_packet_data();
 		let fn_verify_packet_acknowledgement = self.impl_fn_verify_packet_acknowledgement();
 		let fn_verify_next_sequence_recv = self.impl_fn_verify_next_sequence_recv();
 		let fn_verify_packet_receipt_absence = self.impl_fn_verify_packet_receipt_absence();
+		let fn_verify_packet_timeout_receipt = self.impl_fn_verify_packet_timeout_receipt();
+		let fn_update_state_on_verified_proof = self.impl_fn_update_state_on_verified_proof();
 
 		quote! {
 			impl #impl_generics #client_def_trait for #this #ty_generics #where_clause {
@@ -776,11 +978,15 @@ impl State {
 				#fn_verify_client_consensus_state
 				#fn_verify_connection_state
 				#fn_verify_channel_state
+				#fn_verify_channel_upgrade
+				#fn_verify_channel_upgrade_error
 				#fn_verify_client_full_state
 				#fn_verify_packet_data
 				#fn_verify_packet_acknowledgement
 				#fn_verify_next_sequence_recv
 				#fn_verify_packet_receipt_absence
+				#fn_verify_packet_timeout_receipt
+				#fn_update_state_on_verified_proof
 			}
 		}
 	}
@@ -0,0 +1,97 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::error::Error;
+use crate::{
+	core::ics04_channel::msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+	prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+/// Acknowledgement written by fee enabled channels, it wraps the acknowledgement of the underlying
+/// application.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IncentivizedAcknowledgement {
+	/// Acknowledgement of the underlying application
+	#[serde(with = "ibc_proto::base64")]
+	pub app_acknowledgement: Vec<u8>,
+	/// Counterparty payee of the relayer that submitted the packet on the destination chain, the
+	/// recv fee is paid out to this address on the source chain
+	pub forward_relayer_address: String,
+	/// Whether the underlying application processed the packet successfully
+	pub underlying_app_success: bool,
+}
+
+impl IncentivizedAcknowledgement {
+	pub fn app_acknowledgement(&self) -> GenericAcknowledgement {
+		GenericAcknowledgement::from_bytes(self.app_acknowledgement.clone())
+	}
+}
+
+impl TryFrom<&GenericAcknowledgement> for IncentivizedAcknowledgement {
+	type Error = Error;
+
+	fn try_from(ack: &GenericAcknowledgement) -> Result<Self, Self::Error> {
+		serde_json::from_slice(ack.as_ref()).map_err(|_| Error::ack_deserialization())
+	}
+}
+
+impl From<IncentivizedAcknowledgement> for GenericAcknowledgement {
+	fn from(ack: IncentivizedAcknowledgement) -> Self {
+		GenericAcknowledgement::from_bytes(
+			serde_json::to_vec(&ack).expect("acknowledgement is valid JSON; qed"),
+		)
+	}
+}
+
+/// Whether an application acknowledgement signals success. Acknowledgements that follow the ICS-04
+/// recommended format are only unsuccessful when they hold an `error`, opaque acknowledgements
+/// are considered successful.
+pub fn is_app_ack_successful(ack: &GenericAcknowledgement) -> bool {
+	#[derive(Deserialize)]
+	struct ErrorAck {
+		#[allow(dead_code)]
+		error: String,
+	}
+
+	serde_json::from_slice::<ErrorAck>(ack.as_ref()).is_err()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::applications::transfer::acknowledgement::Acknowledgement as Ics20Acknowledgement;
+
+	#[test]
+	fn incentivized_acknowledgement_wraps_the_app_acknowledgement() {
+		let app_ack = GenericAcknowledgement::from_bytes(
+			Ics20Acknowledgement::success().to_string().into_bytes(),
+		);
+		assert!(is_app_ack_successful(&app_ack));
+		let ack = IncentivizedAcknowledgement {
+			app_acknowledgement: app_ack.clone().into_bytes(),
+			forward_relayer_address: "relayer".to_string(),
+			underlying_app_success: true,
+		};
+		let generic: GenericAcknowledgement = ack.clone().into();
+		let decoded = IncentivizedAcknowledgement::try_from(&generic).unwrap();
+		assert_eq!(decoded, ack);
+		assert_eq!(decoded.app_acknowledgement(), app_ack);
+
+		let error_ack = GenericAcknowledgement::from_bytes(
+			Ics20Acknowledgement::Error("failed".to_string()).to_string().into_bytes(),
+		);
+		assert!(!is_app_ack_successful(&error_ack));
+	}
+}
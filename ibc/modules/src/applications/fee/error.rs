@@ -0,0 +1,92 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use flex_error::{define_error, TraceError};
+use tendermint_proto::Error as TendermintProtoError;
+
+use crate::{
+	applications::transfer::error::Error as Ics20Error,
+	core::{
+		ics04_channel::{error as channel_error, Version},
+		ics24_host::{
+			error::ValidationError,
+			identifier::{ChannelId, PortId},
+		},
+	},
+	prelude::*,
+	signer::SignerError,
+};
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		Ics04Channel
+			[ channel_error::Error ]
+			|_ | { "Ics04 channel error" },
+
+		InvalidPortId
+			{ context: String }
+			[ ValidationError ]
+			| _ | { "invalid port identifier" },
+
+		InvalidChannelId
+			{ context: String }
+			[ ValidationError ]
+			| _ | { "invalid channel identifier" },
+
+		Signer
+			[ SignerError ]
+			| _ | { "invalid signer address" },
+
+		InvalidCoin
+			[ Ics20Error ]
+			| _ | { "invalid fee coin" },
+
+		MissingFee
+			| _ | { "fee is missing" },
+
+		InvalidFee
+			| _ | { "fee must not be empty and amounts must be positive" },
+
+		MissingPacketId
+			| _ | { "packet id is missing" },
+
+		InvalidFeeVersion
+			{ version: String }
+			| e | { format_args!("invalid fee version: expected '{0}', got '{1}'", super::VERSION, e.version) },
+
+		InvalidMetadata
+			{ version: Version }
+			| e | { format_args!("channel version is not ICS29 metadata: {0}", e.version) },
+
+		FeeNotEnabled
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | { format_args!("fee module is not enabled for port_id {0} and channel_id {1}", e.port_id, e.channel_id) },
+
+		AckDeserialization
+			| _ | { "failed to deserialize incentivized acknowledgement" },
+
+		DecodeRawMsg
+			[ TraceError<TendermintProtoError> ]
+			| _ | { "error decoding raw msg" },
+
+		UnknownMsgType
+			{ msg_type: String }
+			| e | { format_args!("unknown msg type: {0}", e.msg_type) },
+
+		ImplementationSpecific
+			{ reason: String }
+			| e | { format_args!("implementation specific error: {}", e.reason) },
+	}
+}
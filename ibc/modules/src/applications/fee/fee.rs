@@ -0,0 +1,106 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::error::Error;
+use crate::{applications::transfer::PrefixedCoin, prelude::*, signer::Signer};
+use ibc_proto::ibc::applications::fee::v1::{Fee as RawFee, PacketFee as RawPacketFee};
+
+/// Fees paid to the relayers of a packet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fee {
+	/// Paid to the relayer that submitted the packet to the destination chain
+	pub recv_fee: Vec<PrefixedCoin>,
+	/// Paid to the relayer that submitted the acknowledgement back to the source chain
+	pub ack_fee: Vec<PrefixedCoin>,
+	/// Paid to the relayer that submitted the timeout to the source chain
+	pub timeout_fee: Vec<PrefixedCoin>,
+}
+
+impl Fee {
+	/// Coins that need to be escrowed to pay out the fee, whatever the outcome of the packet.
+	pub fn total(&self) -> impl Iterator<Item = &PrefixedCoin> {
+		self.recv_fee.iter().chain(self.ack_fee.iter()).chain(self.timeout_fee.iter())
+	}
+
+	pub fn validate(&self) -> Result<(), Error> {
+		let mut total = self.total().peekable();
+		if total.peek().is_none() || total.any(|coin| coin.amount.as_u256().is_zero()) {
+			return Err(Error::invalid_fee())
+		}
+		Ok(())
+	}
+}
+
+fn coins_from_raw(
+	coins: Vec<ibc_proto::cosmos::base::v1beta1::Coin>,
+) -> Result<Vec<PrefixedCoin>, Error> {
+	coins
+		.into_iter()
+		.map(|coin| PrefixedCoin::try_from(coin).map_err(Error::invalid_coin))
+		.collect()
+}
+
+impl TryFrom<RawFee> for Fee {
+	type Error = Error;
+
+	fn try_from(raw: RawFee) -> Result<Self, Self::Error> {
+		Ok(Self {
+			recv_fee: coins_from_raw(raw.recv_fee)?,
+			ack_fee: coins_from_raw(raw.ack_fee)?,
+			timeout_fee: coins_from_raw(raw.timeout_fee)?,
+		})
+	}
+}
+
+impl From<Fee> for RawFee {
+	fn from(fee: Fee) -> Self {
+		RawFee {
+			recv_fee: fee.recv_fee.into_iter().map(Into::into).collect(),
+			ack_fee: fee.ack_fee.into_iter().map(Into::into).collect(),
+			timeout_fee: fee.timeout_fee.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Fee escrowed for a packet, together with the account that gets refunded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PacketFee {
+	pub fee: Fee,
+	/// Account refunded with the fees that are not paid out to relayers
+	pub refund_address: Signer,
+	/// Optional list of relayers permitted to receive the fee, not supported yet
+	pub relayers: Vec<String>,
+}
+
+impl TryFrom<RawPacketFee> for PacketFee {
+	type Error = Error;
+
+	fn try_from(raw: RawPacketFee) -> Result<Self, Self::Error> {
+		Ok(Self {
+			fee: raw.fee.ok_or_else(Error::missing_fee)?.try_into()?,
+			refund_address: raw.refund_address.parse().map_err(Error::signer)?,
+			relayers: raw.relayers,
+		})
+	}
+}
+
+impl From<PacketFee> for RawPacketFee {
+	fn from(packet_fee: PacketFee) -> Self {
+		RawPacketFee {
+			fee: Some(packet_fee.fee.into()),
+			refund_address: packet_fee.refund_address.to_string(),
+			relayers: packet_fee.relayers,
+		}
+	}
+}
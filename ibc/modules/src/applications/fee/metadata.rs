@@ -0,0 +1,75 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Channel version negotiated by the fee middleware, it wraps the version of the underlying
+//! application.
+
+use super::{error::Error, VERSION};
+use crate::{core::ics04_channel::Version, prelude::*};
+use serde::{Deserialize, Serialize};
+
+/// JSON encoded into the channel version of fee enabled channels, eg.
+/// `{"fee_version":"ics29-1","app_version":"ics20-1"}`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+	/// ICS29 version
+	pub fee_version: String,
+	/// Version of the underlying application
+	pub app_version: String,
+}
+
+impl Metadata {
+	pub fn new(app_version: &Version) -> Self {
+		Self { fee_version: VERSION.to_string(), app_version: app_version.to_string() }
+	}
+
+	/// Parses the metadata out of a channel version. Returns `None` if the version is not fee
+	/// metadata, which means that the channel is not fee enabled and the version belongs to the
+	/// underlying application.
+	pub fn from_version(version: &Version) -> Option<Self> {
+		serde_json::from_str(&version.to_string()).ok()
+	}
+
+	pub fn validate(&self) -> Result<(), Error> {
+		if self.fee_version != VERSION {
+			return Err(Error::invalid_fee_version(self.fee_version.clone()))
+		}
+		Ok(())
+	}
+
+	pub fn app_version(&self) -> Version {
+		Version::new(self.app_version.clone())
+	}
+
+	pub fn to_version(&self) -> Version {
+		Version::new(serde_json::to_string(self).expect("metadata is valid JSON; qed"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn metadata_round_trips_through_the_channel_version() {
+		let version = Metadata::new(&Version::ics20()).to_version();
+		assert_eq!(version.to_string(), r#"{"fee_version":"ics29-1","app_version":"ics20-1"}"#);
+
+		let metadata = Metadata::from_version(&version).unwrap();
+		metadata.validate().unwrap();
+		assert_eq!(metadata.app_version(), Version::ics20());
+
+		assert_eq!(Metadata::from_version(&Version::ics20()), None);
+	}
+}
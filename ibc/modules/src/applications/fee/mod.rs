@@ -0,0 +1,30 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ICS 29: Fee Middleware incentivises relayers of the packets sent by any application it wraps.
+//! Fees are escrowed on the source chain when a packet is sent, and paid out to the forward and
+//! reverse relayers once the packet is acknowledged, or to the timeout relayer if it times out.
+pub mod acknowledgement;
+pub mod error;
+pub mod fee;
+pub mod metadata;
+pub mod msgs;
+
+pub use fee::*;
+
+/// Module identifier for the ICS29 middleware.
+pub const MODULE_ID_STR: &str = "feeibc";
+
+/// ICS29 middleware current version.
+pub const VERSION: &str = "ics29-1";
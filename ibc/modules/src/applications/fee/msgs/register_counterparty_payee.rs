@@ -0,0 +1,82 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Message used by a relayer to register the address, on the source chain, that receives the
+//! recv fees of the packets it submits to this chain.
+
+use crate::prelude::*;
+
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::applications::fee::v1::MsgRegisterCounterpartyPayee as RawMsgRegisterCounterpartyPayee,
+};
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::fee::error::Error,
+	core::ics24_host::identifier::{ChannelId, PortId},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.applications.fee.v1.MsgRegisterCounterpartyPayee";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgRegisterCounterpartyPayee {
+	/// Port on this chain
+	pub port_id: PortId,
+	/// Channel on this chain
+	pub channel_id: ChannelId,
+	/// Address of the relayer on this chain
+	pub relayer: Signer,
+	/// Address of the payee on the counterparty chain
+	pub counterparty_payee: String,
+}
+
+impl Msg for MsgRegisterCounterpartyPayee {
+	type ValidationError = Error;
+	type Raw = RawMsgRegisterCounterpartyPayee;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl TryFrom<RawMsgRegisterCounterpartyPayee> for MsgRegisterCounterpartyPayee {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgRegisterCounterpartyPayee) -> Result<Self, Self::Error> {
+		if raw_msg.counterparty_payee.trim().is_empty() {
+			return Err(Error::implementation_specific(
+				"counterparty payee must not be empty".to_string(),
+			))
+		}
+		Ok(Self {
+			port_id: raw_msg
+				.port_id
+				.parse()
+				.map_err(|e| Error::invalid_port_id(raw_msg.port_id.clone(), e))?,
+			channel_id: raw_msg
+				.channel_id
+				.parse()
+				.map_err(|e| Error::invalid_channel_id(raw_msg.channel_id.clone(), e))?,
+			relayer: raw_msg.relayer.parse().map_err(Error::signer)?,
+			counterparty_payee: raw_msg.counterparty_payee,
+		})
+	}
+}
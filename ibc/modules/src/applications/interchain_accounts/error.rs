@@ -0,0 +1,61 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use flex_error::{define_error, TraceError};
+use tendermint_proto::Error as TendermintProtoError;
+
+use crate::{
+	core::{ics04_channel::error as channel_error, ics24_host::error::ValidationError},
+	prelude::*,
+};
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		Ics04Channel
+			[ channel_error::Error ]
+			|_ | { "Ics04 channel error" },
+
+		InvalidPortId
+			{ context: String }
+			[ ValidationError ]
+			| _ | { "invalid port identifier" },
+
+		EmptyOwner
+			| _ | { "interchain account owner must not be empty" },
+
+		InvalidVersion
+			{ expected: String, actual: String }
+			| e | { format_args!("invalid interchain accounts version: expected '{0}', got '{1}'", e.expected, e.actual) },
+
+		InvalidMetadata
+			{ reason: String }
+			| e | { format_args!("invalid interchain accounts metadata: {0}", e.reason) },
+
+		InvalidOrder
+			| _ | { "interchain account channels must be ordered" },
+
+		InvalidPacketData
+			{ reason: String }
+			| e | { format_args!("invalid interchain account packet data: {0}", e.reason) },
+
+		DecodeRawMsg
+			[ TraceError<TendermintProtoError> ]
+			| _ | { "error decoding raw msg" },
+
+		ImplementationSpecific
+			{ reason: String }
+			| e | { format_args!("implementation specific error: {}", e.reason) },
+	}
+}
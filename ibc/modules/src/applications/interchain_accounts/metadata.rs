@@ -0,0 +1,115 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Channel version negotiated by interchain account channels.
+
+use super::{error::Error, ENCODING_PROTOBUF, TX_TYPE_SDK_MULTI_MSG, VERSION};
+use crate::{
+	core::{ics04_channel::Version, ics24_host::identifier::ConnectionId},
+	prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+/// JSON encoded into the channel version of interchain account channels. The controller proposes
+/// it without an address, which is then filled in by the host.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+	/// ICS27 version
+	pub version: String,
+	/// Connection on the controller chain
+	pub controller_connection_id: String,
+	/// Connection on the host chain
+	pub host_connection_id: String,
+	/// Address of the interchain account on the host chain
+	#[serde(default)]
+	pub address: String,
+	/// Encoding of the transactions
+	pub encoding: String,
+	/// Type of the transactions
+	pub tx_type: String,
+}
+
+impl Metadata {
+	pub fn new(controller_connection_id: &ConnectionId, host_connection_id: &ConnectionId) -> Self {
+		Self {
+			version: VERSION.to_string(),
+			controller_connection_id: controller_connection_id.to_string(),
+			host_connection_id: host_connection_id.to_string(),
+			address: String::new(),
+			encoding: ENCODING_PROTOBUF.to_string(),
+			tx_type: TX_TYPE_SDK_MULTI_MSG.to_string(),
+		}
+	}
+
+	pub fn from_version(version: &Version) -> Result<Self, Error> {
+		serde_json::from_str(&version.to_string())
+			.map_err(|e| Error::invalid_metadata(format!("{version}: {e}")))
+	}
+
+	/// Checks the metadata against the connection of the channel, the connection ids are given
+	/// from the point of view of the controller chain.
+	pub fn validate(
+		&self,
+		controller_connection_id: &ConnectionId,
+		host_connection_id: &ConnectionId,
+	) -> Result<(), Error> {
+		if self.version != VERSION {
+			return Err(Error::invalid_version(VERSION.to_string(), self.version.clone()))
+		}
+		if self.encoding != ENCODING_PROTOBUF {
+			return Err(Error::invalid_metadata(format!("unsupported encoding {}", self.encoding)))
+		}
+		if self.tx_type != TX_TYPE_SDK_MULTI_MSG {
+			return Err(Error::invalid_metadata(format!("unsupported tx type {}", self.tx_type)))
+		}
+		if self.controller_connection_id != controller_connection_id.as_str() ||
+			self.host_connection_id != host_connection_id.as_str()
+		{
+			return Err(Error::invalid_metadata(format!(
+				"expected connections {controller_connection_id} and {host_connection_id}, got {} and {}",
+				self.controller_connection_id, self.host_connection_id
+			)))
+		}
+		Ok(())
+	}
+
+	pub fn with_address(self, address: String) -> Self {
+		Self { address, ..self }
+	}
+
+	pub fn to_version(&self) -> Version {
+		Version::new(serde_json::to_string(self).expect("metadata is valid JSON; qed"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn metadata_is_validated_against_the_channel_connection() {
+		let controller_connection_id = ConnectionId::new(0);
+		let host_connection_id = ConnectionId::new(1);
+		let metadata = Metadata::new(&controller_connection_id, &host_connection_id);
+		let version = metadata.clone().with_address("host-account".to_string()).to_version();
+
+		let decoded = Metadata::from_version(&version).unwrap();
+		decoded.validate(&controller_connection_id, &host_connection_id).unwrap();
+		assert_eq!(decoded.address, "host-account");
+		assert_eq!(Metadata::from_version(&metadata.to_version()).unwrap(), metadata);
+
+		assert!(decoded.validate(&host_connection_id, &controller_connection_id).is_err());
+		assert!(Metadata::from_version(&Version::new(VERSION.to_string())).is_err());
+	}
+}
@@ -0,0 +1,80 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ICS 27: Interchain Accounts lets a controller chain open an account on a host chain over an
+//! ordered channel, and execute transactions from it by sending packets. Every controller account
+//! binds its own port, `icacontroller-{owner}`, while the host binds a single `icahost` port.
+pub mod error;
+pub mod metadata;
+pub mod packet;
+
+use crate::{core::ics24_host::identifier::PortId, prelude::*};
+use core::str::FromStr;
+use error::Error;
+
+/// Module identifier of the controller submodule.
+pub const CONTROLLER_MODULE_ID_STR: &str = "icacontroller";
+
+/// Module identifier of the host submodule.
+pub const HOST_MODULE_ID_STR: &str = "icahost";
+
+/// Port bound by the host submodule.
+pub const HOST_PORT_ID_STR: &str = "icahost";
+
+/// Prefix of the ports bound by the controller submodule, followed by the owner of the account.
+pub const CONTROLLER_PORT_PREFIX: &str = "icacontroller-";
+
+/// ICS27 current version.
+pub const VERSION: &str = "ics27-1";
+
+/// Encoding of the transactions sent over interchain account channels.
+pub const ENCODING_PROTOBUF: &str = "proto3";
+
+/// Type of the transactions sent over interchain account channels, a list of messages.
+pub const TX_TYPE_SDK_MULTI_MSG: &str = "sdk_multi_msg";
+
+/// Port bound by the controller account of the given owner.
+pub fn controller_port_id(owner: &str) -> Result<PortId, Error> {
+	if owner.trim().is_empty() {
+		return Err(Error::empty_owner())
+	}
+	let port_id = format!("{CONTROLLER_PORT_PREFIX}{owner}");
+	PortId::from_str(&port_id).map_err(|e| Error::invalid_port_id(port_id, e))
+}
+
+/// Owner of the controller account bound to the port, `None` if it is not a controller port.
+pub fn controller_port_owner(port_id: &PortId) -> Option<&str> {
+	port_id
+		.as_str()
+		.strip_prefix(CONTROLLER_PORT_PREFIX)
+		.filter(|owner| !owner.is_empty())
+}
+
+pub fn host_port_id() -> PortId {
+	PortId::from_str(HOST_PORT_ID_STR).expect("HOST_PORT_ID_STR is a valid port id; qed")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn controller_ports_are_bound_to_their_owner() {
+		let port_id = controller_port_id("owner").unwrap();
+		assert_eq!(port_id.as_str(), "icacontroller-owner");
+		assert_eq!(controller_port_owner(&port_id), Some("owner"));
+		assert_eq!(controller_port_owner(&host_port_id()), None);
+		assert!(controller_port_id("").is_err());
+	}
+}
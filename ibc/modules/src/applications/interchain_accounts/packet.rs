@@ -0,0 +1,138 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Packet data sent by the controller to execute transactions from its interchain account.
+
+use super::error::Error;
+use crate::prelude::*;
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::applications::interchain_accounts::v1::{
+		CosmosTx as RawCosmosTx, InterchainAccountPacketData as RawInterchainAccountPacketData,
+		Type as RawType,
+	},
+};
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketType {
+	/// Execute a transaction on the host chain
+	#[serde(rename = "TYPE_EXECUTE_TX")]
+	ExecuteTx,
+}
+
+/// JSON encoded packet data, the same encoding as ibc-go.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InterchainAccountPacketData {
+	#[serde(rename = "type")]
+	pub packet_type: PacketType,
+	/// Transaction encoded according to the channel metadata, see [`CosmosTx`]
+	#[serde(with = "ibc_proto::base64")]
+	pub data: Vec<u8>,
+	#[serde(default)]
+	pub memo: String,
+}
+
+impl InterchainAccountPacketData {
+	pub fn execute_tx(tx: CosmosTx, memo: String) -> Self {
+		Self {
+			packet_type: PacketType::ExecuteTx,
+			data: tx.encode_vec().expect("encoding to vec is infallible; qed"),
+			memo,
+		}
+	}
+
+	pub fn cosmos_tx(&self) -> Result<CosmosTx, Error> {
+		CosmosTx::decode_vec(&self.data).map_err(Error::decode_raw_msg)
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		serde_json::to_vec(self).expect("packet data is valid JSON; qed")
+	}
+}
+
+impl TryFrom<&[u8]> for InterchainAccountPacketData {
+	type Error = Error;
+
+	fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+		serde_json::from_slice(bytes).map_err(|e| Error::invalid_packet_data(e.to_string()))
+	}
+}
+
+impl TryFrom<RawInterchainAccountPacketData> for InterchainAccountPacketData {
+	type Error = Error;
+
+	fn try_from(raw: RawInterchainAccountPacketData) -> Result<Self, Self::Error> {
+		let packet_type = match RawType::from_i32(raw.r#type) {
+			Some(RawType::ExecuteTx) => PacketType::ExecuteTx,
+			_ => return Err(Error::invalid_packet_data(format!("unknown type {}", raw.r#type))),
+		};
+		Ok(Self { packet_type, data: raw.data, memo: raw.memo })
+	}
+}
+
+impl From<InterchainAccountPacketData> for RawInterchainAccountPacketData {
+	fn from(data: InterchainAccountPacketData) -> Self {
+		let r#type = match data.packet_type {
+			PacketType::ExecuteTx => RawType::ExecuteTx as i32,
+		};
+		Self { r#type, data: data.data, memo: data.memo }
+	}
+}
+
+/// Messages executed atomically by the interchain account on the host chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CosmosTx {
+	pub messages: Vec<Any>,
+}
+
+impl Protobuf<RawCosmosTx> for CosmosTx {}
+
+impl From<RawCosmosTx> for CosmosTx {
+	fn from(raw: RawCosmosTx) -> Self {
+		Self { messages: raw.messages }
+	}
+}
+
+impl From<CosmosTx> for RawCosmosTx {
+	fn from(tx: CosmosTx) -> Self {
+		Self { messages: tx.messages }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn packet_data_uses_the_ibc_go_json_encoding() {
+		let tx = CosmosTx {
+			messages: vec![Any {
+				type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+				value: vec![1],
+			}],
+		};
+		let data = InterchainAccountPacketData::execute_tx(tx.clone(), "memo".to_string());
+		let json = String::from_utf8(data.to_bytes()).unwrap();
+		assert!(json.starts_with(r#"{"type":"TYPE_EXECUTE_TX","data":""#));
+
+		let decoded = InterchainAccountPacketData::try_from(json.as_bytes()).unwrap();
+		assert_eq!(decoded, data);
+		assert_eq!(decoded.cosmos_tx().unwrap(), tx);
+
+		let raw = RawInterchainAccountPacketData::from(data.clone());
+		assert_eq!(InterchainAccountPacketData::try_from(raw).unwrap(), data);
+	}
+}
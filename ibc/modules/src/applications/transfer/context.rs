@@ -19,7 +19,7 @@ use crate::{
 	applications::transfer::{
 		acknowledgement::Acknowledgement,
 		events::{AckEvent, AckStatusEvent, RecvEvent, TimeoutEvent},
-		packet::PacketData,
+		packet::PacketDataV2,
 		relay::{
 			on_ack_packet::process_ack_packet, on_recv_packet::process_recv_packet,
 			on_timeout_packet::process_timeout_packet,
@@ -31,7 +31,7 @@ use crate::{
 			channel::{Counterparty, Order},
 			context::{ChannelKeeper, ChannelReader},
 			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
-			packet::Packet,
+			packet::{Packet, Sequence},
 			Version,
 		},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
@@ -45,6 +45,20 @@ pub trait Ics20Keeper:
 	ChannelKeeper + BankKeeper<AccountId = <Self as Ics20Keeper>::AccountId>
 {
 	type AccountId;
+
+	/// Stores a received packet whose tokens are forwarded to the next hop, under the key of the
+	/// packet sending them. The received packet is acknowledged once that packet completes.
+	fn store_forwarded_packet(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		packet: Packet,
+	) -> Result<(), Ics20Error>;
+
+	/// Deletes the received packet forwarded by the packet with the given key.
+	fn delete_forwarded_packet(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+	) -> Result<(), Ics20Error>;
 }
 
 pub trait Ics20Reader: ChannelReader
@@ -72,6 +86,24 @@ where
 			.map_err(|_| Ics20Error::parse_account_failure())
 	}
 
+	/// Returns the address holding the tokens received on a port and channel combination while
+	/// they are forwarded to the next hop.
+	fn get_forward_address(
+		&self,
+		port_id: &PortId,
+		channel_id: ChannelId,
+	) -> Result<Signer, Ics20Error> {
+		let hash = cosmos_adr028_forward_address(self, port_id, channel_id);
+
+		String::from_utf8(hex::encode_upper(hash))
+			.expect("hex encoded bytes are not valid UTF8")
+			.parse::<Signer>()
+			.map_err(Ics20Error::signer)
+	}
+
+	/// Returns the received packet forwarded by the packet with the given key, if any.
+	fn get_forwarded_packet(&self, key: &(PortId, ChannelId, Sequence)) -> Option<Packet>;
+
 	/// Returns true iff send is enabled.
 	fn is_send_enabled(&self) -> bool;
 
@@ -101,6 +133,22 @@ fn cosmos_adr028_escrow_address(
 	hash
 }
 
+/// Same derivation as the escrow address, under the `forward` sub-path of the channel.
+fn cosmos_adr028_forward_address(
+	ctx: &dyn ChannelReader,
+	port_id: &PortId,
+	channel_id: ChannelId,
+) -> Vec<u8> {
+	let contents = format!("forward/{}/{}", port_id, channel_id);
+	let mut data = VERSION.as_bytes().to_vec();
+	data.extend_from_slice(&[0]);
+	data.extend_from_slice(contents.as_bytes());
+
+	let mut hash = ctx.hash(data);
+	hash.truncate(20);
+	hash
+}
+
 pub trait BankKeeper {
 	type AccountId;
 
@@ -157,21 +205,39 @@ fn validate_transfer_channel_params(
 		return Err(Ics20Error::invalid_port(port_id.clone(), bound_port))
 	}
 
-	if version != &Version::ics20() {
+	if !is_supported_version(version) {
 		return Err(Ics20Error::invalid_version(version.clone()))
 	}
 
 	Ok(())
 }
 
+fn is_supported_version(version: &Version) -> bool {
+	version == &Version::ics20() || version == &Version::ics20_v2()
+}
+
 fn validate_counterparty_version(counterparty_version: &Version) -> Result<(), Ics20Error> {
-	if counterparty_version == &Version::ics20() {
+	if is_supported_version(counterparty_version) {
 		Ok(())
 	} else {
 		Err(Ics20Error::invalid_counterparty_version(counterparty_version.clone()))
 	}
 }
 
+/// Decodes the data of a packet sent or received on the given channel, according to the version
+/// of the channel.
+pub fn decode_packet_data(
+	ctx: &impl ChannelReader,
+	port_id: &PortId,
+	channel_id: ChannelId,
+	data: &[u8],
+) -> Result<PacketDataV2, Ics20Error> {
+	let channel_end = ctx
+		.channel_end(&(port_id.clone(), channel_id))
+		.map_err(Ics20Error::ics04_channel)?;
+	PacketDataV2::decode(channel_end.version(), data)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn on_chan_open_init(
 	ctx: &mut impl Ics20Context,
@@ -200,7 +266,8 @@ pub fn on_chan_open_try(
 ) -> Result<Version, Ics20Error> {
 	validate_transfer_channel_params(ctx, order, port_id, channel_id, version)?;
 	validate_counterparty_version(counterparty_version)?;
-	Ok(Version::ics20())
+	// Both versions are supported, so the version proposed by the counterparty is accepted.
+	Ok(counterparty_version.clone())
 }
 
 pub fn on_chan_open_ack(
@@ -223,6 +290,51 @@ pub fn on_chan_open_confirm(
 	Ok(())
 }
 
+/// Validates the parameters proposed by a channel upgrade. The transfer application can move
+/// between `ics20-1` and `ics20-2`, but the channel must stay unordered.
+pub fn on_chan_upgrade_init(
+	ctx: &mut impl Ics20Context,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	proposed_order: Order,
+	_proposed_connection_hops: &[ConnectionId],
+	proposed_version: &Version,
+) -> Result<Version, Ics20Error> {
+	validate_transfer_channel_params(ctx, proposed_order, port_id, channel_id, proposed_version)?;
+	Ok(proposed_version.clone())
+}
+
+pub fn on_chan_upgrade_try(
+	ctx: &mut impl Ics20Context,
+	output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	proposed_order: Order,
+	proposed_connection_hops: &[ConnectionId],
+	proposed_version: &Version,
+) -> Result<Version, Ics20Error> {
+	on_chan_upgrade_init(
+		ctx,
+		output,
+		port_id,
+		channel_id,
+		proposed_order,
+		proposed_connection_hops,
+		proposed_version,
+	)
+}
+
+pub fn on_chan_upgrade_ack(
+	_ctx: &mut impl Ics20Context,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+	counterparty_version: &Version,
+) -> Result<(), Ics20Error> {
+	validate_counterparty_version(counterparty_version)
+}
+
 pub fn on_chan_close_init(
 	_ctx: &mut impl Ics20Context,
 	_output: &mut ModuleOutputBuilder,
@@ -241,15 +353,22 @@ pub fn on_chan_close_confirm(
 	Ok(())
 }
 
+/// Returns `None` when the received tokens are forwarded to another chain, the packet is then
+/// acknowledged once the next hop has acknowledged them.
 pub fn on_recv_packet<Ctx: 'static + Ics20Context>(
 	ctx: &mut Ctx,
 	output: &mut ModuleOutputBuilder,
 	packet: &Packet,
 	_relayer: &Signer,
-) -> Acknowledgement {
-	let data = match serde_json::from_slice::<PacketData>(&packet.data) {
+) -> Option<Acknowledgement> {
+	let data = match decode_packet_data(
+		ctx,
+		&packet.destination_port,
+		packet.destination_channel,
+		&packet.data,
+	) {
 		Ok(data) => data,
-		Err(_) => return Acknowledgement::from_error(Ics20Error::packet_data_deserialization()),
+		Err(e) => return Some(Acknowledgement::from_error(e)),
 	};
 
 	log::debug!(target: "ics::transfer", "dispatch {:?}",&data);
@@ -258,15 +377,21 @@ pub fn on_recv_packet<Ctx: 'static + Ics20Context>(
 		Err(e) => Acknowledgement::from_error(e),
 	};
 
-	let recv_event = RecvEvent {
-		receiver: data.receiver,
-		denom: data.token.denom,
-		amount: data.token.amount,
-		success: ack.is_successful(),
-	};
-	output.emit(recv_event.into());
+	for token in &data.tokens {
+		let recv_event = RecvEvent {
+			receiver: data.receiver.clone(),
+			denom: token.denom.clone(),
+			amount: token.amount,
+			success: ack.is_successful(),
+		};
+		output.emit(recv_event.into());
+	}
 
-	ack
+	if ack.is_successful() && data.should_forward() {
+		None
+	} else {
+		Some(ack)
+	}
 }
 
 pub fn on_acknowledgement_packet(
@@ -276,21 +401,22 @@ pub fn on_acknowledgement_packet(
 	acknowledgement: &GenericAcknowledgement,
 	_relayer: &Signer,
 ) -> Result<(), Ics20Error> {
-	let data = serde_json::from_slice::<PacketData>(&packet.data)
-		.map_err(|_| Ics20Error::packet_data_deserialization())?;
+	let data = decode_packet_data(ctx, &packet.source_port, packet.source_channel, &packet.data)?;
 
 	let acknowledgement = serde_json::from_slice::<Acknowledgement>(acknowledgement.as_ref())
 		.map_err(|_| Ics20Error::ack_deserialization())?;
 
-	process_ack_packet(ctx, packet, &data, &acknowledgement)?;
+	process_ack_packet(ctx, output, packet, &data, &acknowledgement)?;
 
-	let ack_event = AckEvent {
-		receiver: data.receiver,
-		denom: data.token.denom,
-		amount: data.token.amount,
-		acknowledgement: acknowledgement.clone(),
-	};
-	output.emit(ack_event.into());
+	for token in data.tokens {
+		let ack_event = AckEvent {
+			receiver: data.receiver.clone(),
+			denom: token.denom,
+			amount: token.amount,
+			acknowledgement: acknowledgement.clone(),
+		};
+		output.emit(ack_event.into());
+	}
 	output.emit(AckStatusEvent { acknowledgement }.into());
 
 	Ok(())
@@ -302,17 +428,18 @@ pub fn on_timeout_packet(
 	packet: &Packet,
 	_relayer: &Signer,
 ) -> Result<(), Ics20Error> {
-	let data = serde_json::from_slice::<PacketData>(&packet.data)
-		.map_err(|_| Ics20Error::packet_data_deserialization())?;
+	let data = decode_packet_data(ctx, &packet.source_port, packet.source_channel, &packet.data)?;
 
-	process_timeout_packet(ctx, packet, &data)?;
+	process_timeout_packet(ctx, output, packet, &data)?;
 
-	let timeout_event = TimeoutEvent {
-		refund_receiver: data.sender,
-		refund_denom: data.token.denom,
-		refund_amount: data.token.amount,
-	};
-	output.emit(timeout_event.into());
+	for token in data.tokens {
+		let timeout_event = TimeoutEvent {
+			refund_receiver: data.sender.clone(),
+			refund_denom: token.denom,
+			refund_amount: token.amount,
+		};
+		output.emit(timeout_event.into());
+	}
 
 	Ok(())
 }
@@ -17,7 +17,9 @@ use core::{fmt, str::FromStr};
 use derive_more::{Display, From, Into};
 use ibc_proto::{
 	cosmos::base::v1beta1::Coin as RawCoin,
-	ibc::applications::transfer::v1::DenomTrace as RawDenomTrace,
+	ibc::applications::transfer::v1::{
+		Denom as RawDenom, DenomTrace as RawDenomTrace, Hop as RawHop, Token as RawToken,
+	},
 };
 use serde::{Deserialize, Serialize};
 
@@ -68,6 +70,32 @@ impl TracePrefix {
 	pub fn new(port_id: PortId, channel_id: ChannelId) -> Self {
 		Self { port_id, channel_id }
 	}
+
+	pub fn port_id(&self) -> &PortId {
+		&self.port_id
+	}
+
+	pub fn channel_id(&self) -> ChannelId {
+		self.channel_id
+	}
+}
+
+impl TryFrom<RawHop> for TracePrefix {
+	type Error = Error;
+
+	fn try_from(value: RawHop) -> Result<Self, Self::Error> {
+		let port_id = PortId::from_str(&value.port_id)
+			.map_err(|e| Error::invalid_port_id(value.port_id.clone(), e))?;
+		let channel_id = ChannelId::from_str(&value.channel_id)
+			.map_err(|e| Error::invalid_channel_id(value.channel_id.clone(), e))?;
+		Ok(Self { port_id, channel_id })
+	}
+}
+
+impl From<TracePrefix> for RawHop {
+	fn from(value: TracePrefix) -> Self {
+		Self { port_id: value.port_id.to_string(), channel_id: value.channel_id.to_string() }
+	}
 }
 
 impl fmt::Display for TracePrefix {
@@ -105,6 +133,11 @@ impl TracePath {
 	pub fn is_empty(&self) -> bool {
 		self.0.is_empty()
 	}
+
+	/// Returns the prefixes of this path, starting with the most recently added one.
+	pub fn prefixes(&self) -> impl Iterator<Item = &TracePrefix> {
+		self.0.iter().rev()
+	}
 }
 
 impl<'a> TryFrom<Vec<&'a str>> for TracePath {
@@ -284,6 +317,32 @@ impl From<PrefixedDenom> for RawDenomTrace {
 	}
 }
 
+impl TryFrom<RawDenom> for PrefixedDenom {
+	type Error = Error;
+
+	fn try_from(value: RawDenom) -> Result<Self, Self::Error> {
+		let base_denom = BaseDenom::from_str(&value.base)?;
+		// `RawDenom` lists the hops starting with the most recent one, which is the reverse of the
+		// internal `TracePath` ordering.
+		let trace = value
+			.trace
+			.into_iter()
+			.rev()
+			.map(TracePrefix::try_from)
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(Self { trace_path: trace.into(), base_denom })
+	}
+}
+
+impl From<PrefixedDenom> for RawDenom {
+	fn from(value: PrefixedDenom) -> Self {
+		Self {
+			base: value.base_denom.to_string(),
+			trace: value.trace_path.0.into_iter().rev().map(Into::into).collect(),
+		}
+	}
+}
+
 impl From<BaseDenom> for PrefixedDenom {
 	fn from(denom: BaseDenom) -> Self {
 		Self { trace_path: Default::default(), base_denom: denom }
@@ -380,6 +439,22 @@ impl<D: ToString> From<Coin<D>> for RawCoin {
 	}
 }
 
+impl TryFrom<RawToken> for PrefixedCoin {
+	type Error = Error;
+
+	fn try_from(proto: RawToken) -> Result<Self, Self::Error> {
+		let denom = PrefixedDenom::try_from(proto.denom.ok_or_else(Error::invalid_token)?)?;
+		let amount = Amount::from_str(&proto.amount)?;
+		Ok(Self { denom, amount })
+	}
+}
+
+impl From<PrefixedCoin> for RawToken {
+	fn from(coin: PrefixedCoin) -> RawToken {
+		RawToken { denom: Some(coin.denom.into()), amount: coin.amount.to_string() }
+	}
+}
+
 impl From<BaseCoin> for PrefixedCoin {
 	fn from(coin: BaseCoin) -> PrefixedCoin {
 		PrefixedCoin { denom: coin.denom.into(), amount: coin.amount }
@@ -493,6 +568,26 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_raw_denom_conversion() -> Result<(), Error> {
+		let denom = PrefixedDenom::from_str("transfer/channel-0/transfer/channel-1/uatom")?;
+		let raw = RawDenom::from(denom.clone());
+		assert_eq!(raw.base, "uatom");
+		assert_eq!(
+			raw.trace,
+			vec![
+				RawHop { port_id: "transfer".to_string(), channel_id: "channel-0".to_string() },
+				RawHop { port_id: "transfer".to_string(), channel_id: "channel-1".to_string() },
+			]
+		);
+		assert_eq!(PrefixedDenom::try_from(raw)?, denom);
+
+		let hops = denom.trace_path().prefixes().map(ToString::to_string).collect::<Vec<_>>();
+		assert_eq!(hops, vec!["transfer/channel-0", "transfer/channel-1"]);
+
+		Ok(())
+	}
+
 	#[test]
 	fn packet_data_serde() {
 		let packet_data = PacketData {
@@ -119,11 +119,26 @@ define_error! {
 
 		InvalidVersion
 			{ version: Version }
-			| e | { format_args!("expected version '{0}', got '{1}'", Version::ics20(), e.version) },
+			| e | { format_args!("expected version '{0}' or '{1}', got '{2}'", Version::ics20(), Version::ics20_v2(), e.version) },
 
 		InvalidCounterpartyVersion
 			{ version: Version }
-			| e | { format_args!("expected counterparty version '{0}', got '{1}'", Version::ics20(), e.version) },
+			| e | { format_args!("expected counterparty version '{0}' or '{1}', got '{2}'", Version::ics20(), Version::ics20_v2(), e.version) },
+
+		EmptyTokens
+			| _ | { "at least one token must be transferred" },
+
+		UnsupportedByVersion
+			{ version: Version }
+			| e | { format_args!("multiple tokens and forwarding require channel version '{0}', got '{1}'", Version::ics20_v2(), e.version) },
+
+		InvalidForwarding
+			{ reason: String }
+			| e | { format_args!("invalid forwarding: {0}", e.reason) },
+
+		ForwardingFailed
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | { format_args!("forwarding the received tokens over {0}/{1} failed", e.port_id, e.channel_id) },
 
 		CantCloseChannel
 			| _ | { "channel cannot be closed" },
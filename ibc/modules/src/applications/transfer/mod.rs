@@ -35,3 +35,6 @@ pub const PORT_ID_STR: &str = "transfer";
 
 /// ICS20 application current version.
 pub const VERSION: &str = "ics20-1";
+
+/// ICS20 application version supporting multiple tokens per packet and forwarding.
+pub const VERSION_V2: &str = "ics20-2";
@@ -18,13 +18,14 @@ use crate::prelude::*;
 use core::fmt::Display;
 
 use ibc_proto::{
-	cosmos::base::v1beta1::Coin, google::protobuf::Any,
-	ibc::applications::transfer::v1::MsgTransfer as RawMsgTransfer,
+	cosmos::base::v1beta1::Coin,
+	google::protobuf::Any,
+	ibc::applications::transfer::v1::{Forwarding as RawForwarding, MsgTransfer as RawMsgTransfer},
 };
 use tendermint_proto::Protobuf;
 
 use crate::{
-	applications::transfer::error::Error,
+	applications::transfer::{error::Error, TracePrefix},
 	core::{
 		ics02_client::height::Height,
 		ics24_host::identifier::{ChannelId, PortId},
@@ -63,6 +64,41 @@ pub struct MsgTransfer<C = Coin> {
 	pub timeout_timestamp: Timestamp,
 	/// Memo field
 	pub memo: String,
+	/// Further tokens sent in the same packet, only supported on `ics20-2` channels
+	pub additional_tokens: Vec<C>,
+	/// Optional forwarding of the tokens past the destination chain, only supported on `ics20-2`
+	/// channels
+	pub forwarding: Option<Forwarding>,
+}
+
+/// Path through which the transferred tokens are forwarded past the destination chain.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Forwarding {
+	/// Whether the tokens are first sent back through the channels they arrived from, up to the
+	/// chain they originate from.
+	pub unwind: bool,
+	/// Hops through which the tokens are forwarded, after unwinding if requested.
+	pub hops: Vec<TracePrefix>,
+}
+
+impl TryFrom<RawForwarding> for Forwarding {
+	type Error = Error;
+
+	fn try_from(raw: RawForwarding) -> Result<Self, Self::Error> {
+		Ok(Self {
+			unwind: raw.unwind,
+			hops: raw.hops.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+		})
+	}
+}
+
+impl From<Forwarding> for RawForwarding {
+	fn from(forwarding: Forwarding) -> Self {
+		Self {
+			unwind: forwarding.unwind,
+			hops: forwarding.hops.into_iter().map(Into::into).collect(),
+		}
+	}
 }
 
 impl<C> Msg for MsgTransfer<C>
@@ -99,6 +135,12 @@ where
 			})?,
 		};
 
+		// `tokens` supersedes `token` when set, the first token is kept apart so that single token
+		// transfers stay unchanged.
+		let mut tokens = raw_msg.token.into_iter().chain(raw_msg.tokens).map(C::try_from);
+		let token = tokens.next().ok_or_else(Error::invalid_token)??;
+		let additional_tokens = tokens.collect::<Result<Vec<_>, _>>()?;
+
 		Ok(MsgTransfer {
 			source_port: raw_msg
 				.source_port
@@ -108,12 +150,14 @@ where
 				.source_channel
 				.parse()
 				.map_err(|e| Error::invalid_channel_id(raw_msg.source_channel.clone(), e))?,
-			token: C::try_from(raw_msg.token.ok_or_else(Error::invalid_token)?)?,
+			token,
 			sender: raw_msg.sender.parse().map_err(Error::signer)?,
 			receiver: raw_msg.receiver.parse().map_err(Error::signer)?,
 			timeout_height,
 			timeout_timestamp,
 			memo: raw_msg.memo,
+			additional_tokens,
+			forwarding: raw_msg.forwarding.map(TryInto::try_into).transpose()?,
 		})
 	}
 }
@@ -123,15 +167,26 @@ where
 	Coin: From<C>,
 {
 	fn from(domain_msg: MsgTransfer<C>) -> Self {
+		let (token, tokens) = if domain_msg.additional_tokens.is_empty() {
+			(Some(domain_msg.token.into()), vec![])
+		} else {
+			let tokens = core::iter::once(domain_msg.token)
+				.chain(domain_msg.additional_tokens)
+				.map(Into::into)
+				.collect();
+			(None, tokens)
+		};
 		RawMsgTransfer {
 			source_port: domain_msg.source_port.to_string(),
 			source_channel: domain_msg.source_channel.to_string(),
-			token: Some(domain_msg.token.into()),
+			token,
 			sender: domain_msg.sender.to_string(),
 			receiver: domain_msg.receiver.to_string(),
 			timeout_height: Some(domain_msg.timeout_height.into()),
 			timeout_timestamp: domain_msg.timeout_timestamp.nanoseconds(),
 			memo: domain_msg.memo,
+			tokens,
+			forwarding: domain_msg.forwarding.map(Into::into),
 		}
 	}
 }
@@ -210,6 +265,8 @@ pub mod test_util {
 			timeout_timestamp: Timestamp::now().add(Duration::from_secs(10)).unwrap(),
 			timeout_height: Height { revision_number: 0, revision_height: height },
 			memo: "".to_string(),
+			additional_tokens: vec![],
+			forwarding: None,
 		}
 	}
 }
@@ -15,11 +15,15 @@
 use alloc::string::{String, ToString};
 use core::{convert::TryFrom, str::FromStr};
 
-use ibc_proto::ibc::applications::transfer::v2::FungibleTokenPacketData as RawPacketData;
+use ibc_proto::ibc::applications::transfer::v2::{
+	ForwardingPacketData as RawForwardingPacketData, FungibleTokenPacketData as RawPacketData,
+	FungibleTokenPacketDataV2 as RawPacketDataV2,
+};
+use prost::Message;
 use serde::{Deserialize, Serialize};
 
-use super::{error::Error, Amount, PrefixedCoin, PrefixedDenom};
-use crate::signer::Signer;
+use super::{error::Error, Amount, PrefixedCoin, PrefixedDenom, TracePrefix};
+use crate::{core::ics04_channel::Version, prelude::*, signer::Signer};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PacketData {
@@ -58,3 +62,194 @@ impl From<PacketData> for RawPacketData {
 		}
 	}
 }
+
+/// Forwarding information carried by `ics20-2` packets.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ForwardingPacketData {
+	/// Memo passed on to the final destination of the tokens.
+	pub destination_memo: String,
+	/// Intermediate hops through which the tokens are forwarded once received.
+	pub hops: Vec<TracePrefix>,
+}
+
+impl TryFrom<RawForwardingPacketData> for ForwardingPacketData {
+	type Error = Error;
+
+	fn try_from(raw: RawForwardingPacketData) -> Result<Self, Self::Error> {
+		Ok(Self {
+			destination_memo: raw.destination_memo,
+			hops: raw.hops.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+		})
+	}
+}
+
+impl From<ForwardingPacketData> for RawForwardingPacketData {
+	fn from(forwarding: ForwardingPacketData) -> Self {
+		Self {
+			destination_memo: forwarding.destination_memo,
+			hops: forwarding.hops.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Packet data of `ics20-2` channels, which may carry several tokens and forwarding information.
+///
+/// Packets received over `ics20-1` channels are converted into this type as well, so that the
+/// relay logic only deals with a single packet data representation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PacketDataV2 {
+	pub tokens: Vec<PrefixedCoin>,
+	pub sender: Signer,
+	pub receiver: Signer,
+	pub memo: String,
+	pub forwarding: ForwardingPacketData,
+}
+
+impl PacketDataV2 {
+	/// Decodes the packet data sent over a channel of the given version. `ics20-2` packets are
+	/// protobuf encoded, `ics20-1` packets are JSON encoded.
+	pub fn decode(version: &Version, data: &[u8]) -> Result<Self, Error> {
+		if version == &Version::ics20_v2() {
+			RawPacketDataV2::decode(data)
+				.map_err(|_| Error::packet_data_deserialization())?
+				.try_into()
+		} else {
+			serde_json::from_slice::<PacketData>(data)
+				.map(Into::into)
+				.map_err(|_| Error::packet_data_deserialization())
+		}
+	}
+
+	/// Encodes the packet data for a channel of the given version. Only a single token and no
+	/// forwarding information can be sent over `ics20-1` channels.
+	pub fn encode(self, version: &Version) -> Result<Vec<u8>, Error> {
+		if version == &Version::ics20_v2() {
+			Ok(RawPacketDataV2::from(self).encode_to_vec())
+		} else {
+			let data = PacketData::try_from(self)
+				.map_err(|_| Error::unsupported_by_version(version.clone()))?;
+			Ok(serde_json::to_vec(&data).expect("PacketData's infallible Serialize impl failed"))
+		}
+	}
+
+	/// Returns true if the tokens must be forwarded to another chain once received.
+	pub fn should_forward(&self) -> bool {
+		!self.forwarding.hops.is_empty()
+	}
+}
+
+impl From<PacketData> for PacketDataV2 {
+	fn from(data: PacketData) -> Self {
+		Self {
+			tokens: vec![data.token],
+			sender: data.sender,
+			receiver: data.receiver,
+			memo: data.memo,
+			forwarding: Default::default(),
+		}
+	}
+}
+
+impl TryFrom<PacketDataV2> for PacketData {
+	type Error = Error;
+
+	fn try_from(data: PacketDataV2) -> Result<Self, Self::Error> {
+		if data.should_forward() {
+			return Err(Error::invalid_forwarding(
+				"forwarding is not supported by ics20-1 packets".to_string(),
+			))
+		}
+		let mut tokens = data.tokens.into_iter();
+		match (tokens.next(), tokens.next()) {
+			(Some(token), None) =>
+				Ok(Self { token, sender: data.sender, receiver: data.receiver, memo: data.memo }),
+			_ => Err(Error::invalid_token()),
+		}
+	}
+}
+
+impl TryFrom<RawPacketDataV2> for PacketDataV2 {
+	type Error = Error;
+
+	fn try_from(raw_pkt_data: RawPacketDataV2) -> Result<Self, Self::Error> {
+		if raw_pkt_data.tokens.is_empty() {
+			return Err(Error::empty_tokens())
+		}
+		Ok(Self {
+			tokens: raw_pkt_data
+				.tokens
+				.into_iter()
+				.map(TryInto::try_into)
+				.collect::<Result<_, _>>()?,
+			sender: raw_pkt_data.sender.parse().map_err(Error::signer)?,
+			receiver: raw_pkt_data.receiver.parse().map_err(Error::signer)?,
+			memo: raw_pkt_data.memo,
+			forwarding: raw_pkt_data
+				.forwarding
+				.map(TryInto::try_into)
+				.transpose()?
+				.unwrap_or_default(),
+		})
+	}
+}
+
+impl From<PacketDataV2> for RawPacketDataV2 {
+	fn from(pkt_data: PacketDataV2) -> Self {
+		Self {
+			tokens: pkt_data.tokens.into_iter().map(Into::into).collect(),
+			sender: pkt_data.sender.to_string(),
+			receiver: pkt_data.receiver.to_string(),
+			memo: pkt_data.memo,
+			forwarding: Some(pkt_data.forwarding.into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn dummy_packet_data() -> PacketDataV2 {
+		PacketDataV2 {
+			tokens: vec![
+				PrefixedCoin {
+					denom: "transfer/channel-0/uatom".parse().unwrap(),
+					amount: 100u64.into(),
+				},
+				PrefixedCoin { denom: "stake".parse().unwrap(), amount: 7u64.into() },
+			],
+			sender: "sender".parse().unwrap(),
+			receiver: "receiver".parse().unwrap(),
+			memo: "".to_string(),
+			forwarding: ForwardingPacketData {
+				destination_memo: "memo".to_string(),
+				hops: vec![TracePrefix::new(
+					"transfer".parse().unwrap(),
+					"channel-5".parse().unwrap(),
+				)],
+			},
+		}
+	}
+
+	#[test]
+	fn packet_data_v2_roundtrip() {
+		let data = dummy_packet_data();
+		let bytes = data.clone().encode(&Version::ics20_v2()).unwrap();
+		assert_eq!(PacketDataV2::decode(&Version::ics20_v2(), &bytes).unwrap(), data);
+	}
+
+	#[test]
+	fn packet_data_v2_requires_v2_channel_for_multiple_tokens() {
+		let data = dummy_packet_data();
+		assert!(data.encode(&Version::ics20()).is_err());
+
+		let data = PacketDataV2 {
+			tokens: vec![PrefixedCoin { denom: "stake".parse().unwrap(), amount: 7u64.into() }],
+			forwarding: Default::default(),
+			..dummy_packet_data()
+		};
+		let bytes = data.clone().encode(&Version::ics20()).unwrap();
+		assert!(serde_json::from_slice::<PacketData>(&bytes).is_ok());
+		assert_eq!(PacketDataV2::decode(&Version::ics20(), &bytes).unwrap(), data);
+	}
+}
@@ -15,10 +15,18 @@
 //! This module implements the processing logic for ICS20 (token transfer) message.
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, is_sender_chain_source,
-		packet::PacketData,
+		acknowledgement::Acknowledgement,
+		context::{decode_packet_data, Ics20Context},
+		error::Error as Ics20Error,
+		is_receiver_chain_source, is_sender_chain_source,
+		packet::PacketDataV2,
+		PrefixedCoin, TracePrefix,
 	},
-	core::ics04_channel::packet::Packet,
+	core::{
+		ics04_channel::{handler::write_acknowledgement, packet::Packet},
+		ics26_routing::context::ModuleOutputBuilder,
+	},
+	handler::HandlerOutput,
 	prelude::*,
 };
 
@@ -30,7 +38,7 @@ pub mod send_transfer;
 fn refund_packet_token(
 	ctx: &mut impl Ics20Context,
 	packet: &Packet,
-	data: &PacketData,
+	data: &PacketDataV2,
 ) -> Result<(), Ics20Error> {
 	let sender = data
 		.sender
@@ -38,16 +46,102 @@ fn refund_packet_token(
 		.try_into()
 		.map_err(|_| Ics20Error::parse_account_failure())?;
 
-	if is_sender_chain_source(packet.source_port.clone(), packet.source_channel, &data.token.denom)
-	{
-		// unescrow tokens back to sender
-		let escrow_address =
-			ctx.get_channel_escrow_address(&packet.source_port, packet.source_channel)?;
+	for token in &data.tokens {
+		if is_sender_chain_source(packet.source_port.clone(), packet.source_channel, &token.denom) {
+			// unescrow tokens back to sender
+			let escrow_address =
+				ctx.get_channel_escrow_address(&packet.source_port, packet.source_channel)?;
 
-		ctx.send_coins(&escrow_address, &sender, &data.token)
+			ctx.send_coins(&escrow_address, &sender, token)?;
+		}
+		// mint vouchers back to sender
+		else {
+			ctx.mint_coins(&sender, token)?;
+		}
 	}
-	// mint vouchers back to sender
-	else {
-		ctx.mint_coins(&sender, &data.token)
+
+	Ok(())
+}
+
+/// Returns the coin credited on this chain for a token received in `packet`, and whether it is
+/// unescrowed, rather than minted as a voucher, because this chain is the source of the token.
+fn received_coin(packet: &Packet, mut token: PrefixedCoin) -> (PrefixedCoin, bool) {
+	if is_receiver_chain_source(packet.source_port.clone(), packet.source_channel, &token.denom) {
+		let prefix = TracePrefix::new(packet.source_port.clone(), packet.source_channel);
+		token.denom.remove_trace_prefix(&prefix);
+		(token, true)
+	} else {
+		let prefix = TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
+		token.denom.add_trace_prefix(prefix);
+		(token, false)
 	}
 }
+
+/// Acknowledges the received packet whose tokens were forwarded by `packet`, if any. When the
+/// forwarded tokens were refunded, the receive is reverted first so that the acknowledgement
+/// error refunds the original sender.
+fn complete_forward(
+	ctx: &mut impl Ics20Context,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	success: bool,
+) -> Result<(), Ics20Error> {
+	let key = (packet.source_port.clone(), packet.source_channel, packet.sequence);
+	let received = match ctx.get_forwarded_packet(&key) {
+		Some(received) => received,
+		None => return Ok(()),
+	};
+	ctx.delete_forwarded_packet(key)?;
+
+	let ack = if success {
+		Acknowledgement::success()
+	} else {
+		revert_forwarded_receive(ctx, &received)?;
+		Acknowledgement::from_error(Ics20Error::forwarding_failed(
+			packet.source_port.clone(),
+			packet.source_channel,
+		))
+	};
+
+	let HandlerOutput { result, log, events } =
+		write_acknowledgement::process(ctx, received, ack.to_string().into_bytes())
+			.map_err(Ics20Error::ics04_channel)?;
+	ctx.store_packet_result(result).map_err(Ics20Error::ics04_channel)?;
+	output.merge_output(HandlerOutput::builder().with_log(log).with_events(events).with_result(()));
+
+	Ok(())
+}
+
+/// Takes the tokens of a received packet back from the forward address, which holds them again
+/// once the forwarded packet has been refunded: native tokens are escrowed again and vouchers
+/// are burnt.
+fn revert_forwarded_receive(
+	ctx: &mut impl Ics20Context,
+	received: &Packet,
+) -> Result<(), Ics20Error> {
+	let data = decode_packet_data(
+		ctx,
+		&received.destination_port,
+		received.destination_channel,
+		&received.data,
+	)?;
+	let forwarder = ctx
+		.get_forward_address(&received.destination_port, received.destination_channel)?
+		.try_into()
+		.map_err(|_| Ics20Error::parse_account_failure())?;
+
+	for token in data.tokens {
+		match received_coin(received, token) {
+			(coin, true) => {
+				let escrow_address = ctx.get_channel_escrow_address(
+					&received.destination_port,
+					received.destination_channel,
+				)?;
+				ctx.send_coins(&forwarder, &escrow_address, &coin)?;
+			},
+			(coin, false) => ctx.burn_coins(&forwarder, &coin)?,
+		}
+	}
+
+	Ok(())
+}
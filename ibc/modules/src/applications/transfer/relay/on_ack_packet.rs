@@ -14,20 +14,24 @@
 
 use crate::{
 	applications::transfer::{
-		acknowledgement::Acknowledgement, context::Ics20Context, error::Error as Ics20Error,
-		packet::PacketData, relay::refund_packet_token,
+		acknowledgement::Acknowledgement,
+		context::Ics20Context,
+		error::Error as Ics20Error,
+		packet::PacketDataV2,
+		relay::{complete_forward, refund_packet_token},
 	},
-	core::ics04_channel::packet::Packet,
+	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
 };
 
 pub fn process_ack_packet(
 	ctx: &mut impl Ics20Context,
+	output: &mut ModuleOutputBuilder,
 	packet: &Packet,
-	data: &PacketData,
+	data: &PacketDataV2,
 	ack: &Acknowledgement,
 ) -> Result<(), Ics20Error> {
 	if !ack.is_successful() {
 		refund_packet_token(ctx, packet, data)?;
 	}
-	Ok(())
+	complete_forward(ctx, output, packet, ack.is_successful())
 }
@@ -12,63 +12,121 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use core::time::Duration;
+
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, events::DenomTraceEvent,
-		is_receiver_chain_source, packet::PacketData, TracePrefix,
+		context::Ics20Context,
+		error::Error as Ics20Error,
+		events::DenomTraceEvent,
+		msgs::transfer::{Forwarding, MsgTransfer},
+		packet::PacketDataV2,
+		relay::{received_coin, send_transfer::send_transfer},
+		PrefixedCoin,
+	},
+	core::{
+		ics02_client::height::Height, ics04_channel::packet::Packet,
+		ics26_routing::context::ModuleOutputBuilder,
 	},
-	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
 	prelude::*,
+	signer::Signer,
 };
 
+/// Timeout of the packets forwarding received tokens to the next hop, from the host timestamp.
+pub const FORWARDING_PACKET_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Credits the tokens of a received packet. Tokens to be forwarded are credited to the forward
+/// address of the channel and sent to the next hop, the received packet must then be
+/// acknowledged once the next hop has acknowledged them.
 pub fn process_recv_packet<Ctx: 'static + Ics20Context>(
 	ctx: &mut Ctx,
 	output: &mut ModuleOutputBuilder,
 	packet: &Packet,
-	data: PacketData,
+	data: PacketDataV2,
 ) -> Result<(), Ics20Error> {
 	if !ctx.is_receive_enabled() {
 		return Err(Ics20Error::receive_disabled())
 	}
 
-	let receiver_account = data
-		.receiver
-		.clone()
-		.try_into()
-		.map_err(|_| Ics20Error::parse_account_failure())?;
+	let receiver = if data.should_forward() {
+		ctx.get_forward_address(&packet.destination_port, packet.destination_channel)?
+	} else {
+		data.receiver.clone()
+	};
+	let receiver_account =
+		receiver.clone().try_into().map_err(|_| Ics20Error::parse_account_failure())?;
 
-	if is_receiver_chain_source(
-		packet.source_port.clone(),
-		packet.source_channel,
-		&data.token.denom,
-	) {
-		// sender chain is not the source, unescrow tokens
-		let prefix = TracePrefix::new(packet.source_port.clone(), packet.source_channel);
-		let coin = {
-			let mut c = data.token;
-			c.denom.remove_trace_prefix(&prefix);
-			c
-		};
+	let mut received = Vec::with_capacity(data.tokens.len());
+	for token in data.tokens.iter().cloned() {
+		match received_coin(packet, token) {
+			(coin, true) => {
+				// sender chain is not the source, unescrow tokens
+				let escrow_address = ctx.get_channel_escrow_address(
+					&packet.destination_port,
+					packet.destination_channel,
+				)?;
 
-		let escrow_address =
-			ctx.get_channel_escrow_address(&packet.destination_port, packet.destination_channel)?;
+				ctx.send_coins(&escrow_address, &receiver_account, &coin)?;
+				received.push(coin);
+			},
+			(coin, false) => {
+				// sender chain is the source, mint vouchers
+				let denom_trace_event = DenomTraceEvent {
+					trace_hash: ctx.denom_hash_string(&coin.denom),
+					denom: coin.denom.clone(),
+				};
+				output.emit(denom_trace_event.into());
 
-		ctx.send_coins(&escrow_address, &receiver_account, &coin)
-	} else {
-		// sender chain is the source, mint vouchers
-		let prefix = TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
-		let coin = {
-			let mut c = data.token;
-			c.denom.add_trace_prefix(prefix);
-			c
-		};
-
-		let denom_trace_event = DenomTraceEvent {
-			trace_hash: ctx.denom_hash_string(&coin.denom),
-			denom: coin.denom.clone(),
-		};
-		output.emit(denom_trace_event.into());
+				ctx.mint_coins(&receiver_account, &coin)?;
+				received.push(coin);
+			},
+		}
+	}
 
-		ctx.mint_coins(&receiver_account, &coin)
+	if data.should_forward() {
+		forward_received_tokens(ctx, output, packet, data, receiver, received)?;
 	}
+
+	Ok(())
+}
+
+/// Sends the tokens received in `packet` from the forward address to the next hop, which
+/// receives the remaining hops. The memo of the transfer is delivered to the final destination.
+fn forward_received_tokens<Ctx: Ics20Context>(
+	ctx: &mut Ctx,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	data: PacketDataV2,
+	forwarder: Signer,
+	tokens: Vec<PrefixedCoin>,
+) -> Result<(), Ics20Error> {
+	let mut hops = data.forwarding.hops.into_iter();
+	let next_hop = hops.next().ok_or_else(|| {
+		Ics20Error::invalid_forwarding("no hop to forward the tokens to".to_string())
+	})?;
+	let hops = hops.collect::<Vec<_>>();
+
+	let key = (next_hop.port_id().clone(), next_hop.channel_id());
+	let sequence = ctx.get_next_sequence_send(&key).map_err(Ics20Error::ics04_channel)?;
+	let host_timestamp = ctx.host_timestamp();
+	let timeout_timestamp = (host_timestamp + FORWARDING_PACKET_TIMEOUT)
+		.map_err(|_| Ics20Error::invalid_packet_timeout_timestamp(host_timestamp.nanoseconds()))?;
+
+	let mut tokens = tokens.into_iter();
+	let token = tokens.next().ok_or_else(Ics20Error::empty_tokens)?;
+	let msg = MsgTransfer {
+		source_port: key.0.clone(),
+		source_channel: key.1,
+		token,
+		sender: forwarder,
+		receiver: data.receiver,
+		timeout_height: Height::zero(),
+		timeout_timestamp,
+		memo: data.forwarding.destination_memo,
+		additional_tokens: tokens.collect(),
+		forwarding: (!hops.is_empty()).then(|| Forwarding { unwind: false, hops }),
+	};
+	send_transfer(ctx, output, msg)?;
+
+	ctx.store_forwarded_packet((key.0, key.1, sequence), packet.clone())
 }
@@ -14,16 +14,20 @@
 
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, packet::PacketData,
-		relay::refund_packet_token,
+		context::Ics20Context,
+		error::Error as Ics20Error,
+		packet::PacketDataV2,
+		relay::{complete_forward, refund_packet_token},
 	},
-	core::ics04_channel::packet::Packet,
+	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
 };
 
 pub fn process_timeout_packet(
 	ctx: &mut impl Ics20Context,
+	output: &mut ModuleOutputBuilder,
 	packet: &Packet,
-	data: &PacketData,
+	data: &PacketDataV2,
 ) -> Result<(), Ics20Error> {
-	refund_packet_token(ctx, packet, data)
+	refund_packet_token(ctx, packet, data)?;
+	complete_forward(ctx, output, packet, false)
 }
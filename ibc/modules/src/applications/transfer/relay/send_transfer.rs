@@ -14,10 +14,18 @@
 
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error, events::TransferEvent, is_sender_chain_source,
-		msgs::transfer::MsgTransfer, packet::PacketData, Coin, PrefixedCoin,
+		context::Ics20Context,
+		error::Error,
+		events::TransferEvent,
+		is_sender_chain_source,
+		msgs::transfer::{Forwarding, MsgTransfer},
+		packet::{ForwardingPacketData, PacketDataV2},
+		Coin, PrefixedCoin, TracePrefix,
+	},
+	core::{
+		ics04_channel::{handler::send_packet::send_packet, packet::Packet},
+		ics24_host::identifier::{ChannelId, PortId},
 	},
-	core::ics04_channel::{handler::send_packet::send_packet, packet::Packet},
 	handler::{HandlerOutput, HandlerOutputBuilder},
 	prelude::*,
 };
@@ -52,29 +60,45 @@ where
 		.get_next_sequence_send(&(msg.source_port.clone(), msg.source_channel))
 		.map_err(Error::ics04_channel)?;
 
-	let token = msg.token.try_into().map_err(|_| Error::invalid_token())?;
-	let denom = token.denom.clone();
-	let coin = Coin { denom: denom.clone(), amount: token.amount };
+	let tokens = core::iter::once(msg.token)
+		.chain(msg.additional_tokens)
+		.map(|token| token.try_into().map_err(|_| Error::invalid_token()))
+		.collect::<Result<Vec<PrefixedCoin>, _>>()?;
+
+	let hops = match msg.forwarding {
+		Some(forwarding) =>
+			forwarding_hops(&msg.source_port, msg.source_channel, &tokens, forwarding)?,
+		None => vec![],
+	};
 
 	let sender = msg.sender.clone().try_into().map_err(|_| Error::parse_account_failure())?;
 
-	if is_sender_chain_source(msg.source_port.clone(), msg.source_channel, &denom) {
-		let escrow_address =
-			ctx.get_channel_escrow_address(&msg.source_port, msg.source_channel)?;
-		ctx.send_coins(&sender, &escrow_address, &coin)?;
-	} else {
-		ctx.burn_coins(&sender, &coin)?;
+	for token in &tokens {
+		let denom = token.denom.clone();
+		let coin = Coin { denom: denom.clone(), amount: token.amount };
+		if is_sender_chain_source(msg.source_port.clone(), msg.source_channel, &denom) {
+			let escrow_address =
+				ctx.get_channel_escrow_address(&msg.source_port, msg.source_channel)?;
+			ctx.send_coins(&sender, &escrow_address, &coin)?;
+		} else {
+			ctx.burn_coins(&sender, &coin)?;
+		}
 	}
 
-	let data = {
-		let data = PacketData {
-			token: coin,
-			sender: msg.sender.clone(),
-			receiver: msg.receiver.clone(),
-			memo: msg.memo,
-		};
-		serde_json::to_vec(&data).expect("PacketData's infallible Serialize impl failed")
+	// The memo is meant for the final destination when the tokens are forwarded.
+	let (memo, forwarding) = if hops.is_empty() {
+		(msg.memo, ForwardingPacketData::default())
+	} else {
+		(String::new(), ForwardingPacketData { destination_memo: msg.memo, hops })
 	};
+	let data = PacketDataV2 {
+		tokens: tokens.clone(),
+		sender: msg.sender.clone(),
+		receiver: msg.receiver.clone(),
+		memo,
+		forwarding,
+	}
+	.encode(source_channel_end.version())?;
 
 	let packet = Packet {
 		sequence,
@@ -94,13 +118,84 @@ where
 
 	output.merge_output(HandlerOutput::builder().with_log(log).with_events(events).with_result(()));
 
-	output.log(format!(
-		"IBC fungible token transfer: {} --({})--> {}",
-		msg.sender, token, msg.receiver
-	));
+	for token in &tokens {
+		output.log(format!(
+			"IBC fungible token transfer: {} --({})--> {}",
+			msg.sender, token, msg.receiver
+		));
+	}
 
 	let transfer_event = TransferEvent { sender: msg.sender, receiver: msg.receiver };
 	output.emit(transfer_event.into());
 
 	Ok(())
 }
+
+/// Returns the hops through which the tokens are forwarded once received. When unwinding, the
+/// tokens are first sent back along the trace they share, the source channel of the transfer must
+/// then be the channel they were last received on.
+fn forwarding_hops(
+	source_port: &PortId,
+	source_channel: ChannelId,
+	tokens: &[PrefixedCoin],
+	forwarding: Forwarding,
+) -> Result<Vec<TracePrefix>, Error> {
+	if !forwarding.unwind {
+		return Ok(forwarding.hops)
+	}
+
+	let trace_path = tokens[0].denom.trace_path();
+	if tokens.iter().any(|token| token.denom.trace_path() != trace_path) {
+		return Err(Error::invalid_forwarding(
+			"all tokens must share the same trace to be unwound".to_string(),
+		))
+	}
+
+	let mut trace = trace_path.prefixes().cloned();
+	let last_hop = trace
+		.next()
+		.ok_or_else(|| Error::invalid_forwarding("native tokens cannot be unwound".to_string()))?;
+	if last_hop != TracePrefix::new(source_port.clone(), source_channel) {
+		return Err(Error::invalid_forwarding(format!(
+			"unwinding must start from {last_hop}, got {source_port}/{source_channel}"
+		)))
+	}
+
+	Ok(trace.chain(forwarding.hops).collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unwinding_prepends_the_token_trace_to_the_hops() {
+		let port = "transfer".parse().unwrap();
+		let channel = "channel-1".parse().unwrap();
+		let token =
+			|denom: &str| PrefixedCoin { denom: denom.parse().unwrap(), amount: 1u64.into() };
+		let hop = TracePrefix::new("transfer".parse().unwrap(), "channel-9".parse().unwrap());
+		let forwarding = Forwarding { unwind: true, hops: vec![hop.clone()] };
+
+		let tokens = vec![token("transfer/channel-1/transfer/channel-4/uatom")];
+		let hops = forwarding_hops(&port, channel, &tokens, forwarding.clone()).unwrap();
+		assert_eq!(
+			hops,
+			vec![TracePrefix::new("transfer".parse().unwrap(), "channel-4".parse().unwrap()), hop]
+		);
+
+		// the transfer must go through the channel the tokens were received on
+		let tokens = vec![token("transfer/channel-2/uatom")];
+		assert!(forwarding_hops(&port, channel, &tokens, forwarding.clone()).is_err());
+
+		// native tokens have nothing to unwind
+		assert!(forwarding_hops(&port, channel, &[token("uatom")], forwarding.clone()).is_err());
+
+		// tokens with different traces cannot be unwound together
+		let tokens = vec![
+			token("transfer/channel-1/uatom"),
+			token("transfer/channel-1/transfer/channel-4/uatom"),
+		];
+		assert!(forwarding_hops(&port, channel, &tokens, forwarding).is_err());
+	}
+}
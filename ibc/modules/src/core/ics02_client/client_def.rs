@@ -22,6 +22,7 @@ use crate::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			packet::Sequence,
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -161,6 +162,39 @@ pub trait ClientDef: Clone {
 		expected_channel_end: &ChannelEnd,
 	) -> Result<(), Error>;
 
+	/// Verify a `proof` that the counterparty stored `upgrade` as the pending upgrade of a
+	/// channel.
+	#[allow(clippy::too_many_arguments)]
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
+	) -> Result<(), Error>;
+
+	/// Verify a `proof` that the counterparty aborted a channel upgrade with `error_receipt`.
+	#[allow(clippy::too_many_arguments)]
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
+	) -> Result<(), Error>;
+
 	/// Verify the client state for this chain that it is stored on the counterparty chain.
 	#[allow(clippy::too_many_arguments)]
 	fn verify_client_full_state<Ctx: ReaderContext>(
@@ -240,4 +274,33 @@ pub trait ClientDef: Clone {
 		channel_id: &ChannelId,
 		sequence: Sequence,
 	) -> Result<(), Error>;
+
+	/// Verify a `proof` that the packet with the given `sequence` timed out on an
+	/// `ORDERED_ALLOW_TIMEOUT` channel, ie. that a timeout receipt was written for it.
+	#[allow(clippy::too_many_arguments)]
+	fn verify_packet_timeout_receipt<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Error>;
+
+	/// Returns the client state once `proof` was verified against `client_state`. Clients whose
+	/// proofs may only be used once, like solo machines which sign every proof at a new sequence,
+	/// consume the proof here. Any further proof of the same message is verified against the
+	/// returned state, which is stored once the message was handled.
+	fn update_state_on_verified_proof(
+		&self,
+		client_state: Self::ClientState,
+		_proof: &CommitmentProofBytes,
+	) -> Result<Self::ClientState, Error> {
+		Ok(client_state)
+	}
 }
@@ -302,5 +302,30 @@ define_error! {
 		Signer
 			[ SignerError ]
 			| _ | { "failed to parse signer" },
+
+		ClientNotRecoverable
+			{ client_id: ClientId }
+			| e | { format_args!("client {0} is active and cannot be recovered", e.client_id) },
+
+		SubstituteClientNotActive
+			{ client_id: ClientId }
+			| e | { format_args!("substitute client {0} is not active", e.client_id) },
+
+		MismatchedClientTypes
+			{ subject_type: ClientType, substitute_type: ClientType }
+			| e | {
+				format_args!("subject client type {0} does not match substitute client type {1}",
+					e.subject_type, e.substitute_type)
+			},
+
+		LowSubstituteHeight
+			{
+				substitute_height: Height,
+				subject_height: Height,
+			}
+			| e | {
+				format_args!("substitute client height {0} must be greater than subject client height {1}",
+					e.substitute_height, e.subject_height)
+			},
 	}
 }
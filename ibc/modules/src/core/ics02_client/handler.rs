@@ -24,6 +24,7 @@ use crate::{
 use core::fmt::Debug;
 
 pub mod create_client;
+pub mod recover_client;
 pub mod update_client;
 pub mod upgrade_client;
 
@@ -0,0 +1,190 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic for recovering an expired or frozen client with the state of a substitute
+//! client. This is a privileged operation, it is meant to be triggered by governance.
+
+use crate::{
+	core::{
+		ics02_client::{
+			client_def::ClientDef,
+			client_state::{ClientState, Status},
+			error::Error,
+			handler::{update_client, ClientResult},
+		},
+		ics24_host::identifier::ClientId,
+		ics26_routing::context::ReaderContext,
+	},
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+/// Updates the subject client with the state of the substitute client. The subject client must
+/// be frozen or expired, while the substitute must be active, of the same type and at a greater
+/// height. The light client is responsible for checking that both clients match on every field
+/// that must not change during a recovery.
+pub fn process<Ctx>(
+	ctx: &Ctx,
+	subject_client_id: ClientId,
+	substitute_client_id: ClientId,
+) -> HandlerResult<ClientResult<Ctx>, Error>
+where
+	Ctx: ReaderContext,
+{
+	let output = HandlerOutput::builder();
+
+	let subject_client_state = ctx.client_state(&subject_client_id)?;
+	let substitute_client_state = ctx.client_state(&substitute_client_id)?;
+
+	let subject_type = ctx.client_type(&subject_client_id)?;
+	let substitute_type = ctx.client_type(&substitute_client_id)?;
+	if subject_type != substitute_type {
+		return Err(Error::mismatched_client_types(subject_type, substitute_type))
+	}
+
+	if subject_client_state.status(ctx, &subject_client_id) == Status::Active {
+		return Err(Error::client_not_recoverable(subject_client_id))
+	}
+
+	if substitute_client_state.status(ctx, &substitute_client_id) != Status::Active {
+		return Err(Error::substitute_client_not_active(substitute_client_id))
+	}
+
+	if substitute_client_state.latest_height() <= subject_client_state.latest_height() {
+		return Err(Error::low_substitute_height(
+			substitute_client_state.latest_height(),
+			subject_client_state.latest_height(),
+		))
+	}
+
+	let client_def = subject_client_state.client_def();
+	let (client_state, consensus_state) = client_def.check_substitute_and_update_state::<Ctx>(
+		ctx,
+		subject_client_id.clone(),
+		substitute_client_id,
+		subject_client_state,
+		substitute_client_state,
+	)?;
+
+	let result = ClientResult::Update(update_client::Result {
+		client_id: subject_client_id,
+		client_state,
+		consensus_state: Some(consensus_state),
+		processed_time: ctx.host_timestamp(),
+		processed_height: ctx.host_height(),
+	});
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use core::str::FromStr;
+
+	use crate::{
+		core::{
+			ics02_client::{
+				client_state::ClientState,
+				error::{Error, ErrorDetail},
+				handler::{recover_client::process, ClientResult::Update},
+			},
+			ics24_host::identifier::ClientId,
+		},
+		handler::HandlerOutput,
+		mock::{
+			client_state::MockClientState,
+			context::{MockClientTypes, MockContext},
+			header::MockHeader,
+		},
+		Height,
+	};
+
+	fn freeze_client(ctx: &MockContext<MockClientTypes>, client_id: &ClientId, height: Height) {
+		let mut store = ctx.ibc_store.lock().unwrap();
+		let record = store.clients.get_mut(client_id).unwrap();
+		record.client_state = Some(
+			MockClientState { header: MockHeader::new(height), frozen_height: Some(height) }.into(),
+		);
+	}
+
+	#[test]
+	fn test_recover_client_ok() {
+		let subject_id = ClientId::from_str("mockclient1").unwrap();
+		let substitute_id = ClientId::from_str("mockclient2").unwrap();
+
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&subject_id, Height::new(0, 42))
+			.with_client(&substitute_id, Height::new(0, 50));
+		freeze_client(&ctx, &subject_id, Height::new(0, 42));
+
+		let output = process(&ctx, subject_id.clone(), substitute_id);
+
+		match output {
+			Ok(HandlerOutput { result, events, .. }) => {
+				assert!(events.is_empty());
+				match result {
+					Update(res) => {
+						assert_eq!(res.client_id, subject_id);
+						assert_eq!(res.client_state.latest_height(), Height::new(0, 50));
+						assert!(res.client_state.frozen_height().is_none());
+					},
+					_ => panic!("recover handler result has incorrect type"),
+				}
+			},
+			Err(err) => panic!("unexpected error: {}", err),
+		}
+	}
+
+	#[test]
+	fn test_recover_active_client() {
+		let subject_id = ClientId::from_str("mockclient1").unwrap();
+		let substitute_id = ClientId::from_str("mockclient2").unwrap();
+
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&subject_id, Height::new(0, 42))
+			.with_client(&substitute_id, Height::new(0, 50));
+
+		let output = process(&ctx, subject_id.clone(), substitute_id);
+
+		match output {
+			Err(Error(ErrorDetail::ClientNotRecoverable(e), _)) => {
+				assert_eq!(e.client_id, subject_id);
+			},
+			_ => panic!("expected ClientNotRecoverable error, instead got {:?}", output),
+		}
+	}
+
+	#[test]
+	fn test_recover_client_low_substitute_height() {
+		let subject_id = ClientId::from_str("mockclient1").unwrap();
+		let substitute_id = ClientId::from_str("mockclient2").unwrap();
+
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&subject_id, Height::new(0, 42))
+			.with_client(&substitute_id, Height::new(0, 30));
+		freeze_client(&ctx, &subject_id, Height::new(0, 42));
+
+		let output = process(&ctx, subject_id, substitute_id);
+
+		match output {
+			Err(Error(ErrorDetail::LowSubstituteHeight(e), _)) => {
+				assert_eq!(e.substitute_height, Height::new(0, 30));
+				assert_eq!(e.subject_height, Height::new(0, 42));
+			},
+			_ => panic!("expected LowSubstituteHeight error, instead got {:?}", output),
+		}
+	}
+}
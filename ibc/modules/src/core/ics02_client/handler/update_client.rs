@@ -172,7 +172,8 @@ mod tests {
 		mock::{
 			client_state::{AnyClientState, MockClientState},
 			context::{MockClientTypes, MockContext},
-			header::{AnyClientMessage, MockHeader},
+			header::{AnyClientMessage, MockClientMessage, MockHeader},
+			misbehaviour::MockMisbehaviour,
 		},
 		prelude::*,
 		test_utils::get_dummy_account_id,
@@ -303,4 +304,40 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn test_misbehaviour_freezes_client() {
+		let client_id = ClientId::default();
+		let signer = get_dummy_account_id();
+		let height = Height::new(0, 46);
+
+		let ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+		let header1 =
+			MockHeader::new(height).with_timestamp(Timestamp::from_nanoseconds(1).unwrap());
+		let header2 =
+			MockHeader::new(height).with_timestamp(Timestamp::from_nanoseconds(2).unwrap());
+		let msg = MsgUpdateAnyClient {
+			client_id: client_id.clone(),
+			client_message: AnyClientMessage::Mock(MockClientMessage::Misbehaviour(
+				MockMisbehaviour { client_id: client_id.clone(), header1, header2 },
+			)),
+			signer,
+		};
+
+		let output = dispatch(&ctx, ClientMsg::UpdateClient(msg)).unwrap();
+		assert!(matches!(output.events.as_slice(), [IbcEvent::ClientMisbehaviour(_)]));
+		match output.result {
+			Update(upd_res) => {
+				assert_eq!(upd_res.client_id, client_id);
+				assert!(upd_res.consensus_state.is_none());
+				match upd_res.client_state {
+					AnyClientState::Mock(client_state) => {
+						assert_eq!(client_state.frozen_height, Some(height))
+					},
+				}
+			},
+			_ => panic!("update handler result has incorrect type"),
+		}
+	}
 }
@@ -104,8 +104,12 @@ pub(crate) fn process<Ctx: ReaderContext>(
 
 	ctx.validate_self_client(&client_state).map_err(Error::ics02_client)?;
 
-	verify_connection_proof::<Ctx>(
+	// The proofs are verified in order by the local client, which may consume each of them.
+	let local_client_state = ctx.client_state(conn_end.client_id()).map_err(Error::ics02_client)?;
+
+	let local_client_state = verify_connection_proof::<Ctx>(
 		ctx,
+		local_client_state,
 		msg.proofs.height(),
 		&conn_end,
 		&expected_conn,
@@ -113,8 +117,9 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		msg.proofs.object_proof(),
 	)?;
 
-	verify_client_proof::<Ctx>(
+	let local_client_state = verify_client_proof::<Ctx>(
 		ctx,
+		local_client_state,
 		msg.proofs.height(),
 		&conn_end,
 		&client_state,
@@ -124,6 +129,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 
 	verify_consensus_proof::<Ctx>(
 		ctx,
+		local_client_state,
 		msg.proofs.height(),
 		&conn_end,
 		&consensus_proof,
@@ -59,8 +59,10 @@ pub(crate) fn process<Ctx: ReaderContext>(
 	);
 
 	// 2. Pass the details to the verification function.
+	let client_state = ctx.client_state(conn_end.client_id()).map_err(Error::ics02_client)?;
 	verify_connection_proof::<Ctx>(
 		ctx,
+		client_state,
 		msg.proofs.height(),
 		&conn_end,
 		&expected_conn,
@@ -98,8 +98,13 @@ pub(crate) fn process<Ctx: ReaderContext>(
 
 	ctx.validate_self_client(&client_state).map_err(Error::ics02_client)?;
 
-	verify_connection_proof::<_>(
+	// The proofs are verified in order by the local client, which may consume each of them.
+	let local_client_state =
+		ctx.client_state(new_connection_end.client_id()).map_err(Error::ics02_client)?;
+
+	let local_client_state = verify_connection_proof::<_>(
 		ctx,
+		local_client_state,
 		msg.proofs.height(),
 		&new_connection_end,
 		&expected_conn,
@@ -107,8 +112,9 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		msg.proofs.object_proof(),
 	)?;
 
-	verify_client_proof::<_>(
+	let local_client_state = verify_client_proof::<_>(
 		ctx,
+		local_client_state,
 		msg.proofs.height(),
 		&new_connection_end,
 		&client_state,
@@ -118,6 +124,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 
 	verify_consensus_proof::<_>(
 		ctx,
+		local_client_state,
 		msg.proofs.height(),
 		&new_connection_end,
 		&consensus_proof,
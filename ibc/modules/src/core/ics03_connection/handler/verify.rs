@@ -32,17 +32,19 @@ use alloc::vec::Vec;
 /// Verifies the authenticity and semantic correctness of a commitment `proof`. The commitment
 /// claims to prove that an object of type connection exists on the source chain (i.e., the chain
 /// which created this proof). This object must match the state of `expected_conn`.
+///
+/// The proof is verified against `client_state`, the state of the IBC client on the local/host
+/// chain, and the client state after consuming the proof is returned.
 pub fn verify_connection_proof<Ctx: ReaderContext>(
 	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
 	height: Height,
 	connection_end: &ConnectionEnd,
 	expected_conn: &ConnectionEnd,
 	proof_height: Height,
 	proof: &CommitmentProofBytes,
-) -> Result<(), Error> {
-	// Fetch the client state (IBC client on the local/host chain).
+) -> Result<Ctx::AnyClientState, Error> {
 	let client_id = connection_end.client_id();
-	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
 
 	// The client must not be frozen.
 	if client_state.is_frozen(ctx, client_id) {
@@ -76,7 +78,11 @@ pub fn verify_connection_proof<Ctx: ReaderContext>(
 			connection_id,
 			expected_conn,
 		)
-		.map_err(Error::verify_connection_state)
+		.map_err(Error::verify_connection_state)?;
+
+	client_def
+		.update_state_on_verified_proof(client_state, proof)
+		.map_err(Error::ics02_client)
 }
 
 /// Verifies the client `proof` from a connection handshake message, typically from a
@@ -85,18 +91,17 @@ pub fn verify_connection_proof<Ctx: ReaderContext>(
 /// is running on the counterparty chain (the chain which sent this message). This method does a
 /// complete verification: that the client state the counterparty stores is valid (i.e., not frozen,
 /// at the same revision as the current chain, with matching chain identifiers, etc) and that the
-/// `proof` is correct.
+/// `proof` is correct. The client state after consuming the proof is returned.
 pub fn verify_client_proof<Ctx: ReaderContext>(
 	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
 	height: Height,
 	connection_end: &ConnectionEnd,
 	expected_client_state: &Ctx::AnyClientState,
 	proof_height: Height,
 	proof: &CommitmentProofBytes,
-) -> Result<(), Error> {
-	// Fetch the local client state (IBC client running on the host chain).
+) -> Result<Ctx::AnyClientState, Error> {
 	let client_id = connection_end.client_id();
-	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
 
 	if client_state.is_frozen(ctx, client_id) {
 		return Err(Error::frozen_client(client_id.clone()))
@@ -119,19 +124,24 @@ pub fn verify_client_proof<Ctx: ReaderContext>(
 			connection_end.counterparty().client_id(),
 			&expected_client_state,
 		)
-		.map_err(|e| Error::client_state_verification_failure(client_id.clone(), e))
+		.map_err(|e| Error::client_state_verification_failure(client_id.clone(), e))?;
+
+	client_def
+		.update_state_on_verified_proof(client_state, proof)
+		.map_err(Error::ics02_client)
 }
 
+/// Verifies the consensus state `proof` from a connection handshake message and returns the client
+/// state after consuming the proof.
 pub fn verify_consensus_proof<Ctx: ReaderContext>(
 	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
 	height: Height,
 	connection_end: &ConnectionEnd,
 	proof: &ConsensusProof,
 	host_consensus_state_proof: Vec<u8>,
-) -> Result<(), Error> {
-	// Fetch the client state (IBC client on the local chain).
+) -> Result<Ctx::AnyClientState, Error> {
 	let client_id = connection_end.client_id();
-	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
 
 	if client_state.is_frozen(ctx, client_id) {
 		return Err(Error::frozen_client(client_id.clone()))
@@ -168,7 +178,9 @@ pub fn verify_consensus_proof<Ctx: ReaderContext>(
 		)
 		.map_err(|e| Error::consensus_state_verification_failure(proof.height(), e))?;
 
-	Ok(())
+	client
+		.update_state_on_verified_proof(client_state, proof.proof())
+		.map_err(Error::ics02_client)
 }
 
 /// Checks that `claimed_height` is within normal bounds, i.e., fresh enough so that the chain has
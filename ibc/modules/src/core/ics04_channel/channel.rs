@@ -58,6 +58,7 @@ impl TryFrom<RawIdentifiedChannel> for IdentifiedChannelEnd {
 			counterparty: value.counterparty,
 			connection_hops: value.connection_hops,
 			version: value.version,
+			upgrade_sequence: value.upgrade_sequence,
 		};
 
 		Ok(IdentifiedChannelEnd {
@@ -83,6 +84,7 @@ impl From<IdentifiedChannelEnd> for RawIdentifiedChannel {
 			version: value.channel_end.version.to_string(),
 			port_id: value.port_id.to_string(),
 			channel_id: value.channel_id.to_string(),
+			upgrade_sequence: value.channel_end.upgrade_sequence,
 		}
 	}
 }
@@ -94,6 +96,9 @@ pub struct ChannelEnd {
 	pub remote: Counterparty,
 	pub connection_hops: Vec<ConnectionId>,
 	pub version: Version,
+	/// Sequence of the latest upgrade attempt on this channel, `0` if it was never upgraded.
+	#[serde(default)]
+	pub upgrade_sequence: u64,
 }
 
 impl Default for ChannelEnd {
@@ -104,6 +109,7 @@ impl Default for ChannelEnd {
 			remote: Counterparty::default(),
 			connection_hops: Vec::new(),
 			version: Version::default(),
+			upgrade_sequence: 0,
 		}
 	}
 }
@@ -131,7 +137,8 @@ impl TryFrom<RawChannel> for ChannelEnd {
 
 		let version = value.version.into();
 
-		Ok(ChannelEnd::new(chan_state, chan_ordering, remote, connection_hops, version))
+		Ok(ChannelEnd::new(chan_state, chan_ordering, remote, connection_hops, version)
+			.with_upgrade_sequence(value.upgrade_sequence))
 	}
 }
 
@@ -143,6 +150,7 @@ impl From<ChannelEnd> for RawChannel {
 			counterparty: Some(value.counterparty().clone().into()),
 			connection_hops: value.connection_hops.iter().map(|v| v.as_str().to_string()).collect(),
 			version: value.version.to_string(),
+			upgrade_sequence: value.upgrade_sequence,
 		}
 	}
 }
@@ -156,7 +164,11 @@ impl ChannelEnd {
 		connection_hops: Vec<ConnectionId>,
 		version: Version,
 	) -> Self {
-		Self { state, ordering, remote, connection_hops, version }
+		Self { state, ordering, remote, connection_hops, version, upgrade_sequence: 0 }
+	}
+
+	pub fn with_upgrade_sequence(self, upgrade_sequence: u64) -> Self {
+		Self { upgrade_sequence, ..self }
 	}
 
 	/// Updates the ChannelEnd to assume a new State 's'.
@@ -172,6 +184,16 @@ impl ChannelEnd {
 		self.remote.channel_id = Some(c);
 	}
 
+	pub fn set_upgrade_sequence(&mut self, sequence: u64) {
+		self.upgrade_sequence = sequence;
+	}
+
+	/// Returns `true` if this `ChannelEnd` is flushing in-flight packets as part of an upgrade,
+	/// i.e. it is in state [`State::Flushing`] or [`State::FlushComplete`].
+	pub fn is_flushing(&self) -> bool {
+		self.state.is_flushing()
+	}
+
 	/// Returns `true` if this `ChannelEnd` is in state [`State::Open`].
 	pub fn is_open(&self) -> bool {
 		self.state_matches(&State::Open)
@@ -197,6 +219,10 @@ impl ChannelEnd {
 		&self.version
 	}
 
+	pub fn upgrade_sequence(&self) -> u64 {
+		self.upgrade_sequence
+	}
+
 	pub fn validate_basic(&self) -> Result<(), Error> {
 		if self.connection_hops.len() != 1 {
 			return Err(Error::invalid_connection_hops_length(1, self.connection_hops.len()))
@@ -280,6 +306,10 @@ impl From<Counterparty> for RawCounterparty {
 pub enum Order {
 	Unordered = 1,
 	Ordered = 2,
+	/// Packets are delivered in order, but a packet that timed out advances the receive sequence
+	/// instead of closing the channel. This ordering is not part of ibc-go, only counterparties
+	/// running this implementation understand it.
+	OrderedAllowTimeout = 3,
 }
 
 impl Default for Order {
@@ -300,6 +330,16 @@ impl Order {
 		match self {
 			Self::Unordered => "ORDER_UNORDERED",
 			Self::Ordered => "ORDER_ORDERED",
+			Self::OrderedAllowTimeout => "ORDER_ORDERED_ALLOW_TIMEOUT",
+		}
+	}
+
+	/// The connection feature a channel with this ordering requires. Channels that allow timeouts
+	/// only need the connection to support ordered channels.
+	pub fn connection_feature(&self) -> &'static str {
+		match self {
+			Self::OrderedAllowTimeout => Self::Ordered.as_str(),
+			_ => self.as_str(),
 		}
 	}
 
@@ -308,6 +348,7 @@ impl Order {
 		match nr {
 			1 => Ok(Self::Unordered),
 			2 => Ok(Self::Ordered),
+			3 => Ok(Self::OrderedAllowTimeout),
 			_ => Err(Error::unknown_order_type(nr.to_string())),
 		}
 	}
@@ -320,6 +361,7 @@ impl FromStr for Order {
 		match s.to_lowercase().trim_start_matches("order_") {
 			"unordered" => Ok(Self::Unordered),
 			"ordered" => Ok(Self::Ordered),
+			"ordered_allow_timeout" => Ok(Self::OrderedAllowTimeout),
 			_ => Err(Error::unknown_order_type(s.to_string())),
 		}
 	}
@@ -331,6 +373,8 @@ pub enum State {
 	TryOpen = 2,
 	Open = 3,
 	Closed = 4,
+	Flushing = 5,
+	FlushComplete = 6,
 }
 
 impl State {
@@ -341,6 +385,8 @@ impl State {
 			Self::TryOpen => "TRYOPEN",
 			Self::Open => "OPEN",
 			Self::Closed => "CLOSED",
+			Self::Flushing => "FLUSHING",
+			Self::FlushComplete => "FLUSHCOMPLETE",
 		}
 	}
 
@@ -351,6 +397,8 @@ impl State {
 			2 => Ok(Self::TryOpen),
 			3 => Ok(Self::Open),
 			4 => Ok(Self::Closed),
+			5 => Ok(Self::Flushing),
+			6 => Ok(Self::FlushComplete),
 			_ => Err(Error::unknown_state(s)),
 		}
 	}
@@ -360,8 +408,14 @@ impl State {
 		self == State::Open
 	}
 
+	/// Returns whether or not this channel state is one of the upgrade flushing states.
+	pub fn is_flushing(self) -> bool {
+		matches!(self, State::Flushing | State::FlushComplete)
+	}
+
 	/// Returns whether or not the channel with this state
 	/// has progressed less or the same than the argument.
+	/// The upgrade states are ordered after `Closed` and are not part of the opening handshake.
 	///
 	/// # Example
 	/// ```rust,ignore
@@ -421,6 +475,7 @@ pub mod test_util {
 			counterparty: Some(get_dummy_raw_counterparty()),
 			connection_hops: vec![ConnectionId::default().to_string()],
 			version: "ics20".to_string(), // The version is not validated.
+			upgrade_sequence: 0,
 		}
 	}
 }
@@ -491,6 +546,16 @@ mod tests {
 				},
 				want_pass: true,
 			},
+			Test {
+				name: "Raw channel end flushing an upgrade".to_string(),
+				params: RawChannel { state: 5, upgrade_sequence: 1, ..raw_channel_end.clone() },
+				want_pass: true,
+			},
+			Test {
+				name: "Raw channel end with unknown upgrade state".to_string(),
+				params: RawChannel { state: 7, ..raw_channel_end.clone() },
+				want_pass: false,
+			},
 			Test {
 				name: "Raw channel end with correct params".to_string(),
 				params: raw_channel_end,
@@ -528,6 +593,11 @@ mod tests {
 		let tests: Vec<Test> = vec![
 			Test { ordering: "UNORDERED", want_res: Order::Unordered, want_err: false },
 			Test { ordering: "ORDERED", want_res: Order::Ordered, want_err: false },
+			Test {
+				ordering: "ORDER_ORDERED_ALLOW_TIMEOUT",
+				want_res: Order::OrderedAllowTimeout,
+				want_err: false,
+			},
 		]
 		.into_iter()
 		.collect();
@@ -20,12 +20,16 @@ use num_traits::float::FloatCore;
 use crate::{
 	core::{
 		ics04_channel::{
-			channel::ChannelEnd,
+			channel::{ChannelEnd, State},
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			error::Error,
-			handler::{recv_packet::RecvPacketResult, ChannelIdState, ChannelResult},
+			handler::{
+				recv_packet::RecvPacketResult, ChannelIdState, ChannelResult, FlushResult,
+				UpgradeResult,
+			},
 			msgs::acknowledgement::Acknowledgement,
 			packet::Receipt,
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
@@ -109,6 +113,32 @@ pub trait ChannelReader {
 	/// Returns the maximum expected time per block
 	fn max_expected_time_per_block(&self) -> Duration;
 
+	/// Returns the pending upgrade of the given channel, if an upgrade handshake is in progress.
+	fn channel_upgrade(&self, port_channel_id: &(PortId, ChannelId)) -> Result<Upgrade, Error>;
+
+	/// Returns the upgrade the counterparty committed to, as stored on `MsgChannelUpgradeAck` or
+	/// `MsgChannelUpgradeConfirm`.
+	fn counterparty_channel_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, Error>;
+
+	/// Returns the error receipt written when the last upgrade of the given channel was aborted.
+	fn channel_upgrade_error(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, Error>;
+
+	/// Returns true if any packet sent on the given channel is still awaiting an acknowledgement
+	/// or a timeout.
+	fn has_inflight_packets(&self, port_channel_id: &(PortId, ChannelId)) -> Result<bool, Error>;
+
+	/// The period after which a channel upgrade proposed by this chain times out on the
+	/// counterparty.
+	fn upgrade_timeout_period(&self) -> Duration {
+		Duration::from_secs(600)
+	}
+
 	/// Calculates the block delay period using the connection's delay period and the maximum
 	/// expected time per block.
 	fn block_delay(&self, delay_period_time: Duration) -> u64 {
@@ -138,11 +168,53 @@ pub trait ChannelKeeper: ChannelReader {
 			self.store_next_sequence_send((result.port_id.clone(), result.channel_id), 1.into())?;
 			self.store_next_sequence_recv((result.port_id.clone(), result.channel_id), 1.into())?;
 			self.store_next_sequence_ack((result.port_id, result.channel_id), 1.into())?;
+			return Ok(())
+		}
+
+		let port_channel_id = (result.port_id, result.channel_id);
+		match result.upgrade {
+			Some(UpgradeResult::Pending { upgrade, counterparty_upgrade }) => {
+				self.store_channel_upgrade(port_channel_id.clone(), upgrade)?;
+				if let Some(counterparty_upgrade) = counterparty_upgrade {
+					self.store_counterparty_channel_upgrade(port_channel_id, counterparty_upgrade)?;
+				}
+			},
+			Some(UpgradeResult::Completed) => self.delete_channel_upgrade(port_channel_id)?,
+			Some(UpgradeResult::Aborted(error_receipt)) => {
+				self.delete_channel_upgrade(port_channel_id.clone())?;
+				self.store_channel_upgrade_error(port_channel_id, error_receipt)?;
+			},
+			None => {},
 		}
 
 		Ok(())
 	}
 
+	/// Moves a flushing channel to `FLUSHCOMPLETE` once its last in-flight packet has been
+	/// acknowledged or timed out, or aborts its upgrade if flushing did not complete in time.
+	fn store_flush_result(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		flush: FlushResult,
+	) -> Result<(), Error> {
+		match flush {
+			FlushResult::Continue => {
+				let mut channel_end = self.channel_end(&port_channel_id)?;
+				if channel_end.state_matches(&State::Flushing) &&
+					!self.has_inflight_packets(&port_channel_id)?
+				{
+					channel_end.set_state(State::FlushComplete);
+					self.store_channel(port_channel_id, &channel_end)?;
+				}
+			},
+			FlushResult::Aborted(error_receipt) => {
+				self.delete_channel_upgrade(port_channel_id.clone())?;
+				self.store_channel_upgrade_error(port_channel_id, error_receipt)?;
+			},
+		}
+		Ok(())
+	}
+
 	fn store_packet_result(&mut self, general_result: PacketResult) -> Result<(), Error> {
 		match general_result {
 			PacketResult::Send(res) => {
@@ -167,6 +239,10 @@ pub trait ChannelKeeper: ChannelReader {
 					self.store_packet_receipt((port_id.clone(), channel_id, sequence), receipt)?;
 					self.store_recv_packet((port_id, channel_id, packet.sequence), packet)?
 				},
+				RecvPacketResult::Timeout { port_id, channel_id, sequence, next_seq_recv } => {
+					self.store_next_sequence_recv((port_id.clone(), channel_id), next_seq_recv)?;
+					self.store_packet_receipt((port_id, channel_id, sequence), Receipt::Timeout)?
+				},
 
 				RecvPacketResult::NoOp => unreachable!(),
 			},
@@ -187,8 +263,15 @@ pub trait ChannelKeeper: ChannelReader {
 					self.store_next_sequence_ack((res.port_id.clone(), res.channel_id), s)?;
 				}
 
+				if let Some(c) = res.channel {
+					self.store_channel((res.port_id.clone(), res.channel_id), &c)?;
+				}
+
 				// Delete packet commitment since packet has been aknowledged
 				self.delete_packet_commitment((res.port_id.clone(), res.channel_id, res.seq))?;
+				if let Some(flush) = res.flush {
+					self.store_flush_result((res.port_id, res.channel_id), flush)?;
+				}
 			},
 			PacketResult::Timeout(res) => {
 				if let Some(c) = res.channel {
@@ -196,6 +279,9 @@ pub trait ChannelKeeper: ChannelReader {
 					self.store_channel((res.port_id.clone(), res.channel_id), &c)?;
 				}
 				self.delete_packet_commitment((res.port_id.clone(), res.channel_id, res.seq))?;
+				if let Some(flush) = res.flush {
+					self.store_flush_result((res.port_id, res.channel_id), flush)?;
+				}
 			},
 		}
 		Ok(())
@@ -278,6 +364,30 @@ pub trait ChannelKeeper: ChannelReader {
 		seq: Sequence,
 	) -> Result<(), Error>;
 
+	/// Stores the upgrade this chain proposed or agreed to for the given channel.
+	fn store_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), Error>;
+
+	/// Stores the upgrade the counterparty committed to for the given channel.
+	fn store_counterparty_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), Error>;
+
+	/// Removes both the pending upgrade and the counterparty upgrade of the given channel.
+	fn delete_channel_upgrade(&mut self, port_channel_id: (PortId, ChannelId))
+		-> Result<(), Error>;
+
+	fn store_channel_upgrade_error(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		error_receipt: ErrorReceipt,
+	) -> Result<(), Error>;
+
 	/// Called upon channel identifier creation (Init or Try message processing).
 	/// Increases the counter which keeps track of how many channels have been created.
 	/// Should never fail.
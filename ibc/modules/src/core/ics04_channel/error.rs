@@ -95,6 +95,9 @@ define_error! {
 		MissingChannelProof
 			| _ | { "invalid proof: missing channel proof" },
 
+		MissingUpgradeProof
+			| _ | { "invalid proof: missing upgrade proof" },
+
 		MissingNextRecvSeq
 			{ port_channel_id: (PortId, ChannelId) }
 			| e | {
@@ -396,6 +399,52 @@ define_error! {
 
 		NotEnoughtFundsForFee
 			| _ | { "not enough funds for fee" },
+
+		MissingUpgradeFields
+			| _ | { "missing upgrade fields" },
+
+		MissingUpgradeTimeout
+			| _ | { "upgrade timeout height and upgrade timeout timestamp cannot both be 0" },
+
+		MissingErrorReceipt
+			| _ | { "missing upgrade error receipt" },
+
+		UpgradeNotFound
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"no upgrade in progress for the channel end ({0}, {1})",
+					e.port_id, e.channel_id)
+			},
+
+		UpgradeErrorReceiptNotFound
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"no upgrade error receipt for the channel end ({0}, {1})",
+					e.port_id, e.channel_id)
+			},
+
+		InvalidUpgrade
+			{ reason: String }
+			| e | { format_args!("invalid channel upgrade: {}", e.reason) },
+
+		InvalidUpgradeSequence
+			{ expected: u64, actual: u64 }
+			| e | {
+				format_args!(
+					"invalid upgrade sequence: expected {0}; actual {1}",
+					e.expected, e.actual)
+			},
+
+		UpgradeTimeoutNotReached
+			| _ | { "upgrade timeout has not been reached on the counterparty chain" },
+
+		VerifyChannelUpgradeFailed
+			[ client_error::Error ]
+			| _ | {
+				"Error verifying channel upgrade"
+			},
 	}
 }
 
@@ -37,6 +37,7 @@ pub const CHANNEL_ID_ATTRIBUTE_KEY: &str = "channel_id";
 pub const PORT_ID_ATTRIBUTE_KEY: &str = "port_id";
 pub const COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY: &str = "counterparty_channel_id";
 pub const COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY: &str = "counterparty_port_id";
+pub const UPGRADE_SEQUENCE_ATTRIBUTE_KEY: &str = "upgrade_sequence";
 
 /// Packet event attribute keys
 pub const PKT_SEQ_ATTRIBUTE_KEY: &str = "packet_sequence";
@@ -750,6 +751,123 @@ macro_rules! impl_try_from_raw_obj_for_event {
 
 impl_try_from_raw_obj_for_event!(OpenInit, OpenTry, OpenAck, OpenConfirm, CloseInit, CloseConfirm);
 
+/// Attributes shared by every event emitted during a channel upgrade handshake.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UpgradeAttributes {
+	pub height: Height,
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub counterparty_port_id: PortId,
+	pub counterparty_channel_id: Option<ChannelId>,
+	pub upgrade_sequence: u64,
+}
+
+/// Convert upgrade attributes to Tendermint ABCI tags
+impl From<UpgradeAttributes> for Vec<EventAttribute> {
+	fn from(a: UpgradeAttributes) -> Self {
+		let mut attributes = vec![
+			EventAttribute {
+				key: HEIGHT_ATTRIBUTE_KEY.to_string(),
+				value: a.height.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: PORT_ID_ATTRIBUTE_KEY.to_string(),
+				value: a.port_id.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: CHANNEL_ID_ATTRIBUTE_KEY.to_string(),
+				value: a.channel_id.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY.to_string(),
+				value: a.counterparty_port_id.to_string(),
+				index: false,
+			},
+		];
+		if let Some(channel_id) = a.counterparty_channel_id {
+			attributes.push(EventAttribute {
+				key: COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY.to_string(),
+				value: channel_id.to_string(),
+				index: false,
+			});
+		}
+		attributes.push(EventAttribute {
+			key: UPGRADE_SEQUENCE_ATTRIBUTE_KEY.to_string(),
+			value: a.upgrade_sequence.to_string(),
+			index: false,
+		});
+		attributes
+	}
+}
+
+macro_rules! impl_upgrade_event {
+    ($($event:ident => $variant:ident),+) => {
+        $(#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+        pub struct $event(pub UpgradeAttributes);
+
+        impl $event {
+            pub fn port_id(&self) -> &PortId {
+                &self.0.port_id
+            }
+            pub fn channel_id(&self) -> &ChannelId {
+                &self.0.channel_id
+            }
+            pub fn upgrade_sequence(&self) -> u64 {
+                self.0.upgrade_sequence
+            }
+            pub fn height(&self) -> Height {
+                self.0.height
+            }
+            pub fn set_height(&mut self, height: Height) {
+                self.0.height = height;
+            }
+        }
+
+        impl From<UpgradeAttributes> for $event {
+            fn from(attrs: UpgradeAttributes) -> Self {
+                Self(attrs)
+            }
+        }
+
+        impl From<$event> for IbcEvent {
+            fn from(v: $event) -> Self {
+                IbcEvent::$variant(v)
+            }
+        }
+
+        impl EventType for $event {
+            fn event_type() -> IbcEventType {
+                IbcEventType::$variant
+            }
+        }
+
+        impl From<$event> for AbciEvent {
+            fn from(v: $event) -> Self {
+                let attributes = Vec::<EventAttribute>::from(v.0);
+                let kind = <$event>::event_type().as_str().to_string();
+                AbciEvent {
+                    kind,
+                    attributes,
+                }
+            }
+        })+
+    };
+}
+
+impl_upgrade_event!(
+	UpgradeInit => UpgradeInitChannel,
+	UpgradeTry => UpgradeTryChannel,
+	UpgradeAck => UpgradeAckChannel,
+	UpgradeConfirm => UpgradeConfirmChannel,
+	UpgradeOpen => UpgradeOpenChannel,
+	UpgradeTimeout => UpgradeTimeoutChannel,
+	UpgradeCancel => UpgradeCancelChannel,
+	UpgradeError => UpgradeErrorChannel
+);
+
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct SendPacket {
 	pub height: Height,
@@ -17,11 +17,15 @@
 use crate::{
 	core::{
 		ics04_channel::{
-			channel::ChannelEnd,
+			channel::{ChannelEnd, State},
 			error::Error,
-			events::WriteAcknowledgement,
-			msgs::{ChannelMsg, PacketMsg},
+			events::{UpgradeAttributes, WriteAcknowledgement},
+			msgs::{
+				chan_upgrade_confirm::MsgChannelUpgradeConfirm,
+				chan_upgrade_open::MsgChannelUpgradeOpen, ChannelMsg, PacketMsg,
+			},
 			packet::PacketResult,
+			upgrade::{ErrorReceipt, Upgrade, UpgradeFields, UpgradeTimeout},
 		},
 		ics24_host::identifier::{ChannelId, PortId},
 		ics26_routing::context::{
@@ -30,6 +34,7 @@ use crate::{
 	},
 	events::IbcEvent,
 	handler::{HandlerOutput, HandlerOutputBuilder},
+	Height,
 };
 use alloc::string::ToString;
 use core::fmt::Debug;
@@ -41,6 +46,13 @@ pub mod chan_open_ack;
 pub mod chan_open_confirm;
 pub mod chan_open_init;
 pub mod chan_open_try;
+pub mod chan_upgrade_ack;
+pub mod chan_upgrade_cancel;
+pub mod chan_upgrade_confirm;
+pub mod chan_upgrade_init;
+pub mod chan_upgrade_open;
+pub mod chan_upgrade_timeout;
+pub mod chan_upgrade_try;
 pub mod recv_packet;
 pub mod send_packet;
 pub mod timeout;
@@ -65,6 +77,126 @@ pub struct ChannelResult {
 	pub channel_id: ChannelId,
 	pub channel_id_state: ChannelIdState,
 	pub channel_end: ChannelEnd,
+	/// Changes to the upgrade state of the channel, only set by the upgrade handshake handlers.
+	pub upgrade: Option<UpgradeResult>,
+}
+
+/// Outcome of an upgrade handshake step.
+#[derive(Clone, Debug)]
+pub enum UpgradeResult {
+	/// The handshake is in progress, store the upgrade and optionally the counterparty's upgrade.
+	Pending { upgrade: Upgrade, counterparty_upgrade: Option<Upgrade> },
+	/// The channel end was upgraded, the stored upgrades can be pruned.
+	Completed,
+	/// The upgrade was aborted, prune the stored upgrades and write the error receipt.
+	Aborted(ErrorReceipt),
+}
+
+/// Moves `channel_end` into `FLUSHING`, recording the last sequence that must be flushed and the
+/// deadline for the counterparty to finish the handshake.
+pub(crate) fn start_flushing<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	port_channel_id: &(PortId, ChannelId),
+	channel_end: &mut ChannelEnd,
+	upgrade: &mut Upgrade,
+) -> Result<(), Error> {
+	let timestamp = (ctx.host_timestamp() + ctx.upgrade_timeout_period())
+		.map_err(|_| Error::invalid_upgrade("upgrade timeout overflows".to_string()))?;
+	upgrade.timeout = UpgradeTimeout::new(Height::zero(), timestamp);
+	upgrade.next_sequence_send = ctx.get_next_sequence_send(port_channel_id)?;
+	channel_end.set_state(State::Flushing);
+	Ok(())
+}
+
+/// Checks that both ends proposed the same ordering and version, and connection hops that
+/// are counterparties of each other.
+pub(crate) fn upgrades_compatible<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	fields: &UpgradeFields,
+	counterparty_fields: &UpgradeFields,
+) -> Result<bool, Error> {
+	let conn = ctx
+		.connection_end(&fields.connection_hops[0])
+		.map_err(Error::ics03_connection)?;
+	Ok(fields.ordering == counterparty_fields.ordering &&
+		fields.version == counterparty_fields.version &&
+		conn.counterparty().connection_id() == counterparty_fields.connection_hops.first())
+}
+
+/// Restores `channel_end` to `OPEN` and records why the upgrade with the given sequence failed.
+pub(crate) fn abort_upgrade(
+	channel_end: &mut ChannelEnd,
+	sequence: u64,
+	reason: impl ToString,
+) -> UpgradeResult {
+	channel_end.set_state(State::Open);
+	UpgradeResult::Aborted(ErrorReceipt::new(sequence, reason.to_string()))
+}
+
+/// Effect of acknowledging or timing out a packet sent on a channel that is flushing an upgrade.
+#[derive(Clone, Debug)]
+pub enum FlushResult {
+	/// Move the channel to `FLUSHCOMPLETE` if no packets are left in flight.
+	Continue,
+	/// Flushing could not complete in time, prune the upgrade and write the error receipt.
+	Aborted(ErrorReceipt),
+}
+
+/// Decides how a packet acknowledgement or timeout affects a `FLUSHING` channel end. The
+/// upgrade is aborted, and `channel_end` restored to `OPEN`, if the counterparty's upgrade
+/// timeout has passed on this chain.
+pub(crate) fn packet_flush_result<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	port_channel_id: &(PortId, ChannelId),
+	channel_end: &mut ChannelEnd,
+) -> Option<FlushResult> {
+	if !channel_end.state_matches(&State::Flushing) {
+		return None
+	}
+	match ctx.counterparty_channel_upgrade(port_channel_id) {
+		Ok(counterparty_upgrade)
+			if counterparty_upgrade
+				.timeout
+				.has_passed(ctx.host_height(), &ctx.host_timestamp()) =>
+		{
+			channel_end.set_state(State::Open);
+			Some(FlushResult::Aborted(ErrorReceipt::new(
+				channel_end.upgrade_sequence(),
+				"counterparty upgrade timed out while flushing".to_string(),
+			)))
+		},
+		_ => Some(FlushResult::Continue),
+	}
+}
+
+/// A packet timeout closes an ordered channel, which aborts any upgrade it was flushing.
+pub(crate) fn abort_flush_on_close(
+	channel_end: &ChannelEnd,
+	flush: Option<FlushResult>,
+) -> Option<FlushResult> {
+	flush.map(|flush| match flush {
+		FlushResult::Continue => FlushResult::Aborted(ErrorReceipt::new(
+			channel_end.upgrade_sequence(),
+			"ordered channel closed by a packet timeout while flushing".to_string(),
+		)),
+		aborted => aborted,
+	})
+}
+
+pub(crate) fn upgrade_event_attributes<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	channel_end: &ChannelEnd,
+) -> UpgradeAttributes {
+	UpgradeAttributes {
+		height: ctx.host_height(),
+		port_id: port_id.clone(),
+		channel_id: *channel_id,
+		counterparty_port_id: channel_end.counterparty().port_id.clone(),
+		counterparty_channel_id: channel_end.counterparty().channel_id,
+		upgrade_sequence: channel_end.upgrade_sequence(),
+	}
 }
 
 pub fn channel_validate<Ctx>(ctx: &Ctx, msg: &ChannelMsg) -> Result<ModuleId, Error>
@@ -96,6 +228,13 @@ where
 		ChannelMsg::ChannelOpenConfirm(msg) => chan_open_confirm::process::<_>(ctx, msg),
 		ChannelMsg::ChannelCloseInit(msg) => chan_close_init::process(ctx, msg),
 		ChannelMsg::ChannelCloseConfirm(msg) => chan_close_confirm::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeInit(msg) => chan_upgrade_init::process(ctx, msg),
+		ChannelMsg::ChannelUpgradeTry(msg) => chan_upgrade_try::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeAck(msg) => chan_upgrade_ack::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeConfirm(msg) => chan_upgrade_confirm::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeOpen(msg) => chan_upgrade_open::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeTimeout(msg) => chan_upgrade_timeout::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeCancel(msg) => chan_upgrade_cancel::process::<_>(ctx, msg),
 	}?;
 	let HandlerOutput { result, log, events } = output;
 	let builder = HandlerOutput::builder().with_log(log).with_events(events);
@@ -172,6 +311,60 @@ where
 			&result.channel_id,
 			&msg.signer,
 		)?,
+		ChannelMsg::ChannelUpgradeInit(msg) => {
+			if let Some(UpgradeResult::Pending { upgrade, .. }) = result.upgrade.as_mut() {
+				upgrade.fields.version = cb.on_chan_upgrade_init(
+					&ctx_clone,
+					module_output,
+					&msg.port_id,
+					&msg.channel_id,
+					upgrade.fields.ordering,
+					&upgrade.fields.connection_hops,
+					&upgrade.fields.version,
+				)?;
+			}
+		},
+		ChannelMsg::ChannelUpgradeTry(msg) => {
+			if let Some(UpgradeResult::Pending { upgrade, .. }) = result.upgrade.as_mut() {
+				upgrade.fields.version = cb.on_chan_upgrade_try(
+					&ctx_clone,
+					module_output,
+					&msg.port_id,
+					&msg.channel_id,
+					upgrade.fields.ordering,
+					&upgrade.fields.connection_hops,
+					&upgrade.fields.version,
+				)?;
+			}
+		},
+		ChannelMsg::ChannelUpgradeAck(msg) =>
+			if let Some(UpgradeResult::Pending { .. }) = result.upgrade {
+				cb.on_chan_upgrade_ack(
+					&ctx_clone,
+					module_output,
+					&msg.port_id,
+					&msg.channel_id,
+					&msg.counterparty_upgrade.fields.version,
+				)?;
+			},
+		ChannelMsg::ChannelUpgradeConfirm(MsgChannelUpgradeConfirm {
+			port_id, channel_id, ..
+		}) |
+		ChannelMsg::ChannelUpgradeOpen(MsgChannelUpgradeOpen { port_id, channel_id, .. }) =>
+			if let Some(UpgradeResult::Completed) = result.upgrade {
+				cb.on_chan_upgrade_open(
+					&ctx_clone,
+					module_output,
+					port_id,
+					channel_id,
+					result.channel_end.ordering,
+					&result.channel_end.connection_hops,
+					&result.channel_end.version,
+				)?;
+			},
+		// Aborted upgrades leave the channel end untouched, so the application has nothing to
+		// restore.
+		ChannelMsg::ChannelUpgradeTimeout(_) | ChannelMsg::ChannelUpgradeCancel(_) => {},
 	}
 	Ok(result)
 }
@@ -237,8 +430,10 @@ where
 			let ack = cb
 				.on_recv_packet(&ctx_clone, module_output, &mut packet, &msg.signer)
 				.map_err(|e| Error::app_module(e.to_string()))?;
+			// An empty acknowledgement is written asynchronously by the module once it is known,
+			// e.g. after a forwarded transfer has been acknowledged by the next chain
 			if ack.as_ref().is_empty() {
-				return Err(Error::invalid_acknowledgement())
+				return Ok(())
 			}
 			// NOTE: IBC app modules or middlewares might have written the acknowledgement
 			// synchronously on the OnRecvPacket callback so we only write the acknowledgement if it
@@ -280,3 +475,69 @@ where
 	};
 	Ok(())
 }
+
+#[cfg(test)]
+pub mod test_util {
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				msgs::test_util::get_dummy_raw_counterparty,
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				upgrade::{test_util::get_dummy_raw_upgrade, Upgrade},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		},
+		mock::{
+			client_state::MockClientState,
+			context::{MockClientTypes, MockContext},
+		},
+		prelude::*,
+		timestamp::ZERO_DURATION,
+	};
+
+	/// Returns a mock context with an open connection and the default `ics20` channel end in
+	/// `state` at upgrade sequence `1`, for testing only! The client's latest height is the
+	/// host height, so it can be used as the proof height.
+	pub fn get_dummy_upgrade_context(state: State) -> MockContext<MockClientTypes> {
+		let client_id = ClientId::new(&MockClientState::client_type(), 24).unwrap();
+
+		let conn_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			client_id.clone(),
+			ConnectionCounterparty::try_from(get_dummy_raw_counterparty()).unwrap(),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let chan_end = ChannelEnd::new(
+			state,
+			Order::Unordered,
+			Counterparty::new(PortId::default(), Some(ChannelId::default())),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		)
+		.with_upgrade_sequence(1);
+
+		let context = MockContext::<MockClientTypes>::default();
+		let client_height = context.host_height();
+
+		context
+			.with_client(&client_id, client_height)
+			.with_connection(ConnectionId::default(), conn_end)
+			.with_channel(PortId::default(), ChannelId::default(), chan_end)
+			.with_send_sequence(PortId::default(), ChannelId::default(), 1.into())
+	}
+
+	/// Returns the dummy upgrade to `ics20-2` timing out at the given height, for testing only!
+	pub fn get_dummy_upgrade(timeout_height: u64) -> Upgrade {
+		Upgrade::try_from(get_dummy_raw_upgrade(timeout_height)).unwrap()
+	}
+}
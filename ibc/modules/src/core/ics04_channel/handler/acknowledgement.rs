@@ -16,10 +16,12 @@ use crate::{
 	core::{
 		ics03_connection::connection::State as ConnectionState,
 		ics04_channel::{
-			channel::{Counterparty, Order, State},
+			channel::{ChannelEnd, Counterparty, Order, State},
 			error::Error,
 			events::AcknowledgePacket,
-			handler::verify::verify_packet_acknowledgement_proofs,
+			handler::{
+				packet_flush_result, verify::verify_packet_acknowledgement_proofs, FlushResult,
+			},
 			msgs::acknowledgement::MsgAcknowledgement,
 			packet::{PacketResult, Sequence},
 		},
@@ -38,6 +40,10 @@ pub struct AckPacketResult {
 	pub channel_id: ChannelId,
 	pub seq: Sequence,
 	pub seq_number: Option<Sequence>,
+	/// The channel end to store, set when the packet aborted the upgrade the channel was
+	/// flushing.
+	pub channel: Option<ChannelEnd>,
+	pub flush: Option<FlushResult>,
 }
 
 pub fn process<Ctx: ReaderContext>(
@@ -48,10 +54,13 @@ pub fn process<Ctx: ReaderContext>(
 
 	let packet = &msg.packet;
 
-	let source_channel_end =
-		ctx.channel_end(&(packet.source_port.clone(), packet.source_channel))?;
+	let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+	let mut source_channel_end = ctx.channel_end(&port_channel_id)?;
 
-	if !source_channel_end.state_matches(&State::Open) {
+	// In-flight packets are still acknowledged while the channel is flushing.
+	if !source_channel_end.state_matches(&State::Open) &&
+		!source_channel_end.state_matches(&State::Flushing)
+	{
 		return Err(Error::channel_closed(packet.source_channel))
 	}
 
@@ -90,8 +99,10 @@ pub fn process<Ctx: ReaderContext>(
 	}
 
 	// Verify the acknowledgement proof
+	let client_state = ctx.client_state(connection_end.client_id()).map_err(Error::ics02_client)?;
 	verify_packet_acknowledgement_proofs::<Ctx>(
 		ctx,
+		client_state,
 		msg.proofs.height(),
 		packet,
 		msg.acknowledgement.clone(),
@@ -99,6 +110,12 @@ pub fn process<Ctx: ReaderContext>(
 		&msg.proofs,
 	)?;
 
+	let flush = packet_flush_result(ctx, &port_channel_id, &mut source_channel_end);
+	let channel =
+		matches!(flush, Some(FlushResult::Aborted(_))).then(|| source_channel_end.clone());
+
+	// Channels that allow timeouts skip the sequences of timed out packets, so their
+	// acknowledgements are not tracked through the next sequence ack.
 	let result = if source_channel_end.order_matches(&Order::Ordered) {
 		let next_seq_ack =
 			ctx.get_next_sequence_ack(&(packet.source_port.clone(), packet.source_channel))?;
@@ -112,6 +129,8 @@ pub fn process<Ctx: ReaderContext>(
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
 			seq_number: Some(next_seq_ack.increment()),
+			channel,
+			flush,
 		})
 	} else {
 		PacketResult::Ack(AckPacketResult {
@@ -119,6 +138,8 @@ pub fn process<Ctx: ReaderContext>(
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
 			seq_number: None,
+			channel,
+			flush,
 		})
 	};
 
@@ -147,14 +168,17 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
-				context::ChannelReader,
-				handler::acknowledgement::process,
+				context::{ChannelKeeper, ChannelReader},
+				handler::{
+					acknowledgement::process,
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context},
+				},
 				msgs::acknowledgement::{
 					test_util::get_dummy_raw_msg_acknowledgement, MsgAcknowledgement,
 				},
 				Version,
 			},
-			ics24_host::identifier::{ClientId, ConnectionId},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 		},
 		events::IbcEvent,
 		mock::context::{MockClientTypes, MockContext},
@@ -275,4 +299,70 @@ mod tests {
 			}
 		}
 	}
+
+	/// Returns a context whose default channel is flushing an upgrade, with the acknowledged
+	/// packet as the only one in flight and the counterparty upgrade timing out at
+	/// `counterparty_timeout_height`.
+	fn flushing_context(
+		msg: &MsgAcknowledgement,
+		counterparty_timeout_height: u64,
+	) -> MockContext<MockClientTypes> {
+		let context = get_dummy_upgrade_context(State::Flushing);
+		let packet = &msg.packet;
+		let commitment = context.packet_commitment(
+			packet.data.clone(),
+			packet.timeout_height,
+			packet.timeout_timestamp,
+		);
+		let timeout_height = context.host_height().revision_height + 100;
+		context
+			.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				commitment,
+			)
+			.with_channel_upgrade(
+				PortId::default(),
+				ChannelId::default(),
+				get_dummy_upgrade(timeout_height),
+			)
+			.with_counterparty_channel_upgrade(
+				PortId::default(),
+				ChannelId::default(),
+				get_dummy_upgrade(counterparty_timeout_height),
+			)
+	}
+
+	#[test]
+	fn ack_of_last_inflight_packet_completes_flush() {
+		let height = MockContext::<MockClientTypes>::default().host_height().revision_height;
+		let msg = MsgAcknowledgement::try_from(get_dummy_raw_msg_acknowledgement(height)).unwrap();
+		let mut context = flushing_context(&msg, height + 100);
+		let port_channel_id = (PortId::default(), ChannelId::default());
+
+		let output = process(&context, &msg).unwrap();
+		context.store_packet_result(output.result).unwrap();
+
+		let channel_end = context.channel_end(&port_channel_id).unwrap();
+		assert_eq!(channel_end.state, State::FlushComplete);
+		assert!(context.channel_upgrade(&port_channel_id).is_ok());
+	}
+
+	#[test]
+	fn ack_after_counterparty_upgrade_timeout_aborts_upgrade() {
+		let height = MockContext::<MockClientTypes>::default().host_height().revision_height;
+		let msg = MsgAcknowledgement::try_from(get_dummy_raw_msg_acknowledgement(height)).unwrap();
+		let mut context = flushing_context(&msg, 1);
+		let port_channel_id = (PortId::default(), ChannelId::default());
+
+		let output = process(&context, &msg).unwrap();
+		context.store_packet_result(output.result).unwrap();
+
+		let channel_end = context.channel_end(&port_channel_id).unwrap();
+		assert_eq!(channel_end.state, State::Open);
+		assert!(context.channel_upgrade(&port_channel_id).is_err());
+		assert!(context.counterparty_channel_upgrade(&port_channel_id).is_err());
+		assert_eq!(context.channel_upgrade_error(&port_channel_id).unwrap().sequence, 1);
+	}
 }
@@ -78,8 +78,10 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		channel_end.version().clone(),
 	);
 
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
 	verify_channel_proofs::<Ctx>(
 		ctx,
+		client_state,
 		msg.proofs.height(),
 		&channel_end,
 		&conn,
@@ -106,6 +108,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		channel_id: msg.channel_id,
 		channel_id_state: ChannelIdState::Reused,
 		channel_end,
+		upgrade: None,
 	};
 
 	output.emit(IbcEvent::CloseConfirmChannel(
@@ -83,8 +83,10 @@ pub(crate) fn process<Ctx: ReaderContext>(
 	channel_end.set_counterparty_channel_id(msg.counterparty_channel_id);
 
 	// 2. Verify proofs
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
 	verify_channel_proofs::<Ctx>(
 		ctx,
+		client_state,
 		msg.proofs.height(),
 		&channel_end,
 		&conn,
@@ -112,6 +114,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		channel_id: msg.channel_id,
 		channel_id_state: ChannelIdState::Reused,
 		channel_end,
+		upgrade: None,
 	};
 
 	output.emit(IbcEvent::OpenAckChannel(
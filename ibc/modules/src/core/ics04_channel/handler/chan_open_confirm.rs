@@ -81,8 +81,10 @@ where
 		channel_end.version().clone(),
 	);
 	// 2. Verify proofs
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
 	verify_channel_proofs::<Ctx>(
 		ctx,
+		client_state,
 		msg.proofs.height(),
 		&channel_end,
 		&conn,
@@ -110,6 +112,7 @@ where
 		channel_id: msg.channel_id,
 		channel_id_state: ChannelIdState::Reused,
 		channel_end,
+		upgrade: None,
 	};
 
 	output.emit(IbcEvent::OpenConfirmChannel(
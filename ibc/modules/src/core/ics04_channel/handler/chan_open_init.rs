@@ -51,7 +51,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		_ => return Err(Error::invalid_version_length_connection()),
 	};
 
-	let channel_feature = msg.channel.ordering().to_string();
+	let channel_feature = msg.channel.ordering().connection_feature().to_string();
 	if !version.is_supported_feature(channel_feature) {
 		return Err(Error::channel_feature_not_suported_by_connection())
 	}
@@ -86,6 +86,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		channel_id: chan_id,
 		channel_end: new_channel_end,
 		channel_id_state: ChannelIdState::Generated,
+		upgrade: None,
 	};
 
 	output.emit(IbcEvent::OpenInitChannel(
@@ -78,7 +78,7 @@ where
 		_ => return Err(Error::invalid_version_length_connection()),
 	};
 
-	let channel_feature = msg.channel.ordering().to_string();
+	let channel_feature = msg.channel.ordering().connection_feature().to_string();
 	if !version.is_supported_feature(channel_feature) {
 		return Err(Error::channel_feature_not_suported_by_connection())
 	}
@@ -104,8 +104,10 @@ where
 	);
 
 	// 2. Actual proofs are verified now.
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
 	verify_channel_proofs::<Ctx>(
 		ctx,
+		client_state,
 		msg.proofs.height(),
 		&new_channel_end,
 		&conn,
@@ -132,6 +134,7 @@ where
 		channel_id_state: ChannelIdState::Generated,
 		channel_id,
 		channel_end: new_channel_end,
+		upgrade: None,
 	};
 
 	output.emit(IbcEvent::OpenTryChannel(
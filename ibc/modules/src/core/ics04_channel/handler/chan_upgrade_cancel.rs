@@ -0,0 +1,173 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeCancel`.
+
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::State,
+			error::Error,
+			handler::{
+				upgrade_event_attributes, verify::verify_channel_upgrade_error_proofs,
+				ChannelIdState, ChannelResult, UpgradeResult,
+			},
+			msgs::chan_upgrade_cancel::MsgChannelUpgradeCancel,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeCancel,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	// There must be an upgrade to cancel.
+	ctx.channel_upgrade(&port_channel_id)?;
+
+	// Once flushing completed on this end, only a timeout can abort the upgrade.
+	if channel_end.state_matches(&State::FlushComplete) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	if msg.error_receipt.sequence < channel_end.upgrade_sequence() {
+		return Err(Error::invalid_upgrade_sequence(
+			channel_end.upgrade_sequence(),
+			msg.error_receipt.sequence,
+		))
+	}
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
+	verify_channel_upgrade_error_proofs::<Ctx>(
+		ctx,
+		client_state,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&msg.error_receipt,
+		msg.proofs.object_proof(),
+	)?;
+
+	output.log("success: channel upgrade cancel ");
+
+	// Adopt the counterparty's sequence so the next upgrade attempt starts in sync.
+	channel_end.set_upgrade_sequence(msg.error_receipt.sequence);
+	channel_end.set_state(State::Open);
+
+	output.emit(IbcEvent::UpgradeCancelChannel(
+		upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+	));
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult::Aborted(msg.error_receipt.clone())),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics04_channel::{
+				channel::State,
+				handler::{
+					channel_dispatch,
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context},
+					UpgradeResult,
+				},
+				msgs::{
+					chan_upgrade_cancel::{
+						test_util::get_dummy_raw_msg_chan_upgrade_cancel, MsgChannelUpgradeCancel,
+					},
+					ChannelMsg,
+				},
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+	};
+
+	fn setup(state: State) -> (MockContext<MockClientTypes>, MsgChannelUpgradeCancel) {
+		let context = get_dummy_upgrade_context(state);
+		let proof_height = context.host_height().revision_height;
+		let context = context.with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(proof_height + 100),
+		);
+		let msg =
+			MsgChannelUpgradeCancel::try_from(get_dummy_raw_msg_chan_upgrade_cancel(proof_height))
+				.unwrap();
+		(context, msg)
+	}
+
+	#[test]
+	fn chan_upgrade_cancel_restores_channel() {
+		let (context, msg) = setup(State::Flushing);
+
+		let (handler_output_builder, result) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeCancel(msg.clone())).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert_eq!(result.channel_end.state, State::Open);
+		assert_eq!(result.channel_end.upgrade_sequence(), msg.error_receipt.sequence);
+		assert!(matches!(
+			result.upgrade,
+			Some(UpgradeResult::Aborted(ref receipt)) if receipt == &msg.error_receipt
+		));
+		assert!(handler_output
+			.events
+			.iter()
+			.all(|event| matches!(event, &IbcEvent::UpgradeCancelChannel(_))));
+	}
+
+	#[test]
+	fn chan_upgrade_cancel_rejects_outdated_error_receipt() {
+		let (context, mut msg) = setup(State::Flushing);
+		msg.error_receipt.sequence = 0;
+
+		assert!(channel_dispatch(&context, &ChannelMsg::ChannelUpgradeCancel(msg)).is_err());
+	}
+
+	#[test]
+	fn chan_upgrade_cancel_rejects_flush_complete_channel() {
+		let (context, msg) = setup(State::FlushComplete);
+
+		assert!(channel_dispatch(&context, &ChannelMsg::ChannelUpgradeCancel(msg)).is_err());
+	}
+}
@@ -0,0 +1,240 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeConfirm`.
+
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, State},
+			error::Error,
+			handler::{
+				abort_upgrade, upgrade_event_attributes,
+				verify::{verify_channel_proofs, verify_channel_upgrade_proofs},
+				ChannelIdState, ChannelResult, UpgradeResult,
+			},
+			msgs::chan_upgrade_confirm::MsgChannelUpgradeConfirm,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeConfirm,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	if !channel_end.state_matches(&State::Flushing) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	let upgrade = ctx.channel_upgrade(&port_channel_id)?;
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	let ccid = conn.counterparty().connection_id().ok_or_else(|| {
+		Error::undefined_connection_counterparty(channel_end.connection_hops()[0].clone())
+	})?;
+
+	let expected_channel_end = ChannelEnd::new(
+		msg.counterparty_channel_state,
+		*channel_end.ordering(),
+		Counterparty::new(msg.port_id.clone(), Some(msg.channel_id)),
+		vec![ccid.clone()],
+		channel_end.version().clone(),
+	)
+	.with_upgrade_sequence(channel_end.upgrade_sequence());
+
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
+	let client_state = verify_channel_proofs::<Ctx>(
+		ctx,
+		client_state,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		msg.proofs.object_proof(),
+	)?;
+
+	let upgrade_proof =
+		msg.proofs.other_proof().as_ref().ok_or_else(Error::missing_upgrade_proof)?;
+
+	verify_channel_upgrade_proofs::<Ctx>(
+		ctx,
+		client_state,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&msg.counterparty_upgrade,
+		upgrade_proof,
+	)?;
+
+	let result = if msg
+		.counterparty_upgrade
+		.timeout
+		.has_passed(ctx.host_height(), &ctx.host_timestamp())
+	{
+		output.log("channel upgrade confirm: counterparty upgrade timed out");
+		output.emit(IbcEvent::UpgradeErrorChannel(
+			upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+		));
+		let sequence = channel_end.upgrade_sequence();
+		abort_upgrade(&mut channel_end, sequence, "counterparty upgrade timed out")
+	} else {
+		if !ctx.has_inflight_packets(&port_channel_id)? {
+			channel_end.set_state(State::FlushComplete);
+		}
+		output.log("success: channel upgrade confirm ");
+		output.emit(IbcEvent::UpgradeConfirmChannel(
+			upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+		));
+
+		// Both ends finished flushing, the upgrade can be applied right away.
+		if channel_end.state_matches(&State::FlushComplete) &&
+			msg.counterparty_channel_state == State::FlushComplete
+		{
+			upgrade.fields.apply(&mut channel_end);
+			channel_end.set_state(State::Open);
+			output.emit(IbcEvent::UpgradeOpenChannel(
+				upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+			));
+			UpgradeResult::Completed
+		} else {
+			UpgradeResult::Pending {
+				upgrade,
+				counterparty_upgrade: Some(msg.counterparty_upgrade.clone()),
+			}
+		}
+	};
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(result),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics02_client::{context::ClientReader, height::Height},
+			ics04_channel::{
+				channel::State,
+				handler::{
+					channel_dispatch,
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context},
+					UpgradeResult,
+				},
+				msgs::{
+					chan_upgrade_confirm::{
+						test_util::get_dummy_raw_msg_chan_upgrade_confirm, MsgChannelUpgradeConfirm,
+					},
+					ChannelMsg,
+				},
+				upgrade::UpgradeTimeout,
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+		timestamp::Timestamp,
+	};
+
+	fn setup() -> (MockContext<MockClientTypes>, MsgChannelUpgradeConfirm) {
+		let context = get_dummy_upgrade_context(State::Flushing);
+		let proof_height = context.host_height().revision_height;
+		let context = context.with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(proof_height + 100),
+		);
+		let msg = MsgChannelUpgradeConfirm::try_from(get_dummy_raw_msg_chan_upgrade_confirm(
+			proof_height,
+		))
+		.unwrap();
+		(context, msg)
+	}
+
+	#[test]
+	fn chan_upgrade_confirm_opens_when_both_ends_flushed() {
+		let (context, msg) = setup();
+
+		let (handler_output_builder, result) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeConfirm(msg.clone())).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert_eq!(result.channel_end.state, State::Open);
+		assert_eq!(result.channel_end.version(), &msg.counterparty_upgrade.fields.version);
+		assert!(matches!(result.upgrade, Some(UpgradeResult::Completed)));
+		assert!(handler_output
+			.events
+			.iter()
+			.any(|event| matches!(event, &IbcEvent::UpgradeOpenChannel(_))));
+	}
+
+	#[test]
+	fn chan_upgrade_confirm_waits_for_inflight_packets() {
+		let (context, msg) = setup();
+		let context = context.with_packet_commitment(
+			PortId::default(),
+			ChannelId::default(),
+			1.into(),
+			vec![0].into(),
+		);
+
+		let (_, result) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeConfirm(msg)).unwrap();
+
+		assert_eq!(result.channel_end.state, State::Flushing);
+		assert!(matches!(
+			result.upgrade,
+			Some(UpgradeResult::Pending { counterparty_upgrade: Some(_), .. })
+		));
+	}
+
+	#[test]
+	fn chan_upgrade_confirm_aborts_timed_out_upgrade() {
+		let (context, mut msg) = setup();
+		msg.counterparty_upgrade.timeout =
+			UpgradeTimeout::new(Height::new(0, 1), Timestamp::none());
+
+		let (_, result) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeConfirm(msg)).unwrap();
+
+		assert_eq!(result.channel_end.state, State::Open);
+		assert!(matches!(
+			result.upgrade,
+			Some(UpgradeResult::Aborted(ref receipt)) if receipt.sequence == 1
+		));
+	}
+}
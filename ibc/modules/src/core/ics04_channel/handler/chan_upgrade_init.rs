@@ -0,0 +1,189 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeInit`.
+
+use crate::{
+	core::{
+		ics03_connection::connection::State as ConnectionState,
+		ics04_channel::{
+			channel::State,
+			error::Error,
+			handler::{upgrade_event_attributes, ChannelIdState, ChannelResult, UpgradeResult},
+			msgs::chan_upgrade_init::MsgChannelUpgradeInit,
+			packet::Sequence,
+			upgrade::{Upgrade, UpgradeTimeout},
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeInit,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let mut channel_end = ctx.channel_end(&(msg.port_id.clone(), msg.channel_id))?;
+
+	// Only open channels can be upgraded.
+	if !channel_end.state_matches(&State::Open) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	if msg.fields.matches(&channel_end) {
+		return Err(Error::invalid_upgrade(
+			"proposed upgrade fields are identical to the current channel end".to_string(),
+		))
+	}
+
+	// The connection the channel is upgraded to must be OPEN.
+	let conn = ctx
+		.connection_end(&msg.fields.connection_hops[0])
+		.map_err(Error::ics03_connection)?;
+
+	if !conn.state_matches(&ConnectionState::Open) {
+		return Err(Error::connection_not_open(msg.fields.connection_hops[0].clone()))
+	}
+
+	output.log("success: channel upgrade init ");
+
+	channel_end.set_upgrade_sequence(channel_end.upgrade_sequence() + 1);
+
+	// The timeout and the flushing sequence are only set once the handshake reaches FLUSHING.
+	let upgrade = Upgrade::new(msg.fields.clone(), UpgradeTimeout::default(), Sequence::default());
+
+	output.emit(IbcEvent::UpgradeInitChannel(
+		upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+	));
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult::Pending { upgrade, counterparty_upgrade: None }),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::ics04_channel::msgs::{
+			chan_upgrade_init::{
+				test_util::get_dummy_raw_msg_chan_upgrade_init, MsgChannelUpgradeInit,
+			},
+			ChannelMsg,
+		},
+		events::IbcEvent,
+		prelude::*,
+	};
+
+	use crate::core::{
+		ics03_connection::{
+			connection::{
+				ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+			},
+			msgs::test_util::get_dummy_raw_counterparty,
+			version::get_compatible_versions,
+		},
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, Order, State as ChannelState},
+			handler::{channel_dispatch, UpgradeResult},
+			Version,
+		},
+		ics24_host::identifier::{ClientId, ConnectionId},
+	};
+
+	use crate::{
+		core::ics02_client::context::ClientReader,
+		mock::{
+			client_state::MockClientState,
+			context::{MockClientTypes, MockContext},
+		},
+		timestamp::ZERO_DURATION,
+	};
+
+	fn context_with_channel(
+		msg: &MsgChannelUpgradeInit,
+		version: Version,
+	) -> MockContext<MockClientTypes> {
+		let client_id = ClientId::new(&MockClientState::client_type(), 24).unwrap();
+		let conn_id = ConnectionId::default();
+
+		let conn_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			client_id.clone(),
+			ConnectionCounterparty::try_from(get_dummy_raw_counterparty()).unwrap(),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let chan_end = ChannelEnd::new(
+			ChannelState::Open,
+			Order::Unordered,
+			Counterparty::new(msg.port_id.clone(), Some(msg.channel_id)),
+			vec![conn_id.clone()],
+			version,
+		);
+
+		let default_context = MockContext::<MockClientTypes>::default();
+		let client_consensus_state_height = default_context.host_height();
+
+		default_context
+			.with_client(&client_id, client_consensus_state_height)
+			.with_connection(conn_id, conn_end)
+			.with_channel(msg.port_id.clone(), msg.channel_id, chan_end)
+	}
+
+	#[test]
+	fn chan_upgrade_init_starts_upgrade() {
+		let msg = MsgChannelUpgradeInit::try_from(get_dummy_raw_msg_chan_upgrade_init()).unwrap();
+		let context = context_with_channel(&msg, Version::ics20());
+
+		let (handler_output_builder, result) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeInit(msg.clone())).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		// The channel stays open, only the upgrade sequence moves.
+		assert_eq!(result.channel_end.state, ChannelState::Open);
+		assert_eq!(result.channel_end.upgrade_sequence(), 1);
+		assert!(matches!(
+			result.upgrade,
+			Some(UpgradeResult::Pending { ref upgrade, counterparty_upgrade: None })
+				if upgrade.fields == msg.fields
+		));
+
+		assert!(!handler_output.events.is_empty());
+		for event in handler_output.events.iter() {
+			assert!(matches!(event, &IbcEvent::UpgradeInitChannel(_)));
+			assert_eq!(event.height(), context.host_height());
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_init_rejects_identical_fields() {
+		let msg = MsgChannelUpgradeInit::try_from(get_dummy_raw_msg_chan_upgrade_init()).unwrap();
+		// The dummy upgrade only proposes a new version, so matching it leaves nothing to upgrade.
+		let context = context_with_channel(&msg, msg.fields.version.clone());
+
+		let res = channel_dispatch(&context, &ChannelMsg::ChannelUpgradeInit(msg));
+		assert!(res.is_err());
+	}
+}
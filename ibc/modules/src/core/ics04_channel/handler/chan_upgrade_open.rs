@@ -0,0 +1,203 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeOpen`.
+
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, State},
+			error::Error,
+			handler::{
+				upgrade_event_attributes, verify::verify_channel_proofs, ChannelIdState,
+				ChannelResult, UpgradeResult,
+			},
+			msgs::chan_upgrade_open::MsgChannelUpgradeOpen,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeOpen,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	if !channel_end.state_matches(&State::FlushComplete) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	let upgrade = ctx.channel_upgrade(&port_channel_id)?;
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	let counterparty = Counterparty::new(msg.port_id.clone(), Some(msg.channel_id));
+
+	// A counterparty that is already OPEN has applied the upgrade, otherwise it must have
+	// finished flushing with the current channel parameters.
+	let expected_channel_end = if msg.counterparty_channel_state == State::Open {
+		if msg.counterparty_upgrade_sequence < channel_end.upgrade_sequence() {
+			return Err(Error::invalid_upgrade_sequence(
+				channel_end.upgrade_sequence(),
+				msg.counterparty_upgrade_sequence,
+			))
+		}
+		let upgrade_conn = ctx
+			.connection_end(&upgrade.fields.connection_hops[0])
+			.map_err(Error::ics03_connection)?;
+		let ccid = upgrade_conn.counterparty().connection_id().ok_or_else(|| {
+			Error::undefined_connection_counterparty(upgrade.fields.connection_hops[0].clone())
+		})?;
+		ChannelEnd::new(
+			State::Open,
+			upgrade.fields.ordering,
+			counterparty,
+			vec![ccid.clone()],
+			upgrade.fields.version.clone(),
+		)
+		.with_upgrade_sequence(msg.counterparty_upgrade_sequence)
+	} else {
+		let ccid = conn.counterparty().connection_id().ok_or_else(|| {
+			Error::undefined_connection_counterparty(channel_end.connection_hops()[0].clone())
+		})?;
+		ChannelEnd::new(
+			State::FlushComplete,
+			*channel_end.ordering(),
+			counterparty,
+			vec![ccid.clone()],
+			channel_end.version().clone(),
+		)
+		.with_upgrade_sequence(channel_end.upgrade_sequence())
+	};
+
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		client_state,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		msg.proofs.object_proof(),
+	)?;
+
+	output.log("success: channel upgrade open ");
+
+	upgrade.fields.apply(&mut channel_end);
+	channel_end.set_state(State::Open);
+
+	output.emit(IbcEvent::UpgradeOpenChannel(
+		upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+	));
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult::Completed),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics04_channel::{
+				channel::State,
+				handler::{
+					channel_dispatch,
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context},
+					UpgradeResult,
+				},
+				msgs::{
+					chan_upgrade_open::{
+						test_util::get_dummy_raw_msg_chan_upgrade_open, MsgChannelUpgradeOpen,
+					},
+					ChannelMsg,
+				},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+	};
+
+	fn setup(state: State) -> (MockContext<MockClientTypes>, MsgChannelUpgradeOpen) {
+		let context = get_dummy_upgrade_context(state);
+		let proof_height = context.host_height().revision_height;
+		let context = context.with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(proof_height + 100),
+		);
+		let msg =
+			MsgChannelUpgradeOpen::try_from(get_dummy_raw_msg_chan_upgrade_open(proof_height))
+				.unwrap();
+		(context, msg)
+	}
+
+	#[test]
+	fn chan_upgrade_open_applies_upgrade() {
+		let (context, msg) = setup(State::FlushComplete);
+
+		let (handler_output_builder, result) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeOpen(msg)).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert_eq!(result.channel_end.state, State::Open);
+		assert_eq!(result.channel_end.version(), &Version::new("ics20-2".to_string()));
+		assert!(matches!(result.upgrade, Some(UpgradeResult::Completed)));
+		assert!(handler_output
+			.events
+			.iter()
+			.all(|event| matches!(event, &IbcEvent::UpgradeOpenChannel(_))));
+	}
+
+	#[test]
+	fn chan_upgrade_open_applies_upgrade_after_counterparty_opened() {
+		let (context, mut msg) = setup(State::FlushComplete);
+		msg.counterparty_channel_state = State::Open;
+
+		let (_, result) = channel_dispatch(&context, &ChannelMsg::ChannelUpgradeOpen(msg)).unwrap();
+
+		assert_eq!(result.channel_end.state, State::Open);
+		assert!(matches!(result.upgrade, Some(UpgradeResult::Completed)));
+	}
+
+	#[test]
+	fn chan_upgrade_open_rejects_channel_still_flushing() {
+		let (context, msg) = setup(State::Flushing);
+
+		assert!(channel_dispatch(&context, &ChannelMsg::ChannelUpgradeOpen(msg)).is_err());
+	}
+}
@@ -0,0 +1,183 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeTimeout`.
+
+use crate::{
+	core::{
+		ics02_client::client_consensus::ConsensusState,
+		ics04_channel::{
+			channel::State,
+			error::Error,
+			handler::{
+				abort_upgrade, upgrade_event_attributes, verify::verify_channel_proofs,
+				ChannelIdState, ChannelResult,
+			},
+			msgs::chan_upgrade_timeout::MsgChannelUpgradeTimeout,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeTimeout,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	if !channel_end.is_flushing() {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	let upgrade = ctx.channel_upgrade(&port_channel_id)?;
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	// The upgrade timeout must have passed on the counterparty at the proof height.
+	let proof_height = msg.proofs.height();
+	let consensus_state = ctx
+		.consensus_state(conn.client_id(), proof_height)
+		.map_err(|_| Error::error_invalid_consensus_state())?;
+
+	if !upgrade.timeout.has_passed(proof_height, &consensus_state.timestamp()) {
+		return Err(Error::upgrade_timeout_not_reached())
+	}
+
+	// The counterparty must not have completed the upgrade before the timeout.
+	let counterparty = &msg.counterparty_channel;
+	if counterparty.state_matches(&State::FlushComplete) ||
+		(counterparty.state_matches(&State::Open) &&
+			counterparty.upgrade_sequence() == channel_end.upgrade_sequence())
+	{
+		return Err(Error::invalid_upgrade("counterparty already completed the upgrade".to_string()))
+	}
+
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		client_state,
+		proof_height,
+		&channel_end,
+		&conn,
+		counterparty,
+		msg.proofs.object_proof(),
+	)?;
+
+	output.log("success: channel upgrade timeout ");
+
+	output.emit(IbcEvent::UpgradeTimeoutChannel(
+		upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+	));
+
+	let sequence = channel_end.upgrade_sequence();
+	let upgrade_result = abort_upgrade(&mut channel_end, sequence, "upgrade timed out");
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(upgrade_result),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics04_channel::{
+				channel::State,
+				handler::{
+					channel_dispatch,
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context},
+					UpgradeResult,
+				},
+				msgs::{
+					chan_upgrade_timeout::{
+						test_util::get_dummy_raw_msg_chan_upgrade_timeout, MsgChannelUpgradeTimeout,
+					},
+					ChannelMsg,
+				},
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+	};
+
+	fn setup(timeout_height: u64) -> (MockContext<MockClientTypes>, MsgChannelUpgradeTimeout) {
+		let context = get_dummy_upgrade_context(State::Flushing);
+		let proof_height = context.host_height().revision_height;
+		let context = context.with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(timeout_height),
+		);
+		let msg = MsgChannelUpgradeTimeout::try_from(get_dummy_raw_msg_chan_upgrade_timeout(
+			proof_height,
+		))
+		.unwrap();
+		(context, msg)
+	}
+
+	#[test]
+	fn chan_upgrade_timeout_aborts_upgrade() {
+		let (context, msg) = setup(1);
+
+		let (handler_output_builder, result) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTimeout(msg)).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert_eq!(result.channel_end.state, State::Open);
+		assert!(matches!(
+			result.upgrade,
+			Some(UpgradeResult::Aborted(ref receipt)) if receipt.sequence == 1
+		));
+		assert!(handler_output
+			.events
+			.iter()
+			.all(|event| matches!(event, &IbcEvent::UpgradeTimeoutChannel(_))));
+	}
+
+	#[test]
+	fn chan_upgrade_timeout_rejects_unexpired_upgrade() {
+		let (context, msg) = setup(100);
+
+		assert!(channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTimeout(msg)).is_err());
+	}
+
+	#[test]
+	fn chan_upgrade_timeout_rejects_completed_counterparty() {
+		let (context, mut msg) = setup(1);
+		msg.counterparty_channel.set_state(State::FlushComplete);
+
+		assert!(channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTimeout(msg)).is_err());
+	}
+}
@@ -0,0 +1,258 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeTry`.
+
+use crate::{
+	core::{
+		ics03_connection::connection::State as ConnectionState,
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, State},
+			error::Error,
+			handler::{
+				abort_upgrade, start_flushing, upgrade_event_attributes, upgrades_compatible,
+				verify::{verify_channel_proofs, verify_channel_upgrade_proofs},
+				ChannelIdState, ChannelResult, UpgradeResult,
+			},
+			msgs::chan_upgrade_try::MsgChannelUpgradeTry,
+			packet::Sequence,
+			upgrade::{Upgrade, UpgradeFields, UpgradeTimeout},
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeTry,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	if !channel_end.state_matches(&State::Open) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	// If both ends initiated an upgrade (crossing hellos), our own proposal must agree on the
+	// connection. Otherwise this is an implicit upgrade init with the counterparty's proposal.
+	let mut upgrade = match ctx.channel_upgrade(&port_channel_id) {
+		Ok(upgrade) => {
+			if upgrade.fields.connection_hops != msg.proposed_upgrade_connection_hops {
+				return Err(Error::invalid_upgrade(
+					"proposed connection hops do not match the pending upgrade".to_string(),
+				))
+			}
+			upgrade
+		},
+		Err(_) => {
+			let fields = UpgradeFields::new(
+				msg.counterparty_upgrade_fields.ordering,
+				msg.proposed_upgrade_connection_hops.clone(),
+				msg.counterparty_upgrade_fields.version.clone(),
+			);
+			if fields.matches(&channel_end) {
+				return Err(Error::invalid_upgrade(
+					"proposed upgrade fields are identical to the current channel end".to_string(),
+				))
+			}
+			channel_end.set_upgrade_sequence(channel_end.upgrade_sequence() + 1);
+			Upgrade::new(fields, UpgradeTimeout::default(), Sequence::default())
+		},
+	};
+
+	// The connection the channel is upgraded to must be OPEN.
+	let upgrade_conn = ctx
+		.connection_end(&upgrade.fields.connection_hops[0])
+		.map_err(Error::ics03_connection)?;
+
+	if !upgrade_conn.state_matches(&ConnectionState::Open) {
+		return Err(Error::connection_not_open(upgrade.fields.connection_hops[0].clone()))
+	}
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	let ccid = conn.counterparty().connection_id().ok_or_else(|| {
+		Error::undefined_connection_counterparty(channel_end.connection_hops()[0].clone())
+	})?;
+
+	// The counterparty must still be OPEN, with the upgrade sequence it proposed.
+	let expected_channel_end = ChannelEnd::new(
+		State::Open,
+		*channel_end.ordering(),
+		Counterparty::new(msg.port_id.clone(), Some(msg.channel_id)),
+		vec![ccid.clone()],
+		channel_end.version().clone(),
+	)
+	.with_upgrade_sequence(msg.counterparty_upgrade_sequence);
+
+	let client_state = ctx.client_state(conn.client_id()).map_err(Error::ics02_client)?;
+	let client_state = verify_channel_proofs::<Ctx>(
+		ctx,
+		client_state,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		msg.proofs.object_proof(),
+	)?;
+
+	let counterparty_upgrade = Upgrade::new(
+		msg.counterparty_upgrade_fields.clone(),
+		UpgradeTimeout::default(),
+		Sequence::default(),
+	);
+	let upgrade_proof =
+		msg.proofs.other_proof().as_ref().ok_or_else(Error::missing_upgrade_proof)?;
+
+	verify_channel_upgrade_proofs::<Ctx>(
+		ctx,
+		client_state,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&counterparty_upgrade,
+		upgrade_proof,
+	)?;
+
+	// An outdated counterparty proposal is rejected with an error receipt the counterparty can
+	// use to cancel its upgrade and retry with a fresh sequence.
+	let result = if msg.counterparty_upgrade_sequence < channel_end.upgrade_sequence() {
+		let sequence = channel_end.upgrade_sequence() - 1;
+		let reason = Error::invalid_upgrade_sequence(
+			channel_end.upgrade_sequence(),
+			msg.counterparty_upgrade_sequence,
+		);
+		output.log("channel upgrade try: counterparty upgrade sequence is outdated");
+		output.emit(IbcEvent::UpgradeErrorChannel(
+			upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+		));
+		abort_upgrade(&mut channel_end, sequence, reason)
+	} else if !upgrades_compatible(ctx, &upgrade.fields, &counterparty_upgrade.fields)? {
+		channel_end.set_upgrade_sequence(msg.counterparty_upgrade_sequence);
+		output.log("channel upgrade try: incompatible upgrade proposals");
+		output.emit(IbcEvent::UpgradeErrorChannel(
+			upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+		));
+		abort_upgrade(
+			&mut channel_end,
+			msg.counterparty_upgrade_sequence,
+			"incompatible upgrade proposals",
+		)
+	} else {
+		channel_end.set_upgrade_sequence(msg.counterparty_upgrade_sequence);
+		start_flushing(ctx, &port_channel_id, &mut channel_end, &mut upgrade)?;
+		output.log("success: channel upgrade try ");
+		output.emit(IbcEvent::UpgradeTryChannel(
+			upgrade_event_attributes(ctx, &msg.port_id, &msg.channel_id, &channel_end).into(),
+		));
+		UpgradeResult::Pending { upgrade, counterparty_upgrade: None }
+	};
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(result),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics04_channel::{
+				channel::State,
+				handler::{channel_dispatch, test_util::get_dummy_upgrade_context, UpgradeResult},
+				msgs::{
+					chan_upgrade_try::{
+						test_util::get_dummy_raw_msg_chan_upgrade_try, MsgChannelUpgradeTry,
+					},
+					ChannelMsg,
+				},
+			},
+		},
+		events::IbcEvent,
+		prelude::*,
+	};
+
+	#[test]
+	fn chan_upgrade_try_starts_flushing() {
+		let context = get_dummy_upgrade_context(State::Open);
+		let mut msg = MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(
+			context.host_height().revision_height,
+		))
+		.unwrap();
+		msg.counterparty_upgrade_sequence = 2;
+
+		let (handler_output_builder, result) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTry(msg.clone())).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert_eq!(result.channel_end.state, State::Flushing);
+		assert_eq!(result.channel_end.upgrade_sequence(), 2);
+		assert!(matches!(
+			result.upgrade,
+			Some(UpgradeResult::Pending { ref upgrade, counterparty_upgrade: None })
+				if upgrade.fields == msg.counterparty_upgrade_fields && !upgrade.timeout.is_zero()
+		));
+		assert!(handler_output
+			.events
+			.iter()
+			.all(|event| matches!(event, &IbcEvent::UpgradeTryChannel(_))));
+	}
+
+	#[test]
+	fn chan_upgrade_try_aborts_outdated_counterparty_upgrade() {
+		let context = get_dummy_upgrade_context(State::Open);
+		let msg = MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(
+			context.host_height().revision_height,
+		))
+		.unwrap();
+
+		let (_, result) = channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTry(msg)).unwrap();
+
+		// The counterparty proposed sequence 1 while this end moved on to sequence 2.
+		assert_eq!(result.channel_end.state, State::Open);
+		assert!(matches!(
+			result.upgrade,
+			Some(UpgradeResult::Aborted(ref receipt)) if receipt.sequence == 1
+		));
+	}
+
+	#[test]
+	fn chan_upgrade_try_rejects_channel_not_open() {
+		let context = get_dummy_upgrade_context(State::Flushing);
+		let msg = MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(
+			context.host_height().revision_height,
+		))
+		.unwrap();
+
+		assert!(channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTry(msg)).is_err());
+	}
+}
@@ -50,6 +50,14 @@ pub enum RecvPacketResult {
 		next_seq_recv: Sequence,
 		packet: Packet,
 	},
+	/// The packet timed out on an `ORDERED_ALLOW_TIMEOUT` channel. It is not handed to the
+	/// module, the receive sequence moves past it and a timeout receipt is written instead.
+	Timeout {
+		port_id: PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+		next_seq_recv: Sequence,
+	},
 }
 
 pub fn process<Ctx: ReaderContext>(
@@ -63,7 +71,22 @@ pub fn process<Ctx: ReaderContext>(
 	let dest_channel_end =
 		ctx.channel_end(&(packet.destination_port.clone(), packet.destination_channel))?;
 
-	if !dest_channel_end.state_matches(&State::Open) {
+	if dest_channel_end.is_flushing() {
+		// While upgrading, only packets the counterparty sent before it started flushing may be
+		// received.
+		let counterparty_upgrade = ctx.counterparty_channel_upgrade(&(
+			packet.destination_port.clone(),
+			packet.destination_channel,
+		));
+		if let Ok(upgrade) = counterparty_upgrade {
+			if packet.sequence >= upgrade.next_sequence_send {
+				return Err(Error::invalid_channel_state(
+					packet.source_channel,
+					dest_channel_end.state,
+				))
+			}
+		}
+	} else if !dest_channel_end.state_matches(&State::Open) {
 		return Err(Error::invalid_channel_state(packet.source_channel, dest_channel_end.state))
 	}
 
@@ -84,25 +107,34 @@ pub fn process<Ctx: ReaderContext>(
 		return Err(Error::connection_not_open(dest_channel_end.connection_hops()[0].clone()))
 	}
 
+	// Channels that allow timeouts still accept packets that timed out, to move past them.
+	let allow_timeout = dest_channel_end.order_matches(&Order::OrderedAllowTimeout);
+
 	let latest_height = ctx.host_height();
-	if (!packet.timeout_height.is_zero()) && (packet.timeout_height <= latest_height) {
+	let height_timed_out =
+		(!packet.timeout_height.is_zero()) && (packet.timeout_height <= latest_height);
+	if height_timed_out && !allow_timeout {
 		return Err(Error::low_packet_height(latest_height, packet.timeout_height))
 	}
 
 	let latest_timestamp = ctx.host_timestamp();
-	if let Expiry::Expired = latest_timestamp.check_expiry(&packet.timeout_timestamp) {
+	let timestamp_timed_out =
+		matches!(latest_timestamp.check_expiry(&packet.timeout_timestamp), Expiry::Expired);
+	if timestamp_timed_out && !allow_timeout {
 		return Err(Error::low_packet_timestamp())
 	}
 
+	let client_state = ctx.client_state(connection_end.client_id()).map_err(Error::ics02_client)?;
 	verify_packet_recv_proofs::<Ctx>(
 		ctx,
+		client_state,
 		msg.proofs.height(),
 		packet,
 		&connection_end,
 		&msg.proofs,
 	)?;
 
-	let result = if dest_channel_end.order_matches(&Order::Ordered) {
+	let result = if dest_channel_end.order_matches(&Order::Ordered) || allow_timeout {
 		let next_seq_recv = ctx.get_next_sequence_recv(&(
 			packet.destination_port.clone(),
 			packet.destination_channel,
@@ -118,6 +150,16 @@ pub fn process<Ctx: ReaderContext>(
 			return Err(Error::invalid_packet_sequence(packet.sequence, next_seq_recv))
 		}
 
+		if height_timed_out || timestamp_timed_out {
+			output.log("success: packet timed out");
+			return Ok(output.with_result(PacketResult::Recv(RecvPacketResult::Timeout {
+				port_id: packet.destination_port.clone(),
+				channel_id: packet.destination_channel,
+				sequence: packet.sequence,
+				next_seq_recv: next_seq_recv.increment(),
+			})))
+		}
+
 		PacketResult::Recv(RecvPacketResult::Ordered {
 			port_id: packet.destination_port.clone(),
 			channel_id: packet.destination_channel,
@@ -180,9 +222,10 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
-				handler::recv_packet::process,
+				context::{ChannelKeeper, ChannelReader},
+				handler::recv_packet::{process, RecvPacketResult},
 				msgs::recv_packet::{test_util::get_dummy_raw_msg_recv_packet, MsgRecvPacket},
-				packet::Packet,
+				packet::{Packet, PacketResult, Receipt},
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -329,4 +372,79 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn recv_packet_timeout_on_ordered_allow_timeout_channel() {
+		let context = MockContext::default();
+		let host_height = context.latest_height().increment();
+		let client_height = host_height.increment();
+
+		let msg =
+			MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+				.unwrap();
+
+		let packet = Packet {
+			sequence: 1.into(),
+			source_port: PortId::default(),
+			source_channel: ChannelId::default(),
+			destination_port: PortId::default(),
+			destination_channel: ChannelId::default(),
+			data: Vec::new(),
+			timeout_height: client_height,
+			timeout_timestamp: Timestamp::from_nanoseconds(1).unwrap(),
+		};
+		let msg = MsgRecvPacket::new(packet.clone(), msg.proofs, get_dummy_account_id());
+
+		let dest_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::OrderedAllowTimeout,
+			Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let mut ctx = context
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(PortId::default(), ChannelId::default(), dest_channel_end)
+			.with_send_sequence(PortId::default(), ChannelId::default(), 1.into())
+			.with_recv_sequence(PortId::default(), ChannelId::default(), packet.sequence)
+			.with_height(host_height);
+
+		// A timed out packet on an allow-timeout channel is not rejected; it only advances
+		// the receive sequence and records a timeout receipt.
+		let output = process(&ctx, &msg).unwrap();
+		assert!(output.events.is_empty());
+		match &output.result {
+			PacketResult::Recv(RecvPacketResult::Timeout { sequence, next_seq_recv, .. }) => {
+				assert_eq!(*sequence, packet.sequence);
+				assert_eq!(*next_seq_recv, packet.sequence.increment());
+			},
+			result => panic!("unexpected result {:?}", result),
+		}
+
+		ctx.store_packet_result(output.result).unwrap();
+		let port_channel_id = (PortId::default(), ChannelId::default());
+		assert_eq!(
+			ctx.get_next_sequence_recv(&port_channel_id).unwrap(),
+			packet.sequence.increment()
+		);
+		assert!(matches!(
+			ctx.get_packet_receipt(&(PortId::default(), ChannelId::default(), packet.sequence)),
+			Ok(Receipt::Timeout)
+		));
+		assert_eq!(ctx.channel_end(&port_channel_id).unwrap().state, State::Open);
+	}
 }
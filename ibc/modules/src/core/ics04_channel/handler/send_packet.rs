@@ -54,6 +54,11 @@ pub fn send_packet<Ctx: ReaderContext>(
 		return Err(Error::channel_closed(packet.source_channel))
 	}
 
+	// No new packets may be sent while the channel is being upgraded.
+	if source_channel_end.is_flushing() {
+		return Err(Error::invalid_channel_state(packet.source_channel, source_channel_end.state))
+	}
+
 	let counterparty =
 		Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel));
 
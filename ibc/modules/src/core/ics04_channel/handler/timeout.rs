@@ -19,7 +19,14 @@ use crate::{
 			channel::{ChannelEnd, Counterparty, Order, State},
 			error::Error,
 			events::TimeoutPacket,
-			handler::verify::{verify_next_sequence_recv, verify_packet_receipt_absence},
+			handler::{
+				abort_flush_on_close, packet_flush_result,
+				verify::{
+					verify_next_sequence_recv, verify_packet_receipt_absence,
+					verify_packet_timeout_receipt,
+				},
+				FlushResult,
+			},
 			msgs::timeout::MsgTimeout,
 			packet::{PacketResult, Sequence},
 		},
@@ -37,7 +44,10 @@ pub struct TimeoutPacketResult {
 	pub port_id: PortId,
 	pub channel_id: ChannelId,
 	pub seq: Sequence,
+	/// The channel end to store, set when an ordered channel is closed or the packet aborted the
+	/// upgrade the channel was flushing.
 	pub channel: Option<ChannelEnd>,
+	pub flush: Option<FlushResult>,
 }
 
 pub fn process<Ctx>(ctx: &Ctx, msg: &MsgTimeout) -> HandlerResult<PacketResult, Error>
@@ -48,10 +58,13 @@ where
 
 	let packet = &msg.packet;
 
-	let mut source_channel_end =
-		ctx.channel_end(&(packet.source_port.clone(), packet.source_channel))?;
+	let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+	let mut source_channel_end = ctx.channel_end(&port_channel_id)?;
 
-	if !source_channel_end.state_matches(&State::Open) {
+	// In-flight packets may still time out while the channel is flushing.
+	if !source_channel_end.state_matches(&State::Open) &&
+		!source_channel_end.state_matches(&State::Flushing)
+	{
 		return Err(Error::channel_closed(packet.source_channel))
 	}
 
@@ -102,12 +115,18 @@ where
 		return Err(Error::incorrect_packet_commitment(packet.sequence))
 	}
 
+	let flush = packet_flush_result(ctx, &port_channel_id, &mut source_channel_end);
+	let channel =
+		matches!(flush, Some(FlushResult::Aborted(_))).then(|| source_channel_end.clone());
+
+	let client_state = ctx.client_state(connection_end.client_id()).map_err(Error::ics02_client)?;
 	let result = if source_channel_end.order_matches(&Order::Ordered) {
 		if packet.sequence < msg.next_sequence_recv {
 			return Err(Error::invalid_packet_sequence(packet.sequence, msg.next_sequence_recv))
 		}
 		verify_next_sequence_recv::<Ctx>(
 			ctx,
+			client_state,
 			msg.proofs.height(),
 			&connection_end,
 			packet.clone(),
@@ -120,11 +139,35 @@ where
 			port_id: packet.source_port.clone(),
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
+			flush: abort_flush_on_close(&source_channel_end, flush),
 			channel: Some(source_channel_end),
 		})
+	} else if source_channel_end.order_matches(&Order::OrderedAllowTimeout) {
+		// The counterparty must have moved past the packet, writing a timeout receipt for it. The
+		// channel stays open.
+		if packet.sequence >= msg.next_sequence_recv {
+			return Err(Error::invalid_packet_sequence(packet.sequence, msg.next_sequence_recv))
+		}
+		verify_packet_timeout_receipt::<Ctx>(
+			ctx,
+			client_state,
+			msg.proofs.height(),
+			&connection_end,
+			packet.clone(),
+			&msg.proofs,
+		)?;
+
+		PacketResult::Timeout(TimeoutPacketResult {
+			port_id: packet.source_port.clone(),
+			channel_id: packet.source_channel,
+			seq: packet.sequence,
+			channel,
+			flush,
+		})
 	} else {
 		verify_packet_receipt_absence::<Ctx>(
 			ctx,
+			client_state,
 			msg.proofs.height(),
 			&connection_end,
 			packet.clone(),
@@ -135,7 +178,8 @@ where
 			port_id: packet.source_port.clone(),
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
-			channel: None,
+			channel,
+			flush,
 		})
 	};
 
@@ -164,9 +208,13 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
-				context::ChannelReader,
-				handler::timeout::process,
+				context::{ChannelKeeper, ChannelReader},
+				handler::{
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context},
+					timeout::process,
+				},
 				msgs::timeout::{test_util::get_dummy_raw_msg_timeout, MsgTimeout},
+				packet::PacketResult,
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -341,4 +389,134 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn timeout_keeps_ordered_allow_timeout_channel_open() {
+		let context = MockContext::default();
+		let client_height = Height::new(0, Height::default().revision_height + 2);
+
+		let mut msg =
+			MsgTimeout::try_from(get_dummy_raw_msg_timeout(client_height.revision_height, 0))
+				.unwrap();
+		let packet = msg.packet.clone();
+		let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+
+		let commitment = context.packet_commitment(
+			packet.data.clone(),
+			packet.timeout_height,
+			packet.timeout_timestamp,
+		);
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::OrderedAllowTimeout,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let mut context = context
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(packet.source_port.clone(), packet.source_channel, channel_end)
+			.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				commitment,
+			);
+
+		// The counterparty has not moved past the packet yet.
+		msg.next_sequence_recv = packet.sequence;
+		assert!(process(&context, &msg).is_err());
+
+		// The counterparty wrote a timeout receipt and advanced its receive sequence.
+		msg.next_sequence_recv = packet.sequence.increment();
+		let output = process(&context, &msg).unwrap();
+		assert!(matches!(
+			&output.result,
+			PacketResult::Timeout(res) if res.channel.is_none() && res.seq == packet.sequence
+		));
+		context.store_packet_result(output.result).unwrap();
+
+		let channel_end = context.channel_end(&port_channel_id).unwrap();
+		assert_eq!(channel_end.state, State::Open);
+		assert!(context
+			.get_packet_commitment(&(packet.source_port, packet.source_channel, packet.sequence))
+			.is_err());
+	}
+
+	/// Returns a context whose default channel is flushing an upgrade, with the timed out packet
+	/// as the only one in flight.
+	fn flushing_context(msg: &MsgTimeout, order: Order) -> MockContext<MockClientTypes> {
+		let context = get_dummy_upgrade_context(State::Flushing);
+		let packet = &msg.packet;
+		let commitment = context.packet_commitment(
+			packet.data.clone(),
+			packet.timeout_height,
+			packet.timeout_timestamp,
+		);
+		let timeout_height = context.host_height().revision_height + 100;
+		let mut channel_end =
+			context.channel_end(&(PortId::default(), ChannelId::default())).unwrap();
+		channel_end.ordering = order;
+		context
+			.with_channel(PortId::default(), ChannelId::default(), channel_end)
+			.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				commitment,
+			)
+			.with_channel_upgrade(
+				PortId::default(),
+				ChannelId::default(),
+				get_dummy_upgrade(timeout_height),
+			)
+			.with_counterparty_channel_upgrade(
+				PortId::default(),
+				ChannelId::default(),
+				get_dummy_upgrade(timeout_height),
+			)
+	}
+
+	#[test]
+	fn timeout_of_last_inflight_packet_completes_flush() {
+		let height = MockContext::<MockClientTypes>::default().host_height().revision_height;
+		let msg = MsgTimeout::try_from(get_dummy_raw_msg_timeout(height, 0)).unwrap();
+		let mut context = flushing_context(&msg, Order::Unordered);
+		let port_channel_id = (PortId::default(), ChannelId::default());
+
+		let output = process(&context, &msg).unwrap();
+		context.store_packet_result(output.result).unwrap();
+
+		let channel_end = context.channel_end(&port_channel_id).unwrap();
+		assert_eq!(channel_end.state, State::FlushComplete);
+		assert!(context.channel_upgrade(&port_channel_id).is_ok());
+	}
+
+	#[test]
+	fn timeout_closing_ordered_channel_aborts_upgrade() {
+		let height = MockContext::<MockClientTypes>::default().host_height().revision_height;
+		let msg = MsgTimeout::try_from(get_dummy_raw_msg_timeout(height, 0)).unwrap();
+		let mut context = flushing_context(&msg, Order::Ordered);
+		let port_channel_id = (PortId::default(), ChannelId::default());
+
+		let output = process(&context, &msg).unwrap();
+		context.store_packet_result(output.result).unwrap();
+
+		let channel_end = context.channel_end(&port_channel_id).unwrap();
+		assert_eq!(channel_end.state, State::Closed);
+		assert!(context.channel_upgrade(&port_channel_id).is_err());
+		assert_eq!(context.channel_upgrade_error(&port_channel_id).unwrap().sequence, 1);
+	}
 }
@@ -19,10 +19,13 @@ use crate::{
 			error::Error,
 			events::TimeoutOnClosePacket,
 			handler::{
+				abort_flush_on_close, packet_flush_result,
 				timeout::TimeoutPacketResult,
 				verify::{
-					verify_channel_proofs, verify_next_sequence_recv, verify_packet_receipt_absence,
+					verify_channel_proofs, verify_next_sequence_recv,
+					verify_packet_receipt_absence, verify_packet_timeout_receipt,
 				},
+				FlushResult,
 			},
 			msgs::timeout_on_close::MsgTimeoutOnClose,
 			packet::PacketResult,
@@ -42,8 +45,8 @@ pub fn process<Ctx: ReaderContext>(
 
 	let packet = &msg.packet;
 
-	let source_channel_end =
-		ctx.channel_end(&(packet.source_port.clone(), packet.source_channel))?;
+	let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+	let mut source_channel_end = ctx.channel_end(&port_channel_id)?;
 
 	let counterparty =
 		Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel));
@@ -90,8 +93,11 @@ pub fn process<Ctx: ReaderContext>(
 		source_channel_end.version().clone(),
 	);
 
-	verify_channel_proofs::<Ctx>(
+	// The channel proof is verified first, the packet proof against the client state after it.
+	let client_state = ctx.client_state(connection_end.client_id()).map_err(Error::ics02_client)?;
+	let client_state = verify_channel_proofs::<Ctx>(
 		ctx,
+		client_state,
 		msg.proofs.height(),
 		&source_channel_end,
 		&connection_end,
@@ -102,12 +108,17 @@ pub fn process<Ctx: ReaderContext>(
 			.ok_or_else(|| Error::missing_channel_proof())?,
 	)?;
 
+	let flush = packet_flush_result(ctx, &port_channel_id, &mut source_channel_end);
+	let channel =
+		matches!(flush, Some(FlushResult::Aborted(_))).then(|| source_channel_end.clone());
+
 	let result = if source_channel_end.order_matches(&Order::Ordered) {
 		if packet.sequence < msg.next_sequence_recv {
 			return Err(Error::invalid_packet_sequence(packet.sequence, msg.next_sequence_recv))
 		}
 		verify_next_sequence_recv::<Ctx>(
 			ctx,
+			client_state,
 			msg.proofs.height(),
 			&connection_end,
 			packet.clone(),
@@ -115,15 +126,49 @@ pub fn process<Ctx: ReaderContext>(
 			&msg.proofs,
 		)?;
 
+		source_channel_end.state = State::Closed;
 		PacketResult::Timeout(TimeoutPacketResult {
 			port_id: packet.source_port.clone(),
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
+			flush: abort_flush_on_close(&source_channel_end, flush),
 			channel: Some(source_channel_end),
 		})
+	} else if source_channel_end.order_matches(&Order::OrderedAllowTimeout) {
+		// A packet the closed counterparty moved past must have timed out there, any later packet
+		// was never received.
+		if packet.sequence < msg.next_sequence_recv {
+			verify_packet_timeout_receipt::<Ctx>(
+				ctx,
+				client_state,
+				msg.proofs.height(),
+				&connection_end,
+				packet.clone(),
+				&msg.proofs,
+			)?;
+		} else {
+			verify_next_sequence_recv::<Ctx>(
+				ctx,
+				client_state,
+				msg.proofs.height(),
+				&connection_end,
+				packet.clone(),
+				msg.next_sequence_recv,
+				&msg.proofs,
+			)?;
+		}
+
+		PacketResult::Timeout(TimeoutPacketResult {
+			port_id: packet.source_port.clone(),
+			channel_id: packet.source_channel,
+			seq: packet.sequence,
+			channel,
+			flush,
+		})
 	} else {
 		verify_packet_receipt_absence::<Ctx>(
 			ctx,
+			client_state,
 			msg.proofs.height(),
 			&connection_end,
 			packet.clone(),
@@ -134,7 +179,8 @@ pub fn process<Ctx: ReaderContext>(
 			port_id: packet.source_port.clone(),
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
-			channel: None,
+			channel,
+			flush,
 		})
 	};
 
@@ -163,11 +209,12 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
-				context::ChannelReader,
+				context::{ChannelKeeper, ChannelReader},
 				handler::timeout_on_close::process,
 				msgs::timeout_on_close::{
 					test_util::get_dummy_raw_msg_timeout_on_close, MsgTimeoutOnClose,
 				},
+				packet::PacketResult,
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -302,4 +349,72 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn timeout_on_close_keeps_ordered_allow_timeout_channel_open() {
+		let context = MockContext::default();
+		let client_height = Height::new(0, Height::default().revision_height + 2);
+
+		let mut msg = MsgTimeoutOnClose::try_from(get_dummy_raw_msg_timeout_on_close(
+			client_height.revision_height,
+			0,
+		))
+		.unwrap();
+		let packet = msg.packet.clone();
+		let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+
+		let commitment = context.packet_commitment(
+			packet.data.clone(),
+			packet.timeout_height,
+			packet.timeout_timestamp,
+		);
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::OrderedAllowTimeout,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let mut context = context
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(packet.source_port.clone(), packet.source_channel, channel_end)
+			.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				commitment,
+			);
+
+		// Whether the closed counterparty never received the packet or advanced its receive
+		// sequence past it with a timeout receipt, the channel on this end stays open.
+		for next_sequence_recv in [packet.sequence, packet.sequence.increment()] {
+			msg.next_sequence_recv = next_sequence_recv;
+			let output = process(&context, &msg).unwrap();
+			assert!(matches!(
+				&output.result,
+				PacketResult::Timeout(res) if res.channel.is_none() && res.seq == packet.sequence
+			));
+		}
+
+		let output = process(&context, &msg).unwrap();
+		context.store_packet_result(output.result).unwrap();
+
+		let channel_end = context.channel_end(&port_channel_id).unwrap();
+		assert_eq!(channel_end.state, State::Open);
+		assert!(context
+			.get_packet_commitment(&(packet.source_port, packet.source_channel, packet.sequence))
+			.is_err());
+	}
 }
@@ -23,6 +23,7 @@ use crate::{
 			error::Error,
 			msgs::acknowledgement::Acknowledgement,
 			packet::{Packet, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::CommitmentProofBytes,
 		ics26_routing::context::ReaderContext,
@@ -33,22 +34,24 @@ use crate::{
 };
 
 /// Entry point for verifying all proofs bundled in any ICS4 message for channel protocols.
+///
+/// Like all verification functions below, the proof is verified against `client_state`, the state
+/// of the client on the connection, and the client state after consuming the proof is returned.
 pub fn verify_channel_proofs<Ctx>(
 	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
 	height: Height,
 	channel_end: &ChannelEnd,
 	connection_end: &ConnectionEnd,
 	expected_chan: &ChannelEnd,
 	proof: &CommitmentProofBytes,
-) -> Result<(), Error>
+) -> Result<Ctx::AnyClientState, Error>
 where
 	Ctx: ReaderContext,
 {
 	// This is the client which will perform proof verification.
 	let client_id = connection_end.client_id().clone();
 
-	let client_state = ctx.client_state(&client_id).map_err(Error::ics02_client)?;
-
 	// The client must not be frozen.
 	if client_state.is_frozen(ctx, &client_id) {
 		return Err(Error::frozen_client(client_id))
@@ -78,19 +81,121 @@ where
 				.ok_or_else(|| Error::missing_channel_id())?,
 			expected_chan,
 		)
-		.map_err(Error::verify_channel_failed)
+		.map_err(Error::verify_channel_failed)?;
+
+	client_def
+		.update_state_on_verified_proof(client_state, proof)
+		.map_err(Error::ics02_client)
+}
+
+/// Verifies that the counterparty of `channel_end` stored `upgrade` as its pending upgrade.
+pub fn verify_channel_upgrade_proofs<Ctx>(
+	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
+	height: Height,
+	channel_end: &ChannelEnd,
+	connection_end: &ConnectionEnd,
+	upgrade: &Upgrade,
+	proof: &CommitmentProofBytes,
+) -> Result<Ctx::AnyClientState, Error>
+where
+	Ctx: ReaderContext,
+{
+	let client_id = connection_end.client_id().clone();
+
+	// The client must not be frozen.
+	if client_state.is_frozen(ctx, &client_id) {
+		return Err(Error::frozen_client(client_id))
+	}
+
+	let consensus_state = ctx
+		.consensus_state(&client_id, height)
+		.map_err(|_| Error::error_invalid_consensus_state())?;
+
+	let client_def = client_state.client_def();
+
+	client_def
+		.verify_channel_upgrade(
+			ctx,
+			&client_id,
+			&client_state,
+			height,
+			connection_end.counterparty().prefix(),
+			proof,
+			consensus_state.root(),
+			channel_end.counterparty().port_id(),
+			channel_end
+				.counterparty()
+				.channel_id()
+				.ok_or_else(|| Error::missing_channel_id())?,
+			upgrade,
+		)
+		.map_err(Error::verify_channel_upgrade_failed)?;
+
+	client_def
+		.update_state_on_verified_proof(client_state, proof)
+		.map_err(Error::ics02_client)
+}
+
+/// Verifies that the counterparty of `channel_end` aborted its upgrade with `error_receipt`.
+pub fn verify_channel_upgrade_error_proofs<Ctx>(
+	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
+	height: Height,
+	channel_end: &ChannelEnd,
+	connection_end: &ConnectionEnd,
+	error_receipt: &ErrorReceipt,
+	proof: &CommitmentProofBytes,
+) -> Result<Ctx::AnyClientState, Error>
+where
+	Ctx: ReaderContext,
+{
+	let client_id = connection_end.client_id().clone();
+
+	// The client must not be frozen.
+	if client_state.is_frozen(ctx, &client_id) {
+		return Err(Error::frozen_client(client_id))
+	}
+
+	let consensus_state = ctx
+		.consensus_state(&client_id, height)
+		.map_err(|_| Error::error_invalid_consensus_state())?;
+
+	let client_def = client_state.client_def();
+
+	client_def
+		.verify_channel_upgrade_error(
+			ctx,
+			&client_id,
+			&client_state,
+			height,
+			connection_end.counterparty().prefix(),
+			proof,
+			consensus_state.root(),
+			channel_end.counterparty().port_id(),
+			channel_end
+				.counterparty()
+				.channel_id()
+				.ok_or_else(|| Error::missing_channel_id())?,
+			error_receipt,
+		)
+		.map_err(Error::verify_channel_upgrade_failed)?;
+
+	client_def
+		.update_state_on_verified_proof(client_state, proof)
+		.map_err(Error::ics02_client)
 }
 
 /// Entry point for verifying all proofs bundled in a ICS4 packet recv. message.
 pub fn verify_packet_recv_proofs<Ctx: ReaderContext>(
 	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
 	height: Height,
 	packet: &Packet,
 	connection_end: &ConnectionEnd,
 	proofs: &Proofs,
-) -> Result<(), Error> {
+) -> Result<Ctx::AnyClientState, Error> {
 	let client_id = connection_end.client_id();
-	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
 
 	// The client must not be frozen.
 	if client_state.is_frozen(ctx, client_id) {
@@ -123,20 +228,22 @@ pub fn verify_packet_recv_proofs<Ctx: ReaderContext>(
 		)
 		.map_err(|e| Error::packet_verification_failed(packet.sequence, e))?;
 
-	Ok(())
+	client_def
+		.update_state_on_verified_proof(client_state, proofs.object_proof())
+		.map_err(Error::ics02_client)
 }
 
 /// Entry point for verifying all proofs bundled in an ICS4 packet ack message.
 pub fn verify_packet_acknowledgement_proofs<Ctx: ReaderContext>(
 	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
 	height: Height,
 	packet: &Packet,
 	acknowledgement: Acknowledgement,
 	connection_end: &ConnectionEnd,
 	proofs: &Proofs,
-) -> Result<(), Error> {
+) -> Result<Ctx::AnyClientState, Error> {
 	let client_id = connection_end.client_id();
-	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
 
 	// The client must not be frozen.
 	if client_state.is_frozen(ctx, client_id) {
@@ -168,23 +275,25 @@ pub fn verify_packet_acknowledgement_proofs<Ctx: ReaderContext>(
 		)
 		.map_err(|e| Error::packet_verification_failed(packet.sequence, e))?;
 
-	Ok(())
+	client_def
+		.update_state_on_verified_proof(client_state, proofs.object_proof())
+		.map_err(Error::ics02_client)
 }
 
 /// Entry point for verifying all timeout proofs.
 pub fn verify_next_sequence_recv<Ctx>(
 	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
 	height: Height,
 	connection_end: &ConnectionEnd,
 	packet: Packet,
 	seq: Sequence,
 	proofs: &Proofs,
-) -> Result<(), Error>
+) -> Result<Ctx::AnyClientState, Error>
 where
 	Ctx: ReaderContext,
 {
 	let client_id = connection_end.client_id();
-	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
 
 	// The client must not be frozen.
 	if client_state.is_frozen(ctx, client_id) {
@@ -213,21 +322,23 @@ where
 		)
 		.map_err(|e| Error::packet_verification_failed(seq, e))?;
 
-	Ok(())
+	client_def
+		.update_state_on_verified_proof(client_state, proofs.object_proof())
+		.map_err(Error::ics02_client)
 }
 
 pub fn verify_packet_receipt_absence<Ctx>(
 	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
 	height: Height,
 	connection_end: &ConnectionEnd,
 	packet: Packet,
 	proofs: &Proofs,
-) -> Result<(), Error>
+) -> Result<Ctx::AnyClientState, Error>
 where
 	Ctx: ReaderContext,
 {
 	let client_id = connection_end.client_id();
-	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
 
 	// The client must not be frozen.
 	if client_state.is_frozen(ctx, client_id) {
@@ -256,5 +367,54 @@ where
 		)
 		.map_err(|e| Error::packet_verification_failed(packet.sequence, e))?;
 
-	Ok(())
+	client_def
+		.update_state_on_verified_proof(client_state, proofs.object_proof())
+		.map_err(Error::ics02_client)
+}
+
+/// Verifies the timeout receipt written for a packet that timed out on an `ORDERED_ALLOW_TIMEOUT`
+/// channel.
+pub fn verify_packet_timeout_receipt<Ctx>(
+	ctx: &Ctx,
+	client_state: Ctx::AnyClientState,
+	height: Height,
+	connection_end: &ConnectionEnd,
+	packet: Packet,
+	proofs: &Proofs,
+) -> Result<Ctx::AnyClientState, Error>
+where
+	Ctx: ReaderContext,
+{
+	let client_id = connection_end.client_id();
+
+	// The client must not be frozen.
+	if client_state.is_frozen(ctx, client_id) {
+		return Err(Error::frozen_client(client_id.clone()))
+	}
+
+	let consensus_state = ctx
+		.consensus_state(client_id, proofs.height())
+		.map_err(|_| Error::error_invalid_consensus_state())?;
+
+	let client_def = client_state.client_def();
+
+	// Verify the proof for the packet against the chain store.
+	client_def
+		.verify_packet_timeout_receipt(
+			ctx,
+			client_id,
+			&client_state,
+			height,
+			connection_end,
+			proofs.object_proof(),
+			consensus_state.root(),
+			&packet.destination_port,
+			&packet.destination_channel,
+			packet.sequence,
+		)
+		.map_err(|e| Error::packet_verification_failed(packet.sequence, e))?;
+
+	client_def
+		.update_state_on_verified_proof(client_state, proofs.object_proof())
+		.map_err(Error::ics02_client)
 }
@@ -46,7 +46,8 @@ pub fn process<Ctx: ReaderContext>(
 	let dest_channel_end =
 		ctx.channel_end(&(packet.destination_port.clone(), packet.destination_channel))?;
 
-	if !dest_channel_end.state_matches(&State::Open) {
+	// Packets received while flushing an upgrade are still acknowledged.
+	if !dest_channel_end.state_matches(&State::Open) && !dest_channel_end.is_flushing() {
 		return Err(Error::invalid_channel_state(packet.source_channel, dest_channel_end.state))
 	}
 
@@ -68,9 +69,10 @@ pub fn process<Ctx: ReaderContext>(
 		return Err(Error::invalid_acknowledgement())
 	}
 
+	// The acknowledgement is stored under the receiving end of the packet
 	let result = PacketResult::WriteAck(WriteAckPacketResult {
-		port_id: packet.source_port.clone(),
-		channel_id: packet.source_channel,
+		port_id: packet.destination_port.clone(),
+		channel_id: packet.destination_channel,
 		seq: packet.sequence,
 		ack: ack.clone(),
 	});
@@ -104,7 +106,7 @@ mod tests {
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
 				handler::write_acknowledgement::process,
-				packet::{test_utils::get_dummy_raw_packet, Packet},
+				packet::{test_utils::get_dummy_raw_packet, Packet, PacketResult},
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -206,6 +208,11 @@ mod tests {
                     );
 
 					assert!(!proto_output.events.is_empty()); // Some events must exist.
+					assert!(matches!(
+						&proto_output.result,
+						PacketResult::WriteAck(res) if res.port_id == test.packet.destination_port &&
+							res.channel_id == test.packet.destination_channel
+					));
 
 					for e in proto_output.events.iter() {
 						assert!(matches!(e, &IbcEvent::WriteAcknowledgement(_)));
@@ -22,8 +22,12 @@ use crate::core::{
 			acknowledgement::MsgAcknowledgement, chan_close_confirm::MsgChannelCloseConfirm,
 			chan_close_init::MsgChannelCloseInit, chan_open_ack::MsgChannelOpenAck,
 			chan_open_confirm::MsgChannelOpenConfirm, chan_open_init::MsgChannelOpenInit,
-			chan_open_try::MsgChannelOpenTry, recv_packet::MsgRecvPacket, timeout::MsgTimeout,
-			timeout_on_close::MsgTimeoutOnClose,
+			chan_open_try::MsgChannelOpenTry, chan_upgrade_ack::MsgChannelUpgradeAck,
+			chan_upgrade_cancel::MsgChannelUpgradeCancel,
+			chan_upgrade_confirm::MsgChannelUpgradeConfirm,
+			chan_upgrade_init::MsgChannelUpgradeInit, chan_upgrade_open::MsgChannelUpgradeOpen,
+			chan_upgrade_timeout::MsgChannelUpgradeTimeout, chan_upgrade_try::MsgChannelUpgradeTry,
+			recv_packet::MsgRecvPacket, timeout::MsgTimeout, timeout_on_close::MsgTimeoutOnClose,
 		},
 	},
 	ics26_routing::context::{Ics26Context, ModuleId},
@@ -39,6 +43,15 @@ pub mod chan_open_try;
 pub mod chan_close_confirm;
 pub mod chan_close_init;
 
+// Upgrade handshake messages.
+pub mod chan_upgrade_ack;
+pub mod chan_upgrade_cancel;
+pub mod chan_upgrade_confirm;
+pub mod chan_upgrade_init;
+pub mod chan_upgrade_open;
+pub mod chan_upgrade_timeout;
+pub mod chan_upgrade_try;
+
 // Packet specific messages.
 pub mod acknowledgement;
 pub mod recv_packet;
@@ -54,6 +67,13 @@ pub enum ChannelMsg {
 	ChannelOpenConfirm(MsgChannelOpenConfirm),
 	ChannelCloseInit(MsgChannelCloseInit),
 	ChannelCloseConfirm(MsgChannelCloseConfirm),
+	ChannelUpgradeInit(MsgChannelUpgradeInit),
+	ChannelUpgradeTry(MsgChannelUpgradeTry),
+	ChannelUpgradeAck(MsgChannelUpgradeAck),
+	ChannelUpgradeConfirm(MsgChannelUpgradeConfirm),
+	ChannelUpgradeOpen(MsgChannelUpgradeOpen),
+	ChannelUpgradeTimeout(MsgChannelUpgradeTimeout),
+	ChannelUpgradeCancel(MsgChannelUpgradeCancel),
 }
 
 impl ChannelMsg {
@@ -71,6 +91,20 @@ impl ChannelMsg {
 				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
 			ChannelMsg::ChannelCloseConfirm(msg) =>
 				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeInit(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeTry(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeAck(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeConfirm(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeOpen(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeTimeout(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeCancel(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
 		};
 		Ok(module_id)
 	}
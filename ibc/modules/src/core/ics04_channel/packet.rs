@@ -57,6 +57,19 @@ pub enum PacketResult {
 #[derive(Clone, Debug)]
 pub enum Receipt {
 	Ok,
+	/// Written instead of processing a packet that timed out before being received on an
+	/// `ORDERED_ALLOW_TIMEOUT` channel, so the sender can prove the timeout.
+	Timeout,
+}
+
+impl Receipt {
+	/// The value the receipt is committed as.
+	pub fn as_bytes(&self) -> &'static [u8] {
+		match self {
+			Receipt::Ok => b"Ok",
+			Receipt::Timeout => b"Timeout",
+		}
+	}
 }
 
 impl core::fmt::Display for PacketMsgType {
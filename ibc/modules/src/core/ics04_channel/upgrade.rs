@@ -0,0 +1,288 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types used by the ICS-04 channel upgrade handshake.
+
+use crate::prelude::*;
+
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::channel::v1::{
+	ErrorReceipt as RawErrorReceipt, Timeout as RawTimeout, Upgrade as RawUpgrade,
+	UpgradeFields as RawUpgradeFields,
+};
+
+use crate::{
+	core::{
+		ics02_client::height::Height,
+		ics04_channel::{
+			channel::{ChannelEnd, Order},
+			error::Error,
+			packet::Sequence,
+			Version,
+		},
+		ics24_host::identifier::ConnectionId,
+	},
+	timestamp::{Expiry, Timestamp},
+};
+
+/// The fields of a channel end which may be changed by an upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeFields {
+	pub ordering: Order,
+	pub connection_hops: Vec<ConnectionId>,
+	pub version: Version,
+}
+
+impl UpgradeFields {
+	pub fn new(ordering: Order, connection_hops: Vec<ConnectionId>, version: Version) -> Self {
+		Self { ordering, connection_hops, version }
+	}
+
+	pub fn validate_basic(&self) -> Result<(), Error> {
+		if self.connection_hops.len() != 1 {
+			return Err(Error::invalid_connection_hops_length(1, self.connection_hops.len()))
+		}
+		if self.version.to_string().trim().is_empty() {
+			return Err(Error::invalid_upgrade("proposed version cannot be empty".to_string()))
+		}
+		Ok(())
+	}
+
+	/// Returns `true` if applying these fields to `channel_end` would not change it.
+	pub fn matches(&self, channel_end: &ChannelEnd) -> bool {
+		channel_end.order_matches(&self.ordering) &&
+			channel_end.connection_hops_matches(&self.connection_hops) &&
+			channel_end.version_matches(&self.version)
+	}
+
+	/// Applies the upgraded fields to the given channel end.
+	pub fn apply(&self, channel_end: &mut ChannelEnd) {
+		channel_end.ordering = self.ordering;
+		channel_end.connection_hops = self.connection_hops.clone();
+		channel_end.version = self.version.clone();
+	}
+}
+
+impl Protobuf<RawUpgradeFields> for UpgradeFields {}
+
+impl TryFrom<RawUpgradeFields> for UpgradeFields {
+	type Error = Error;
+
+	fn try_from(value: RawUpgradeFields) -> Result<Self, Self::Error> {
+		let ordering = Order::from_i32(value.ordering)?;
+		let connection_hops = value
+			.connection_hops
+			.into_iter()
+			.map(|conn_id| ConnectionId::from_str(conn_id.as_str()))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(Error::identifier)?;
+
+		Ok(UpgradeFields { ordering, connection_hops, version: value.version.into() })
+	}
+}
+
+impl From<UpgradeFields> for RawUpgradeFields {
+	fn from(value: UpgradeFields) -> Self {
+		RawUpgradeFields {
+			ordering: value.ordering as i32,
+			connection_hops: value.connection_hops.iter().map(|v| v.as_str().to_string()).collect(),
+			version: value.version.to_string(),
+		}
+	}
+}
+
+/// Deadline on the counterparty chain for an upgrade handshake, after which the upgrade may be
+/// aborted with a `MsgChannelUpgradeTimeout`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeTimeout {
+	pub height: Height,
+	pub timestamp: Timestamp,
+}
+
+impl Default for UpgradeTimeout {
+	fn default() -> Self {
+		Self { height: Height::zero(), timestamp: Timestamp::none() }
+	}
+}
+
+impl UpgradeTimeout {
+	pub fn new(height: Height, timestamp: Timestamp) -> Self {
+		Self { height, timestamp }
+	}
+
+	/// Returns `true` if neither a timeout height nor a timeout timestamp is set.
+	pub fn is_zero(&self) -> bool {
+		self.height.is_zero() && self.timestamp == Timestamp::none()
+	}
+
+	/// Checks whether the timeout has passed relative to the given counterparty height and
+	/// timestamp.
+	pub fn has_passed(&self, height: Height, timestamp: &Timestamp) -> bool {
+		(!self.height.is_zero() && self.height <= height) ||
+			(self.timestamp != Timestamp::none() &&
+				timestamp.check_expiry(&self.timestamp) == Expiry::Expired)
+	}
+}
+
+impl Protobuf<RawTimeout> for UpgradeTimeout {}
+
+impl TryFrom<RawTimeout> for UpgradeTimeout {
+	type Error = Error;
+
+	fn try_from(value: RawTimeout) -> Result<Self, Self::Error> {
+		let height = value.height.map(Into::into).unwrap_or_else(Height::zero);
+		let timestamp = Timestamp::from_nanoseconds(value.timestamp)
+			.map_err(Error::invalid_packet_timestamp)?;
+		Ok(UpgradeTimeout { height, timestamp })
+	}
+}
+
+impl From<UpgradeTimeout> for RawTimeout {
+	fn from(value: UpgradeTimeout) -> Self {
+		RawTimeout { height: Some(value.height.into()), timestamp: value.timestamp.nanoseconds() }
+	}
+}
+
+/// A proposed channel upgrade, stored on both ends of the channel for the duration of the
+/// handshake.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upgrade {
+	pub fields: UpgradeFields,
+	pub timeout: UpgradeTimeout,
+	/// The next send sequence of the channel end when it started flushing. Packets with a lower
+	/// sequence must be flushed before the upgrade can complete.
+	pub next_sequence_send: Sequence,
+}
+
+impl Upgrade {
+	pub fn new(
+		fields: UpgradeFields,
+		timeout: UpgradeTimeout,
+		next_sequence_send: Sequence,
+	) -> Self {
+		Self { fields, timeout, next_sequence_send }
+	}
+}
+
+impl Protobuf<RawUpgrade> for Upgrade {}
+
+impl TryFrom<RawUpgrade> for Upgrade {
+	type Error = Error;
+
+	fn try_from(value: RawUpgrade) -> Result<Self, Self::Error> {
+		Ok(Upgrade {
+			fields: value.fields.ok_or_else(Error::missing_upgrade_fields)?.try_into()?,
+			timeout: value.timeout.map(TryInto::try_into).transpose()?.unwrap_or_default(),
+			next_sequence_send: value.next_sequence_send.into(),
+		})
+	}
+}
+
+impl From<Upgrade> for RawUpgrade {
+	fn from(value: Upgrade) -> Self {
+		RawUpgrade {
+			fields: Some(value.fields.into()),
+			timeout: Some(value.timeout.into()),
+			next_sequence_send: value.next_sequence_send.into(),
+		}
+	}
+}
+
+/// Written when an upgrade attempt is aborted, so that the counterparty can prove the failure
+/// and cancel its side of the handshake.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReceipt {
+	/// The upgrade sequence of the aborted attempt.
+	pub sequence: u64,
+	pub message: String,
+}
+
+impl ErrorReceipt {
+	pub fn new(sequence: u64, message: String) -> Self {
+		Self { sequence, message }
+	}
+}
+
+impl Protobuf<RawErrorReceipt> for ErrorReceipt {}
+
+impl TryFrom<RawErrorReceipt> for ErrorReceipt {
+	type Error = Error;
+
+	fn try_from(value: RawErrorReceipt) -> Result<Self, Self::Error> {
+		Ok(ErrorReceipt { sequence: value.sequence, message: value.message })
+	}
+}
+
+impl From<ErrorReceipt> for RawErrorReceipt {
+	fn from(value: ErrorReceipt) -> Self {
+		RawErrorReceipt { sequence: value.sequence, message: value.message }
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use crate::{core::ics24_host::identifier::ConnectionId, prelude::*};
+	use ibc_proto::ibc::core::{
+		channel::v1::{
+			Timeout as RawTimeout, Upgrade as RawUpgrade, UpgradeFields as RawUpgradeFields,
+		},
+		client::v1::Height as RawHeight,
+	};
+
+	/// Returns dummy `RawUpgradeFields`, for testing only!
+	pub fn get_dummy_raw_upgrade_fields() -> RawUpgradeFields {
+		RawUpgradeFields {
+			ordering: 1,
+			connection_hops: vec![ConnectionId::default().to_string()],
+			version: "ics20-2".to_string(),
+		}
+	}
+
+	/// Returns a dummy `RawUpgrade`, for testing only!
+	pub fn get_dummy_raw_upgrade(timeout_height: u64) -> RawUpgrade {
+		RawUpgrade {
+			fields: Some(get_dummy_raw_upgrade_fields()),
+			timeout: Some(RawTimeout {
+				height: Some(RawHeight { revision_number: 0, revision_height: timeout_height }),
+				timestamp: 0,
+			}),
+			next_sequence_send: 1,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{test_util::get_dummy_raw_upgrade, *};
+
+	#[test]
+	fn upgrade_roundtrips_through_raw() {
+		let raw = get_dummy_raw_upgrade(10);
+		let upgrade = Upgrade::try_from(raw.clone()).unwrap();
+		assert_eq!(RawUpgrade::from(upgrade.clone()), raw);
+		assert!(upgrade.fields.validate_basic().is_ok());
+		assert!(upgrade.timeout.has_passed(Height::new(0, 10), &Timestamp::none()));
+		assert!(!upgrade.timeout.has_passed(Height::new(0, 9), &Timestamp::none()));
+	}
+
+	#[test]
+	fn upgrade_without_fields_is_rejected() {
+		let raw = RawUpgrade { fields: None, ..get_dummy_raw_upgrade(10) };
+		assert!(Upgrade::try_from(raw).is_err());
+	}
+}
@@ -58,6 +58,8 @@ pub enum Path {
 	Commitments(CommitmentsPath),
 	Acks(AcksPath),
 	Receipts(ReceiptsPath),
+	ChannelUpgrade(ChannelUpgradePath),
+	ChannelUpgradeError(ChannelUpgradeErrorPath),
 	Upgrade(ClientUpgradePath),
 	Outside(OutsidePath),
 }
@@ -141,6 +143,14 @@ pub struct ReceiptsPath {
 	pub sequence: Sequence,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+#[display(fmt = "channelUpgrades/upgrades/ports/{}/channels/{}", _0, _1)]
+pub struct ChannelUpgradePath(pub PortId, pub ChannelId);
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+#[display(fmt = "channelUpgrades/upgradeError/ports/{}/channels/{}", _0, _1)]
+pub struct ChannelUpgradeErrorPath(pub PortId, pub ChannelId);
+
 /// Paths that are specific for client upgrades.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
 pub enum ClientUpgradePath {
@@ -194,6 +204,7 @@ impl FromStr for Path {
 			.or_else(|| parse_commitments(&components))
 			.or_else(|| parse_acks(&components))
 			.or_else(|| parse_receipts(&components))
+			.or_else(|| parse_channel_upgrades(&components))
 			.or_else(|| parse_upgrades(&components))
 			.or_else(|| parse_outside_paths(&components))
 			.ok_or_else(|| PathError::parse_failure(s.to_string()))
@@ -507,6 +518,31 @@ fn parse_receipts(components: &[&str]) -> Option<Path> {
 	Some(ReceiptsPath { port_id, channel_id, sequence }.into())
 }
 
+fn parse_channel_upgrades(components: &[&str]) -> Option<Path> {
+	if components.len() != 6 {
+		return None
+	}
+
+	if components[0] != "channelUpgrades" {
+		return None
+	}
+
+	let port = parse_ports(&components[2..=3]);
+	let channel = parse_channels(&components[4..=5]);
+
+	let port_id =
+		if let Some(Path::Ports(PortsPath(port_id))) = port { port_id } else { return None };
+
+	let channel_id =
+		if let Some(SubPath::Channels(channel_id)) = channel { channel_id } else { return None };
+
+	match components[1] {
+		"upgrades" => Some(ChannelUpgradePath(port_id, channel_id).into()),
+		"upgradeError" => Some(ChannelUpgradeErrorPath(port_id, channel_id).into()),
+		_ => None,
+	}
+}
+
 fn parse_upgrades(components: &[&str]) -> Option<Path> {
 	if components.len() != 3 {
 		return None
@@ -876,6 +912,47 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_parse_channel_upgrades_fn() {
+		let path = "channelUpgrades/upgrades/ports/defaultPort/channels/channel-0";
+		let components: Vec<&str> = path.split('/').collect();
+
+		assert_eq!(
+			parse_channel_upgrades(&components),
+			Some(Path::ChannelUpgrade(ChannelUpgradePath(PortId::default(), ChannelId::default()))),
+		);
+
+		let path = "channelUpgrades/upgradeError/ports/defaultPort/channels/channel-0";
+		let components: Vec<&str> = path.split('/').collect();
+
+		assert_eq!(
+			parse_channel_upgrades(&components),
+			Some(Path::ChannelUpgradeError(ChannelUpgradeErrorPath(
+				PortId::default(),
+				ChannelId::default()
+			))),
+		);
+
+		let path = "channelUpgrades/upgradeReceipt/ports/defaultPort/channels/channel-0";
+		let components: Vec<&str> = path.split('/').collect();
+
+		assert_eq!(parse_channel_upgrades(&components), None);
+	}
+
+	#[test]
+	fn channel_upgrade_path_parses() {
+		let path = "channelUpgrades/upgrades/ports/defaultPort/channels/channel-0";
+		let parsed = Path::from_str(path);
+
+		assert!(parsed.is_ok());
+		let parsed = parsed.unwrap();
+		assert_eq!(
+			parsed,
+			Path::ChannelUpgrade(ChannelUpgradePath(PortId::default(), ChannelId::default())),
+		);
+		assert_eq!(parsed.to_string(), path);
+	}
+
 	#[test]
 	fn test_parse_upgrades_fn() {
 		let path = "upgradedIBCState/0/upgradedClient";
@@ -180,6 +180,64 @@ pub trait Module: Send + Sync + AsAnyMut {
 		Ok(())
 	}
 
+	/// Called when this chain proposes a channel upgrade. Returns the application version the
+	/// channel will be upgraded to.
+	#[allow(clippy::too_many_arguments)]
+	fn on_chan_upgrade_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_proposed_order: Order,
+		_proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Error> {
+		Ok(proposed_version.clone())
+	}
+
+	/// Called when the counterparty proposed a channel upgrade. Returns the application version
+	/// the channel will be upgraded to.
+	#[allow(clippy::too_many_arguments)]
+	fn on_chan_upgrade_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_proposed_order: Order,
+		_proposed_connection_hops: &[ConnectionId],
+		proposed_version: &Version,
+	) -> Result<Version, Error> {
+		Ok(proposed_version.clone())
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty_version: &Version,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	/// Called once the upgraded parameters have been applied to the channel end.
+	#[allow(clippy::too_many_arguments)]
+	fn on_chan_upgrade_open(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_version: &Version,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
 	/// Modules can choose to write acknowledgement to storage in this callback
 	fn on_recv_packet(
 		&self,
@@ -220,7 +278,7 @@ pub trait RouterBuilder: Sized {
 	/// Registers `Module` against the specified `ModuleId` in the `Router`'s internal map
 	///
 	/// Returns an error if a `Module` has already been registered against the specified `ModuleId`
-	fn add_route(self, module_id: ModuleId, module: impl Module) -> Result<Self, String>;
+	fn add_route(self, module_id: ModuleId, module: impl Module + Clone) -> Result<Self, String>;
 
 	/// Consumes the `RouterBuilder` and returns a `Router` as configured
 	fn build(self) -> Self::Router;
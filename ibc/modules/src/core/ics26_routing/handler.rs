@@ -15,10 +15,12 @@
 use crate::{
 	core::{
 		ics02_client::{
+			client_def::ClientDef,
+			client_state::ClientState,
 			context::{ClientKeeper, ClientTypes},
 			handler::dispatch as ics2_msg_dispatcher,
 		},
-		ics03_connection::handler::dispatch as ics3_msg_dispatcher,
+		ics03_connection::{handler::dispatch as ics3_msg_dispatcher, msgs::ConnectionMsg},
 		ics04_channel::{
 			handler::{
 				channel_callback as ics4_callback, channel_dispatch as ics4_msg_dispatcher,
@@ -26,8 +28,11 @@ use crate::{
 				packet_callback as ics4_packet_callback,
 				packet_dispatch as ics4_packet_msg_dispatcher, recv_packet::RecvPacketResult,
 			},
+			msgs::{ChannelMsg, PacketMsg},
 			packet::PacketResult,
 		},
+		ics23_commitment::commitment::CommitmentProofBytes,
+		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 		ics26_routing::{
 			context::{Ics26Context, ModuleOutputBuilder, ReaderContext},
 			error::Error,
@@ -101,12 +106,16 @@ where
 		},
 
 		Ics3Msg(msg) => {
+			let verified_proofs = connection_msg_proofs(ctx, &msg);
 			let handler_output =
 				ics3_msg_dispatcher::<_>(ctx, msg).map_err(Error::ics03_connection)?;
 
 			// Apply any results to the host chain store.
 			ctx.store_connection_result(handler_output.result)
 				.map_err(Error::ics03_connection)?;
+			if let Some((client_id, proofs)) = verified_proofs {
+				store_verified_proofs(ctx, client_id, &proofs)?;
+			}
 
 			HandlerOutput::builder()
 				.with_log(handler_output.log)
@@ -116,6 +125,7 @@ where
 
 		Ics4ChannelMsg(msg) => {
 			let module_id = ics4_validate(ctx, &msg).map_err(Error::ics04_channel)?;
+			let verified_proofs = channel_msg_proofs(ctx, &msg);
 			let (mut handler_builder, channel_result) =
 				ics4_msg_dispatcher::<_>(ctx, &msg).map_err(Error::ics04_channel)?;
 
@@ -127,19 +137,34 @@ where
 
 			// Apply any results to the host chain store.
 			ctx.store_channel_result(channel_result).map_err(Error::ics04_channel)?;
+			if let Some((client_id, proofs)) = verified_proofs {
+				store_verified_proofs(ctx, client_id, proofs)?;
+			}
 
 			handler_builder.with_result(())
 		},
 
 		Ics4PacketMsg(msg) => {
 			let module_id = get_module_for_packet_msg(ctx, &msg).map_err(Error::ics04_channel)?;
+			let verified_proofs = packet_msg_proofs(ctx, &msg);
 			let (mut handler_builder, packet_result) =
 				ics4_packet_msg_dispatcher::<_>(ctx, &msg).map_err(Error::ics04_channel)?;
 
+			// The proofs were verified even if the packet needs no further processing.
+			if let Some((client_id, proofs)) = verified_proofs {
+				store_verified_proofs(ctx, client_id, proofs)?;
+			}
+
 			if matches!(packet_result, PacketResult::Recv(RecvPacketResult::NoOp)) {
 				return Ok(handler_builder.with_result(()))
 			}
 
+			// A packet that timed out is never handed to the module, only its receipt is stored.
+			if matches!(packet_result, PacketResult::Recv(RecvPacketResult::Timeout { .. })) {
+				ctx.store_packet_result(packet_result).map_err(Error::ics04_channel)?;
+				return Ok(handler_builder.with_result(()))
+			}
+
 			let mut module_output = ModuleOutputBuilder::new();
 			let cb_result = ics4_packet_callback(ctx, &module_id, &msg, &mut module_output);
 			handler_builder.merge(module_output);
@@ -155,6 +180,159 @@ where
 	Ok(output)
 }
 
+/// Stores the state of the client with id `client_id` once it verified `proofs` for a message,
+/// as clients may consume the proofs they verify, see
+/// [`ClientDef::update_state_on_verified_proof`].
+fn store_verified_proofs<'a, Ctx>(
+	ctx: &mut Ctx,
+	client_id: ClientId,
+	proofs: impl IntoIterator<Item = &'a CommitmentProofBytes>,
+) -> Result<(), Error>
+where
+	Ctx: ReaderContext,
+{
+	let client_state = ctx.client_state(&client_id).map_err(Error::ics02_client)?;
+	let client_def = client_state.client_def();
+	let updated_client_state = proofs
+		.into_iter()
+		.try_fold(client_state.clone(), |client_state, proof| {
+			client_def.update_state_on_verified_proof(client_state, proof)
+		})
+		.map_err(Error::ics02_client)?;
+	if updated_client_state != client_state {
+		ctx.store_client_state(client_id, updated_client_state)
+			.map_err(Error::ics02_client)?;
+	}
+	Ok(())
+}
+
+/// Returns the client verifying the proofs of a connection handshake message, together with the
+/// proofs in the order the handlers verify them.
+fn connection_msg_proofs<Ctx>(
+	ctx: &Ctx,
+	msg: &ConnectionMsg<Ctx>,
+) -> Option<(ClientId, Vec<CommitmentProofBytes>)>
+where
+	Ctx: ReaderContext,
+{
+	let (client_id, proofs) = match msg {
+		ConnectionMsg::ConnectionOpenInit(_) => return None,
+		ConnectionMsg::ConnectionOpenTry(msg) => (msg.client_id.clone(), &msg.proofs),
+		ConnectionMsg::ConnectionOpenAck(msg) =>
+			(ctx.connection_end(&msg.connection_id).ok()?.client_id().clone(), &msg.proofs),
+		ConnectionMsg::ConnectionOpenConfirm(msg) =>
+			(ctx.connection_end(&msg.connection_id).ok()?.client_id().clone(), &msg.proofs),
+	};
+	let proofs = core::iter::once(proofs.object_proof().clone())
+		.chain(proofs.client_proof().clone())
+		.chain(proofs.consensus_proof().map(|proof| proof.proof().clone()))
+		.collect();
+	Some((client_id, proofs))
+}
+
+/// Returns the client verifying the proofs of a channel message, together with the proofs in the
+/// order the handlers verify them.
+fn channel_msg_proofs<'a, Ctx>(
+	ctx: &Ctx,
+	msg: &'a ChannelMsg,
+) -> Option<(ClientId, Vec<&'a CommitmentProofBytes>)>
+where
+	Ctx: ReaderContext,
+{
+	let (connection_id, proofs) = match msg {
+		ChannelMsg::ChannelOpenInit(_) |
+		ChannelMsg::ChannelCloseInit(_) |
+		ChannelMsg::ChannelUpgradeInit(_) => return None,
+		ChannelMsg::ChannelOpenTry(msg) =>
+			(msg.channel.connection_hops().first()?.clone(), vec![msg.proofs.object_proof()]),
+		ChannelMsg::ChannelOpenAck(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof()],
+		),
+		ChannelMsg::ChannelOpenConfirm(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof()],
+		),
+		ChannelMsg::ChannelCloseConfirm(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof()],
+		),
+		ChannelMsg::ChannelUpgradeTry(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof(), msg.proofs.other_proof().as_ref()?],
+		),
+		ChannelMsg::ChannelUpgradeAck(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof(), msg.proofs.other_proof().as_ref()?],
+		),
+		ChannelMsg::ChannelUpgradeConfirm(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof(), msg.proofs.other_proof().as_ref()?],
+		),
+		ChannelMsg::ChannelUpgradeOpen(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof()],
+		),
+		ChannelMsg::ChannelUpgradeTimeout(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof()],
+		),
+		ChannelMsg::ChannelUpgradeCancel(msg) => (
+			channel_connection(ctx, &msg.port_id, msg.channel_id)?,
+			vec![msg.proofs.object_proof()],
+		),
+	};
+	let client_id = ctx.connection_end(&connection_id).ok()?.client_id().clone();
+	Some((client_id, proofs))
+}
+
+/// Returns the client verifying the proofs of a packet message, together with the proofs in the
+/// order the handlers verify them.
+fn packet_msg_proofs<'a, Ctx>(
+	ctx: &Ctx,
+	msg: &'a PacketMsg,
+) -> Option<(ClientId, Vec<&'a CommitmentProofBytes>)>
+where
+	Ctx: ReaderContext,
+{
+	let (connection_id, proofs) = match msg {
+		PacketMsg::RecvPacket(msg) => (
+			channel_connection(ctx, &msg.packet.destination_port, msg.packet.destination_channel)?,
+			vec![msg.proofs.object_proof()],
+		),
+		PacketMsg::AckPacket(msg) => (
+			channel_connection(ctx, &msg.packet.source_port, msg.packet.source_channel)?,
+			vec![msg.proofs.object_proof()],
+		),
+		PacketMsg::ToPacket(msg) => (
+			channel_connection(ctx, &msg.packet.source_port, msg.packet.source_channel)?,
+			vec![msg.proofs.object_proof()],
+		),
+		PacketMsg::ToClosePacket(msg) => (
+			channel_connection(ctx, &msg.packet.source_port, msg.packet.source_channel)?,
+			vec![msg.proofs.other_proof().as_ref()?, msg.proofs.object_proof()],
+		),
+	};
+	let client_id = ctx.connection_end(&connection_id).ok()?.client_id().clone();
+	Some((client_id, proofs))
+}
+
+/// Returns the connection the channel `channel_id` on `port_id` currently runs on.
+fn channel_connection<Ctx>(
+	ctx: &Ctx,
+	port_id: &PortId,
+	channel_id: ChannelId,
+) -> Option<ConnectionId>
+where
+	Ctx: ReaderContext,
+{
+	ctx.channel_end(&(port_id.clone(), channel_id))
+		.ok()?
+		.connection_hops()
+		.first()
+		.cloned()
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::prelude::*;
@@ -142,6 +142,15 @@ const CHANNEL_OPEN_ACK_EVENT: &str = "channel_open_ack";
 const CHANNEL_OPEN_CONFIRM_EVENT: &str = "channel_open_confirm";
 const CHANNEL_CLOSE_INIT_EVENT: &str = "channel_close_init";
 const CHANNEL_CLOSE_CONFIRM_EVENT: &str = "channel_close_confirm";
+/// Channel upgrade event types
+const CHANNEL_UPGRADE_INIT_EVENT: &str = "channel_upgrade_init";
+const CHANNEL_UPGRADE_TRY_EVENT: &str = "channel_upgrade_try";
+const CHANNEL_UPGRADE_ACK_EVENT: &str = "channel_upgrade_ack";
+const CHANNEL_UPGRADE_CONFIRM_EVENT: &str = "channel_upgrade_confirm";
+const CHANNEL_UPGRADE_OPEN_EVENT: &str = "channel_upgrade_open";
+const CHANNEL_UPGRADE_TIMEOUT_EVENT: &str = "channel_upgrade_timeout";
+const CHANNEL_UPGRADE_CANCEL_EVENT: &str = "channel_upgrade_cancel";
+const CHANNEL_UPGRADE_ERROR_EVENT: &str = "channel_upgrade_error";
 /// Packet event types
 const SEND_PACKET_EVENT: &str = "send_packet";
 const RECEIVE_PACKET_EVENT: &str = "recv_packet";
@@ -169,6 +178,14 @@ pub enum IbcEventType {
 	OpenConfirmChannel,
 	CloseInitChannel,
 	CloseConfirmChannel,
+	UpgradeInitChannel,
+	UpgradeTryChannel,
+	UpgradeAckChannel,
+	UpgradeConfirmChannel,
+	UpgradeOpenChannel,
+	UpgradeTimeoutChannel,
+	UpgradeCancelChannel,
+	UpgradeErrorChannel,
 	SendPacket,
 	ReceivePacket,
 	WriteAck,
@@ -199,6 +216,14 @@ impl IbcEventType {
 			IbcEventType::OpenConfirmChannel => CHANNEL_OPEN_CONFIRM_EVENT,
 			IbcEventType::CloseInitChannel => CHANNEL_CLOSE_INIT_EVENT,
 			IbcEventType::CloseConfirmChannel => CHANNEL_CLOSE_CONFIRM_EVENT,
+			IbcEventType::UpgradeInitChannel => CHANNEL_UPGRADE_INIT_EVENT,
+			IbcEventType::UpgradeTryChannel => CHANNEL_UPGRADE_TRY_EVENT,
+			IbcEventType::UpgradeAckChannel => CHANNEL_UPGRADE_ACK_EVENT,
+			IbcEventType::UpgradeConfirmChannel => CHANNEL_UPGRADE_CONFIRM_EVENT,
+			IbcEventType::UpgradeOpenChannel => CHANNEL_UPGRADE_OPEN_EVENT,
+			IbcEventType::UpgradeTimeoutChannel => CHANNEL_UPGRADE_TIMEOUT_EVENT,
+			IbcEventType::UpgradeCancelChannel => CHANNEL_UPGRADE_CANCEL_EVENT,
+			IbcEventType::UpgradeErrorChannel => CHANNEL_UPGRADE_ERROR_EVENT,
 			IbcEventType::SendPacket => SEND_PACKET_EVENT,
 			IbcEventType::ReceivePacket => RECEIVE_PACKET_EVENT,
 			IbcEventType::WriteAck => WRITE_ACK_EVENT,
@@ -233,6 +258,14 @@ impl FromStr for IbcEventType {
 			CHANNEL_OPEN_CONFIRM_EVENT => Ok(IbcEventType::OpenConfirmChannel),
 			CHANNEL_CLOSE_INIT_EVENT => Ok(IbcEventType::CloseInitChannel),
 			CHANNEL_CLOSE_CONFIRM_EVENT => Ok(IbcEventType::CloseConfirmChannel),
+			CHANNEL_UPGRADE_INIT_EVENT => Ok(IbcEventType::UpgradeInitChannel),
+			CHANNEL_UPGRADE_TRY_EVENT => Ok(IbcEventType::UpgradeTryChannel),
+			CHANNEL_UPGRADE_ACK_EVENT => Ok(IbcEventType::UpgradeAckChannel),
+			CHANNEL_UPGRADE_CONFIRM_EVENT => Ok(IbcEventType::UpgradeConfirmChannel),
+			CHANNEL_UPGRADE_OPEN_EVENT => Ok(IbcEventType::UpgradeOpenChannel),
+			CHANNEL_UPGRADE_TIMEOUT_EVENT => Ok(IbcEventType::UpgradeTimeoutChannel),
+			CHANNEL_UPGRADE_CANCEL_EVENT => Ok(IbcEventType::UpgradeCancelChannel),
+			CHANNEL_UPGRADE_ERROR_EVENT => Ok(IbcEventType::UpgradeErrorChannel),
 			SEND_PACKET_EVENT => Ok(IbcEventType::SendPacket),
 			RECEIVE_PACKET_EVENT => Ok(IbcEventType::ReceivePacket),
 			WRITE_ACK_EVENT => Ok(IbcEventType::WriteAck),
@@ -271,6 +304,15 @@ pub enum IbcEvent {
 	CloseInitChannel(ChannelEvents::CloseInit),
 	CloseConfirmChannel(ChannelEvents::CloseConfirm),
 
+	UpgradeInitChannel(ChannelEvents::UpgradeInit),
+	UpgradeTryChannel(ChannelEvents::UpgradeTry),
+	UpgradeAckChannel(ChannelEvents::UpgradeAck),
+	UpgradeConfirmChannel(ChannelEvents::UpgradeConfirm),
+	UpgradeOpenChannel(ChannelEvents::UpgradeOpen),
+	UpgradeTimeoutChannel(ChannelEvents::UpgradeTimeout),
+	UpgradeCancelChannel(ChannelEvents::UpgradeCancel),
+	UpgradeErrorChannel(ChannelEvents::UpgradeError),
+
 	SendPacket(ChannelEvents::SendPacket),
 	ReceivePacket(ChannelEvents::ReceivePacket),
 	WriteAcknowledgement(ChannelEvents::WriteAcknowledgement),
@@ -325,6 +367,15 @@ impl fmt::Display for IbcEvent {
 			IbcEvent::CloseInitChannel(ev) => write!(f, "CloseInitChannelEv({})", ev),
 			IbcEvent::CloseConfirmChannel(ev) => write!(f, "CloseConfirmChannelEv({:?})", ev),
 
+			IbcEvent::UpgradeInitChannel(ev) => write!(f, "UpgradeInitChannelEv({:?})", ev),
+			IbcEvent::UpgradeTryChannel(ev) => write!(f, "UpgradeTryChannelEv({:?})", ev),
+			IbcEvent::UpgradeAckChannel(ev) => write!(f, "UpgradeAckChannelEv({:?})", ev),
+			IbcEvent::UpgradeConfirmChannel(ev) => write!(f, "UpgradeConfirmChannelEv({:?})", ev),
+			IbcEvent::UpgradeOpenChannel(ev) => write!(f, "UpgradeOpenChannelEv({:?})", ev),
+			IbcEvent::UpgradeTimeoutChannel(ev) => write!(f, "UpgradeTimeoutChannelEv({:?})", ev),
+			IbcEvent::UpgradeCancelChannel(ev) => write!(f, "UpgradeCancelChannelEv({:?})", ev),
+			IbcEvent::UpgradeErrorChannel(ev) => write!(f, "UpgradeErrorChannelEv({:?})", ev),
+
 			IbcEvent::SendPacket(ev) => write!(f, "SendPacketEv({})", ev),
 			IbcEvent::ReceivePacket(ev) => write!(f, "ReceivePacketEv({})", ev),
 			IbcEvent::WriteAcknowledgement(ev) => write!(f, "WriteAcknowledgementEv({})", ev),
@@ -365,6 +416,14 @@ impl IbcEvent {
 			IbcEvent::OpenConfirmChannel(ev) => ev.height(),
 			IbcEvent::CloseInitChannel(ev) => ev.height(),
 			IbcEvent::CloseConfirmChannel(ev) => ev.height(),
+			IbcEvent::UpgradeInitChannel(ev) => ev.height(),
+			IbcEvent::UpgradeTryChannel(ev) => ev.height(),
+			IbcEvent::UpgradeAckChannel(ev) => ev.height(),
+			IbcEvent::UpgradeConfirmChannel(ev) => ev.height(),
+			IbcEvent::UpgradeOpenChannel(ev) => ev.height(),
+			IbcEvent::UpgradeTimeoutChannel(ev) => ev.height(),
+			IbcEvent::UpgradeCancelChannel(ev) => ev.height(),
+			IbcEvent::UpgradeErrorChannel(ev) => ev.height(),
 			IbcEvent::SendPacket(ev) => ev.height(),
 			IbcEvent::ReceivePacket(ev) => ev.height(),
 			IbcEvent::WriteAcknowledgement(ev) => ev.height(),
@@ -392,6 +451,14 @@ impl IbcEvent {
 			IbcEvent::OpenConfirmChannel(ev) => ev.set_height(height),
 			IbcEvent::CloseInitChannel(ev) => ev.set_height(height),
 			IbcEvent::CloseConfirmChannel(ev) => ev.set_height(height),
+			IbcEvent::UpgradeInitChannel(ev) => ev.set_height(height),
+			IbcEvent::UpgradeTryChannel(ev) => ev.set_height(height),
+			IbcEvent::UpgradeAckChannel(ev) => ev.set_height(height),
+			IbcEvent::UpgradeConfirmChannel(ev) => ev.set_height(height),
+			IbcEvent::UpgradeOpenChannel(ev) => ev.set_height(height),
+			IbcEvent::UpgradeTimeoutChannel(ev) => ev.set_height(height),
+			IbcEvent::UpgradeCancelChannel(ev) => ev.set_height(height),
+			IbcEvent::UpgradeErrorChannel(ev) => ev.set_height(height),
 			IbcEvent::SendPacket(ev) => ev.set_height(height),
 			IbcEvent::ReceivePacket(ev) => ev.set_height(height),
 			IbcEvent::WriteAcknowledgement(ev) => ev.set_height(height),
@@ -420,6 +487,14 @@ impl IbcEvent {
 			IbcEvent::OpenConfirmChannel(_) => IbcEventType::OpenConfirmChannel,
 			IbcEvent::CloseInitChannel(_) => IbcEventType::CloseInitChannel,
 			IbcEvent::CloseConfirmChannel(_) => IbcEventType::CloseConfirmChannel,
+			IbcEvent::UpgradeInitChannel(_) => IbcEventType::UpgradeInitChannel,
+			IbcEvent::UpgradeTryChannel(_) => IbcEventType::UpgradeTryChannel,
+			IbcEvent::UpgradeAckChannel(_) => IbcEventType::UpgradeAckChannel,
+			IbcEvent::UpgradeConfirmChannel(_) => IbcEventType::UpgradeConfirmChannel,
+			IbcEvent::UpgradeOpenChannel(_) => IbcEventType::UpgradeOpenChannel,
+			IbcEvent::UpgradeTimeoutChannel(_) => IbcEventType::UpgradeTimeoutChannel,
+			IbcEvent::UpgradeCancelChannel(_) => IbcEventType::UpgradeCancelChannel,
+			IbcEvent::UpgradeErrorChannel(_) => IbcEventType::UpgradeErrorChannel,
 			IbcEvent::SendPacket(_) => IbcEventType::SendPacket,
 			IbcEvent::ReceivePacket(_) => IbcEventType::ReceivePacket,
 			IbcEvent::WriteAcknowledgement(_) => IbcEventType::WriteAck,
@@ -25,6 +25,7 @@ use crate::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			packet::Sequence,
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::{
 			commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
@@ -143,6 +144,38 @@ impl ClientDef for MockClient {
 		Ok(())
 	}
 
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		_client_state: &Self::ClientState,
+		_height: Height,
+		_prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_upgrade: &Upgrade,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		_client_state: &Self::ClientState,
+		_height: Height,
+		_prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_error_receipt: &ErrorReceipt,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
@@ -223,6 +256,22 @@ impl ClientDef for MockClient {
 		Ok(())
 	}
 
+	fn verify_packet_timeout_receipt<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		_client_state: &Self::ClientState,
+		_height: Height,
+		_connection_end: &ConnectionEnd,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_sequence: Sequence,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
 	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
@@ -254,9 +303,9 @@ impl ClientDef for MockClient {
 	fn update_state_on_misbehaviour(
 		&self,
 		client_state: Self::ClientState,
-		_client_msg: Self::ClientMessage,
+		client_msg: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error> {
-		Ok(client_state)
+		Ok(MockClientState { frozen_height: Some(client_msg.height()), ..client_state })
 	}
 
 	fn check_for_misbehaviour<Ctx: ReaderContext>(
@@ -264,9 +313,15 @@ impl ClientDef for MockClient {
 		_ctx: &Ctx,
 		_client_id: ClientId,
 		_client_state: Self::ClientState,
-		_client_msg: Self::ClientMessage,
+		client_msg: Self::ClientMessage,
 	) -> Result<bool, Error> {
-		Ok(false)
+		match client_msg {
+			// two distinct headers for the same height
+			MockClientMessage::Misbehaviour(misbehaviour) => Ok(misbehaviour.header1.height ==
+				misbehaviour.header2.height &&
+				misbehaviour.header1 != misbehaviour.header2),
+			MockClientMessage::Header(_) => Ok(false),
+		}
 	}
 
 	fn check_substitute_and_update_state<Ctx: ReaderContext>(
@@ -275,8 +330,14 @@ impl ClientDef for MockClient {
 		_subject_client_id: ClientId,
 		_substitute_client_id: ClientId,
 		_old_client_state: Self::ClientState,
-		_substitute_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		todo!()
+		let header = substitute_client_state.header;
+		Ok((
+			MockClientState { header, frozen_height: None },
+			ConsensusUpdateResult::Single(
+				Ctx::AnyConsensusState::wrap(&MockConsensusState::new(header)).unwrap(),
+			),
+		))
 	}
 }
@@ -48,7 +48,8 @@ use crate::{
 			context::{ChannelKeeper, ChannelReader},
 			error::Error as Ics04Error,
 			msgs::acknowledgement::Acknowledgement,
-			packet::{Receipt, Sequence},
+			packet::{Packet, Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics05_port::{
 			context::PortReader,
@@ -244,6 +245,34 @@ where
 		self
 	}
 
+	pub fn with_channel_upgrade(
+		self,
+		port_id: PortId,
+		chan_id: ChannelId,
+		upgrade: Upgrade,
+	) -> Self {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.channel_upgrades
+			.insert((port_id, chan_id), upgrade);
+		self
+	}
+
+	pub fn with_counterparty_channel_upgrade(
+		self,
+		port_id: PortId,
+		chan_id: ChannelId,
+		upgrade: Upgrade,
+	) -> Self {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.counterparty_channel_upgrades
+			.insert((port_id, chan_id), upgrade);
+		self
+	}
+
 	pub fn with_send_sequence(
 		self,
 		port_id: PortId,
@@ -542,6 +571,18 @@ pub struct MockIbcStore<C: HostBlockType> {
 
 	// Used by unordered channel
 	pub packet_receipt: BTreeMap<(PortId, ChannelId, Sequence), Receipt>,
+
+	/// Pending channel upgrades proposed or accepted by this chain.
+	pub channel_upgrades: BTreeMap<(PortId, ChannelId), Upgrade>,
+
+	/// Upgrades the counterparty committed to for channels that are flushing.
+	pub counterparty_channel_upgrades: BTreeMap<(PortId, ChannelId), Upgrade>,
+
+	/// Error receipts of aborted channel upgrades.
+	pub channel_upgrade_errors: BTreeMap<(PortId, ChannelId), ErrorReceipt>,
+
+	/// Received transfer packets whose tokens are forwarded, indexed by the forwarding packet.
+	pub forwarded_packets: BTreeMap<(PortId, ChannelId, Sequence), Packet>,
 }
 
 #[derive(Default)]
@@ -550,8 +591,12 @@ pub struct MockRouterBuilder(MockRouter);
 impl RouterBuilder for MockRouterBuilder {
 	type Router = MockRouter;
 
-	fn add_route(mut self, module_id: ModuleId, module: impl Module) -> Result<Self, String> {
-		match self.0 .0.insert(module_id, Arc::new(module)) {
+	fn add_route(
+		mut self,
+		module_id: ModuleId,
+		module: impl Module + Clone,
+	) -> Result<Self, String> {
+		match self.0 .0.insert(module_id, Box::new(module)) {
 			None => Ok(self),
 			Some(_) => Err("Duplicate module_id".to_owned()),
 		}
@@ -562,8 +607,32 @@ impl RouterBuilder for MockRouterBuilder {
 	}
 }
 
-#[derive(Clone, Default)]
-pub struct MockRouter(BTreeMap<ModuleId, Arc<dyn Module>>);
+/// A [`Module`] that is cloned along with the [`MockRouter`], so that a clone of the context
+/// handed to the module callbacks doesn't share its routes with the original one.
+pub trait CloneableModule: Module {
+	fn clone_box(&self) -> Box<dyn CloneableModule>;
+
+	fn as_module_mut(&mut self) -> &mut dyn Module;
+}
+
+impl<M: Module + Clone> CloneableModule for M {
+	fn clone_box(&self) -> Box<dyn CloneableModule> {
+		Box::new(self.clone())
+	}
+
+	fn as_module_mut(&mut self) -> &mut dyn Module {
+		self
+	}
+}
+
+#[derive(Default)]
+pub struct MockRouter(BTreeMap<ModuleId, Box<dyn CloneableModule>>);
+
+impl Clone for MockRouter {
+	fn clone(&self) -> Self {
+		Self(self.0.iter().map(|(id, module)| (id.clone(), module.clone_box())).collect())
+	}
+}
 
 impl Debug for MockRouter {
 	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
@@ -573,7 +642,7 @@ impl Debug for MockRouter {
 
 impl Router for MockRouter {
 	fn get_route_mut(&mut self, module_id: &ModuleId) -> Option<&mut dyn Module> {
-		self.0.get_mut(module_id.as_ref()).and_then(Arc::get_mut)
+		self.0.get_mut(module_id.as_ref()).map(|module| module.as_module_mut())
 	}
 
 	fn has_route(&self, module_id: &ModuleId) -> bool {
@@ -749,6 +818,43 @@ impl<C: HostBlockType> ChannelReader for MockContext<C> {
 	fn max_expected_time_per_block(&self) -> Duration {
 		self.block_time
 	}
+
+	fn channel_upgrade(&self, pcid: &(PortId, ChannelId)) -> Result<Upgrade, Ics04Error> {
+		match self.ibc_store.lock().unwrap().channel_upgrades.get(pcid) {
+			Some(upgrade) => Ok(upgrade.clone()),
+			None => Err(Ics04Error::upgrade_not_found(pcid.0.clone(), pcid.1)),
+		}
+	}
+
+	fn counterparty_channel_upgrade(
+		&self,
+		pcid: &(PortId, ChannelId),
+	) -> Result<Upgrade, Ics04Error> {
+		match self.ibc_store.lock().unwrap().counterparty_channel_upgrades.get(pcid) {
+			Some(upgrade) => Ok(upgrade.clone()),
+			None => Err(Ics04Error::upgrade_not_found(pcid.0.clone(), pcid.1)),
+		}
+	}
+
+	fn channel_upgrade_error(
+		&self,
+		pcid: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, Ics04Error> {
+		match self.ibc_store.lock().unwrap().channel_upgrade_errors.get(pcid) {
+			Some(error_receipt) => Ok(error_receipt.clone()),
+			None => Err(Ics04Error::upgrade_error_receipt_not_found(pcid.0.clone(), pcid.1)),
+		}
+	}
+
+	fn has_inflight_packets(&self, pcid: &(PortId, ChannelId)) -> Result<bool, Ics04Error> {
+		Ok(self
+			.ibc_store
+			.lock()
+			.unwrap()
+			.packet_commitment
+			.keys()
+			.any(|(port_id, channel_id, _)| port_id == &pcid.0 && channel_id == &pcid.1))
+	}
 }
 
 impl<C: HostBlockType> ChannelKeeper for MockContext<C> {
@@ -837,6 +943,51 @@ impl<C: HostBlockType> ChannelKeeper for MockContext<C> {
 		Ok(())
 	}
 
+	fn store_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store.lock().unwrap().channel_upgrades.insert(port_channel_id, upgrade);
+		Ok(())
+	}
+
+	fn store_counterparty_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.counterparty_channel_upgrades
+			.insert(port_channel_id, upgrade);
+		Ok(())
+	}
+
+	fn delete_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Ics04Error> {
+		let mut ibc_store = self.ibc_store.lock().unwrap();
+		ibc_store.channel_upgrades.remove(&port_channel_id);
+		ibc_store.counterparty_channel_upgrades.remove(&port_channel_id);
+		Ok(())
+	}
+
+	fn store_channel_upgrade_error(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		error_receipt: ErrorReceipt,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.channel_upgrade_errors
+			.insert(port_channel_id, error_receipt);
+		Ok(())
+	}
+
 	fn increase_channel_counter(&mut self) {
 		self.ibc_store.lock().unwrap().channel_ids_counter += 1;
 	}
@@ -1057,13 +1208,25 @@ where
 	fn host_consensus_state(
 		&self,
 		height: Height,
-		_proof: Option<Vec<u8>>,
+		proof: Option<Vec<u8>>,
 		_client_state: &C::AnyClientState,
 	) -> Result<C::AnyConsensusState, Ics02Error> {
-		match self.host_block(height) {
-			Some(block_ref) => Ok(block_ref.clone().into()),
-			None => Err(Ics02Error::missing_local_consensus_state(height)),
+		let consensus_state: C::AnyConsensusState = match self.host_block(height) {
+			Some(block_ref) => block_ref.clone().into(),
+			None => return Err(Ics02Error::missing_local_consensus_state(height)),
+		};
+		// The mock host "proves" its consensus state by sending it over as an encoded `Any`,
+		// which must match the one built from the local block.
+		if let Some(proof) = proof {
+			let any: Any = prost::Message::decode(proof.as_slice()).map_err(Ics02Error::decode)?;
+			let proven = C::AnyConsensusState::try_from(any)?;
+			if proven != consensus_state {
+				return Err(Ics02Error::implementation_specific(format!(
+					"host consensus state proof at {height} doesn't match the local block"
+				)))
+			}
 		}
+		Ok(consensus_state)
 	}
 
 	fn client_counter(&self) -> Result<u64, Ics02Error> {
@@ -1196,6 +1359,7 @@ mod tests {
 
 	use crate::{
 		core::{
+			ics02_client::context::ClientReader,
 			ics04_channel::{
 				channel::{Counterparty, Order},
 				error::Error,
@@ -1209,12 +1373,16 @@ mod tests {
 			},
 		},
 		mock::{
+			client_state::{AnyClientState, AnyConsensusState, MockClientState},
 			context::{MockClientTypes, MockContext, MockRouterBuilder},
+			header::MockHeader,
 			host::{HostBlock, MockHostType},
 		},
 		prelude::*,
+		protobuf::Protobuf,
 		signer::Signer,
 		test_utils::get_dummy_bech32_account,
+		timestamp::Timestamp,
 		Height,
 	};
 
@@ -1326,7 +1494,7 @@ mod tests {
 
 		impl Acknowledgement for MockAck {}
 
-		#[derive(Debug, Default)]
+		#[derive(Debug, Default, Clone)]
 		struct FooModule;
 
 		impl Module for FooModule {
@@ -1347,7 +1515,7 @@ mod tests {
 			}
 		}
 
-		#[derive(Debug, Default)]
+		#[derive(Debug, Default, Clone)]
 		struct BarModule;
 
 		impl Module for BarModule {
@@ -1383,12 +1551,10 @@ mod tests {
 		)
 		.with_router(r);
 
-		let ctx_clone = MockContext::<MockClientTypes>::new(
-			ChainId::new("mockgaia".to_string(), 1),
-			MockHostType::Mock,
-			1,
-			Height::new(1, 1),
-		);
+		// the clone keeps the routes, without preventing the original from borrowing them mutably
+		let ctx_clone = ctx.clone();
+		assert!(ctx_clone.router.has_route(&ModuleId::from_str("foomodule").unwrap()));
+		assert!(ctx_clone.router.has_route(&ModuleId::from_str("barmodule").unwrap()));
 
 		let mut on_recv_packet_result = |module_id: &'static str| {
 			let module_id = ModuleId::from_str(module_id).unwrap();
@@ -1409,6 +1575,30 @@ mod tests {
 			.collect::<Result<Vec<_>, _>>()
 			.unwrap();
 	}
+
+	#[test]
+	fn test_host_consensus_state_proof() {
+		let ctx = MockContext::<MockClientTypes>::default();
+		let height = ctx.latest_height().decrement().unwrap();
+		let client_state = AnyClientState::Mock(MockClientState::new(
+			MockHeader::new(height)
+				.with_timestamp(Timestamp::from_nanoseconds(1).unwrap())
+				.into(),
+		));
+		let expected = ctx.host_consensus_state(height, None, &client_state).unwrap();
+
+		let proof = expected.clone().encode_vec().unwrap();
+		assert_eq!(ctx.host_consensus_state(height, Some(proof), &client_state).unwrap(), expected);
+
+		// a consensus state that doesn't match the host block is rejected
+		let forged = AnyConsensusState::from(
+			MockHeader::new(height).with_timestamp(Timestamp::from_nanoseconds(1).unwrap()),
+		);
+		assert!(ctx
+			.host_consensus_state(height, Some(forged.encode_vec().unwrap()), &client_state)
+			.is_err());
+		assert!(ctx.host_consensus_state(height, Some(vec![1, 2, 3]), &client_state).is_err());
+	}
 }
 
 #[cfg(test)]
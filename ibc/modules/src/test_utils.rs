@@ -37,7 +37,8 @@ use crate::{
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			context::{ChannelKeeper, ChannelReader},
 			error::Error,
-			packet::{Receipt, Sequence},
+			packet::{Packet, Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 			Version,
 		},
 		ics05_port::{context::PortReader, error::Error as PortError},
@@ -265,6 +266,23 @@ impl ics23::HostFunctionsProvider for Crypto {
 
 impl<C: HostBlockType> Ics20Keeper for DummyTransferModule<C> {
 	type AccountId = Signer;
+
+	fn store_forwarded_packet(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		packet: Packet,
+	) -> Result<(), Ics20Error> {
+		self.ibc_store.lock().unwrap().forwarded_packets.insert(key, packet);
+		Ok(())
+	}
+
+	fn delete_forwarded_packet(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+	) -> Result<(), Ics20Error> {
+		self.ibc_store.lock().unwrap().forwarded_packets.remove(&key);
+		Ok(())
+	}
 }
 
 impl<C: HostBlockType> ChannelKeeper for DummyTransferModule<C> {
@@ -348,6 +366,37 @@ impl<C: HostBlockType> ChannelKeeper for DummyTransferModule<C> {
 		unimplemented!()
 	}
 
+	fn store_channel_upgrade(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+		_upgrade: Upgrade,
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
+	fn store_counterparty_channel_upgrade(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+		_upgrade: Upgrade,
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
+	fn delete_channel_upgrade(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
+	fn store_channel_upgrade_error(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+		_error_receipt: ErrorReceipt,
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
 	fn increase_channel_counter(&mut self) {
 		unimplemented!()
 	}
@@ -419,6 +468,10 @@ impl<C: HostBlockType> Ics20Reader for DummyTransferModule<C> {
 		Ok(PortId::transfer())
 	}
 
+	fn get_forwarded_packet(&self, key: &(PortId, ChannelId, Sequence)) -> Option<Packet> {
+		self.ibc_store.lock().unwrap().forwarded_packets.get(key).cloned()
+	}
+
 	fn is_send_enabled(&self) -> bool {
 		true
 	}
@@ -607,6 +660,28 @@ impl<C: HostBlockType> ChannelReader for DummyTransferModule<C> {
 	fn max_expected_time_per_block(&self) -> Duration {
 		unimplemented!()
 	}
+
+	fn channel_upgrade(&self, _port_channel_id: &(PortId, ChannelId)) -> Result<Upgrade, Error> {
+		unimplemented!()
+	}
+
+	fn counterparty_channel_upgrade(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, Error> {
+		unimplemented!()
+	}
+
+	fn channel_upgrade_error(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, Error> {
+		unimplemented!()
+	}
+
+	fn has_inflight_packets(&self, _port_channel_id: &(PortId, ChannelId)) -> Result<bool, Error> {
+		unimplemented!()
+	}
 }
 
 impl<C: HostBlockType> ClientTypes for DummyTransferModule<C> {
@@ -96,13 +96,19 @@ impl CompileCmd {
 	) {
 		println!("[info ] Compiling IBC .proto files to Rust into '{}'...", out_dir.display());
 
+		let root = env!("CARGO_MANIFEST_DIR");
+		// ibc-go proto files patched in this repository replace the ibc-go files at the same path
+		let vendored_dir = PathBuf::from(format!("{root}/../proto/definitions"));
+
 		// Paths
 		let proto_paths = [
+			format!("{}/ibc", vendored_dir.display()),
 			// ibc-go proto files
 			format!("{}/proto/ibc", ibc_dir.display()),
 		];
 
 		let proto_includes_paths = [
+			format!("{}", vendored_dir.display()),
 			format!("{}", gogo.display()),
 			format!("{}", google.display()),
 			format!("{}/proto", cosmos_proto.display()),
@@ -113,6 +119,7 @@ impl CompileCmd {
 		];
 
 		// List available proto files
+		let ibc_proto_dir = ibc_dir.join("proto");
 		let mut protos: Vec<PathBuf> = vec![];
 		for proto_path in &proto_paths {
 			println!("Looking for proto files in {proto_path:?}");
@@ -125,6 +132,11 @@ impl CompileCmd {
 							e.path().extension().is_some() &&
 							e.path().extension().unwrap() == "proto"
 					})
+					// skip the ibc-go files that are vendored
+					.filter(|e| match e.path().strip_prefix(&ibc_proto_dir) {
+						Ok(path) => !vendored_dir.join(path).exists(),
+						Err(_) => true,
+					})
 					.map(|e| e.into_path())
 					.collect(),
 			);
@@ -35,6 +35,14 @@ pub struct MsgTransfer {
     /// optional memo
     #[prost(string, tag = "8")]
     pub memo: ::prost::alloc::string::String,
+    /// tokens to be transferred
+    #[prost(message, repeated, tag = "9")]
+    pub tokens: ::prost::alloc::vec::Vec<
+        super::super::super::super::cosmos::base::v1beta1::Coin,
+    >,
+    /// optional forwarding information
+    #[prost(message, optional, tag = "10")]
+    pub forwarding: ::core::option::Option<Forwarding>,
 }
 /// MsgTransferResponse defines the Msg/Transfer response type.
 #[derive(::serde::Serialize, ::serde::Deserialize)]
@@ -930,3 +938,52 @@ pub struct GenesisState {
     #[prost(message, optional, tag = "3")]
     pub params: ::core::option::Option<Params>,
 }
+/// Token defines a struct which represents a token to be transferred.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Token {
+    /// the token denomination
+    #[prost(message, optional, tag = "1")]
+    pub denom: ::core::option::Option<Denom>,
+    /// the token amount to be transferred
+    #[prost(string, tag = "2")]
+    pub amount: ::prost::alloc::string::String,
+}
+/// Denom holds the base denom of a Token and a trace of the chains it was sent through.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Denom {
+    /// the base token denomination
+    #[prost(string, tag = "1")]
+    pub base: ::prost::alloc::string::String,
+    /// the trace of the token
+    #[prost(message, repeated, tag = "3")]
+    pub trace: ::prost::alloc::vec::Vec<Hop>,
+}
+/// Forwarding defines a list of port ID, channel ID pairs determining the path
+/// through which a packet must be forwarded, and an unwind boolean indicating if
+/// the coin should be unwinded to its native chain before forwarding.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Forwarding {
+    /// optional unwinding for the token transferred
+    #[prost(bool, tag = "1")]
+    pub unwind: bool,
+    /// optional intermediate path through which packet will be forwarded
+    #[prost(message, repeated, tag = "2")]
+    pub hops: ::prost::alloc::vec::Vec<Hop>,
+}
+/// Hop defines a port ID, channel ID pair specifying where tokens must be forwarded
+/// next in a multihop transfer, or the trace of an existing token.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Hop {
+    #[prost(string, tag = "1")]
+    pub port_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub channel_id: ::prost::alloc::string::String,
+}
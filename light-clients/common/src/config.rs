@@ -226,6 +226,11 @@ pub trait ParaLifecycleT {
 	fn is_parachain(&self) -> bool;
 }
 
+pub trait AccountInfoT {
+	/// Free balance of the account, in the chain's smallest denomination.
+	fn free_balance(&self) -> u128;
+}
+
 pub trait AsInner {
 	type Inner: Encode + Decode + DecodeAsType + EncodeAsType + Send + Sync;
 
@@ -243,6 +248,7 @@ pub trait RuntimeStorage {
 	type Id: From<u32> + Into<u32> + Send + Sync + AsInner;
 	type ParaLifecycle: ParaLifecycleT + Send + Sync + AsInner;
 	type BeefyAuthoritySet: BeefyAuthoritySetT + Send + Sync + AsInner;
+	type AccountInfo: AccountInfoT + Send + Sync + AsInner;
 
 	fn timestamp_now() -> Address<StaticStorageMapKey, u64, Yes, Yes, ()>;
 
@@ -268,6 +274,10 @@ pub trait RuntimeStorage {
 	) -> LocalAddress<StaticStorageMapKey, <Self::BeefyAuthoritySet as AsInner>::Inner, Yes, Yes, ()>;
 
 	fn babe_epoch_start() -> Address<StaticStorageMapKey, (u32, u32), Yes, Yes, ()>;
+
+	fn system_account(
+		account: [u8; 32],
+	) -> LocalAddress<StaticStorageMapKey, <Self::AccountInfo as AsInner>::Inner, Yes, Yes, Yes>;
 }
 
 pub trait RuntimeCall {
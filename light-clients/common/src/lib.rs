@@ -39,7 +39,14 @@ use ibc::{
 	},
 	Height,
 };
-use serde::{Deserialize, Serialize};
+use serde::{
+	de::{
+		self,
+		value::{MapAccessDeserializer, SeqAccessDeserializer},
+		MapAccess, SeqAccess, Visitor,
+	},
+	Deserialize, Deserializer, Serialize,
+};
 use sp_core::H256;
 use sp_storage::ChildInfo;
 use sp_trie::StorageProof;
@@ -116,22 +123,87 @@ where
 	Ok(())
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
-pub enum RelayChain {
-	Polkadot = 0,
-	Kusama = 1,
-	Rococo = 2,
+/// Parameters of the relay chain that secures a parachain. The known relay chains are available
+/// as constants, any other relay chain (eg. a local zombienet network with a short unbonding
+/// period) can be described with [`RelayChain::new`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct RelayChain {
+	/// Relay chain identifier. The identifiers of the known relay chains match the values of the
+	/// `RelayChain` protobuf enum.
+	pub id: u32,
+	/// Unbonding period of the relay chain validators
+	pub unbonding_period: Duration,
+	/// Fraction of the unbonding period during which a client is trusted, as
+	/// `(numerator, denominator)`
+	pub trusting_period_ratio: (u32, u32),
+}
+
+/// Fields of a [`RelayChain`] as they are serialized.
+#[derive(Deserialize)]
+#[serde(rename = "RelayChain")]
+struct RelayChainFields {
+	id: u32,
+	unbonding_period: Duration,
+	trusting_period_ratio: (u32, u32),
+}
+
+/// Accepts the parameters of the relay chain as well as the name of a known one, which is how
+/// relay chains were serialized (eg. in relayer configs) before their parameters were stored.
+impl<'de> Deserialize<'de> for RelayChain {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct RelayChainVisitor;
+
+		impl<'de> Visitor<'de> for RelayChainVisitor {
+			type Value = RelayChain;
+
+			fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+				write!(f, "the name of a known relay chain or its parameters")
+			}
+
+			fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+				RelayChain::from_str(name).map_err(E::custom)
+			}
+
+			fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+				RelayChainFields::deserialize(MapAccessDeserializer::new(map))?
+					.try_into()
+					.map_err(de::Error::custom)
+			}
+
+			fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+				RelayChainFields::deserialize(SeqAccessDeserializer::new(seq))?
+					.try_into()
+					.map_err(de::Error::custom)
+			}
+		}
+
+		deserializer.deserialize_any(RelayChainVisitor)
+	}
+}
+
+impl TryFrom<RelayChainFields> for RelayChain {
+	type Error = anyhow::Error;
+
+	fn try_from(fields: RelayChainFields) -> Result<Self, Self::Error> {
+		let relay_chain =
+			Self::new(fields.id, fields.unbonding_period, fields.trusting_period_ratio);
+		relay_chain.validate()?;
+		Ok(relay_chain)
+	}
 }
 
 impl Default for RelayChain {
 	fn default() -> Self {
-		RelayChain::Rococo
+		RelayChain::ROCOCO
 	}
 }
 
 impl Display for RelayChain {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-		write!(f, "{}", self.as_str())
+		match self.name() {
+			Some(name) => write!(f, "{name}"),
+			None => write!(f, "Relay{}", self.id),
+		}
 	}
 }
 
@@ -140,38 +212,113 @@ const POLKADOT_UNBONDING_PERIOD: u64 = 28;
 const KUSAMA_UNBONDING_PERIOD: u64 = 7;
 // number of seconds in a day
 const DAY: u64 = 24 * 60 * 60;
+// Trusting period is 1/3 of unbonding period
+const DEFAULT_TRUSTING_PERIOD_RATIO: (u32, u32) = (1, 3);
 
 impl RelayChain {
-	/// Yields the Order as a string
-	pub fn as_str(&self) -> &'static str {
-		match self {
-			Self::Polkadot => "Polkadot",
-			Self::Kusama => "Kusama",
-			Self::Rococo => "Rococo",
+	pub const POLKADOT: Self = Self::new(
+		0,
+		Duration::from_secs(POLKADOT_UNBONDING_PERIOD * DAY),
+		DEFAULT_TRUSTING_PERIOD_RATIO,
+	);
+	pub const KUSAMA: Self = Self::new(
+		1,
+		Duration::from_secs(KUSAMA_UNBONDING_PERIOD * DAY),
+		DEFAULT_TRUSTING_PERIOD_RATIO,
+	);
+	pub const ROCOCO: Self = Self::new(
+		2,
+		Duration::from_secs(KUSAMA_UNBONDING_PERIOD * DAY),
+		DEFAULT_TRUSTING_PERIOD_RATIO,
+	);
+	pub const WESTEND: Self = Self::new(
+		3,
+		Duration::from_secs(KUSAMA_UNBONDING_PERIOD * DAY),
+		DEFAULT_TRUSTING_PERIOD_RATIO,
+	);
+	pub const PASEO: Self = Self::new(
+		4,
+		Duration::from_secs(POLKADOT_UNBONDING_PERIOD * DAY),
+		DEFAULT_TRUSTING_PERIOD_RATIO,
+	);
+
+	/// Relay chains with a well known identifier
+	pub const KNOWN: [Self; 5] =
+		[Self::POLKADOT, Self::KUSAMA, Self::ROCOCO, Self::WESTEND, Self::PASEO];
+
+	pub const fn new(
+		id: u32,
+		unbonding_period: Duration,
+		trusting_period_ratio: (u32, u32),
+	) -> Self {
+		Self { id, unbonding_period, trusting_period_ratio }
+	}
+
+	/// Builds the relay chain from the parameters stored in a client state. The parameters must be
+	/// checked with [`RelayChain::validate`].
+	pub const fn from_params(
+		id: u32,
+		unbonding_period_secs: u64,
+		trusting_period_numerator: u32,
+		trusting_period_denominator: u32,
+	) -> Self {
+		Self::new(
+			id,
+			Duration::from_secs(unbonding_period_secs),
+			(trusting_period_numerator, trusting_period_denominator),
+		)
+	}
+
+	/// Checks that the unbonding period is non-zero, the trusting period ratio is in (0, 1] and
+	/// the trusting period can be computed.
+	pub fn validate(&self) -> Result<(), anyhow::Error> {
+		let (numerator, denominator) = self.trusting_period_ratio;
+		if self.unbonding_period.is_zero() {
+			return Err(anyhow!("Unbonding period of relay chain {} must be non-zero", self.id))
+		}
+		if numerator == 0 || denominator == 0 || numerator > denominator {
+			return Err(anyhow!(
+				"Invalid trusting period ratio {numerator}/{denominator} for relay chain {}",
+				self.id
+			))
 		}
+		self.trusting_period()?;
+		Ok(())
 	}
 
-	// Parses the Order out from a i32.
-	pub fn from_i32(nr: i32) -> Result<Self, anyhow::Error> {
-		match nr {
-			0 => Ok(Self::Polkadot),
-			1 => Ok(Self::Kusama),
-			2 => Ok(Self::Rococo),
-			id => Err(anyhow!("Unknown relay chain {id}")),
+	/// Yields the name of the relay chain, if it's a known one
+	pub fn name(&self) -> Option<&'static str> {
+		match self.id {
+			0 => Some("Polkadot"),
+			1 => Some("Kusama"),
+			2 => Some("Rococo"),
+			3 => Some("Westend"),
+			4 => Some("Paseo"),
+			_ => None,
 		}
 	}
 
+	/// Parses a known relay chain out from its protobuf enum value. Client states that were
+	/// created before the relay chain parameters were stored only hold this value.
+	pub fn from_i32(nr: i32) -> Result<Self, anyhow::Error> {
+		Self::KNOWN
+			.into_iter()
+			.find(|relay_chain| relay_chain.id as i32 == nr)
+			.ok_or_else(|| anyhow!("Unknown relay chain {nr}"))
+	}
+
 	pub fn unbonding_period(&self) -> Duration {
-		match self {
-			Self::Polkadot => Duration::from_secs(POLKADOT_UNBONDING_PERIOD * DAY),
-			Self::Kusama | Self::Rococo => Duration::from_secs(KUSAMA_UNBONDING_PERIOD * DAY),
-		}
+		self.unbonding_period
 	}
 
-	pub fn trusting_period(&self) -> Duration {
-		let unbonding_period = self.unbonding_period();
-		// Trusting period is 1/3 of unbonding period
-		unbonding_period.checked_div(3).unwrap()
+	/// Fraction of the unbonding period during which a client is trusted. Fails if the ratio has
+	/// a zero denominator or the trusting period overflows.
+	pub fn trusting_period(&self) -> Result<Duration, anyhow::Error> {
+		let (numerator, denominator) = self.trusting_period_ratio;
+		self.unbonding_period
+			.checked_mul(numerator)
+			.and_then(|period| period.checked_div(denominator))
+			.ok_or_else(|| anyhow!("Trusting period of relay chain {} overflows", self.id))
 	}
 }
 
@@ -179,12 +326,13 @@ impl FromStr for RelayChain {
 	type Err = anyhow::Error;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		match s.to_lowercase().trim_start_matches("order_") {
-			"polkadot" => Ok(Self::Polkadot),
-			"kusama" => Ok(Self::Kusama),
-			"rococo" => Ok(Self::Rococo),
-			_ => Err(anyhow!("Unknown relay chain {s}")),
-		}
+		let name = s.to_lowercase();
+		Self::KNOWN
+			.into_iter()
+			.find(|relay_chain| {
+				relay_chain.name().map(|known| known.to_lowercase() == name).unwrap_or_default()
+			})
+			.ok_or_else(|| anyhow!("Unknown relay chain {s}"))
 	}
 }
 
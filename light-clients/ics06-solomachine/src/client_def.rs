@@ -0,0 +1,552 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::marker::PhantomData;
+
+use ibc::core::{
+	ics02_client::{
+		client_consensus::ConsensusState as _,
+		client_def::{ClientDef, ConsensusUpdateResult},
+		client_state::ClientState as _,
+		error::Error as Ics02Error,
+	},
+	ics03_connection::connection::ConnectionEnd,
+	ics04_channel::{
+		channel::ChannelEnd,
+		commitment::{AcknowledgementCommitment, PacketCommitment},
+		packet::{Receipt, Sequence},
+		upgrade::{ErrorReceipt, Upgrade},
+	},
+	ics23_commitment::{
+		commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		merkle::apply_prefix,
+	},
+	ics24_host::{
+		identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		path::{
+			AcksPath, ChannelEndsPath, ChannelUpgradeErrorPath, ChannelUpgradePath,
+			ClientConsensusStatePath, ClientStatePath, CommitmentsPath, ConnectionsPath,
+			ReceiptsPath, SeqRecvsPath,
+		},
+		Path,
+	},
+	ics26_routing::context::ReaderContext,
+};
+use ibc_proto::ibc::{
+	core::commitment::v1::MerklePath,
+	lightclients::solomachine::v1::{
+		HeaderData as RawHeaderData, SignBytes as RawSignBytes,
+		TimestampedSignatureData as RawTimestampedSignatureData,
+	},
+};
+use prost::Message;
+use tendermint_proto::Protobuf;
+
+use crate::{
+	client_message::ClientMessage,
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+	error::Error,
+	public_key::{PublicKey, SignatureData},
+	HostFunctionsProvider,
+};
+use ibc::{prelude::*, Height};
+
+/// Path the solo machine signs header data under.
+pub const SENTINEL_HEADER_PATH: &str = "solomachine:header";
+
+/// Solo machine client. Headers and proofs are signatures of the public key of the latest
+/// consensus state over `SignBytes` at the current sequence of the client.
+///
+/// Like in ibc-go, every header and every verified proof consumes the sequence it was signed at,
+/// and a proof moves the consensus state timestamp to its own, so no signature can be used twice.
+/// The proofs of a message are signed at consecutive sequences, in the order they are verified.
+/// The proof height isn't signed, it only has to be a height the client has a consensus state
+/// for. Two different signatures at the same sequence are misbehaviour and freeze the client.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct SoloMachineClient<H>(PhantomData<H>);
+
+impl<H> ClientDef for SoloMachineClient<H>
+where
+	H: HostFunctionsProvider,
+{
+	type ClientMessage = ClientMessage;
+	type ClientState = ClientState<H>;
+	type ConsensusState = ConsensusState;
+
+	fn verify_client_message<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		client_state: Self::ClientState,
+		client_msg: Self::ClientMessage,
+	) -> Result<(), Ics02Error> {
+		let consensus_state = &client_state.consensus_state;
+		match client_msg {
+			ClientMessage::Header(header) => {
+				if header.timestamp < consensus_state.timestamp {
+					return Err(Error::header_timestamp_too_old(
+						header.timestamp,
+						consensus_state.timestamp,
+					)
+					.into())
+				}
+
+				let data = RawHeaderData {
+					new_pub_key: Some(header.new_public_key.into()),
+					new_diversifier: header.new_diversifier,
+				}
+				.encode_to_vec();
+				let sign_bytes = RawSignBytes {
+					sequence: client_state.sequence,
+					timestamp: header.timestamp,
+					diversifier: consensus_state.diversifier.clone(),
+					path: SENTINEL_HEADER_PATH.as_bytes().to_vec(),
+					data,
+				}
+				.encode_to_vec();
+				verify_signature::<H>(&consensus_state.public_key, &sign_bytes, &header.signature)?;
+			},
+			ClientMessage::Misbehaviour(misbehaviour) => {
+				misbehaviour.validate_basic()?;
+				for signature_and_data in [misbehaviour.signature_one, misbehaviour.signature_two] {
+					let sign_bytes = RawSignBytes {
+						sequence: misbehaviour.sequence,
+						timestamp: signature_and_data.timestamp,
+						diversifier: consensus_state.diversifier.clone(),
+						path: signature_and_data.path,
+						data: signature_and_data.data,
+					}
+					.encode_to_vec();
+					verify_signature::<H>(
+						&consensus_state.public_key,
+						&sign_bytes,
+						&signature_and_data.signature,
+					)?;
+				}
+			},
+		}
+
+		Ok(())
+	}
+
+	fn update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		let header = match client_message {
+			ClientMessage::Header(header) => header,
+			_ => unreachable!("02-client will check for Header before calling update_state; qed"),
+		};
+		let consensus_state =
+			ConsensusState::new(header.new_public_key, header.new_diversifier, header.timestamp);
+		let cs = Ctx::AnyConsensusState::wrap(&consensus_state).ok_or_else(|| {
+			Ics02Error::unknown_consensus_state_type("Ctx::AnyConsensusState".to_string())
+		})?;
+		Ok((client_state.with_consensus_state(consensus_state), ConsensusUpdateResult::Single(cs)))
+	}
+
+	fn update_state_on_misbehaviour(
+		&self,
+		client_state: Self::ClientState,
+		_client_message: Self::ClientMessage,
+	) -> Result<Self::ClientState, Ics02Error> {
+		Ok(client_state.with_frozen())
+	}
+
+	/// Any valid misbehaviour message is proof of misbehaviour, while headers can't conflict with
+	/// each other as they are only accepted at the current sequence.
+	fn check_for_misbehaviour<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_client_state: Self::ClientState,
+		client_msg: Self::ClientMessage,
+	) -> Result<bool, Ics02Error> {
+		Ok(matches!(client_msg, ClientMessage::Misbehaviour(_)))
+	}
+
+	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_old_client_state: &Self::ClientState,
+		_upgrade_client_state: &Self::ClientState,
+		_upgrade_consensus_state: &Self::ConsensusState,
+		_proof_upgrade_client: Vec<u8>,
+		_proof_upgrade_consensus_state: Vec<u8>,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		Err(Error::upgrade_not_supported().into())
+	}
+
+	/// Replaces the subject client with the substitute, which must be controlled by a different
+	/// public key.
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		if old_client_state.consensus_state.public_key ==
+			substitute_client_state.consensus_state.public_key
+		{
+			return Err(Error::mismatched_substitute_client_state().into())
+		}
+
+		let cs = Ctx::AnyConsensusState::wrap(&substitute_client_state.consensus_state)
+			.ok_or_else(|| {
+				Ics02Error::unknown_consensus_state_type("Ctx::AnyConsensusState".to_string())
+			})?;
+		let client_state = ClientState { is_frozen: false, ..substitute_client_state };
+
+		Ok((client_state, ConsensusUpdateResult::Single(cs)))
+	}
+
+	fn verify_client_consensus_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		client_state: &Self::ClientState,
+		_height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		client_id: &ClientId,
+		consensus_height: Height,
+		expected_consensus_state: &Ctx::AnyConsensusState,
+	) -> Result<(), Ics02Error> {
+		let path = ClientConsensusStatePath {
+			client_id: client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		};
+		let value = expected_consensus_state.encode_to_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, path, value)
+	}
+
+	fn verify_connection_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		connection_id: &ConnectionId,
+		expected_connection_end: &ConnectionEnd,
+	) -> Result<(), Ics02Error> {
+		let path = ConnectionsPath(connection_id.clone());
+		let value = expected_connection_end.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, path, value)
+	}
+
+	fn verify_channel_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		expected_channel_end: &ChannelEnd,
+	) -> Result<(), Ics02Error> {
+		let path = ChannelEndsPath(port_id.clone(), *channel_id);
+		let value = expected_channel_end.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, path, value)
+	}
+
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
+	) -> Result<(), Ics02Error> {
+		let path = ChannelUpgradePath(port_id.clone(), *channel_id);
+		let value = upgrade.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, path, value)
+	}
+
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
+	) -> Result<(), Ics02Error> {
+		let path = ChannelUpgradeErrorPath(port_id.clone(), *channel_id);
+		let value = error_receipt.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, path, value)
+	}
+
+	fn verify_client_full_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		client_state: &Self::ClientState,
+		_height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		client_id: &ClientId,
+		expected_client_state: &Ctx::AnyClientState,
+	) -> Result<(), Ics02Error> {
+		let path = ClientStatePath(client_id.clone());
+		let value = expected_client_state.encode_to_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, path, value)
+	}
+
+	fn verify_packet_data<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		commitment: PacketCommitment,
+	) -> Result<(), Ics02Error> {
+		let commitment_path =
+			CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			commitment_path,
+			commitment.into_vec(),
+		)
+	}
+
+	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack_commitment: AcknowledgementCommitment,
+	) -> Result<(), Ics02Error> {
+		let ack_path = AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			ack_path,
+			ack_commitment.into_vec(),
+		)
+	}
+
+	fn verify_next_sequence_recv<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		let mut seq_bytes = Vec::new();
+		u64::from(sequence).encode(&mut seq_bytes).expect("buffer size too small");
+
+		let seq_path = SeqRecvsPath(port_id.clone(), *channel_id);
+		verify_membership::<H, _>(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			seq_path,
+			seq_bytes,
+		)
+	}
+
+	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_non_membership::<H, _>(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			receipt_path,
+		)
+	}
+
+	fn verify_packet_timeout_receipt<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		_height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			receipt_path,
+			Receipt::Timeout.as_bytes().to_vec(),
+		)
+	}
+
+	/// A verified proof consumes the sequence it was signed at, and its timestamp becomes the
+	/// timestamp of the consensus state.
+	fn update_state_on_verified_proof(
+		&self,
+		client_state: Self::ClientState,
+		proof: &CommitmentProofBytes,
+	) -> Result<Self::ClientState, Ics02Error> {
+		let RawTimestampedSignatureData { timestamp, .. } =
+			RawTimestampedSignatureData::decode(proof.as_bytes())
+				.map_err(|e| Error::invalid_proof(e.to_string()))?;
+		Ok(client_state.with_verified_proof(timestamp))
+	}
+}
+
+/// Verifies that `proof` is a `TimestampedSignatureData` of the solo machine over `value` stored
+/// under `path`, signed at the current sequence of the client.
+pub fn verify_membership<H, P>(
+	client_state: &ClientState<H>,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	path: P,
+	value: Vec<u8>,
+) -> Result<(), Ics02Error>
+where
+	P: Into<Path>,
+	H: HostFunctionsProvider,
+{
+	let RawTimestampedSignatureData { signature_data, timestamp } =
+		RawTimestampedSignatureData::decode(proof.as_bytes())
+			.map_err(|e| Error::invalid_proof(e.to_string()))?;
+	let consensus_state = &client_state.consensus_state;
+	if timestamp < consensus_state.timestamp {
+		return Err(Error::proof_timestamp_too_old(timestamp, consensus_state.timestamp).into())
+	}
+
+	let merkle_path = apply_prefix(prefix, vec![path.into().to_string()]);
+	let sign_bytes = RawSignBytes {
+		sequence: client_state.sequence,
+		timestamp,
+		diversifier: consensus_state.diversifier.clone(),
+		path: merkle_path_to_string(&merkle_path).into_bytes(),
+		data: value,
+	}
+	.encode_to_vec();
+
+	verify_signature::<H>(&consensus_state.public_key, &sign_bytes, &signature_data)
+		.map_err(Into::into)
+}
+
+/// Verifies that `proof` is a signature of the solo machine over the absence of `path`, ie. over
+/// empty data.
+pub fn verify_non_membership<H, P>(
+	client_state: &ClientState<H>,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	path: P,
+) -> Result<(), Ics02Error>
+where
+	P: Into<Path>,
+	H: HostFunctionsProvider,
+{
+	verify_membership::<H, _>(client_state, prefix, proof, path, Vec::new())
+}
+
+fn verify_signature<H: HostFunctionsProvider>(
+	public_key: &PublicKey,
+	sign_bytes: &[u8],
+	signature: &[u8],
+) -> Result<(), Error> {
+	let signature = SignatureData::decode(signature)?;
+	public_key.verify_signature::<H>(sign_bytes, &signature)
+}
+
+/// Formats the merkle path as ibc-go's `MerklePath::String`, which is what solo machines sign:
+/// every key is path escaped and prefixed with a `/`.
+pub fn merkle_path_to_string(merkle_path: &MerklePath) -> String {
+	let mut path = String::new();
+	for key in &merkle_path.key_path {
+		path.push('/');
+		for byte in key.bytes() {
+			match byte {
+				b'A'..=b'Z' |
+				b'a'..=b'z' |
+				b'0'..=b'9' |
+				b'-' |
+				b'_' |
+				b'.' |
+				b'~' |
+				b'$' |
+				b'&' |
+				b'+' |
+				b',' |
+				b':' |
+				b';' |
+				b'=' |
+				b'@' => path.push(byte as char),
+				_ => path.push_str(&format!("%{:02X}", byte)),
+			}
+		}
+	}
+	path
+}
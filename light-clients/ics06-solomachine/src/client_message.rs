@@ -0,0 +1,242 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use crate::{error::Error, public_key::PublicKey};
+use ibc::core::ics02_client;
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::lightclients::solomachine::v1::{
+		Header as RawHeader, Misbehaviour as RawMisbehaviour,
+		SignatureAndData as RawSignatureAndData,
+	},
+};
+use tendermint_proto::Protobuf;
+
+pub const SOLOMACHINE_HEADER_TYPE_URL: &str = "/ibc.lightclients.solomachine.v3.Header";
+pub const SOLOMACHINE_MISBEHAVIOUR_TYPE_URL: &str = "/ibc.lightclients.solomachine.v3.Misbehaviour";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientMessage {
+	Header(Header),
+	Misbehaviour(Misbehaviour),
+}
+
+impl ics02_client::client_message::ClientMessage for ClientMessage {
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<Any> for ClientMessage {}
+
+impl TryFrom<Any> for ClientMessage {
+	type Error = Error;
+
+	fn try_from(any: Any) -> Result<Self, Self::Error> {
+		let msg = match &*any.type_url {
+			SOLOMACHINE_HEADER_TYPE_URL => Self::Header(
+				Header::decode_vec(&any.value)
+					.map_err(|e| Error::invalid_raw_header(format!("{e:?}")))?,
+			),
+			SOLOMACHINE_MISBEHAVIOUR_TYPE_URL => Self::Misbehaviour(
+				Misbehaviour::decode_vec(&any.value)
+					.map_err(|e| Error::invalid_raw_misbehaviour(format!("{e:?}")))?,
+			),
+			_ => Err(Error::invalid_raw_header(format!("Unknown type: {}", any.type_url)))?,
+		};
+
+		Ok(msg)
+	}
+}
+
+impl From<ClientMessage> for Any {
+	fn from(msg: ClientMessage) -> Self {
+		match msg {
+			ClientMessage::Header(header) => Any {
+				value: header.encode_vec().expect("failed to encode ClientMessage.header"),
+				type_url: SOLOMACHINE_HEADER_TYPE_URL.to_string(),
+			},
+			ClientMessage::Misbehaviour(misbehaviour) => Any {
+				value: misbehaviour
+					.encode_vec()
+					.expect("failed to encode ClientMessage.misbehaviour"),
+				type_url: SOLOMACHINE_MISBEHAVIOUR_TYPE_URL.to_string(),
+			},
+		}
+	}
+}
+
+/// Rotates the public key and diversifier of the solo machine. It is signed by the current
+/// public key at the current sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+	/// Timestamp in nanoseconds.
+	pub timestamp: u64,
+	/// Encoded [`SignatureData`](crate::public_key::SignatureData).
+	pub signature: Vec<u8>,
+	pub new_public_key: PublicKey,
+	pub new_diversifier: String,
+}
+
+impl Protobuf<RawHeader> for Header {}
+
+impl TryFrom<RawHeader> for Header {
+	type Error = Error;
+
+	fn try_from(raw: RawHeader) -> Result<Self, Self::Error> {
+		if raw.timestamp == 0 {
+			return Err(Error::invalid_raw_header("timestamp cannot be 0".into()))
+		}
+		ibc::timestamp::Timestamp::from_nanoseconds(raw.timestamp)
+			.map_err(|e| Error::invalid_raw_header(format!("invalid timestamp: {}", e)))?;
+		if raw.signature.is_empty() {
+			return Err(Error::invalid_raw_header("signature cannot be empty".into()))
+		}
+		if !raw.new_diversifier.is_empty() && raw.new_diversifier.trim().is_empty() {
+			return Err(Error::invalid_raw_header("diversifier cannot be blank".into()))
+		}
+
+		Ok(Self {
+			timestamp: raw.timestamp,
+			signature: raw.signature,
+			new_public_key: raw
+				.new_public_key
+				.ok_or_else(|| Error::invalid_raw_header("missing new public key".into()))?
+				.try_into()?,
+			new_diversifier: raw.new_diversifier,
+		})
+	}
+}
+
+impl From<Header> for RawHeader {
+	fn from(value: Header) -> Self {
+		RawHeader {
+			timestamp: value.timestamp,
+			signature: value.signature,
+			new_public_key: Some(value.new_public_key.into()),
+			new_diversifier: value.new_diversifier,
+		}
+	}
+}
+
+/// Two different signatures of the solo machine for the same sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Misbehaviour {
+	pub sequence: u64,
+	pub signature_one: SignatureAndData,
+	pub signature_two: SignatureAndData,
+}
+
+impl Misbehaviour {
+	/// Checks that the two signatures attest to conflicting statements, following ibc-go's
+	/// `Misbehaviour::ValidateBasic`.
+	pub fn validate_basic(&self) -> Result<(), Error> {
+		if self.sequence == 0 {
+			return Err(Error::invalid_misbehaviour("sequence cannot be 0".into()))
+		}
+		self.signature_one.validate_basic()?;
+		self.signature_two.validate_basic()?;
+		if self.signature_one.signature == self.signature_two.signature {
+			return Err(Error::invalid_misbehaviour("signatures cannot be equal".into()))
+		}
+		// Every header and proof consumes a sequence, so an honest solo machine never signs two
+		// different statements at the same sequence, whatever their paths.
+		if self.signature_one.path == self.signature_two.path &&
+			self.signature_one.data == self.signature_two.data
+		{
+			return Err(Error::invalid_misbehaviour(
+				"signed data cannot be equal for the same path".into(),
+			))
+		}
+		Ok(())
+	}
+}
+
+impl Protobuf<RawMisbehaviour> for Misbehaviour {}
+
+impl TryFrom<RawMisbehaviour> for Misbehaviour {
+	type Error = Error;
+
+	fn try_from(raw: RawMisbehaviour) -> Result<Self, Self::Error> {
+		Ok(Self {
+			sequence: raw.sequence,
+			signature_one: raw
+				.signature_one
+				.ok_or_else(|| Error::invalid_raw_misbehaviour("missing signature one".into()))?
+				.into(),
+			signature_two: raw
+				.signature_two
+				.ok_or_else(|| Error::invalid_raw_misbehaviour("missing signature two".into()))?
+				.into(),
+		})
+	}
+}
+
+impl From<Misbehaviour> for RawMisbehaviour {
+	fn from(value: Misbehaviour) -> Self {
+		RawMisbehaviour {
+			sequence: value.sequence,
+			signature_one: Some(value.signature_one.into()),
+			signature_two: Some(value.signature_two.into()),
+		}
+	}
+}
+
+/// A signature of the solo machine together with the path and data it signed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureAndData {
+	/// Encoded [`SignatureData`](crate::public_key::SignatureData).
+	pub signature: Vec<u8>,
+	pub path: Vec<u8>,
+	pub data: Vec<u8>,
+	/// Timestamp in nanoseconds.
+	pub timestamp: u64,
+}
+
+impl SignatureAndData {
+	pub fn validate_basic(&self) -> Result<(), Error> {
+		if self.signature.is_empty() {
+			return Err(Error::invalid_misbehaviour("signature cannot be empty".into()))
+		}
+		if self.path.is_empty() {
+			return Err(Error::invalid_misbehaviour("path cannot be empty".into()))
+		}
+		if self.data.is_empty() {
+			return Err(Error::invalid_misbehaviour("data cannot be empty".into()))
+		}
+		if self.timestamp == 0 {
+			return Err(Error::invalid_misbehaviour("timestamp cannot be 0".into()))
+		}
+		Ok(())
+	}
+}
+
+impl From<RawSignatureAndData> for SignatureAndData {
+	fn from(raw: RawSignatureAndData) -> Self {
+		Self { signature: raw.signature, path: raw.path, data: raw.data, timestamp: raw.timestamp }
+	}
+}
+
+impl From<SignatureAndData> for RawSignatureAndData {
+	fn from(value: SignatureAndData) -> Self {
+		RawSignatureAndData {
+			signature: value.signature,
+			path: value.path,
+			data: value.data,
+			timestamp: value.timestamp,
+		}
+	}
+}
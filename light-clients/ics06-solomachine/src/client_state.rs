@@ -0,0 +1,180 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use core::{fmt::Debug, marker::PhantomData, time::Duration};
+
+use crate::{
+	client_def::SoloMachineClient, consensus_state::ConsensusState, error::Error,
+	HostFunctionsProvider,
+};
+use ibc::{
+	core::{
+		ics02_client::client_state::{ClientType, Status},
+		ics24_host::identifier::{ChainId, ClientId},
+		ics26_routing::context::ReaderContext,
+	},
+	Height,
+};
+use ibc_proto::{
+	google::protobuf::Any, ibc::lightclients::solomachine::v1::ClientState as RawClientState,
+};
+use tendermint_proto::Protobuf;
+
+pub const SOLOMACHINE_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.solomachine.v3.ClientState";
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ClientState<H> {
+	/// Sequence of the next signature, it is the revision height of the client.
+	pub sequence: u64,
+	/// Set once misbehaviour of the solo machine has been submitted.
+	pub is_frozen: bool,
+	pub consensus_state: ConsensusState,
+	pub _phantom: PhantomData<H>,
+}
+
+impl<H> ClientState<H> {
+	pub fn new(sequence: u64, consensus_state: ConsensusState) -> Self {
+		Self { sequence, is_frozen: false, consensus_state, _phantom: PhantomData }
+	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: SOLOMACHINE_CLIENT_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ClientState"),
+		}
+	}
+
+	/// Solo machines have a single revision, so the height is the sequence.
+	pub fn latest_height(&self) -> Height {
+		Height::new(0, self.sequence)
+	}
+
+	/// Solo machines have no chain identifier.
+	pub fn chain_id(&self) -> ChainId {
+		ChainId::default()
+	}
+
+	pub fn client_type() -> ClientType {
+		"06-solomachine".to_string()
+	}
+
+	/// Solo machines don't track the height of the misbehaviour, so a frozen client reports the
+	/// lowest height, like ibc-go does.
+	pub fn frozen_height(&self) -> Option<Height> {
+		self.is_frozen.then(|| Height::new(0, 1))
+	}
+
+	/// Installs the consensus state of a verified header and moves on to the next sequence.
+	pub fn with_consensus_state(self, consensus_state: ConsensusState) -> Self {
+		Self { sequence: self.sequence + 1, consensus_state, ..self }
+	}
+
+	/// Moves on to the next sequence once a proof signed at `timestamp` was verified.
+	pub fn with_verified_proof(self, timestamp: u64) -> Self {
+		let consensus_state = ConsensusState { timestamp, ..self.consensus_state };
+		Self { sequence: self.sequence + 1, consensus_state, ..self }
+	}
+
+	pub fn with_frozen(self) -> Self {
+		Self { is_frozen: true, ..self }
+	}
+}
+
+impl<H> ibc::core::ics02_client::client_state::ClientState for ClientState<H>
+where
+	H: HostFunctionsProvider,
+{
+	type UpgradeOptions = ();
+	type ClientDef = SoloMachineClient<H>;
+
+	fn chain_id(&self) -> ChainId {
+		self.chain_id()
+	}
+
+	fn client_def(&self) -> Self::ClientDef {
+		SoloMachineClient::default()
+	}
+
+	fn client_type(&self) -> ClientType {
+		Self::client_type()
+	}
+
+	fn latest_height(&self) -> Height {
+		self.latest_height()
+	}
+
+	fn status<Ctx: ReaderContext>(&self, _ctx: &Ctx, _client_id: &ClientId) -> Status {
+		if self.is_frozen {
+			return Status::Frozen
+		}
+		Status::Active
+	}
+
+	fn frozen_height(&self) -> Option<Height> {
+		self.frozen_height()
+	}
+
+	/// Solo machines can't be upgraded, the client state is returned unchanged.
+	fn upgrade(
+		self,
+		_upgrade_height: Height,
+		_upgrade_options: Self::UpgradeOptions,
+		_chain_id: ChainId,
+	) -> Self {
+		self
+	}
+
+	/// Solo machines have no trusting period, their consensus state never expires.
+	fn expired(&self, _elapsed: Duration) -> bool {
+		false
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl<H> Protobuf<RawClientState> for ClientState<H> {}
+
+impl<H> TryFrom<RawClientState> for ClientState<H> {
+	type Error = Error;
+
+	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+		if raw.sequence == 0 {
+			return Err(Error::invalid_raw_client_state("sequence cannot be 0".into()))
+		}
+
+		Ok(Self {
+			sequence: raw.sequence,
+			is_frozen: raw.is_frozen,
+			consensus_state: raw
+				.consensus_state
+				.ok_or_else(|| Error::invalid_raw_client_state("missing consensus state".into()))?
+				.try_into()?,
+			_phantom: PhantomData,
+		})
+	}
+}
+
+impl<H> From<ClientState<H>> for RawClientState {
+	fn from(value: ClientState<H>) -> Self {
+		RawClientState {
+			sequence: value.sequence,
+			is_frozen: value.is_frozen,
+			consensus_state: Some(value.consensus_state.into()),
+		}
+	}
+}
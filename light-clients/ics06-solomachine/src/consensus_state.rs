@@ -0,0 +1,109 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use core::convert::Infallible;
+
+use crate::{error::Error, public_key::PublicKey};
+use ibc::{core::ics23_commitment::commitment::CommitmentRoot, timestamp::Timestamp};
+use ibc_proto::{
+	google::protobuf::Any, ibc::lightclients::solomachine::v1::ConsensusState as RawConsensusState,
+};
+use tendermint_proto::Protobuf;
+
+/// Protobuf type url for Solo Machine Consensus State
+pub const SOLOMACHINE_CONSENSUS_STATE_TYPE_URL: &str =
+	"/ibc.lightclients.solomachine.v3.ConsensusState";
+
+/// Solo machines don't commit to their state, every proof is checked against the public key
+/// instead.
+static EMPTY_ROOT: CommitmentRoot = CommitmentRoot { bytes: Vec::new() };
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusState {
+	/// Public key the solo machine signs headers and proofs with.
+	pub public_key: PublicKey,
+	/// Arbitrary string added to the sign bytes, so that a signature for one client can't be
+	/// replayed on another client sharing the same public key.
+	pub diversifier: String,
+	/// Timestamp in nanoseconds.
+	pub timestamp: u64,
+}
+
+impl ConsensusState {
+	pub fn new(public_key: PublicKey, diversifier: String, timestamp: u64) -> Self {
+		Self { public_key, diversifier, timestamp }
+	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: SOLOMACHINE_CONSENSUS_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ConsensusState"),
+		}
+	}
+}
+
+impl ibc::core::ics02_client::client_consensus::ConsensusState for ConsensusState {
+	type Error = Infallible;
+
+	fn root(&self) -> &CommitmentRoot {
+		&EMPTY_ROOT
+	}
+
+	fn timestamp(&self) -> Timestamp {
+		Timestamp::from_nanoseconds(self.timestamp)
+			.expect("timestamp is validated when decoding the consensus state; qed")
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+	type Error = Error;
+
+	fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+		if raw.timestamp == 0 {
+			return Err(Error::invalid_raw_consensus_state("timestamp cannot be 0".into()))
+		}
+		Timestamp::from_nanoseconds(raw.timestamp)
+			.map_err(|e| Error::invalid_raw_consensus_state(format!("invalid timestamp: {}", e)))?;
+		if !raw.diversifier.is_empty() && raw.diversifier.trim().is_empty() {
+			return Err(Error::invalid_raw_consensus_state("diversifier cannot be blank".into()))
+		}
+
+		Ok(Self {
+			public_key: raw
+				.public_key
+				.ok_or_else(|| Error::invalid_raw_consensus_state("missing public key".into()))?
+				.try_into()?,
+			diversifier: raw.diversifier,
+			timestamp: raw.timestamp,
+		})
+	}
+}
+
+impl From<ConsensusState> for RawConsensusState {
+	fn from(value: ConsensusState) -> Self {
+		RawConsensusState {
+			public_key: Some(value.public_key.into()),
+			diversifier: value.diversifier,
+			timestamp: value.timestamp,
+		}
+	}
+}
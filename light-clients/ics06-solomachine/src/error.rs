@@ -0,0 +1,101 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use flex_error::define_error;
+
+use crate::client_state::ClientState;
+use ibc::core::ics02_client::error::Error as Ics02Error;
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		InvalidRawClientState
+			{ reason: String }
+			|e| { format_args!("invalid raw client state: {}", e.reason) },
+
+		InvalidRawConsensusState
+			{ reason: String }
+			|e| { format_args!("invalid raw consensus state: {}", e.reason) },
+
+		InvalidRawHeader
+			{ reason: String }
+			|e| { format_args!("invalid raw header: {}", e.reason) },
+
+		InvalidRawMisbehaviour
+			{ reason: String }
+			|e| { format_args!("invalid raw misbehaviour: {}", e.reason) },
+
+		InvalidMisbehaviour
+			{ reason: String }
+			|e| { format_args!("invalid misbehaviour: {}", e.reason) },
+
+		InvalidPublicKey
+			{ reason: String }
+			|e| { format_args!("invalid public key: {}", e.reason) },
+
+		UnknownPublicKeyType
+			{ type_url: String }
+			|e| { format_args!("unknown public key type: {}", e.type_url) },
+
+		InvalidSignatureData
+			{ reason: String }
+			|e| { format_args!("invalid signature data: {}", e.reason) },
+
+		InvalidSignature
+			|_| { "signature verification failed" },
+
+		SignatureTypeMismatch
+			|_| { "signature data does not match the type of the public key" },
+
+		InvalidMultisig
+			{ reason: String }
+			|e| { format_args!("invalid multisig signature: {}", e.reason) },
+
+		InsufficientSignatures
+			{ threshold: u32, signers: usize }
+			|e| {
+				format_args!("insufficient signatures: threshold={0} signers={1}", e.threshold, e.signers)
+			},
+
+		InvalidProof
+			{ reason: String }
+			|e| { format_args!("invalid proof: {}", e.reason) },
+
+		HeaderTimestampTooOld
+			{ header_timestamp: u64, consensus_timestamp: u64 }
+			|e| {
+				format_args!("header timestamp {0} is less than the consensus state timestamp {1}", e.header_timestamp, e.consensus_timestamp)
+			},
+
+		ProofTimestampTooOld
+			{ proof_timestamp: u64, consensus_timestamp: u64 }
+			|e| {
+				format_args!("proof timestamp {0} is less than the consensus state timestamp {1}", e.proof_timestamp, e.consensus_timestamp)
+			},
+
+		UpgradeNotSupported
+			|_| { "solo machine client does not support upgrades" },
+
+		MismatchedSubstituteClientState
+			|_| { "subject and substitute client states have the same public key" },
+	}
+}
+
+impl From<Error> for Ics02Error {
+	fn from(e: Error) -> Self {
+		Ics02Error::client_error(ClientState::<()>::client_type().to_owned(), e.to_string())
+	}
+}
@@ -0,0 +1,47 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![allow(clippy::all)]
+
+//! ICS 06: Solo Machine Client implements a client verification algorithm for standalone
+//! machines, such as a single device or an off-chain process, identified by a public key.
+//! Every state transition and every proof is a signature of that key over the bytes being
+//! attested, bound to a monotonically increasing sequence number.
+
+extern crate alloc;
+
+use core::fmt::Debug;
+
+pub mod client_def;
+pub mod client_message;
+pub mod client_state;
+pub mod consensus_state;
+pub mod error;
+pub mod public_key;
+
+#[cfg(test)]
+mod tests;
+
+/// Host functions that allow the light client verify signatures of the solo machine in native.
+pub trait HostFunctionsProvider: Debug + Clone + Send + Sync + Default + Eq + 'static {
+	/// Returns the SHA-256 digest of `data`.
+	fn sha256_digest(data: &[u8]) -> [u8; 32];
+
+	/// Verifies a 64 byte ed25519 `signature` of `msg` against a 32 byte `public_key`.
+	fn ed25519_verify(signature: &[u8], msg: &[u8], public_key: &[u8]) -> bool;
+
+	/// Verifies a 64 byte `r || s` secp256k1 `signature` of the message hash `msg` against a
+	/// 33 byte compressed `public_key`.
+	fn secp256k1_verify(signature: &[u8], msg: &[u8; 32], public_key: &[u8]) -> bool;
+}
@@ -0,0 +1,325 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Public keys of a solo machine and the signatures they produce, following the encodings of
+//! the Cosmos SDK `cosmos.crypto` and `cosmos.tx.signing` packages.
+
+use ibc::prelude::*;
+
+use crate::{error::Error, HostFunctionsProvider};
+use ibc_proto::{
+	cosmos::{
+		crypto::multisig::v1beta1::CompactBitArray,
+		tx::signing::v1beta1::{
+			signature_descriptor::{
+				data::{Multi as RawMulti, Single as RawSingle, Sum},
+				Data as RawSignatureData,
+			},
+			SignMode,
+		},
+	},
+	google::protobuf::Any,
+};
+use prost::Message;
+
+pub const SECP256K1_PUBLIC_KEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+pub const ED25519_PUBLIC_KEY_TYPE_URL: &str = "/cosmos.crypto.ed25519.PubKey";
+pub const MULTISIG_PUBLIC_KEY_TYPE_URL: &str = "/cosmos.crypto.multisig.LegacyAminoPubKey";
+
+const SECP256K1_PUBLIC_KEY_LENGTH: usize = 33;
+const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+const SIGNATURE_LENGTH: usize = 64;
+
+/// Maximum nesting of multisig public keys, deeper keys are rejected when decoding.
+pub const MAX_MULTISIG_DEPTH: usize = 4;
+
+/// Half of the order of the secp256k1 curve. Signatures with an `s` value above it are rejected,
+/// as the Cosmos SDK only accepts the lower form of the two malleable signatures.
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+	0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+	0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// Protobuf messages of the Cosmos SDK public keys, which are not part of `ibc-proto`.
+pub mod proto {
+	use ibc::prelude::*;
+	use ibc_proto::google::protobuf::Any;
+
+	/// `cosmos.crypto.secp256k1.PubKey` and `cosmos.crypto.ed25519.PubKey`.
+	#[derive(Clone, PartialEq, ::prost::Message)]
+	pub struct PubKey {
+		#[prost(bytes = "vec", tag = "1")]
+		pub key: Vec<u8>,
+	}
+
+	/// `cosmos.crypto.multisig.LegacyAminoPubKey`.
+	#[derive(Clone, PartialEq, ::prost::Message)]
+	pub struct LegacyAminoPubKey {
+		#[prost(uint32, tag = "1")]
+		pub threshold: u32,
+		#[prost(message, repeated, tag = "2")]
+		pub public_keys: Vec<Any>,
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PublicKey {
+	/// Compressed secp256k1 public key.
+	Secp256k1(Vec<u8>),
+	Ed25519(Vec<u8>),
+	/// `threshold`-of-`public_keys` multisig.
+	Multisig {
+		threshold: u32,
+		public_keys: Vec<PublicKey>,
+	},
+}
+
+impl PublicKey {
+	/// Verifies `signature` over `msg`. Multisig signatures must contain a valid signature of
+	/// `msg` for every signer set in their bit array, and at least `threshold` of them.
+	pub fn verify_signature<H: HostFunctionsProvider>(
+		&self,
+		msg: &[u8],
+		signature: &SignatureData,
+	) -> Result<(), Error> {
+		match (self, signature) {
+			(PublicKey::Secp256k1(public_key), SignatureData::Single(signature)) => {
+				if signature.len() != SIGNATURE_LENGTH || signature[32..] > SECP256K1_HALF_ORDER[..]
+				{
+					return Err(Error::invalid_signature())
+				}
+				let msg_hash = H::sha256_digest(msg);
+				H::secp256k1_verify(signature, &msg_hash, public_key)
+					.then_some(())
+					.ok_or_else(Error::invalid_signature)
+			},
+			(PublicKey::Ed25519(public_key), SignatureData::Single(signature)) => {
+				if signature.len() != SIGNATURE_LENGTH {
+					return Err(Error::invalid_signature())
+				}
+				H::ed25519_verify(signature, msg, public_key)
+					.then_some(())
+					.ok_or_else(Error::invalid_signature)
+			},
+			(
+				PublicKey::Multisig { threshold, public_keys },
+				SignatureData::Multi { bit_array, signatures },
+			) => {
+				let size = bit_array_len(bit_array);
+				if size != public_keys.len() {
+					return Err(Error::invalid_multisig(format!(
+						"bit array size {} does not match the number of public keys {}",
+						size,
+						public_keys.len()
+					)))
+				}
+
+				let mut signatures = signatures.iter();
+				let mut signers = 0;
+				for (index, public_key) in public_keys.iter().enumerate() {
+					if !bit_array_get(bit_array, index) {
+						continue
+					}
+					let signature = signatures.next().ok_or_else(|| {
+						Error::invalid_multisig("fewer signatures than signers".to_string())
+					})?;
+					public_key.verify_signature::<H>(msg, signature)?;
+					signers += 1;
+				}
+				if signatures.next().is_some() {
+					return Err(Error::invalid_multisig("more signatures than signers".to_string()))
+				}
+				if signers < *threshold as usize {
+					return Err(Error::insufficient_signatures(*threshold, signers))
+				}
+
+				Ok(())
+			},
+			_ => Err(Error::signature_type_mismatch()),
+		}
+	}
+}
+
+impl TryFrom<Any> for PublicKey {
+	type Error = Error;
+
+	fn try_from(any: Any) -> Result<Self, Self::Error> {
+		Self::decode_nested(any, 0)
+	}
+}
+
+impl PublicKey {
+	/// Decodes a public key nested in `depth` multisig keys.
+	fn decode_nested(any: Any, depth: usize) -> Result<Self, Error> {
+		match any.type_url.as_str() {
+			SECP256K1_PUBLIC_KEY_TYPE_URL => {
+				let key = decode_key(&any.value, SECP256K1_PUBLIC_KEY_LENGTH)?;
+				Ok(Self::Secp256k1(key))
+			},
+			ED25519_PUBLIC_KEY_TYPE_URL => {
+				let key = decode_key(&any.value, ED25519_PUBLIC_KEY_LENGTH)?;
+				Ok(Self::Ed25519(key))
+			},
+			MULTISIG_PUBLIC_KEY_TYPE_URL => {
+				if depth >= MAX_MULTISIG_DEPTH {
+					return Err(Error::invalid_public_key(format!(
+						"multisig public keys cannot be nested more than {MAX_MULTISIG_DEPTH} levels"
+					)))
+				}
+				let raw = proto::LegacyAminoPubKey::decode(&*any.value)
+					.map_err(|e| Error::invalid_public_key(e.to_string()))?;
+				if raw.threshold == 0 || raw.threshold as usize > raw.public_keys.len() {
+					return Err(Error::invalid_public_key(format!(
+						"invalid multisig threshold {} for {} public keys",
+						raw.threshold,
+						raw.public_keys.len()
+					)))
+				}
+				let public_keys = raw
+					.public_keys
+					.into_iter()
+					.map(|public_key| Self::decode_nested(public_key, depth + 1))
+					.collect::<Result<_, _>>()?;
+				Ok(Self::Multisig { threshold: raw.threshold, public_keys })
+			},
+			_ => Err(Error::unknown_public_key_type(any.type_url)),
+		}
+	}
+}
+
+impl From<PublicKey> for Any {
+	fn from(public_key: PublicKey) -> Self {
+		match public_key {
+			PublicKey::Secp256k1(key) => Any {
+				type_url: SECP256K1_PUBLIC_KEY_TYPE_URL.to_string(),
+				value: proto::PubKey { key }.encode_to_vec(),
+			},
+			PublicKey::Ed25519(key) => Any {
+				type_url: ED25519_PUBLIC_KEY_TYPE_URL.to_string(),
+				value: proto::PubKey { key }.encode_to_vec(),
+			},
+			PublicKey::Multisig { threshold, public_keys } => Any {
+				type_url: MULTISIG_PUBLIC_KEY_TYPE_URL.to_string(),
+				value: proto::LegacyAminoPubKey {
+					threshold,
+					public_keys: public_keys.into_iter().map(Into::into).collect(),
+				}
+				.encode_to_vec(),
+			},
+		}
+	}
+}
+
+fn decode_key(bytes: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+	let proto::PubKey { key } =
+		proto::PubKey::decode(bytes).map_err(|e| Error::invalid_public_key(e.to_string()))?;
+	if key.len() != length {
+		return Err(Error::invalid_public_key(format!(
+			"expected {} bytes, got {}",
+			length,
+			key.len()
+		)))
+	}
+	Ok(key)
+}
+
+/// Signature of a solo machine, encoded as a `cosmos.tx.signing.v1beta1.SignatureDescriptor.Data`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignatureData {
+	Single(Vec<u8>),
+	/// Signatures of the multisig signers whose bit is set in `bit_array`, in signer order.
+	Multi {
+		bit_array: CompactBitArray,
+		signatures: Vec<SignatureData>,
+	},
+}
+
+impl SignatureData {
+	pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+		RawSignatureData::decode(bytes)
+			.map_err(|e| Error::invalid_signature_data(e.to_string()))?
+			.try_into()
+	}
+
+	pub fn encode_to_vec(self) -> Vec<u8> {
+		RawSignatureData::from(self).encode_to_vec()
+	}
+}
+
+impl TryFrom<RawSignatureData> for SignatureData {
+	type Error = Error;
+
+	fn try_from(raw: RawSignatureData) -> Result<Self, Self::Error> {
+		match raw
+			.sum
+			.ok_or_else(|| Error::invalid_signature_data("missing sum".to_string()))?
+		{
+			Sum::Single(RawSingle { signature, .. }) => Ok(Self::Single(signature)),
+			Sum::Multi(RawMulti { bitarray, signatures }) => {
+				let bit_array = bitarray.ok_or_else(|| {
+					Error::invalid_signature_data("missing multisig bit array".to_string())
+				})?;
+				if bit_array.extra_bits_stored >= 8 {
+					return Err(Error::invalid_signature_data(format!(
+						"invalid multisig bit array: {} extra bits stored",
+						bit_array.extra_bits_stored
+					)))
+				}
+				Ok(Self::Multi {
+					bit_array,
+					signatures: signatures
+						.into_iter()
+						.map(SignatureData::try_from)
+						.collect::<Result<_, _>>()?,
+				})
+			},
+		}
+	}
+}
+
+impl From<SignatureData> for RawSignatureData {
+	fn from(value: SignatureData) -> Self {
+		let sum = match value {
+			SignatureData::Single(signature) =>
+				Sum::Single(RawSingle { mode: SignMode::Direct as i32, signature }),
+			SignatureData::Multi { bit_array, signatures } => Sum::Multi(RawMulti {
+				bitarray: Some(bit_array),
+				signatures: signatures.into_iter().map(Into::into).collect(),
+			}),
+		};
+		RawSignatureData { sum: Some(sum) }
+	}
+}
+
+/// Number of bits in the array, see the Cosmos SDK `CompactBitArray::Count`.
+pub fn bit_array_len(bit_array: &CompactBitArray) -> usize {
+	match bit_array.extra_bits_stored {
+		0 => bit_array.elems.len() * 8,
+		extra => bit_array.elems.len().saturating_sub(1) * 8 + extra as usize,
+	}
+}
+
+/// Whether the bit at `index` is set. Bits are stored most significant first.
+pub fn bit_array_get(bit_array: &CompactBitArray, index: usize) -> bool {
+	index < bit_array_len(bit_array) && bit_array.elems[index / 8] & (1 << (7 - index % 8)) != 0
+}
+
+/// Builds a bit array of `bits.len()` bits, with the bits set where `bits` is `true`.
+pub fn bit_array_from_bits(bits: &[bool]) -> CompactBitArray {
+	let mut elems = vec![0u8; (bits.len() + 7) / 8];
+	for (index, _) in bits.iter().enumerate().filter(|(_, bit)| **bit) {
+		elems[index / 8] |= 1 << (7 - index % 8);
+	}
+	CompactBitArray { extra_bits_stored: (bits.len() % 8) as u32, elems }
+}
@@ -0,0 +1,483 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_def::{merkle_path_to_string, SoloMachineClient, SENTINEL_HEADER_PATH},
+	client_message::{ClientMessage, Header, Misbehaviour, SignatureAndData},
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+	public_key::{bit_array_from_bits, PublicKey, SignatureData, MAX_MULTISIG_DEPTH},
+	HostFunctionsProvider,
+};
+use ibc::{
+	core::{
+		ics02_client::client_def::ClientDef,
+		ics03_connection::connection::ConnectionEnd,
+		ics23_commitment::{
+			commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+			merkle::apply_prefix,
+		},
+		ics24_host::identifier::{ClientId, ConnectionId},
+	},
+	mock::context::{MockClientTypes, MockContext},
+	prelude::*,
+	Height,
+};
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::lightclients::solomachine::v1::{
+		HeaderData as RawHeaderData, SignBytes as RawSignBytes,
+		TimestampedSignatureData as RawTimestampedSignatureData,
+	},
+};
+use prost::Message;
+use sha2::Digest;
+use sp_core::{ecdsa, ed25519, Pair};
+use tendermint_proto::Protobuf;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Crypto;
+
+impl HostFunctionsProvider for Crypto {
+	fn sha256_digest(data: &[u8]) -> [u8; 32] {
+		sha2::Sha256::digest(data).into()
+	}
+
+	fn ed25519_verify(signature: &[u8], msg: &[u8], public_key: &[u8]) -> bool {
+		match (ed25519::Signature::try_from(signature), ed25519::Public::try_from(public_key)) {
+			(Ok(signature), Ok(public_key)) => ed25519::Pair::verify(&signature, msg, &public_key),
+			_ => false,
+		}
+	}
+
+	fn secp256k1_verify(signature: &[u8], msg: &[u8; 32], public_key: &[u8]) -> bool {
+		let public_key = match ecdsa::Public::try_from(public_key) {
+			Ok(public_key) if signature.len() == 64 => public_key,
+			_ => return false,
+		};
+		(0..2).any(|recovery_id| {
+			let mut raw = [0u8; 65];
+			raw[..64].copy_from_slice(signature);
+			raw[64] = recovery_id;
+			ecdsa::Pair::verify_prehashed(&ecdsa::Signature::from_raw(raw), msg, &public_key)
+		})
+	}
+}
+
+enum TestKey {
+	Ed25519(ed25519::Pair),
+	Secp256k1(ecdsa::Pair),
+}
+
+impl TestKey {
+	fn ed25519(seed: u8) -> Self {
+		Self::Ed25519(ed25519::Pair::from_seed(&[seed; 32]))
+	}
+
+	fn secp256k1(seed: u8) -> Self {
+		Self::Secp256k1(ecdsa::Pair::from_seed(&[seed; 32]))
+	}
+
+	fn public_key(&self) -> PublicKey {
+		match self {
+			Self::Ed25519(pair) => PublicKey::Ed25519(pair.public().as_ref().to_vec()),
+			Self::Secp256k1(pair) => PublicKey::Secp256k1(pair.public().as_ref().to_vec()),
+		}
+	}
+
+	fn sign(&self, msg: &[u8]) -> SignatureData {
+		match self {
+			Self::Ed25519(pair) => SignatureData::Single(pair.sign(msg).as_ref().to_vec()),
+			Self::Secp256k1(pair) => {
+				let signature = pair.sign_prehashed(&Crypto::sha256_digest(msg));
+				SignatureData::Single(signature.as_ref()[..64].to_vec())
+			},
+		}
+	}
+}
+
+const DIVERSIFIER: &str = "diversifier";
+const TIMESTAMP: u64 = 1_000_000_000;
+
+fn client_state(public_key: PublicKey) -> ClientState<Crypto> {
+	ClientState::new(1, ConsensusState::new(public_key, DIVERSIFIER.to_string(), TIMESTAMP))
+}
+
+fn sign_header(key: &TestKey, sequence: u64, new_public_key: PublicKey) -> Header {
+	let data = RawHeaderData {
+		new_pub_key: Some(new_public_key.clone().into()),
+		new_diversifier: DIVERSIFIER.to_string(),
+	}
+	.encode_to_vec();
+	let sign_bytes = RawSignBytes {
+		sequence,
+		timestamp: TIMESTAMP + 1,
+		diversifier: DIVERSIFIER.to_string(),
+		path: SENTINEL_HEADER_PATH.as_bytes().to_vec(),
+		data,
+	}
+	.encode_to_vec();
+	Header {
+		timestamp: TIMESTAMP + 1,
+		signature: key.sign(&sign_bytes).encode_to_vec(),
+		new_public_key,
+		new_diversifier: DIVERSIFIER.to_string(),
+	}
+}
+
+fn connection_proof(
+	sequence: u64,
+	connection_id: &ConnectionId,
+	value: Vec<u8>,
+	sign: impl Fn(&[u8]) -> SignatureData,
+) -> CommitmentProofBytes {
+	let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+	let merkle_path = apply_prefix(&prefix, vec![format!("connections/{}", connection_id)]);
+	let sign_bytes = RawSignBytes {
+		sequence,
+		timestamp: TIMESTAMP,
+		diversifier: DIVERSIFIER.to_string(),
+		path: merkle_path_to_string(&merkle_path).into_bytes(),
+		data: value,
+	}
+	.encode_to_vec();
+	RawTimestampedSignatureData {
+		signature_data: sign(&sign_bytes).encode_to_vec(),
+		timestamp: TIMESTAMP,
+	}
+	.encode_to_vec()
+	.try_into()
+	.unwrap()
+}
+
+fn verify_connection(
+	client_state: &ClientState<Crypto>,
+	height: Height,
+	proof: &CommitmentProofBytes,
+	connection_id: &ConnectionId,
+	connection_end: &ConnectionEnd,
+) -> Result<(), ibc::core::ics02_client::error::Error> {
+	let ctx = MockContext::<MockClientTypes>::default();
+	SoloMachineClient::<Crypto>::default().verify_connection_state(
+		&ctx,
+		&ClientId::new("06-solomachine", 0).unwrap(),
+		client_state,
+		height,
+		&CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap(),
+		proof,
+		&CommitmentRoot::from_bytes(&[]),
+		connection_id,
+		connection_end,
+	)
+}
+
+#[test]
+fn header_rotates_public_key() {
+	let ctx = MockContext::<MockClientTypes>::default();
+	let client = SoloMachineClient::<Crypto>::default();
+	let client_id = ClientId::new("06-solomachine", 0).unwrap();
+	let old_key = TestKey::ed25519(1);
+	let new_key = TestKey::secp256k1(2);
+	let client_state = client_state(old_key.public_key());
+
+	// signed by the key being rotated to
+	let header = sign_header(&new_key, 1, new_key.public_key());
+	client
+		.verify_client_message(
+			&ctx,
+			client_id.clone(),
+			client_state.clone(),
+			ClientMessage::Header(header),
+		)
+		.unwrap_err();
+
+	// signed for a past sequence
+	let header = sign_header(&old_key, 0, new_key.public_key());
+	client
+		.verify_client_message(
+			&ctx,
+			client_id.clone(),
+			client_state.clone(),
+			ClientMessage::Header(header),
+		)
+		.unwrap_err();
+
+	let header = sign_header(&old_key, 1, new_key.public_key());
+	client
+		.verify_client_message(
+			&ctx,
+			client_id.clone(),
+			client_state.clone(),
+			ClientMessage::Header(header.clone()),
+		)
+		.unwrap();
+	assert!(!client
+		.check_for_misbehaviour(
+			&ctx,
+			client_id,
+			client_state.clone(),
+			ClientMessage::Header(header)
+		)
+		.unwrap());
+
+	let updated = client_state.with_consensus_state(ConsensusState::new(
+		new_key.public_key(),
+		DIVERSIFIER.to_string(),
+		TIMESTAMP + 1,
+	));
+	assert_eq!(updated.latest_height(), Height::new(0, 2));
+	assert_eq!(updated.consensus_state.public_key, new_key.public_key());
+}
+
+#[test]
+fn verify_signed_connection_state() {
+	let key = TestKey::secp256k1(1);
+	let client_state = client_state(key.public_key());
+	let connection_id = ConnectionId::new(0);
+	let connection_end = ConnectionEnd::default();
+	let value = connection_end.encode_vec().unwrap();
+	let proof = connection_proof(1, &connection_id, value.clone(), |msg| key.sign(msg));
+
+	verify_connection(&client_state, Height::new(0, 1), &proof, &connection_id, &connection_end)
+		.unwrap();
+
+	// signed at another sequence
+	let proof_at_next_sequence =
+		connection_proof(2, &connection_id, value.clone(), |msg| key.sign(msg));
+	verify_connection(
+		&client_state,
+		Height::new(0, 1),
+		&proof_at_next_sequence,
+		&connection_id,
+		&connection_end,
+	)
+	.unwrap_err();
+
+	// another key signed the proof
+	let other_key = TestKey::secp256k1(2);
+	let proof = connection_proof(1, &connection_id, value.clone(), |msg| other_key.sign(msg));
+	verify_connection(&client_state, Height::new(0, 1), &proof, &connection_id, &connection_end)
+		.unwrap_err();
+
+	// the signed value doesn't match the expected one
+	let mut other_value = value;
+	other_value.push(0);
+	let proof = connection_proof(1, &connection_id, other_value, |msg| key.sign(msg));
+	verify_connection(&client_state, Height::new(0, 1), &proof, &connection_id, &connection_end)
+		.unwrap_err();
+}
+
+#[test]
+fn verify_multisig_threshold() {
+	let keys = [TestKey::ed25519(1), TestKey::secp256k1(2), TestKey::ed25519(3)];
+	let public_key = PublicKey::Multisig {
+		threshold: 2,
+		public_keys: keys.iter().map(TestKey::public_key).collect(),
+	};
+	let client_state = client_state(public_key);
+	let connection_id = ConnectionId::new(0);
+	let connection_end = ConnectionEnd::default();
+	let value = connection_end.encode_vec().unwrap();
+	let sign_with = |signers: [bool; 3]| {
+		let keys = &keys;
+		move |msg: &[u8]| SignatureData::Multi {
+			bit_array: bit_array_from_bits(&signers),
+			signatures: keys
+				.iter()
+				.zip(signers)
+				.filter(|(_, signer)| *signer)
+				.map(|(key, _)| key.sign(msg))
+				.collect(),
+		}
+	};
+
+	let proof = connection_proof(1, &connection_id, value.clone(), sign_with([true, false, true]));
+	verify_connection(&client_state, Height::new(0, 1), &proof, &connection_id, &connection_end)
+		.unwrap();
+
+	let proof = connection_proof(1, &connection_id, value.clone(), sign_with([false, true, false]));
+	verify_connection(&client_state, Height::new(0, 1), &proof, &connection_id, &connection_end)
+		.unwrap_err();
+
+	// the signature of the second signer is attributed to the third one
+	let proof = connection_proof(1, &connection_id, value, |msg| SignatureData::Multi {
+		bit_array: bit_array_from_bits(&[true, false, true]),
+		signatures: vec![keys[0].sign(msg), keys[1].sign(msg)],
+	});
+	verify_connection(&client_state, Height::new(0, 1), &proof, &connection_id, &connection_end)
+		.unwrap_err();
+}
+
+#[test]
+fn misbehaviour_freezes_client() {
+	let ctx = MockContext::<MockClientTypes>::default();
+	let client = SoloMachineClient::<Crypto>::default();
+	let client_id = ClientId::new("06-solomachine", 0).unwrap();
+	let key = TestKey::ed25519(1);
+	let client_state = client_state(key.public_key());
+
+	let signature_and_data = |data: &[u8]| {
+		let sign_bytes = RawSignBytes {
+			sequence: 1,
+			timestamp: TIMESTAMP,
+			diversifier: DIVERSIFIER.to_string(),
+			path: b"/ibc/connections%2Fconnection-0".to_vec(),
+			data: data.to_vec(),
+		}
+		.encode_to_vec();
+		SignatureAndData {
+			signature: key.sign(&sign_bytes).encode_to_vec(),
+			path: b"/ibc/connections%2Fconnection-0".to_vec(),
+			data: data.to_vec(),
+			timestamp: TIMESTAMP,
+		}
+	};
+
+	let misbehaviour = Misbehaviour {
+		sequence: 1,
+		signature_one: signature_and_data(b"one"),
+		signature_two: signature_and_data(b"one"),
+	};
+	client
+		.verify_client_message(
+			&ctx,
+			client_id.clone(),
+			client_state.clone(),
+			ClientMessage::Misbehaviour(misbehaviour),
+		)
+		.unwrap_err();
+
+	let misbehaviour = ClientMessage::Misbehaviour(Misbehaviour {
+		sequence: 1,
+		signature_one: signature_and_data(b"one"),
+		signature_two: signature_and_data(b"two"),
+	});
+	client
+		.verify_client_message(&ctx, client_id.clone(), client_state.clone(), misbehaviour.clone())
+		.unwrap();
+	assert!(client
+		.check_for_misbehaviour(&ctx, client_id, client_state.clone(), misbehaviour.clone())
+		.unwrap());
+
+	let frozen = client.update_state_on_misbehaviour(client_state, misbehaviour).unwrap();
+	assert!(frozen.is_frozen);
+	assert!(frozen.frozen_height().is_some());
+}
+
+#[test]
+fn verified_proofs_consume_the_sequence() {
+	let client = SoloMachineClient::<Crypto>::default();
+	let key = TestKey::ed25519(1);
+	let client_state = client_state(key.public_key());
+	let connection_id = ConnectionId::new(0);
+	let connection_end = ConnectionEnd::default();
+	let value = connection_end.encode_vec().unwrap();
+
+	let proof = connection_proof(1, &connection_id, value.clone(), |msg| key.sign(msg));
+	verify_connection(&client_state, Height::new(0, 1), &proof, &connection_id, &connection_end)
+		.unwrap();
+	let client_state = client.update_state_on_verified_proof(client_state, &proof).unwrap();
+	assert_eq!(client_state.sequence, 2);
+	assert_eq!(client_state.consensus_state.timestamp, TIMESTAMP);
+
+	// the proof can't be replayed
+	verify_connection(&client_state, Height::new(0, 1), &proof, &connection_id, &connection_end)
+		.unwrap_err();
+
+	// the next proof of the same message is signed at the next sequence
+	let other_connection_id = ConnectionId::new(1);
+	let other_proof = connection_proof(2, &other_connection_id, value, |msg| key.sign(msg));
+	verify_connection(
+		&client_state,
+		Height::new(0, 1),
+		&other_proof,
+		&other_connection_id,
+		&connection_end,
+	)
+	.unwrap();
+}
+
+#[test]
+fn signatures_for_different_paths_at_the_same_sequence_are_misbehaviour() {
+	let ctx = MockContext::<MockClientTypes>::default();
+	let client = SoloMachineClient::<Crypto>::default();
+	let client_id = ClientId::new("06-solomachine", 0).unwrap();
+	let key = TestKey::ed25519(1);
+	let client_state = client_state(key.public_key());
+	let connection_end = ConnectionEnd::default();
+	let value = connection_end.encode_vec().unwrap();
+
+	let signature_and_data = |connection_id: &ConnectionId| {
+		let proof = connection_proof(1, connection_id, value.clone(), |msg| key.sign(msg));
+		let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+		let merkle_path = apply_prefix(&prefix, vec![format!("connections/{}", connection_id)]);
+		let signature = RawTimestampedSignatureData::decode(&*Vec::<u8>::from(proof)).unwrap();
+		SignatureAndData {
+			signature: signature.signature_data,
+			path: merkle_path_to_string(&merkle_path).into_bytes(),
+			data: value.clone(),
+			timestamp: signature.timestamp,
+		}
+	};
+	let misbehaviour = ClientMessage::Misbehaviour(Misbehaviour {
+		sequence: 1,
+		signature_one: signature_and_data(&ConnectionId::new(0)),
+		signature_two: signature_and_data(&ConnectionId::new(1)),
+	});
+	client
+		.verify_client_message(&ctx, client_id.clone(), client_state.clone(), misbehaviour.clone())
+		.unwrap();
+	assert!(client
+		.check_for_misbehaviour(&ctx, client_id, client_state, misbehaviour)
+		.unwrap());
+}
+
+#[test]
+fn nested_multisig_depth_is_capped() {
+	let nest = |depth: usize| {
+		(0..depth).fold(TestKey::ed25519(1).public_key(), |public_key, _| PublicKey::Multisig {
+			threshold: 1,
+			public_keys: vec![public_key],
+		})
+	};
+	let public_key = nest(MAX_MULTISIG_DEPTH);
+	assert_eq!(PublicKey::try_from(Any::from(public_key.clone())).unwrap(), public_key);
+	assert!(PublicKey::try_from(Any::from(nest(MAX_MULTISIG_DEPTH + 1))).is_err());
+}
+
+#[test]
+fn merkle_path_is_escaped() {
+	let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+	let merkle_path =
+		apply_prefix(&prefix, vec!["channelEnds/ports/transfer/channels/channel-0".into()]);
+	assert_eq!(
+		merkle_path_to_string(&merkle_path),
+		"/ibc/channelEnds%2Fports%2Ftransfer%2Fchannels%2Fchannel-0"
+	);
+}
+
+#[test]
+fn client_state_protobuf_roundtrip() {
+	let public_key = PublicKey::Multisig {
+		threshold: 1,
+		public_keys: vec![TestKey::ed25519(1).public_key(), TestKey::secp256k1(2).public_key()],
+	};
+	let client_state = client_state(public_key);
+	let decoded = ClientState::<Crypto>::decode_vec(&client_state.encode_vec().unwrap()).unwrap();
+	assert_eq!(decoded, client_state);
+
+	let invalid = PublicKey::Multisig { threshold: 3, public_keys: vec![] };
+	let consensus_state = ConsensusState::new(invalid, DIVERSIFIER.to_string(), TIMESTAMP);
+	assert!(ConsensusState::decode_vec(&consensus_state.encode_vec().unwrap()).is_err());
+}
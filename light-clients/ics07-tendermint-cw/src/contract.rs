@@ -17,7 +17,7 @@ use crate::{
 	context::Context,
 	error::ContractError,
 	helpers::{
-		check_substitute_and_update_state, prune_oldest_consensus_state, verify_delay_passed,
+		check_substitute_and_update_state, prune_expired_consensus_states, verify_delay_passed,
 		verify_upgrade_and_update_state,
 	},
 	ics23::ReadonlyProcessedStates,
@@ -206,7 +206,7 @@ fn process_message(
 				.map_err(|e| ContractError::Tendermint(e.to_string()))?;
 			let msg = UpdateStateMsg::try_from(msg_raw)?;
 			let latest_revision_height = client_state.latest_height().revision_height;
-			prune_oldest_consensus_state(ctx, &client_state, ctx.host_timestamp().nanoseconds());
+			prune_expired_consensus_states(ctx, &client_state, ctx.host_timestamp().nanoseconds());
 			client
 				.update_state(ctx, client_id.clone(), client_state, msg.client_message)
 				.map_err(|e| ContractError::Tendermint(e.to_string()))
@@ -223,21 +223,88 @@ pub fn check_substitute_and_update_state<H: HostFunctionsProvider + 'static>(
 	Ok((subject_client_state, substitute_consensus_state))
 }
 
-pub fn prune_oldest_consensus_state<H: HostFunctionsProvider + 'static>(
+/// Maximum number of expired consensus states removed on every client update, this keeps the gas
+/// cost of updates bounded.
+pub const MAX_PRUNED_CONSENSUS_STATES: usize = 8;
+
+/// Removes up to [`MAX_PRUNED_CONSENSUS_STATES`] of the oldest consensus states that are past the
+/// trusting period, along with their processed time and height.
+pub fn prune_expired_consensus_states<H: HostFunctionsProvider + 'static>(
 	ctx: &mut Context<H>,
 	client_state: &ClientState<H>,
 	current_time: u64,
 ) {
-	let mut processed_states = ProcessedStates::new(ctx.storage_mut());
-	if let Some(earliest_height) = processed_states.get_earliest_height(client_state.latest_height)
-	{
+	for _ in 0..MAX_PRUNED_CONSENSUS_STATES {
+		let mut processed_states = ProcessedStates::new(ctx.storage_mut());
+		let Some(earliest_height) =
+			processed_states.get_earliest_height(client_state.latest_height)
+		else {
+			break
+		};
 		let processed_time =
 			processed_states.get_processed_time(earliest_height, &mut Vec::new()).unwrap();
-		let elapsed = Duration::from_nanos(current_time - processed_time);
-		if client_state.expired(elapsed) {
-			processed_states.remove_states_at_height(earliest_height);
-			let mut consensus_states = ConsensusStates::new(ctx.storage_mut());
-			consensus_states.remove(earliest_height);
+		let elapsed = Duration::from_nanos(current_time.saturating_sub(processed_time));
+		if !client_state.expired(elapsed) {
+			break
+		}
+		processed_states.remove_states_at_height(earliest_height);
+		let mut consensus_states = ConsensusStates::new(ctx.storage_mut());
+		consensus_states.remove(earliest_height);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::contract::HostFunctions;
+	use cosmwasm_std::testing::{mock_dependencies, mock_env};
+	use ibc::core::{
+		ics02_client::trust_threshold::TrustThreshold, ics23_commitment::specs::ProofSpecs,
+		ics24_host::identifier::ChainId,
+	};
+
+	#[test]
+	fn prune_expired_consensus_states_keeps_unexpired_ones() {
+		let mut deps = mock_dependencies();
+		let env = mock_env();
+		let now = env.block.time.nanos();
+		let client_state = ClientState::<HostFunctions>::new(
+			ChainId::new("test".to_string(), 0),
+			TrustThreshold::default(),
+			Duration::from_secs(100),
+			Duration::from_secs(200),
+			Duration::from_secs(10),
+			Height::new(0, 4),
+			ProofSpecs::default(),
+			vec![],
+		)
+		.unwrap();
+		let mut ctx = Context::<HostFunctions>::new(deps.as_mut(), env);
+
+		// heights 1 and 2 are past the trusting period, 3 isn't, and 4 is the latest height
+		for (height, age) in [(1, 300), (2, 150), (3, 50), (4, 400)] {
+			let height = Height::new(0, height);
+			ConsensusStates::new(ctx.storage_mut()).insert(height, vec![1]);
+			let mut processed_states = ProcessedStates::new(ctx.storage_mut());
+			let processed_time = now - Duration::from_secs(age).as_nanos() as u64;
+			processed_states.set_processed_time(height, processed_time, &mut Vec::new());
+			processed_states.set_processed_height(height, 1, &mut Vec::new());
+			processed_states.set_iteration_key(height, &mut Vec::new());
+		}
+		prune_expired_consensus_states(&mut ctx, &client_state, now);
+
+		for (height, pruned) in [(1, true), (2, true), (3, false), (4, false)] {
+			let height = Height::new(0, height);
+			assert_eq!(ConsensusStates::new(ctx.storage_mut()).get(height).is_none(), pruned);
+			let processed_states = ProcessedStates::new(ctx.storage_mut());
+			assert_eq!(
+				processed_states.get_processed_time(height, &mut Vec::new()).is_none(),
+				pruned
+			);
+			assert_eq!(
+				processed_states.get_processed_height(height, &mut Vec::new()).is_none(),
+				pruned
+			);
 		}
 	}
 }
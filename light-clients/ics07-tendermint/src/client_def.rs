@@ -25,7 +25,8 @@ use ibc::core::{
 	ics04_channel::{
 		channel::ChannelEnd,
 		commitment::{AcknowledgementCommitment, PacketCommitment},
-		packet::Sequence,
+		packet::{Receipt, Sequence},
+		upgrade::{ErrorReceipt, Upgrade},
 	},
 	ics23_commitment::{
 		commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
@@ -34,14 +35,15 @@ use ibc::core::{
 	ics24_host::{
 		identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
 		path::{
-			AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath, CommitmentsPath,
-			ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+			AcksPath, ChannelEndsPath, ChannelUpgradeErrorPath, ChannelUpgradePath,
+			ClientConsensusStatePath, ClientStatePath, CommitmentsPath, ConnectionsPath,
+			ReceiptsPath, SeqRecvsPath,
 		},
 		Path,
 	},
 	ics26_routing::context::ReaderContext,
 };
-use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
+use ibc_proto::ibc::core::commitment::v1::{MerklePath, MerkleProof as RawMerkleProof, MerkleRoot};
 use prost::Message;
 use tendermint_light_client_verifier::{
 	types::{TrustedBlockState, UntrustedBlockState},
@@ -52,12 +54,17 @@ use tendermint_proto::Protobuf;
 use crate::{
 	client_message::{ClientMessage, Header},
 	client_state::ClientState,
-	consensus_state::ConsensusState,
+	consensus_state::{ConsensusState, SENTINEL_ROOT},
 	error::Error,
 	HostFunctionsProvider, ProdVerifier,
 };
 use ibc::{prelude::*, Height};
 
+/// Key suffix of the upgraded client state in the counterparty's upgrade store.
+const UPGRADED_CLIENT_STATE_KEY: &str = "upgradedClient";
+/// Key suffix of the upgraded consensus state in the counterparty's upgrade store.
+const UPGRADED_CONSENSUS_STATE_KEY: &str = "upgradedConsState";
+
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct TendermintClient<H>(PhantomData<H>);
 
@@ -321,30 +328,138 @@ where
 		Ok(false)
 	}
 
+	/// Verifies the upgraded client and consensus states against the `upgradedIBCState` proofs
+	/// committed by the counterparty at the latest height of the current client, following
+	/// ibc-go's `VerifyUpgradeAndUpdateState`.
 	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
-		_client_id: ClientId,
-		_old_client_state: &Self::ClientState,
-		_upgrade_client_state: &Self::ClientState,
-		_upgrade_consensus_state: &Self::ConsensusState,
-		_proof_upgrade_client: Vec<u8>,
-		_proof_upgrade_consensus_state: Vec<u8>,
+		ctx: &Ctx,
+		client_id: ClientId,
+		old_client_state: &Self::ClientState,
+		upgrade_client_state: &Self::ClientState,
+		upgrade_consensus_state: &Self::ConsensusState,
+		proof_upgrade_client: Vec<u8>,
+		proof_upgrade_consensus_state: Vec<u8>,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
-		// TODO: tendermint verify_upgrade_and_update_state
-		Err(Ics02Error::implementation_specific("Not implemented".to_string()))
+		if old_client_state.upgrade_path.is_empty() {
+			return Err(Error::empty_upgrade_path().into())
+		}
+
+		// The upgraded client must be at a greater height than the current client
+		let last_height = old_client_state.latest_height();
+		if upgrade_client_state.latest_height() <= last_height {
+			return Err(Ics02Error::low_upgrade_height(
+				upgrade_client_state.latest_height(),
+				last_height,
+			))
+		}
+
+		let proof_upgrade_client =
+			decode_upgrade_proof::<H>(proof_upgrade_client, "upgraded client state")?;
+		let proof_upgrade_consensus_state =
+			decode_upgrade_proof::<H>(proof_upgrade_consensus_state, "upgraded consensus state")?;
+
+		// Must prove against latest consensus state to ensure we are verifying against latest
+		// upgrade plan. This verifies that upgrade is intended for the provided revision, since
+		// committed client must exist at this consensus state.
+		let consensus_state: Self::ConsensusState =
+			ctx.consensus_state(&client_id, last_height)?.downcast().ok_or(
+				Ics02Error::client_args_type_mismatch(ClientState::<H>::client_type().to_owned()),
+			)?;
+		let root: MerkleRoot = consensus_state.root.into();
+
+		// The counterparty chain commits the upgraded client with all client-customizable fields
+		// zeroed out at the upgrade path specified by the current client.
+		let client_value = Ctx::AnyClientState::wrap(&upgrade_client_state.zero_custom_fields())
+			.ok_or_else(|| {
+				Ics02Error::unknown_client_state_type("Ctx::AnyClientState".to_string())
+			})?
+			.encode_to_vec()
+			.map_err(Ics02Error::encode)?;
+		proof_upgrade_client
+			.verify_membership(
+				&old_client_state.proof_specs,
+				root.clone(),
+				upgrade_client_merkle_path(&old_client_state.upgrade_path, last_height),
+				client_value,
+				0,
+			)
+			.map_err(|e| Error::invalid_upgrade_proof(format!("upgraded client state: {}", e)))?;
+
+		let consensus_value = Ctx::AnyConsensusState::wrap(upgrade_consensus_state)
+			.ok_or_else(|| {
+				Ics02Error::unknown_consensus_state_type("Ctx::AnyConsensusState".to_string())
+			})?
+			.encode_to_vec()
+			.map_err(Ics02Error::encode)?;
+		proof_upgrade_consensus_state
+			.verify_membership(
+				&old_client_state.proof_specs,
+				root,
+				upgrade_consensus_state_merkle_path(&old_client_state.upgrade_path, last_height),
+				consensus_value,
+				0,
+			)
+			.map_err(|e| {
+				Error::invalid_upgrade_proof(format!("upgraded consensus state: {}", e))
+			})?;
+
+		// Relayer chosen client parameters are ignored. All chain-chosen parameters come from
+		// the committed client, all client-chosen parameters come from the current client.
+		let new_client_state = old_client_state
+			.upgraded_with(upgrade_client_state)
+			.map_err(|e| Error::invalid_upgrade_client_state(e.to_string()))?;
+
+		// The new consensus state is merely used as a trusted kernel against which headers on the
+		// new chain can be verified. The root is just a stand-in sentinel value as it cannot be
+		// known in advance, thus no proof verification will pass.
+		let new_consensus_state = ConsensusState::new(
+			CommitmentRoot::from_bytes(SENTINEL_ROOT.as_bytes()),
+			upgrade_consensus_state.timestamp,
+			upgrade_consensus_state.next_validators_hash,
+		);
+		let cs = Ctx::AnyConsensusState::wrap(&new_consensus_state).ok_or_else(|| {
+			Ics02Error::unknown_consensus_state_type("Ctx::AnyConsensusState".to_string())
+		})?;
+
+		Ok((new_client_state, ConsensusUpdateResult::Single(cs)))
 	}
 
+	/// Will try to update the client with the state of the substitute.
+	///
+	/// The subject and substitute client states must match in all parameters except
+	/// `latest_height`, `frozen_height`, `trusting_period` and `chain_id`, which are copied over
+	/// from the substitute along with its latest consensus state.
 	fn check_substitute_and_update_state<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_subject_client_id: ClientId,
-		_substitute_client_id: ClientId,
-		_old_client_state: Self::ClientState,
-		_substitute_client_state: Self::ClientState,
+		substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
-		// TODO: tendermint check_substitute_and_update_state
-		Err(Ics02Error::implementation_specific("Not implemented".to_string()))
+		if !old_client_state.matches_substitute(&substitute_client_state) {
+			return Err(Error::mismatched_substitute_client_state().into())
+		}
+
+		let height = substitute_client_state.latest_height();
+		let consensus_state: Self::ConsensusState =
+			ctx.consensus_state(&substitute_client_id, height)?.downcast().ok_or(
+				Ics02Error::client_args_type_mismatch(ClientState::<H>::client_type().to_owned()),
+			)?;
+		let cs = Ctx::AnyConsensusState::wrap(&consensus_state).ok_or_else(|| {
+			Ics02Error::unknown_consensus_state_type("Ctx::AnyConsensusState".to_string())
+		})?;
+
+		let client_state = ClientState {
+			latest_height: substitute_client_state.latest_height,
+			chain_id: substitute_client_state.chain_id,
+			trusting_period: substitute_client_state.trusting_period,
+			frozen_height: None,
+			..old_client_state
+		};
+
+		Ok((client_state, ConsensusUpdateResult::Single(cs)))
 	}
 
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
@@ -409,6 +524,46 @@ where
 		verify_membership::<H, _>(client_state, prefix, proof, root, path, value)
 	}
 
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+
+		let path = ChannelUpgradePath(port_id.clone(), *channel_id);
+		let value = upgrade.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, root, path, value)
+	}
+
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+
+		let path = ChannelUpgradeErrorPath(port_id.clone(), *channel_id);
+		let value = error_receipt.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, root, path, value)
+	}
+
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
@@ -542,6 +697,34 @@ where
 			receipt_path,
 		)
 	}
+
+	fn verify_packet_timeout_receipt<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			receipt_path,
+			Receipt::Timeout.as_bytes().to_vec(),
+		)
+	}
 }
 
 pub fn verify_membership<H, P>(
@@ -587,6 +770,35 @@ where
 		.map_err(|e| Error::ics23_error(e).into())
 }
 
+/// Constructs the merkle path under which the counterparty commits the upgraded client state:
+/// the last key of the upgrade path is suffixed with `/{height}/upgradedClient`.
+pub fn upgrade_client_merkle_path(upgrade_path: &[String], last_height: Height) -> MerklePath {
+	upgrade_merkle_path(upgrade_path, last_height, UPGRADED_CLIENT_STATE_KEY)
+}
+
+/// Constructs the merkle path under which the counterparty commits the upgraded consensus state:
+/// the last key of the upgrade path is suffixed with `/{height}/upgradedConsState`.
+pub fn upgrade_consensus_state_merkle_path(
+	upgrade_path: &[String],
+	last_height: Height,
+) -> MerklePath {
+	upgrade_merkle_path(upgrade_path, last_height, UPGRADED_CONSENSUS_STATE_KEY)
+}
+
+fn upgrade_merkle_path(upgrade_path: &[String], last_height: Height, key: &str) -> MerklePath {
+	let mut key_path = upgrade_path.to_vec();
+	if let Some(last_key) = key_path.last_mut() {
+		*last_key = format!("{}/{}/{}", last_key, last_height.revision_height, key);
+	}
+	MerklePath { key_path }
+}
+
+fn decode_upgrade_proof<H>(proof: Vec<u8>, name: &str) -> Result<MerkleProof<H>, Ics02Error> {
+	let proof = RawMerkleProof::decode(proof.as_slice())
+		.map_err(|e| Error::invalid_upgrade_proof(format!("{}: {}", name, e)))?;
+	Ok(proof.into())
+}
+
 fn verify_delay_passed<Ctx: ReaderContext>(
 	ctx: &Ctx,
 	height: Height,
@@ -34,10 +34,14 @@ use crate::{
 use ibc::{
 	core::{
 		ics02_client::{
-			client_state::ClientType, error::Error as Ics02Error, trust_threshold::TrustThreshold,
+			client_consensus::ConsensusState,
+			client_state::{ClientType, Status},
+			error::Error as Ics02Error,
+			trust_threshold::TrustThreshold,
 		},
 		ics23_commitment::specs::ProofSpecs,
-		ics24_host::identifier::ChainId,
+		ics24_host::identifier::{ChainId, ClientId},
+		ics26_routing::context::ReaderContext,
 	},
 	timestamp::Timestamp,
 	Height,
@@ -191,6 +195,61 @@ impl<H> ClientState<H> {
 		self
 	}
 
+	/// Returns a copy of the client state with all client-customizable (relayer chosen) fields
+	/// zeroed out. This is the form in which the counterparty chain commits the upgraded client
+	/// state under the upgrade path.
+	pub fn zero_custom_fields(&self) -> Self {
+		Self {
+			chain_id: self.chain_id.clone(),
+			trust_level: TrustThreshold::ZERO,
+			trusting_period: Duration::ZERO,
+			unbonding_period: self.unbonding_period,
+			max_clock_drift: Duration::ZERO,
+			latest_height: self.latest_height,
+			proof_specs: self.proof_specs.clone(),
+			upgrade_path: self.upgrade_path.clone(),
+			frozen_height: None,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Builds the client state that results from upgrading `self` to `upgraded`. All
+	/// chain-chosen parameters come from the committed upgraded client, while the
+	/// client-chosen parameters are kept from the current client. If the unbonding period
+	/// shrinks, the trusting period is scaled down by the same ratio.
+	pub fn upgraded_with(&self, upgraded: &Self) -> Result<Self, Error> {
+		let trusting_period = if upgraded.unbonding_period < self.unbonding_period {
+			let scaled = self.trusting_period.as_nanos() * upgraded.unbonding_period.as_nanos() /
+				self.unbonding_period.as_nanos();
+			Duration::from_nanos(u64::try_from(scaled).map_err(|_| {
+				Error::invalid_trusting_period("trusting period overflow".to_string())
+			})?)
+		} else {
+			self.trusting_period
+		};
+
+		Self::new(
+			upgraded.chain_id.clone(),
+			self.trust_level,
+			trusting_period,
+			upgraded.unbonding_period,
+			self.max_clock_drift,
+			upgraded.latest_height,
+			upgraded.proof_specs.clone(),
+			upgraded.upgrade_path.clone(),
+		)
+	}
+
+	/// Returns true if `substitute` may be used to recover this client, i.e. both clients match
+	/// in every field except the latest height, frozen height, trusting period and chain id.
+	pub fn matches_substitute(&self, substitute: &Self) -> bool {
+		self.trust_level == substitute.trust_level &&
+			self.unbonding_period == substitute.unbonding_period &&
+			self.max_clock_drift == substitute.max_clock_drift &&
+			self.proof_specs == substitute.proof_specs &&
+			self.upgrade_path == substitute.upgrade_path
+	}
+
 	/// Check if the state is expired when `elapsed` time has passed since the latest consensus
 	/// state timestamp
 	pub fn expired(&self, elapsed: Duration) -> bool {
@@ -278,6 +337,33 @@ where
 		self.latest_height()
 	}
 
+	fn status<Ctx: ReaderContext>(&self, ctx: &Ctx, client_id: &ClientId) -> Status {
+		if self.frozen_height.is_some() {
+			return Status::Frozen
+		}
+
+		// get latest consensus state from clientStore to check for expiry
+		let consensus_state = match ctx.consensus_state(client_id, self.latest_height()) {
+			Ok(consensus_state) => consensus_state,
+			Err(_) => {
+				// if the client state does not have an associated consensus state for its latest
+				// height then it must be expired
+				return Status::Expired
+			},
+		};
+
+		let elapsed = ctx
+			.host_timestamp()
+			.duration_since(&consensus_state.timestamp())
+			.unwrap_or_else(|| Duration::from_secs(0));
+
+		if self.expired(elapsed) {
+			return Status::Expired
+		}
+
+		Status::Active
+	}
+
 	fn frozen_height(&self) -> Option<Height> {
 		self.frozen_height()
 	}
@@ -30,6 +30,10 @@ use ibc_proto::{
 pub const TENDERMINT_CONSENSUS_STATE_TYPE_URL: &str =
 	"/ibc.lightclients.tendermint.v1.ConsensusState";
 
+/// Stand-in commitment root for the consensus state installed by a client upgrade. The real root
+/// of the upgraded chain cannot be known in advance, so no proof will verify against it.
+pub const SENTINEL_ROOT: &str = "sentinel_root";
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ConsensusState {
 	pub timestamp: Time,
@@ -284,6 +284,24 @@ define_error! {
 			| e | {
 				format_args!("the client is frozen: frozen_height={0} target_height={1}", e.frozen_height, e.target_height)
 			},
+
+		EmptyUpgradePath
+			| _ | { "cannot upgrade client, no upgrade path set" },
+
+		InvalidUpgradeProof
+			{ reason: String }
+			| e | {
+				format_args!("invalid upgrade proof: {}", e.reason)
+			},
+
+		InvalidUpgradeClientState
+			{ reason: String }
+			| e | {
+				format_args!("invalid upgraded client state: {}", e.reason)
+			},
+
+		MismatchedSubstituteClientState
+			| _ | { "subject client state does not match substitute client state" },
 	}
 }
 
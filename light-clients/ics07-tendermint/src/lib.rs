@@ -59,6 +59,7 @@ pub trait HostFunctionsProvider:
 	+ Sync
 	+ Default
 	+ Eq
+	+ 'static
 {
 }
 
@@ -82,23 +83,33 @@ mod tests {
 	};
 
 	use crate::{
+		client_def::{upgrade_client_merkle_path, upgrade_consensus_state_merkle_path},
 		client_message::test_util::{get_dummy_ics07_header, get_dummy_tendermint_header},
-		mock::{AnyClientState, AnyConsensusState, MockClientTypes},
+		consensus_state::SENTINEL_ROOT,
+		mock::{
+			context::with_client_parametrized, AnyClientState, AnyConsensusState, Crypto,
+			MockClientTypes,
+		},
 	};
 
 	use crate::{client_message::ClientMessage, mock::AnyClientMessage};
 	use ibc::{
 		core::{
 			ics02_client::{
+				client_consensus::ConsensusState as _,
+				client_def::ConsensusUpdateResult,
+				client_state::ClientState as _,
 				context::ClientReader,
-				handler::{dispatch, ClientResult},
+				error::{Error as Ics02Error, ErrorDetail},
+				handler::{dispatch, recover_client, ClientResult},
 				msgs::{
-					create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient, ClientMsg,
+					create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient,
+					upgrade_client::MsgUpgradeAnyClient, ClientMsg,
 				},
 				trust_threshold::TrustThreshold,
 			},
-			ics23_commitment::specs::ProofSpecs,
-			ics24_host::identifier::ClientId,
+			ics23_commitment::{commitment::CommitmentRoot, specs::ProofSpecs},
+			ics24_host::identifier::{ChainId, ClientId},
 		},
 		events::IbcEvent,
 		handler::HandlerOutput,
@@ -107,7 +118,17 @@ mod tests {
 		test_utils::get_dummy_account_id,
 		Height,
 	};
-	use ibc_proto::ibc::core::client::v1::{MsgCreateClient, MsgUpdateClient};
+	use ibc_proto::{
+		cosmos::ics23::v1::{
+			commitment_proof::Proof, CommitmentProof, ExistenceProof, HashOp, InnerOp, InnerSpec,
+			LeafOp, LengthOp, ProofSpec as RawProofSpec,
+		},
+		ibc::core::{
+			client::v1::{MsgCreateClient, MsgUpdateClient},
+			commitment::v1::MerkleProof as RawMerkleProof,
+		},
+	};
+	use sha2::{Digest, Sha256};
 	use std::time::Duration;
 	use test_log::test;
 
@@ -210,4 +231,373 @@ mod tests {
 		assert_eq!(msg, msg_back);
 		assert_eq!(raw, raw_back);
 	}
+
+	#[test]
+	fn upgrade_merkle_paths() {
+		let upgrade_path = vec!["upgrade".to_string(), "upgradedIBCState".to_string()];
+		let height = Height::new(1, 42);
+
+		assert_eq!(
+			upgrade_client_merkle_path(&upgrade_path, height).key_path,
+			vec!["upgrade".to_string(), "upgradedIBCState/42/upgradedClient".to_string()]
+		);
+		assert_eq!(
+			upgrade_consensus_state_merkle_path(&upgrade_path, height).key_path,
+			vec!["upgrade".to_string(), "upgradedIBCState/42/upgradedConsState".to_string()]
+		);
+	}
+
+	#[test]
+	fn upgraded_client_state_keeps_client_chosen_fields() {
+		let client_state = TendermintClientState::<Crypto>::new(
+			ChainId::new("mockgaia".to_string(), 1),
+			TrustThreshold::TWO_THIRDS,
+			Duration::from_secs(64000),
+			Duration::from_secs(128000),
+			Duration::from_millis(3000),
+			Height::new(1, 42),
+			ProofSpecs::default(),
+			vec!["upgrade".to_string(), "upgradedIBCState".to_string()],
+		)
+		.unwrap();
+		let upgraded = TendermintClientState::<Crypto>::new(
+			ChainId::new("mockgaia".to_string(), 2),
+			TrustThreshold::ONE_THIRD,
+			Duration::from_secs(1000),
+			Duration::from_secs(64000),
+			Duration::from_millis(1),
+			Height::new(2, 1),
+			ProofSpecs::default(),
+			vec!["upgrade".to_string(), "upgradedIBCState".to_string()],
+		)
+		.unwrap();
+
+		let zeroed = upgraded.zero_custom_fields();
+		assert_eq!(zeroed.trust_level, TrustThreshold::ZERO);
+		assert_eq!(zeroed.trusting_period, Duration::ZERO);
+		assert_eq!(zeroed.max_clock_drift, Duration::ZERO);
+		assert_eq!(zeroed.unbonding_period, upgraded.unbonding_period);
+		assert_eq!(zeroed.latest_height, upgraded.latest_height);
+
+		let new_client_state = client_state.upgraded_with(&upgraded).unwrap();
+		assert_eq!(new_client_state.chain_id, upgraded.chain_id);
+		assert_eq!(new_client_state.latest_height, upgraded.latest_height);
+		assert_eq!(new_client_state.unbonding_period, upgraded.unbonding_period);
+		assert_eq!(new_client_state.trust_level, client_state.trust_level);
+		assert_eq!(new_client_state.max_clock_drift, client_state.max_clock_drift);
+		// unbonding period was halved, so is the trusting period
+		assert_eq!(new_client_state.trusting_period, Duration::from_secs(32000));
+	}
+
+	/// Spec of the two-leaf stores committing the upgraded states in the upgrade tests, used for
+	/// both the upgrade store and the multistore.
+	fn upgrade_proof_spec() -> RawProofSpec {
+		RawProofSpec {
+			leaf_spec: Some(upgrade_leaf_op()),
+			inner_spec: Some(InnerSpec {
+				child_order: vec![0, 1],
+				child_size: 32,
+				min_prefix_length: 1,
+				max_prefix_length: 1,
+				empty_child: vec![],
+				hash: HashOp::Sha256 as i32,
+			}),
+			max_depth: 0,
+			min_depth: 0,
+			prehash_key_before_comparison: false,
+		}
+	}
+
+	fn upgrade_leaf_op() -> LeafOp {
+		LeafOp {
+			hash: HashOp::Sha256 as i32,
+			prehash_key: HashOp::NoHash as i32,
+			prehash_value: HashOp::NoHash as i32,
+			length: LengthOp::NoPrefix as i32,
+			prefix: vec![0],
+		}
+	}
+
+	fn sha256(parts: &[&[u8]]) -> Vec<u8> {
+		let mut hasher = Sha256::new();
+		parts.iter().for_each(|part| hasher.update(part));
+		hasher.finalize().to_vec()
+	}
+
+	fn existence_proof(key: &str, value: Vec<u8>, path: Vec<InnerOp>) -> CommitmentProof {
+		CommitmentProof {
+			proof: Some(Proof::Exist(ExistenceProof {
+				key: key.as_bytes().to_vec(),
+				value,
+				leaf: Some(upgrade_leaf_op()),
+				path,
+			})),
+		}
+	}
+
+	/// Commits the upgraded states under the upgrade path of the client at `height`, and returns
+	/// the commitment root along with the encoded proofs of the client and consensus states.
+	fn commit_upgrade(
+		upgrade_path: &[String],
+		height: Height,
+		client_state: &AnyClientState,
+		consensus_state: &AnyConsensusState,
+	) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+		let client_path = upgrade_client_merkle_path(upgrade_path, height).key_path;
+		let consensus_path = upgrade_consensus_state_merkle_path(upgrade_path, height).key_path;
+		let client_value = client_state.encode_to_vec().unwrap();
+		let consensus_value = consensus_state.encode_to_vec().unwrap();
+
+		// the upgrade store holds both states, the client state is its left leaf
+		let client_leaf = sha256(&[&[0], client_path[1].as_bytes(), &client_value]);
+		let consensus_leaf = sha256(&[&[0], consensus_path[1].as_bytes(), &consensus_value]);
+		let store_root = sha256(&[&[1], &client_leaf, &consensus_leaf]);
+		let inner_op = |prefix: Vec<u8>, suffix: Vec<u8>| InnerOp {
+			hash: HashOp::Sha256 as i32,
+			prefix,
+			suffix,
+		};
+		let client_proof =
+			existence_proof(&client_path[1], client_value, vec![inner_op(vec![1], consensus_leaf)]);
+		let consensus_proof = existence_proof(
+			&consensus_path[1],
+			consensus_value,
+			vec![inner_op([vec![1], client_leaf].concat(), vec![])],
+		);
+
+		// the multistore only holds the root of the upgrade store
+		let root = sha256(&[&[0], upgrade_path[0].as_bytes(), &store_root]);
+		let store_proof = existence_proof(&upgrade_path[0], store_root, vec![]);
+
+		let encode = |proof: CommitmentProof| {
+			prost::Message::encode_to_vec(&RawMerkleProof {
+				proofs: vec![proof, store_proof.clone()],
+			})
+		};
+		(root, encode(client_proof), encode(consensus_proof))
+	}
+
+	/// Creates a tendermint client at `client_height` whose upgrades are committed under the
+	/// cosmos upgrade path.
+	fn upgradable_client(
+		client_id: &ClientId,
+		client_height: Height,
+	) -> MockContext<MockClientTypes> {
+		let ctx = with_client_parametrized(
+			MockContext::<MockClientTypes>::default(),
+			client_id,
+			client_height,
+			Some(ClientState::<()>::client_type()),
+			None,
+		);
+		modify_tm_client_state(&ctx, client_id, |client_state| {
+			client_state.upgrade_path = vec!["upgrade".to_string(), "upgradedIBCState".to_string()];
+			client_state.proof_specs =
+				ProofSpecs::from(vec![upgrade_proof_spec(), upgrade_proof_spec()]);
+		});
+		ctx
+	}
+
+	fn upgraded_states(
+		client_height: Height,
+	) -> (TendermintClientState<Crypto>, AnyConsensusState) {
+		let tm_header = get_dummy_tendermint_header();
+		let upgraded_client_state = TendermintClientState::<Crypto>::new(
+			tm_header.chain_id.clone().into(),
+			TrustThreshold::ONE_THIRD,
+			Duration::from_secs(64000),
+			Duration::from_secs(128000),
+			Duration::from_millis(3000),
+			Height::new(client_height.revision_number + 1, 1),
+			ProofSpecs::default(),
+			vec!["upgrade".to_string(), "upgradedIBCState".to_string()],
+		)
+		.unwrap();
+		(upgraded_client_state, AnyConsensusState::Tendermint(tm_header.try_into().unwrap()))
+	}
+
+	#[test]
+	fn test_tm_upgrade_client_ok() {
+		let client_id = ClientId::new(&ClientState::<()>::client_type(), 0).unwrap();
+		let client_height = Height::new(1, 42);
+		let ctx = upgradable_client(&client_id, client_height);
+		let (upgraded_client_state, upgraded_consensus_state) = upgraded_states(client_height);
+
+		// the counterparty commits the upgraded client without its client-chosen fields
+		let (root, proof_upgrade_client, proof_upgrade_consensus_state) = commit_upgrade(
+			&["upgrade".to_string(), "upgradedIBCState".to_string()],
+			client_height,
+			&AnyClientState::Tendermint(upgraded_client_state.zero_custom_fields()),
+			&upgraded_consensus_state,
+		);
+		{
+			let mut store = ctx.ibc_store.lock().unwrap();
+			let record = store.clients.get_mut(&client_id).unwrap();
+			match record.consensus_states.get_mut(&client_height) {
+				Some(AnyConsensusState::Tendermint(consensus_state)) =>
+					consensus_state.root = CommitmentRoot::from_bytes(&root),
+				_ => panic!("expected a tendermint consensus state"),
+			}
+		}
+
+		let msg = MsgUpgradeAnyClient::<MockContext<MockClientTypes>> {
+			client_id: client_id.clone(),
+			client_state: AnyClientState::Tendermint(upgraded_client_state.clone()),
+			consensus_state: upgraded_consensus_state.clone(),
+			proof_upgrade_client,
+			proof_upgrade_consensus_state,
+			signer: get_dummy_account_id(),
+		};
+
+		let output = dispatch(&ctx, ClientMsg::UpgradeClient(msg)).unwrap();
+		let result = match output.result {
+			ClientResult::Upgrade(result) => result,
+			_ => panic!("expected an upgrade result"),
+		};
+		assert_eq!(result.client_id, client_id);
+		match result.client_state {
+			AnyClientState::Tendermint(client_state) => {
+				assert_eq!(client_state.latest_height, upgraded_client_state.latest_height);
+				assert_eq!(client_state.chain_id, upgraded_client_state.chain_id);
+			},
+			_ => panic!("expected a tendermint client state"),
+		}
+		match (result.consensus_state, upgraded_consensus_state) {
+			(
+				Some(ConsensusUpdateResult::Single(AnyConsensusState::Tendermint(consensus_state))),
+				AnyConsensusState::Tendermint(upgraded),
+			) => {
+				// the root of the upgraded chain can't be known in advance
+				assert_eq!(
+					consensus_state.root,
+					CommitmentRoot::from_bytes(SENTINEL_ROOT.as_bytes())
+				);
+				assert_eq!(consensus_state.timestamp, upgraded.timestamp);
+				assert_eq!(consensus_state.next_validators_hash, upgraded.next_validators_hash);
+			},
+			_ => panic!("expected a single tendermint consensus state"),
+		}
+	}
+
+	#[test]
+	fn test_tm_upgrade_client_invalid_proof() {
+		let client_id = ClientId::new(&ClientState::<()>::client_type(), 0).unwrap();
+		let client_height = Height::new(1, 42);
+		let ctx = upgradable_client(&client_id, client_height);
+		let (upgraded_client_state, upgraded_consensus_state) = upgraded_states(client_height);
+
+		// the proofs are valid, but against a root the client doesn't have
+		let (_, proof_upgrade_client, proof_upgrade_consensus_state) = commit_upgrade(
+			&["upgrade".to_string(), "upgradedIBCState".to_string()],
+			client_height,
+			&AnyClientState::Tendermint(upgraded_client_state.zero_custom_fields()),
+			&upgraded_consensus_state,
+		);
+		let msg = MsgUpgradeAnyClient::<MockContext<MockClientTypes>> {
+			client_id: client_id.clone(),
+			client_state: AnyClientState::Tendermint(upgraded_client_state.clone()),
+			consensus_state: upgraded_consensus_state.clone(),
+			proof_upgrade_client,
+			proof_upgrade_consensus_state,
+			signer: get_dummy_account_id(),
+		};
+		match dispatch(&ctx, ClientMsg::UpgradeClient(msg)) {
+			Err(Ics02Error(ErrorDetail::ClientError(e), _)) => assert!(
+				e.inner.contains("invalid upgrade proof: upgraded client state"),
+				"unexpected error: {}",
+				e.inner
+			),
+			_ => panic!("expected an invalid upgrade proof error"),
+		}
+
+		let msg = MsgUpgradeAnyClient::<MockContext<MockClientTypes>> {
+			client_id,
+			client_state: AnyClientState::Tendermint(upgraded_client_state),
+			consensus_state: upgraded_consensus_state,
+			proof_upgrade_client: Default::default(),
+			proof_upgrade_consensus_state: Default::default(),
+			signer: get_dummy_account_id(),
+		};
+		match dispatch(&ctx, ClientMsg::UpgradeClient(msg)) {
+			Err(Ics02Error(ErrorDetail::ClientError(e), _)) =>
+				assert!(e.inner.contains("invalid upgrade proof"), "unexpected error: {}", e.inner),
+			_ => panic!("expected an invalid upgrade proof error"),
+		}
+	}
+
+	fn modify_tm_client_state(
+		ctx: &MockContext<MockClientTypes>,
+		client_id: &ClientId,
+		f: impl FnOnce(&mut TendermintClientState<Crypto>),
+	) {
+		let mut store = ctx.ibc_store.lock().unwrap();
+		let record = store.clients.get_mut(client_id).unwrap();
+		match record.client_state.as_mut() {
+			Some(AnyClientState::Tendermint(client_state)) => f(client_state),
+			_ => panic!("expected a tendermint client state"),
+		}
+	}
+
+	#[test]
+	fn test_tm_recover_client_with_substitute() {
+		let subject_id = ClientId::new(&ClientState::<()>::client_type(), 0).unwrap();
+		let substitute_id = ClientId::new(&ClientState::<()>::client_type(), 1).unwrap();
+		let ctx = MockContext::<MockClientTypes>::default();
+		let revision = ChainId::chain_version(ctx.host_chain_id.as_str());
+		let subject_height = Height::new(revision, 42);
+		let substitute_height = Height::new(revision, 50);
+		let ctx = with_client_parametrized(
+			ctx,
+			&subject_id,
+			subject_height,
+			Some(ClientState::<()>::client_type()),
+			None,
+		);
+		let ctx = with_client_parametrized(
+			ctx,
+			&substitute_id,
+			substitute_height,
+			Some(ClientState::<()>::client_type()),
+			None,
+		);
+		modify_tm_client_state(&ctx, &subject_id, |client_state| {
+			client_state.frozen_height = Some(subject_height);
+			client_state.trusting_period = Duration::from_secs(1000);
+		});
+
+		// the substitute must keep the parameters of the subject client
+		modify_tm_client_state(&ctx, &substitute_id, |client_state| {
+			client_state.unbonding_period = Duration::from_secs(256000);
+		});
+		let output = recover_client::process(&ctx, subject_id.clone(), substitute_id.clone());
+		match output {
+			Err(Ics02Error(ErrorDetail::ClientError(e), _)) => assert!(
+				e.inner.contains("does not match substitute"),
+				"unexpected error: {}",
+				e.inner
+			),
+			_ => panic!("expected a mismatched substitute error"),
+		}
+
+		modify_tm_client_state(&ctx, &substitute_id, |client_state| {
+			client_state.unbonding_period = Duration::from_secs(128000);
+		});
+		let output = recover_client::process(&ctx, subject_id.clone(), substitute_id).unwrap();
+		let result = match output.result {
+			ClientResult::Update(result) => result,
+			_ => panic!("expected an update result"),
+		};
+		assert_eq!(result.client_id, subject_id);
+		assert!(result.consensus_state.is_some());
+		match result.client_state {
+			AnyClientState::Tendermint(client_state) => {
+				assert_eq!(client_state.latest_height, substitute_height);
+				assert_eq!(client_state.frozen_height, None);
+				// the trusting period is taken from the substitute
+				assert_eq!(client_state.trusting_period, Duration::from_secs(64000));
+				assert_eq!(client_state.unbonding_period, Duration::from_secs(128000));
+			},
+			_ => panic!("expected a tendermint client state"),
+		}
+	}
 }
@@ -34,6 +34,7 @@ use ibc::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			packet::Sequence,
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -266,6 +267,60 @@ where
 		)
 	}
 
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
+	) -> Result<(), Error> {
+		self.inner.verify_channel_upgrade(
+			ctx,
+			client_id,
+			&client_state.inner,
+			height,
+			prefix,
+			proof,
+			root,
+			port_id,
+			channel_id,
+			upgrade,
+		)
+	}
+
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
+	) -> Result<(), Error> {
+		self.inner.verify_channel_upgrade_error(
+			ctx,
+			client_id,
+			&client_state.inner,
+			height,
+			prefix,
+			proof,
+			root,
+			port_id,
+			channel_id,
+			error_receipt,
+		)
+	}
+
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		ctx: &Ctx,
@@ -400,4 +455,47 @@ where
 			sequence,
 		)
 	}
+
+	fn verify_packet_timeout_receipt<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Error> {
+		self.inner.verify_packet_timeout_receipt(
+			ctx,
+			client_id,
+			&client_state.inner,
+			height,
+			connection_end,
+			proof,
+			root,
+			port_id,
+			channel_id,
+			sequence,
+		)
+	}
+
+	fn update_state_on_verified_proof(
+		&self,
+		client_state: Self::ClientState,
+		proof: &CommitmentProofBytes,
+	) -> Result<Self::ClientState, Error> {
+		let inner_client_state =
+			self.inner.update_state_on_verified_proof(*client_state.inner, proof)?;
+		Ok(ClientState {
+			data: client_state.data.clone(),
+			checksum: client_state.checksum.clone(),
+			inner: Box::new(inner_client_state),
+			latest_height: client_state.latest_height,
+			_phantom: PhantomData,
+		})
+	}
 }
@@ -0,0 +1,440 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::marker::PhantomData;
+
+use codec::Encode;
+use ibc::core::{
+	ics02_client::{
+		client_consensus::ConsensusState as _,
+		client_def::{ClientDef, ConsensusUpdateResult},
+		client_state::ClientState as _,
+		error::Error as Ics02Error,
+	},
+	ics03_connection::{connection::ConnectionEnd, context::ConnectionReader},
+	ics04_channel::{
+		channel::ChannelEnd,
+		commitment::{AcknowledgementCommitment, PacketCommitment},
+		packet::{Receipt, Sequence},
+		upgrade::{ErrorReceipt, Upgrade},
+	},
+	ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+	ics24_host::{
+		identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		path::{
+			AcksPath, ChannelEndsPath, ChannelUpgradeErrorPath, ChannelUpgradePath,
+			ClientStatePath, CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+		},
+		Path,
+	},
+	ics26_routing::context::ReaderContext,
+};
+use tendermint_proto::Protobuf;
+
+use crate::{
+	client_message::ClientMessage, client_state::ClientState, consensus_state::ConsensusState,
+	error::Error, HostFunctionsProvider,
+};
+use ibc::{prelude::*, Height};
+
+/// Proof relayers are expected to submit for the localhost client, proof bytes may not be empty
+/// but their content is ignored.
+pub const SENTINEL_PROOF: &[u8] = &[0x01];
+
+/// Localhost client. The counterparty is the host itself, so values are verified by reading them
+/// from the host's `ics23` store rather than by checking proofs.
+///
+/// Values are compared against the store as of the current block, whatever the proof height is,
+/// as the host keeps no history of its store. The proof height may thus not be greater than the
+/// latest height of the client, which is advanced to the host height by submitting a header.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct LocalhostClient<H>(PhantomData<H>);
+
+impl<H> ClientDef for LocalhostClient<H>
+where
+	H: HostFunctionsProvider,
+{
+	type ClientMessage = ClientMessage;
+	type ClientState = ClientState<H>;
+	type ConsensusState = ConsensusState;
+
+	/// Headers carry no data, there's nothing to verify.
+	fn verify_client_message<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_client_state: Self::ClientState,
+		_client_msg: Self::ClientMessage,
+	) -> Result<(), Ics02Error> {
+		Ok(())
+	}
+
+	fn update_state<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: ClientId,
+		client_state: Self::ClientState,
+		_client_message: Self::ClientMessage,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		let consensus_state = ConsensusState::new(ctx.host_timestamp());
+		let cs = Ctx::AnyConsensusState::wrap(&consensus_state).ok_or_else(|| {
+			Ics02Error::unknown_consensus_state_type("Ctx::AnyConsensusState".to_string())
+		})?;
+		Ok((client_state.with_latest_height(ctx.host_height()), ConsensusUpdateResult::Single(cs)))
+	}
+
+	fn update_state_on_misbehaviour(
+		&self,
+		client_state: Self::ClientState,
+		_client_message: Self::ClientMessage,
+	) -> Result<Self::ClientState, Ics02Error> {
+		Ok(client_state)
+	}
+
+	/// The host can't misbehave towards itself.
+	fn check_for_misbehaviour<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_client_state: Self::ClientState,
+		_client_msg: Self::ClientMessage,
+	) -> Result<bool, Ics02Error> {
+		Ok(false)
+	}
+
+	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_old_client_state: &Self::ClientState,
+		_upgrade_client_state: &Self::ClientState,
+		_upgrade_consensus_state: &Self::ConsensusState,
+		_proof_upgrade_client: Vec<u8>,
+		_proof_upgrade_consensus_state: Vec<u8>,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		Err(Error::upgrade_not_supported().into())
+	}
+
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		_old_client_state: Self::ClientState,
+		_substitute_client_state: Self::ClientState,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		Err(Error::substitute_not_supported().into())
+	}
+
+	/// The counterparty tracks the host itself, whose consensus state is valid by definition.
+	fn verify_client_consensus_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_state: &Self::ClientState,
+		_height: Height,
+		_prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		_client_id: &ClientId,
+		_consensus_height: Height,
+		_expected_consensus_state: &Ctx::AnyConsensusState,
+	) -> Result<(), Ics02Error> {
+		Ok(())
+	}
+
+	fn verify_connection_state<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		connection_id: &ConnectionId,
+		expected_connection_end: &ConnectionEnd,
+	) -> Result<(), Ics02Error> {
+		let path = ConnectionsPath(connection_id.clone());
+		let value = expected_connection_end.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _, _>(ctx, client_state, height, prefix, path, value)
+	}
+
+	fn verify_channel_state<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		expected_channel_end: &ChannelEnd,
+	) -> Result<(), Ics02Error> {
+		let path = ChannelEndsPath(port_id.clone(), *channel_id);
+		let value = expected_channel_end.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _, _>(ctx, client_state, height, prefix, path, value)
+	}
+
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
+	) -> Result<(), Ics02Error> {
+		let path = ChannelUpgradePath(port_id.clone(), *channel_id);
+		let value = upgrade.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _, _>(ctx, client_state, height, prefix, path, value)
+	}
+
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
+	) -> Result<(), Ics02Error> {
+		let path = ChannelUpgradeErrorPath(port_id.clone(), *channel_id);
+		let value = error_receipt.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _, _>(ctx, client_state, height, prefix, path, value)
+	}
+
+	fn verify_client_full_state<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		client_id: &ClientId,
+		expected_client_state: &Ctx::AnyClientState,
+	) -> Result<(), Ics02Error> {
+		let path = ClientStatePath(client_id.clone());
+		let value = expected_client_state.encode_to_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _, _>(ctx, client_state, height, prefix, path, value)
+	}
+
+	fn verify_packet_data<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		commitment: PacketCommitment,
+	) -> Result<(), Ics02Error> {
+		let commitment_path =
+			CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _, _>(
+			ctx,
+			client_state,
+			height,
+			connection_end.counterparty().prefix(),
+			commitment_path,
+			commitment.into_vec(),
+		)
+	}
+
+	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack_commitment: AcknowledgementCommitment,
+	) -> Result<(), Ics02Error> {
+		let ack_path = AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _, _>(
+			ctx,
+			client_state,
+			height,
+			connection_end.counterparty().prefix(),
+			ack_path,
+			ack_commitment.into_vec(),
+		)
+	}
+
+	fn verify_next_sequence_recv<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		let seq_path = SeqRecvsPath(port_id.clone(), *channel_id);
+		verify_membership::<H, _, _>(
+			ctx,
+			client_state,
+			height,
+			connection_end.counterparty().prefix(),
+			seq_path,
+			u64::from(sequence),
+		)
+	}
+
+	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_non_membership::<H, _, _>(
+			ctx,
+			client_state,
+			height,
+			connection_end.counterparty().prefix(),
+			receipt_path,
+		)
+	}
+
+	fn verify_packet_timeout_receipt<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _, _>(
+			ctx,
+			client_state,
+			height,
+			connection_end.counterparty().prefix(),
+			receipt_path,
+			Receipt::Timeout.as_bytes().to_vec(),
+		)
+	}
+}
+
+/// Verifies that the host store holds `value` under `path`. The store keeps SCALE encoded values,
+/// so `value` is SCALE encoded before the comparison.
+pub fn verify_membership<H, Ctx, P>(
+	ctx: &Ctx,
+	client_state: &ClientState<H>,
+	height: Height,
+	prefix: &CommitmentPrefix,
+	path: P,
+	value: impl Encode,
+) -> Result<(), Ics02Error>
+where
+	H: HostFunctionsProvider,
+	Ctx: ConnectionReader,
+	P: Into<Path>,
+{
+	let path = path.into().to_string();
+	match read_storage::<H, _>(ctx, client_state, height, prefix, &path)? {
+		Some(stored) if stored == value.encode() => Ok(()),
+		Some(_) => Err(Error::value_mismatch(path).into()),
+		None => Err(Error::value_not_found(path).into()),
+	}
+}
+
+/// Verifies that the host store holds no value under `path`.
+pub fn verify_non_membership<H, Ctx, P>(
+	ctx: &Ctx,
+	client_state: &ClientState<H>,
+	height: Height,
+	prefix: &CommitmentPrefix,
+	path: P,
+) -> Result<(), Ics02Error>
+where
+	H: HostFunctionsProvider,
+	Ctx: ConnectionReader,
+	P: Into<Path>,
+{
+	let path = path.into().to_string();
+	match read_storage::<H, _>(ctx, client_state, height, prefix, &path)? {
+		Some(_) => Err(Error::value_found(path).into()),
+		None => Ok(()),
+	}
+}
+
+fn read_storage<H, Ctx>(
+	ctx: &Ctx,
+	client_state: &ClientState<H>,
+	height: Height,
+	prefix: &CommitmentPrefix,
+	path: &str,
+) -> Result<Option<Vec<u8>>, Error>
+where
+	H: HostFunctionsProvider,
+	Ctx: ConnectionReader,
+{
+	let latest_height = client_state.latest_height();
+	if height > latest_height {
+		return Err(Error::proof_height_too_high(latest_height, height))
+	}
+
+	// the counterparty is the host itself, reading any other store would let a connection
+	// attest to values that were never committed by its counterparty
+	let host_prefix = ctx.commitment_prefix();
+	if prefix != &host_prefix {
+		return Err(Error::prefix_mismatch(
+			host_prefix.as_bytes().to_vec(),
+			prefix.as_bytes().to_vec(),
+		))
+	}
+
+	let mut key = prefix.as_bytes().to_vec();
+	key.extend(path.as_bytes());
+	Ok(H::get_storage(prefix.as_bytes(), &key))
+}
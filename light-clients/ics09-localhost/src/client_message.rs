@@ -0,0 +1,83 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use crate::{error::Error, proto::Header as RawHeader};
+use ibc::core::ics02_client;
+use ibc_proto::google::protobuf::Any;
+use tendermint_proto::Protobuf;
+
+pub const LOCALHOST_HEADER_TYPE_URL: &str = "/ibc.lightclients.localhost.v2.Header";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientMessage {
+	Header(Header),
+}
+
+impl ics02_client::client_message::ClientMessage for ClientMessage {
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<Any> for ClientMessage {}
+
+impl TryFrom<Any> for ClientMessage {
+	type Error = Error;
+
+	fn try_from(any: Any) -> Result<Self, Self::Error> {
+		let msg = match &*any.type_url {
+			LOCALHOST_HEADER_TYPE_URL => Self::Header(
+				Header::decode_vec(&any.value)
+					.map_err(|e| Error::invalid_raw_header(format!("{e:?}")))?,
+			),
+			_ => Err(Error::invalid_raw_header(format!("Unknown type: {}", any.type_url)))?,
+		};
+
+		Ok(msg)
+	}
+}
+
+impl From<ClientMessage> for Any {
+	fn from(msg: ClientMessage) -> Self {
+		match msg {
+			ClientMessage::Header(header) => Any {
+				value: header.encode_vec().expect("failed to encode ClientMessage.header"),
+				type_url: LOCALHOST_HEADER_TYPE_URL.to_string(),
+			},
+		}
+	}
+}
+
+/// Moves the client to the current height of the host. It carries no data, as the client reads
+/// the height and timestamp from the host itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Header;
+
+impl Protobuf<RawHeader> for Header {}
+
+impl TryFrom<RawHeader> for Header {
+	type Error = Error;
+
+	fn try_from(_raw: RawHeader) -> Result<Self, Self::Error> {
+		Ok(Header)
+	}
+}
+
+impl From<Header> for RawHeader {
+	fn from(_value: Header) -> Self {
+		RawHeader {}
+	}
+}
@@ -0,0 +1,147 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use core::{fmt::Debug, marker::PhantomData, time::Duration};
+
+use crate::{
+	client_def::LocalhostClient, error::Error, proto::ClientState as RawClientState,
+	HostFunctionsProvider,
+};
+use ibc::{
+	core::{
+		ics02_client::client_state::{ClientType, Status},
+		ics24_host::identifier::{ChainId, ClientId},
+		ics26_routing::context::ReaderContext,
+	},
+	Height,
+};
+use ibc_proto::google::protobuf::Any;
+use tendermint_proto::Protobuf;
+
+pub const LOCALHOST_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.localhost.v2.ClientState";
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ClientState<H> {
+	/// Host height the client was last updated at.
+	pub latest_height: Height,
+	pub _phantom: PhantomData<H>,
+}
+
+impl<H> ClientState<H> {
+	pub fn new(latest_height: Height) -> Self {
+		Self { latest_height, _phantom: PhantomData }
+	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: LOCALHOST_CLIENT_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ClientState"),
+		}
+	}
+
+	pub fn latest_height(&self) -> Height {
+		self.latest_height
+	}
+
+	/// The chain identifier of the counterparty is the one of the host, which the client has no
+	/// access to.
+	pub fn chain_id(&self) -> ChainId {
+		ChainId::default()
+	}
+
+	pub fn client_type() -> ClientType {
+		"09-localhost".to_string()
+	}
+
+	pub fn with_latest_height(self, latest_height: Height) -> Self {
+		Self { latest_height, ..self }
+	}
+}
+
+impl<H> ibc::core::ics02_client::client_state::ClientState for ClientState<H>
+where
+	H: HostFunctionsProvider,
+{
+	type UpgradeOptions = ();
+	type ClientDef = LocalhostClient<H>;
+
+	fn chain_id(&self) -> ChainId {
+		self.chain_id()
+	}
+
+	fn client_def(&self) -> Self::ClientDef {
+		LocalhostClient::default()
+	}
+
+	fn client_type(&self) -> ClientType {
+		Self::client_type()
+	}
+
+	fn latest_height(&self) -> Height {
+		self.latest_height()
+	}
+
+	/// The host can't misbehave towards itself, so the client is always active.
+	fn status<Ctx: ReaderContext>(&self, _ctx: &Ctx, _client_id: &ClientId) -> Status {
+		Status::Active
+	}
+
+	fn frozen_height(&self) -> Option<Height> {
+		None
+	}
+
+	/// Localhost clients can't be upgraded, the client state is returned unchanged.
+	fn upgrade(
+		self,
+		_upgrade_height: Height,
+		_upgrade_options: Self::UpgradeOptions,
+		_chain_id: ChainId,
+	) -> Self {
+		self
+	}
+
+	fn expired(&self, _elapsed: Duration) -> bool {
+		false
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl<H> Protobuf<RawClientState> for ClientState<H> {}
+
+impl<H> TryFrom<RawClientState> for ClientState<H> {
+	type Error = Error;
+
+	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+		let latest_height: Height = raw
+			.latest_height
+			.ok_or_else(|| Error::invalid_raw_client_state("missing latest height".into()))?
+			.into();
+		if latest_height.is_zero() {
+			return Err(Error::invalid_raw_client_state("latest height cannot be zero".into()))
+		}
+
+		Ok(Self::new(latest_height))
+	}
+}
+
+impl<H> From<ClientState<H>> for RawClientState {
+	fn from(value: ClientState<H>) -> Self {
+		RawClientState { latest_height: Some(value.latest_height.into()) }
+	}
+}
@@ -0,0 +1,82 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use core::convert::Infallible;
+
+use crate::{error::Error, proto::ConsensusState as RawConsensusState};
+use ibc::{core::ics23_commitment::commitment::CommitmentRoot, timestamp::Timestamp};
+use ibc_proto::google::protobuf::Any;
+use tendermint_proto::Protobuf;
+
+/// Protobuf type url for Localhost Consensus State
+pub const LOCALHOST_CONSENSUS_STATE_TYPE_URL: &str =
+	"/ibc.lightclients.localhost.v2.ConsensusState";
+
+/// The localhost client reads the host store directly, there's no root to check proofs against.
+static EMPTY_ROOT: CommitmentRoot = CommitmentRoot { bytes: Vec::new() };
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusState {
+	/// Timestamp of the host block the client was updated at.
+	pub timestamp: Timestamp,
+}
+
+impl ConsensusState {
+	pub fn new(timestamp: Timestamp) -> Self {
+		Self { timestamp }
+	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: LOCALHOST_CONSENSUS_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ConsensusState"),
+		}
+	}
+}
+
+impl ibc::core::ics02_client::client_consensus::ConsensusState for ConsensusState {
+	type Error = Infallible;
+
+	fn root(&self) -> &CommitmentRoot {
+		&EMPTY_ROOT
+	}
+
+	fn timestamp(&self) -> Timestamp {
+		self.timestamp
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+	type Error = Error;
+
+	fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+		let timestamp = Timestamp::from_nanoseconds(raw.timestamp)
+			.map_err(|e| Error::invalid_raw_consensus_state(format!("invalid timestamp: {}", e)))?;
+		Ok(Self { timestamp })
+	}
+}
+
+impl From<ConsensusState> for RawConsensusState {
+	fn from(value: ConsensusState) -> Self {
+		RawConsensusState { timestamp: value.timestamp.nanoseconds() }
+	}
+}
@@ -0,0 +1,73 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::prelude::*;
+
+use flex_error::define_error;
+
+use crate::client_state::ClientState;
+use ibc::{core::ics02_client::error::Error as Ics02Error, Height};
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		InvalidRawClientState
+			{ reason: String }
+			|e| { format_args!("invalid raw client state: {}", e.reason) },
+
+		InvalidRawConsensusState
+			{ reason: String }
+			|e| { format_args!("invalid raw consensus state: {}", e.reason) },
+
+		InvalidRawHeader
+			{ reason: String }
+			|e| { format_args!("invalid raw header: {}", e.reason) },
+
+		PrefixMismatch
+			{ host_prefix: Vec<u8>, prefix: Vec<u8> }
+			|e| {
+				format_args!("commitment prefix {0:?} is not the prefix of the host store {1:?}", e.prefix, e.host_prefix)
+			},
+
+		ProofHeightTooHigh
+			{ latest_height: Height, proof_height: Height }
+			|e| {
+				format_args!("proof height {1} is greater than the latest height {0} of the client", e.latest_height, e.proof_height)
+			},
+
+		ValueMismatch
+			{ path: String }
+			|e| { format_args!("value stored under {} does not match the expected value", e.path) },
+
+		ValueNotFound
+			{ path: String }
+			|e| { format_args!("no value is stored under {}", e.path) },
+
+		ValueFound
+			{ path: String }
+			|e| { format_args!("a value is stored under {}", e.path) },
+
+		UpgradeNotSupported
+			|_| { "localhost client does not support upgrades" },
+
+		SubstituteNotSupported
+			|_| { "localhost client can not be substituted" },
+	}
+}
+
+impl From<Error> for Ics02Error {
+	fn from(e: Error) -> Self {
+		Ics02Error::client_error(ClientState::<()>::client_type().to_owned(), e.to_string())
+	}
+}
@@ -0,0 +1,43 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![allow(clippy::all)]
+
+//! ICS 09: Localhost Client lets two modules of the same chain talk to each other over IBC.
+//! The counterparty of the client is the host itself, so instead of checking proofs against a
+//! commitment root, the client reads the expected values straight out of the host's `ics23`
+//! store. Proofs are ignored, relayers can submit any non empty bytes, e.g.
+//! [`SENTINEL_PROOF`](client_def::SENTINEL_PROOF).
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+pub mod client_def;
+pub mod client_message;
+pub mod client_state;
+pub mod consensus_state;
+pub mod error;
+pub mod proto;
+
+#[cfg(test)]
+mod tests;
+
+/// Host functions that give the light client read access to the `ics23` store of the host.
+pub trait HostFunctionsProvider: Debug + Clone + Send + Sync + Default + Eq + 'static {
+	/// Returns the raw value stored under `key` in the child trie of the IBC store that is
+	/// identified by the commitment `prefix`, or `None` if the key doesn't exist.
+	fn get_storage(prefix: &[u8], key: &[u8]) -> Option<Vec<u8>>;
+}
@@ -0,0 +1,38 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protobuf messages of the localhost client. `ibc-proto` doesn't ship them, so they are defined
+//! here. `ClientState` matches ibc-go's `ibc.lightclients.localhost.v2.ClientState`, while
+//! ibc-go has no localhost consensus states or headers, so those two are local additions.
+
+use ibc::prelude::*;
+use ibc_proto::ibc::core::client::v1::Height;
+
+/// `ibc.lightclients.localhost.v2.ClientState`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClientState {
+	#[prost(message, optional, tag = "1")]
+	pub latest_height: Option<Height>,
+}
+
+/// `ibc.lightclients.localhost.v2.ConsensusState`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsensusState {
+	#[prost(uint64, tag = "1")]
+	pub timestamp: u64,
+}
+
+/// `ibc.lightclients.localhost.v2.Header`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Header {}
@@ -0,0 +1,253 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_def::{LocalhostClient, SENTINEL_PROOF},
+	client_state::ClientState,
+	HostFunctionsProvider,
+};
+use codec::Encode;
+use ibc::{
+	core::{
+		ics02_client::client_def::ClientDef,
+		ics03_connection::{
+			connection::{ConnectionEnd, Counterparty, State},
+			context::ConnectionReader,
+		},
+		ics04_channel::packet::Sequence,
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+	},
+	mock::context::{MockClientTypes, MockContext},
+	prelude::*,
+	Height,
+};
+use std::{cell::RefCell, collections::HashMap, time::Duration};
+use tendermint_proto::Protobuf;
+
+thread_local! {
+	static STORAGE: RefCell<HashMap<(Vec<u8>, Vec<u8>), Vec<u8>>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct HostStore;
+
+impl HostStore {
+	fn insert(prefix: &CommitmentPrefix, path: String, value: impl Encode) {
+		let mut key = prefix.as_bytes().to_vec();
+		key.extend(path.as_bytes());
+		STORAGE.with(|storage| {
+			storage.borrow_mut().insert((prefix.as_bytes().to_vec(), key), value.encode())
+		});
+	}
+}
+
+impl HostFunctionsProvider for HostStore {
+	fn get_storage(prefix: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+		STORAGE.with(|storage| storage.borrow().get(&(prefix.to_vec(), key.to_vec())).cloned())
+	}
+}
+
+fn client_id() -> ClientId {
+	ClientId::new("09-localhost", 0).unwrap()
+}
+
+fn proof() -> CommitmentProofBytes {
+	SENTINEL_PROOF.to_vec().try_into().unwrap()
+}
+
+fn verify_connection(
+	ctx: &MockContext<MockClientTypes>,
+	client_state: &ClientState<HostStore>,
+	height: Height,
+	prefix: &CommitmentPrefix,
+	connection_id: &ConnectionId,
+	connection_end: &ConnectionEnd,
+) -> Result<(), ibc::core::ics02_client::error::Error> {
+	LocalhostClient::<HostStore>::default().verify_connection_state(
+		ctx,
+		&client_id(),
+		client_state,
+		height,
+		prefix,
+		&proof(),
+		&CommitmentRoot::from_bytes(&[]),
+		connection_id,
+		connection_end,
+	)
+}
+
+#[test]
+fn verify_connection_state_against_host_store() {
+	let ctx = MockContext::<MockClientTypes>::default();
+	let prefix = ctx.commitment_prefix();
+	let client_state = ClientState::<HostStore>::new(Height::new(0, 10));
+	let connection_id = ConnectionId::new(0);
+	let connection_end = ConnectionEnd::new(
+		State::Init,
+		client_id(),
+		Counterparty::new(client_id(), None, prefix.clone()),
+		vec![],
+		Duration::ZERO,
+	);
+
+	// nothing is stored yet
+	verify_connection(
+		&ctx,
+		&client_state,
+		Height::new(0, 10),
+		&prefix,
+		&connection_id,
+		&connection_end,
+	)
+	.unwrap_err();
+
+	HostStore::insert(
+		&prefix,
+		format!("connections/{}", connection_id),
+		connection_end.encode_vec().unwrap(),
+	);
+	verify_connection(
+		&ctx,
+		&client_state,
+		Height::new(0, 10),
+		&prefix,
+		&connection_id,
+		&connection_end,
+	)
+	.unwrap();
+	verify_connection(
+		&ctx,
+		&client_state,
+		Height::new(0, 5),
+		&prefix,
+		&connection_id,
+		&connection_end,
+	)
+	.unwrap();
+
+	// the proof height is ahead of the client
+	verify_connection(
+		&ctx,
+		&client_state,
+		Height::new(0, 11),
+		&prefix,
+		&connection_id,
+		&connection_end,
+	)
+	.unwrap_err();
+
+	// the stored connection end differs from the expected one
+	verify_connection(
+		&ctx,
+		&client_state,
+		Height::new(0, 10),
+		&prefix,
+		&connection_id,
+		&ConnectionEnd::default(),
+	)
+	.unwrap_err();
+
+	// values can only be read from the store of the host
+	let other_prefix = CommitmentPrefix::try_from(b"other".to_vec()).unwrap();
+	HostStore::insert(
+		&other_prefix,
+		format!("connections/{}", connection_id),
+		connection_end.encode_vec().unwrap(),
+	);
+	verify_connection(
+		&ctx,
+		&client_state,
+		Height::new(0, 10),
+		&other_prefix,
+		&connection_id,
+		&connection_end,
+	)
+	.unwrap_err();
+}
+
+#[test]
+fn verify_packet_receipts_and_sequences() {
+	let ctx = MockContext::<MockClientTypes>::default();
+	let prefix = ctx.commitment_prefix();
+	let client = LocalhostClient::<HostStore>::default();
+	let client_state = ClientState::<HostStore>::new(Height::new(0, 10));
+	let connection_end = ConnectionEnd::new(
+		State::Open,
+		client_id(),
+		Counterparty::new(client_id(), Some(ConnectionId::new(1)), prefix.clone()),
+		vec![],
+		Duration::ZERO,
+	);
+	let port_id = PortId::transfer();
+	let channel_id = ChannelId::new(0);
+	let height = Height::new(0, 10);
+	let root = CommitmentRoot::from_bytes(&[]);
+
+	let receipt_absence = |sequence: u64| {
+		client.verify_packet_receipt_absence(
+			&ctx,
+			&client_id(),
+			&client_state,
+			height,
+			&connection_end,
+			&proof(),
+			&root,
+			&port_id,
+			&channel_id,
+			Sequence::from(sequence),
+		)
+	};
+	receipt_absence(1).unwrap();
+	HostStore::insert(
+		&prefix,
+		format!("receipts/ports/{}/channels/{}/sequences/1", port_id, channel_id),
+		b"Ok".to_vec(),
+	);
+	receipt_absence(1).unwrap_err();
+	receipt_absence(2).unwrap();
+
+	HostStore::insert(
+		&prefix,
+		format!("nextSequenceRecv/ports/{}/channels/{}", port_id, channel_id),
+		3u64,
+	);
+	let next_sequence_recv = |sequence: u64| {
+		client.verify_next_sequence_recv(
+			&ctx,
+			&client_id(),
+			&client_state,
+			height,
+			&connection_end,
+			&proof(),
+			&root,
+			&port_id,
+			&channel_id,
+			Sequence::from(sequence),
+		)
+	};
+	next_sequence_recv(3).unwrap();
+	next_sequence_recv(2).unwrap_err();
+}
+
+#[test]
+fn client_state_protobuf_roundtrip() {
+	let client_state = ClientState::<HostStore>::new(Height::new(2000, 42));
+	let decoded =
+		ClientState::<HostStore>::decode_vec(&client_state.encode_vec().unwrap()).unwrap();
+	assert_eq!(decoded, client_state);
+
+	let zero_height = ClientState::<HostStore>::new(Height::zero());
+	assert!(ClientState::<HostStore>::decode_vec(&zero_height.encode_vec().unwrap()).is_err());
+}
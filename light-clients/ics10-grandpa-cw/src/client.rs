@@ -15,17 +15,22 @@
 
 use crate::{
 	context::Context,
-	contract::{CLIENT_COUNTER, CONSENSUS_STATES_HEIGHTS, HOST_CONSENSUS_STATE},
+	contract::{
+		CLIENT_COUNTER, CLIENT_UPDATE_HEIGHT, CLIENT_UPDATE_TIME, CONSENSUS_STATES_HEIGHTS,
+		HOST_CONSENSUS_STATE, MAX_PRUNED_CONSENSUS_STATES,
+	},
 	ics23::{
 		ClientStates, ConsensusStates, FakeInner, ReadonlyClientStates, ReadonlyClients,
 		ReadonlyConsensusStates,
 	},
 };
+use cosmwasm_std::StdError;
 use grandpa_light_client_primitives::HostFunctions;
 use ibc::{
 	core::{
 		ics02_client::{
-			client_state::ClientType,
+			client_consensus::ConsensusState as _,
+			client_state::{ClientState as _, ClientType},
 			context::{ClientKeeper, ClientReader, ClientTypes},
 			error::Error,
 			events::Checksum,
@@ -201,13 +206,20 @@ impl<'a, H: HostFunctions<Header = RelayChainHeader>> ClientKeeper for Context<'
 
 	fn store_consensus_state(
 		&mut self,
-		_client_id: ClientId,
+		client_id: ClientId,
 		height: Height,
 		consensus_state: Self::AnyConsensusState,
 	) -> Result<(), Error> {
 		let encoded = Self::encode_consensus_state(consensus_state);
 		let mut consensus_states = ConsensusStates::new(self.storage_mut());
 		consensus_states.insert(height, encoded);
+		CONSENSUS_STATES_HEIGHTS
+			.update(self.storage_mut(), client_id.as_bytes().to_owned(), |heights| {
+				let mut heights = heights.unwrap_or_default();
+				heights.insert(height);
+				Ok::<_, StdError>(heights)
+			})
+			.map_err(|e| Error::implementation_specific(format!("[store_consensus_state]: {e}")))?;
 		Ok(())
 	}
 
@@ -217,20 +229,27 @@ impl<'a, H: HostFunctions<Header = RelayChainHeader>> ClientKeeper for Context<'
 
 	fn store_update_time(
 		&mut self,
-		_client_id: ClientId,
-		_height: Height,
-		_timestamp: Timestamp,
+		client_id: ClientId,
+		height: Height,
+		timestamp: Timestamp,
 	) -> Result<(), Error> {
-		unimplemented!()
+		let key = (client_id.as_bytes().to_owned(), height.encode_vec().map_err(Error::encode)?);
+		CLIENT_UPDATE_TIME
+			.save(self.storage_mut(), key, &timestamp.nanoseconds())
+			.map_err(|e| Error::implementation_specific(format!("[store_update_time]: {e}")))
 	}
 
 	fn store_update_height(
 		&mut self,
-		_client_id: ClientId,
-		_height: Height,
-		_host_height: Height,
+		client_id: ClientId,
+		height: Height,
+		host_height: Height,
 	) -> Result<(), Error> {
-		unimplemented!()
+		let key = (client_id.as_bytes().to_owned(), height.encode_vec().map_err(Error::encode)?);
+		let host_height = host_height.encode_vec().map_err(Error::encode)?;
+		CLIENT_UPDATE_HEIGHT
+			.save(self.storage_mut(), key, &host_height)
+			.map_err(|e| Error::implementation_specific(format!("[store_update_height]: {e}")))
 	}
 
 	fn validate_self_client(&self, _client_state: &Self::AnyClientState) -> Result<(), Error> {
@@ -238,6 +257,51 @@ impl<'a, H: HostFunctions<Header = RelayChainHeader>> ClientKeeper for Context<'
 	}
 }
 
+impl<'a, H: HostFunctions<Header = RelayChainHeader>> Context<'a, H> {
+	/// Removes up to [`MAX_PRUNED_CONSENSUS_STATES`] of the oldest consensus states that are past
+	/// the trusting period, along with their processed time and height. The consensus state at the
+	/// latest client height is never removed.
+	pub fn prune_expired_consensus_states(
+		&mut self,
+		client_id: &ClientId,
+		client_state: &ClientState<H>,
+	) -> Result<(), Error> {
+		let mut heights = CONSENSUS_STATES_HEIGHTS
+			.load(self.storage(), client_id.as_bytes().to_owned())
+			.unwrap_or_default();
+		let now = self.host_timestamp();
+		let expired = heights
+			.range(..client_state.latest_height())
+			.copied()
+			.take(MAX_PRUNED_CONSENSUS_STATES)
+			.take_while(|height| match self.consensus_state(client_id, *height) {
+				Ok(consensus_state) => {
+					let timestamp = consensus_state.timestamp();
+					client_state.expired(now.duration_since(&timestamp).unwrap_or_default())
+				},
+				Err(_) => true,
+			})
+			.collect::<Vec<_>>();
+		if expired.is_empty() {
+			return Ok(())
+		}
+
+		for height in &expired {
+			ConsensusStates::new(self.storage_mut()).remove(*height);
+			let key =
+				(client_id.as_bytes().to_owned(), height.encode_vec().map_err(Error::encode)?);
+			CLIENT_UPDATE_TIME.remove(self.storage_mut(), key.clone());
+			CLIENT_UPDATE_HEIGHT.remove(self.storage_mut(), key);
+			heights.remove(height);
+		}
+		CONSENSUS_STATES_HEIGHTS
+			.save(self.storage_mut(), client_id.as_bytes().to_owned(), &heights)
+			.map_err(|e| {
+				Error::implementation_specific(format!("[prune_expired_consensus_states]: {e}"))
+			})
+	}
+}
+
 impl<'a, H: Clone> Context<'a, H> {
 	pub fn decode_client_state(data: &[u8]) -> Result<ClientState<H>, Error> {
 		let any = Any::decode(data).map_err(Error::decode)?;
@@ -80,6 +80,9 @@ pub const GRANDPA_HEADER_HASHES_SET_STORAGE: Map<Vec<u8>, ()> =
 	Map::new("grandpa_header_hashes_set");
 
 pub const GRANDPA_BLOCK_HASHES_CACHE_SIZE: usize = 500;
+/// Maximum number of expired consensus states removed on every client update, this keeps the gas
+/// cost of updates bounded.
+pub const MAX_PRUNED_CONSENSUS_STATES: usize = 8;
 
 #[derive(Clone, Copy, Debug, PartialEq, Default, Eq)]
 pub struct HostFunctions;
@@ -190,6 +193,8 @@ fn process_message(
 				.client_state(&client_id)
 				.map_err(|e| ContractError::Grandpa(e.to_string()))?;
 			let msg = UpdateStateMsg::try_from(msg_raw)?;
+			ctx.prune_expired_consensus_states(&client_id, &client_state)
+				.map_err(|e| ContractError::Grandpa(e.to_string()))?;
 
 			let finalized_headers = match &msg.client_message {
 				ClientMessage::Header(header) => {
@@ -415,6 +420,13 @@ where
 					.map_err(|e| ContractError::Grandpa(e.to_string()))?;
 			},
 	}
+	let (host_timestamp, host_height) = (ctx.host_timestamp(), ctx.host_height());
+	for height in &heights {
+		ctx.store_update_time(client_id.clone(), *height, host_timestamp)
+			.map_err(|e| ContractError::Grandpa(e.to_string()))?;
+		ctx.store_update_height(client_id.clone(), *height, host_height)
+			.map_err(|e| ContractError::Grandpa(e.to_string()))?;
+	}
 	log!(ctx, "Storing client state with height: {:?}", height);
 	ctx.store_client_state(client_id, client_state)
 		.map_err(|e| ContractError::Grandpa(e.to_string()))?;
@@ -521,4 +533,45 @@ mod tests {
 			assert_eq!(resp, QueryResponse::success().status(expected.to_string()));
 		}
 	}
+
+	#[test]
+	fn prune_expired_consensus_states_keeps_unexpired_ones() {
+		let mut deps = mock_dependencies();
+		let env = mock_env();
+		let now = env.block.time.seconds() as i64;
+		let mut client_state = ics10_grandpa::client_state::ClientState::<HostFunctions>::default();
+		client_state.latest_para_height = 4;
+		let revision = client_state.latest_height().revision_number;
+		let trusting_period = client_state.relay_chain.trusting_period().unwrap().as_secs() as i64;
+		let client_id = ClientId::default();
+		let mut ctx = Context::<HostFunctions>::new(deps.as_mut(), env);
+
+		// heights 1 and 2 are past the trusting period, 3 isn't, and 4 is the latest height
+		for (height, age) in [(1, 20), (2, 10), (3, -10), (4, 30)] {
+			let height = Height::new(revision, height);
+			let consensus_state = ics10_grandpa::consensus_state::ConsensusState::new(
+				vec![],
+				Time::from_unix_timestamp(now - trusting_period - age, 0).unwrap(),
+			);
+			ctx.store_consensus_state(client_id.clone(), height, consensus_state).unwrap();
+			ctx.store_update_time(client_id.clone(), height, ctx.host_timestamp()).unwrap();
+			ctx.store_update_height(client_id.clone(), height, ctx.host_height()).unwrap();
+		}
+		ctx.prune_expired_consensus_states(&client_id, &client_state).unwrap();
+
+		let heights = CONSENSUS_STATES_HEIGHTS
+			.load(ctx.storage(), client_id.as_bytes().to_owned())
+			.unwrap();
+		assert_eq!(
+			heights.into_iter().collect::<Vec<_>>(),
+			vec![Height::new(revision, 3), Height::new(revision, 4)]
+		);
+		for (height, pruned) in [(1, true), (2, true), (3, false), (4, false)] {
+			let height = Height::new(revision, height);
+			assert_eq!(ctx.consensus_state(&client_id, height).is_err(), pruned);
+			let key = (client_id.as_bytes().to_owned(), height.encode_vec().unwrap());
+			assert_eq!(!CLIENT_UPDATE_TIME.has(ctx.storage(), key.clone()), pruned);
+			assert_eq!(!CLIENT_UPDATE_HEIGHT.has(ctx.storage(), key), pruned);
+		}
+	}
 }
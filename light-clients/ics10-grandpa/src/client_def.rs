@@ -27,7 +27,7 @@ use grandpa_client_primitives::{
 	justification::{
 		find_forced_change, find_scheduled_change, AncestryChain, GrandpaJustification,
 	},
-	ParachainHeadersWithFinalityProof,
+	ParachainHeaderProofs, ParachainHeadersWithFinalityProof,
 };
 use ibc::{
 	core::{
@@ -39,14 +39,16 @@ use ibc::{
 		ics04_channel::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
-			packet::Sequence,
+			packet::{Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
 		ics24_host::{
 			identifier::{ChannelId, ClientId, ConnectionId, PortId},
 			path::{
-				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
-				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+				AcksPath, ChannelEndsPath, ChannelUpgradeErrorPath, ChannelUpgradePath,
+				ClientConsensusStatePath, ClientStatePath, CommitmentsPath, ConnectionsPath,
+				ReceiptsPath, SeqRecvsPath,
 			},
 		},
 		ics26_routing::context::ReaderContext,
@@ -97,10 +99,17 @@ where
 					latest_para_height: header.height.revision_height as u32,
 				};
 
-				grandpa_client::verify_parachain_headers_with_grandpa_finality_proof::<
-					RelayChainHeader,
-					H,
-				>(client_state.into(), headers_with_finality_proof)
+				if client_state.solo_chain {
+					grandpa_client::verify_solo_chain_headers_with_grandpa_finality_proof::<
+						RelayChainHeader,
+						H,
+					>(client_state.into(), headers_with_finality_proof)
+				} else {
+					grandpa_client::verify_parachain_headers_with_grandpa_finality_proof::<
+						RelayChainHeader,
+						H,
+					>(client_state.into(), headers_with_finality_proof)
+				}
 				.map_err(Error::GrandpaPrimitives)?;
 			},
 			ClientMessage::Misbehaviour(misbehavior) => {
@@ -241,11 +250,8 @@ where
 				Error::Custom(format!("No relay chain header found for hash: {relay_hash:?}"))
 			})?;
 
-			let (height, consensus_state) = ConsensusState::from_header::<H>(
-				parachain_header_proof,
-				client_state.para_id,
-				header.state_root.clone(),
-			)?;
+			let (height, consensus_state) =
+				consensus_state_from_header(&client_state, header, parachain_header_proof)?;
 
 			// Skip duplicate consensus states
 			if ctx.consensus_state(&client_id, height).is_ok() {
@@ -343,11 +349,8 @@ where
 				return Ok(true)
 			}
 
-			let (height, consensus_state) = ConsensusState::from_header::<H>(
-				parachain_header_proof,
-				client_state.para_id,
-				header.state_root.clone(),
-			)?;
+			let (height, consensus_state) =
+				consensus_state_from_header(&client_state, header, parachain_header_proof)?;
 
 			match ctx.maybe_consensus_state(&client_id, height)? {
 				Some(cs) => {
@@ -457,18 +460,43 @@ where
 	///
 	/// The following must always be true:
 	///   - The substitute client is the same type as the subject client
-	///   - The subject and substitute client states match in all parameters (expect `relay_chain`,
-	/// `para_id`, `latest_para_height`, `latest_relay_height`, `latest_relay_hash`,
-	/// `frozen_height`, `latest_para_height`, `current_set_id` and `current_authorities`).
+	///   - The subject and substitute client states track the same chain, i.e. they match on
+	/// `relay_chain`, `para_id` and `solo_chain`. All other fields (`latest_para_height`,
+	/// `latest_relay_height`, `latest_relay_hash`, `current_set_id` and `current_authorities`)
+	/// are copied over from the substitute and the subject is unfrozen.
 	fn check_substitute_and_update_state<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_subject_client_id: ClientId,
-		_substitute_client_id: ClientId,
-		_old_client_state: Self::ClientState,
-		_substitute_client_state: Self::ClientState,
+		substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
-		unimplemented!("check_substitute_and_update_state not implemented for Grandpa client")
+		if !old_client_state.matches_substitute(&substitute_client_state) {
+			Err(Error::Custom(
+				"Subject client state does not match substitute client state".to_string(),
+			))?
+		}
+
+		let height = substitute_client_state.latest_height();
+		let consensus_state = ctx
+			.consensus_state(&substitute_client_id, height)?
+			.downcast::<Self::ConsensusState>()
+			.ok_or_else(|| {
+				Error::Custom(format!(
+					"Wrong consensus state type stored for Grandpa client with {substitute_client_id} at {height}"
+				))
+			})?;
+
+		let client_state = ClientState { frozen_height: None, ..substitute_client_state };
+
+		Ok((
+			client_state,
+			ConsensusUpdateResult::Single(
+				Ctx::AnyConsensusState::wrap(&consensus_state)
+					.expect("AnyConsensusState is type-checked; qed"),
+			),
+		))
 	}
 
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
@@ -536,6 +564,48 @@ where
 		Ok(())
 	}
 
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ChannelUpgradePath(port_id.clone(), *channel_id);
+		let value = upgrade.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H::BlakeTwo256, _>(prefix, proof, root, path, value)
+			.map_err(Error::Anyhow)?;
+		Ok(())
+	}
+
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ChannelUpgradeErrorPath(port_id.clone(), *channel_id);
+		let value = error_receipt.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H::BlakeTwo256, _>(prefix, proof, root, path, value)
+			.map_err(Error::Anyhow)?;
+		Ok(())
+	}
+
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
@@ -672,4 +742,55 @@ where
 		.map_err(Error::Anyhow)?;
 		Ok(())
 	}
+
+	fn verify_packet_timeout_receipt<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H::BlakeTwo256, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			receipt_path,
+			Receipt::Timeout.as_bytes().to_vec(),
+		)
+		.map_err(Error::Anyhow)?;
+		Ok(())
+	}
+}
+
+/// Builds the consensus state of the parachain header proven in the state of the given finalized
+/// relay chain header. Solo chains finalize their own headers, so the consensus state is built from
+/// the finalized header itself.
+fn consensus_state_from_header<H>(
+	client_state: &ClientState<H>,
+	header: &RelayChainHeader,
+	header_proof: ParachainHeaderProofs,
+) -> Result<(Height, ConsensusState), Error>
+where
+	H: grandpa_client_primitives::HostFunctions<Header = RelayChainHeader>,
+{
+	if client_state.solo_chain {
+		ConsensusState::from_solo_chain_header(header, header_proof, client_state.para_id)
+	} else {
+		ConsensusState::from_header::<H>(
+			header_proof,
+			client_state.para_id,
+			header.state_root.clone(),
+		)
+	}
 }
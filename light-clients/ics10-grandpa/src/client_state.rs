@@ -17,7 +17,10 @@ use crate::{
 	client_def::GrandpaClient,
 	client_message::RelayChainHeader,
 	error::Error,
-	proto::{Authority as RawAuthority, ClientState as RawClientState},
+	proto::{
+		Authority as RawAuthority, ClientState as RawClientState,
+		RelayChainParams as RawRelayChainParams,
+	},
 };
 use alloc::{format, string::ToString, vec::Vec};
 use anyhow::anyhow;
@@ -61,6 +64,9 @@ pub struct ClientState<H> {
 	pub current_set_id: u64,
 	/// authorities for the current round
 	pub current_authorities: AuthorityList,
+	/// Whether this client follows a standalone chain's own GRANDPA finality. The relay chain
+	/// fields then track the chain itself and `para_id` is only used as the revision number.
+	pub solo_chain: bool,
 	/// phantom type.
 	pub _phantom: PhantomData<H>,
 }
@@ -141,9 +147,19 @@ impl<H> ClientState<H> {
 	}
 
 	/// Check if the state is expired when `elapsed` time has passed since the latest consensus
-	/// state timestamp
+	/// state timestamp. A client whose trusting period can't be computed is always expired.
 	pub fn expired(&self, elapsed: Duration) -> bool {
-		elapsed > self.relay_chain.trusting_period()
+		self.relay_chain
+			.trusting_period()
+			.map_or(true, |trusting_period| elapsed > trusting_period)
+	}
+
+	/// Returns true if `substitute` may be used to recover this client, i.e. both clients follow
+	/// the same chain: they match on the relay chain, the para id and the solo chain flag.
+	pub fn matches_substitute(&self, substitute: &Self) -> bool {
+		self.relay_chain == substitute.relay_chain &&
+			self.para_id == substitute.para_id &&
+			self.solo_chain == substitute.solo_chain
 	}
 
 	pub fn with_frozen_height(self, h: Height) -> Result<Self, Error> {
@@ -242,7 +258,18 @@ impl<H> TryFrom<RawClientState> for ClientState<H> {
 			})
 			.collect::<Result<_, Error>>()?;
 
-		let relay_chain = RelayChain::from_i32(raw.relay_chain)?;
+		// client states created before the relay chain parameters were stored only hold the id
+		// of a known relay chain
+		let relay_chain = match raw.relay_chain_params {
+			Some(params) => RelayChain::from_params(
+				params.id,
+				params.unbonding_period,
+				params.trusting_period_numerator,
+				params.trusting_period_denominator,
+			),
+			None => RelayChain::from_i32(raw.relay_chain)?,
+		};
+		relay_chain.validate()?;
 		if raw.latest_relay_hash.len() != 32 {
 			Err(anyhow!("Invalid ed25519 public key lenght: {}", raw.latest_relay_hash.len()))?
 		}
@@ -259,6 +286,7 @@ impl<H> TryFrom<RawClientState> for ClientState<H> {
 			current_authorities,
 			latest_relay_hash,
 			latest_relay_height: raw.latest_relay_height,
+			solo_chain: raw.solo_chain,
 			_phantom: Default::default(),
 		})
 	}
@@ -273,7 +301,13 @@ impl<H> From<ClientState<H>> for RawClientState {
 			frozen_height: client_state
 				.frozen_height
 				.map(|frozen_height| frozen_height.revision_height),
-			relay_chain: client_state.relay_chain as i32,
+			relay_chain: client_state.relay_chain.id as i32,
+			relay_chain_params: Some(RawRelayChainParams {
+				id: client_state.relay_chain.id,
+				unbonding_period: client_state.relay_chain.unbonding_period.as_secs(),
+				trusting_period_numerator: client_state.relay_chain.trusting_period_ratio.0,
+				trusting_period_denominator: client_state.relay_chain.trusting_period_ratio.1,
+			}),
 			para_id: client_state.para_id,
 			latest_para_height: client_state.latest_para_height,
 			current_authorities: client_state
@@ -285,6 +319,7 @@ impl<H> From<ClientState<H>> for RawClientState {
 					weight,
 				})
 				.collect(),
+			solo_chain: client_state.solo_chain,
 		}
 	}
 }
@@ -146,6 +146,7 @@ async fn test_continuous_update_of_grandpa_client() {
 			para_id: prover.para_id,
 			current_set_id: client_state.current_set_id,
 			current_authorities: client_state.current_authorities,
+			solo_chain: false,
 			_phantom: Default::default(),
 		};
 		let subxt_block_number: subxt::rpc::types::BlockNumber = decoded_para_head.number.into();
@@ -306,3 +307,162 @@ async fn test_continuous_update_of_grandpa_client() {
 		}
 	}
 }
+
+#[test]
+fn client_state_relay_chain_encoding_is_backward_compatible() {
+	use crate::proto::ClientState as RawClientState;
+	use light_client_common::RelayChain;
+
+	// client states encoded before the relay chain parameters existed only hold the enum value
+	let mut raw = RawClientState::from(ClientState::<HostFunctionsManager>::default());
+	raw.relay_chain = 1;
+	raw.relay_chain_params = None;
+	let client_state = ClientState::<HostFunctionsManager>::try_from(raw.clone()).unwrap();
+	assert_eq!(client_state.relay_chain, RelayChain::KUSAMA);
+
+	raw.relay_chain = 42;
+	assert!(ClientState::<HostFunctionsManager>::try_from(raw).is_err());
+
+	// custom relay chains round trip through their parameters
+	let local = RelayChain::new(42, Duration::from_secs(60 * 60), (1, 2));
+	let client_state =
+		ClientState::<HostFunctionsManager> { relay_chain: local, ..Default::default() };
+	let decoded =
+		ClientState::<HostFunctionsManager>::try_from(RawClientState::from(client_state)).unwrap();
+	assert_eq!(decoded.relay_chain, local);
+	assert_eq!(decoded.relay_chain.trusting_period().unwrap(), Duration::from_secs(30 * 60));
+	assert_eq!(decoded.chain_id().to_string(), "Relay42-0");
+
+	// invalid parameters are rejected
+	let mut raw = RawClientState::from(ClientState::<HostFunctionsManager> {
+		relay_chain: RelayChain::new(42, Duration::from_secs(60 * 60), (2, 1)),
+		..Default::default()
+	});
+	assert!(ClientState::<HostFunctionsManager>::try_from(raw.clone()).is_err());
+	let params = raw.relay_chain_params.as_mut().unwrap();
+	(params.trusting_period_numerator, params.trusting_period_denominator) = (2, 3);
+	params.unbonding_period = u64::MAX;
+	assert!(ClientState::<HostFunctionsManager>::try_from(raw).is_err());
+}
+
+#[test]
+fn client_state_solo_chain_round_trips() {
+	use crate::proto::ClientState as RawClientState;
+
+	// client states encoded before solo chains were supported follow a parachain
+	let raw = RawClientState::from(ClientState::<HostFunctionsManager>::default());
+	assert!(!raw.solo_chain);
+	let client_state = ClientState::<HostFunctionsManager>::try_from(raw).unwrap();
+	assert!(!client_state.solo_chain);
+
+	let client_state =
+		ClientState::<HostFunctionsManager> { solo_chain: true, ..Default::default() };
+	let decoded =
+		ClientState::<HostFunctionsManager>::try_from(RawClientState::from(client_state.clone()))
+			.unwrap();
+	assert_eq!(decoded, client_state);
+}
+
+#[test]
+fn solo_chain_consensus_state_is_built_from_the_finalized_header() {
+	use codec::Compact;
+	use ibc::timestamp::Timestamp;
+
+	let mut extrinsic = (1u8, 0u8, Compact(1_650_894_363_000u64)).encode();
+	extrinsic.splice(0..0, [0u8, 0u8]);
+	let header = RelayChainHeader {
+		parent_hash: Default::default(),
+		number: 42,
+		state_root: H256::repeat_byte(7),
+		extrinsics_root: Default::default(),
+		digest: Default::default(),
+	};
+	let proofs = ParachainHeaderProofs { state_proof: vec![], extrinsic, extrinsic_proof: vec![] };
+
+	let (height, consensus_state) =
+		ConsensusState::from_solo_chain_header(&header, proofs, 2000).unwrap();
+
+	// solo chains use the para id as revision number and their own header's state root
+	assert_eq!(height, Height::new(2000, 42));
+	assert_eq!(consensus_state.root.as_bytes(), H256::repeat_byte(7).as_bytes());
+	assert_eq!(
+		Timestamp::from(consensus_state.timestamp).nanoseconds(),
+		1_650_894_363_000 * 1_000_000
+	);
+}
+
+#[test]
+fn recover_client_checks_substitute_follows_the_same_chain() {
+	use ibc::core::ics02_client::{
+		error::{Error as Ics02Error, ErrorDetail},
+		handler::recover_client,
+	};
+
+	let subject_id = ClientId::new(&ClientState::<HostFunctionsManager>::client_type(), 0).unwrap();
+	let substitute_id =
+		ClientId::new(&ClientState::<HostFunctionsManager>::client_type(), 1).unwrap();
+	let mut ctx = MockContext::<MockClientTypes>::default();
+	let timestamp = ctx.host_timestamp().into_tm_time().unwrap();
+
+	let subject = ClientState::<HostFunctionsManager> {
+		para_id: 2000,
+		latest_para_height: 10,
+		frozen_height: Some(Height::new(2000, 10)),
+		..Default::default()
+	};
+	let substitute = ClientState::<HostFunctionsManager> {
+		para_id: 2000,
+		latest_para_height: 20,
+		latest_relay_height: 100,
+		current_set_id: 3,
+		..Default::default()
+	};
+	let store_client = |ctx: &mut MockContext<MockClientTypes>,
+	                    client_id: &ClientId,
+	                    client_state: &ClientState<HostFunctionsManager>| {
+		ctx.store_client_type(
+			client_id.clone(),
+			ClientState::<HostFunctionsManager>::client_type(),
+		)
+		.unwrap();
+		ctx.store_client_state(client_id.clone(), AnyClientState::Grandpa(client_state.clone()))
+			.unwrap();
+		ctx.store_consensus_state(
+			client_id.clone(),
+			client_state.latest_height(),
+			AnyConsensusState::Grandpa(ConsensusState::new(vec![1; 32], timestamp)),
+		)
+		.unwrap();
+	};
+	store_client(&mut ctx, &subject_id, &subject);
+
+	// a substitute following a different chain can't recover the client
+	for mismatched in [
+		ClientState { para_id: 2001, ..substitute.clone() },
+		ClientState { solo_chain: true, ..substitute.clone() },
+	] {
+		store_client(&mut ctx, &substitute_id, &mismatched);
+		match recover_client::process(&ctx, subject_id.clone(), substitute_id.clone()) {
+			Err(Ics02Error(ErrorDetail::ClientError(e), _)) => assert!(
+				e.inner.contains("does not match substitute"),
+				"unexpected error: {}",
+				e.inner
+			),
+			_ => panic!("expected a mismatched substitute error"),
+		}
+	}
+
+	store_client(&mut ctx, &substitute_id, &substitute);
+	let output = recover_client::process(&ctx, subject_id.clone(), substitute_id).unwrap();
+	match output.result {
+		Update(res) => {
+			assert_eq!(res.client_id, subject_id);
+			assert!(res.consensus_state.is_some());
+			match res.client_state {
+				AnyClientState::Grandpa(client_state) => assert_eq!(client_state, substitute),
+				_ => panic!("expected a grandpa client state"),
+			}
+		},
+		_ => panic!("recover handler result has incorrect type"),
+	}
+}
@@ -17,12 +17,13 @@ use tendermint_proto::Protobuf;
 
 use crate::{
 	error::Error,
+	misbehaviour::BeefyMisbehaviour,
 	proto::{
 		client_message, BeefyAuthoritySet as RawBeefyAuthoritySet, BeefyMmrLeaf as RawBeefyMmrLeaf,
 		BeefyMmrLeafPartial as RawBeefyMmrLeafPartial, ClientMessage as RawClientMessage,
 		ClientStateUpdateProof as RawMmrUpdateProof, Commitment as RawCommitment,
-		CommitmentSignature, ConsensusStateUpdateProof, Header as RawBeefyHeader,
-		Misbehaviour as RawMisbehaviour, PayloadItem, SignedCommitment as RawSignedCommitment,
+		CommitmentSignature, ConsensusStateUpdateProof, Header as RawBeefyHeader, PayloadItem,
+		SignedCommitment as RawSignedCommitment,
 	},
 };
 use alloc::{format, vec, vec::Vec};
@@ -60,7 +61,7 @@ pub enum ClientMessage {
 	/// Header variant for updating the client
 	Header(BeefyHeader),
 	/// Misbehaviour variant for freezing the client.
-	Misbehaviour(()),
+	Misbehaviour(BeefyMisbehaviour),
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -116,7 +117,7 @@ impl TryFrom<RawClientMessage> for ClientMessage {
 			client_message::Message::Header(raw_header) => {
 				let headers_with_proof = raw_header
 					.consensus_state
-					.map(|consensus_update| {
+					.map(|consensus_update| -> Result<_, Error> {
 						let parachain_headers = consensus_update
 							.parachain_headers
 							.into_iter()
@@ -128,8 +129,7 @@ impl TryFrom<RawClientMessage> for ClientMessage {
 										))
 									})?;
 								let parent_hash =
-									H256::decode(&mut mmr_partial_leaf.parent_hash.as_slice())
-										.unwrap();
+									H256::decode(&mut mmr_partial_leaf.parent_hash.as_slice())?;
 								let beefy_next_authority_set = if let Some(next_set) =
 									mmr_partial_leaf.beefy_next_authority_set
 								{
@@ -181,9 +181,16 @@ impl TryFrom<RawClientMessage> for ClientMessage {
 									timestamp_extrinsic: raw_para_header.timestamp_extrinsic,
 								})
 							})
-							.collect::<Result<Vec<_>, Error>>()
-							.ok();
-						parachain_headers.map(|parachain_headers| ParachainHeadersWithProof {
+							.collect::<Result<Vec<_>, Error>>()?;
+						// every parachain header is proven against its own leaf in the batch proof
+						if consensus_update.leaf_indices.len() != parachain_headers.len() {
+							Err(Error::Custom(format!(
+								"Expected {} mmr leaf indices for the parachain headers, found {}",
+								parachain_headers.len(),
+								consensus_update.leaf_indices.len()
+							)))?
+						}
+						Ok(ParachainHeadersWithProof {
 							headers: parachain_headers,
 							mmr_proofs: consensus_update.mmr_proofs,
 							mmr_size: consensus_update.mmr_size,
@@ -191,7 +198,7 @@ impl TryFrom<RawClientMessage> for ClientMessage {
 							leaf_count: consensus_update.leaf_count,
 						})
 					})
-					.flatten();
+					.transpose()?;
 
 				let mmr_update_proof = if let Some(mmr_update) = raw_header.client_state {
 					let commitment = mmr_update
@@ -287,8 +294,13 @@ impl TryFrom<RawClientMessage> for ClientMessage {
 								.map_err(|e| Error::Custom(format!("{e}")))?,
 						},
 						mmr_proof: Proof {
-							leaf_indices: vec![mmr_update.mmr_leaf_index], // TODO(blas): fix this
-							leaf_count: mmr_update.mmr_leaf_index + 1,
+							leaf_indices: vec![mmr_update.mmr_leaf_index],
+							// proofs encoded without the leaf count are for the latest leaf
+							leaf_count: if mmr_update.mmr_leaf_count == 0 {
+								mmr_update.mmr_leaf_index + 1
+							} else {
+								mmr_update.mmr_leaf_count
+							},
 							items: mmr_update
 								.mmr_proof
 								.into_iter()
@@ -320,7 +332,8 @@ impl TryFrom<RawClientMessage> for ClientMessage {
 
 				ClientMessage::Header(BeefyHeader { headers_with_proof, mmr_update_proof })
 			},
-			client_message::Message::Misbehaviour(_) => ClientMessage::Misbehaviour(()),
+			client_message::Message::Misbehaviour(raw_misbehaviour) =>
+				ClientMessage::Misbehaviour(raw_misbehaviour.try_into()?),
 		};
 
 		Ok(message)
@@ -412,8 +425,15 @@ impl From<ClientMessage> for RawClientMessage {
 								}),
 								parachain_heads: mmr_update.latest_mmr_leaf.leaf_extra.encode(),
 							}),
-							mmr_leaf_index: mmr_update.mmr_proof.leaf_indices[0], /* TODO(blas):
-							                                                       * fix this */
+							// proofs of more or less than a single leaf are rejected by
+							// `encode_vec`
+							mmr_leaf_index: mmr_update
+								.mmr_proof
+								.leaf_indices
+								.first()
+								.copied()
+								.unwrap_or_default(),
+							mmr_leaf_count: mmr_update.mmr_proof.leaf_count,
 							mmr_proof: mmr_update
 								.mmr_proof
 								.items
@@ -462,11 +482,25 @@ impl From<ClientMessage> for RawClientMessage {
 					},
 				})),
 			},
-			ClientMessage::Misbehaviour(_) => RawClientMessage {
-				message: Some(client_message::Message::Misbehaviour(RawMisbehaviour {})),
+			ClientMessage::Misbehaviour(misbehaviour) => RawClientMessage {
+				message: Some(client_message::Message::Misbehaviour(misbehaviour.into())),
 			},
 		}
 	}
 }
 
-impl Protobuf<RawClientMessage> for ClientMessage {}
+impl Protobuf<RawClientMessage> for ClientMessage {
+	fn encode_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		// the mmr update proof is encoded with the index of the single leaf it proves
+		if let ClientMessage::Header(BeefyHeader { mmr_update_proof: Some(mmr_update), .. }) = self
+		{
+			let leaf_count = mmr_update.mmr_proof.leaf_indices.len();
+			if leaf_count != 1 {
+				return Err(tendermint_proto::Error::try_from_protobuf(format!(
+					"Mmr update proof must prove a single leaf, found {leaf_count} leaf indices"
+				)))
+			}
+		}
+		Ok(prost::Message::encode_to_vec(&RawClientMessage::from(self.clone())))
+	}
+}
@@ -23,7 +23,9 @@ use sp_core::H256;
 use sp_runtime::SaturatedConversion;
 use tendermint_proto::Protobuf;
 
-use crate::proto::{BeefyAuthoritySet, ClientState as RawClientState};
+use crate::proto::{
+	BeefyAuthoritySet, ClientState as RawClientState, RelayChainParams as RawRelayChainParams,
+};
 
 use crate::{client_message::BeefyHeader, error::Error};
 
@@ -216,9 +218,11 @@ impl<H> ClientState<H> {
 	}
 
 	/// Check if the state is expired when `elapsed` time has passed since the latest consensus
-	/// state timestamp
+	/// state timestamp. A client whose trusting period can't be computed is always expired.
 	pub fn expired(&self, elapsed: Duration) -> bool {
-		elapsed > self.relay_chain.trusting_period()
+		self.relay_chain
+			.trusting_period()
+			.map_or(true, |trusting_period| elapsed > trusting_period)
 	}
 }
 
@@ -294,7 +298,18 @@ impl<H> TryFrom<RawClientState> for ClientState<H> {
 			.ok_or_else(|| Error::Custom(format!("Next authority set is missing")))?;
 
 		let mmr_root_hash = H256::decode(&mut &*raw.mmr_root_hash)?;
-		let relay_chain = RelayChain::from_i32(raw.relay_chain)?;
+		// client states created before the relay chain parameters were stored only hold the id
+		// of a known relay chain
+		let relay_chain = match raw.relay_chain_params {
+			Some(params) => RelayChain::from_params(
+				params.id,
+				params.unbonding_period,
+				params.trusting_period_numerator,
+				params.trusting_period_denominator,
+			),
+			None => RelayChain::from_i32(raw.relay_chain)?,
+		};
+		relay_chain.validate()?;
 		let chain_id = ChainId::new(relay_chain.to_string(), raw.para_id.into());
 
 		Ok(Self {
@@ -330,7 +345,13 @@ impl<H> From<ClientState<H>> for RawClientState {
 				len: client_state.next_authority_set.len,
 				authority_root: client_state.next_authority_set.keyset_commitment.encode(),
 			}),
-			relay_chain: client_state.relay_chain as i32,
+			relay_chain: client_state.relay_chain.id as i32,
+			relay_chain_params: Some(RawRelayChainParams {
+				id: client_state.relay_chain.id,
+				unbonding_period: client_state.relay_chain.unbonding_period.as_secs(),
+				trusting_period_numerator: client_state.relay_chain.trusting_period_ratio.0,
+				trusting_period_denominator: client_state.relay_chain.trusting_period_ratio.1,
+			}),
 			para_id: client_state.para_id,
 			latest_para_height: client_state.latest_para_height,
 		}
@@ -345,7 +366,7 @@ pub mod test_util {
 	pub fn get_dummy_beefy_state() -> AnyClientState {
 		AnyClientState::Beefy(
 			ClientState::new(
-				RelayChain::Rococo,
+				RelayChain::ROCOCO,
 				2000,
 				0,
 				Default::default(),
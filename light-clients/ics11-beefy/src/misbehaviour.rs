@@ -12,3 +12,222 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+//! BEEFY misbehaviour: equivocating signed commitments of the BEEFY authorities.
+
+use crate::{
+	client_state::ClientState,
+	error::Error,
+	proto::{
+		Commitment as RawCommitment, CommitmentSignature, Misbehaviour as RawMisbehaviour,
+		PayloadItem, SignedCommitment as RawSignedCommitment,
+		SignedCommitmentWithProof as RawSignedCommitmentWithProof,
+	},
+};
+use alloc::{format, vec::Vec};
+use beefy_light_client_primitives::{
+	ClientState as LightClientState, Hash, SignatureWithAuthorityIndex, SignedCommitment,
+};
+use beefy_primitives::{BeefyPayloadId, Commitment, Payload};
+use codec::{Decode, Encode};
+
+/// A [`SignedCommitment`] along with the merkle multi-proof of the authorities that signed it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SignedCommitmentWithProof {
+	/// Commitment and the signatures of the authorities
+	pub signed_commitment: SignedCommitment,
+	/// Proof of the signing authorities in the authority set
+	pub authority_proof: Vec<Hash>,
+}
+
+/// Equivocation of the BEEFY authorities: two signed commitments for the same block number with
+/// different payloads, each signed by a supermajority of the current or next authority set.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BeefyMisbehaviour {
+	/// First signed commitment
+	pub first: SignedCommitmentWithProof,
+	/// Signed commitment conflicting with the first one
+	pub second: SignedCommitmentWithProof,
+}
+
+impl BeefyMisbehaviour {
+	/// Verifies that both commitments are for the same block number, commit to different payloads
+	/// and were signed by the authorities known to the client.
+	pub fn verify<H>(&self, client_state: &ClientState<H>) -> Result<(), Error>
+	where
+		H: beefy_light_client_primitives::HostFunctions + Clone,
+	{
+		let first = &self.first.signed_commitment.commitment;
+		let second = &self.second.signed_commitment.commitment;
+
+		if first.block_number != second.block_number {
+			Err(Error::Custom(format!(
+				"Misbehaviour commitments are for different blocks: {} and {}",
+				first.block_number, second.block_number
+			)))?
+		}
+
+		if first.payload == second.payload {
+			Err(Error::Custom(format!(
+				"Misbehaviour commitments for block {} have the same payload",
+				first.block_number
+			)))?
+		}
+
+		let light_client_state = LightClientState {
+			latest_beefy_height: client_state.latest_beefy_height,
+			mmr_root_hash: client_state.mmr_root_hash,
+			current_authorities: client_state.authority.clone(),
+			next_authorities: client_state.next_authority_set.clone(),
+		};
+
+		for SignedCommitmentWithProof { signed_commitment, authority_proof } in
+			[&self.first, &self.second]
+		{
+			beefy_client::verify_signed_commitment::<H>(
+				&light_client_state,
+				signed_commitment,
+				authority_proof.clone(),
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl TryFrom<RawMisbehaviour> for BeefyMisbehaviour {
+	type Error = Error;
+
+	fn try_from(raw: RawMisbehaviour) -> Result<Self, Self::Error> {
+		let first = raw
+			.first
+			.ok_or_else(|| Error::Custom(format!("Misbehaviour is missing first")))?;
+		let second = raw
+			.second
+			.ok_or_else(|| Error::Custom(format!("Misbehaviour is missing second")))?;
+		Ok(Self { first: first.try_into()?, second: second.try_into()? })
+	}
+}
+
+impl From<BeefyMisbehaviour> for RawMisbehaviour {
+	fn from(misbehaviour: BeefyMisbehaviour) -> Self {
+		RawMisbehaviour {
+			first: Some(misbehaviour.first.into()),
+			second: Some(misbehaviour.second.into()),
+		}
+	}
+}
+
+impl TryFrom<RawSignedCommitmentWithProof> for SignedCommitmentWithProof {
+	type Error = Error;
+
+	fn try_from(raw: RawSignedCommitmentWithProof) -> Result<Self, Self::Error> {
+		let raw_signed_commitment = raw
+			.signed_commitment
+			.ok_or_else(|| Error::Custom(format!("Signed commitment is missing")))?;
+		let raw_commitment = raw_signed_commitment
+			.commitment
+			.ok_or_else(|| Error::Custom(format!("Commitment is missing")))?;
+
+		// Unlike header updates, all payload items are kept, since the authorities sign the
+		// complete commitment.
+		let mut payload_items = raw_commitment
+			.payload
+			.into_iter()
+			.map(|item| {
+				let payload_id: BeefyPayloadId =
+					item.payload_id.as_slice().try_into().map_err(|_| {
+						Error::Custom(format!("Invalid payload id: {:?}", item.payload_id))
+					})?;
+				Ok((payload_id, item.payload_data))
+			})
+			.collect::<Result<Vec<_>, Error>>()?
+			.into_iter();
+		let (payload_id, payload_data) = payload_items
+			.next()
+			.ok_or_else(|| Error::Custom(format!("Commitment payload is empty")))?;
+		let payload = payload_items.fold(
+			Payload::from_single_entry(payload_id, payload_data),
+			|payload, (payload_id, payload_data)| payload.push_raw(payload_id, payload_data),
+		);
+
+		let signatures = raw_signed_commitment
+			.signatures
+			.into_iter()
+			.map(|commitment_sig| {
+				let signature = commitment_sig.signature.as_slice().try_into().map_err(|_| {
+					Error::Custom(format!(
+						"Invalid signature length: {}",
+						commitment_sig.signature.len()
+					))
+				})?;
+				Ok(SignatureWithAuthorityIndex { signature, index: commitment_sig.authority_index })
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		let authority_proof = raw
+			.authorities_proof
+			.into_iter()
+			.map(|item| {
+				item.as_slice().try_into().map_err(|_| {
+					Error::Custom(format!(
+						"Invalid authorities proof item with len: {}",
+						item.len()
+					))
+				})
+			})
+			.collect::<Result<Vec<Hash>, Error>>()?;
+
+		Ok(Self {
+			signed_commitment: SignedCommitment {
+				commitment: Commitment {
+					payload,
+					block_number: raw_commitment.block_numer,
+					validator_set_id: raw_commitment.validator_set_id,
+				},
+				signatures,
+			},
+			authority_proof,
+		})
+	}
+}
+
+impl From<SignedCommitmentWithProof> for RawSignedCommitmentWithProof {
+	fn from(value: SignedCommitmentWithProof) -> Self {
+		let commitment = value.signed_commitment.commitment;
+		// `Payload` doesn't expose its items, but encodes as the sorted list of them.
+		let payload_items =
+			Vec::<(BeefyPayloadId, Vec<u8>)>::decode(&mut &*commitment.payload.encode())
+				.expect("Payload is encoded as a list of payload items; qed");
+
+		RawSignedCommitmentWithProof {
+			signed_commitment: Some(RawSignedCommitment {
+				commitment: Some(RawCommitment {
+					payload: payload_items
+						.into_iter()
+						.map(|(payload_id, payload_data)| PayloadItem {
+							payload_id: payload_id.to_vec(),
+							payload_data,
+						})
+						.collect(),
+					block_numer: commitment.block_number,
+					validator_set_id: commitment.validator_set_id,
+				}),
+				signatures: value
+					.signed_commitment
+					.signatures
+					.into_iter()
+					.map(|item| CommitmentSignature {
+						signature: item.signature.to_vec(),
+						authority_index: item.index,
+					})
+					.collect(),
+			}),
+			authorities_proof: value
+				.authority_proof
+				.into_iter()
+				.map(|item| item.to_vec())
+				.collect(),
+		}
+	}
+}
@@ -14,21 +14,32 @@
 // limitations under the License.
 
 use crate::{
+	client_def::BeefyClient,
 	client_message::{
 		BeefyHeader, ClientMessage, ParachainHeader as BeefyParachainHeader,
 		ParachainHeadersWithProof,
 	},
 	client_state::{ClientState as BeefyClientState, ClientState},
 	consensus_state::ConsensusState,
+	misbehaviour::{BeefyMisbehaviour, SignedCommitmentWithProof},
 	mock::{
 		AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager, MockClientTypes,
 	},
 };
-use beefy_light_client_primitives::{EncodedVersionedFinalityProof, NodesUtils, PartialMmrLeaf};
-use beefy_primitives::VersionedFinalityProof;
+use beefy_light_client_primitives::{
+	EncodedVersionedFinalityProof, MerkleHasher, MmrUpdateProof, NodesUtils, PartialMmrLeaf,
+	SignatureWithAuthorityIndex, SignedCommitment,
+};
+use beefy_primitives::{
+	known_payloads::MMR_ROOT_ID,
+	mmr::{BeefyNextAuthoritySet, MmrLeaf, MmrLeafVersion},
+	Commitment, Payload, VersionedFinalityProof,
+};
 use beefy_prover::{
-	helpers::{fetch_timestamp_extrinsic_with_proof, TimeStampExtWithProof},
-	Prover,
+	helpers::{
+		fetch_timestamp_extrinsic_with_proof, hash_authority_addresses, TimeStampExtWithProof,
+	},
+	Crypto, Prover,
 };
 use codec::{Decode, Encode};
 use futures::stream::StreamExt;
@@ -36,6 +47,7 @@ use hyperspace_core::substrate::DefaultConfig as PolkadotConfig;
 use ibc::{
 	core::{
 		ics02_client::{
+			client_def::ClientDef,
 			client_state::ClientState as _,
 			context::{ClientKeeper, ClientReader},
 			handler::{dispatch, ClientResult::Update},
@@ -51,9 +63,13 @@ use ibc::{
 	test_utils::get_dummy_account_id,
 	Height,
 };
-use light_client_common::config::RuntimeStorage;
+use light_client_common::{config::RuntimeStorage, RelayChain};
+use pallet_mmr_primitives::Proof;
+use sp_core::{ecdsa, keccak_256, Pair, H256};
+use sp_runtime::{generic::Header as SubstrateHeader, traits::BlakeTwo256};
 use std::time::Duration;
 use subxt::rpc::{rpc_params, Subscription};
+use tendermint_proto::Protobuf;
 
 #[tokio::test]
 #[ignore]
@@ -306,3 +322,254 @@ async fn test_continuous_update_of_beefy_client() {
 		}
 	}
 }
+
+const AUTHORITIES: usize = 5;
+
+fn authority_keys() -> Vec<ecdsa::Pair> {
+	(0..AUTHORITIES).map(|i| ecdsa::Pair::from_seed(&[i as u8 + 1; 32])).collect()
+}
+
+fn authority_leaves(keys: &[ecdsa::Pair]) -> Vec<[u8; 32]> {
+	hash_authority_addresses(keys.iter().map(|key| key.public().encode()).collect()).unwrap()
+}
+
+fn misbehaviour_client_state(keys: &[ecdsa::Pair]) -> BeefyClientState<HostFunctionsManager> {
+	let tree = rs_merkle::MerkleTree::<MerkleHasher<Crypto>>::from_leaves(&authority_leaves(keys));
+	let authority = BeefyNextAuthoritySet {
+		id: 0,
+		len: keys.len() as u32,
+		keyset_commitment: H256::from(tree.root().unwrap()),
+	};
+	let next_authority_set = BeefyNextAuthoritySet { id: 1, ..authority.clone() };
+	BeefyClientState::new(
+		RelayChain::ROCOCO,
+		2000,
+		10,
+		H256::default(),
+		100,
+		authority,
+		next_authority_set,
+	)
+	.unwrap()
+}
+
+fn sign_commitment(
+	keys: &[ecdsa::Pair],
+	signers: &[usize],
+	block_number: u32,
+	mmr_root: H256,
+) -> SignedCommitmentWithProof {
+	let commitment = Commitment {
+		payload: Payload::from_single_entry(MMR_ROOT_ID, mmr_root.encode()),
+		block_number,
+		validator_set_id: 0,
+	};
+	let message = keccak_256(&commitment.encode());
+	let signatures = signers
+		.iter()
+		.map(|&index| {
+			let mut signature = [0u8; 65];
+			signature.copy_from_slice(keys[index].sign_prehashed(&message).as_ref());
+			SignatureWithAuthorityIndex { signature, index: index as u32 }
+		})
+		.collect();
+	let tree = rs_merkle::MerkleTree::<MerkleHasher<Crypto>>::from_leaves(&authority_leaves(keys));
+	let authority_proof = tree.proof(signers).proof_hashes().to_vec();
+
+	SignedCommitmentWithProof {
+		signed_commitment: SignedCommitment { commitment, signatures },
+		authority_proof,
+	}
+}
+
+#[test]
+fn test_beefy_misbehaviour_freezes_client() {
+	let keys = authority_keys();
+	let client_state = misbehaviour_client_state(&keys);
+	let signers = [0, 1, 2, 3];
+	let misbehaviour = BeefyMisbehaviour {
+		first: sign_commitment(&keys, &signers, 120, H256::repeat_byte(1)),
+		second: sign_commitment(&keys, &signers, 120, H256::repeat_byte(2)),
+	};
+	misbehaviour.verify::<HostFunctionsManager>(&client_state).unwrap();
+
+	let message = ClientMessage::Misbehaviour(misbehaviour);
+	let decoded = ClientMessage::decode_vec(&message.encode_vec().unwrap()).unwrap();
+	assert_eq!(decoded, message);
+
+	let ctx = MockContext::<MockClientTypes>::default();
+	let client_id = ClientId::new(&ClientState::<()>::client_type(), 0).unwrap();
+	let client = BeefyClient::<HostFunctionsManager>::default();
+	client
+		.verify_client_message(&ctx, client_id.clone(), client_state.clone(), message.clone())
+		.unwrap();
+	assert!(client
+		.check_for_misbehaviour(&ctx, client_id, client_state.clone(), message.clone())
+		.unwrap());
+
+	let frozen = client.update_state_on_misbehaviour(client_state, message).unwrap();
+	assert_eq!(frozen.frozen_height, Some(Height::new(2000, 10)));
+}
+
+#[test]
+fn test_beefy_misbehaviour_rejects_invalid_evidence() {
+	let keys = authority_keys();
+	let client_state = misbehaviour_client_state(&keys);
+	let signers = [0, 1, 2, 3];
+
+	// same payload is not an equivocation
+	let misbehaviour = BeefyMisbehaviour {
+		first: sign_commitment(&keys, &signers, 120, H256::repeat_byte(1)),
+		second: sign_commitment(&keys, &signers, 120, H256::repeat_byte(1)),
+	};
+	assert!(misbehaviour.verify::<HostFunctionsManager>(&client_state).is_err());
+
+	// commitments for different blocks
+	let misbehaviour = BeefyMisbehaviour {
+		first: sign_commitment(&keys, &signers, 120, H256::repeat_byte(1)),
+		second: sign_commitment(&keys, &signers, 121, H256::repeat_byte(2)),
+	};
+	assert!(misbehaviour.verify::<HostFunctionsManager>(&client_state).is_err());
+
+	// not enough signatures
+	let misbehaviour = BeefyMisbehaviour {
+		first: sign_commitment(&keys, &signers, 120, H256::repeat_byte(1)),
+		second: sign_commitment(&keys, &[0, 1, 2], 120, H256::repeat_byte(2)),
+	};
+	assert!(misbehaviour.verify::<HostFunctionsManager>(&client_state).is_err());
+
+	// signature from a key outside of the authority set
+	let mut second = sign_commitment(&keys, &signers, 120, H256::repeat_byte(2));
+	let outsider = ecdsa::Pair::from_seed(&[42u8; 32]);
+	let message = keccak_256(&second.signed_commitment.commitment.encode());
+	second.signed_commitment.signatures[3]
+		.signature
+		.copy_from_slice(outsider.sign_prehashed(&message).as_ref());
+	let misbehaviour = BeefyMisbehaviour {
+		first: sign_commitment(&keys, &signers, 120, H256::repeat_byte(1)),
+		second,
+	};
+	assert!(misbehaviour.verify::<HostFunctionsManager>(&client_state).is_err());
+}
+
+fn parachain_headers_with_batch_proof(leaf_indices: Vec<u64>) -> ParachainHeadersWithProof {
+	let headers = leaf_indices
+		.iter()
+		.map(|leaf_index| BeefyParachainHeader {
+			parachain_header: SubstrateHeader::<u32, BlakeTwo256> {
+				parent_hash: Default::default(),
+				number: *leaf_index as u32 + 1,
+				state_root: Default::default(),
+				extrinsics_root: Default::default(),
+				digest: Default::default(),
+			},
+			partial_mmr_leaf: PartialMmrLeaf {
+				version: MmrLeafVersion::new(0, 0),
+				parent_number_and_hash: (*leaf_index as u32, H256::repeat_byte(1)),
+				beefy_next_authority_set: BeefyNextAuthoritySet {
+					id: 1,
+					len: 5,
+					keyset_commitment: H256::repeat_byte(2),
+				},
+			},
+			parachain_heads_proof: vec![[3u8; 32]],
+			heads_leaf_index: 0,
+			heads_total_count: 2,
+			extrinsic_proof: vec![vec![4u8; 8]],
+			timestamp_extrinsic: vec![5u8; 8],
+		})
+		.collect();
+
+	ParachainHeadersWithProof {
+		headers,
+		mmr_proofs: vec![H256::repeat_byte(6).encode(), H256::repeat_byte(7).encode()],
+		mmr_size: NodesUtils::new(20).size(),
+		leaf_indices,
+		leaf_count: 20,
+	}
+}
+
+#[test]
+fn test_batch_proof_client_message_roundtrip() {
+	let message = ClientMessage::Header(BeefyHeader {
+		headers_with_proof: Some(parachain_headers_with_batch_proof(vec![2, 7, 8, 15])),
+		mmr_update_proof: None,
+	});
+	let decoded = ClientMessage::decode_vec(&message.encode_vec().unwrap()).unwrap();
+	assert_eq!(decoded, message);
+
+	// every parachain header must have its own leaf index
+	let mut headers_with_proof = parachain_headers_with_batch_proof(vec![2, 7, 8]);
+	headers_with_proof.leaf_indices.pop();
+	let message = ClientMessage::Header(BeefyHeader {
+		headers_with_proof: Some(headers_with_proof),
+		mmr_update_proof: None,
+	});
+	assert!(ClientMessage::decode_vec(&message.encode_vec().unwrap()).is_err());
+}
+
+#[test]
+fn test_mmr_update_proof_must_prove_a_single_leaf() {
+	let keys = authority_keys();
+	let SignedCommitmentWithProof { signed_commitment, authority_proof } =
+		sign_commitment(&keys, &[0, 1, 2], 10, H256::repeat_byte(1));
+	let mmr_update_proof = |leaf_indices: Vec<u64>| MmrUpdateProof {
+		signed_commitment: signed_commitment.clone(),
+		latest_mmr_leaf: MmrLeaf {
+			version: MmrLeafVersion::new(0, 0),
+			parent_number_and_hash: (9, H256::repeat_byte(2)),
+			beefy_next_authority_set: BeefyNextAuthoritySet {
+				id: 1,
+				len: keys.len() as u32,
+				keyset_commitment: H256::repeat_byte(3),
+			},
+			leaf_extra: H256::repeat_byte(4),
+		},
+		mmr_proof: Proof { leaf_indices, leaf_count: 10, items: vec![H256::repeat_byte(5)] },
+		authority_proof: authority_proof.clone(),
+	};
+	let message = |leaf_indices| {
+		ClientMessage::Header(BeefyHeader {
+			headers_with_proof: None,
+			mmr_update_proof: Some(mmr_update_proof(leaf_indices)),
+		})
+	};
+
+	let decoded = ClientMessage::decode_vec(&message(vec![9]).encode_vec().unwrap()).unwrap();
+	assert_eq!(decoded, message(vec![9]));
+
+	assert!(message(vec![]).encode_vec().is_err());
+	assert!(message(vec![8, 9]).encode_vec().is_err());
+}
+
+#[test]
+fn test_relay_chain_is_backward_compatible() {
+	use crate::proto::ClientState as RawClientState;
+
+	// relayer configs written before the relay chain parameters existed only name the relay chain
+	let relay_chain: RelayChain = json::from_str("\"Rococo\"").unwrap();
+	assert_eq!(relay_chain, RelayChain::ROCOCO);
+	let relay_chain: RelayChain = json::from_str("\"kusama\"").unwrap();
+	assert_eq!(relay_chain, RelayChain::KUSAMA);
+	assert!(json::from_str::<RelayChain>("\"Relay42\"").is_err());
+
+	// custom relay chains round trip through their parameters
+	let local = RelayChain::new(42, Duration::from_secs(60 * 60), (1, 2));
+	let relay_chain: RelayChain = json::from_str(&json::to_string(&local).unwrap()).unwrap();
+	assert_eq!(relay_chain, local);
+
+	// invalid parameters are rejected
+	let overflowing = RelayChain::new(42, Duration::MAX, (2, 3));
+	assert!(overflowing.trusting_period().is_err());
+	assert!(json::from_str::<RelayChain>(&json::to_string(&overflowing).unwrap()).is_err());
+	let zero_unbonding = RelayChain::new(42, Duration::ZERO, (1, 2));
+	assert!(json::from_str::<RelayChain>(&json::to_string(&zero_unbonding).unwrap()).is_err());
+
+	// client states encoded before the relay chain parameters existed only hold the enum value
+	let mut raw = RawClientState::from(ClientState::<HostFunctionsManager>::default());
+	raw.relay_chain = RelayChain::POLKADOT.id as i32;
+	raw.relay_chain_params = None;
+	let client_state = ClientState::<HostFunctionsManager>::try_from(raw).unwrap();
+	assert_eq!(client_state.relay_chain, RelayChain::POLKADOT);
+	assert_eq!(client_state.chain_id().to_string(), "Polkadot-0");
+}
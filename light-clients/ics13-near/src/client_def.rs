@@ -22,28 +22,39 @@ use super::{
 };
 use crate::header::NearClientMessage;
 use borsh::BorshSerialize;
-use core::fmt::Debug;
+use core::{fmt::Debug, marker::PhantomData};
 use ibc::{
 	core::{
 		ics02_client::{
+			client_consensus::ConsensusState as _,
 			client_def::{ClientDef, ConsensusUpdateResult},
+			client_state::ClientState as _,
 			error::Error,
 		},
 		ics03_connection::connection::ConnectionEnd,
 		ics04_channel::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
-			packet::Sequence,
+			packet::{Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ChannelUpgradeErrorPath, ChannelUpgradePath,
+				ClientConsensusStatePath, ClientStatePath, CommitmentsPath, ConnectionsPath,
+				ReceiptsPath, SeqRecvsPath,
+			},
+			Path,
+		},
 		ics26_routing::context::ReaderContext,
 	},
 	prelude::*,
 	Height,
 };
 use ics23::HostFunctionsProvider;
-use std::marker::PhantomData;
+use tendermint_proto::Protobuf;
 
 pub trait HostFunctionsTrait:
 	HostFunctions + HostFunctionsProvider + Clone + Debug + PartialEq + Eq + Default + Send + Sync
@@ -139,8 +150,8 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 	// rehydrate client from its own storage, then call this function
 	fn verify_client_message<Ctx>(
 		&self,
-		_ctx: &Ctx,
-		_client_id: ClientId,
+		ctx: &Ctx,
+		client_id: ClientId,
 		client_state: Self::ClientState,
 		client_message: Self::ClientMessage,
 	) -> Result<(), Error>
@@ -149,9 +160,25 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 	{
 		match client_message {
 			NearClientMessage::Header(header) => {
-				// your light client, shouldn't do storage anymore, it should just do verification
-				// here.
-				validate_light_block::<H>(&header, client_state)
+				if header.height() > client_state.latest_height() {
+					// The head is only trusted within the trusting period of its consensus state.
+					let consensus_state =
+						ctx.consensus_state(&client_id, client_state.latest_height())?;
+					client_state
+						.verify_not_expired(consensus_state.timestamp(), ctx.host_timestamp())?;
+					return validate_light_block::<H>(&header, client_state)
+				}
+				// A block at or below the head is only accepted as evidence of misbehaviour: it
+				// must be approved by the block producers and conflict with the consensus state
+				// already installed at its height.
+				verify_light_block_approvals::<H>(
+					header.get_light_client_block_view(),
+					&client_state,
+				)?;
+				if !conflicts_with_consensus_state(ctx, &client_id, &header)? {
+					return Err(NearError::height_too_old().into())
+				}
+				Ok(())
 			},
 		}
 	}
@@ -160,38 +187,50 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		&self,
 		_ctx: &Ctx,
 		_client_id: ClientId,
-		_client_state: Self::ClientState,
-		_client_message: Self::ClientMessage,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		// 1. create new client state from this header, return that.
-		// 2. as well as all the neccessary consensus states.
-		//
-		//
-		// []--[]--[]--[]--[]--[]--[]--[]--[]--[]
-		// 11  12  13  14  15  16  17  18  19  20 <- block merkle root
-		// ^                                    ^
-		// |    <-------consensus states----->  |
-		// current state                       new state
-
-		todo!()
+		// The light block has already been validated against the block producers of its epoch,
+		// so it becomes the new head of the client. A block from the next epoch rotates the
+		// tracked block producers.
+		let header = match client_message {
+			NearClientMessage::Header(header) => header,
+		};
+		let block = header.get_light_client_block_view();
+		let consensus_state = ConsensusState::from_light_block(block);
+		let client_state = client_state.with_head(block.clone());
+
+		Ok((
+			client_state,
+			ConsensusUpdateResult::Single(
+				Ctx::AnyConsensusState::wrap(&consensus_state)
+					.expect("AnyConsensusState is type-checked; qed"),
+			),
+		))
 	}
 
 	fn update_state_on_misbehaviour(
 		&self,
-		_client_state: Self::ClientState,
-		_client_message: Self::ClientMessage,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error> {
-		todo!()
+		let height = match client_message {
+			NearClientMessage::Header(header) => header.height(),
+		};
+		Ok(client_state.with_frozen_height(height))
 	}
 
 	fn check_for_misbehaviour<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
-		_client_id: ClientId,
+		ctx: &Ctx,
+		client_id: ClientId,
 		_client_state: Self::ClientState,
-		_client_message: Self::ClientMessage,
+		client_message: Self::ClientMessage,
 	) -> Result<bool, Error> {
-		Ok(false)
+		let header = match client_message {
+			NearClientMessage::Header(header) => header,
+		};
+		conflicts_with_consensus_state(ctx, &client_id, &header)
 	}
 
 	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
@@ -204,33 +243,60 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		_proof_upgrade_client: Vec<u8>,
 		_proof_upgrade_consensus_state: Vec<u8>,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		todo!()
+		Err(NearError::upgrade_not_supported().into())
 	}
 
 	fn check_substitute_and_update_state<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_subject_client_id: ClientId,
-		_substitute_client_id: ClientId,
-		_old_client_state: Self::ClientState,
-		_substitute_client_state: Self::ClientState,
+		substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		todo!()
+		if old_client_state.chain_id() != substitute_client_state.chain_id() {
+			return Err(NearError::mismatched_substitute_client_state().into())
+		}
+
+		let height = substitute_client_state.latest_height();
+		let consensus_state = ctx
+			.consensus_state(&substitute_client_id, height)?
+			.downcast::<Self::ConsensusState>()
+			.ok_or_else(
+				|| Error::client_args_type_mismatch(NearClientState::<()>::client_type()),
+			)?;
+
+		let client_state = substitute_client_state.unfrozen();
+
+		Ok((
+			client_state,
+			ConsensusUpdateResult::Single(
+				Ctx::AnyConsensusState::wrap(&consensus_state)
+					.expect("AnyConsensusState is type-checked; qed"),
+			),
+		))
 	}
 
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_client_id: &ClientId,
-		_consensus_height: Height,
-		_expected_consensus_state: &Ctx::AnyConsensusState,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		client_id: &ClientId,
+		consensus_height: Height,
+		expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ClientConsensusStatePath {
+			client_id: client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		};
+		let value = expected_consensus_state.encode_to_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(prefix, proof, root, path, value)
 	}
 
 	// Consensus state will be verified in the verification functions  before these are called
@@ -238,112 +304,339 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		&self,
 		_ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_connection_id: &ConnectionId,
-		_expected_connection_end: &ConnectionEnd,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		connection_id: &ConnectionId,
+		expected_connection_end: &ConnectionEnd,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ConnectionsPath(connection_id.clone());
+		let value = expected_connection_end.encode_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(prefix, proof, root, path, value)
 	}
 
 	fn verify_channel_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_expected_channel_end: &ChannelEnd,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		expected_channel_end: &ChannelEnd,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ChannelEndsPath(port_id.clone(), *channel_id);
+		let value = expected_channel_end.encode_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(prefix, proof, root, path, value)
 	}
 
-	fn verify_client_full_state<Ctx: ReaderContext>(
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
 		_client_id: &ClientId,
-		_expected_client_state: &Ctx::AnyClientState,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ChannelUpgradePath(port_id.clone(), *channel_id);
+		let value = upgrade.encode_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(prefix, proof, root, path, value)
 	}
 
-	fn verify_packet_data<Ctx: ReaderContext>(
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
-		_commitment: PacketCommitment,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ChannelUpgradeErrorPath(port_id.clone(), *channel_id);
+		let value = error_receipt.encode_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(prefix, proof, root, path, value)
 	}
 
-	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		client_id: &ClientId,
+		expected_client_state: &Ctx::AnyClientState,
+	) -> Result<(), Error> {
+		client_state.verify_height(height)?;
+		let path = ClientStatePath(client_id.clone());
+		let value = expected_client_state.encode_to_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(prefix, proof, root, path, value)
+	}
+
+	fn verify_packet_data<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		commitment: PacketCommitment,
+	) -> Result<(), Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let commitment_path =
+			CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			commitment_path,
+			commitment.into_vec(),
+		)
+	}
+
+	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
-		_ack: AcknowledgementCommitment,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack: AcknowledgementCommitment,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let ack_path = AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			ack_path,
+			ack.into_vec(),
+		)
 	}
 
 	fn verify_next_sequence_recv<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let seq_bytes = codec::Encode::encode(&u64::from(sequence));
+		let seq_path = SeqRecvsPath(port_id.clone(), *channel_id);
+		verify_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			seq_path,
+			seq_bytes,
+		)
 	}
 
 	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_non_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			receipt_path,
+		)
 	}
+
+	fn verify_packet_timeout_receipt<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			receipt_path,
+			Receipt::Timeout.as_bytes().to_vec(),
+		)
+	}
+}
+
+/// Membership proof verification of `value` at the prefixed `path`, using the trie proof host
+/// function against the state root of the consensus state.
+pub fn verify_membership<H, P>(
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+	value: Vec<u8>,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let (root, trie_proof, key) = decode_state_proof(prefix, proof, root, path)?;
+	H::verify_membership_trie_proof(&root, &trie_proof, &key, &value)
+}
+
+/// Non-membership proof verification of the prefixed `path`, using the trie proof host
+/// function against the state root of the consensus state.
+pub fn verify_non_membership<H, P>(
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let (root, trie_proof, key) = decode_state_proof(prefix, proof, root, path)?;
+	H::verify_non_membership_trie_proof(&root, &trie_proof, &key)
+}
+
+/// Returns the state root, the scale-encoded proof nodes and the storage key of `path`.
+fn decode_state_proof<P: Into<Path>>(
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+) -> Result<([u8; 32], Vec<Vec<u8>>, Vec<u8>), Error> {
+	let root: [u8; 32] = root
+		.as_bytes()
+		.try_into()
+		.map_err(|_| Error::from(NearError::invalid_commitment_root(root.as_bytes().len())))?;
+	let path: Path = path.into();
+	let mut key = prefix.as_bytes().to_vec();
+	key.extend(path.to_string().as_bytes());
+	let trie_proof: Vec<Vec<u8>> = codec::Decode::decode(&mut proof.as_bytes()).map_err(|e| {
+		Error::from(NearError::invalid_state_proof(format!(
+			"failed to decode proof nodes for path {path}: {e}"
+		)))
+	})?;
+	Ok((root, trie_proof, key))
+}
+
+fn verify_delay_passed<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	height: Height,
+	connection_end: &ConnectionEnd,
+) -> Result<(), Error> {
+	let current_time = ctx.host_timestamp();
+	let current_height = ctx.host_height();
+
+	let client_id = connection_end.client_id();
+	let processed_time = ctx
+		.client_update_time(client_id, height)
+		.map_err(|_| Error::from(NearError::processed_time_not_found(height)))?;
+	let processed_height = ctx
+		.client_update_height(client_id, height)
+		.map_err(|_| Error::from(NearError::processed_height_not_found(height)))?;
+
+	let delay_period_time = connection_end.delay_period();
+	let delay_period_blocks = ctx.block_delay(delay_period_time);
+
+	let earliest_time = (processed_time + delay_period_time)
+		.map_err(|e| Error::from(NearError::timestamp_overflow(e)))?;
+	if !(current_time == earliest_time || current_time.after(&earliest_time)) {
+		return Err(NearError::not_enough_time_elapsed(
+			current_time.to_string(),
+			earliest_time.to_string(),
+		)
+		.into())
+	}
+
+	let earliest_height = processed_height.add(delay_period_blocks);
+	if current_height < earliest_height {
+		return Err(NearError::not_enough_blocks_elapsed(current_height, earliest_height).into())
+	}
+
+	Ok(())
+}
+
+/// A valid light block whose consensus state differs from the one already installed at the
+/// same height means the block producers have signed two different blocks.
+fn conflicts_with_consensus_state<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	header: &NearHeader,
+) -> Result<bool, Error> {
+	let header_consensus_state =
+		ConsensusState::from_light_block(header.get_light_client_block_view());
+	let existing_consensus_state = match ctx.maybe_consensus_state(client_id, header.height())? {
+		Some(cs) => cs.downcast::<ConsensusState>().ok_or_else(|| {
+			Error::client_args_type_mismatch(NearClientState::<()>::client_type())
+		})?,
+		None => return Ok(false),
+	};
+
+	Ok(existing_consensus_state != header_consensus_state)
 }
 
 /// validates a light block that's contained on the `NearHeader` based on the current
@@ -369,15 +662,25 @@ where
 	// it's not on the spec, but it's an extra validation
 
 	let new_block_view = header.get_light_client_block_view();
-	let current_block_view = client_state.get_head();
-	let (_current_block_hash, _next_block_hash, approval_message) =
-		reconstruct_light_client_block_view_fields::<H>(new_block_view)?;
 
 	// (1)
-	if new_block_view.inner_lite.height <= current_block_view.inner_lite.height {
+	if new_block_view.inner_lite.height <= client_state.get_head().inner_lite.height {
 		return Err(NearError::height_too_old().into())
 	}
 
+	verify_light_block_approvals::<H>(new_block_view, &client_state)
+}
+
+/// Checks (2) to (6) of [`validate_light_block`]: the block belongs to an epoch known to the
+/// client and is approved by more than 2/3 of the stake of its block producers.
+pub fn verify_light_block_approvals<H: HostFunctionsTrait>(
+	new_block_view: &LightClientBlockView,
+	client_state: &NearClientState<H>,
+) -> Result<(), Error> {
+	let current_block_view = client_state.get_head();
+	let (_current_block_hash, _next_block_hash, approval_message) =
+		reconstruct_light_client_block_view_fields::<H>(new_block_view)?;
+
 	// (2)
 	if ![current_block_view.inner_lite.epoch_id, current_block_view.inner_lite.next_epoch_id]
 		.contains(&new_block_view.inner_lite.epoch_id)
@@ -407,22 +710,25 @@ where
 		let bp_stake = bp_stake_view.stake;
 		total_stake += bp_stake;
 
-		if maybe_signature.is_none() {
-			continue
-		}
+		let signature = match maybe_signature {
+			Some(signature) => signature,
+			None => continue,
+		};
 
 		approved_stake += bp_stake;
 
 		let validator_public_key = &bp_stake_view.public_key;
-		let data = H::sha256_digest(&approval_message);
-		let signature = maybe_signature.as_ref().unwrap();
-		if H::ed25519_verify(signature.get_inner(), &data, validator_public_key.get_inner()) {
+		if !H::ed25519_verify(
+			signature.get_inner(),
+			&approval_message,
+			validator_public_key.get_inner(),
+		) {
 			return Err(NearError::invalid_signature().into())
 		}
 	}
 
-	let threshold = total_stake * 2 / 3;
-	if approved_stake <= threshold {
+	// approved_stake > 2/3 * total_stake, without the rounding of the integer division
+	if approved_stake * 3 <= total_stake * 2 {
 		return Err(NearError::insufficient_staked_amount().into())
 	}
 
@@ -437,7 +743,7 @@ where
 		if H::sha256_digest(new_block_view_next_bps_serialized.as_ref()).as_slice() !=
 			new_block_view.inner_lite.next_bp_hash.as_ref()
 		{
-			return Err(NearError::serialization_error().into())
+			return Err(NearError::invalid_next_block_producers_hash().into())
 		}
 	}
 	Ok(())
@@ -14,19 +14,41 @@
 // limitations under the License.
 
 use super::types::{CryptoHash, LightClientBlockView, ValidatorStakeView};
-use crate::client_def::{HostFunctionsTrait, NearClient};
+use crate::{
+	client_def::{HostFunctionsTrait, NearClient},
+	error::Error,
+	proto::ClientState as RawClientState,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::{marker::PhantomData, time::Duration};
 use ibc::{
 	core::{
 		ics02_client::client_state::{ClientState, ClientType},
 		ics24_host::identifier::ChainId,
 	},
 	prelude::*,
+	timestamp::Timestamp,
 	Height,
 };
+use ibc_proto::google::protobuf::Any;
 use serde::{Deserialize, Serialize};
-use std::{marker::PhantomData, time::Duration};
 use tendermint_proto::Protobuf;
 
+pub const NEAR_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.near.v1.ClientState";
+
+/// Number of epochs after which block producers can withdraw their unstaked tokens, nearcore's
+/// `NUM_EPOCHS_TO_UNLOCK`.
+pub const NUM_EPOCHS_TO_UNLOCK: u32 = 4;
+
+/// Returns the trusting period of a client of a chain whose epochs last `epoch_duration`.
+///
+/// Block producers may withdraw their stake [`NUM_EPOCHS_TO_UNLOCK`] epochs after unstaking it,
+/// after which their approvals can't be slashed anymore. Like tendermint clients, the client only
+/// trusts its consensus states for 2/3 of that unbonding period.
+pub fn trusting_period(epoch_duration: Duration) -> Duration {
+	epoch_duration.saturating_mul(2 * NUM_EPOCHS_TO_UNLOCK) / 3
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NearClientState<H> {
 	chain_id: ChainId,
@@ -35,6 +57,8 @@ pub struct NearClientState<H> {
 	next_epoch: CryptoHash,
 	current_validators: Vec<ValidatorStakeView>,
 	next_validators: Vec<ValidatorStakeView>,
+	trusting_period: Duration,
+	frozen_height: Option<Height>,
 	_phantom: PhantomData<H>,
 }
 
@@ -42,6 +66,83 @@ pub struct NearClientState<H> {
 pub struct NearUpgradeOptions {}
 
 impl<H: HostFunctionsTrait> NearClientState<H> {
+	/// Creates a client state that trusts `head`, with `current_validators` being the block
+	/// producers of the head's epoch and `next_validators` those of the following epoch. The
+	/// consensus states of the client expire after `trusting_period`, see [`trusting_period`].
+	pub fn new(
+		chain_id: ChainId,
+		head: LightClientBlockView,
+		current_validators: Vec<ValidatorStakeView>,
+		next_validators: Vec<ValidatorStakeView>,
+		trusting_period: Duration,
+	) -> Self {
+		Self {
+			chain_id,
+			current_epoch: head.inner_lite.epoch_id,
+			next_epoch: head.inner_lite.next_epoch_id,
+			head,
+			current_validators,
+			next_validators,
+			trusting_period,
+			frozen_height: None,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Returns the client state that tracks `block` as its new head.
+	///
+	/// When `block` belongs to the next epoch, the next block producers become the current ones.
+	/// If the block carries `next_bps`, these are the block producers of its `next_epoch_id`.
+	/// The block must have been validated with [`crate::client_def::validate_light_block`].
+	pub fn with_head(&self, block: LightClientBlockView) -> Self {
+		let mut client_state = self.clone();
+		if block.inner_lite.epoch_id == self.next_epoch {
+			client_state.current_epoch = self.next_epoch;
+			client_state.current_validators = self.next_validators.clone();
+		}
+		if let Some(next_bps) = block.next_bps.clone() {
+			client_state.next_epoch = block.inner_lite.next_epoch_id;
+			client_state.next_validators = next_bps;
+		}
+		client_state.head = block;
+		client_state
+	}
+
+	pub fn with_frozen_height(self, frozen_height: Height) -> Self {
+		Self { frozen_height: Some(frozen_height), ..self }
+	}
+
+	pub fn unfrozen(self) -> Self {
+		Self { frozen_height: None, ..self }
+	}
+
+	/// Verify that the client is at a sufficient height and unfrozen at the given height
+	pub fn verify_height(&self, height: Height) -> Result<(), Error> {
+		let latest_height = self.head.get_height();
+		if latest_height < height {
+			return Err(Error::insufficient_height(latest_height, height))
+		}
+
+		match self.frozen_height {
+			Some(frozen_height) if frozen_height <= height =>
+				Err(Error::client_frozen(frozen_height, height)),
+			_ => Ok(()),
+		}
+	}
+
+	/// Verify that a consensus state with the given `timestamp` is still trusted at `now`.
+	pub fn verify_not_expired(&self, timestamp: Timestamp, now: Timestamp) -> Result<(), Error> {
+		match now.duration_since(&timestamp) {
+			Some(elapsed) if self.expired(elapsed) =>
+				Err(Error::consensus_state_expired(timestamp, now)),
+			_ => Ok(()),
+		}
+	}
+
+	pub fn trusting_period(&self) -> Duration {
+		self.trusting_period
+	}
+
 	pub fn get_validators_by_epoch(
 		&self,
 		epoch_id: &CryptoHash,
@@ -58,6 +159,13 @@ impl<H: HostFunctionsTrait> NearClientState<H> {
 	pub fn get_head(&self) -> &LightClientBlockView {
 		&self.head
 	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: NEAR_CLIENT_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ClientState"),
+		}
+	}
 }
 
 impl<H: HostFunctionsTrait> ClientState for NearClientState<H> {
@@ -81,8 +189,7 @@ impl<H: HostFunctionsTrait> ClientState for NearClientState<H> {
 	}
 
 	fn frozen_height(&self) -> Option<Height> {
-		// TODO: validate this
-		Some(self.head.get_height())
+		self.frozen_height
 	}
 
 	fn upgrade(
@@ -95,12 +202,12 @@ impl<H: HostFunctionsTrait> ClientState for NearClientState<H> {
 		self
 	}
 
-	fn expired(&self, _elapsed: Duration) -> bool {
-		todo!()
+	fn expired(&self, elapsed: Duration) -> bool {
+		elapsed > self.trusting_period
 	}
 
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		todo!("implement encoding")
+		self.encode_vec()
 	}
 }
 
@@ -110,16 +217,55 @@ impl<H> NearClientState<H> {
 	}
 }
 
-impl<H: HostFunctionsTrait> Protobuf<()> for NearClientState<H> {}
+impl<H: Clone> Protobuf<RawClientState> for NearClientState<H> {}
 
-impl<H: HostFunctionsTrait> From<NearClientState<H>> for () {
-	fn from(_: NearClientState<H>) -> Self {
-		todo!()
+impl<H> TryFrom<RawClientState> for NearClientState<H> {
+	type Error = Error;
+
+	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+		let decode_error = |field: &str, e: borsh::maybestd::io::Error| {
+			Error::invalid_raw_client_state(format!("invalid {field}: {e}"))
+		};
+		Ok(Self {
+			chain_id: ChainId::from_string(&raw.chain_id),
+			head: LightClientBlockView::try_from_slice(&raw.head)
+				.map_err(|e| decode_error("head", e))?,
+			current_epoch: CryptoHash::try_from_slice(&raw.current_epoch)
+				.map_err(|e| decode_error("current epoch", e))?,
+			next_epoch: CryptoHash::try_from_slice(&raw.next_epoch)
+				.map_err(|e| decode_error("next epoch", e))?,
+			current_validators: Vec::<ValidatorStakeView>::try_from_slice(&raw.current_validators)
+				.map_err(|e| decode_error("current validators", e))?,
+			next_validators: Vec::<ValidatorStakeView>::try_from_slice(&raw.next_validators)
+				.map_err(|e| decode_error("next validators", e))?,
+			trusting_period: raw
+				.trusting_period
+				.ok_or_else(|| Error::invalid_raw_client_state("missing trusting period".into()))?
+				.try_into()
+				.map_err(|_| Error::invalid_raw_client_state("negative trusting period".into()))?,
+			frozen_height: raw.frozen_height.map(Height::from),
+			_phantom: PhantomData,
+		})
 	}
 }
 
-impl<H: HostFunctionsTrait> From<()> for NearClientState<H> {
-	fn from(_: ()) -> Self {
-		todo!()
+impl<H> From<NearClientState<H>> for RawClientState {
+	fn from(value: NearClientState<H>) -> Self {
+		RawClientState {
+			chain_id: value.chain_id.to_string(),
+			head: value.head.try_to_vec().expect("borsh encoding to a vec is infallible; qed"),
+			current_epoch: value.current_epoch.0.to_vec(),
+			next_epoch: value.next_epoch.0.to_vec(),
+			current_validators: value
+				.current_validators
+				.try_to_vec()
+				.expect("borsh encoding to a vec is infallible; qed"),
+			next_validators: value
+				.next_validators
+				.try_to_vec()
+				.expect("borsh encoding to a vec is infallible; qed"),
+			trusting_period: Some(value.trusting_period.into()),
+			frozen_height: value.frozen_height.map(Into::into),
+		}
 	}
 }
@@ -13,20 +13,85 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::error::Error;
+use super::{
+	client_def::HostFunctions,
+	error::Error,
+	proto::ConsensusState as RawConsensusState,
+	types::{BlockHeaderInnerLiteView, CryptoHash, LightClientBlockLiteView, LightClientBlockView},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
 use ibc::{
 	core::{
 		ics02_client::client_consensus::{self},
 		ics23_commitment::commitment::CommitmentRoot,
 	},
+	prelude::*,
 	timestamp::Timestamp,
 };
+use ibc_proto::google::protobuf::Any;
 use serde::Serialize;
 use tendermint_proto::Protobuf;
 
+pub const NEAR_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.near.v1.ConsensusState";
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ConsensusState {
 	commitment_root: CommitmentRoot,
+	timestamp: Timestamp,
+}
+
+impl ConsensusState {
+	pub fn new(commitment_root: CommitmentRoot, timestamp: Timestamp) -> Self {
+		Self { commitment_root, timestamp }
+	}
+
+	/// Derives the consensus state of a light client block. The commitment root is the
+	/// `prev_state_root` of the block, i.e. the state root after the chunks of its parent
+	/// block have been applied, which is what state proofs queried at this block are
+	/// verified against.
+	pub fn from_light_block(block: &LightClientBlockView) -> Self {
+		Self::from_inner_lite(&block.inner_lite)
+	}
+
+	fn from_inner_lite(inner_lite: &BlockHeaderInnerLiteView) -> Self {
+		Self {
+			commitment_root: CommitmentRoot::from_bytes(inner_lite.prev_state_root.as_ref()),
+			timestamp: Timestamp::from_nanoseconds(inner_lite.timestamp)
+				.expect("timestamp is a valid u64 nanoseconds value; qed"),
+		}
+	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: NEAR_CONSENSUS_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ConsensusState"),
+		}
+	}
+}
+
+/// Proof of the consensus state of a NEAR block, submitted to the NEAR chain itself when the
+/// counterparty's client of it is checked during the connection handshake. The chain compares the
+/// hash of the block header with the hash of its own block at the same height.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct HostConsensusProof {
+	/// Header of the block the consensus state is derived from
+	pub block: LightClientBlockLiteView,
+	/// Checksum of the WASM client, if the counterparty wraps the NEAR client in a WASM one
+	pub checksum: Option<Vec<u8>>,
+}
+
+impl HostConsensusProof {
+	/// Returns the consensus state of the proven block, if its hash is the given hash of the host
+	/// block at the same height.
+	pub fn verify<H: HostFunctions>(
+		&self,
+		block_hash: &CryptoHash,
+	) -> Result<ConsensusState, Error> {
+		if &self.block.current_block_hash::<H>() != block_hash {
+			return Err(Error::invalid_host_consensus_proof(self.block.inner_lite.height))
+		}
+		Ok(ConsensusState::from_inner_lite(&self.block.inner_lite))
+	}
 }
 
 impl client_consensus::ConsensusState for ConsensusState {
@@ -37,24 +102,35 @@ impl client_consensus::ConsensusState for ConsensusState {
 	}
 
 	fn timestamp(&self) -> Timestamp {
-		todo!()
+		self.timestamp
 	}
 
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		todo!("Encode to vec")
+		self.encode_vec()
 	}
 }
 
-impl Protobuf<()> for ConsensusState {}
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+	type Error = Error;
+
+	fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+		if raw.commitment_root.is_empty() {
+			return Err(Error::invalid_raw_consensus_state("commitment root cannot be empty".into()))
+		}
+		let timestamp = Timestamp::from_nanoseconds(raw.timestamp)
+			.map_err(|e| Error::invalid_raw_consensus_state(format!("invalid timestamp: {e}")))?;
 
-impl From<ConsensusState> for () {
-	fn from(_: ConsensusState) -> Self {
-		todo!()
+		Ok(Self { commitment_root: raw.commitment_root.into(), timestamp })
 	}
 }
 
-impl From<()> for ConsensusState {
-	fn from(_: ()) -> Self {
-		todo!()
+impl From<ConsensusState> for RawConsensusState {
+	fn from(value: ConsensusState) -> Self {
+		RawConsensusState {
+			commitment_root: value.commitment_root.into_vec(),
+			timestamp: value.timestamp.nanoseconds(),
+		}
 	}
 }
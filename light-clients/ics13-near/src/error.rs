@@ -16,7 +16,12 @@
 use super::types::CryptoHash;
 use crate::client_state::NearClientState;
 use flex_error::define_error;
-use ibc::core::ics02_client::error::Error as Ics02Error;
+use ibc::{
+	core::ics02_client::error::Error as Ics02Error,
+	prelude::*,
+	timestamp::{Timestamp, TimestampOverflowError},
+	Height,
+};
 
 define_error! {
 	#[derive(Debug, PartialEq, Eq)]
@@ -44,6 +49,101 @@ define_error! {
 		| _ | { format_args!(
 			"unavailable block producers")
 		},
+		InvalidNextBlockProducersHash
+		| _ | { format_args!(
+			"next block producers do not match the next_bp_hash of the block")
+		},
+		InvalidCommitmentRoot
+		{ len: usize }
+		| e | { format_args!(
+			"invalid commitment root length: {}", e.len)
+		},
+		InvalidStateProof
+		{ reason: String }
+		| e | { format_args!(
+			"invalid state proof: {}", e.reason)
+		},
+		InsufficientHeight
+		{
+			latest_height: Height,
+			target_height: Height,
+		}
+		| e | { format_args!(
+			"the height is insufficient: latest_height={0} target_height={1}", e.latest_height, e.target_height)
+		},
+		ClientFrozen
+		{
+			frozen_height: Height,
+			target_height: Height,
+		}
+		| e | { format_args!(
+			"the client is frozen: frozen_height={0} target_height={1}", e.frozen_height, e.target_height)
+		},
+		ProcessedTimeNotFound
+		{ height: Height }
+		| e | { format_args!(
+			"processed time for the client at height {0} not found", e.height)
+		},
+		ProcessedHeightNotFound
+		{ height: Height }
+		| e | { format_args!(
+			"processed height for the client at height {0} not found", e.height)
+		},
+		TimestampOverflow
+		[ TimestampOverflowError ]
+		| _ | { "timestamp overflowed" },
+		NotEnoughTimeElapsed
+		{
+			current_time: String,
+			earliest_time: String,
+		}
+		| e | { format_args!(
+			"not enough time elapsed, current timestamp {0} is still less than earliest acceptable timestamp {1}", e.current_time, e.earliest_time)
+		},
+		NotEnoughBlocksElapsed
+		{
+			current_height: Height,
+			earliest_height: Height,
+		}
+		| e | { format_args!(
+			"not enough blocks elapsed, current height {0} is still less than earliest acceptable height {1}", e.current_height, e.earliest_height)
+		},
+		MismatchedSubstituteClientState
+		| _ | { format_args!(
+			"subject client state does not match substitute client state")
+		},
+		UpgradeNotSupported
+		| _ | { format_args!(
+			"NEAR light client does not support upgrades")
+		},
+		InvalidRawClientState
+		{ reason: String }
+		| e | { format_args!(
+			"invalid raw client state: {}", e.reason)
+		},
+		InvalidRawConsensusState
+		{ reason: String }
+		| e | { format_args!(
+			"invalid raw consensus state: {}", e.reason)
+		},
+		InvalidRawHeader
+		{ reason: String }
+		| e | { format_args!(
+			"invalid raw header: {}", e.reason)
+		},
+		ConsensusStateExpired
+		{
+			timestamp: Timestamp,
+			now: Timestamp,
+		}
+		| e | { format_args!(
+			"the consensus state at {0} is outside of the trusting period at {1}", e.timestamp, e.now)
+		},
+		InvalidHostConsensusProof
+		{ height: u64 }
+		| e | { format_args!(
+			"host consensus proof does not match the block at height {}", e.height)
+		},
 	}
 }
 
@@ -13,10 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use ibc::{core::ics02_client::client_message::ClientMessage, Height};
+use borsh::{BorshDeserialize, BorshSerialize};
+use ibc::{core::ics02_client::client_message::ClientMessage, prelude::*, Height};
+use ibc_proto::google::protobuf::Any;
 use tendermint_proto::Protobuf;
 
-use super::types::LightClientBlockView;
+use super::{error::Error, proto::Header as RawHeader, types::LightClientBlockView};
+
+pub const NEAR_HEADER_TYPE_URL: &str = "/ibc.lightclients.near.v1.Header";
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct NearHeader {
@@ -24,30 +28,43 @@ pub struct NearHeader {
 }
 
 impl NearHeader {
+	pub fn new(inner: LightClientBlockView) -> Self {
+		Self { inner }
+	}
+
 	pub fn get_light_client_block_view(&self) -> &LightClientBlockView {
 		&self.inner
 	}
 
 	pub fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		unimplemented!()
+		self.encode_vec()
 	}
 
 	pub fn height(&self) -> Height {
-		todo!()
+		self.inner.get_height()
 	}
 }
 
-impl Protobuf<()> for NearHeader {}
+impl Protobuf<RawHeader> for NearHeader {}
+
+impl TryFrom<RawHeader> for NearHeader {
+	type Error = Error;
 
-impl From<NearHeader> for () {
-	fn from(_: NearHeader) -> Self {
-		todo!()
+	fn try_from(raw: RawHeader) -> Result<Self, Self::Error> {
+		let inner = LightClientBlockView::try_from_slice(&raw.light_client_block)
+			.map_err(|e| Error::invalid_raw_header(format!("invalid light client block: {e}")))?;
+		Ok(Self { inner })
 	}
 }
 
-impl From<()> for NearHeader {
-	fn from(_: ()) -> Self {
-		todo!()
+impl From<NearHeader> for RawHeader {
+	fn from(value: NearHeader) -> Self {
+		RawHeader {
+			light_client_block: value
+				.inner
+				.try_to_vec()
+				.expect("borsh encoding to a vec is infallible; qed"),
+		}
 	}
 }
 
@@ -58,6 +75,35 @@ pub enum NearClientMessage {
 
 impl ClientMessage for NearClientMessage {
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		todo!()
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<Any> for NearClientMessage {}
+
+impl TryFrom<Any> for NearClientMessage {
+	type Error = Error;
+
+	fn try_from(any: Any) -> Result<Self, Self::Error> {
+		let msg = match &*any.type_url {
+			NEAR_HEADER_TYPE_URL => Self::Header(
+				NearHeader::decode_vec(&any.value)
+					.map_err(|e| Error::invalid_raw_header(format!("{e:?}")))?,
+			),
+			_ => Err(Error::invalid_raw_header(format!("Unknown type: {}", any.type_url)))?,
+		};
+
+		Ok(msg)
+	}
+}
+
+impl From<NearClientMessage> for Any {
+	fn from(msg: NearClientMessage) -> Self {
+		match msg {
+			NearClientMessage::Header(header) => Any {
+				value: header.encode_vec().expect("failed to encode NearClientMessage.header"),
+				type_url: NEAR_HEADER_TYPE_URL.to_string(),
+			},
+		}
 	}
 }
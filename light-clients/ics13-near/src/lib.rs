@@ -13,6 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::all)]
 
 extern crate alloc;
@@ -22,4 +23,11 @@ pub mod client_state;
 pub mod consensus_state;
 pub mod error;
 pub mod header;
+pub mod proto;
 pub mod types;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
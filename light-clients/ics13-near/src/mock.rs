@@ -0,0 +1,466 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Host functions and fixture loaders for testing the NEAR light client.
+
+use crate::{
+	client_def::{HostFunctions, HostFunctionsTrait},
+	types::{
+		BlockHeaderInnerLiteView, CryptoHash, LightClientBlockView, PublicKey, Signature,
+		ValidatorStakeView, ValidatorStakeViewV1,
+	},
+};
+use ibc::core::ics02_client::error::Error;
+use serde_json::Value;
+use sha2::Digest;
+use sp_core::{ed25519, Pair};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Default, PartialEq, Debug, Eq)]
+pub struct HostFunctionsManager;
+
+impl HostFunctions for HostFunctionsManager {
+	fn keccak_256(input: &[u8]) -> [u8; 32] {
+		sp_core::keccak_256(input)
+	}
+
+	fn secp256k1_ecdsa_recover_compressed(
+		_signature: &[u8; 65],
+		_value: &[u8; 32],
+	) -> Option<Vec<u8>> {
+		unimplemented!()
+	}
+
+	fn ed25519_verify(signature: &[u8; 64], msg: &[u8], pubkey: &[u8]) -> bool {
+		let pubkey = match <[u8; 32]>::try_from(pubkey) {
+			Ok(pubkey) => ed25519::Public::from_raw(pubkey),
+			Err(_) => return false,
+		};
+		ed25519::Pair::verify(&ed25519::Signature::from_raw(*signature), msg, &pubkey)
+	}
+
+	fn verify_membership_trie_proof(
+		root: &[u8; 32],
+		proof: &[Vec<u8>],
+		key: &[u8],
+		value: &[u8],
+	) -> Result<(), Error> {
+		match lookup(root, proof, key)? {
+			Some(value_ref) if value_ref == ValueRef::of(value) => Ok(()),
+			_ => Err(Error::implementation_specific(format!(
+				"key {} is not a member of state root {root:?}",
+				String::from_utf8_lossy(key),
+			))),
+		}
+	}
+
+	fn verify_non_membership_trie_proof(
+		root: &[u8; 32],
+		proof: &[Vec<u8>],
+		key: &[u8],
+	) -> Result<(), Error> {
+		match lookup(root, proof, key)? {
+			None => Ok(()),
+			Some(_) => Err(Error::implementation_specific(format!(
+				"key {} is a member of state root {root:?}",
+				String::from_utf8_lossy(key),
+			))),
+		}
+	}
+
+	fn verify_timestamp_extrinsic(
+		_root: &[u8; 32],
+		_proof: &[Vec<u8>],
+		_value: &[u8],
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
+	fn sha256_digest(data: &[u8]) -> [u8; 32] {
+		sha2::Sha256::digest(data).into()
+	}
+
+	fn sha2_256(message: &[u8]) -> [u8; 32] {
+		sha2::Sha256::digest(message).into()
+	}
+
+	fn sha2_512(message: &[u8]) -> [u8; 64] {
+		let mut hash = [0u8; 64];
+		hash.copy_from_slice(&sha2::Sha512::digest(message));
+		hash
+	}
+
+	fn sha2_512_truncated(message: &[u8]) -> [u8; 32] {
+		let mut hash = [0u8; 32];
+		hash.copy_from_slice(&sha2::Sha512::digest(message)[..32]);
+		hash
+	}
+
+	fn sha3_512(message: &[u8]) -> [u8; 64] {
+		let mut hash = [0u8; 64];
+		hash.copy_from_slice(&sha3::Sha3_512::digest(message));
+		hash
+	}
+
+	fn ripemd160(message: &[u8]) -> [u8; 20] {
+		let mut hash = [0u8; 20];
+		hash.copy_from_slice(&ripemd::Ripemd160::digest(message));
+		hash
+	}
+}
+
+impl ics23::HostFunctionsProvider for HostFunctionsManager {
+	fn sha2_256(message: &[u8]) -> [u8; 32] {
+		<Self as HostFunctions>::sha2_256(message)
+	}
+
+	fn sha2_512(message: &[u8]) -> [u8; 64] {
+		<Self as HostFunctions>::sha2_512(message)
+	}
+
+	fn sha2_512_truncated(message: &[u8]) -> [u8; 32] {
+		<Self as HostFunctions>::sha2_512_truncated(message)
+	}
+
+	fn sha3_512(message: &[u8]) -> [u8; 64] {
+		<Self as HostFunctions>::sha3_512(message)
+	}
+
+	fn ripemd160(message: &[u8]) -> [u8; 20] {
+		<Self as HostFunctions>::ripemd160(message)
+	}
+}
+
+impl HostFunctionsTrait for HostFunctionsManager {}
+
+/// Reference to a value stored in the NEAR state trie.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct ValueRef {
+	length: u32,
+	hash: [u8; 32],
+}
+
+impl ValueRef {
+	fn of(value: &[u8]) -> Self {
+		Self { length: value.len() as u32, hash: sha2::Sha256::digest(value).into() }
+	}
+}
+
+/// A node of the NEAR state trie, see `RawTrieNode` in nearcore.
+enum TrieNode {
+	Leaf(Vec<u8>, ValueRef),
+	Branch(Option<ValueRef>, [Option<[u8; 32]>; 16]),
+	Extension(Vec<u8>, [u8; 32]),
+}
+
+impl TrieNode {
+	/// Borsh encoding of `RawTrieNodeWithSize`, whose sha256 hash is the hash of the node.
+	fn encode(&self) -> Vec<u8> {
+		let mut out = vec![];
+		match self {
+			TrieNode::Leaf(nibbles, value) => {
+				out.push(0);
+				encode_bytes(&mut out, &encode_nibbles(nibbles, true));
+				encode_value_ref(&mut out, value);
+			},
+			TrieNode::Branch(None, children) => {
+				out.push(1);
+				encode_children(&mut out, children);
+			},
+			TrieNode::Branch(Some(value), children) => {
+				out.push(2);
+				encode_value_ref(&mut out, value);
+				encode_children(&mut out, children);
+			},
+			TrieNode::Extension(nibbles, child) => {
+				out.push(3);
+				encode_bytes(&mut out, &encode_nibbles(nibbles, false));
+				out.extend_from_slice(child);
+			},
+		}
+		// memory usage of the subtree, not needed to verify proofs
+		out.extend_from_slice(&0u64.to_le_bytes());
+		out
+	}
+
+	fn decode(mut input: &[u8]) -> Option<Self> {
+		let node = match take(&mut input, 1)?[0] {
+			0 => {
+				let (nibbles, is_leaf) = decode_nibbles(&decode_bytes(&mut input)?)?;
+				if !is_leaf {
+					return None
+				}
+				TrieNode::Leaf(nibbles, decode_value_ref(&mut input)?)
+			},
+			1 => TrieNode::Branch(None, decode_children(&mut input)?),
+			2 => {
+				let value = decode_value_ref(&mut input)?;
+				TrieNode::Branch(Some(value), decode_children(&mut input)?)
+			},
+			3 => {
+				let (nibbles, is_leaf) = decode_nibbles(&decode_bytes(&mut input)?)?;
+				if is_leaf {
+					return None
+				}
+				TrieNode::Extension(nibbles, decode_hash(&mut input)?)
+			},
+			_ => return None,
+		};
+		take(&mut input, 8)?;
+		input.is_empty().then_some(node)
+	}
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+	if input.len() < len {
+		return None
+	}
+	let (head, tail) = input.split_at(len);
+	*input = tail;
+	Some(head)
+}
+
+fn encode_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+	out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+	out.extend_from_slice(bytes);
+}
+
+fn decode_bytes(input: &mut &[u8]) -> Option<Vec<u8>> {
+	let len = u32::from_le_bytes(take(input, 4)?.try_into().ok()?);
+	Some(take(input, len as usize)?.to_vec())
+}
+
+fn decode_hash(input: &mut &[u8]) -> Option<[u8; 32]> {
+	take(input, 32)?.try_into().ok()
+}
+
+fn encode_value_ref(out: &mut Vec<u8>, value: &ValueRef) {
+	out.extend_from_slice(&value.length.to_le_bytes());
+	out.extend_from_slice(&value.hash);
+}
+
+fn decode_value_ref(input: &mut &[u8]) -> Option<ValueRef> {
+	let length = u32::from_le_bytes(take(input, 4)?.try_into().ok()?);
+	Some(ValueRef { length, hash: decode_hash(input)? })
+}
+
+/// Children are encoded as a bitmap of the present children followed by their hashes.
+fn encode_children(out: &mut Vec<u8>, children: &[Option<[u8; 32]>; 16]) {
+	let bitmap = children
+		.iter()
+		.enumerate()
+		.fold(0u16, |bitmap, (i, child)| bitmap | ((child.is_some() as u16) << i));
+	out.extend_from_slice(&bitmap.to_le_bytes());
+	children.iter().flatten().for_each(|child| out.extend_from_slice(child));
+}
+
+fn decode_children(input: &mut &[u8]) -> Option<[Option<[u8; 32]>; 16]> {
+	let bitmap = u16::from_le_bytes(take(input, 2)?.try_into().ok()?);
+	let mut children = [None; 16];
+	for (i, child) in children.iter_mut().enumerate() {
+		if bitmap & (1 << i) != 0 {
+			*child = Some(decode_hash(input)?);
+		}
+	}
+	Some(children)
+}
+
+/// Hex-prefix encoding of a nibble path, as done by `NibbleSlice::encoded` in nearcore.
+fn encode_nibbles(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+	let odd = nibbles.len() % 2 == 1;
+	let first = (if odd { 0x10 + nibbles[0] } else { 0 }) + (if is_leaf { 0x20 } else { 0 });
+	let rest = if odd { &nibbles[1..] } else { nibbles };
+	[vec![first], rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]).collect()].concat()
+}
+
+fn decode_nibbles(encoded: &[u8]) -> Option<(Vec<u8>, bool)> {
+	let (first, rest) = encoded.split_first()?;
+	let mut nibbles = if first & 0x10 != 0 { vec![first & 0x0f] } else { vec![] };
+	nibbles.extend(to_nibbles(rest));
+	Some((nibbles, first & 0x20 != 0))
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+	bytes.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+/// Walks the nodes of a NEAR state proof from `root` along `key`. Returns the reference to the
+/// value stored under `key`, or `None` if the proof shows that the key is absent.
+fn lookup(root: &[u8; 32], proof: &[Vec<u8>], key: &[u8]) -> Result<Option<ValueRef>, Error> {
+	let invalid_proof = |reason: &str| {
+		Error::implementation_specific(format!(
+			"invalid proof for key {}: {reason}",
+			String::from_utf8_lossy(key)
+		))
+	};
+	let nodes = proof
+		.iter()
+		.map(|node| (<[u8; 32]>::from(sha2::Sha256::digest(node)), node.as_slice()))
+		.collect::<BTreeMap<_, _>>();
+	let nibbles = to_nibbles(key);
+	let mut remaining = nibbles.as_slice();
+	let mut hash = *root;
+	loop {
+		// the root of an empty trie is the default hash
+		if hash == [0u8; 32] {
+			return Ok(None)
+		}
+		let node = nodes.get(&hash).ok_or_else(|| invalid_proof("missing trie node"))?;
+		match TrieNode::decode(node).ok_or_else(|| invalid_proof("malformed trie node"))? {
+			TrieNode::Leaf(path, value) => return Ok((remaining == path).then_some(value)),
+			TrieNode::Extension(path, child) => {
+				if !remaining.starts_with(&path) {
+					return Ok(None)
+				}
+				remaining = &remaining[path.len()..];
+				hash = child;
+			},
+			TrieNode::Branch(value, children) => {
+				let (index, rest) = match remaining.split_first() {
+					Some(split) => split,
+					None => return Ok(value),
+				};
+				match children[*index as usize] {
+					Some(child) => {
+						remaining = rest;
+						hash = child;
+					},
+					None => return Ok(None),
+				}
+			},
+		}
+	}
+}
+
+/// Builds the NEAR state trie holding `entries`, returns its root and all of its nodes, which
+/// form a proof for any of its keys.
+pub fn build_trie(entries: &[(Vec<u8>, Vec<u8>)]) -> ([u8; 32], Vec<Vec<u8>>) {
+	let entries = entries
+		.iter()
+		.map(|(key, value)| (to_nibbles(key), value.as_slice()))
+		.collect::<Vec<_>>();
+	let mut nodes = vec![];
+	let root = build_node(&entries, &mut nodes);
+	(root, nodes)
+}
+
+fn build_node(entries: &[(Vec<u8>, &[u8])], nodes: &mut Vec<Vec<u8>>) -> [u8; 32] {
+	let node = match entries {
+		[(nibbles, value)] => TrieNode::Leaf(nibbles.clone(), ValueRef::of(value)),
+		_ => {
+			let prefix_len = (0..)
+				.take_while(|&i| {
+					entries.iter().all(|(nibbles, _)| {
+						nibbles.get(i).is_some() && nibbles.get(i) == entries[0].0.get(i)
+					})
+				})
+				.count();
+			if prefix_len > 0 {
+				let stripped = entries
+					.iter()
+					.map(|(nibbles, value)| (nibbles[prefix_len..].to_vec(), *value))
+					.collect::<Vec<_>>();
+				TrieNode::Extension(
+					entries[0].0[..prefix_len].to_vec(),
+					build_node(&stripped, nodes),
+				)
+			} else {
+				let value = entries
+					.iter()
+					.find(|(nibbles, _)| nibbles.is_empty())
+					.map(|(_, value)| ValueRef::of(value));
+				let mut children = [None; 16];
+				for (index, child) in children.iter_mut().enumerate() {
+					let stripped = entries
+						.iter()
+						.filter(|(nibbles, _)| nibbles.first() == Some(&(index as u8)))
+						.map(|(nibbles, value)| (nibbles[1..].to_vec(), *value))
+						.collect::<Vec<_>>();
+					if !stripped.is_empty() {
+						*child = Some(build_node(&stripped, nodes));
+					}
+				}
+				TrieNode::Branch(value, children)
+			}
+		},
+	};
+	let encoded = node.encode();
+	let hash = sha2::Sha256::digest(&encoded).into();
+	nodes.push(encoded);
+	hash
+}
+
+/// Parses a light client block as returned by the `next_light_client_block` RPC method.
+pub fn light_client_block_from_json(json: &str) -> LightClientBlockView {
+	let value: Value = serde_json::from_str(json).unwrap();
+	let inner_lite = &value["inner_lite"];
+	LightClientBlockView {
+		prev_block_hash: crypto_hash(&value["prev_block_hash"]),
+		next_block_inner_hash: crypto_hash(&value["next_block_inner_hash"]),
+		inner_lite: BlockHeaderInnerLiteView {
+			height: inner_lite["height"].as_u64().unwrap(),
+			epoch_id: crypto_hash(&inner_lite["epoch_id"]),
+			next_epoch_id: crypto_hash(&inner_lite["next_epoch_id"]),
+			prev_state_root: crypto_hash(&inner_lite["prev_state_root"]),
+			outcome_root: crypto_hash(&inner_lite["outcome_root"]),
+			timestamp: inner_lite["timestamp"].as_u64().unwrap(),
+			timestamp_nanosec: inner_lite["timestamp_nanosec"].as_str().unwrap().parse().unwrap(),
+			next_bp_hash: crypto_hash(&inner_lite["next_bp_hash"]),
+			block_merkle_root: crypto_hash(&inner_lite["block_merkle_root"]),
+		},
+		inner_rest_hash: crypto_hash(&value["inner_rest_hash"]),
+		next_bps: match &value["next_bps"] {
+			Value::Null => None,
+			next_bps => Some(block_producers_from_value(next_bps)),
+		},
+		approvals_after_next: value["approvals_after_next"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|approval| {
+				approval.as_str().map(|approval| Signature::from_raw(&ed25519_key(approval)))
+			})
+			.collect(),
+	}
+}
+
+/// Parses a list of block producers in the format of the `next_bps` RPC field.
+pub fn block_producers_from_json(json: &str) -> Vec<ValidatorStakeView> {
+	block_producers_from_value(&serde_json::from_str(json).unwrap())
+}
+
+fn block_producers_from_value(value: &Value) -> Vec<ValidatorStakeView> {
+	value
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|bp| {
+			ValidatorStakeView::V1(ValidatorStakeViewV1 {
+				account_id: bp["account_id"].as_str().unwrap().to_string(),
+				public_key: PublicKey::from_raw(&ed25519_key(bp["public_key"].as_str().unwrap())),
+				stake: bp["stake"].as_str().unwrap().parse().unwrap(),
+			})
+		})
+		.collect()
+}
+
+fn crypto_hash(value: &Value) -> CryptoHash {
+	let bytes = bs58::decode(value.as_str().unwrap()).into_vec().unwrap();
+	CryptoHash::try_from(bytes.as_slice()).unwrap()
+}
+
+fn ed25519_key(value: &str) -> Vec<u8> {
+	bs58::decode(value.strip_prefix("ed25519:").unwrap()).into_vec().unwrap()
+}
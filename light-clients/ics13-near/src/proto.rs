@@ -0,0 +1,63 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protobuf messages of the NEAR light client. The NEAR data structures are carried as their
+//! borsh encoding, which is what NEAR hashes and signs.
+
+use ibc::prelude::*;
+use ibc_proto::{google::protobuf::Duration, ibc::core::client::v1::Height};
+
+/// `ibc.lightclients.near.v1.ClientState`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClientState {
+	#[prost(string, tag = "1")]
+	pub chain_id: String,
+	/// Borsh encoded `LightClientBlockView` of the head of the client.
+	#[prost(bytes = "vec", tag = "2")]
+	pub head: Vec<u8>,
+	#[prost(bytes = "vec", tag = "3")]
+	pub current_epoch: Vec<u8>,
+	#[prost(bytes = "vec", tag = "4")]
+	pub next_epoch: Vec<u8>,
+	/// Borsh encoded `Vec<ValidatorStakeView>`.
+	#[prost(bytes = "vec", tag = "5")]
+	pub current_validators: Vec<u8>,
+	/// Borsh encoded `Vec<ValidatorStakeView>`.
+	#[prost(bytes = "vec", tag = "6")]
+	pub next_validators: Vec<u8>,
+	#[prost(message, optional, tag = "7")]
+	pub frozen_height: Option<Height>,
+	/// Duration after which the consensus states of the client expire.
+	#[prost(message, optional, tag = "8")]
+	pub trusting_period: Option<Duration>,
+}
+
+/// `ibc.lightclients.near.v1.ConsensusState`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsensusState {
+	#[prost(bytes = "vec", tag = "1")]
+	pub commitment_root: Vec<u8>,
+	/// Timestamp in nanoseconds.
+	#[prost(uint64, tag = "2")]
+	pub timestamp: u64,
+}
+
+/// `ibc.lightclients.near.v1.Header`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Header {
+	/// Borsh encoded `LightClientBlockView`.
+	#[prost(bytes = "vec", tag = "1")]
+	pub light_client_block: Vec<u8>,
+}
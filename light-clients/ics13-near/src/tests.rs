@@ -0,0 +1,384 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_def::{
+		validate_light_block, verify_light_block_approvals, verify_membership,
+		verify_non_membership,
+	},
+	client_state::{trusting_period, NearClientState},
+	consensus_state::{ConsensusState, HostConsensusProof},
+	error::Error,
+	header::{NearClientMessage, NearHeader},
+	mock::{
+		block_producers_from_json, build_trie, light_client_block_from_json, HostFunctionsManager,
+	},
+	types::{LightClientBlockView, Signature},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::time::Duration;
+use ibc::{
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState as _, client_state::ClientState as _,
+			error::Error as Ics02Error,
+		},
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		ics24_host::{
+			identifier::{ChainId, ConnectionId},
+			path::ConnectionsPath,
+		},
+	},
+	timestamp::Timestamp,
+	Height,
+};
+use ibc_proto::google::protobuf::Any;
+use tendermint_proto::Protobuf;
+
+const BLOCK_PRODUCERS_EPOCH_1: &str = include_str!("fixtures/block_producers_epoch_1.json");
+/// Duration of the epochs of NEAR testnet, 43200 blocks of about a second.
+const EPOCH_DURATION: Duration = Duration::from_secs(43_200);
+
+const LIGHT_CLIENT_BLOCK_100: &str = include_str!("fixtures/light_client_block_100.json");
+const LIGHT_CLIENT_BLOCK_105: &str = include_str!("fixtures/light_client_block_105.json");
+/// Block 105 with a different state root, approved by the same block producers.
+const LIGHT_CLIENT_BLOCK_105_FORK: &str = include_str!("fixtures/light_client_block_105_fork.json");
+const LIGHT_CLIENT_BLOCK_160: &str = include_str!("fixtures/light_client_block_160.json");
+
+/// Client state trusting block 100, the block producers of its epoch and those of the next
+/// epoch, announced by the block itself.
+fn initial_client_state() -> NearClientState<HostFunctionsManager> {
+	let head = light_client_block_from_json(LIGHT_CLIENT_BLOCK_100);
+	let next_validators = head.next_bps.clone().unwrap();
+	NearClientState::new(
+		ChainId::from_string("near-testnet"),
+		head,
+		block_producers_from_json(BLOCK_PRODUCERS_EPOCH_1),
+		next_validators,
+		trusting_period(EPOCH_DURATION),
+	)
+}
+
+fn validate(
+	client_state: &NearClientState<HostFunctionsManager>,
+	block: &LightClientBlockView,
+) -> Result<(), Ics02Error> {
+	validate_light_block::<HostFunctionsManager>(
+		&NearHeader::new(block.clone()),
+		client_state.clone(),
+	)
+}
+
+fn assert_near_error(result: Result<(), Ics02Error>, expected: Error) {
+	assert_eq!(result.unwrap_err().to_string(), Ics02Error::from(expected).to_string());
+}
+
+#[test]
+fn should_update_with_block_from_current_epoch() {
+	let client_state = initial_client_state();
+	let block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+	// one of the four equally staked block producers didn't approve the block
+	assert!(block.approvals_after_next.contains(&None));
+
+	validate(&client_state, &block).unwrap();
+
+	let new_client_state = client_state.with_head(block.clone());
+	assert_eq!(new_client_state.latest_height(), Height::new(0, 105));
+	assert_eq!(
+		new_client_state.get_validators_by_epoch(&block.inner_lite.epoch_id),
+		client_state.get_validators_by_epoch(&block.inner_lite.epoch_id)
+	);
+	assert_eq!(
+		new_client_state.get_validators_by_epoch(&block.inner_lite.next_epoch_id),
+		client_state.get_validators_by_epoch(&block.inner_lite.next_epoch_id)
+	);
+}
+
+#[test]
+fn should_rotate_block_producers_on_epoch_transition() {
+	let client_state = initial_client_state();
+	let head = client_state.get_head().clone();
+	let block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_160);
+	assert_eq!(block.inner_lite.epoch_id, head.inner_lite.next_epoch_id);
+
+	validate(&client_state, &block).unwrap();
+
+	let new_client_state = client_state.with_head(block.clone());
+	assert_eq!(new_client_state.latest_height(), Height::new(0, 160));
+	assert_eq!(new_client_state.get_validators_by_epoch(&head.inner_lite.epoch_id), None);
+	assert_eq!(
+		new_client_state.get_validators_by_epoch(&block.inner_lite.epoch_id),
+		head.next_bps.as_ref()
+	);
+	assert_eq!(
+		new_client_state.get_validators_by_epoch(&block.inner_lite.next_epoch_id),
+		block.next_bps.as_ref()
+	);
+}
+
+#[test]
+fn should_update_across_epochs_sequentially() {
+	let mut client_state = initial_client_state();
+	for json in [LIGHT_CLIENT_BLOCK_105, LIGHT_CLIENT_BLOCK_160] {
+		let block = light_client_block_from_json(json);
+		validate(&client_state, &block).unwrap();
+		client_state = client_state.with_head(block);
+	}
+	assert_eq!(client_state.latest_height(), Height::new(0, 160));
+}
+
+#[test]
+fn should_reject_block_with_invalid_signature() {
+	let client_state = initial_client_state();
+	let mut block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+	let mut signature = *block.approvals_after_next[0].as_ref().unwrap().get_inner();
+	signature[0] ^= 1;
+	block.approvals_after_next[0] = Some(Signature::from_raw(&signature));
+
+	assert_near_error(validate(&client_state, &block), Error::invalid_signature());
+}
+
+#[test]
+fn should_reject_block_without_supermajority_of_stake() {
+	let client_state = initial_client_state();
+	let mut block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+	// with two of the four block producers missing, exactly half of the stake approved the block
+	block.approvals_after_next[0] = None;
+
+	assert_near_error(validate(&client_state, &block), Error::insufficient_staked_amount());
+}
+
+#[test]
+fn should_reject_block_at_or_below_head() {
+	let client_state =
+		initial_client_state().with_head(light_client_block_from_json(LIGHT_CLIENT_BLOCK_105));
+	let block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+
+	assert_near_error(validate(&client_state, &block), Error::height_too_old());
+}
+
+#[test]
+fn should_accept_approvals_of_conflicting_block_at_head() {
+	let client_state =
+		initial_client_state().with_head(light_client_block_from_json(LIGHT_CLIENT_BLOCK_105));
+	let block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+	let fork = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105_FORK);
+
+	// the fork can't become the new head, but its approvals are valid evidence of misbehaviour
+	assert_near_error(validate(&client_state, &fork), Error::height_too_old());
+	verify_light_block_approvals::<HostFunctionsManager>(&fork, &client_state).unwrap();
+	assert_ne!(ConsensusState::from_light_block(&fork), ConsensusState::from_light_block(&block));
+
+	let mut forged = fork;
+	forged.inner_lite.prev_state_root = block.inner_lite.outcome_root;
+	assert_near_error(
+		verify_light_block_approvals::<HostFunctionsManager>(&forged, &client_state),
+		Error::invalid_signature(),
+	);
+}
+
+#[test]
+fn should_reject_block_from_unknown_epoch() {
+	let client_state = initial_client_state();
+	let mut block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_160);
+	// block 160's next epoch is two epochs ahead of the head
+	block.inner_lite.epoch_id = block.inner_lite.next_epoch_id;
+
+	assert_near_error(
+		validate(&client_state, &block),
+		Error::invalid_epoch(block.inner_lite.epoch_id),
+	);
+}
+
+#[test]
+fn should_reject_next_epoch_block_without_next_block_producers() {
+	let client_state = initial_client_state();
+	let mut block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_160);
+	block.next_bps = None;
+
+	assert_near_error(validate(&client_state, &block), Error::unavailable_block_producers());
+}
+
+#[test]
+fn should_reject_next_block_producers_not_matching_hash() {
+	let client_state = initial_client_state();
+	let mut block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_160);
+	// next_bps is not part of the signed block hash, only its commitment in next_bp_hash is
+	block.next_bps.as_mut().unwrap().pop();
+
+	assert_near_error(validate(&client_state, &block), Error::invalid_next_block_producers_hash());
+}
+
+#[test]
+fn should_derive_consensus_state_from_block() {
+	let block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+	let consensus_state = ConsensusState::from_light_block(&block);
+
+	assert_eq!(consensus_state.root().as_bytes(), block.inner_lite.prev_state_root.as_ref());
+	assert_eq!(
+		consensus_state.timestamp(),
+		Timestamp::from_nanoseconds(block.inner_lite.timestamp).unwrap()
+	);
+}
+
+#[test]
+fn should_verify_host_consensus_proof_against_block_hash() {
+	let block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+	let proof = HostConsensusProof { block: (&block).into(), checksum: None };
+	let proof = HostConsensusProof::try_from_slice(&proof.try_to_vec().unwrap()).unwrap();
+
+	let consensus_state = proof
+		.verify::<HostFunctionsManager>(&block.current_block_hash::<HostFunctionsManager>())
+		.unwrap();
+	assert_eq!(consensus_state, ConsensusState::from_light_block(&block));
+
+	// the host block at that height is the one of the fork
+	let fork = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105_FORK);
+	let error = proof
+		.verify::<HostFunctionsManager>(&fork.current_block_hash::<HostFunctionsManager>())
+		.unwrap_err();
+	assert_eq!(error.to_string(), Error::invalid_host_consensus_proof(105).to_string());
+}
+
+#[test]
+fn should_freeze_client_at_height() {
+	let client_state = initial_client_state();
+	assert_eq!(client_state.frozen_height(), None);
+	client_state.verify_height(Height::new(0, 100)).unwrap();
+	client_state.verify_height(Height::new(0, 101)).unwrap_err();
+
+	let frozen = client_state.with_frozen_height(Height::new(0, 100));
+	assert_eq!(frozen.frozen_height(), Some(Height::new(0, 100)));
+	frozen.verify_height(Height::new(0, 99)).unwrap();
+	frozen.verify_height(Height::new(0, 100)).unwrap_err();
+}
+
+#[test]
+fn should_verify_state_proofs_against_state_root() {
+	let prefix = CommitmentPrefix::try_from(b"ibc/".to_vec()).unwrap();
+	let path = ConnectionsPath(ConnectionId::new(0));
+	let sibling_path = ConnectionsPath(ConnectionId::new(10));
+	let absent_path = ConnectionsPath(ConnectionId::new(1));
+	let key = |path: &ConnectionsPath| [prefix.as_bytes(), path.to_string().as_bytes()].concat();
+	let value = b"connection end".to_vec();
+
+	let (root, nodes) = build_trie(&[
+		(key(&path), value.clone()),
+		(key(&sibling_path), b"other connection end".to_vec()),
+		(b"other/key".to_vec(), b"other value".to_vec()),
+	]);
+	let root = CommitmentRoot::from_bytes(&root);
+	let proof = |nodes: &Vec<Vec<u8>>| {
+		CommitmentProofBytes::try_from(codec::Encode::encode(nodes)).unwrap()
+	};
+
+	verify_membership::<HostFunctionsManager, _>(
+		&prefix,
+		&proof(&nodes),
+		&root,
+		path.clone(),
+		value.clone(),
+	)
+	.unwrap();
+	verify_membership::<HostFunctionsManager, _>(
+		&prefix,
+		&proof(&nodes),
+		&root,
+		path.clone(),
+		b"other connection end".to_vec(),
+	)
+	.unwrap_err();
+	verify_non_membership::<HostFunctionsManager, _>(&prefix, &proof(&nodes), &root, absent_path)
+		.unwrap();
+	verify_non_membership::<HostFunctionsManager, _>(&prefix, &proof(&nodes), &root, path.clone())
+		.unwrap_err();
+
+	// the proof doesn't lead to the state root of another block
+	let block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+	let other_root = ConsensusState::from_light_block(&block).root().clone();
+	verify_membership::<HostFunctionsManager, _>(
+		&prefix,
+		&proof(&nodes),
+		&other_root,
+		path.clone(),
+		value.clone(),
+	)
+	.unwrap_err();
+
+	// a tampered node no longer hashes to the reference held by its parent
+	let mut tampered = nodes.clone();
+	let leaf = tampered.first_mut().unwrap();
+	*leaf.last_mut().unwrap() ^= 1;
+	verify_membership::<HostFunctionsManager, _>(
+		&prefix,
+		&proof(&tampered),
+		&root,
+		path.clone(),
+		value.clone(),
+	)
+	.unwrap_err();
+
+	// every node on the path to the key is needed
+	verify_membership::<HostFunctionsManager, _>(
+		&prefix,
+		&proof(&nodes[..nodes.len() - 1].to_vec()),
+		&root,
+		path.clone(),
+		value,
+	)
+	.unwrap_err();
+
+	// proofs that don't decode to trie nodes are rejected
+	let malformed_proof = CommitmentProofBytes::try_from(vec![0xff]).unwrap();
+	verify_non_membership::<HostFunctionsManager, _>(&prefix, &malformed_proof, &root, path)
+		.unwrap_err();
+}
+
+#[test]
+fn should_expire_consensus_states_after_trusting_period() {
+	let client_state = initial_client_state();
+	// block producers can withdraw their stake after 4 epochs
+	assert_eq!(client_state.trusting_period(), Duration::from_secs(32 * 60 * 60));
+	assert!(!client_state.expired(client_state.trusting_period()));
+	assert!(client_state.expired(client_state.trusting_period() + Duration::from_secs(1)));
+
+	let head = light_client_block_from_json(LIGHT_CLIENT_BLOCK_100);
+	let timestamp = ConsensusState::from_light_block(&head).timestamp();
+	let trusted_until = (timestamp + client_state.trusting_period()).unwrap();
+	client_state.verify_not_expired(timestamp, trusted_until).unwrap();
+	let expired_at = (trusted_until + Duration::from_secs(1)).unwrap();
+	assert_eq!(
+		client_state.verify_not_expired(timestamp, expired_at).unwrap_err().to_string(),
+		Error::consensus_state_expired(timestamp, expired_at).to_string()
+	);
+}
+
+#[test]
+fn protobuf_roundtrip() {
+	let client_state = initial_client_state().with_frozen_height(Height::new(0, 100));
+	let decoded =
+		NearClientState::<HostFunctionsManager>::decode_vec(&client_state.encode_vec().unwrap())
+			.unwrap();
+	assert_eq!(decoded, client_state);
+
+	let block = light_client_block_from_json(LIGHT_CLIENT_BLOCK_105);
+	let consensus_state = ConsensusState::from_light_block(&block);
+	let decoded = ConsensusState::decode_vec(&consensus_state.encode_vec().unwrap()).unwrap();
+	assert_eq!(decoded, consensus_state);
+
+	let message = NearClientMessage::Header(NearHeader::new(block));
+	let decoded = NearClientMessage::try_from(Any::from(message.clone())).unwrap();
+	assert_eq!(decoded, message);
+}
@@ -121,13 +121,23 @@ pub type MerkleHash = CryptoHash;
 #[derive(Debug, Clone, BorshDeserialize)]
 pub struct MerklePath(pub Vec<MerklePathItem>);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct LightClientBlockLiteView {
 	pub prev_block_hash: CryptoHash,
 	pub inner_rest_hash: CryptoHash,
 	pub inner_lite: BlockHeaderInnerLiteView,
 }
 
+impl From<&LightClientBlockView> for LightClientBlockLiteView {
+	fn from(block: &LightClientBlockView) -> Self {
+		Self {
+			prev_block_hash: block.prev_block_hash,
+			inner_rest_hash: block.inner_rest_hash,
+			inner_lite: block.inner_lite.clone(),
+		}
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct LightClientBlockView {
 	pub prev_block_hash: CryptoHash,
@@ -166,6 +176,21 @@ pub struct BlockHeaderInnerLiteViewFinal {
 	pub block_merkle_root: CryptoHash,
 }
 
+impl From<&BlockHeaderInnerLiteView> for BlockHeaderInnerLiteViewFinal {
+	fn from(view: &BlockHeaderInnerLiteView) -> Self {
+		Self {
+			height: view.height,
+			epoch_id: view.epoch_id,
+			next_epoch_id: view.next_epoch_id,
+			prev_state_root: view.prev_state_root,
+			outcome_root: view.outcome_root,
+			timestamp: view.timestamp,
+			next_bp_hash: view.next_bp_hash,
+			block_merkle_root: view.block_merkle_root,
+		}
+	}
+}
+
 #[derive(Debug, BorshDeserialize, BorshSerialize)]
 pub enum ApprovalInner {
 	Endorsement(CryptoHash),
@@ -276,7 +301,7 @@ impl BorshDeserialize for PublicKey {
 
 fn read_key_type<R: io::Read>(rd: &mut R) -> io::Result<()> {
 	match u8::deserialize_reader(rd)? {
-		8 => Ok(()),
+		0 => Ok(()),
 		key_type => Err(io::Error::new(
 			io::ErrorKind::InvalidData,
 			format!("Unrecognised key type: {key_type}"),
@@ -289,12 +314,23 @@ impl LightClientBlockView {
 		Height { revision_number: 0, revision_height: self.inner_lite.height }
 	}
 
+	pub fn current_block_hash<H: HostFunctions>(&self) -> CryptoHash {
+		LightClientBlockLiteView::from(self).current_block_hash::<H>()
+	}
+}
+
+impl LightClientBlockLiteView {
 	pub fn current_block_hash<H: HostFunctions>(&self) -> CryptoHash {
 		current_block_hash::<H>(
-			H::sha256_digest(self.inner_lite.try_to_vec().unwrap().as_ref())
-				.as_slice()
-				.try_into()
-				.unwrap(),
+			H::sha256_digest(
+				BlockHeaderInnerLiteViewFinal::from(&self.inner_lite)
+					.try_to_vec()
+					.unwrap()
+					.as_ref(),
+			)
+			.as_slice()
+			.try_into()
+			.unwrap(),
 			self.inner_rest_hash,
 			self.prev_block_hash,
 		)
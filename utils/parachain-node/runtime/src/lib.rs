@@ -554,7 +554,7 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
 	pub const ExpectedBlockTime: u64 = MILLISECS_PER_BLOCK;
-	pub const RelayChainId: RelayChain = RelayChain::Rococo;
+	pub const RelayChainId: RelayChain = RelayChain::ROCOCO;
 	pub const SpamProtectionDeposit: Balance = 1_000_000_000_000;
 	pub const NativeAssetId: AssetId = 1;
 	pub const MinimumConnectionDelay: u64 = 0; // well we don't need the security tbh.
@@ -562,7 +562,7 @@ parameter_types! {
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Router {
-	pallet_ibc_ping: pallet_ibc_ping::IbcModule<Runtime>,
+	pallet_ibc_ping: pallet_ibc::ics29_fee::Ics29Fee<Runtime, pallet_ibc_ping::IbcModule<Runtime>>,
 }
 
 impl ModuleRouter for Router {
@@ -724,6 +724,7 @@ parameter_types! {
 	pub AssetIdUSDT: AssetId = 0;
 	pub FlatFeeUSDTAmount: Balance = 0;
 	pub IbcIcs20ServiceCharge: Perbill = Perbill::from_rational(0_u32, 1000_u32 );
+	pub const RelayerFeePalletId: PalletId = PalletId(*b"ics29fee");
 }
 
 fn create_alice_key() -> <Runtime as pallet_ibc::Config>::AccountIdConversion {
@@ -806,6 +807,7 @@ impl pallet_ibc::Config for Runtime {
 	type IsReceiveEnabled = sp_core::ConstBool<true>;
 	type IsSendEnabled = sp_core::ConstBool<true>;
 	type HandleMemo = ();
+	type Ics20RateLimiter = ();
 	type PalletPrefix = IbcTriePrefix;
 	type LightClientProtocol = GRANDPA;
 	type IbcAccountId = Self::AccountId;
@@ -818,6 +820,12 @@ impl pallet_ibc::Config for Runtime {
 	type SubstrateMultihopXcmHandler = SubstrateMultihopXcmHandlerNone<Runtime>;
 }
 
+impl pallet_ibc::ics29_fee::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type PalletId = RelayerFeePalletId;
+	type WeightInfo = ();
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub enum Runtime where
@@ -852,6 +860,7 @@ construct_runtime!(
 		IbcPing: pallet_ibc_ping = 36,
 		Assets: pallet_assets = 37,
 		AssetRegistry: asset_registry = 38,
+		Ics29Fee: pallet_ibc::ics29_fee = 39,
 		// pallet-ibc, should be the last module in your runtime
 		Ibc: pallet_ibc = 255,
 	}
@@ -871,6 +880,7 @@ mod benches {
 		[pallet_collator_selection, CollatorSelection]
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
 		[pallet_ibc, Ibc]
+		[pallet_ibc::ics29_fee, Ics29Fee]
 	);
 }
 
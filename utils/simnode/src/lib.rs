@@ -141,6 +141,7 @@ mod tests {
 				para_id: 100,
 				current_set_id: 1,
 				current_authorities: Default::default(),
+				solo_chain: false,
 				_phantom: Default::default(),
 			};
 